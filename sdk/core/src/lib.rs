@@ -43,10 +43,11 @@ pub use http_client::{new_http_client, to_json, HttpClient};
 pub use models::*;
 pub use options::*;
 pub use pipeline_context::PipelineContext;
-pub use policies::{Policy, PolicyResult};
+pub use policies::{BearerTokenCredentialPolicy, Policy, PolicyResult};
 pub use request::*;
 pub use response::*;
 pub use seekable_stream::*;
+pub use sleep::{sleep, Sleep};
 
 pub type RequestId = Uuid;
 pub type SessionToken = String;
@@ -68,11 +69,79 @@ impl TokenResponse {
     }
 }
 
+/// Options of a token request.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TokenRequestOptions {
+    /// The tenant to request the token from, overriding the one of the credential.
+    pub tenant_id: Option<String>,
+    /// Additional claims the token must include, as returned by a continuous access
+    /// evaluation (CAE) challenge. Such requests bypass any token cache.
+    pub claims: Option<String>,
+}
+
+impl TokenRequestOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    setters! {
+        tenant_id: String => Some(tenant_id),
+        claims: String => Some(claims),
+    }
+}
+
 /// Represents a credential capable of providing an OAuth token.
+///
+/// The trait is object safe so credentials can be shared as `Arc<dyn TokenCredential>`.
 #[async_trait::async_trait]
 pub trait TokenCredential: Send + Sync {
-    /// Gets a `TokenResponse` for the specified resource
-    async fn get_token(&self, resource: &str) -> Result<TokenResponse, Error>;
+    /// Gets a `TokenResponse` for the specified scopes
+    async fn get_token_with_options(
+        &self,
+        scopes: &[&str],
+        options: &TokenRequestOptions,
+    ) -> Result<TokenResponse, Error>;
+
+    /// Gets a `TokenResponse` for the specified resource, requesting its `/.default` scope
+    async fn get_token(&self, resource: &str) -> Result<TokenResponse, Error> {
+        let scope = resource_to_scope(resource);
+        self.get_token_with_options(&[&scope], &TokenRequestOptions::default())
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl<T> TokenCredential for std::sync::Arc<T>
+where
+    T: TokenCredential + ?Sized,
+{
+    async fn get_token_with_options(
+        &self,
+        scopes: &[&str],
+        options: &TokenRequestOptions,
+    ) -> Result<TokenResponse, Error> {
+        (**self).get_token_with_options(scopes, options).await
+    }
+
+    async fn get_token(&self, resource: &str) -> Result<TokenResponse, Error> {
+        (**self).get_token(resource).await
+    }
+}
+
+/// Converts a resource, such as `https://vault.azure.net`, to its `/.default` scope.
+/// Scopes are returned unchanged.
+pub fn resource_to_scope(resource: &str) -> String {
+    if resource.ends_with("/.default") {
+        resource.to_owned()
+    } else {
+        format!("{}/.default", resource)
+    }
+}
+
+/// Converts a scope to the resource it belongs to, for the endpoints still expecting
+/// resources such as managed identity.
+pub fn scope_to_resource(scope: &str) -> &str {
+    scope.strip_suffix("/.default").unwrap_or(scope)
 }
 
 pub trait AppendToUrlQuery {
@@ -0,0 +1,543 @@
+use crate::policies::{Policy, PolicyResult};
+use crate::TokenResponse;
+use crate::{PipelineContext, Request, Response, TokenCredential, TokenRequestOptions};
+use chrono::{Duration, Utc};
+use http::header::{AUTHORIZATION, WWW_AUTHENTICATE};
+use http::{HeaderValue, StatusCode};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// How long before `expires_on` a cached token stops being used.
+const REFRESH_WINDOW_SECS: i64 = 120;
+
+/// Authorizes requests with a bearer token obtained from a `TokenCredential`.
+///
+/// The policy is meant to be a per-retry policy so a fresh token is used on every attempt.
+/// Tokens are cached until shortly before they expire. When the service answers `401` with a
+/// `WWW-Authenticate` challenge the policy gets a new token and re-sends the request once:
+///
+/// * a challenge with `claims` (continuous access evaluation) requests a token including them;
+/// * when tenant discovery is enabled, as Key Vault requires, the tenant and scope of the token
+///   are taken from the `authorization_uri` and `resource`/`scope` of the challenge and kept for
+///   the following requests. A challenge whose scope is not the domain of the request, or one of
+///   its parent domains, is ignored: otherwise any endpoint could obtain a token for any
+///   audience.
+#[derive(Clone)]
+pub struct BearerTokenCredentialPolicy {
+    credential: Arc<dyn TokenCredential>,
+    scopes: Vec<String>,
+    tenant_discovery: bool,
+    cache: Arc<TokenCache>,
+}
+
+impl BearerTokenCredentialPolicy {
+    pub fn new<S: Into<String>>(
+        credential: Arc<dyn TokenCredential>,
+        scopes: impl IntoIterator<Item = S>,
+    ) -> Self {
+        Self {
+            credential,
+            scopes: scopes.into_iter().map(Into::into).collect(),
+            tenant_discovery: false,
+            cache: Arc::new(TokenCache::default()),
+        }
+    }
+
+    /// Enables taking the tenant and scope of the tokens from the challenges of the service.
+    pub fn with_tenant_discovery(mut self, tenant_discovery: bool) -> Self {
+        self.tenant_discovery = tenant_discovery;
+        self
+    }
+
+    async fn authorize(
+        &self,
+        request: &mut Request,
+        claims: Option<String>,
+    ) -> Result<(), crate::Error> {
+        let token = match claims {
+            None => match self.cache.get() {
+                Some(token) => token,
+                None => self.request_token(None).await?,
+            },
+            claims => self.request_token(claims).await?,
+        };
+        let header = HeaderValue::from_str(&format!("Bearer {}", token.token.secret()))
+            .map_err(|error| crate::Error::GetTokenError(Box::new(error)))?;
+        request.headers_mut().insert(AUTHORIZATION, header);
+        Ok(())
+    }
+
+    async fn request_token(&self, claims: Option<String>) -> Result<TokenResponse, crate::Error> {
+        let (scopes, tenant_id) = self.cache.scopes_and_tenant(&self.scopes);
+        let scopes: Vec<&str> = scopes.iter().map(String::as_str).collect();
+        let options = TokenRequestOptions { tenant_id, claims };
+        let token = self
+            .credential
+            .get_token_with_options(&scopes, &options)
+            .await?;
+        self.cache.set(token.clone());
+        Ok(token)
+    }
+
+    /// Handles a challenge to a request to `host`, returning whether the request should be
+    /// re-sent with a new token.
+    fn on_challenge(
+        &self,
+        challenge: &AuthenticationChallenge,
+        host: Option<&str>,
+    ) -> (bool, Option<String>) {
+        let claims = challenge.claims();
+        let mut discovered = false;
+        if self.tenant_discovery {
+            if let Some(tenant_id) = challenge.tenant_id() {
+                let scope = challenge.scope();
+                match &scope {
+                    Some(scope) if !scope_matches_host(scope, host) => {
+                        log::warn!(
+                            "ignoring the authentication challenge of {:?} for scope {}",
+                            host,
+                            scope
+                        );
+                    }
+                    _ => discovered = self.cache.discover(&tenant_id, scope),
+                }
+            }
+        }
+        (claims.is_some() || discovered, claims)
+    }
+}
+
+impl std::fmt::Debug for BearerTokenCredentialPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BearerTokenCredentialPolicy")
+            .field("scopes", &self.scopes)
+            .field("tenant_discovery", &self.tenant_discovery)
+            .finish()
+    }
+}
+
+#[async_trait::async_trait]
+impl<C> Policy<C> for BearerTokenCredentialPolicy
+where
+    C: Send + Sync,
+{
+    async fn send(
+        &self,
+        ctx: &mut PipelineContext<C>,
+        request: &mut Request,
+        next: &[Arc<dyn Policy<C>>],
+    ) -> PolicyResult<Response> {
+        if next.is_empty() {
+            return Err(Box::new(crate::PipelineError::InvalidTailPolicy(
+                "Authorization policies cannot be the last policy of a pipeline".to_owned(),
+            )));
+        }
+
+        self.authorize(request, None).await?;
+        let response = next[0].send(ctx, request, &next[1..]).await?;
+        if response.status() != StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+
+        let challenge = match response
+            .headers()
+            .get(WWW_AUTHENTICATE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(AuthenticationChallenge::parse)
+        {
+            Some(challenge) => challenge,
+            None => return Ok(response),
+        };
+        let host = request.uri().host().map(ToOwned::to_owned);
+        let (resend, claims) = self.on_challenge(&challenge, host.as_deref());
+        if !resend {
+            return Ok(response);
+        }
+
+        log::debug!("re-sending the request after an authentication challenge");
+        self.authorize(request, claims).await?;
+        next[0].send(ctx, request, &next[1..]).await
+    }
+}
+
+/// Whether the domain of `scope` is `host` or one of its parent domains, such as
+/// `https://vault.azure.net/.default` for `myvault.vault.azure.net`.
+fn scope_matches_host(scope: &str, host: Option<&str>) -> bool {
+    let (scope, host) = match (url::Url::parse(scope), host) {
+        (Ok(scope), Some(host)) => (scope, host.to_ascii_lowercase()),
+        _ => return false,
+    };
+    match scope.host_str() {
+        Some(domain) => {
+            let domain = domain.to_ascii_lowercase();
+            host == domain || host.ends_with(&format!(".{}", domain))
+        }
+        None => false,
+    }
+}
+
+/// The token of the policy, along with the tenant and scope discovered from the challenges.
+#[derive(Debug, Default)]
+struct TokenCache {
+    state: RwLock<TokenCacheState>,
+}
+
+#[derive(Debug, Default)]
+struct TokenCacheState {
+    token: Option<TokenResponse>,
+    tenant_id: Option<String>,
+    scope: Option<String>,
+}
+
+impl TokenCache {
+    /// The cached token, unless it is about to expire.
+    fn get(&self) -> Option<TokenResponse> {
+        let refresh_after = Utc::now() + Duration::seconds(REFRESH_WINDOW_SECS);
+        self.state
+            .read()
+            .unwrap()
+            .token
+            .clone()
+            .filter(|token| token.expires_on > refresh_after)
+    }
+
+    fn set(&self, token: TokenResponse) {
+        self.state.write().unwrap().token = Some(token);
+    }
+
+    /// The scopes and tenant to request tokens for, the discovered ones taking precedence.
+    fn scopes_and_tenant(&self, scopes: &[String]) -> (Vec<String>, Option<String>) {
+        let state = self.state.read().unwrap();
+        let scopes = match &state.scope {
+            Some(scope) => vec![scope.clone()],
+            None => scopes.to_vec(),
+        };
+        (scopes, state.tenant_id.clone())
+    }
+
+    /// Records a discovered tenant and scope, returning whether they changed.
+    fn discover(&self, tenant_id: &str, scope: Option<String>) -> bool {
+        let mut state = self.state.write().unwrap();
+        let scope = scope.or_else(|| state.scope.clone());
+        if state.tenant_id.as_deref() == Some(tenant_id) && state.scope == scope {
+            return false;
+        }
+        state.tenant_id = Some(tenant_id.to_owned());
+        state.scope = scope;
+        state.token = None;
+        true
+    }
+}
+
+/// A `WWW-Authenticate` challenge, such as
+/// `Bearer authorization_uri="https://login.microsoftonline.com/{tenant}", resource="https://vault.azure.net"`.
+#[derive(Debug, Clone, PartialEq)]
+struct AuthenticationChallenge {
+    scheme: String,
+    parameters: HashMap<String, String>,
+}
+
+impl AuthenticationChallenge {
+    fn parse(header: &str) -> Option<Self> {
+        let header = header.trim();
+        let (scheme, mut rest) = match header.find(' ') {
+            Some(index) => (&header[..index], header[index + 1..].trim_start()),
+            None => (header, ""),
+        };
+        if scheme.is_empty() {
+            return None;
+        }
+
+        let mut parameters = HashMap::new();
+        while !rest.is_empty() {
+            let equal = rest.find('=')?;
+            let name = rest[..equal].trim().to_ascii_lowercase();
+            rest = rest[equal + 1..].trim_start();
+            let value = if let Some(quoted) = rest.strip_prefix('"') {
+                let end = quoted.find('"')?;
+                rest = &quoted[end + 1..];
+                &quoted[..end]
+            } else {
+                let end = rest.find(',').unwrap_or(rest.len());
+                let value = rest[..end].trim();
+                rest = &rest[end..];
+                value
+            };
+            parameters.insert(name, value.to_owned());
+            rest = rest.trim_start().trim_start_matches(',').trim_start();
+        }
+
+        Some(Self {
+            scheme: scheme.to_owned(),
+            parameters,
+        })
+    }
+
+    fn parameter(&self, name: &str) -> Option<&str> {
+        self.parameters
+            .get(name)
+            .map(String::as_str)
+            .filter(|value| !value.is_empty())
+    }
+
+    /// The claims of a continuous access evaluation challenge, base64 decoded.
+    fn claims(&self) -> Option<String> {
+        if !matches!(self.parameter("error"), Some("insufficient_claims") | None) {
+            return None;
+        }
+        let claims = self.parameter("claims")?;
+        let decoded = base64::decode(claims)
+            .or_else(|_| base64::decode_config(claims, base64::URL_SAFE_NO_PAD))
+            .ok()
+            .and_then(|decoded| String::from_utf8(decoded).ok());
+        Some(decoded.unwrap_or_else(|| claims.to_owned()))
+    }
+
+    /// The tenant id, first path segment of the authorization uri.
+    fn tenant_id(&self) -> Option<String> {
+        let authorization = self
+            .parameter("authorization_uri")
+            .or_else(|| self.parameter("authorization"))?;
+        let authorization = url::Url::parse(authorization).ok()?;
+        let tenant_id = authorization.path_segments()?.next()?;
+        if tenant_id.is_empty() {
+            None
+        } else {
+            Some(tenant_id.to_owned())
+        }
+    }
+
+    /// The scope to request, from either the `scope` or the `resource` parameter.
+    fn scope(&self) -> Option<String> {
+        self.parameter("scope")
+            .map(ToOwned::to_owned)
+            .or_else(|| self.parameter("resource").map(crate::resource_to_scope))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::response::ResponseBuilder;
+    use crate::Context;
+    use oauth2::AccessToken;
+    use std::sync::Mutex;
+
+    #[derive(Debug, Default)]
+    struct RecordingCredential {
+        requests: Mutex<Vec<(Vec<String>, TokenRequestOptions)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl TokenCredential for RecordingCredential {
+        async fn get_token_with_options(
+            &self,
+            scopes: &[&str],
+            options: &TokenRequestOptions,
+        ) -> Result<TokenResponse, crate::Error> {
+            let mut requests = self.requests.lock().unwrap();
+            requests.push((
+                scopes.iter().map(|scope| scope.to_string()).collect(),
+                options.clone(),
+            ));
+            Ok(TokenResponse::new(
+                AccessToken::new(format!("token-{}", requests.len())),
+                Utc::now() + Duration::hours(1),
+            ))
+        }
+    }
+
+    /// Answers with the given challenge until a request carries `accepted_token`.
+    #[derive(Debug)]
+    struct ChallengingTransport {
+        challenge: &'static str,
+        accepted_token: &'static str,
+        authorizations: Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Policy<()> for ChallengingTransport {
+        async fn send(
+            &self,
+            _ctx: &mut PipelineContext<()>,
+            request: &mut Request,
+            _next: &[Arc<dyn Policy<()>>],
+        ) -> PolicyResult<Response> {
+            let authorization = request.headers()[AUTHORIZATION].to_str()?.to_owned();
+            let accepted = authorization == format!("Bearer {}", self.accepted_token);
+            self.authorizations.lock().unwrap().push(authorization);
+
+            let status = if accepted {
+                StatusCode::OK
+            } else {
+                StatusCode::UNAUTHORIZED
+            };
+            let mut builder = ResponseBuilder::new(status);
+            if !accepted {
+                builder.with_header(&WWW_AUTHENTICATE, HeaderValue::from_static(self.challenge));
+            }
+            Ok(builder.with_pinned_stream(Box::pin(futures::stream::empty())))
+        }
+    }
+
+    async fn send(
+        policy: &BearerTokenCredentialPolicy,
+        transport: Arc<ChallengingTransport>,
+    ) -> StatusCode {
+        let mut ctx = PipelineContext::new(Context::new(), ());
+        let mut request: Request = http::Request::get("https://myvault.vault.azure.net/keys")
+            .body(bytes::Bytes::new())
+            .unwrap()
+            .into();
+        let next: Vec<Arc<dyn Policy<()>>> = vec![transport];
+        Policy::send(policy, &mut ctx, &mut request, &next)
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[test]
+    fn parse_key_vault_challenge() {
+        let challenge = AuthenticationChallenge::parse(
+            r#"Bearer authorization="https://login.microsoftonline.com/72f988bf-86f1-41af-91ab-2d7cd011db47", resource="https://vault.azure.net""#,
+        )
+        .unwrap();
+        assert_eq!(challenge.scheme, "Bearer");
+        assert_eq!(
+            challenge.tenant_id().as_deref(),
+            Some("72f988bf-86f1-41af-91ab-2d7cd011db47")
+        );
+        assert_eq!(
+            challenge.scope().as_deref(),
+            Some("https://vault.azure.net/.default")
+        );
+        assert_eq!(challenge.claims(), None);
+    }
+
+    #[test]
+    fn parse_cae_challenge() {
+        let challenge = AuthenticationChallenge::parse(
+            r#"Bearer realm="", authorization_uri="https://login.microsoftonline.com/common/oauth2/authorize", error="insufficient_claims", claims="eyJhY2Nlc3NfdG9rZW4iOnsibmJmIjp7ImVzc2VudGlhbCI6dHJ1ZX19fQ==""#,
+        )
+        .unwrap();
+        assert_eq!(
+            challenge.claims().as_deref(),
+            Some(r#"{"access_token":{"nbf":{"essential":true}}}"#)
+        );
+        assert_eq!(challenge.tenant_id().as_deref(), Some("common"));
+    }
+
+    #[test]
+    fn resend_with_claims() {
+        let credential = Arc::new(RecordingCredential::default());
+        let policy = BearerTokenCredentialPolicy::new(
+            credential.clone(),
+            vec!["https://vault.azure.net/.default"],
+        );
+        let transport = Arc::new(ChallengingTransport {
+            challenge: r#"Bearer realm="", error="insufficient_claims", claims="eyJhY2Nlc3NfdG9rZW4iOnt9fQ==""#,
+            accepted_token: "token-2",
+            authorizations: Mutex::new(Vec::new()),
+        });
+
+        let status = futures::executor::block_on(send(&policy, transport.clone()));
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            *transport.authorizations.lock().unwrap(),
+            vec!["Bearer token-1", "Bearer token-2"]
+        );
+        let requests = credential.requests.lock().unwrap();
+        assert_eq!(
+            requests[1].1.claims.as_deref(),
+            Some(r#"{"access_token":{}}"#)
+        );
+    }
+
+    #[test]
+    fn tenant_discovery() {
+        let credential = Arc::new(RecordingCredential::default());
+        let policy = BearerTokenCredentialPolicy::new(
+            credential.clone(),
+            vec!["https://vault.azure.net/.default"],
+        )
+        .with_tenant_discovery(true);
+        let transport = Arc::new(ChallengingTransport {
+            challenge: r#"Bearer authorization="https://login.microsoftonline.com/tenant-id", resource="https://vault.azure.net""#,
+            accepted_token: "token-2",
+            authorizations: Mutex::new(Vec::new()),
+        });
+
+        assert_eq!(
+            futures::executor::block_on(send(&policy, transport.clone())),
+            StatusCode::OK
+        );
+        // the discovered tenant is kept: the cached token is used
+        assert_eq!(
+            futures::executor::block_on(send(&policy, transport.clone())),
+            StatusCode::OK
+        );
+        assert_eq!(
+            *transport.authorizations.lock().unwrap(),
+            vec!["Bearer token-1", "Bearer token-2", "Bearer token-2"]
+        );
+        let requests = credential.requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].1.tenant_id.as_deref(), Some("tenant-id"));
+    }
+
+    #[test]
+    fn challenge_for_another_domain_is_ignored() {
+        assert!(scope_matches_host(
+            "https://vault.azure.net/.default",
+            Some("myvault.vault.azure.net")
+        ));
+        assert!(!scope_matches_host(
+            "https://management.azure.com/.default",
+            Some("myvault.vault.azure.net")
+        ));
+        assert!(!scope_matches_host(
+            "https://net/.default",
+            Some("myvault.vault.azurenet")
+        ));
+
+        let credential = Arc::new(RecordingCredential::default());
+        let policy = BearerTokenCredentialPolicy::new(
+            credential.clone(),
+            vec!["https://vault.azure.net/.default"],
+        )
+        .with_tenant_discovery(true);
+        let transport = Arc::new(ChallengingTransport {
+            challenge: r#"Bearer authorization="https://login.microsoftonline.com/tenant-id", resource="https://management.azure.com""#,
+            accepted_token: "token-2",
+            authorizations: Mutex::new(Vec::new()),
+        });
+
+        assert_eq!(
+            futures::executor::block_on(send(&policy, transport)),
+            StatusCode::UNAUTHORIZED
+        );
+        let requests = credential.requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].0, vec!["https://vault.azure.net/.default"]);
+    }
+
+    #[test]
+    fn challenge_without_discovery_is_returned() {
+        let credential = Arc::new(RecordingCredential::default());
+        let policy = BearerTokenCredentialPolicy::new(
+            credential.clone(),
+            vec!["https://vault.azure.net/.default"],
+        );
+        let transport = Arc::new(ChallengingTransport {
+            challenge: r#"Bearer authorization="https://login.microsoftonline.com/tenant-id", resource="https://vault.azure.net""#,
+            accepted_token: "token-2",
+            authorizations: Mutex::new(Vec::new()),
+        });
+
+        assert_eq!(
+            futures::executor::block_on(send(&policy, transport)),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(credential.requests.lock().unwrap().len(), 1);
+    }
+}
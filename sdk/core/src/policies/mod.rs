@@ -1,8 +1,10 @@
+mod bearer_token_credential_policy;
 mod retry_policies;
 mod telemetry_policy;
 mod transport;
 
 use crate::{PipelineContext, Request, Response};
+pub use bearer_token_credential_policy::*;
 pub use retry_policies::*;
 use std::error::Error;
 use std::sync::Arc;
@@ -4,14 +4,16 @@ use std::task::{Context, Poll};
 use std::thread;
 use std::time::Duration;
 
-pub(crate) fn sleep(duration: Duration) -> Sleep {
+/// Returns a future completing after `duration`, without depending on an async runtime.
+pub fn sleep(duration: Duration) -> Sleep {
     Sleep {
         thread: None,
         duration,
     }
 }
 
-pub(crate) struct Sleep {
+#[derive(Debug)]
+pub struct Sleep {
     thread: Option<thread::JoinHandle<()>>,
     duration: Duration,
 }
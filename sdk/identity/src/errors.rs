@@ -1,4 +1,6 @@
 //! Errors specific to identity services.
+// `Error::DefaultCredentialError` is deprecated but its derived implementations are not.
+#![allow(deprecated)]
 use serde::{Deserialize, Serialize};
 
 #[non_exhaustive]
@@ -6,6 +8,15 @@ use serde::{Deserialize, Serialize};
 pub enum Error {
     #[error("Error getting token credentials from Azure CLI: {0}")]
     AzureCliCredentialError(#[from] crate::token_credentials::AzureCliCredentialError),
+    #[error("Client assertion credentials error: {0}")]
+    ClientAssertionCredentialError(
+        #[from] crate::token_credentials::ClientAssertionCredentialError,
+    ),
+    #[cfg(feature = "client_certificate")]
+    #[error("Client certificate credentials error: {0}")]
+    ClientCertificateCredentialError(
+        #[from] crate::token_credentials::ClientCertificateCredentialError,
+    ),
     #[error("Client secret credentials error: {0}")]
     ClientSecretCredentialError(#[from] crate::token_credentials::ClientSecretCredentialError),
     #[error("Error getting environment credential: {0}")]
@@ -14,8 +25,17 @@ pub enum Error {
     ManagedIdentityCredentialError(
         #[from] crate::token_credentials::ManagedIdentityCredentialError,
     ),
+    #[error("Error getting workload identity credential: {0}")]
+    WorkloadIdentityCredentialError(
+        #[from] crate::token_credentials::WorkloadIdentityCredentialError,
+    ),
+    #[deprecated(
+        note = "`DefaultCredential` fails with a `ChainedTokenCredentialError`, see `ChainedTokenCredentialError`"
+    )]
     #[error("Error getting default credential: {0}")]
     DefaultCredentialError(#[from] crate::token_credentials::DefaultCredentialError),
+    #[error("Error getting chained credential: {0}")]
+    ChainedTokenCredentialError(#[from] crate::token_credentials::ChainedTokenCredentialError),
     #[error("Error refreshing token: {0}")]
     RefreshTokenError(#[from] crate::refresh_token::Error),
     /// An unrecognized error response from an identity service.
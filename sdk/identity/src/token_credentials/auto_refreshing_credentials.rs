@@ -0,0 +1,417 @@
+use azure_core::{TokenCredential, TokenRequestOptions, TokenResponse};
+use chrono::{Duration, Utc};
+use futures::lock::Mutex as AsyncMutex;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// How long before `expires_on` a cached token starts being refreshed.
+const DEFAULT_REFRESH_WINDOW_SECS: i64 = 300;
+
+/// Caches the tokens of another credential, one per set of scopes and tenant, refreshing them
+/// ahead of expiry.
+///
+/// Once a token enters its refresh window, the first caller to ask for it starts a refresh in
+/// the background, on the Tokio runtime it is called from, and every caller is immediately
+/// served the still valid cached token until the new one replaces it. Should the refresh fail,
+/// the next caller starts another one. Only when no valid token is cached do callers wait, and
+/// they are then coalesced, with any running refresh, into a single request to the wrapped
+/// credential.
+///
+/// Outside of a Tokio runtime no task can be spawned: the first caller inside the refresh
+/// window refreshes the token inline instead, while the other callers are served the cached
+/// token.
+///
+/// Requests with claims, made to answer a continuous access evaluation challenge, always
+/// reach the wrapped credential and their token replaces the cached one.
+pub struct AutoRefreshingTokenCredential {
+    credential: Arc<dyn TokenCredential>,
+    refresh_window: Duration,
+    cache: Mutex<HashMap<CacheKey, Arc<CacheEntry>>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    scopes: Vec<String>,
+    tenant_id: Option<String>,
+}
+
+impl CacheKey {
+    fn new(scopes: &[&str], options: &TokenRequestOptions) -> Self {
+        let mut scopes: Vec<String> = scopes.iter().map(|scope| scope.to_string()).collect();
+        scopes.sort();
+        Self {
+            scopes,
+            tenant_id: options.tenant_id.clone(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct CacheEntry {
+    token: RwLock<Option<TokenResponse>>,
+    refresh: AsyncMutex<()>,
+    /// Whether a background refresh is running.
+    refreshing: AtomicBool,
+}
+
+impl CacheEntry {
+    fn token(&self) -> Option<TokenResponse> {
+        self.token.read().unwrap().clone()
+    }
+
+    fn set_token(&self, token: TokenResponse) {
+        *self.token.write().unwrap() = Some(token);
+    }
+}
+
+impl AutoRefreshingTokenCredential {
+    /// Create a new `AutoRefreshingTokenCredential` wrapping `credential`.
+    pub fn new(credential: Arc<dyn TokenCredential>) -> Self {
+        Self {
+            credential,
+            refresh_window: Duration::seconds(DEFAULT_REFRESH_WINDOW_SECS),
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sets how long before expiry tokens are refreshed. The default is five minutes.
+    pub fn with_refresh_window(mut self, refresh_window: Duration) -> Self {
+        self.refresh_window = refresh_window;
+        self
+    }
+
+    /// The wrapped credential.
+    pub fn credential(&self) -> &Arc<dyn TokenCredential> {
+        &self.credential
+    }
+
+    /// Drops every cached token, forcing the next calls to request new ones.
+    pub fn clear(&self) {
+        self.cache.lock().unwrap().clear();
+    }
+
+    fn entry(&self, key: CacheKey) -> Arc<CacheEntry> {
+        self.cache.lock().unwrap().entry(key).or_default().clone()
+    }
+
+    /// Refreshes the token of `entry` on `runtime`, unless a refresh is already running.
+    fn refresh_in_background(
+        &self,
+        runtime: &tokio::runtime::Handle,
+        entry: Arc<CacheEntry>,
+        scopes: &[&str],
+        options: &TokenRequestOptions,
+    ) {
+        if entry.refreshing.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let credential = self.credential.clone();
+        let refresh_window = self.refresh_window;
+        let scopes: Vec<String> = scopes.iter().map(|scope| scope.to_string()).collect();
+        let options = options.clone();
+        runtime.spawn(async move {
+            {
+                let _refresh = entry.refresh.lock().await;
+                // a waiting caller may have refreshed the token in the meantime
+                let refreshed = entry
+                    .token()
+                    .filter(|token| token.expires_on > Utc::now() + refresh_window);
+                if refreshed.is_none() {
+                    let scopes: Vec<&str> = scopes.iter().map(String::as_str).collect();
+                    match credential.get_token_with_options(&scopes, &options).await {
+                        Ok(token) => entry.set_token(token),
+                        Err(error) => log::debug!("Failed to refresh token: {}", error),
+                    }
+                }
+            }
+            entry.refreshing.store(false, Ordering::SeqCst);
+        });
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenCredential for AutoRefreshingTokenCredential {
+    async fn get_token_with_options(
+        &self,
+        scopes: &[&str],
+        options: &TokenRequestOptions,
+    ) -> Result<TokenResponse, azure_core::Error> {
+        let entry = self.entry(CacheKey::new(scopes, options));
+
+        if options.claims.is_some() {
+            let _refresh = entry.refresh.lock().await;
+            let token = self
+                .credential
+                .get_token_with_options(scopes, options)
+                .await?;
+            entry.set_token(token.clone());
+            return Ok(token);
+        }
+
+        let now = Utc::now();
+        if let Some(token) = entry.token() {
+            if token.expires_on > now + self.refresh_window {
+                return Ok(token);
+            }
+            if token.expires_on > now {
+                if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+                    self.refresh_in_background(&runtime, entry.clone(), scopes, options);
+                    return Ok(token);
+                }
+                // still valid: refresh unless someone else already is
+                let _refresh = match entry.refresh.try_lock() {
+                    Some(refresh) => refresh,
+                    None => return Ok(token),
+                };
+                if let Some(refreshed) = entry.token() {
+                    if refreshed.expires_on > Utc::now() + self.refresh_window {
+                        return Ok(refreshed);
+                    }
+                }
+                return match self
+                    .credential
+                    .get_token_with_options(scopes, options)
+                    .await
+                {
+                    Ok(refreshed) => {
+                        entry.set_token(refreshed.clone());
+                        Ok(refreshed)
+                    }
+                    Err(error) => {
+                        log::debug!("Failed to refresh token, using the cached one");
+                        if token.expires_on > Utc::now() {
+                            Ok(token)
+                        } else {
+                            Err(error)
+                        }
+                    }
+                };
+            }
+        }
+
+        let _refresh = entry.refresh.lock().await;
+        // a concurrent caller may have refreshed the token while we waited
+        if let Some(token) = entry.token() {
+            if token.expires_on > Utc::now() + self.refresh_window {
+                return Ok(token);
+            }
+        }
+        let token = self
+            .credential
+            .get_token_with_options(scopes, options)
+            .await?;
+        entry.set_token(token.clone());
+        Ok(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use futures::FutureExt;
+    use oauth2::AccessToken;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::task::{Context, Poll};
+
+    /// Returns `Pending` once, letting concurrent futures make progress.
+    struct YieldNow(bool);
+
+    impl Future for YieldNow {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.0 {
+                Poll::Ready(())
+            } else {
+                self.0 = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Hands out tokens expiring after `lifetime`, named after the request count.
+    struct CountingCredential {
+        calls: AtomicUsize,
+        lifetime: Duration,
+        fail: bool,
+        hang: bool,
+    }
+
+    impl CountingCredential {
+        fn new(lifetime: Duration) -> Self {
+            Self {
+                calls: AtomicUsize::new(0),
+                lifetime,
+                fail: false,
+                hang: false,
+            }
+        }
+
+        fn calls(&self) -> usize {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl TokenCredential for CountingCredential {
+        async fn get_token_with_options(
+            &self,
+            scopes: &[&str],
+            _options: &TokenRequestOptions,
+        ) -> Result<TokenResponse, azure_core::Error> {
+            let calls = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            // let concurrent callers run before answering
+            YieldNow(false).await;
+            if self.hang && calls > 1 {
+                futures::future::pending::<()>().await;
+            }
+            if self.fail && calls > 1 {
+                return Err(azure_core::Error::GetTokenError(Box::new(
+                    std::io::Error::new(std::io::ErrorKind::Other, "failed"),
+                )));
+            }
+            Ok(TokenResponse::new(
+                AccessToken::new(format!("{}-{}", scopes.join(" "), calls)),
+                Utc::now() + self.lifetime,
+            ))
+        }
+    }
+
+    fn secret(token: TokenResponse) -> String {
+        token.token.secret().to_owned()
+    }
+
+    #[test]
+    fn caches_per_scope() {
+        let inner = Arc::new(CountingCredential::new(Duration::hours(1)));
+        let credential = AutoRefreshingTokenCredential::new(inner.clone());
+
+        let first = block_on(credential.get_token("vault")).unwrap();
+        let second = block_on(credential.get_token("vault")).unwrap();
+        let storage = block_on(credential.get_token("storage")).unwrap();
+
+        assert_eq!(secret(first), "vault/.default-1");
+        assert_eq!(secret(second), "vault/.default-1");
+        assert_eq!(secret(storage), "storage/.default-2");
+        assert_eq!(inner.calls(), 2);
+
+        credential.clear();
+        assert_eq!(
+            secret(block_on(credential.get_token("vault")).unwrap()),
+            "vault/.default-3"
+        );
+    }
+
+    #[test]
+    fn claims_bypass_cache() {
+        let inner = Arc::new(CountingCredential::new(Duration::hours(1)));
+        let credential = AutoRefreshingTokenCredential::new(inner.clone());
+
+        block_on(credential.get_token("vault")).unwrap();
+        let options = TokenRequestOptions::new().claims("{\"access_token\":{}}");
+        let challenged =
+            block_on(credential.get_token_with_options(&["vault/.default"], &options)).unwrap();
+        let cached = block_on(credential.get_token("vault")).unwrap();
+
+        assert_eq!(secret(challenged), "vault/.default-2");
+        assert_eq!(secret(cached), "vault/.default-2");
+        assert_eq!(inner.calls(), 2);
+    }
+
+    #[test]
+    fn coalesces_concurrent_requests() {
+        let inner = Arc::new(CountingCredential::new(Duration::hours(1)));
+        let credential = AutoRefreshingTokenCredential::new(inner.clone());
+
+        let (first, second) = block_on(futures::future::join(
+            credential.get_token("vault"),
+            credential.get_token("vault"),
+        ));
+
+        assert_eq!(secret(first.unwrap()), "vault/.default-1");
+        assert_eq!(secret(second.unwrap()), "vault/.default-1");
+        assert_eq!(inner.calls(), 1);
+    }
+
+    #[test]
+    fn refreshes_ahead_of_expiry() {
+        // every token is issued inside the refresh window
+        let inner = Arc::new(CountingCredential::new(Duration::minutes(2)));
+        let credential = AutoRefreshingTokenCredential::new(inner.clone());
+
+        block_on(credential.get_token("vault")).unwrap();
+        let (first, second) = block_on(futures::future::join(
+            credential.get_token("vault"),
+            credential.get_token("vault"),
+        ));
+
+        // the refreshing caller gets the new token, the other one the cached token
+        assert_eq!(secret(first.unwrap()), "vault/.default-2");
+        assert_eq!(secret(second.unwrap()), "vault/.default-1");
+        assert_eq!(inner.calls(), 2);
+    }
+
+    #[test]
+    fn failed_refresh_keeps_valid_token() {
+        let mut inner = CountingCredential::new(Duration::minutes(2));
+        inner.fail = true;
+        let inner = Arc::new(inner);
+        let credential = AutoRefreshingTokenCredential::new(inner.clone());
+
+        block_on(credential.get_token("vault")).unwrap();
+        let token = block_on(credential.get_token("vault")).unwrap();
+
+        assert_eq!(secret(token), "vault/.default-1");
+        assert_eq!(inner.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn refreshes_in_background() {
+        // every token is issued inside the refresh window
+        let inner = Arc::new(CountingCredential::new(Duration::minutes(2)));
+        let credential = AutoRefreshingTokenCredential::new(inner.clone());
+
+        credential.get_token("vault").await.unwrap();
+        let (first, second) =
+            futures::future::join(credential.get_token("vault"), credential.get_token("vault"))
+                .await;
+
+        // both callers get the cached token while a single refresh runs
+        assert_eq!(secret(first.unwrap()), "vault/.default-1");
+        assert_eq!(secret(second.unwrap()), "vault/.default-1");
+
+        let entry = credential.entry(CacheKey::new(
+            &["vault/.default"],
+            &TokenRequestOptions::default(),
+        ));
+        for _ in 0..1000 {
+            if entry.token().map(secret).as_deref() == Some("vault/.default-2") {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(secret(entry.token().unwrap()), "vault/.default-2");
+        assert_eq!(inner.calls(), 2);
+    }
+
+    #[test]
+    fn pending_refresh_does_not_block_other_callers() {
+        let mut inner = CountingCredential::new(Duration::minutes(2));
+        inner.hang = true;
+        let inner = Arc::new(inner);
+        let credential = AutoRefreshingTokenCredential::new(inner.clone());
+
+        block_on(credential.get_token("vault")).unwrap();
+        let mut refresh = Box::pin(credential.get_token("vault"));
+        assert!((&mut refresh).now_or_never().is_none());
+
+        let token = credential.get_token("vault").now_or_never().unwrap();
+        assert_eq!(secret(token.unwrap()), "vault/.default-1");
+        assert_eq!(inner.calls(), 2);
+    }
+}
@@ -0,0 +1,145 @@
+use azure_core::{TokenCredential, TokenRequestOptions, TokenResponse};
+use log::debug;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Tries a list of credentials in order until one of them provides a token.
+///
+/// The source that last provided a token is tried first on the following calls. When every
+/// source fails the error of each of them is returned in a `ChainedTokenCredentialError`.
+pub struct ChainedTokenCredential {
+    sources: Vec<Arc<dyn TokenCredential>>,
+    successful_source: AtomicUsize,
+}
+
+impl ChainedTokenCredential {
+    pub fn new(sources: Vec<Arc<dyn TokenCredential>>) -> Self {
+        Self {
+            sources,
+            successful_source: AtomicUsize::new(0),
+        }
+    }
+
+    /// The credentials of the chain, in the order they are tried.
+    pub fn sources(&self) -> &[Arc<dyn TokenCredential>] {
+        &self.sources
+    }
+}
+
+/// The errors of every source of a `ChainedTokenCredential`, in the order they were tried.
+#[derive(Debug)]
+pub struct ChainedTokenCredentialError {
+    errors: Vec<azure_core::Error>,
+}
+
+impl ChainedTokenCredentialError {
+    pub fn errors(&self) -> &[azure_core::Error] {
+        &self.errors
+    }
+}
+
+impl fmt::Display for ChainedTokenCredentialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.errors.is_empty() {
+            return write!(f, "No credential to get a token from");
+        }
+        write!(f, "No credential provided a token:")?;
+        for (index, error) in self.errors.iter().enumerate() {
+            write!(f, " {}) {}", index + 1, error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ChainedTokenCredentialError {}
+
+#[async_trait::async_trait]
+impl TokenCredential for ChainedTokenCredential {
+    async fn get_token_with_options(
+        &self,
+        scopes: &[&str],
+        options: &TokenRequestOptions,
+    ) -> Result<TokenResponse, azure_core::Error> {
+        let successful_source = self.successful_source.load(Ordering::Relaxed);
+        let order = std::iter::once(successful_source)
+            .chain((0..self.sources.len()).filter(|index| *index != successful_source));
+
+        let mut errors = Vec::new();
+        for index in order {
+            let source = match self.sources.get(index) {
+                Some(source) => source,
+                None => continue,
+            };
+            match source.get_token_with_options(scopes, options).await {
+                Ok(token) => {
+                    self.successful_source.store(index, Ordering::Relaxed);
+                    return Ok(token);
+                }
+                Err(error) => {
+                    debug!("Failed to get credentials: {:?}", error);
+                    errors.push(error);
+                }
+            }
+        }
+        Err(azure_core::Error::GetTokenError(Box::new(
+            ChainedTokenCredentialError { errors },
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use futures::executor::block_on;
+    use oauth2::AccessToken;
+
+    struct StaticCredential(Option<&'static str>);
+
+    #[async_trait::async_trait]
+    impl TokenCredential for StaticCredential {
+        async fn get_token_with_options(
+            &self,
+            _scopes: &[&str],
+            _options: &TokenRequestOptions,
+        ) -> Result<TokenResponse, azure_core::Error> {
+            match self.0 {
+                Some(token) => Ok(TokenResponse::new(
+                    AccessToken::new(token.to_owned()),
+                    Utc::now(),
+                )),
+                None => Err(azure_core::Error::GetTokenError(Box::new(
+                    std::io::Error::new(std::io::ErrorKind::Other, "unavailable"),
+                ))),
+            }
+        }
+    }
+
+    #[test]
+    fn first_successful_source() {
+        let chain = ChainedTokenCredential::new(vec![
+            Arc::new(StaticCredential(None)),
+            Arc::new(StaticCredential(Some("second"))),
+            Arc::new(StaticCredential(Some("third"))),
+        ]);
+        let token = block_on(chain.get_token("https://vault.azure.net")).unwrap();
+        assert_eq!(token.token.secret(), "second");
+        assert_eq!(chain.successful_source.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn aggregates_errors() {
+        let chain = ChainedTokenCredential::new(vec![
+            Arc::new(StaticCredential(None)),
+            Arc::new(StaticCredential(None)),
+        ]);
+        let error = match block_on(chain.get_token("https://vault.azure.net")) {
+            Err(azure_core::Error::GetTokenError(error)) => error,
+            other => panic!("unexpected result {:?}", other),
+        };
+        let error = error.downcast_ref::<ChainedTokenCredentialError>().unwrap();
+        assert_eq!(error.errors().len(), 2);
+        assert!(error.to_string().contains("2) "));
+    }
+}
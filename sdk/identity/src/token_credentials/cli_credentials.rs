@@ -1,5 +1,4 @@
-use super::TokenCredential;
-use azure_core::TokenResponse;
+use azure_core::{TokenCredential, TokenRequestOptions, TokenResponse};
 use chrono::{DateTime, Utc};
 use oauth2::AccessToken;
 use serde::Deserialize;
@@ -48,14 +47,17 @@ pub enum AzureCliCredentialError {
     CommandFailed(String),
     #[error("Azure CLI not installed")]
     NotInstalled,
+    #[error("Azure CLI tokens are requested for a single scope, got {0}")]
+    InvalidScopeCount(usize),
     #[error("Unknown error of kind: {0:?}")]
     UnknownError(ErrorKind),
 }
 
 impl AzureCliCredential {
-    /// Get an access token for an optional resource
+    /// Get an access token for an optional resource, from an optional tenant
     fn get_access_token(
         resource: Option<&str>,
+        tenant_id: Option<&str>,
     ) -> Result<CliTokenResponse, AzureCliCredentialError> {
         // on window az is a cmd and it should be called like this
         // see https://doc.rust-lang.org/nightly/std/process/struct.Command.html
@@ -77,6 +79,10 @@ impl AzureCliCredential {
             args.push("--resource");
             args.push(resource);
         }
+        if let Some(tenant_id) = tenant_id {
+            args.push("--tenant");
+            args.push(tenant_id);
+        }
 
         match Command::new(program).args(args).output() {
             Ok(az_output) if az_output.status.success() => {
@@ -100,34 +106,39 @@ impl AzureCliCredential {
 
     /// Returns the current subscription ID from the Azure CLI.
     pub fn get_subscription() -> Result<String, AzureCliCredentialError> {
-        let tr = Self::get_access_token(None)?;
+        let tr = Self::get_access_token(None, None)?;
         Ok(tr.subscription)
     }
 
     /// Returns the current tenant ID from the Azure CLI.
     pub fn get_tenant() -> Result<String, AzureCliCredentialError> {
-        let tr = Self::get_access_token(None)?;
+        let tr = Self::get_access_token(None, None)?;
         Ok(tr.tenant)
     }
 }
 
-#[async_trait::async_trait]
-impl TokenCredential for AzureCliCredential {
-    type Error = AzureCliCredentialError;
-    async fn get_token(&self, resource: &str) -> Result<TokenResponse, Self::Error> {
-        let tr = Self::get_access_token(Some(resource))?;
+impl AzureCliCredential {
+    fn get_cli_token(
+        scopes: &[&str],
+        options: &TokenRequestOptions,
+    ) -> Result<TokenResponse, AzureCliCredentialError> {
+        let resource = match scopes {
+            [scope] => azure_core::scope_to_resource(scope),
+            _ => return Err(AzureCliCredentialError::InvalidScopeCount(scopes.len())),
+        };
+        let tr = Self::get_access_token(Some(resource), options.tenant_id.as_deref())?;
         Ok(TokenResponse::new(tr.access_token, tr.expires_on))
     }
 }
 
 #[async_trait::async_trait]
-impl azure_core::TokenCredential for AzureCliCredential {
-    async fn get_token(
+impl TokenCredential for AzureCliCredential {
+    async fn get_token_with_options(
         &self,
-        resource: &str,
-    ) -> Result<azure_core::TokenResponse, azure_core::Error> {
-        TokenCredential::get_token(self, resource)
-            .await
+        scopes: &[&str],
+        options: &TokenRequestOptions,
+    ) -> Result<TokenResponse, azure_core::Error> {
+        Self::get_cli_token(scopes, options)
             .map_err(|error| azure_core::Error::GetTokenError(Box::new(error)))
     }
 }
@@ -0,0 +1,217 @@
+use super::TokenCredentialOptions;
+use azure_core::{TokenCredential, TokenRequestOptions, TokenResponse};
+use chrono::Utc;
+use futures::future::BoxFuture;
+use oauth2::{
+    basic::{BasicClient, BasicErrorResponseType},
+    reqwest::async_http_client,
+    AccessToken, AuthType, AuthUrl, Scope, StandardErrorResponse, TokenUrl,
+};
+use std::{future::Future, str, sync::Arc, time::Duration};
+use url::Url;
+
+const CLIENT_ASSERTION_TYPE: &str = "urn:ietf:params:oauth:client-assertion-type:jwt-bearer";
+
+type AssertionError = Box<dyn std::error::Error + Send + Sync>;
+type AssertionCallback =
+    Arc<dyn Fn() -> BoxFuture<'static, Result<String, AssertionError>> + Send + Sync>;
+
+/// Enables authentication to Azure Active Directory using a client assertion, such as a
+/// federated token issued by another identity provider.
+///
+/// The assertion is requested from the callback on every token exchange so short lived
+/// assertions can be renewed by the caller.
+///
+/// More information on federated identity credentials can be found here:
+/// https://docs.microsoft.com/azure/active-directory/develop/workload-identity-federation
+pub struct ClientAssertionCredential {
+    tenant_id: String,
+    client_id: oauth2::ClientId,
+    get_assertion: AssertionCallback,
+    options: TokenCredentialOptions,
+}
+
+impl ClientAssertionCredential {
+    pub fn new<F, Fut, E>(
+        tenant_id: String,
+        client_id: String,
+        get_assertion: F,
+        options: TokenCredentialOptions,
+    ) -> ClientAssertionCredential
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<String, E>> + Send + 'static,
+        E: Into<AssertionError>,
+    {
+        let get_assertion = move || -> BoxFuture<'static, Result<String, AssertionError>> {
+            let assertion = get_assertion();
+            Box::pin(async move { assertion.await.map_err(Into::into) })
+        };
+        ClientAssertionCredential {
+            tenant_id,
+            client_id: oauth2::ClientId::new(client_id),
+            get_assertion: Arc::new(get_assertion),
+            options,
+        }
+    }
+
+    fn options(&self) -> &TokenCredentialOptions {
+        &self.options
+    }
+}
+
+impl std::fmt::Debug for ClientAssertionCredential {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClientAssertionCredential")
+            .field("tenant_id", &self.tenant_id)
+            .field("client_id", &self.client_id)
+            .field("options", &self.options)
+            .finish()
+    }
+}
+
+#[non_exhaustive]
+#[derive(Debug, thiserror::Error)]
+pub enum ClientAssertionCredentialError {
+    #[error("Failed to get the client assertion: {0}")]
+    AssertionError(AssertionError),
+    #[error("Failed to construct token endpoint with tenant id {1}: {0}")]
+    FailedConstructTokenEndpoint(url::ParseError, String),
+    #[error("Failed to construct authorize endpoint with tenant id {1}: {0}")]
+    FailedConstructAuthorizeEndpoint(url::ParseError, String),
+    #[error("Request token error: {0}")]
+    RequestTokenError(
+        oauth2::RequestTokenError<
+            oauth2::reqwest::Error<reqwest::Error>,
+            StandardErrorResponse<BasicErrorResponseType>,
+        >,
+    ),
+}
+
+impl ClientAssertionCredential {
+    async fn request_token(
+        &self,
+        scopes: &[&str],
+        request_options: &TokenRequestOptions,
+    ) -> Result<TokenResponse, ClientAssertionCredentialError> {
+        let options = self.options();
+        let authority_host = options.authority_host();
+        let tenant_id = request_options
+            .tenant_id
+            .as_deref()
+            .unwrap_or(&self.tenant_id);
+
+        let token_url = TokenUrl::from_url(
+            Url::parse(&format!(
+                "{}/{}/oauth2/v2.0/token",
+                authority_host, tenant_id
+            ))
+            .map_err(|error| {
+                ClientAssertionCredentialError::FailedConstructTokenEndpoint(
+                    error,
+                    tenant_id.to_owned(),
+                )
+            })?,
+        );
+
+        let auth_url = AuthUrl::from_url(
+            Url::parse(&format!(
+                "{}/{}/oauth2/v2.0/authorize",
+                authority_host, tenant_id
+            ))
+            .map_err(|error| {
+                ClientAssertionCredentialError::FailedConstructAuthorizeEndpoint(
+                    error,
+                    tenant_id.to_owned(),
+                )
+            })?,
+        );
+
+        let client_assertion = (self.get_assertion)()
+            .await
+            .map_err(ClientAssertionCredentialError::AssertionError)?;
+
+        let client = BasicClient::new(self.client_id.clone(), None, auth_url, Some(token_url))
+            .set_auth_type(AuthType::RequestBody);
+
+        let mut request = client
+            .exchange_client_credentials()
+            .add_scopes(scopes.iter().map(|scope| Scope::new(scope.to_string())))
+            .add_extra_param("client_assertion_type", CLIENT_ASSERTION_TYPE)
+            .add_extra_param("client_assertion", client_assertion);
+        if let Some(claims) = &request_options.claims {
+            request = request.add_extra_param("claims", claims.clone());
+        }
+
+        let token_result = request
+            .request_async(async_http_client)
+            .await
+            .map(|r| {
+                use oauth2::TokenResponse as _;
+                TokenResponse::new(
+                    AccessToken::new(r.access_token().secret().to_owned()),
+                    Utc::now()
+                        + chrono::Duration::from_std(
+                            r.expires_in().unwrap_or_else(|| Duration::from_secs(0)),
+                        )
+                        .unwrap(),
+                )
+            })
+            .map_err(ClientAssertionCredentialError::RequestTokenError)?;
+
+        Ok(token_result)
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenCredential for ClientAssertionCredential {
+    async fn get_token_with_options(
+        &self,
+        scopes: &[&str],
+        options: &TokenRequestOptions,
+    ) -> Result<TokenResponse, azure_core::Error> {
+        self.request_token(scopes, options)
+            .await
+            .map_err(|error| azure_core::Error::GetTokenError(Box::new(error)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn assertion_error_is_surfaced() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let credential = ClientAssertionCredential::new(
+            "tenant".to_owned(),
+            "client-id".to_owned(),
+            {
+                let calls = calls.clone();
+                move || {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    async {
+                        Err::<String, _>(std::io::Error::new(
+                            std::io::ErrorKind::NotFound,
+                            "token file not found",
+                        ))
+                    }
+                }
+            },
+            TokenCredentialOptions::default(),
+        );
+
+        for expected_calls in 1..=2 {
+            let result = futures::executor::block_on(credential.request_token(
+                &["https://storage.azure.com/.default"],
+                &TokenRequestOptions::default(),
+            ));
+            assert!(matches!(
+                result,
+                Err(ClientAssertionCredentialError::AssertionError(_))
+            ));
+            assert_eq!(calls.load(Ordering::SeqCst), expected_calls);
+        }
+    }
+}
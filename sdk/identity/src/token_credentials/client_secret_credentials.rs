@@ -1,5 +1,4 @@
-use super::TokenCredential;
-use azure_core::TokenResponse;
+use azure_core::{TokenCredential, TokenRequestOptions, TokenResponse};
 use chrono::Utc;
 use oauth2::{
     basic::{BasicClient, BasicErrorResponseType},
@@ -109,23 +108,28 @@ pub enum ClientSecretCredentialError {
     ),
 }
 
-#[async_trait::async_trait]
-impl TokenCredential for ClientSecretCredential {
-    type Error = ClientSecretCredentialError;
-
-    async fn get_token(&self, resource: &str) -> Result<TokenResponse, Self::Error> {
+impl ClientSecretCredential {
+    async fn request_token(
+        &self,
+        scopes: &[&str],
+        request_options: &TokenRequestOptions,
+    ) -> Result<TokenResponse, ClientSecretCredentialError> {
         let options = self.options();
         let authority_host = options.authority_host();
+        let tenant_id = request_options
+            .tenant_id
+            .as_deref()
+            .unwrap_or(&self.tenant_id);
 
         let token_url = TokenUrl::from_url(
             Url::parse(&format!(
                 "{}/{}/oauth2/v2.0/token",
-                authority_host, self.tenant_id
+                authority_host, tenant_id
             ))
             .map_err(|error| {
                 ClientSecretCredentialError::FailedConstructTokenEndpoint(
                     error,
-                    self.tenant_id.clone(),
+                    tenant_id.to_owned(),
                 )
             })?,
         );
@@ -133,12 +137,12 @@ impl TokenCredential for ClientSecretCredential {
         let auth_url = AuthUrl::from_url(
             Url::parse(&format!(
                 "{}/{}/oauth2/v2.0/authorize",
-                authority_host, self.tenant_id
+                authority_host, tenant_id
             ))
             .map_err(|error| {
                 ClientSecretCredentialError::FailedConstructAuthorizeEndpoint(
                     error,
-                    self.tenant_id.clone(),
+                    tenant_id.to_owned(),
                 )
             })?,
         );
@@ -151,9 +155,14 @@ impl TokenCredential for ClientSecretCredential {
         )
         .set_auth_type(AuthType::RequestBody);
 
-        let token_result = client
+        let mut request = client
             .exchange_client_credentials()
-            .add_scope(Scope::new(format!("{}/.default", resource)))
+            .add_scopes(scopes.iter().map(|scope| Scope::new(scope.to_string())));
+        if let Some(claims) = &request_options.claims {
+            request = request.add_extra_param("claims", claims.clone());
+        }
+
+        let token_result = request
             .request_async(async_http_client)
             .await
             .map(|r| {
@@ -174,12 +183,13 @@ impl TokenCredential for ClientSecretCredential {
 }
 
 #[async_trait::async_trait]
-impl azure_core::TokenCredential for ClientSecretCredential {
-    async fn get_token(
+impl TokenCredential for ClientSecretCredential {
+    async fn get_token_with_options(
         &self,
-        resource: &str,
-    ) -> Result<azure_core::TokenResponse, azure_core::Error> {
-        TokenCredential::get_token(self, resource)
+        scopes: &[&str],
+        options: &TokenRequestOptions,
+    ) -> Result<TokenResponse, azure_core::Error> {
+        self.request_token(scopes, options)
             .await
             .map_err(|error| azure_core::Error::GetTokenError(Box::new(error)))
     }
@@ -1,15 +1,51 @@
 use super::{
-    AzureCliCredential, EnvironmentCredential, ManagedIdentityCredential, TokenCredential,
+    AzureCliCredential, ChainedTokenCredential, EnvironmentCredential, ManagedIdentityCredential,
+    WorkloadIdentityCredential,
 };
-use azure_core::TokenResponse;
-use log::debug;
+use azure_core::{TokenCredential, TokenRequestOptions, TokenResponse};
+use std::sync::Arc;
 
-#[derive(Debug, Default)]
-/// Provides a mechanism of selectively disabling credentials used for a `DefaultCredential` instance
+/// Provides a mechanism of selectively disabling credentials used for a `DefaultCredential` instance,
+/// and of adding custom ones
 pub struct DefaultCredentialBuilder {
     include_environment_credential: bool,
+    include_workload_identity_credential: bool,
     include_managed_identity_credential: bool,
     include_cli_credential: bool,
+    additional_sources: Vec<Arc<dyn TokenCredential>>,
+}
+
+impl Default for DefaultCredentialBuilder {
+    fn default() -> Self {
+        Self {
+            include_environment_credential: true,
+            include_workload_identity_credential: true,
+            include_managed_identity_credential: true,
+            include_cli_credential: true,
+            additional_sources: Vec::new(),
+        }
+    }
+}
+
+impl std::fmt::Debug for DefaultCredentialBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DefaultCredentialBuilder")
+            .field(
+                "include_environment_credential",
+                &self.include_environment_credential,
+            )
+            .field(
+                "include_workload_identity_credential",
+                &self.include_workload_identity_credential,
+            )
+            .field(
+                "include_managed_identity_credential",
+                &self.include_managed_identity_credential,
+            )
+            .field("include_cli_credential", &self.include_cli_credential)
+            .field("additional_sources", &self.additional_sources.len())
+            .finish()
+    }
 }
 
 impl DefaultCredentialBuilder {
@@ -24,6 +60,12 @@ impl DefaultCredentialBuilder {
         self
     }
 
+    /// Exclude using workload identity credentials
+    pub fn exclude_workload_identity_credential(&mut self) -> &mut Self {
+        self.include_workload_identity_credential = false;
+        self
+    }
+
     /// Exclude using credentials from the cli
     pub fn exclude_cli_credential(&mut self) -> &mut Self {
         self.include_cli_credential = false;
@@ -36,125 +78,102 @@ impl DefaultCredentialBuilder {
         self
     }
 
+    /// Add a custom credential, tried after the built-in ones
+    pub fn add_source(&mut self, source: Arc<dyn TokenCredential>) -> &mut Self {
+        self.additional_sources.push(source);
+        self
+    }
+
     pub fn build(&self) -> DefaultCredential {
-        let source_count = self.include_cli_credential as usize
+        let source_count = self.include_environment_credential as usize
+            + self.include_workload_identity_credential as usize
+            + self.include_managed_identity_credential as usize
             + self.include_cli_credential as usize
-            + self.include_managed_identity_credential as usize;
-        let mut sources = Vec::<DefaultCredentialEnum>::with_capacity(source_count);
+            + self.additional_sources.len();
+        let mut sources = Vec::<Arc<dyn TokenCredential>>::with_capacity(source_count);
         if self.include_environment_credential {
-            sources.push(DefaultCredentialEnum::Environment(
-                EnvironmentCredential::default(),
-            ));
+            sources.push(Arc::new(EnvironmentCredential::default()));
+        }
+        if self.include_workload_identity_credential {
+            sources.push(Arc::new(WorkloadIdentityCredential::default()));
         }
         if self.include_managed_identity_credential {
-            sources.push(DefaultCredentialEnum::ManagedIdentity(
-                ManagedIdentityCredential {},
-            ))
+            sources.push(Arc::new(ManagedIdentityCredential::default()));
         }
         if self.include_cli_credential {
-            sources.push(DefaultCredentialEnum::AzureCli(AzureCliCredential {}));
+            sources.push(Arc::new(AzureCliCredential {}));
         }
+        sources.extend(self.additional_sources.iter().cloned());
         DefaultCredential::with_sources(sources)
     }
 }
 
-#[non_exhaustive]
-#[derive(Debug, thiserror::Error)]
-pub enum DefaultCredentialError {
-    #[error("Error getting token credential from Azure CLI: {0}")]
-    AzureCliCredentialError(#[from] super::AzureCliCredentialError),
-    #[error("Error getting environment credential: {0}")]
-    EnvironmentCredentialError(#[from] super::EnvironmentCredentialError),
-    #[error("Error getting managed identity credential: {0}")]
-    ManagedIdentityCredentialError(#[from] super::ManagedIdentityCredentialError),
-    #[error("End of default list")]
-    EndOfDefaultList,
-}
-
-/// Types of TokenCredential supported by DefaultCredential
-pub enum DefaultCredentialEnum {
-    Environment(EnvironmentCredential),
-    ManagedIdentity(ManagedIdentityCredential),
-    AzureCli(AzureCliCredential),
-}
-
-#[async_trait::async_trait]
-impl TokenCredential for DefaultCredentialEnum {
-    type Error = DefaultCredentialError;
-
-    async fn get_token(&self, resource: &str) -> Result<TokenResponse, Self::Error> {
-        match self {
-            DefaultCredentialEnum::Environment(credential) => credential
-                .get_token(resource)
-                .await
-                .map_err(DefaultCredentialError::EnvironmentCredentialError),
-            DefaultCredentialEnum::ManagedIdentity(credential) => credential
-                .get_token(resource)
-                .await
-                .map_err(DefaultCredentialError::ManagedIdentityCredentialError),
-            DefaultCredentialEnum::AzureCli(credential) => credential
-                .get_token(resource)
-                .await
-                .map_err(DefaultCredentialError::AzureCliCredentialError),
-        }
-    }
-}
-
 /// Provides a default `TokenCredential` authentication flow for applications that will be deployed to Azure.
 ///
 /// The following credential types if enabled will be tried, in order:
 /// - EnvironmentCredential
+/// - WorkloadIdentityCredential
 /// - ManagedIdentityCredential
 /// - AzureCliCredential
+///
 /// Consult the documentation of these credential types for more information on how they attempt authentication.
+///
+/// The sources are tried through a `ChainedTokenCredential`: the source that last provided a token
+/// is tried first on the following calls, and the errors of every source are returned when all fail.
 pub struct DefaultCredential {
-    sources: Vec<DefaultCredentialEnum>,
+    chain: ChainedTokenCredential,
 }
 
 impl DefaultCredential {
-    pub fn with_sources(sources: Vec<DefaultCredentialEnum>) -> Self {
-        DefaultCredential { sources }
+    pub fn with_sources(sources: Vec<Arc<dyn TokenCredential>>) -> Self {
+        DefaultCredential {
+            chain: ChainedTokenCredential::new(sources),
+        }
     }
 }
 
 impl Default for DefaultCredential {
     fn default() -> Self {
-        DefaultCredential {
-            sources: vec![
-                DefaultCredentialEnum::Environment(EnvironmentCredential::default()),
-                DefaultCredentialEnum::ManagedIdentity(ManagedIdentityCredential {}),
-                DefaultCredentialEnum::AzureCli(AzureCliCredential {}),
-            ],
-        }
+        DefaultCredentialBuilder::new().build()
     }
 }
 
 #[async_trait::async_trait]
 impl TokenCredential for DefaultCredential {
-    type Error = DefaultCredentialError;
     /// Try to fetch a token using each of the credential sources until one succeeds
-    async fn get_token(&self, resource: &str) -> Result<TokenResponse, Self::Error> {
-        for source in &self.sources {
-            let token_res = source.get_token(resource).await;
-
-            if let Ok(token) = token_res {
-                return Ok(token);
-            } else {
-                debug!("Failed to get credentials: {:?}", token_res.err().unwrap());
-            }
-        }
-        Err(DefaultCredentialError::EndOfDefaultList)
+    async fn get_token_with_options(
+        &self,
+        scopes: &[&str],
+        options: &TokenRequestOptions,
+    ) -> Result<TokenResponse, azure_core::Error> {
+        self.chain.get_token_with_options(scopes, options).await
     }
 }
 
-#[async_trait::async_trait]
-impl azure_core::TokenCredential for DefaultCredential {
-    async fn get_token(
-        &self,
-        resource: &str,
-    ) -> Result<azure_core::TokenResponse, azure_core::Error> {
-        TokenCredential::get_token(self, resource)
-            .await
-            .map_err(|error| azure_core::Error::GetTokenError(Box::new(error)))
+#[allow(deprecated)]
+pub use deprecated::DefaultCredentialError;
+
+mod deprecated {
+    // the derived implementations use the deprecated type
+    #![allow(deprecated)]
+
+    /// The error of each source of a `DefaultCredential`, from before it was built on a
+    /// `ChainedTokenCredential`.
+    #[deprecated(
+        note = "`DefaultCredential` fails with a `ChainedTokenCredentialError`, holding the error of every source"
+    )]
+    #[non_exhaustive]
+    #[derive(Debug, thiserror::Error)]
+    pub enum DefaultCredentialError {
+        #[error("Error getting token credential from Azure CLI: {0}")]
+        AzureCliCredentialError(#[from] super::super::AzureCliCredentialError),
+        #[error("Error getting environment credential: {0}")]
+        EnvironmentCredentialError(#[from] super::super::EnvironmentCredentialError),
+        #[error("Error getting workload identity credential: {0}")]
+        WorkloadIdentityCredentialError(#[from] super::super::WorkloadIdentityCredentialError),
+        #[error("Error getting managed identity credential: {0}")]
+        ManagedIdentityCredentialError(#[from] super::super::ManagedIdentityCredentialError),
+        #[error("End of default list")]
+        EndOfDefaultList,
     }
 }
@@ -1,5 +1,8 @@
-use super::{ClientSecretCredential, TokenCredential, TokenCredentialOptions};
-use azure_core::TokenResponse;
+#[cfg(feature = "client_certificate")]
+use super::{ClientCertificateCredential, ClientCertificateCredentialOptions};
+use super::{ClientSecretCredential, TokenCredentialOptions};
+use azure_core::{TokenCredential, TokenRequestOptions, TokenResponse};
+use std::sync::Arc;
 
 const AZURE_TENANT_ID_ENV_KEY: &str = "AZURE_TENANT_ID";
 const AZURE_CLIENT_ID_ENV_KEY: &str = "AZURE_CLIENT_ID";
@@ -7,8 +10,12 @@ const AZURE_CLIENT_SECRET_ENV_KEY: &str = "AZURE_CLIENT_SECRET";
 const AZURE_USERNAME_ENV_KEY: &str = "AZURE_USERNAME";
 const AZURE_PASSWORD_ENV_KEY: &str = "AZURE_PASSWORD";
 const AZURE_CLIENT_CERTIFICATE_PATH_ENV_KEY: &str = "AZURE_CLIENT_CERTIFICATE_PATH";
+#[cfg(feature = "client_certificate")]
+const AZURE_CLIENT_CERTIFICATE_PASSWORD_ENV_KEY: &str = "AZURE_CLIENT_CERTIFICATE_PASSWORD";
+#[cfg(feature = "client_certificate")]
+const AZURE_CLIENT_SEND_CERTIFICATE_CHAIN_ENV_KEY: &str = "AZURE_CLIENT_SEND_CERTIFICATE_CHAIN";
 
-/// Enables authentication to Azure Active Directory using client secret, or a username and password.
+/// Enables authentication to Azure Active Directory using a client secret or certificate, or a username and password.
 ///
 /// Details configured in the following environment variables:
 ///
@@ -17,25 +24,31 @@ const AZURE_CLIENT_CERTIFICATE_PATH_ENV_KEY: &str = "AZURE_CLIENT_CERTIFICATE_PA
 /// | `AZURE_TENANT_ID`                   | The Azure Active Directory tenant(directory) ID. |
 /// | `AZURE_CLIENT_ID`                   | The client(application) ID of an App Registration in the tenant. |
 /// | `AZURE_CLIENT_SECRET`               | A client secret that was generated for the App Registration. |
+/// | `AZURE_CLIENT_CERTIFICATE_PATH`     | The path to a PEM or PKCS#12 certificate, including its private key, uploaded to the App Registration. |
+/// | `AZURE_CLIENT_CERTIFICATE_PASSWORD` | (optional) The password protecting the certificate file. |
+/// | `AZURE_CLIENT_SEND_CERTIFICATE_CHAIN` | (optional) Set to `true` or `1` to send the certificate chain, enabling subject name/issuer authentication. |
 ///
-/// This credential ultimately uses a `ClientSecretCredential` to perform the authentication using
-/// these details.
-/// Please consult the documentation of that class for more details.
+/// This credential ultimately uses a `ClientSecretCredential` or a `ClientCertificateCredential` to perform
+/// the authentication using these details.
+/// Please consult the documentation of those classes for more details.
+///
+/// The environment is read, and the certificate file loaded, once when the credential is created.
+/// Certificates require the `client_certificate` feature.
 pub struct EnvironmentCredential {
-    options: TokenCredentialOptions,
+    credential: Result<Arc<dyn TokenCredential>, Arc<EnvironmentCredentialError>>,
 }
 
 impl EnvironmentCredential {
     pub fn new(options: TokenCredentialOptions) -> Self {
-        Self { options }
+        Self {
+            credential: Self::credential(options).map(Arc::from).map_err(Arc::new),
+        }
     }
 }
 
 impl Default for EnvironmentCredential {
     fn default() -> Self {
-        Self {
-            options: TokenCredentialOptions::default(),
-        }
+        Self::new(TokenCredentialOptions::default())
     }
 }
 
@@ -54,15 +67,23 @@ pub enum EnvironmentCredentialError {
     MissingClientId(std::env::VarError),
     #[error("No valid environment credential providers")]
     NoValid,
+    #[error("Failed to read the certificate file {1}: {0}")]
+    ReadCertificateError(std::io::Error, String),
+    #[cfg(feature = "client_certificate")]
     #[error(transparent)]
-    ClientSecretCredentialError(super::ClientSecretCredentialError),
+    ClientCertificateCredentialError(super::ClientCertificateCredentialError),
+    #[error(
+        "A certificate is set in {} but the client_certificate feature is disabled",
+        AZURE_CLIENT_CERTIFICATE_PATH_ENV_KEY
+    )]
+    CertificateNotSupported,
 }
 
-#[async_trait::async_trait]
-impl TokenCredential for EnvironmentCredential {
-    type Error = EnvironmentCredentialError;
-
-    async fn get_token(&self, resource: &str) -> Result<TokenResponse, Self::Error> {
+impl EnvironmentCredential {
+    /// Builds the credential configured by the environment.
+    fn credential(
+        options: TokenCredentialOptions,
+    ) -> Result<Box<dyn TokenCredential>, EnvironmentCredentialError> {
         let tenant_id = std::env::var(AZURE_TENANT_ID_ENV_KEY)
             .map_err(EnvironmentCredentialError::MissingTenantId)?;
         let client_id = std::env::var(AZURE_CLIENT_ID_ENV_KEY)
@@ -74,36 +95,65 @@ impl TokenCredential for EnvironmentCredential {
         let client_certificate_path = std::env::var(AZURE_CLIENT_CERTIFICATE_PATH_ENV_KEY);
 
         if let Ok(client_secret) = client_secret {
-            let credential = ClientSecretCredential::new(
-                tenant_id,
-                client_id,
-                client_secret,
-                self.options.clone(),
-            );
-            return credential
-                .get_token(resource)
-                .await
-                .map_err(EnvironmentCredentialError::ClientSecretCredentialError);
+            let credential =
+                ClientSecretCredential::new(tenant_id, client_id, client_secret, options);
+            return Ok(Box::new(credential));
         } else if username.is_ok() && password.is_ok() {
             // Could use multiple if-let with #![feature(let_chains)] once stabilised - see https://github.com/rust-lang/rust/issues/53667
             // TODO: username & password credential
-        } else if let Ok(_path) = client_certificate_path {
-            // TODO: client certificate credential
-            todo!()
+        } else if let Ok(path) = client_certificate_path {
+            return certificate_credential(tenant_id, client_id, path, options);
         }
 
         Err(EnvironmentCredentialError::NoValid)
     }
 }
 
+#[cfg(feature = "client_certificate")]
+fn certificate_credential(
+    tenant_id: String,
+    client_id: String,
+    path: String,
+    options: TokenCredentialOptions,
+) -> Result<Box<dyn TokenCredential>, EnvironmentCredentialError> {
+    let certificate = std::fs::read(&path)
+        .map_err(|error| EnvironmentCredentialError::ReadCertificateError(error, path))?;
+    let certificate_password = std::env::var(AZURE_CLIENT_CERTIFICATE_PASSWORD_ENV_KEY).ok();
+    let send_certificate_chain = std::env::var(AZURE_CLIENT_SEND_CERTIFICATE_CHAIN_ENV_KEY)
+        .map(|value| value == "1" || value.eq_ignore_ascii_case("true"))
+        .unwrap_or(false);
+
+    let credential = ClientCertificateCredential::new(
+        tenant_id,
+        client_id,
+        certificate,
+        certificate_password,
+        ClientCertificateCredentialOptions::new(options, send_certificate_chain),
+    )
+    .map_err(EnvironmentCredentialError::ClientCertificateCredentialError)?;
+    Ok(Box::new(credential))
+}
+
+#[cfg(not(feature = "client_certificate"))]
+fn certificate_credential(
+    _tenant_id: String,
+    _client_id: String,
+    _path: String,
+    _options: TokenCredentialOptions,
+) -> Result<Box<dyn TokenCredential>, EnvironmentCredentialError> {
+    Err(EnvironmentCredentialError::CertificateNotSupported)
+}
+
 #[async_trait::async_trait]
-impl azure_core::TokenCredential for EnvironmentCredential {
-    async fn get_token(
+impl TokenCredential for EnvironmentCredential {
+    async fn get_token_with_options(
         &self,
-        resource: &str,
-    ) -> Result<azure_core::TokenResponse, azure_core::Error> {
-        TokenCredential::get_token(self, resource)
-            .await
-            .map_err(|error| azure_core::Error::GetTokenError(Box::new(error)))
+        scopes: &[&str],
+        options: &TokenRequestOptions,
+    ) -> Result<TokenResponse, azure_core::Error> {
+        match &self.credential {
+            Ok(credential) => credential.get_token_with_options(scopes, options).await,
+            Err(error) => Err(azure_core::Error::GetTokenError(Box::new(error.clone()))),
+        }
     }
 }
@@ -1,67 +1,443 @@
-use super::TokenCredential;
-use azure_core::TokenResponse;
+use azure_core::{TokenCredential, TokenRequestOptions, TokenResponse};
 use chrono::{DateTime, Utc};
 use oauth2::AccessToken;
+use reqwest::{header, StatusCode};
 use serde::Deserialize;
+use sha1::{Digest, Sha1};
+use std::path::{Path, PathBuf};
 use std::str;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 use url::Url;
 
-const MSI_ENDPOINT_ENV_KEY: &str = "IDENTITY_ENDPOINT";
-const MSI_SECRET_ENV_KEY: &str = "IDENTITY_HEADER";
-const MSI_API_VERSION: &str = "2019-08-01";
+const IDENTITY_ENDPOINT_ENV_KEY: &str = "IDENTITY_ENDPOINT";
+const IDENTITY_HEADER_ENV_KEY: &str = "IDENTITY_HEADER";
+const IDENTITY_SERVER_THUMBPRINT_ENV_KEY: &str = "IDENTITY_SERVER_THUMBPRINT";
+const IMDS_ENDPOINT_ENV_KEY: &str = "IMDS_ENDPOINT";
+const MSI_ENDPOINT_ENV_KEY: &str = "MSI_ENDPOINT";
+
+const APP_SERVICE_API_VERSION: &str = "2019-08-01";
+const SERVICE_FABRIC_API_VERSION: &str = "2019-07-01-preview";
+const AZURE_ARC_API_VERSION: &str = "2019-11-01";
+const IMDS_API_VERSION: &str = "2018-02-01";
+const IMDS_ENDPOINT: &str = "http://169.254.169.254/metadata/identity/oauth2/token";
+/// The directory where the Azure Arc agent writes its key files, on Linux.
+const AZURE_ARC_KEY_DIRECTORY: &str = "/var/opt/azcmagent/tokens";
+/// The largest key file the Azure Arc agent writes.
+const AZURE_ARC_MAX_KEY_SIZE: u64 = 4096;
+/// How long to wait for IMDS to accept the connection before deciding it is not there.
+const DEFAULT_IMDS_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Identifies a user-assigned managed identity.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ManagedIdentityId {
+    /// The client(application) ID of the identity.
+    ClientId(String),
+    /// The object(principal) ID of the identity.
+    ObjectId(String),
+    /// The Azure resource ID of the identity.
+    ResourceId(String),
+}
 
 /// Attempts authentication using a managed identity that has been assigned to the deployment environment.
 ///
-/// This authentication type works in Azure VMs, App Service and Azure Functions applications, as well as the Azure Cloud Shell
+/// The hosting environment is detected from the variables it sets: App Service and Azure Functions,
+/// Service Fabric, Azure Arc and the Azure Cloud Shell are supported, falling back to the
+/// Instance Metadata Service (IMDS) available to Azure VMs and AKS nodes.
+/// A user-assigned identity can be selected with [`ManagedIdentityCredential::user_assigned`],
+/// otherwise the system-assigned identity is used.
 ///
 /// Built up from docs at [https://docs.microsoft.com/azure/app-service/overview-managed-identity#using-the-rest-protocol](https://docs.microsoft.com/azure/app-service/overview-managed-identity#using-the-rest-protocol)
-pub struct ManagedIdentityCredential;
+/// and [https://docs.microsoft.com/azure/active-directory/managed-identities-azure-resources/how-to-use-vm-token](https://docs.microsoft.com/azure/active-directory/managed-identities-azure-resources/how-to-use-vm-token)
+#[derive(Debug, Clone)]
+pub struct ManagedIdentityCredential {
+    identity: Option<ManagedIdentityId>,
+    imds_timeout: Duration,
+}
+
+impl Default for ManagedIdentityCredential {
+    fn default() -> Self {
+        Self {
+            identity: None,
+            imds_timeout: DEFAULT_IMDS_TIMEOUT,
+        }
+    }
+}
+
+impl ManagedIdentityCredential {
+    /// Create a credential using the system-assigned identity.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a credential using the given user-assigned identity.
+    pub fn user_assigned(identity: ManagedIdentityId) -> Self {
+        Self {
+            identity: Some(identity),
+            ..Self::default()
+        }
+    }
+
+    /// Sets how long to wait when connecting to IMDS. The default is one second, so that
+    /// a `DefaultCredential` fails fast when not running in Azure.
+    pub fn with_imds_timeout(mut self, imds_timeout: Duration) -> Self {
+        self.imds_timeout = imds_timeout;
+        self
+    }
+
+    /// The user-assigned identity, `None` for the system-assigned one.
+    pub fn identity(&self) -> Option<&ManagedIdentityId> {
+        self.identity.as_ref()
+    }
+}
+
+/// The managed identity endpoint available in the hosting environment.
+#[derive(Debug, Clone, PartialEq)]
+enum ManagedIdentitySource {
+    AppService {
+        endpoint: String,
+        header: String,
+    },
+    ServiceFabric {
+        endpoint: String,
+        header: String,
+        thumbprint: String,
+    },
+    AzureArc {
+        endpoint: String,
+    },
+    CloudShell {
+        endpoint: String,
+    },
+    Imds,
+}
+
+impl ManagedIdentitySource {
+    fn from_env() -> Self {
+        Self::detect(|name| std::env::var(name).ok())
+    }
+
+    fn detect(var: impl Fn(&str) -> Option<String>) -> Self {
+        match (
+            var(IDENTITY_ENDPOINT_ENV_KEY),
+            var(IDENTITY_HEADER_ENV_KEY),
+            var(MSI_ENDPOINT_ENV_KEY),
+        ) {
+            (Some(endpoint), Some(header), _) => match var(IDENTITY_SERVER_THUMBPRINT_ENV_KEY) {
+                Some(thumbprint) => Self::ServiceFabric {
+                    endpoint,
+                    header,
+                    thumbprint,
+                },
+                None => Self::AppService { endpoint, header },
+            },
+            (Some(endpoint), None, _) if var(IMDS_ENDPOINT_ENV_KEY).is_some() => {
+                Self::AzureArc { endpoint }
+            }
+            (None, _, Some(endpoint)) => Self::CloudShell { endpoint },
+            _ => Self::Imds,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::AppService { .. } => "App Service",
+            Self::ServiceFabric { .. } => "Service Fabric",
+            Self::AzureArc { .. } => "Azure Arc",
+            Self::CloudShell { .. } => "Cloud Shell",
+            Self::Imds => "IMDS",
+        }
+    }
+}
 
 #[non_exhaustive]
 #[derive(Debug, thiserror::Error)]
 pub enum ManagedIdentityCredentialError {
     #[error("Error parsing url for MSI endpoint: {0}")]
     MsiEndpointParseUrlError(url::ParseError),
-    #[error(
-        "Missing MSI secret set in {} environment variable",
-        MSI_SECRET_ENV_KEY
-    )]
-    MissingMsiSecret(std::env::VarError),
+    #[error("Managed identity tokens are requested for a single scope, got {0}")]
+    InvalidScopeCount(usize),
+    #[error("User-assigned identities cannot be selected at runtime in {0}")]
+    UserAssignedNotSupported(&'static str),
+    #[error("Managed identity endpoint IMDS is unavailable: {0}")]
+    ImdsUnavailable(reqwest::Error),
+    #[error("Error building the HTTP client: {0}")]
+    ClientBuildError(reqwest::Error),
     #[error("Refresh token send error: {0}")]
     SendError(reqwest::Error),
     #[error("Error getting text for refresh token: {0}")]
     TextError(reqwest::Error),
+    #[error("Managed identity endpoint returned {0}: {1}")]
+    ErrorResponse(StatusCode, String),
+    #[error("Azure Arc challenge is missing or invalid: {0}")]
+    InvalidArcChallenge(String),
+    #[error("Error reading the Azure Arc key file: {0}")]
+    ReadArcKeyError(std::io::Error),
     #[error("Error deserializing refresh token: {0}")]
     DeserializeError(serde_json::Error),
 }
 
-#[async_trait::async_trait]
-impl TokenCredential for ManagedIdentityCredential {
-    type Error = ManagedIdentityCredentialError;
+impl ManagedIdentityCredential {
+    /// The query parameter selecting the user-assigned identity, named as each endpoint expects.
+    fn identity_query(&self, source: &ManagedIdentitySource) -> Option<(&'static str, &str)> {
+        let identity = self.identity.as_ref()?;
+        Some(match (source, identity) {
+            (ManagedIdentitySource::AppService { .. }, ManagedIdentityId::ObjectId(id)) => {
+                ("principal_id", id)
+            }
+            (ManagedIdentitySource::AppService { .. }, ManagedIdentityId::ResourceId(id)) => {
+                ("mi_res_id", id)
+            }
+            (_, ManagedIdentityId::ClientId(id)) => ("client_id", id),
+            (_, ManagedIdentityId::ObjectId(id)) => ("object_id", id),
+            (_, ManagedIdentityId::ResourceId(id)) => ("msi_res_id", id),
+        })
+    }
 
-    async fn get_token(&self, resource: &str) -> Result<TokenResponse, Self::Error> {
-        let msi_endpoint = std::env::var(MSI_ENDPOINT_ENV_KEY)
-            .unwrap_or_else(|_| "http://169.254.169.254/metadata/identity/oauth2/token".to_owned());
+    async fn request_token(
+        &self,
+        source: &ManagedIdentitySource,
+        resource: &str,
+    ) -> Result<reqwest::Response, ManagedIdentityCredentialError> {
+        if self.identity.is_some()
+            && matches!(
+                source,
+                ManagedIdentitySource::ServiceFabric { .. }
+                    | ManagedIdentitySource::AzureArc { .. }
+            )
+        {
+            return Err(ManagedIdentityCredentialError::UserAssignedNotSupported(
+                source.name(),
+            ));
+        }
 
-        let query_items = vec![("api-version", MSI_API_VERSION), ("resource", resource)];
+        let mut query_items = vec![("resource", resource)];
+        query_items.extend(self.identity_query(source));
 
-        let msi_endpoint_url = Url::parse_with_params(&msi_endpoint, &query_items)
-            .map_err(ManagedIdentityCredentialError::MsiEndpointParseUrlError)?;
+        let parse_url = |endpoint: &str, api_version: &'static str| {
+            let mut query_items = query_items.clone();
+            query_items.insert(0, ("api-version", api_version));
+            Url::parse_with_params(endpoint, &query_items)
+                .map_err(ManagedIdentityCredentialError::MsiEndpointParseUrlError)
+        };
 
-        let msi_secret = std::env::var(MSI_SECRET_ENV_KEY)
-            .map_err(ManagedIdentityCredentialError::MissingMsiSecret)?;
+        let response = match source {
+            ManagedIdentitySource::AppService { endpoint, header } => reqwest::Client::new()
+                .get(parse_url(endpoint, APP_SERVICE_API_VERSION)?)
+                .header("X-IDENTITY-HEADER", header)
+                .send()
+                .await
+                .map_err(ManagedIdentityCredentialError::SendError)?,
+            ManagedIdentitySource::ServiceFabric {
+                endpoint,
+                header,
+                thumbprint,
+            } => {
+                // Service Fabric serves the endpoint with a self-signed certificate, trusted
+                // only when its thumbprint is the one given by the environment
+                let tls = rustls::ClientConfig::builder()
+                    .with_safe_defaults()
+                    .with_custom_certificate_verifier(Arc::new(ThumbprintVerifier::new(thumbprint)))
+                    .with_no_client_auth();
+                reqwest::Client::builder()
+                    .use_preconfigured_tls(tls)
+                    .build()
+                    .map_err(ManagedIdentityCredentialError::ClientBuildError)?
+                    .get(parse_url(endpoint, SERVICE_FABRIC_API_VERSION)?)
+                    .header("secret", header)
+                    .send()
+                    .await
+                    .map_err(ManagedIdentityCredentialError::SendError)?
+            }
+            ManagedIdentitySource::AzureArc { endpoint } => {
+                self.request_arc_token(parse_url(endpoint, AZURE_ARC_API_VERSION)?)
+                    .await?
+            }
+            ManagedIdentitySource::CloudShell { endpoint } => reqwest::Client::new()
+                .post(endpoint.as_str())
+                .header("Metadata", "true")
+                .form(&query_items)
+                .send()
+                .await
+                .map_err(ManagedIdentityCredentialError::SendError)?,
+            ManagedIdentitySource::Imds => reqwest::Client::builder()
+                .connect_timeout(self.imds_timeout)
+                .build()
+                .map_err(ManagedIdentityCredentialError::ClientBuildError)?
+                .get(parse_url(IMDS_ENDPOINT, IMDS_API_VERSION)?)
+                .header("Metadata", "true")
+                .send()
+                .await
+                .map_err(|error| {
+                    if error.is_connect() || error.is_timeout() {
+                        ManagedIdentityCredentialError::ImdsUnavailable(error)
+                    } else {
+                        ManagedIdentityCredentialError::SendError(error)
+                    }
+                })?,
+        };
+        Ok(response)
+    }
 
+    /// Azure Arc first answers with a challenge pointing to a key file only readable by
+    /// privileged users; its content is the secret to send back. Only the key files of the
+    /// directory of the agent are read, so that the endpoint cannot have any file sent back.
+    async fn request_arc_token(
+        &self,
+        url: Url,
+    ) -> Result<reqwest::Response, ManagedIdentityCredentialError> {
         let client = reqwest::Client::new();
-        let res_body = client
-            .get(msi_endpoint_url)
+        let challenge = client
+            .get(url.clone())
             .header("Metadata", "true")
-            .header("X-IDENTITY-HEADER", msi_secret)
             .send()
             .await
-            .map_err(ManagedIdentityCredentialError::SendError)?
+            .map_err(ManagedIdentityCredentialError::SendError)?;
+        if challenge.status() != StatusCode::UNAUTHORIZED {
+            return Ok(challenge);
+        }
+
+        let realm = challenge
+            .headers()
+            .get(header::WWW_AUTHENTICATE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split_once("realm=").map(|(_, realm)| realm))
+            .ok_or_else(|| {
+                ManagedIdentityCredentialError::InvalidArcChallenge(
+                    "no realm in the WWW-Authenticate header".to_owned(),
+                )
+            })?;
+        let directory = arc_key_directory().ok_or_else(|| {
+            ManagedIdentityCredentialError::InvalidArcChallenge(
+                "the key directory of the Azure Arc agent is unknown".to_owned(),
+            )
+        })?;
+        let key = read_arc_key(Path::new(realm.trim_matches('"')), &directory).await?;
+
+        client
+            .get(url)
+            .header("Metadata", "true")
+            .header(header::AUTHORIZATION, format!("Basic {}", key.trim()))
+            .send()
+            .await
+            .map_err(ManagedIdentityCredentialError::SendError)
+    }
+}
+
+/// Accepts the certificate of the Service Fabric endpoint when its SHA-1 thumbprint is the
+/// expected one, whatever its issuer and name.
+struct ThumbprintVerifier {
+    thumbprint: String,
+}
+
+impl ThumbprintVerifier {
+    fn new(thumbprint: &str) -> Self {
+        Self {
+            thumbprint: thumbprint.trim().to_ascii_lowercase(),
+        }
+    }
+
+    fn matches(&self, certificate: &[u8]) -> bool {
+        let thumbprint: String = Sha1::digest(certificate)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        thumbprint == self.thumbprint
+    }
+}
+
+impl rustls::client::ServerCertVerifier for ThumbprintVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &rustls::Certificate,
+        _intermediates: &[rustls::Certificate],
+        _server_name: &rustls::ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<rustls::client::ServerCertVerified, rustls::Error> {
+        if self.matches(&end_entity.0) {
+            Ok(rustls::client::ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::General(
+                "the thumbprint of the Service Fabric certificate is not the expected one"
+                    .to_owned(),
+            ))
+        }
+    }
+}
+
+fn arc_key_directory() -> Option<PathBuf> {
+    if cfg!(windows) {
+        std::env::var_os("ProgramData").map(|program_data| {
+            PathBuf::from(program_data)
+                .join("AzureConnectedMachineAgent")
+                .join("Tokens")
+        })
+    } else {
+        Some(PathBuf::from(AZURE_ARC_KEY_DIRECTORY))
+    }
+}
+
+/// Reads the Azure Arc key file at `path`, which must be a `.key` file of `directory` no larger
+/// than the agent writes.
+async fn read_arc_key(
+    path: &Path,
+    directory: &Path,
+) -> Result<String, ManagedIdentityCredentialError> {
+    let invalid = |reason: String| ManagedIdentityCredentialError::InvalidArcChallenge(reason);
+    let directory = tokio::fs::canonicalize(directory)
+        .await
+        .map_err(ManagedIdentityCredentialError::ReadArcKeyError)?;
+    let path = tokio::fs::canonicalize(path)
+        .await
+        .map_err(ManagedIdentityCredentialError::ReadArcKeyError)?;
+    if path.parent() != Some(directory.as_path())
+        || path.extension().and_then(|extension| extension.to_str()) != Some("key")
+    {
+        return Err(invalid(format!("unexpected key file {}", path.display())));
+    }
+    let size = tokio::fs::metadata(&path)
+        .await
+        .map_err(ManagedIdentityCredentialError::ReadArcKeyError)?
+        .len();
+    if size > AZURE_ARC_MAX_KEY_SIZE {
+        return Err(invalid(format!(
+            "the key file of {} bytes exceeds {} bytes",
+            size, AZURE_ARC_MAX_KEY_SIZE
+        )));
+    }
+    tokio::fs::read_to_string(&path)
+        .await
+        .map_err(ManagedIdentityCredentialError::ReadArcKeyError)
+}
+
+impl ManagedIdentityCredential {
+    async fn get_managed_identity_token(
+        &self,
+        scopes: &[&str],
+    ) -> Result<TokenResponse, ManagedIdentityCredentialError> {
+        let resource = match scopes {
+            [scope] => azure_core::scope_to_resource(scope),
+            _ => {
+                return Err(ManagedIdentityCredentialError::InvalidScopeCount(
+                    scopes.len(),
+                ))
+            }
+        };
+        let source = ManagedIdentitySource::from_env();
+        let response = self.request_token(&source, resource).await?;
+
+        let status = response.status();
+        let res_body = response
             .text()
             .await
             .map_err(ManagedIdentityCredentialError::TextError)?;
+        if !status.is_success() {
+            return Err(ManagedIdentityCredentialError::ErrorResponse(
+                status, res_body,
+            ));
+        }
 
         let token_response = serde_json::from_str::<MsiTokenResponse>(&res_body)
             .map_err(ManagedIdentityCredentialError::DeserializeError)?;
@@ -74,21 +450,193 @@ impl TokenCredential for ManagedIdentityCredential {
 }
 
 #[async_trait::async_trait]
-impl azure_core::TokenCredential for ManagedIdentityCredential {
-    async fn get_token(
+impl TokenCredential for ManagedIdentityCredential {
+    /// The tenant of the identity is fixed: `options` are ignored.
+    async fn get_token_with_options(
         &self,
-        resource: &str,
-    ) -> Result<azure_core::TokenResponse, azure_core::Error> {
-        TokenCredential::get_token(self, resource)
+        scopes: &[&str],
+        _options: &TokenRequestOptions,
+    ) -> Result<TokenResponse, azure_core::Error> {
+        self.get_managed_identity_token(scopes)
             .await
             .map_err(|error| azure_core::Error::GetTokenError(Box::new(error)))
     }
 }
 
+/// The endpoints report `expires_on` as seconds since the epoch, either as a number or a
+/// string. Older App Service versions use a `MM/DD/YYYY HH:MM:SS +00:00` date instead.
+mod expires_on_format {
+    use chrono::{DateTime, TimeZone, Utc};
+    use serde::{de, Deserialize, Deserializer};
+
+    const APP_SERVICE_FORMAT: &str = "%m/%d/%Y %H:%M:%S %z";
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum ExpiresOn {
+        Seconds(i64),
+        Text(String),
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let seconds = match ExpiresOn::deserialize(deserializer)? {
+            ExpiresOn::Seconds(seconds) => seconds,
+            ExpiresOn::Text(text) => match text.parse::<i64>() {
+                Ok(seconds) => seconds,
+                Err(_) => {
+                    return DateTime::parse_from_str(&text, APP_SERVICE_FORMAT)
+                        .or_else(|_| DateTime::parse_from_rfc3339(&text))
+                        .map(|date| date.with_timezone(&Utc))
+                        .map_err(de::Error::custom)
+                }
+            },
+        };
+        Utc.timestamp_opt(seconds, 0)
+            .single()
+            .ok_or_else(|| de::Error::custom(format!("invalid expires_on {}", seconds)))
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 struct MsiTokenResponse {
     pub access_token: AccessToken,
+    #[serde(with = "expires_on_format")]
     pub expires_on: DateTime<Utc>,
-    pub token_type: String,
-    pub resource: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn detect(vars: &[(&str, &str)]) -> ManagedIdentitySource {
+        let vars: HashMap<_, _> = vars.iter().cloned().collect();
+        ManagedIdentitySource::detect(|name| vars.get(name).map(|value| value.to_string()))
+    }
+
+    #[test]
+    fn detect_source() {
+        assert_eq!(detect(&[]), ManagedIdentitySource::Imds);
+        assert_eq!(
+            detect(&[
+                (
+                    IDENTITY_ENDPOINT_ENV_KEY,
+                    "http://localhost:42356/msi/token"
+                ),
+                (IDENTITY_HEADER_ENV_KEY, "secret"),
+            ]),
+            ManagedIdentitySource::AppService {
+                endpoint: "http://localhost:42356/msi/token".to_owned(),
+                header: "secret".to_owned(),
+            }
+        );
+        assert!(matches!(
+            detect(&[
+                (
+                    IDENTITY_ENDPOINT_ENV_KEY,
+                    "https://localhost:2377/metadata/identity"
+                ),
+                (IDENTITY_HEADER_ENV_KEY, "secret"),
+                (IDENTITY_SERVER_THUMBPRINT_ENV_KEY, "thumbprint"),
+            ]),
+            ManagedIdentitySource::ServiceFabric { .. }
+        ));
+        assert!(matches!(
+            detect(&[
+                (
+                    IDENTITY_ENDPOINT_ENV_KEY,
+                    "http://localhost:40342/metadata/identity/oauth2/token"
+                ),
+                (IMDS_ENDPOINT_ENV_KEY, "http://localhost:40342"),
+            ]),
+            ManagedIdentitySource::AzureArc { .. }
+        ));
+        assert!(matches!(
+            detect(&[(MSI_ENDPOINT_ENV_KEY, "http://localhost:50342/oauth2/token")]),
+            ManagedIdentitySource::CloudShell { .. }
+        ));
+    }
+
+    #[test]
+    fn identity_query_names() {
+        let app_service = ManagedIdentitySource::AppService {
+            endpoint: String::new(),
+            header: String::new(),
+        };
+        let object_id =
+            ManagedIdentityCredential::user_assigned(ManagedIdentityId::ObjectId("oid".to_owned()));
+        assert_eq!(
+            object_id.identity_query(&app_service),
+            Some(("principal_id", "oid"))
+        );
+        assert_eq!(
+            object_id.identity_query(&ManagedIdentitySource::Imds),
+            Some(("object_id", "oid"))
+        );
+        assert_eq!(
+            ManagedIdentityCredential::new().identity_query(&ManagedIdentitySource::Imds),
+            None
+        );
+    }
+
+    #[test]
+    fn thumbprints_are_compared_case_insensitively() {
+        let verifier = ThumbprintVerifier::new("A9993E364706816ABA3E25717850C26C9CD0D89D");
+        assert!(verifier.matches(b"abc"));
+        assert!(!verifier.matches(b"abd"));
+    }
+
+    #[tokio::test]
+    async fn arc_keys_are_read_from_the_agent_directory_only() {
+        let root = std::env::temp_dir().join(format!("azcmagent-{}", uuid::Uuid::new_v4()));
+        let directory = root.join("tokens");
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("secret.key"), "secret\n").unwrap();
+        std::fs::write(root.join("outside.key"), "outside").unwrap();
+        std::fs::write(directory.join("secret.txt"), "text").unwrap();
+        std::fs::write(
+            directory.join("large.key"),
+            vec![b'a'; AZURE_ARC_MAX_KEY_SIZE as usize + 1],
+        )
+        .unwrap();
+
+        let key = read_arc_key(&directory.join("secret.key"), &directory).await;
+        assert_eq!(key.unwrap(), "secret\n");
+        for rejected in &[
+            directory.join("..").join("outside.key"),
+            directory.join("secret.txt"),
+            directory.join("large.key"),
+        ] {
+            assert!(matches!(
+                read_arc_key(rejected, &directory).await,
+                Err(ManagedIdentityCredentialError::InvalidArcChallenge(_))
+            ));
+        }
+        assert!(matches!(
+            read_arc_key(&directory.join("missing.key"), &directory).await,
+            Err(ManagedIdentityCredentialError::ReadArcKeyError(_))
+        ));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn parse_token_responses() {
+        let expected = "2020-04-15T21:05:35Z".parse::<DateTime<Utc>>().unwrap();
+        for expires_on in &[
+            "1586984735",
+            "\"1586984735\"",
+            "\"04/15/2020 21:05:35 +00:00\"",
+        ] {
+            let body = format!(
+                r#"{{"access_token":"token","expires_on":{},"resource":"https://vault.azure.net","token_type":"Bearer"}}"#,
+                expires_on
+            );
+            let response: MsiTokenResponse = serde_json::from_str(&body).unwrap();
+            assert_eq!(response.access_token.secret(), "token");
+            assert_eq!(response.expires_on, expected);
+        }
+    }
 }
@@ -5,38 +5,34 @@
 //! * Azure CLI credentials cache
 //! * Managed identity
 //! * Client secret
+//! * Client certificate, with the `client_certificate` feature
+//! * Client assertion, such as an AKS workload identity federated token
+//!
+//! Credentials implement `azure_core::TokenCredential` and can be shared as `Arc<dyn TokenCredential>`.
+//! They can be tried in order with a `ChainedTokenCredential`, and their tokens cached and refreshed
+//! ahead of expiry with an `AutoRefreshingTokenCredential`.
+mod auto_refreshing_credentials;
+mod chained_credentials;
 mod cli_credentials;
+mod client_assertion_credentials;
+#[cfg(feature = "client_certificate")]
+mod client_certificate_credentials;
 mod client_secret_credentials;
 mod default_credentials;
 mod environment_credentials;
 mod managed_identity_credentials;
+mod workload_identity_credentials;
 
+pub use auto_refreshing_credentials::*;
+pub use chained_credentials::*;
 pub use cli_credentials::*;
+pub use client_assertion_credentials::*;
+#[cfg(feature = "client_certificate")]
+pub use client_certificate_credentials::*;
 pub use client_secret_credentials::*;
 pub use default_credentials::*;
 pub use environment_credentials::*;
 pub use managed_identity_credentials::*;
+pub use workload_identity_credentials::*;
 
-/// Represents a credential capable of providing an OAuth token.
-/// Same as [azure_core::TokenCredential](azure_core::TokenCredential), except a more specific error is returned.
-#[async_trait::async_trait]
-pub trait TokenCredential: Send + Sync {
-    type Error;
-    /// Gets a `TokenResponse` for the specified resource
-    async fn get_token(&self, resource: &str) -> Result<azure_core::TokenResponse, Self::Error>;
-}
-
-#[async_trait::async_trait]
-impl<Error> azure_core::TokenCredential for dyn TokenCredential<Error = Error>
-where
-    Error: std::error::Error + Send + Sync + 'static,
-{
-    async fn get_token(
-        &self,
-        resource: &str,
-    ) -> Result<azure_core::TokenResponse, azure_core::Error> {
-        TokenCredential::get_token(self, resource)
-            .await
-            .map_err(|error| azure_core::Error::GetTokenError(Box::new(error)))
-    }
-}
+pub use azure_core::{TokenCredential, TokenRequestOptions};
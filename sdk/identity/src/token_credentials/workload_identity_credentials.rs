@@ -0,0 +1,100 @@
+use super::{ClientAssertionCredential, TokenCredentialOptions};
+use azure_core::{TokenCredential, TokenRequestOptions, TokenResponse};
+
+const AZURE_TENANT_ID_ENV_KEY: &str = "AZURE_TENANT_ID";
+const AZURE_CLIENT_ID_ENV_KEY: &str = "AZURE_CLIENT_ID";
+const AZURE_FEDERATED_TOKEN_FILE_ENV_KEY: &str = "AZURE_FEDERATED_TOKEN_FILE";
+const AZURE_AUTHORITY_HOST_ENV_KEY: &str = "AZURE_AUTHORITY_HOST";
+
+/// Enables authentication to Azure Active Directory using a federated token projected into
+/// the pod by Azure Kubernetes Service workload identity.
+///
+/// Details configured in the following environment variables, which are set by the
+/// workload identity webhook:
+///
+/// | Variable                            | Description                                      |
+/// |-------------------------------------|--------------------------------------------------|
+/// | `AZURE_TENANT_ID`                   | The Azure Active Directory tenant(directory) ID. |
+/// | `AZURE_CLIENT_ID`                   | The client(application) ID of the App Registration or managed identity. |
+/// | `AZURE_FEDERATED_TOKEN_FILE`        | The path to the projected service account token. |
+/// | `AZURE_AUTHORITY_HOST`              | (optional) The authority host, overriding the one of the options. |
+///
+/// The token file is read again on every exchange as the kubelet rotates it.
+/// This credential ultimately uses a `ClientAssertionCredential` to perform the authentication.
+///
+/// More information on workload identity can be found here:
+/// https://azure.github.io/azure-workload-identity/docs/
+#[derive(Default)]
+pub struct WorkloadIdentityCredential {
+    options: TokenCredentialOptions,
+}
+
+impl WorkloadIdentityCredential {
+    pub fn new(options: TokenCredentialOptions) -> Self {
+        Self { options }
+    }
+}
+
+#[non_exhaustive]
+#[derive(Debug, thiserror::Error)]
+pub enum WorkloadIdentityCredentialError {
+    #[error(
+        "Missing tenant id set in {} environment variable",
+        AZURE_TENANT_ID_ENV_KEY
+    )]
+    MissingTenantId(std::env::VarError),
+    #[error(
+        "Missing client id set in {} environment variable",
+        AZURE_CLIENT_ID_ENV_KEY
+    )]
+    MissingClientId(std::env::VarError),
+    #[error(
+        "Missing federated token file set in {} environment variable",
+        AZURE_FEDERATED_TOKEN_FILE_ENV_KEY
+    )]
+    MissingTokenFile(std::env::VarError),
+}
+
+impl WorkloadIdentityCredential {
+    /// Builds the credential configured by the environment.
+    fn credential(&self) -> Result<ClientAssertionCredential, WorkloadIdentityCredentialError> {
+        let tenant_id = std::env::var(AZURE_TENANT_ID_ENV_KEY)
+            .map_err(WorkloadIdentityCredentialError::MissingTenantId)?;
+        let client_id = std::env::var(AZURE_CLIENT_ID_ENV_KEY)
+            .map_err(WorkloadIdentityCredentialError::MissingClientId)?;
+        let token_file = std::env::var(AZURE_FEDERATED_TOKEN_FILE_ENV_KEY)
+            .map_err(WorkloadIdentityCredentialError::MissingTokenFile)?;
+
+        let mut options = self.options.clone();
+        if let Ok(authority_host) = std::env::var(AZURE_AUTHORITY_HOST_ENV_KEY) {
+            options.set_authority_host(authority_host.trim_end_matches('/').to_owned());
+        }
+
+        Ok(ClientAssertionCredential::new(
+            tenant_id,
+            client_id,
+            move || {
+                let token_file = token_file.clone();
+                async move {
+                    let token = tokio::fs::read_to_string(token_file).await?;
+                    Ok::<_, std::io::Error>(token.trim().to_owned())
+                }
+            },
+            options,
+        ))
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenCredential for WorkloadIdentityCredential {
+    async fn get_token_with_options(
+        &self,
+        scopes: &[&str],
+        options: &TokenRequestOptions,
+    ) -> Result<TokenResponse, azure_core::Error> {
+        self.credential()
+            .map_err(|error| azure_core::Error::GetTokenError(Box::new(error)))?
+            .get_token_with_options(scopes, options)
+            .await
+    }
+}
@@ -1,6 +1,7 @@
 use azure_identity::token_credentials::{ClientSecretCredential, TokenCredentialOptions};
 use azure_key_vault::KeyClient;
 use std::env;
+use std::sync::Arc;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -18,7 +19,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         client_secret,
         TokenCredentialOptions::default(),
     );
-    let mut client = KeyClient::new(&keyvault_url, &creds)?;
+    let client = KeyClient::new(&keyvault_url, Arc::new(creds))?;
     client.delete_secret(&secret_name).await?;
 
     Ok(())
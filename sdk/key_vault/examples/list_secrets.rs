@@ -1,6 +1,8 @@
 use azure_identity::token_credentials::{ClientSecretCredential, TokenCredentialOptions};
 use azure_key_vault::KeyClient;
+use futures::TryStreamExt;
 use std::env;
+use std::sync::Arc;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -17,10 +19,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         client_secret,
         TokenCredentialOptions::default(),
     );
-    let mut client = KeyClient::new(&keyvault_url, &creds)?;
+    let client = KeyClient::new(&keyvault_url, Arc::new(creds))?;
 
-    let secrets = client.list_secrets().await?;
-    dbg!(&secrets);
+    let mut pages = client.list_secrets();
+    while let Some(page) = pages.try_next().await? {
+        for secret in page.items() {
+            dbg!(secret);
+        }
+    }
 
     Ok(())
 }
@@ -0,0 +1,1413 @@
+use crate::client::parse_response;
+use crate::key::{deser_base64_opt, KeyCurveName, KeyType};
+use crate::Error;
+use crate::{KeyClient, PageStream};
+
+use chrono::serde::ts_seconds_option;
+use chrono::{DateTime, Utc};
+use getset::Getters;
+use openssl::pkcs12::Pkcs12;
+use openssl::pkey::{PKey, Private};
+use openssl::x509::X509;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+use std::time::{Duration, Instant};
+use url::Url;
+
+/// The issuer name of self-signed certificates.
+pub const SELF_ISSUER_NAME: &str = "Self";
+/// The issuer name of certificates whose CSR is signed outside of Key Vault and then merged.
+pub const UNKNOWN_ISSUER_NAME: &str = "Unknown";
+
+/// A certificate stored in the Key Vault, along with its policy.
+#[derive(Debug, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct KeyVaultCertificate {
+    /// The certificate identifier.
+    id: String,
+    /// The identifier of the key backing the certificate.
+    #[serde(rename = "kid")]
+    key_id: Option<String>,
+    /// The identifier of the secret holding the certificate and its private key.
+    #[serde(rename = "sid")]
+    secret_id: Option<String>,
+    /// The SHA-1 thumbprint of the certificate.
+    #[serde(rename = "x5t", deserialize_with = "deser_base64_opt", default)]
+    x509_thumbprint: Option<Vec<u8>>,
+    /// The DER encoded certificate.
+    #[serde(deserialize_with = "deser_base64_std_opt", default)]
+    cer: Option<Vec<u8>>,
+    /// The management policy of the certificate.
+    policy: Option<CertificatePolicy>,
+    attributes: CertificateAttributes,
+    /// Application specific metadata in the form of key-value pairs.
+    tags: Option<Map<String, Value>>,
+}
+
+impl KeyVaultCertificate {
+    /// The name of the certificate, taken from its identifier.
+    pub fn name(&self) -> &str {
+        certificate_name_from_id(&self.id)
+    }
+
+    /// The parsed certificate.
+    pub fn x509(&self) -> Result<X509, Error> {
+        let cer = self
+            .cer
+            .as_ref()
+            .ok_or_else(|| Error::CertificateContent("missing certificate".to_owned()))?;
+        Ok(X509::from_der(cer)?)
+    }
+}
+
+/// A certificate stored in the Key Vault, as listed by `list_certificates` and
+/// `list_certificate_versions`.
+#[derive(Debug, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct CertificateItem {
+    /// The certificate identifier.
+    id: String,
+    /// The SHA-1 thumbprint of the certificate.
+    #[serde(rename = "x5t", deserialize_with = "deser_base64_opt", default)]
+    x509_thumbprint: Option<Vec<u8>>,
+    attributes: CertificateAttributes,
+    /// Application specific metadata in the form of key-value pairs.
+    tags: Option<Map<String, Value>>,
+}
+
+impl CertificateItem {
+    /// The name of the certificate, taken from its identifier.
+    pub fn name(&self) -> &str {
+        certificate_name_from_id(&self.id)
+    }
+}
+
+#[derive(Debug, Deserialize, Getters)]
+#[getset(get = "pub")]
+#[serde(rename_all = "camelCase")]
+pub struct CertificateAttributes {
+    /// Creation time in UTC.
+    #[serde(rename = "created", with = "ts_seconds_option", default)]
+    created_on: Option<DateTime<Utc>>,
+    /// Determines whether the object is enabled.
+    enabled: Option<bool>,
+    /// Expiry date in UTC.
+    #[serde(rename = "exp", with = "ts_seconds_option", default)]
+    expires_on: Option<DateTime<Utc>>,
+    /// Not before date in UTC.
+    #[serde(rename = "nbf", with = "ts_seconds_option", default)]
+    not_before: Option<DateTime<Utc>>,
+    /// softDelete data retention days. Value should be >=7 and <=90 when softDelete enabled, otherwise 0.
+    recoverable_days: Option<u8>,
+    /// Reflects the deletion recovery level currently in effect for certificates in the current vault.
+    recovery_level: Option<String>,
+    /// Last updated time in UTC.
+    #[serde(rename = "updated", with = "ts_seconds_option", default)]
+    updated_on: Option<DateTime<Utc>>,
+}
+
+/// The policy used to create, and renew, a certificate.
+///
+/// # Example
+///
+/// ```
+/// use azure_key_vault::certificate::{
+///     CertificatePolicy, CertificatePolicyAction, LifetimeActionTrigger,
+///     SubjectAlternativeNames, SELF_ISSUER_NAME,
+/// };
+/// use azure_key_vault::key::KeyType;
+///
+/// let policy = CertificatePolicy::new(SELF_ISSUER_NAME, "CN=contoso.com")
+///     .with_subject_alternative_names(
+///         SubjectAlternativeNames::new().with_dns_names(vec!["www.contoso.com".to_owned()]),
+///     )
+///     .with_key_type(KeyType::Rsa)
+///     .with_key_size(2048)
+///     .with_validity_months(12)
+///     .with_lifetime_action(
+///         CertificatePolicyAction::AutoRenew,
+///         LifetimeActionTrigger::DaysBeforeExpiry(30),
+///     );
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct CertificatePolicy {
+    /// The policy identifier.
+    #[serde(skip_serializing)]
+    id: Option<String>,
+    #[serde(rename = "key_props", skip_serializing_if = "Option::is_none")]
+    key_properties: Option<CertificateKeyProperties>,
+    #[serde(rename = "secret_props", skip_serializing_if = "Option::is_none")]
+    secret_properties: Option<CertificateSecretProperties>,
+    #[serde(rename = "x509_props", skip_serializing_if = "Option::is_none")]
+    x509_properties: Option<X509CertificateProperties>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lifetime_actions: Option<Vec<CertificateLifetimeAction>>,
+    #[serde(rename = "issuer", skip_serializing_if = "Option::is_none")]
+    issuer_parameters: Option<IssuerParameters>,
+}
+
+impl CertificatePolicy {
+    /// Creates a policy for certificates of `subject`, an X.500 distinguished name such as
+    /// `CN=contoso.com`, issued by `issuer_name`.
+    pub fn new(issuer_name: &str, subject: &str) -> Self {
+        let mut policy = Self::default();
+        policy.issuer_parameters_mut().name = Some(issuer_name.to_owned());
+        policy.x509_properties_mut().subject = Some(subject.to_owned());
+        policy
+    }
+
+    /// Sets the type of certificate requested from the issuer, such as `OV-SSL` or `EV-SSL`.
+    pub fn with_certificate_type(mut self, certificate_type: &str) -> Self {
+        self.issuer_parameters_mut().certificate_type = Some(certificate_type.to_owned());
+        self
+    }
+
+    /// Sets whether the certificate is published to certificate transparency logs.
+    pub fn with_certificate_transparency(mut self, certificate_transparency: bool) -> Self {
+        self.issuer_parameters_mut().certificate_transparency = Some(certificate_transparency);
+        self
+    }
+
+    pub fn with_subject_alternative_names(
+        mut self,
+        subject_alternative_names: SubjectAlternativeNames,
+    ) -> Self {
+        self.x509_properties_mut().subject_alternative_names = Some(subject_alternative_names);
+        self
+    }
+
+    /// Sets the enhanced key usage object identifiers, such as `1.3.6.1.5.5.7.3.1` for server authentication.
+    pub fn with_enhanced_key_usage(mut self, enhanced_key_usage: Vec<String>) -> Self {
+        self.x509_properties_mut().enhanced_key_usage = Some(enhanced_key_usage);
+        self
+    }
+
+    pub fn with_key_usage(mut self, key_usage: Vec<CertificateKeyUsage>) -> Self {
+        self.x509_properties_mut().key_usage = Some(key_usage);
+        self
+    }
+
+    /// Sets how many months the certificate is valid for.
+    pub fn with_validity_months(mut self, validity_months: u32) -> Self {
+        self.x509_properties_mut().validity_months = Some(validity_months);
+        self
+    }
+
+    pub fn with_key_type(mut self, key_type: KeyType) -> Self {
+        self.key_properties_mut().key_type = Some(key_type);
+        self
+    }
+
+    pub fn with_key_size(mut self, key_size: u32) -> Self {
+        self.key_properties_mut().key_size = Some(key_size);
+        self
+    }
+
+    pub fn with_key_curve_name(mut self, curve_name: KeyCurveName) -> Self {
+        self.key_properties_mut().curve_name = Some(curve_name);
+        self
+    }
+
+    /// Sets whether the private key can be exported, through the secret of the certificate.
+    pub fn with_exportable(mut self, exportable: bool) -> Self {
+        self.key_properties_mut().exportable = Some(exportable);
+        self
+    }
+
+    /// Sets whether the key is kept when the certificate is renewed.
+    pub fn with_reuse_key(mut self, reuse_key: bool) -> Self {
+        self.key_properties_mut().reuse_key = Some(reuse_key);
+        self
+    }
+
+    /// Sets the format of the secret holding the certificate and its private key.
+    pub fn with_content_type(mut self, content_type: CertificateContentType) -> Self {
+        self.secret_properties
+            .get_or_insert_with(Default::default)
+            .content_type = Some(content_type);
+        self
+    }
+
+    /// Adds an action performed when `trigger` fires.
+    pub fn with_lifetime_action(
+        mut self,
+        action: CertificatePolicyAction,
+        trigger: LifetimeActionTrigger,
+    ) -> Self {
+        self.lifetime_actions
+            .get_or_insert_with(Vec::new)
+            .push(CertificateLifetimeAction { trigger, action });
+        self
+    }
+
+    fn key_properties_mut(&mut self) -> &mut CertificateKeyProperties {
+        self.key_properties.get_or_insert_with(Default::default)
+    }
+
+    fn x509_properties_mut(&mut self) -> &mut X509CertificateProperties {
+        self.x509_properties.get_or_insert_with(Default::default)
+    }
+
+    fn issuer_parameters_mut(&mut self) -> &mut IssuerParameters {
+        self.issuer_parameters.get_or_insert_with(Default::default)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct CertificateKeyProperties {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exportable: Option<bool>,
+    #[serde(rename = "kty", skip_serializing_if = "Option::is_none")]
+    key_type: Option<KeyType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key_size: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reuse_key: Option<bool>,
+    #[serde(rename = "crv", skip_serializing_if = "Option::is_none")]
+    curve_name: Option<KeyCurveName>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct CertificateSecretProperties {
+    #[serde(rename = "contentType", skip_serializing_if = "Option::is_none")]
+    content_type: Option<CertificateContentType>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct X509CertificateProperties {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    subject: Option<String>,
+    #[serde(rename = "ekus", skip_serializing_if = "Option::is_none")]
+    enhanced_key_usage: Option<Vec<String>>,
+    #[serde(rename = "sans", skip_serializing_if = "Option::is_none")]
+    subject_alternative_names: Option<SubjectAlternativeNames>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key_usage: Option<Vec<CertificateKeyUsage>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    validity_months: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct SubjectAlternativeNames {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    emails: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dns_names: Option<Vec<String>>,
+    #[serde(rename = "upns", skip_serializing_if = "Option::is_none")]
+    user_principal_names: Option<Vec<String>>,
+}
+
+impl SubjectAlternativeNames {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_emails(mut self, emails: Vec<String>) -> Self {
+        self.emails = Some(emails);
+        self
+    }
+
+    pub fn with_dns_names(mut self, dns_names: Vec<String>) -> Self {
+        self.dns_names = Some(dns_names);
+        self
+    }
+
+    pub fn with_user_principal_names(mut self, user_principal_names: Vec<String>) -> Self {
+        self.user_principal_names = Some(user_principal_names);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct IssuerParameters {
+    /// The name of the issuer, `Self`, `Unknown` or one set with `set_certificate_issuer`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    /// The type of certificate requested from the issuer.
+    #[serde(rename = "cty", skip_serializing_if = "Option::is_none")]
+    certificate_type: Option<String>,
+    #[serde(rename = "cert_transparency", skip_serializing_if = "Option::is_none")]
+    certificate_transparency: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct CertificateLifetimeAction {
+    trigger: LifetimeActionTrigger,
+    action: CertificatePolicyAction,
+}
+
+/// When a lifetime action is performed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LifetimeActionTrigger {
+    /// Once this percentage of the validity period has elapsed.
+    LifetimePercentage(u8),
+    /// This many days before the certificate expires.
+    DaysBeforeExpiry(u32),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action_type")]
+pub enum CertificatePolicyAction {
+    /// Renews the certificate.
+    AutoRenew,
+    /// Emails the certificate contacts.
+    EmailContacts,
+}
+
+/// The format of the secret holding a certificate and its private key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CertificateContentType {
+    #[serde(rename = "application/x-pkcs12")]
+    Pkcs12,
+    #[serde(rename = "application/x-pem-file")]
+    Pem,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CertificateKeyUsage {
+    DigitalSignature,
+    NonRepudiation,
+    KeyEncipherment,
+    DataEncipherment,
+    KeyAgreement,
+    KeyCertSign,
+    #[serde(rename = "cRLSign")]
+    CrlSign,
+    EncipherOnly,
+    DecipherOnly,
+}
+
+/// The operation creating a certificate, pending until the certificate is issued.
+#[derive(Debug, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct CertificateOperation {
+    /// The operation identifier.
+    id: Option<String>,
+    #[serde(rename = "issuer")]
+    issuer_parameters: Option<IssuerParameters>,
+    /// The certificate signing request, to sign and merge with `merge_certificate` for the
+    /// `Unknown` issuer.
+    #[serde(deserialize_with = "deser_base64_std_opt", default)]
+    csr: Option<Vec<u8>>,
+    cancellation_requested: Option<bool>,
+    /// `inProgress`, `completed`, `failed` or `cancelled`.
+    status: Option<String>,
+    status_details: Option<String>,
+    error: Option<CertificateOperationError>,
+    /// The location of the created certificate.
+    target: Option<String>,
+    request_id: Option<String>,
+}
+
+impl CertificateOperation {
+    /// Whether the operation is still in progress.
+    pub fn is_pending(&self) -> bool {
+        self.status.as_deref() == Some("inProgress")
+    }
+}
+
+#[derive(Debug, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct CertificateOperationError {
+    code: Option<String>,
+    message: Option<String>,
+}
+
+/// The options of a certificate import.
+#[derive(Debug, Clone)]
+pub struct ImportCertificateOptions {
+    value: String,
+    password: Option<String>,
+    policy: Option<CertificatePolicy>,
+    enabled: Option<bool>,
+    tags: Option<Map<String, Value>>,
+}
+
+impl ImportCertificateOptions {
+    /// Imports a PKCS#12 (PFX) file holding the certificate and its private key.
+    pub fn pkcs12(pfx: &[u8]) -> Self {
+        Self::new(base64::encode(pfx), CertificateContentType::Pkcs12)
+    }
+
+    /// Imports a PEM file holding the certificate and its private key.
+    pub fn pem(pem: &str) -> Self {
+        Self::new(pem.to_owned(), CertificateContentType::Pem)
+    }
+
+    fn new(value: String, content_type: CertificateContentType) -> Self {
+        Self {
+            value,
+            password: None,
+            policy: Some(CertificatePolicy::default().with_content_type(content_type)),
+            enabled: None,
+            tags: None,
+        }
+    }
+
+    /// Sets the password protecting the PKCS#12 file.
+    pub fn with_password(mut self, password: &str) -> Self {
+        self.password = Some(password.to_owned());
+        self
+    }
+
+    /// Sets the policy of the certificate. Its content type is the one of the imported file
+    /// unless set.
+    pub fn with_policy(mut self, mut policy: CertificatePolicy) -> Self {
+        let content_type = self
+            .policy
+            .take()
+            .and_then(|policy| policy.secret_properties)
+            .and_then(|secret_properties| secret_properties.content_type);
+        if let (None, Some(content_type)) = (
+            policy
+                .secret_properties
+                .as_ref()
+                .and_then(|secret_properties| secret_properties.content_type.as_ref()),
+            content_type,
+        ) {
+            policy = policy.with_content_type(content_type);
+        }
+        self.policy = Some(policy);
+        self
+    }
+
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = Some(enabled);
+        self
+    }
+
+    pub fn with_tags(mut self, tags: Map<String, Value>) -> Self {
+        self.tags = Some(tags);
+        self
+    }
+}
+
+/// An issuer of certificates, referenced by name in the `CertificatePolicy`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct CertificateIssuer {
+    /// The issuer identifier.
+    #[serde(skip_serializing)]
+    id: Option<String>,
+    /// The issuer provider, such as `DigiCert` or `GlobalSign`.
+    provider: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    credentials: Option<IssuerCredentials>,
+    #[serde(rename = "org_details", skip_serializing_if = "Option::is_none")]
+    organization_details: Option<OrganizationDetails>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attributes: Option<IssuerAttributes>,
+}
+
+impl CertificateIssuer {
+    pub fn new(provider: &str) -> Self {
+        Self {
+            provider: Some(provider.to_owned()),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the account used to authenticate to the issuer.
+    pub fn with_credentials(mut self, account_id: &str, password: &str) -> Self {
+        self.credentials = Some(IssuerCredentials {
+            account_id: Some(account_id.to_owned()),
+            password: Some(password.to_owned()),
+        });
+        self
+    }
+
+    pub fn with_organization_id(mut self, organization_id: &str) -> Self {
+        self.organization_details
+            .get_or_insert_with(Default::default)
+            .id = Some(organization_id.to_owned());
+        self
+    }
+
+    pub fn with_administrator(mut self, administrator: AdministratorDetails) -> Self {
+        self.organization_details
+            .get_or_insert_with(Default::default)
+            .admin_details
+            .get_or_insert_with(Vec::new)
+            .push(administrator);
+        self
+    }
+
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.attributes.get_or_insert_with(Default::default).enabled = Some(enabled);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct IssuerCredentials {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    account_id: Option<String>,
+    #[serde(rename = "pwd", skip_serializing_if = "Option::is_none")]
+    #[getset(skip)]
+    password: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct OrganizationDetails {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    admin_details: Option<Vec<AdministratorDetails>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct AdministratorDetails {
+    first_name: Option<String>,
+    last_name: Option<String>,
+    email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    phone: Option<String>,
+}
+
+impl AdministratorDetails {
+    pub fn new(first_name: &str, last_name: &str, email: &str) -> Self {
+        Self {
+            first_name: Some(first_name.to_owned()),
+            last_name: Some(last_name.to_owned()),
+            email: Some(email.to_owned()),
+            phone: None,
+        }
+    }
+
+    pub fn with_phone(mut self, phone: &str) -> Self {
+        self.phone = Some(phone.to_owned());
+        self
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct IssuerAttributes {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enabled: Option<bool>,
+    /// Creation time in UTC.
+    #[serde(
+        rename = "created",
+        with = "ts_seconds_option",
+        default,
+        skip_serializing
+    )]
+    created_on: Option<DateTime<Utc>>,
+    /// Last updated time in UTC.
+    #[serde(
+        rename = "updated",
+        with = "ts_seconds_option",
+        default,
+        skip_serializing
+    )]
+    updated_on: Option<DateTime<Utc>>,
+}
+
+/// A certificate issuer, as listed by `list_certificate_issuers`.
+#[derive(Debug, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct CertificateIssuerItem {
+    /// The issuer identifier.
+    id: String,
+    provider: Option<String>,
+}
+
+impl CertificateIssuerItem {
+    /// The name of the issuer, taken from its identifier.
+    pub fn name(&self) -> &str {
+        certificate_name_from_id(&self.id)
+    }
+}
+
+/// A contact notified of certificate events, such as an upcoming expiry.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct CertificateContact {
+    #[serde(rename = "email")]
+    email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    phone: Option<String>,
+}
+
+impl CertificateContact {
+    pub fn new(email: &str) -> Self {
+        Self {
+            email: Some(email.to_owned()),
+            name: None,
+            phone: None,
+        }
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_owned());
+        self
+    }
+
+    pub fn with_phone(mut self, phone: &str) -> Self {
+        self.phone = Some(phone.to_owned());
+        self
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CertificateContacts {
+    #[serde(rename = "contacts", default)]
+    contacts: Vec<CertificateContact>,
+}
+
+/// A certificate downloaded with its private key, from the secret backing it.
+#[derive(Getters)]
+#[getset(get = "pub")]
+pub struct CertificateWithPrivateKey {
+    certificate: X509,
+    private_key: PKey<Private>,
+    /// The issuers of the certificate, when included in the secret.
+    chain: Vec<X509>,
+}
+
+impl std::fmt::Debug for CertificateWithPrivateKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CertificateWithPrivateKey")
+            .field("certificate", &self.certificate)
+            .field("chain", &self.chain)
+            .finish()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct CertificateSecretResponse {
+    value: String,
+    #[serde(rename = "contentType")]
+    content_type: Option<String>,
+}
+
+fn deser_base64_std_opt<'de, D>(deserializer: D) -> Result<Option<Vec<u8>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s: Option<String> = Option::deserialize(deserializer)?;
+    s.map(|s| base64::decode(s).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+fn certificate_name_from_id(id: &str) -> &str {
+    // https://{vault}/certificates/{name}[/{version}] or https://{vault}/certificates/issuers/{name}
+    let mut segments = id.trim_end_matches('/').rsplit('/');
+    let last = segments.next().unwrap_or_default();
+    match segments.next() {
+        Some("certificates") | Some("issuers") | None => last,
+        Some(name) => name,
+    }
+}
+
+fn parse_certificate_secret(
+    secret: CertificateSecretResponse,
+) -> Result<CertificateWithPrivateKey, Error> {
+    match secret.content_type.as_deref() {
+        Some("application/x-pem-file") => {
+            let private_key = PKey::private_key_from_pem(secret.value.as_bytes())?;
+            let certificates = X509::stack_from_pem(secret.value.as_bytes())?;
+            split_leaf(private_key, certificates)
+        }
+        Some("application/x-pkcs12") | None => {
+            let pfx = base64::decode(&secret.value)
+                .map_err(|error| Error::CertificateContent(error.to_string()))?;
+            let parsed = Pkcs12::from_der(&pfx)?.parse2("")?;
+            let private_key = parsed
+                .pkey
+                .ok_or_else(|| Error::CertificateContent("missing private key".to_owned()))?;
+            let mut certificates: Vec<X509> = parsed.cert.into_iter().collect();
+            if let Some(ca) = parsed.ca {
+                certificates.extend(ca);
+            }
+            split_leaf(private_key, certificates)
+        }
+        Some(content_type) => Err(Error::CertificateContent(format!(
+            "unsupported content type {}",
+            content_type
+        ))),
+    }
+}
+
+/// Separates the certificate of `private_key` from the other certificates of its chain.
+fn split_leaf(
+    private_key: PKey<Private>,
+    certificates: Vec<X509>,
+) -> Result<CertificateWithPrivateKey, Error> {
+    let mut leaf = None;
+    let mut chain = Vec::new();
+    for certificate in certificates {
+        let matches_key = leaf.is_none()
+            && certificate
+                .public_key()
+                .map(|public_key| public_key.public_eq(&private_key))
+                .unwrap_or(false);
+        if matches_key {
+            leaf = Some(certificate);
+        } else {
+            chain.push(certificate);
+        }
+    }
+    let certificate =
+        leaf.ok_or_else(|| Error::CertificateContent("missing certificate".to_owned()))?;
+    Ok(CertificateWithPrivateKey {
+        certificate,
+        private_key,
+        chain,
+    })
+}
+
+impl KeyClient {
+    /// Creates a new certificate, or a new version of an existing certificate.
+    /// The certificate is issued asynchronously: use `wait_for_certificate_operation` to wait
+    /// for its completion. This operation requires the certificates/create permission.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use azure_key_vault::KeyClient;
+    /// use azure_key_vault::certificate::{CertificatePolicy, SELF_ISSUER_NAME};
+    /// use azure_identity::token_credentials::DefaultCredential;
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    /// use tokio::runtime::Runtime;
+    ///
+    /// async fn example() {
+    ///     let creds = DefaultCredential::default();
+    ///     let client = KeyClient::new(&"KEYVAULT_URL", Arc::new(creds)).unwrap();
+    ///     let policy = CertificatePolicy::new(SELF_ISSUER_NAME, "CN=contoso.com");
+    ///     client.create_certificate("CERTIFICATE_NAME", &policy, None).await.unwrap();
+    ///     let operation = client
+    ///         .wait_for_certificate_operation(
+    ///             "CERTIFICATE_NAME",
+    ///             Duration::from_secs(5),
+    ///             Duration::from_secs(300),
+    ///         )
+    ///         .await
+    ///         .unwrap();
+    ///     dbg!(&operation);
+    /// }
+    ///
+    /// Runtime::new().unwrap().block_on(example());
+    /// ```
+    pub async fn create_certificate(
+        &self,
+        certificate_name: &str,
+        policy: &CertificatePolicy,
+        tags: Option<Map<String, Value>>,
+    ) -> Result<CertificateOperation, Error> {
+        // POST {vaultBaseUrl}/certificates/{certificate-name}/create?api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!("certificates/{}/create", certificate_name));
+        uri.set_query(Some(&self.api_version_param()));
+
+        let mut request_body = Map::new();
+        request_body.insert("policy".to_owned(), serde_json::to_value(policy)?);
+        if let Some(tags) = tags {
+            request_body.insert("tags".to_owned(), Value::Object(tags));
+        }
+
+        let response = self
+            .post_authed(
+                uri.to_string(),
+                Some(Value::Object(request_body).to_string()),
+            )
+            .await?;
+        parse_response(&response)
+    }
+
+    /// Gets the operation creating a certificate.
+    /// This operation requires the certificates/get permission.
+    pub async fn get_certificate_operation(
+        &self,
+        certificate_name: &str,
+    ) -> Result<CertificateOperation, Error> {
+        // GET {vaultBaseUrl}/certificates/{certificate-name}/pending?api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!("certificates/{}/pending", certificate_name));
+        uri.set_query(Some(&self.api_version_param()));
+
+        let response = self.get_authed(uri.to_string()).await?;
+        parse_response(&response)
+    }
+
+    /// Polls the operation creating a certificate every `poll_interval` until it is no longer
+    /// in progress, returning the completed, failed or cancelled operation, or fails with
+    /// `Error::OperationTimedOut` once `max_duration` has elapsed.
+    /// This operation requires the certificates/get permission.
+    pub async fn wait_for_certificate_operation(
+        &self,
+        certificate_name: &str,
+        poll_interval: Duration,
+        max_duration: Duration,
+    ) -> Result<CertificateOperation, Error> {
+        let deadline = Instant::now() + max_duration;
+        loop {
+            let operation = self.get_certificate_operation(certificate_name).await?;
+            if !operation.is_pending() {
+                return Ok(operation);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(Error::OperationTimedOut(max_duration));
+            }
+            azure_core::sleep(poll_interval.min(deadline - now)).await;
+        }
+    }
+
+    /// Merges a certificate, or a certificate chain, with the key pair of a pending
+    /// certificate operation, typically after signing its CSR with an external issuer.
+    /// This operation requires the certificates/create permission.
+    ///
+    /// # Arguments
+    ///
+    /// * `certificate_name` - Name of the certificate
+    /// * `x509_certificates` - The DER encoded certificate, followed by its issuers
+    pub async fn merge_certificate(
+        &self,
+        certificate_name: &str,
+        x509_certificates: &[Vec<u8>],
+    ) -> Result<KeyVaultCertificate, Error> {
+        // POST {vaultBaseUrl}/certificates/{certificate-name}/pending/merge?api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!("certificates/{}/pending/merge", certificate_name));
+        uri.set_query(Some(&self.api_version_param()));
+
+        let mut request_body = Map::new();
+        request_body.insert(
+            "x5c".to_owned(),
+            Value::Array(
+                x509_certificates
+                    .iter()
+                    .map(|certificate| Value::String(base64::encode(certificate)))
+                    .collect(),
+            ),
+        );
+
+        let response = self
+            .post_authed(
+                uri.to_string(),
+                Some(Value::Object(request_body).to_string()),
+            )
+            .await?;
+        parse_response(&response)
+    }
+
+    /// Imports a certificate along with its private key.
+    /// This operation requires the certificates/import permission.
+    pub async fn import_certificate(
+        &self,
+        certificate_name: &str,
+        options: ImportCertificateOptions,
+    ) -> Result<KeyVaultCertificate, Error> {
+        // POST {vaultBaseUrl}/certificates/{certificate-name}/import?api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!("certificates/{}/import", certificate_name));
+        uri.set_query(Some(&self.api_version_param()));
+
+        let mut request_body = Map::new();
+        request_body.insert("value".to_owned(), Value::String(options.value));
+        if let Some(password) = options.password {
+            request_body.insert("pwd".to_owned(), Value::String(password));
+        }
+        if let Some(policy) = &options.policy {
+            request_body.insert("policy".to_owned(), serde_json::to_value(policy)?);
+        }
+        if let Some(enabled) = options.enabled {
+            let mut attributes = Map::new();
+            attributes.insert("enabled".to_owned(), Value::Bool(enabled));
+            request_body.insert("attributes".to_owned(), Value::Object(attributes));
+        }
+        if let Some(tags) = options.tags {
+            request_body.insert("tags".to_owned(), Value::Object(tags));
+        }
+
+        let response = self
+            .post_authed(
+                uri.to_string(),
+                Some(Value::Object(request_body).to_string()),
+            )
+            .await?;
+        parse_response(&response)
+    }
+
+    /// Gets a certificate, along with its policy.
+    /// This operation requires the certificates/get permission.
+    ///
+    /// # Arguments
+    ///
+    /// * `certificate_name` - Name of the certificate
+    /// * `certificate_version` - Version of the certificate. Use an empty string for the latest version
+    pub async fn get_certificate(
+        &self,
+        certificate_name: &str,
+        certificate_version: &str,
+    ) -> Result<KeyVaultCertificate, Error> {
+        // GET {vaultBaseUrl}/certificates/{certificate-name}/{certificate-version}?api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!(
+            "certificates/{}/{}",
+            certificate_name, certificate_version
+        ));
+        uri.set_query(Some(&self.api_version_param()));
+
+        let response = self.get_authed(uri.to_string()).await?;
+        parse_response(&response)
+    }
+
+    /// Lists the certificates in the Key Vault, one page at a time.
+    /// This operation requires the certificates/list permission.
+    pub fn list_certificates(&self) -> PageStream<'_, CertificateItem> {
+        // GET {vaultBaseUrl}/certificates?maxresults=25&api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path("certificates");
+        uri.set_query(Some(&self.api_version_max_results_param()));
+        self.list_pages(uri)
+    }
+
+    /// Lists the versions of a certificate, one page at a time.
+    /// This operation requires the certificates/list permission.
+    pub fn list_certificate_versions(
+        &self,
+        certificate_name: &str,
+    ) -> PageStream<'_, CertificateItem> {
+        // GET {vaultBaseUrl}/certificates/{certificate-name}/versions?maxresults=25&api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!("certificates/{}/versions", certificate_name));
+        uri.set_query(Some(&self.api_version_max_results_param()));
+        self.list_pages(uri)
+    }
+
+    /// Downloads a certificate along with its private key, from the secret backing it.
+    /// The policy of the certificate must have made its key exportable.
+    /// This operation requires the certificates/get and secrets/get permissions.
+    ///
+    /// # Arguments
+    ///
+    /// * `certificate_name` - Name of the certificate
+    /// * `certificate_version` - Version of the certificate. Use an empty string for the latest version
+    pub async fn download_certificate(
+        &self,
+        certificate_name: &str,
+        certificate_version: &str,
+    ) -> Result<CertificateWithPrivateKey, Error> {
+        let certificate = self
+            .get_certificate(certificate_name, certificate_version)
+            .await?;
+        let secret_id = certificate
+            .secret_id
+            .as_deref()
+            .ok_or_else(|| Error::CertificateContent("missing secret id".to_owned()))?;
+
+        // the secret id is on the vault url, keep the one of the client
+        let mut uri = self.vault_url.clone();
+        uri.set_path(Url::parse(secret_id)?.path());
+        uri.set_query(Some(&self.api_version_param()));
+
+        let response = self.get_authed(uri.to_string()).await?;
+        parse_certificate_secret(parse_response(&response)?)
+    }
+
+    /// Creates or updates a certificate issuer.
+    /// This operation requires the certificates/setissuers permission.
+    pub async fn set_certificate_issuer(
+        &self,
+        issuer_name: &str,
+        issuer: &CertificateIssuer,
+    ) -> Result<CertificateIssuer, Error> {
+        // PUT {vaultBaseUrl}/certificates/issuers/{issuer-name}?api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!("certificates/issuers/{}", issuer_name));
+        uri.set_query(Some(&self.api_version_param()));
+
+        let response = self
+            .put_authed(uri.to_string(), serde_json::to_string(issuer)?)
+            .await?;
+        parse_response(&response)
+    }
+
+    /// Gets a certificate issuer.
+    /// This operation requires the certificates/manageissuers/getissuers permission.
+    pub async fn get_certificate_issuer(
+        &self,
+        issuer_name: &str,
+    ) -> Result<CertificateIssuer, Error> {
+        // GET {vaultBaseUrl}/certificates/issuers/{issuer-name}?api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!("certificates/issuers/{}", issuer_name));
+        uri.set_query(Some(&self.api_version_param()));
+
+        let response = self.get_authed(uri.to_string()).await?;
+        parse_response(&response)
+    }
+
+    /// Deletes a certificate issuer.
+    /// This operation requires the certificates/manageissuers/deleteissuers permission.
+    pub async fn delete_certificate_issuer(
+        &self,
+        issuer_name: &str,
+    ) -> Result<CertificateIssuer, Error> {
+        // DELETE {vaultBaseUrl}/certificates/issuers/{issuer-name}?api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!("certificates/issuers/{}", issuer_name));
+        uri.set_query(Some(&self.api_version_param()));
+
+        let response = self.delete_authed(uri.to_string()).await?;
+        parse_response(&response)
+    }
+
+    /// Lists the certificate issuers, one page at a time.
+    /// This operation requires the certificates/manageissuers/getissuers permission.
+    pub fn list_certificate_issuers(&self) -> PageStream<'_, CertificateIssuerItem> {
+        // GET {vaultBaseUrl}/certificates/issuers?maxresults=25&api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path("certificates/issuers");
+        uri.set_query(Some(&self.api_version_max_results_param()));
+        self.list_pages(uri)
+    }
+
+    /// Sets the contacts notified of certificate events, replacing the existing ones.
+    /// This operation requires the certificates/managecontacts permission.
+    pub async fn set_certificate_contacts(
+        &self,
+        contacts: &[CertificateContact],
+    ) -> Result<Vec<CertificateContact>, Error> {
+        // PUT {vaultBaseUrl}/certificates/contacts?api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path("certificates/contacts");
+        uri.set_query(Some(&self.api_version_param()));
+
+        let request_body = CertificateContacts {
+            contacts: contacts.to_vec(),
+        };
+        let response = self
+            .put_authed(uri.to_string(), serde_json::to_string(&request_body)?)
+            .await?;
+        Ok(parse_response::<CertificateContacts>(&response)?.contacts)
+    }
+
+    /// Gets the contacts notified of certificate events.
+    /// This operation requires the certificates/managecontacts permission.
+    pub async fn get_certificate_contacts(&self) -> Result<Vec<CertificateContact>, Error> {
+        // GET {vaultBaseUrl}/certificates/contacts?api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path("certificates/contacts");
+        uri.set_query(Some(&self.api_version_param()));
+
+        let response = self.get_authed(uri.to_string()).await?;
+        Ok(parse_response::<CertificateContacts>(&response)?.contacts)
+    }
+
+    /// Deletes the contacts notified of certificate events, returning them.
+    /// This operation requires the certificates/managecontacts permission.
+    pub async fn delete_certificate_contacts(&self) -> Result<Vec<CertificateContact>, Error> {
+        // DELETE {vaultBaseUrl}/certificates/contacts?api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path("certificates/contacts");
+        uri.set_query(Some(&self.api_version_param()));
+
+        let response = self.delete_authed(uri.to_string()).await?;
+        Ok(parse_response::<CertificateContacts>(&response)?.contacts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use mockito::{mock, Matcher};
+    use openssl::asn1::Asn1Time;
+    use openssl::hash::MessageDigest;
+    use openssl::rsa::Rsa;
+    use openssl::x509::X509NameBuilder;
+    use serde_json::json;
+
+    use crate::client::API_VERSION;
+    use crate::mock_client;
+    use crate::tests::MockCredential;
+
+    fn self_signed() -> (PKey<Private>, X509) {
+        let private_key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let mut name = X509NameBuilder::new().unwrap();
+        name.append_entry_by_text("CN", "contoso.com").unwrap();
+        let name = name.build();
+        let mut builder = X509::builder().unwrap();
+        builder.set_version(2).unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_issuer_name(&name).unwrap();
+        builder.set_pubkey(&private_key).unwrap();
+        builder
+            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::days_from_now(365).unwrap())
+            .unwrap();
+        builder.sign(&private_key, MessageDigest::sha256()).unwrap();
+        (private_key, builder.build())
+    }
+
+    #[test]
+    fn serializes_policy() {
+        let policy = CertificatePolicy::new(SELF_ISSUER_NAME, "CN=contoso.com")
+            .with_subject_alternative_names(
+                SubjectAlternativeNames::new().with_dns_names(vec!["www.contoso.com".to_owned()]),
+            )
+            .with_key_type(KeyType::Ec)
+            .with_key_curve_name(KeyCurveName::P256)
+            .with_exportable(true)
+            .with_content_type(CertificateContentType::Pem)
+            .with_key_usage(vec![
+                CertificateKeyUsage::DigitalSignature,
+                CertificateKeyUsage::CrlSign,
+            ])
+            .with_validity_months(12)
+            .with_lifetime_action(
+                CertificatePolicyAction::AutoRenew,
+                LifetimeActionTrigger::LifetimePercentage(80),
+            );
+
+        assert_eq!(
+            serde_json::to_value(&policy).unwrap(),
+            json!({
+                "key_props": { "exportable": true, "kty": "EC", "crv": "P-256" },
+                "secret_props": { "contentType": "application/x-pem-file" },
+                "x509_props": {
+                    "subject": "CN=contoso.com",
+                    "sans": { "dns_names": ["www.contoso.com"] },
+                    "key_usage": ["digitalSignature", "cRLSign"],
+                    "validity_months": 12
+                },
+                "lifetime_actions": [{
+                    "trigger": { "lifetime_percentage": 80 },
+                    "action": { "action_type": "AutoRenew" }
+                }],
+                "issuer": { "name": "Self" }
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn can_create_certificate() {
+        let _create = mock("POST", "/certificates/created-certificate/create")
+            .match_query(Matcher::UrlEncoded("api-version".into(), API_VERSION.into()))
+            .match_body(Matcher::PartialJson(json!({
+                "policy": { "issuer": { "name": "Unknown" }, "x509_props": { "subject": "CN=contoso.com" } }
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "id": "https://myvault.vault.azure.net/certificates/created-certificate/pending",
+                    "issuer": { "name": "Unknown" },
+                    "csr": "Y3Ny",
+                    "cancellation_requested": false,
+                    "status": "inProgress",
+                    "status_details": "Pending certificate created. Please Perform Merge to complete the request.",
+                    "request_id": "6faeb8fc6b2f4b7c9f2d0b4ac36e6e2d"
+                })
+                .to_string(),
+            )
+            .with_status(202)
+            .create();
+        let _pending = mock("GET", "/certificates/created-certificate/pending")
+            .match_query(Matcher::UrlEncoded("api-version".into(), API_VERSION.into()))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "id": "https://myvault.vault.azure.net/certificates/created-certificate/pending",
+                    "issuer": { "name": "Unknown" },
+                    "status": "completed",
+                    "target": "https://myvault.vault.azure.net/certificates/created-certificate"
+                })
+                .to_string(),
+            )
+            .with_status(200)
+            .create();
+
+        let creds = MockCredential;
+        let client = mock_client!(&"test-keyvault", creds,);
+
+        let policy = CertificatePolicy::new(UNKNOWN_ISSUER_NAME, "CN=contoso.com");
+        let operation = client
+            .create_certificate("created-certificate", &policy, None)
+            .await
+            .unwrap();
+        assert!(operation.is_pending());
+        assert_eq!(operation.csr().as_deref(), Some(&b"csr"[..]));
+
+        let operation = client
+            .wait_for_certificate_operation(
+                "created-certificate",
+                Duration::from_millis(10),
+                Duration::from_secs(5),
+            )
+            .await
+            .unwrap();
+        assert_eq!(operation.status().as_deref(), Some("completed"));
+        assert_eq!(
+            operation.target().as_deref(),
+            Some("https://myvault.vault.azure.net/certificates/created-certificate")
+        );
+    }
+
+    #[tokio::test]
+    async fn can_download_certificate() {
+        let (private_key, certificate) = self_signed();
+        let pfx = Pkcs12::builder()
+            .name("downloaded-certificate")
+            .pkey(&private_key)
+            .cert(&certificate)
+            .build2("")
+            .unwrap()
+            .to_der()
+            .unwrap();
+        let cer = certificate.to_der().unwrap();
+
+        let _certificate = mock("GET", "/certificates/downloaded-certificate/")
+            .match_query(Matcher::UrlEncoded("api-version".into(), API_VERSION.into()))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "id": "https://myvault.vault.azure.net/certificates/downloaded-certificate/1",
+                    "kid": "https://myvault.vault.azure.net/keys/downloaded-certificate/1",
+                    "sid": "https://myvault.vault.azure.net/secrets/downloaded-certificate/1",
+                    "x5t": "fLi3U52HunIVNXubkEnf8tP6Wbo",
+                    "cer": base64::encode(&cer),
+                    "attributes": { "enabled": true, "created": 1482188947, "updated": 1482188947 },
+                    "policy": {
+                        "id": "https://myvault.vault.azure.net/certificates/downloaded-certificate/policy",
+                        "key_props": { "exportable": true, "kty": "RSA", "key_size": 2048, "reuse_key": false },
+                        "secret_props": { "contentType": "application/x-pkcs12" },
+                        "x509_props": { "subject": "CN=contoso.com", "validity_months": 12 },
+                        "issuer": { "name": "Self" }
+                    }
+                })
+                .to_string(),
+            )
+            .with_status(200)
+            .create();
+        let _secret = mock("GET", "/secrets/downloaded-certificate/1")
+            .match_query(Matcher::UrlEncoded(
+                "api-version".into(),
+                API_VERSION.into(),
+            ))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "value": base64::encode(&pfx),
+                    "contentType": "application/x-pkcs12",
+                    "id": "https://myvault.vault.azure.net/secrets/downloaded-certificate/1",
+                    "managed": true,
+                    "kid": "https://myvault.vault.azure.net/keys/downloaded-certificate/1",
+                    "attributes": { "enabled": true, "created": 1482188947, "updated": 1482188947 }
+                })
+                .to_string(),
+            )
+            .with_status(200)
+            .create();
+
+        let creds = MockCredential;
+        let client = mock_client!(&"test-keyvault", creds,);
+
+        let fetched = client
+            .get_certificate("downloaded-certificate", "")
+            .await
+            .unwrap();
+        assert_eq!(fetched.name(), "downloaded-certificate");
+        assert_eq!(fetched.x509().unwrap().to_der().unwrap(), cer);
+        assert_eq!(
+            fetched
+                .policy()
+                .as_ref()
+                .and_then(|policy| policy.key_properties().as_ref())
+                .and_then(|key_properties| key_properties.key_type().clone()),
+            Some(KeyType::Rsa)
+        );
+
+        let downloaded = client
+            .download_certificate("downloaded-certificate", "")
+            .await
+            .unwrap();
+        assert_eq!(downloaded.certificate().to_der().unwrap(), cer);
+        assert!(downloaded.private_key().public_eq(&private_key));
+        assert!(downloaded.chain().is_empty());
+    }
+
+    #[test]
+    fn parses_pem_secret() {
+        let (private_key, certificate) = self_signed();
+        let mut pem = String::from_utf8(private_key.private_key_to_pem_pkcs8().unwrap()).unwrap();
+        pem.push_str(&String::from_utf8(certificate.to_pem().unwrap()).unwrap());
+
+        let parsed = parse_certificate_secret(CertificateSecretResponse {
+            value: pem,
+            content_type: Some("application/x-pem-file".to_owned()),
+        })
+        .unwrap();
+        assert_eq!(
+            parsed.certificate().to_der().unwrap(),
+            certificate.to_der().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn can_set_contacts() {
+        let _m = mock("PUT", "/certificates/contacts")
+            .match_query(Matcher::UrlEncoded(
+                "api-version".into(),
+                API_VERSION.into(),
+            ))
+            .match_body(Matcher::Json(json!({
+                "contacts": [{ "email": "admin@contoso.com", "name": "Admin" }]
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "id": "https://myvault.vault.azure.net/certificates/contacts",
+                    "contacts": [{ "email": "admin@contoso.com", "name": "Admin" }]
+                })
+                .to_string(),
+            )
+            .with_status(200)
+            .create();
+
+        let creds = MockCredential;
+        let client = mock_client!(&"test-keyvault", creds,);
+
+        let contacts = client
+            .set_certificate_contacts(&[
+                CertificateContact::new("admin@contoso.com").with_name("Admin")
+            ])
+            .await
+            .unwrap();
+        assert_eq!(contacts.len(), 1);
+        assert_eq!(contacts[0].email().as_deref(), Some("admin@contoso.com"));
+    }
+}
@@ -1,30 +1,84 @@
 use crate::Error;
-use azure_core::{TokenCredential, TokenResponse};
-use const_format::formatcp;
+use azure_core::pipeline::Pipeline;
+use azure_core::setters;
+use azure_core::{
+    collect_pinned_stream, resource_to_scope, BearerTokenCredentialPolicy, ClientOptions, Context,
+    PipelineContext, Policy, Request, TokenCredential,
+};
+use futures::stream::{unfold, Stream};
+use getset::Getters;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::pin::Pin;
+use std::sync::Arc;
 use url::Url;
 
-pub(crate) const API_VERSION: &str = "7.0";
-pub(crate) const API_VERSION_PARAM: &str = formatcp!("api-version={}", API_VERSION);
+/// The default version of the Key Vault API.
+pub(crate) const API_VERSION: &str = "7.3";
+pub(crate) const DEFAULT_MAX_RESULTS: usize = 25;
 
 /// Client for Key Vault operations - getting a secret, listing secrets, etc.
 ///
+/// The client is cheap to clone and can be shared across tasks: requests go through an
+/// `azure_core` pipeline, authorized by a `BearerTokenCredentialPolicy` which takes the tenant
+/// of the vault from its authentication challenges.
+///
 /// # Example
 ///
 /// ```no_run
 /// use azure_key_vault::KeyClient;
 /// use azure_identity::token_credentials::DefaultCredential;
+/// use std::sync::Arc;
 /// let creds = DefaultCredential::default();
-/// let client = KeyClient::new(&"https://test-key-vault.vault.azure.net", &creds).unwrap();
+/// let client = KeyClient::new(&"https://test-key-vault.vault.azure.net", Arc::new(creds)).unwrap();
 /// ```
-#[derive(Debug)]
-pub struct KeyClient<'a, T> {
+#[derive(Debug, Clone)]
+pub struct KeyClient {
     pub(crate) vault_url: Url,
-    pub(crate) endpoint: String,
-    pub(crate) token_credential: &'a T,
-    pub(crate) token: Option<TokenResponse>,
+    pub(crate) api_version: String,
+    pub(crate) max_results: usize,
+    pub(crate) pipeline: Pipeline<()>,
+}
+
+/// Options for specifying how a `KeyClient` will behave.
+///
+/// # Example
+///
+/// ```
+/// use azure_core::{ClientOptions, RetryOptions};
+/// use azure_key_vault::KeyClientOptions;
+/// let options = KeyClientOptions::default()
+///     .api_version("7.2")
+///     .max_results(10usize)
+///     .options(ClientOptions::default().retry(RetryOptions::default().max_retries(5u32)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct KeyClientOptions {
+    options: ClientOptions<()>,
+    api_version: String,
+    /// The number of items in each page of the list operations, at most 25.
+    max_results: usize,
+}
+
+impl Default for KeyClientOptions {
+    fn default() -> Self {
+        Self {
+            options: ClientOptions::default(),
+            api_version: API_VERSION.to_owned(),
+            max_results: DEFAULT_MAX_RESULTS,
+        }
+    }
+}
+
+impl KeyClientOptions {
+    setters! {
+        options: ClientOptions<()> => options,
+        api_version: String => api_version,
+        max_results: usize => max_results,
+    }
 }
 
-impl<'a, T: TokenCredential> KeyClient<'a, T> {
+impl KeyClient {
     /// Creates a new `KeyClient`.
     ///
     /// # Example
@@ -32,136 +86,210 @@ impl<'a, T: TokenCredential> KeyClient<'a, T> {
     /// ```no_run
     /// use azure_key_vault::KeyClient;
     /// use azure_identity::token_credentials::DefaultCredential;
+    /// use std::sync::Arc;
     /// let creds = DefaultCredential::default();
-    /// let client = KeyClient::new("test-key-vault.vault.azure.net", &creds).unwrap();
+    /// let client = KeyClient::new("test-key-vault.vault.azure.net", Arc::new(creds)).unwrap();
     /// ```
-    pub fn new(vault_url: &str, token_credential: &'a T) -> Result<Self, Error> {
+    pub fn new(vault_url: &str, token_credential: Arc<dyn TokenCredential>) -> Result<Self, Error> {
+        Self::new_with_options(vault_url, token_credential, KeyClientOptions::default())
+    }
+
+    /// Creates a new `KeyClient` with the given retry, telemetry and transport options, and
+    /// API version.
+    pub fn new_with_options(
+        vault_url: &str,
+        token_credential: Arc<dyn TokenCredential>,
+        options: KeyClientOptions,
+    ) -> Result<Self, Error> {
         let vault_url = Url::parse(vault_url)?;
         let endpoint = extract_endpoint(&vault_url)?;
+        let auth_policy: Arc<dyn Policy<()>> = Arc::new(
+            BearerTokenCredentialPolicy::new(token_credential, vec![resource_to_scope(&endpoint)])
+                .with_tenant_discovery(true),
+        );
+        let pipeline = Pipeline::new(
+            option_env!("CARGO_PKG_NAME"),
+            option_env!("CARGO_PKG_VERSION"),
+            &options.options,
+            Vec::new(),
+            vec![auth_policy],
+        );
         let client = KeyClient {
             vault_url,
-            endpoint,
-            token_credential,
-            token: None,
+            api_version: options.api_version,
+            max_results: options.max_results,
+            pipeline,
         };
         Ok(client)
     }
 
-    pub(crate) async fn refresh_token(&mut self) -> Result<(), Error> {
-        if matches!(&self.token, Some(token) if token.expires_on > chrono::Utc::now()) {
-            // Token is valid, return it.
-            return Ok(());
-        }
-
-        let token = self
-            .token_credential
-            .get_token(&self.endpoint)
-            .await
-            .map_err(|_| Error::Authorization)?;
-        self.token = Some(token);
-        Ok(())
+    /// The version of the Key Vault API targeted by the client.
+    pub fn api_version(&self) -> &str {
+        &self.api_version
     }
 
-    pub(crate) async fn get_authed(&mut self, uri: String) -> Result<String, Error> {
-        self.refresh_token().await?;
+    pub(crate) fn api_version_param(&self) -> String {
+        format!("api-version={}", self.api_version)
+    }
 
-        let resp = reqwest::Client::new()
-            .get(&uri)
-            .bearer_auth(self.token.as_ref().unwrap().token.secret())
-            .send()
-            .await
-            .unwrap();
-        let body = resp.text().await.unwrap();
-        Ok(body)
+    pub(crate) fn api_version_max_results_param(&self) -> String {
+        format!(
+            "{}&maxresults={}",
+            self.api_version_param(),
+            self.max_results
+        )
     }
 
-    pub(crate) async fn put_authed(&mut self, uri: String, body: String) -> Result<String, Error> {
-        self.refresh_token().await?;
+    /// Sends a request, returning the status and the body of the response whatever the status.
+    async fn execute(
+        &self,
+        method: http::Method,
+        uri: String,
+        body: Option<String>,
+    ) -> Result<(http::StatusCode, String), Error> {
+        let mut builder = http::Request::builder().method(method).uri(uri);
+        let body = match body {
+            Some(body) => {
+                builder = builder.header(http::header::CONTENT_TYPE, "application/json");
+                bytes::Bytes::from(body)
+            }
+            None => {
+                builder = builder.header(http::header::CONTENT_LENGTH, 0);
+                bytes::Bytes::new()
+            }
+        };
+        let mut request: Request = builder.body(body).map_err(azure_core::Error::from)?.into();
 
-        let resp = reqwest::Client::new()
-            .put(&uri)
-            .bearer_auth(self.token.as_ref().unwrap().token.secret())
-            .header("Content-Type", "application/json")
-            .body(body)
-            .send()
+        let mut pipeline_context = PipelineContext::new(Context::new(), ());
+        let response = self
+            .pipeline
+            .send(&mut pipeline_context, &mut request)
+            .await?;
+        let (status, _, pinned_stream) = response.deconstruct();
+        let body = collect_pinned_stream(pinned_stream)
             .await
-            .unwrap();
-        let body = resp.text().await?;
-        Ok(body)
+            .map_err(azure_core::Error::from)?;
+        Ok((status, String::from_utf8_lossy(&body).into_owned()))
     }
 
-    pub(crate) async fn post_authed(
-        &mut self,
+    /// Sends a request, failing with the error reported by Key Vault unless it succeeded.
+    async fn send(
+        &self,
+        method: http::Method,
         uri: String,
-        json_body: Option<String>,
+        body: Option<String>,
     ) -> Result<String, Error> {
-        self.refresh_token().await?;
+        let (status, body) = self.execute(method, uri, body).await?;
+        check_status(status, body)
+    }
 
-        let mut req = reqwest::Client::new()
-            .post(&uri)
-            .bearer_auth(self.token.as_ref().unwrap().token.secret());
+    pub(crate) async fn get_authed(&self, uri: String) -> Result<String, Error> {
+        self.send(http::Method::GET, uri, None).await
+    }
 
-        if let Some(body) = json_body {
-            req = req.header("Content-Type", "application/json").body(body);
-        } else {
-            req = req.header("Content-Length", 0);
+    /// Like `get_authed`, but `None` when the resource is not found.
+    pub(crate) async fn get_authed_optional(&self, uri: String) -> Result<Option<String>, Error> {
+        let (status, body) = self.execute(http::Method::GET, uri, None).await?;
+        if status == http::StatusCode::NOT_FOUND {
+            return Ok(None);
         }
+        check_status(status, body).map(Some)
+    }
 
-        let resp = req.send().await?;
+    pub(crate) async fn put_authed(&self, uri: String, body: String) -> Result<String, Error> {
+        self.send(http::Method::PUT, uri, Some(body)).await
+    }
 
-        let body = resp.text().await?;
+    pub(crate) async fn post_authed(
+        &self,
+        uri: String,
+        json_body: Option<String>,
+    ) -> Result<String, Error> {
+        self.send(http::Method::POST, uri, json_body).await
+    }
 
-        let body_serialized = serde_json::from_str::<serde_json::Value>(&body).unwrap();
+    pub(crate) async fn patch_authed(&self, uri: String, body: String) -> Result<String, Error> {
+        self.send(http::Method::PATCH, uri, Some(body)).await
+    }
 
-        if let Some(err) = body_serialized.get("error") {
-            let msg = err.get("message").ok_or(Error::UnparsableError)?;
-            Err(Error::General(msg.to_string()))
-        } else {
-            Ok(body)
-        }
+    pub(crate) async fn delete_authed(&self, uri: String) -> Result<String, Error> {
+        self.send(http::Method::DELETE, uri, None).await
     }
+}
 
-    pub(crate) async fn patch_authed(
-        &mut self,
-        uri: String,
-        body: String,
-    ) -> Result<String, Error> {
-        self.refresh_token().await?;
-
-        let resp = reqwest::Client::new()
-            .patch(&uri)
-            .bearer_auth(self.token.as_ref().unwrap().token.secret())
-            .header("Content-Type", "application/json")
-            .body(body)
-            .send()
-            .await
-            .unwrap();
+/// Returns the body of a successful response, or the error reported by Key Vault.
+fn check_status(status: http::StatusCode, body: String) -> Result<String, Error> {
+    if status.is_success() {
+        return Ok(body);
+    }
 
-        let body = resp.text().await.unwrap();
+    let message = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|body| {
+            body.get("error")?
+                .get("message")?
+                .as_str()
+                .map(ToOwned::to_owned)
+        })
+        .unwrap_or_else(|| format!("{} {}", status, body));
+    Err(Error::General(message))
+}
 
-        let body_serialized = serde_json::from_str::<serde_json::Value>(&body).unwrap();
+/// A page of a list operation.
+#[derive(Debug, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct Page<T> {
+    #[serde(rename = "value", default = "Vec::new")]
+    items: Vec<T>,
+    /// The link to the next page, if any.
+    #[serde(rename = "nextLink")]
+    next_link: Option<String>,
+}
 
-        if let Some(err) = body_serialized.get("error") {
-            let msg = err.get("message").ok_or(Error::UnparsableError)?;
-            Err(Error::General(msg.to_string()))
-        } else {
-            Ok(body)
-        }
+impl<T> Page<T> {
+    pub fn into_items(self) -> Vec<T> {
+        self.items
     }
+}
 
-    pub(crate) async fn delete_authed(&mut self, uri: String) -> Result<String, Error> {
-        self.refresh_token().await?;
+/// The pages of a list operation, requested as the stream is polled.
+pub type PageStream<'b, T> = Pin<Box<dyn Stream<Item = Result<Page<T>, Error>> + Send + 'b>>;
 
-        let resp = reqwest::Client::new()
-            .delete(&uri)
-            .bearer_auth(self.token.as_ref().unwrap().token.secret())
-            .header("Content-Type", "application/json")
-            .send()
-            .await
-            .unwrap();
-        let body = resp.text().await.unwrap();
-        Ok(body)
+impl KeyClient {
+    /// Streams the pages starting at `uri`, following their `nextLink`.
+    pub(crate) fn list_pages<'b, I>(&'b self, uri: Url) -> PageStream<'b, I>
+    where
+        I: DeserializeOwned + Send + 'b,
+    {
+        Box::pin(unfold(Some((self, uri)), |state| async move {
+            let (client, uri) = state?;
+            let page = match client.get_authed(uri.to_string()).await {
+                Ok(body) => parse_response::<Page<I>>(&body),
+                Err(error) => Err(error),
+            };
+            let page = match page {
+                Ok(page) => page,
+                Err(error) => return Some((Err(error), None)),
+            };
+            match page.next_link.as_deref().filter(|link| !link.is_empty()) {
+                None => Some((Ok(page), None)),
+                Some(next_link) => match Url::parse(next_link) {
+                    Ok(next_uri) => Some((Ok(page), Some((client, next_uri)))),
+                    Err(error) => Some((Err(error.into()), None)),
+                },
+            }
+        }))
+    }
+}
+
+/// Deserializes a response body, returning the error reported by Key Vault if any.
+pub(crate) fn parse_response<R: DeserializeOwned>(body: &str) -> Result<R, Error> {
+    let value = serde_json::from_str::<serde_json::Value>(body)?;
+    if let Some(err) = value.get("error") {
+        let msg = err.get("message").ok_or(Error::UnparsableError)?;
+        return Err(Error::General(msg.to_string()));
     }
+    Ok(serde_json::from_value(value)?)
 }
 
 /// Helper to get vault endpoint with a scheme and a trailing slash
@@ -199,4 +327,53 @@ mod tests {
                 .unwrap();
         assert_eq!(suffix, "some-scheme://vault.azure.net");
     }
+
+    #[tokio::test]
+    async fn client_is_shared_across_tasks() {
+        use crate::tests::MockCredential;
+        use mockito::{mock, Matcher};
+        use serde_json::json;
+
+        fn assert_shareable<C: Clone + Send + Sync>(_client: &C) {}
+
+        let _m = mock("GET", "/secrets/shared-secret/")
+            .match_query(Matcher::UrlEncoded("api-version".into(), "7.2".into()))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "value": "secret-value",
+                    "id": "https://test-keyvault.vault.azure.net/secrets/shared-secret/1",
+                    "attributes": {
+                        "enabled": true,
+                        "created": 1482188947,
+                        "updated": 1482188947,
+                        "recoveryLevel": "Recoverable+Purgeable"
+                    }
+                })
+                .to_string(),
+            )
+            .with_status(200)
+            .expect(2)
+            .create();
+
+        let client = KeyClient::new_with_options(
+            &mockito::server_url(),
+            Arc::new(MockCredential),
+            KeyClientOptions::default().api_version("7.2"),
+        )
+        .unwrap();
+        assert_shareable(&client);
+        assert_eq!(client.api_version(), "7.2");
+
+        let tasks: Vec<_> = (0..2)
+            .map(|_| {
+                let client = client.clone();
+                tokio::spawn(async move { client.get_secret("shared-secret").await })
+            })
+            .collect();
+        for task in tasks {
+            assert_eq!(task.await.unwrap().unwrap().value(), "secret-value");
+        }
+        _m.assert();
+    }
 }
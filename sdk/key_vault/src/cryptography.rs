@@ -0,0 +1,664 @@
+use crate::key::{
+    DecryptParameters, DecryptResult, EncryptParameters, EncryptResult, JsonWebKey,
+    KeyWrapAlgorithm, SignResult, SignatureAlgorithm, UnwrapResult, VerifyResult, WrapResult,
+    BASE64_URL_SAFE,
+};
+use crate::{Error, KeyClient};
+
+use chrono::{DateTime, Utc};
+use futures::lock::Mutex;
+
+use openssl::bn::BigNum;
+use openssl::ec::{EcGroup, EcKey};
+use openssl::ecdsa::EcdsaSig;
+use openssl::encrypt::Encrypter;
+use openssl::hash::MessageDigest;
+use openssl::md::Md;
+use openssl::nid::Nid;
+use openssl::pkey::{PKey, Public};
+use openssl::pkey_ctx::PkeyCtx;
+use openssl::rsa::{Padding, Rsa};
+use openssl::sign::RsaPssSaltlen;
+use std::sync::Arc;
+
+/// Client performing cryptographic operations with a single key.
+///
+/// Operations only needing the public part of an asymmetric key, `encrypt`, `wrap_key` and
+/// `verify`, are performed locally from the `JsonWebKey` without a round trip to the service.
+/// The key is fetched on first use, or can be given with `with_key`. When the key cannot be
+/// fetched, the algorithm is not supported locally, or the key is disabled, not yet valid or
+/// expired, the operations are sent to the service.
+///
+/// The operations take `&self`: the fetched key is shared by the clones of the client.
+///
+/// # Example
+///
+/// ```no_run
+/// use azure_key_vault::{CryptographyClient, KeyClient};
+/// use azure_key_vault::key::KeyWrapAlgorithm;
+/// use azure_identity::token_credentials::DefaultCredential;
+/// use std::sync::Arc;
+/// use tokio::runtime::Runtime;
+///
+/// async fn example() {
+///     let creds = DefaultCredential::default();
+///     let client = KeyClient::new(&"KEYVAULT_URL", Arc::new(creds)).unwrap();
+///     let crypto = CryptographyClient::new(client, "KEY_NAME", "KEY_VERSION");
+///     let wrapped = crypto
+///         .wrap_key(KeyWrapAlgorithm::RsaOaep256, b"data encryption key")
+///         .await
+///         .unwrap();
+///     dbg!(&wrapped);
+/// }
+///
+/// Runtime::new().unwrap().block_on(example());
+/// ```
+#[derive(Debug, Clone)]
+pub struct CryptographyClient {
+    client: KeyClient,
+    key_name: String,
+    key_version: String,
+    local_key: Arc<Mutex<LocalKeyCache>>,
+}
+
+#[derive(Debug, Default)]
+struct LocalKeyCache {
+    fetched: bool,
+    key: Option<LocalKey>,
+}
+
+/// A key used for the local operations, with the attributes limiting its use.
+#[derive(Debug)]
+struct LocalKey {
+    key: JsonWebKey,
+    enabled: Option<bool>,
+    not_before: Option<DateTime<Utc>>,
+    expires_on: Option<DateTime<Utc>>,
+}
+
+impl LocalKey {
+    fn is_valid_at(&self, now: DateTime<Utc>) -> bool {
+        self.enabled != Some(false)
+            && self.not_before.is_none_or(|not_before| not_before <= now)
+            && self.expires_on.is_none_or(|expires_on| now < expires_on)
+    }
+}
+
+impl CryptographyClient {
+    /// Creates a new `CryptographyClient` for a version of a key.
+    pub fn new(client: KeyClient, key_name: &str, key_version: &str) -> Self {
+        Self {
+            client,
+            key_name: key_name.to_owned(),
+            key_version: key_version.to_owned(),
+            local_key: Arc::default(),
+        }
+    }
+
+    /// Uses `key` for the local operations instead of fetching it.
+    ///
+    /// Without its attributes, the key is considered enabled and valid at any time.
+    pub fn with_key(mut self, key: JsonWebKey) -> Self {
+        self.local_key = Arc::new(Mutex::new(LocalKeyCache {
+            fetched: true,
+            key: Some(LocalKey {
+                key,
+                enabled: None,
+                not_before: None,
+                expires_on: None,
+            }),
+        }));
+        self
+    }
+
+    /// The `KeyClient` used for the operations sent to the service.
+    pub fn key_client(&self) -> &KeyClient {
+        &self.client
+    }
+
+    /// The key used for the local operations, fetching it on first use.
+    ///
+    /// `None` when the key is disabled, not valid at the current time, or does not allow
+    /// `operation`: the service then performs the operation, or reports why it cannot.
+    async fn local_key(&self, operation: &str) -> Option<JsonWebKey> {
+        let mut cache = self.local_key.lock().await;
+        if !cache.fetched {
+            cache.fetched = true;
+            // without the keys/get permission every operation is sent to the service
+            if let Ok(key) = self
+                .client
+                .get_key(&self.key_name, Some(&self.key_version))
+                .await
+            {
+                let attributes = key.properties().attributes();
+                cache.key = Some(LocalKey {
+                    key: key.key().clone(),
+                    enabled: *attributes.enabled(),
+                    not_before: *attributes.not_before(),
+                    expires_on: *attributes.expires_on(),
+                });
+            }
+        }
+        cache
+            .key
+            .as_ref()
+            .filter(|key| key.is_valid_at(Utc::now()))
+            .map(|key| &key.key)
+            .filter(|key| match key.key_ops() {
+                Some(key_ops) => key_ops.iter().any(|key_op| key_op == operation),
+                None => true,
+            })
+            .cloned()
+    }
+
+    /// Encrypts `plaintext`, locally for the RSA algorithms.
+    pub async fn encrypt(&self, parameters: EncryptParameters) -> Result<EncryptResult, Error> {
+        if let Some(key) = self.local_key("encrypt").await {
+            if let Some(result) = rsa_encrypt(
+                &key,
+                &parameters.algorithm().to_string(),
+                parameters.plaintext(),
+            ) {
+                return Ok(EncryptResult {
+                    ciphertext: result?,
+                    algorithm: parameters.algorithm().clone(),
+                    key_id: key.id().clone().unwrap_or_default(),
+                    iv: None,
+                    authentication_tag: None,
+                    additional_authenticated_data: None,
+                });
+            }
+        }
+        self.client
+            .encrypt(&self.key_name, &self.key_version, parameters)
+            .await
+    }
+
+    /// Decrypts `ciphertext`, always through the service.
+    pub async fn decrypt(&self, parameters: DecryptParameters) -> Result<DecryptResult, Error> {
+        self.client
+            .decrypt(&self.key_name, &self.key_version, parameters)
+            .await
+    }
+
+    /// Wraps `key`, locally for the RSA algorithms.
+    pub async fn wrap_key(
+        &self,
+        algorithm: KeyWrapAlgorithm,
+        key: &[u8],
+    ) -> Result<WrapResult, Error> {
+        if let Some(local_key) = self.local_key("wrapKey").await {
+            if let Some(result) = rsa_encrypt(&local_key, &algorithm.to_string(), key) {
+                return Ok(WrapResult {
+                    encrypted_key: result?,
+                    algorithm,
+                    key_id: local_key.id().clone().unwrap_or_default(),
+                });
+            }
+        }
+        self.client
+            .wrap_key(algorithm, &self.key_name, &self.key_version, key)
+            .await
+    }
+
+    /// Unwraps `encrypted_key`, always through the service.
+    pub async fn unwrap_key(
+        &self,
+        algorithm: KeyWrapAlgorithm,
+        encrypted_key: &[u8],
+    ) -> Result<UnwrapResult, Error> {
+        self.client
+            .unwrap_key(algorithm, &self.key_name, &self.key_version, encrypted_key)
+            .await
+    }
+
+    /// Signs `digest`, always through the service.
+    pub async fn sign(
+        &self,
+        algorithm: SignatureAlgorithm,
+        digest: &[u8],
+    ) -> Result<SignResult, Error> {
+        self.client
+            .sign(
+                algorithm,
+                &self.key_name,
+                &self.key_version,
+                &base64::encode_config(digest, BASE64_URL_SAFE),
+            )
+            .await
+    }
+
+    /// Verifies the `signature` of `digest`, locally for the RSA and EC algorithms.
+    pub async fn verify(
+        &self,
+        algorithm: SignatureAlgorithm,
+        digest: &[u8],
+        signature: &[u8],
+    ) -> Result<VerifyResult, Error> {
+        if let Some(key) = self.local_key("verify").await {
+            if let Some(result) = verify(&key, &algorithm, digest, signature) {
+                return Ok(VerifyResult {
+                    is_valid: result?,
+                    algorithm,
+                });
+            }
+        }
+        self.client
+            .verify(
+                algorithm,
+                &self.key_name,
+                &self.key_version,
+                digest,
+                signature,
+            )
+            .await
+    }
+}
+
+fn rsa_public_key(key: &JsonWebKey) -> Option<Result<PKey<Public>, Error>> {
+    if !matches!(key.key_type().as_str(), "RSA" | "RSA-HSM") {
+        return None;
+    }
+    let (n, e) = match (key.n(), key.e()) {
+        (Some(n), Some(e)) => (n, e),
+        _ => return None,
+    };
+    Some((|| {
+        let rsa = Rsa::from_public_components(BigNum::from_slice(n)?, BigNum::from_slice(e)?)?;
+        Ok(PKey::from_rsa(rsa)?)
+    })())
+}
+
+fn ec_public_key(key: &JsonWebKey, curve_name: &str) -> Option<Result<EcKey<Public>, Error>> {
+    if !matches!(key.key_type().as_str(), "EC" | "EC-HSM")
+        || key.curve_name().as_deref() != Some(curve_name)
+    {
+        return None;
+    }
+    let nid = match curve_name {
+        "P-256" => Nid::X9_62_PRIME256V1,
+        "P-256K" => Nid::SECP256K1,
+        "P-384" => Nid::SECP384R1,
+        "P-521" => Nid::SECP521R1,
+        _ => return None,
+    };
+    let (x, y) = match (key.x(), key.y()) {
+        (Some(x), Some(y)) => (x, y),
+        _ => return None,
+    };
+    Some((|| {
+        let group = EcGroup::from_curve_name(nid)?;
+        Ok(EcKey::from_public_key_affine_coordinates(
+            &group,
+            &*BigNum::from_slice(x)?,
+            &*BigNum::from_slice(y)?,
+        )?)
+    })())
+}
+
+/// Encrypts with the public part of an RSA key, `None` when done by the service instead.
+fn rsa_encrypt(key: &JsonWebKey, algorithm: &str, data: &[u8]) -> Option<Result<Vec<u8>, Error>> {
+    let (padding, digest) = match algorithm {
+        "RSA-OAEP" => (Padding::PKCS1_OAEP, None),
+        "RSA-OAEP-256" => (Padding::PKCS1_OAEP, Some(MessageDigest::sha256())),
+        "RSA1_5" => (Padding::PKCS1, None),
+        _ => return None,
+    };
+    let public_key = match rsa_public_key(key)? {
+        Ok(public_key) => public_key,
+        Err(error) => return Some(Err(error)),
+    };
+    Some((|| {
+        let mut encrypter = Encrypter::new(&public_key)?;
+        encrypter.set_rsa_padding(padding)?;
+        if let Some(digest) = digest {
+            encrypter.set_rsa_oaep_md(digest)?;
+            encrypter.set_rsa_mgf1_md(digest)?;
+        }
+        let mut encrypted = vec![0; encrypter.encrypt_len(data)?];
+        let length = encrypter.encrypt(data, &mut encrypted)?;
+        encrypted.truncate(length);
+        Ok(encrypted)
+    })())
+}
+
+/// Verifies a signature with the public part of a key, `None` when done by the service instead.
+fn verify(
+    key: &JsonWebKey,
+    algorithm: &SignatureAlgorithm,
+    digest: &[u8],
+    signature: &[u8],
+) -> Option<Result<bool, Error>> {
+    let (md, padding) = match algorithm {
+        SignatureAlgorithm::RS256 => (Md::sha256(), Padding::PKCS1),
+        SignatureAlgorithm::RS384 => (Md::sha384(), Padding::PKCS1),
+        SignatureAlgorithm::RS512 => (Md::sha512(), Padding::PKCS1),
+        SignatureAlgorithm::PS256 => (Md::sha256(), Padding::PKCS1_PSS),
+        SignatureAlgorithm::PS384 => (Md::sha384(), Padding::PKCS1_PSS),
+        SignatureAlgorithm::PS512 => (Md::sha512(), Padding::PKCS1_PSS),
+        SignatureAlgorithm::ES256 => return ec_verify(key, "P-256", digest, signature),
+        SignatureAlgorithm::ES256K => return ec_verify(key, "P-256K", digest, signature),
+        SignatureAlgorithm::ES384 => return ec_verify(key, "P-384", digest, signature),
+        SignatureAlgorithm::ES512 => return ec_verify(key, "P-521", digest, signature),
+        SignatureAlgorithm::Custom(_) => return None,
+    };
+    let public_key = match rsa_public_key(key)? {
+        Ok(public_key) => public_key,
+        Err(error) => return Some(Err(error)),
+    };
+    Some((|| {
+        let mut ctx = PkeyCtx::new(&public_key)?;
+        ctx.verify_init()?;
+        ctx.set_rsa_padding(padding)?;
+        if padding == Padding::PKCS1_PSS {
+            ctx.set_rsa_pss_saltlen(RsaPssSaltlen::DIGEST_LENGTH)?;
+        }
+        ctx.set_signature_md(md)?;
+        // a malformed signature is reported as an error by openssl
+        Ok(ctx.verify(digest, signature).unwrap_or(false))
+    })())
+}
+
+/// Verifies a JWS signature, the concatenation of `r` and `s`, with an EC key.
+fn ec_verify(
+    key: &JsonWebKey,
+    curve_name: &str,
+    digest: &[u8],
+    signature: &[u8],
+) -> Option<Result<bool, Error>> {
+    let public_key = match ec_public_key(key, curve_name)? {
+        Ok(public_key) => public_key,
+        Err(error) => return Some(Err(error)),
+    };
+    let half = signature.len() / 2;
+    if half == 0 || half * 2 != signature.len() {
+        return Some(Ok(false));
+    }
+    let (r, s) = signature.split_at(half);
+    Some((|| {
+        let signature =
+            EcdsaSig::from_private_components(BigNum::from_slice(r)?, BigNum::from_slice(s)?)?;
+        Ok(signature.verify(digest, &public_key)?)
+    })())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use mockito::{mock, Matcher};
+    use openssl::ec::EcKey;
+    use openssl::pkey::Private;
+    use openssl::sha::{sha256, sha512};
+    use openssl::sign::Signer;
+    use serde_json::json;
+
+    use crate::client::API_VERSION;
+    use crate::key::EncryptionAlgorithm;
+    use crate::mock_client;
+    use crate::tests::MockCredential;
+
+    fn encode(bytes: &[u8]) -> String {
+        base64::encode_config(bytes, BASE64_URL_SAFE)
+    }
+
+    fn rsa_key() -> (Rsa<Private>, JsonWebKey) {
+        let rsa = Rsa::generate(2048).unwrap();
+        let key = serde_json::from_value(json!({
+            "kid": "https://myvault.vault.azure.net/keys/local-key/1",
+            "kty": "RSA",
+            "key_ops": ["encrypt", "decrypt", "verify", "wrapKey", "unwrapKey"],
+            "n": encode(&rsa.n().to_vec()),
+            "e": encode(&rsa.e().to_vec()),
+        }))
+        .unwrap();
+        (rsa, key)
+    }
+
+    #[tokio::test]
+    async fn encrypts_and_wraps_locally() {
+        let (rsa, key) = rsa_key();
+        let private_key = PKey::from_rsa(rsa).unwrap();
+        let creds = MockCredential;
+        let client =
+            CryptographyClient::new(mock_client!(&"test-keyvault", creds,), "local-key", "1")
+                .with_key(key);
+
+        let encrypted = client
+            .encrypt(EncryptParameters::new(
+                EncryptionAlgorithm::RsaOaep256,
+                "plaintext",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(
+            encrypted.key_id(),
+            "https://myvault.vault.azure.net/keys/local-key/1"
+        );
+        let mut decrypter = openssl::encrypt::Decrypter::new(&private_key).unwrap();
+        decrypter.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
+        decrypter.set_rsa_oaep_md(MessageDigest::sha256()).unwrap();
+        decrypter.set_rsa_mgf1_md(MessageDigest::sha256()).unwrap();
+        let mut decrypted = vec![0; decrypter.decrypt_len(encrypted.ciphertext()).unwrap()];
+        let length = decrypter
+            .decrypt(encrypted.ciphertext(), &mut decrypted)
+            .unwrap();
+        assert_eq!(&decrypted[..length], b"plaintext");
+
+        let wrapped = client
+            .wrap_key(KeyWrapAlgorithm::Rsa15, b"key")
+            .await
+            .unwrap();
+        let mut decrypted = vec![0; private_key.size()];
+        let length = private_key
+            .rsa()
+            .unwrap()
+            .private_decrypt(wrapped.encrypted_key(), &mut decrypted, Padding::PKCS1)
+            .unwrap();
+        assert_eq!(&decrypted[..length], b"key");
+    }
+
+    #[tokio::test]
+    async fn verifies_rsa_locally() {
+        let (rsa, key) = rsa_key();
+        let private_key = PKey::from_rsa(rsa).unwrap();
+        let creds = MockCredential;
+        let client =
+            CryptographyClient::new(mock_client!(&"test-keyvault", creds,), "local-key", "1")
+                .with_key(key);
+
+        let mut signer = Signer::new(MessageDigest::sha512(), &private_key).unwrap();
+        signer.set_rsa_padding(Padding::PKCS1_PSS).unwrap();
+        signer
+            .set_rsa_pss_saltlen(RsaPssSaltlen::DIGEST_LENGTH)
+            .unwrap();
+        let signature = signer.sign_oneshot_to_vec(b"message").unwrap();
+
+        let digest = sha512(b"message");
+        let verified = client
+            .verify(SignatureAlgorithm::PS512, &digest, &signature)
+            .await
+            .unwrap();
+        assert!(verified.is_valid());
+        let tampered = client
+            .verify(SignatureAlgorithm::PS512, &sha512(b"other"), &signature)
+            .await
+            .unwrap();
+        assert!(!tampered.is_valid());
+    }
+
+    #[tokio::test]
+    async fn verifies_ec_locally() {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let ec = EcKey::generate(&group).unwrap();
+        let mut ctx = openssl::bn::BigNumContext::new().unwrap();
+        let (mut x, mut y) = (BigNum::new().unwrap(), BigNum::new().unwrap());
+        ec.public_key()
+            .affine_coordinates(&group, &mut x, &mut y, &mut ctx)
+            .unwrap();
+        let key = serde_json::from_value(json!({
+            "kid": "https://myvault.vault.azure.net/keys/local-key/1",
+            "kty": "EC",
+            "crv": "P-256",
+            "x": encode(&x.to_vec_padded(32).unwrap()),
+            "y": encode(&y.to_vec_padded(32).unwrap()),
+        }))
+        .unwrap();
+        let creds = MockCredential;
+        let client =
+            CryptographyClient::new(mock_client!(&"test-keyvault", creds,), "local-key", "1")
+                .with_key(key);
+
+        let digest = sha256(b"message");
+        let signature = EcdsaSig::sign(&digest, &ec).unwrap();
+        let mut jws_signature = signature.r().to_vec_padded(32).unwrap();
+        jws_signature.extend(signature.s().to_vec_padded(32).unwrap());
+
+        let verified = client
+            .verify(SignatureAlgorithm::ES256, &digest, &jws_signature)
+            .await
+            .unwrap();
+        assert!(verified.is_valid());
+    }
+
+    #[tokio::test]
+    async fn fetches_key_and_decrypts_remotely() {
+        let (rsa, key) = rsa_key();
+        let _get = mock("GET", "/keys/remote-key/1")
+            .match_query(Matcher::UrlEncoded(
+                "api-version".into(),
+                API_VERSION.into(),
+            ))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "key": serde_json::to_value(&key).unwrap(),
+                    "attributes": { "enabled": true }
+                })
+                .to_string(),
+            )
+            .with_status(200)
+            .create();
+        let decrypt = mock("POST", "/keys/remote-key/1/decrypt")
+            .match_query(Matcher::UrlEncoded(
+                "api-version".into(),
+                API_VERSION.into(),
+            ))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "kid": "https://myvault.vault.azure.net/keys/remote-key/1",
+                    "value": encode(b"key")
+                })
+                .to_string(),
+            )
+            .with_status(200)
+            .expect(1)
+            .create();
+
+        let creds = MockCredential;
+        let client =
+            CryptographyClient::new(mock_client!(&"test-keyvault", creds,), "remote-key", "1");
+
+        let wrapped = client
+            .wrap_key(KeyWrapAlgorithm::RsaOaep, b"key")
+            .await
+            .unwrap();
+        let mut decrypted = vec![0; rsa.size() as usize];
+        let length = rsa
+            .private_decrypt(wrapped.encrypted_key(), &mut decrypted, Padding::PKCS1_OAEP)
+            .unwrap();
+        assert_eq!(&decrypted[..length], b"key");
+
+        let unwrapped = client
+            .decrypt(DecryptParameters::new(
+                EncryptionAlgorithm::RsaOaep,
+                wrapped.encrypted_key().clone(),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(unwrapped.plaintext(), b"key");
+        decrypt.assert();
+    }
+
+    #[tokio::test]
+    async fn sends_operations_with_expired_key_to_service() {
+        let (_, key) = rsa_key();
+        let _get = mock("GET", "/keys/expired-key/1")
+            .match_query(Matcher::UrlEncoded(
+                "api-version".into(),
+                API_VERSION.into(),
+            ))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "key": serde_json::to_value(&key).unwrap(),
+                    "attributes": { "enabled": true, "exp": 1500000000 }
+                })
+                .to_string(),
+            )
+            .with_status(200)
+            .create();
+        let wrap = mock("POST", "/keys/expired-key/1/wrapkey")
+            .match_query(Matcher::UrlEncoded(
+                "api-version".into(),
+                API_VERSION.into(),
+            ))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "error": { "code": "Forbidden", "message": "Operation wrapKey is not allowed on an expired key." }
+                })
+                .to_string(),
+            )
+            .with_status(403)
+            .expect(1)
+            .create();
+
+        let creds = MockCredential;
+        let client =
+            CryptographyClient::new(mock_client!(&"test-keyvault", creds,), "expired-key", "1");
+
+        assert!(client
+            .wrap_key(KeyWrapAlgorithm::RsaOaep, b"key")
+            .await
+            .is_err());
+        wrap.assert();
+    }
+
+    #[tokio::test]
+    async fn signs_encoded_digest() {
+        let digest = sha256(b"message");
+        let sign = mock("POST", "/keys/remote-key/1/sign")
+            .match_query(Matcher::UrlEncoded(
+                "api-version".into(),
+                API_VERSION.into(),
+            ))
+            .match_body(Matcher::Json(json!({
+                "alg": "ES256",
+                "value": encode(&digest)
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "kid": "https://myvault.vault.azure.net/keys/remote-key/1",
+                    "value": encode(b"signature")
+                })
+                .to_string(),
+            )
+            .with_status(200)
+            .expect(1)
+            .create();
+
+        let creds = MockCredential;
+        let client =
+            CryptographyClient::new(mock_client!(&"test-keyvault", creds,), "remote-key", "1");
+
+        let signed = client
+            .sign(SignatureAlgorithm::ES256, &digest)
+            .await
+            .unwrap();
+        assert_eq!(signed.signature(), b"signature");
+        sign.assert();
+    }
+}
@@ -1,6 +1,5 @@
 use std::fmt::{Debug, Display};
 
-use azure_core::TokenCredential;
 use base64::{CharacterSet, Config};
 use chrono::serde::ts_seconds_option;
 use chrono::{DateTime, Utc};
@@ -8,9 +7,9 @@ use getset::Getters;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::{Map, Value};
 
-use crate::client::API_VERSION_PARAM;
+use crate::client::parse_response;
 use crate::Error;
-use crate::KeyClient;
+use crate::{KeyClient, PageStream};
 
 /// A KeyBundle consisting of a WebKey plus its attributes.
 #[derive(Debug, Deserialize, Getters)]
@@ -57,7 +56,7 @@ pub struct KeyAttributes {
     updated_on: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Getters)]
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
 #[getset(get = "pub")]
 pub struct JsonWebKey {
     /// Elliptic curve name. For valid values, see JsonWebKeyCurveName.
@@ -158,7 +157,7 @@ pub struct JsonWebKey {
     y: Option<Vec<u8>>,
 }
 
-const BASE64_URL_SAFE: Config = Config::new(CharacterSet::UrlSafe, false);
+pub(crate) const BASE64_URL_SAFE: Config = Config::new(CharacterSet::UrlSafe, false);
 
 fn ser_base64_opt<S>(bytes: &Option<Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error>
 where
@@ -181,11 +180,11 @@ where
     Ok(res)
 }
 
-fn deser_base64_opt<'de, D>(deserializer: D) -> Result<Option<Vec<u8>>, D::Error>
+pub(crate) fn deser_base64_opt<'de, D>(deserializer: D) -> Result<Option<Vec<u8>>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let s: Option<&str> = Option::deserialize(deserializer)?;
+    let s: Option<String> = Option::deserialize(deserializer)?;
     let res = match s {
         Some(s) => {
             Some(base64::decode_config(s, BASE64_URL_SAFE).map_err(serde::de::Error::custom)?)
@@ -195,6 +194,10 @@ where
     Ok(res)
 }
 
+fn encode_base64(bytes: &[u8]) -> Value {
+    Value::String(base64::encode_config(bytes, BASE64_URL_SAFE))
+}
+
 #[derive(Debug, Deserialize, Getters)]
 #[getset(get = "pub")]
 pub struct SignResult {
@@ -210,80 +213,1162 @@ pub struct SignResult {
     key_id: String,
 }
 
-/// The signing/verification algorithm identifier
-#[derive(Debug, Deserialize, Serialize)]
-#[serde(untagged)]
-pub enum SignatureAlgorithm {
-    ES256,  // ECDSA using P-256 and SHA-256, as described in https://tools.ietf.org/html/rfc7518.
-    ES256K, // ECDSA using P-256K and SHA-256, as described in https://tools.ietf.org/html/rfc7518
-    ES384,  // ECDSA using P-384 and SHA-384, as described in https://tools.ietf.org/html/rfc7518
-    ES512,  // ECDSA using P-521 and SHA-512, as described in https://tools.ietf.org/html/rfc7518
-    PS256, // RSASSA-PSS using SHA-256 and MGF1 with SHA-256, as described in https://tools.ietf.org/html/rfc7518
-    PS384, // RSASSA-PSS using SHA-384 and MGF1 with SHA-384, as described in https://tools.ietf.org/html/rfc7518
-    PS512, // RSASSA-PSS using SHA-512 and MGF1 with SHA-512, as described in https://tools.ietf.org/html/rfc7518
-    RS256, // RSASSA-PKCS1-v1_5 using SHA-256, as described in https://tools.ietf.org/html/rfc7518
-    RS384, // RSASSA-PKCS1-v1_5 using SHA-384, as described in https://tools.ietf.org/html/rfc7518
-    RS512, // RSASSA-PKCS1-v1_5 using SHA-512, as described in https://tools.ietf.org/html/rfc7518
-    Custom(String),
-}
+#[derive(Debug, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct VerifyResult {
+    /// True if the signature is valid for the digest.
+    #[serde(rename = "value")]
+    pub(crate) is_valid: bool,
+    #[serde(skip)]
+    pub(crate) algorithm: SignatureAlgorithm,
+}
+
+/// The signing/verification algorithm identifier
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum SignatureAlgorithm {
+    ES256,  // ECDSA using P-256 and SHA-256, as described in https://tools.ietf.org/html/rfc7518.
+    ES256K, // ECDSA using P-256K and SHA-256, as described in https://tools.ietf.org/html/rfc7518
+    ES384,  // ECDSA using P-384 and SHA-384, as described in https://tools.ietf.org/html/rfc7518
+    ES512,  // ECDSA using P-521 and SHA-512, as described in https://tools.ietf.org/html/rfc7518
+    PS256, // RSASSA-PSS using SHA-256 and MGF1 with SHA-256, as described in https://tools.ietf.org/html/rfc7518
+    PS384, // RSASSA-PSS using SHA-384 and MGF1 with SHA-384, as described in https://tools.ietf.org/html/rfc7518
+    PS512, // RSASSA-PSS using SHA-512 and MGF1 with SHA-512, as described in https://tools.ietf.org/html/rfc7518
+    RS256, // RSASSA-PKCS1-v1_5 using SHA-256, as described in https://tools.ietf.org/html/rfc7518
+    RS384, // RSASSA-PKCS1-v1_5 using SHA-384, as described in https://tools.ietf.org/html/rfc7518
+    RS512, // RSASSA-PKCS1-v1_5 using SHA-512, as described in https://tools.ietf.org/html/rfc7518
+    Custom(String),
+}
+
+impl Default for SignatureAlgorithm {
+    fn default() -> Self {
+        SignatureAlgorithm::Custom("".to_string())
+    }
+}
+
+impl Display for SignatureAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+/// The encryption/decryption algorithm identifier
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncryptionAlgorithm {
+    /// RSAES using Optimal Asymmetric Encryption Padding, with SHA-1 and MGF1 with SHA-1.
+    RsaOaep,
+    /// RSAES using Optimal Asymmetric Encryption Padding, with SHA-256 and MGF1 with SHA-256.
+    RsaOaep256,
+    /// RSAES-PKCS1-v1_5.
+    Rsa15,
+    /// AES-GCM with a 128 bit key, managed HSM only.
+    A128Gcm,
+    /// AES-GCM with a 192 bit key, managed HSM only.
+    A192Gcm,
+    /// AES-GCM with a 256 bit key, managed HSM only.
+    A256Gcm,
+    /// AES-CBC with a 128 bit key, managed HSM only.
+    A128Cbc,
+    /// AES-CBC with a 192 bit key, managed HSM only.
+    A192Cbc,
+    /// AES-CBC with a 256 bit key, managed HSM only.
+    A256Cbc,
+    /// AES-CBC with a 128 bit key and PKCS#7 padding, managed HSM only.
+    A128CbcPad,
+    /// AES-CBC with a 192 bit key and PKCS#7 padding, managed HSM only.
+    A192CbcPad,
+    /// AES-CBC with a 256 bit key and PKCS#7 padding, managed HSM only.
+    A256CbcPad,
+    Custom(String),
+}
+
+impl Default for EncryptionAlgorithm {
+    fn default() -> Self {
+        EncryptionAlgorithm::Custom("".to_string())
+    }
+}
+
+impl Display for EncryptionAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let algorithm = match self {
+            EncryptionAlgorithm::RsaOaep => "RSA-OAEP",
+            EncryptionAlgorithm::RsaOaep256 => "RSA-OAEP-256",
+            EncryptionAlgorithm::Rsa15 => "RSA1_5",
+            EncryptionAlgorithm::A128Gcm => "A128GCM",
+            EncryptionAlgorithm::A192Gcm => "A192GCM",
+            EncryptionAlgorithm::A256Gcm => "A256GCM",
+            EncryptionAlgorithm::A128Cbc => "A128CBC",
+            EncryptionAlgorithm::A192Cbc => "A192CBC",
+            EncryptionAlgorithm::A256Cbc => "A256CBC",
+            EncryptionAlgorithm::A128CbcPad => "A128CBCPAD",
+            EncryptionAlgorithm::A192CbcPad => "A192CBCPAD",
+            EncryptionAlgorithm::A256CbcPad => "A256CBCPAD",
+            EncryptionAlgorithm::Custom(algorithm) => algorithm,
+        };
+        f.write_str(algorithm)
+    }
+}
+
+/// The key wrapping/unwrapping algorithm identifier
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyWrapAlgorithm {
+    /// RSAES using Optimal Asymmetric Encryption Padding, with SHA-1 and MGF1 with SHA-1.
+    RsaOaep,
+    /// RSAES using Optimal Asymmetric Encryption Padding, with SHA-256 and MGF1 with SHA-256.
+    RsaOaep256,
+    /// RSAES-PKCS1-v1_5.
+    Rsa15,
+    /// AES key wrap with a 128 bit key, managed HSM only.
+    A128Kw,
+    /// AES key wrap with a 192 bit key, managed HSM only.
+    A192Kw,
+    /// AES key wrap with a 256 bit key, managed HSM only.
+    A256Kw,
+    Custom(String),
+}
+
+impl Default for KeyWrapAlgorithm {
+    fn default() -> Self {
+        KeyWrapAlgorithm::Custom("".to_string())
+    }
+}
+
+impl Display for KeyWrapAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let algorithm = match self {
+            KeyWrapAlgorithm::RsaOaep => "RSA-OAEP",
+            KeyWrapAlgorithm::RsaOaep256 => "RSA-OAEP-256",
+            KeyWrapAlgorithm::Rsa15 => "RSA1_5",
+            KeyWrapAlgorithm::A128Kw => "A128KW",
+            KeyWrapAlgorithm::A192Kw => "A192KW",
+            KeyWrapAlgorithm::A256Kw => "A256KW",
+            KeyWrapAlgorithm::Custom(algorithm) => algorithm,
+        };
+        f.write_str(algorithm)
+    }
+}
+
+/// The parameters of an encryption.
+///
+/// The initialization vector and additional authenticated data only apply to the AES algorithms.
+#[derive(Debug, Clone, Getters)]
+#[getset(get = "pub")]
+pub struct EncryptParameters {
+    algorithm: EncryptionAlgorithm,
+    plaintext: Vec<u8>,
+    iv: Option<Vec<u8>>,
+    additional_authenticated_data: Option<Vec<u8>>,
+}
+
+impl EncryptParameters {
+    pub fn new(algorithm: EncryptionAlgorithm, plaintext: impl Into<Vec<u8>>) -> Self {
+        Self {
+            algorithm,
+            plaintext: plaintext.into(),
+            iv: None,
+            additional_authenticated_data: None,
+        }
+    }
+
+    /// Sets the initialization vector of AES-CBC. It is generated by the service for AES-GCM.
+    pub fn with_iv(mut self, iv: impl Into<Vec<u8>>) -> Self {
+        self.iv = Some(iv.into());
+        self
+    }
+
+    /// Sets the additional authenticated data of AES-GCM.
+    pub fn with_additional_authenticated_data(
+        mut self,
+        additional_authenticated_data: impl Into<Vec<u8>>,
+    ) -> Self {
+        self.additional_authenticated_data = Some(additional_authenticated_data.into());
+        self
+    }
+}
+
+/// The parameters of a decryption.
+///
+/// The initialization vector, additional authenticated data and authentication tag only
+/// apply to the AES algorithms, and are the ones returned in the `EncryptResult`.
+#[derive(Debug, Clone, Getters)]
+#[getset(get = "pub")]
+pub struct DecryptParameters {
+    algorithm: EncryptionAlgorithm,
+    ciphertext: Vec<u8>,
+    iv: Option<Vec<u8>>,
+    additional_authenticated_data: Option<Vec<u8>>,
+    authentication_tag: Option<Vec<u8>>,
+}
+
+impl DecryptParameters {
+    pub fn new(algorithm: EncryptionAlgorithm, ciphertext: impl Into<Vec<u8>>) -> Self {
+        Self {
+            algorithm,
+            ciphertext: ciphertext.into(),
+            iv: None,
+            additional_authenticated_data: None,
+            authentication_tag: None,
+        }
+    }
+
+    /// Sets the initialization vector of AES-CBC and AES-GCM.
+    pub fn with_iv(mut self, iv: impl Into<Vec<u8>>) -> Self {
+        self.iv = Some(iv.into());
+        self
+    }
+
+    /// Sets the additional authenticated data of AES-GCM.
+    pub fn with_additional_authenticated_data(
+        mut self,
+        additional_authenticated_data: impl Into<Vec<u8>>,
+    ) -> Self {
+        self.additional_authenticated_data = Some(additional_authenticated_data.into());
+        self
+    }
+
+    /// Sets the authentication tag of AES-GCM.
+    pub fn with_authentication_tag(mut self, authentication_tag: impl Into<Vec<u8>>) -> Self {
+        self.authentication_tag = Some(authentication_tag.into());
+        self
+    }
+}
+
+#[derive(Debug, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct EncryptResult {
+    #[serde(rename = "value", deserialize_with = "deser_base64")]
+    pub(crate) ciphertext: Vec<u8>,
+    #[serde(skip)]
+    pub(crate) algorithm: EncryptionAlgorithm,
+    #[serde(rename = "kid")]
+    pub(crate) key_id: String,
+    /// The initialization vector, for the AES algorithms.
+    #[serde(deserialize_with = "deser_base64_opt", default)]
+    pub(crate) iv: Option<Vec<u8>>,
+    /// The authentication tag, for AES-GCM.
+    #[serde(rename = "tag", deserialize_with = "deser_base64_opt", default)]
+    pub(crate) authentication_tag: Option<Vec<u8>>,
+    /// The additional authenticated data, for AES-GCM.
+    #[serde(rename = "aad", deserialize_with = "deser_base64_opt", default)]
+    pub(crate) additional_authenticated_data: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct DecryptResult {
+    #[serde(rename = "value", deserialize_with = "deser_base64")]
+    plaintext: Vec<u8>,
+    #[serde(skip)]
+    algorithm: EncryptionAlgorithm,
+    #[serde(rename = "kid")]
+    key_id: String,
+}
+
+#[derive(Debug, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct WrapResult {
+    #[serde(rename = "value", deserialize_with = "deser_base64")]
+    pub(crate) encrypted_key: Vec<u8>,
+    #[serde(skip)]
+    pub(crate) algorithm: KeyWrapAlgorithm,
+    #[serde(rename = "kid")]
+    pub(crate) key_id: String,
+}
+
+#[derive(Debug, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct UnwrapResult {
+    #[serde(rename = "value", deserialize_with = "deser_base64")]
+    key: Vec<u8>,
+    #[serde(skip)]
+    algorithm: KeyWrapAlgorithm,
+    #[serde(rename = "kid")]
+    key_id: String,
+}
+
+/// A key stored in the Key Vault, as listed by `list_keys` and `list_key_versions`.
+#[derive(Debug, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct KeyItem {
+    /// Key identifier.
+    #[serde(rename = "kid")]
+    id: String,
+    /// The key management properties.
+    #[serde(flatten)]
+    properties: KeyProperties,
+}
+
+impl KeyItem {
+    /// The name of the key, taken from its identifier.
+    pub fn name(&self) -> &str {
+        key_name_from_id(&self.id)
+    }
+}
+
+/// A deleted key, recoverable until its scheduled purge date.
+#[derive(Debug, Deserialize, Getters)]
+#[getset(get = "pub")]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedKey {
+    #[serde(flatten)]
+    key: KeyVaultKey,
+    /// The url of the recovery object, used to recover the deleted key.
+    recovery_id: Option<String>,
+    /// The time when the key is scheduled to be purged, in UTC.
+    #[serde(with = "ts_seconds_option", default)]
+    scheduled_purge_date: Option<DateTime<Utc>>,
+    /// The time when the key was deleted, in UTC.
+    #[serde(with = "ts_seconds_option", default)]
+    deleted_date: Option<DateTime<Utc>>,
+}
+
+/// A deleted key, as listed by `list_deleted_keys`.
+#[derive(Debug, Deserialize, Getters)]
+#[getset(get = "pub")]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedKeyItem {
+    #[serde(flatten)]
+    item: KeyItem,
+    /// The url of the recovery object, used to recover the deleted key.
+    recovery_id: Option<String>,
+    /// The time when the key is scheduled to be purged, in UTC.
+    #[serde(with = "ts_seconds_option", default)]
+    scheduled_purge_date: Option<DateTime<Utc>>,
+    /// The time when the key was deleted, in UTC.
+    #[serde(with = "ts_seconds_option", default)]
+    deleted_date: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct KeyBackupBlob {
+    /// The backup blob of the key, to give to `restore_key`.
+    #[serde(rename = "value", deserialize_with = "deser_base64")]
+    value: Vec<u8>,
+}
+
+/// JsonWebKey Key Type (kty), as defined in https://tools.ietf.org/html/draft-ietf-jose-json-web-algorithms-40.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KeyType {
+    /// Elliptic Curve.
+    #[serde(rename = "EC")]
+    Ec,
+    /// Elliptic Curve with a private key which is not exportable from the HSM.
+    #[serde(rename = "EC-HSM")]
+    EcHsm,
+    /// RSA (https://tools.ietf.org/html/rfc3447).
+    #[serde(rename = "RSA")]
+    Rsa,
+    /// RSA with a private key which is not exportable from the HSM.
+    #[serde(rename = "RSA-HSM")]
+    RsaHsm,
+    /// Octet sequence (used to represent symmetric keys).
+    #[serde(rename = "oct")]
+    Oct,
+    /// Octet sequence (used to represent symmetric keys) which is not exportable from the HSM.
+    #[serde(rename = "oct-HSM")]
+    OctHsm,
+}
+
+/// Elliptic curve name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KeyCurveName {
+    /// The NIST P-256 elliptic curve, AKA SECG curve SECP256R1.
+    #[serde(rename = "P-256")]
+    P256,
+    /// The SECG SECP256K1 elliptic curve.
+    #[serde(rename = "P-256K")]
+    P256K,
+    /// The NIST P-384 elliptic curve, AKA SECG curve SECP384R1.
+    #[serde(rename = "P-384")]
+    P384,
+    /// The NIST P-521 elliptic curve, AKA SECG curve SECP521R1.
+    #[serde(rename = "P-521")]
+    P521,
+}
+
+/// An operation a key can be used for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KeyOperation {
+    Encrypt,
+    Decrypt,
+    Sign,
+    Verify,
+    WrapKey,
+    UnwrapKey,
+    Import,
+}
+
+impl Display for KeyOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let operation = match self {
+            KeyOperation::Encrypt => "encrypt",
+            KeyOperation::Decrypt => "decrypt",
+            KeyOperation::Sign => "sign",
+            KeyOperation::Verify => "verify",
+            KeyOperation::WrapKey => "wrapKey",
+            KeyOperation::UnwrapKey => "unwrapKey",
+            KeyOperation::Import => "import",
+        };
+        f.write_str(operation)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+struct KeyRequestAttributes {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enabled: Option<bool>,
+    #[serde(
+        rename = "nbf",
+        with = "ts_seconds_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    not_before: Option<DateTime<Utc>>,
+    #[serde(
+        rename = "exp",
+        with = "ts_seconds_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    expires_on: Option<DateTime<Utc>>,
+}
+
+/// Implements the setters of the properties shared by the key creation and update options.
+macro_rules! key_properties_setters {
+    ($options:ty) => {
+        impl $options {
+            /// Sets the operations the key can be used for.
+            pub fn with_key_operations(mut self, key_operations: Vec<KeyOperation>) -> Self {
+                self.key_operations = Some(key_operations);
+                self
+            }
+
+            /// Sets whether the key is enabled.
+            pub fn with_enabled(mut self, enabled: bool) -> Self {
+                self.attributes.enabled = Some(enabled);
+                self
+            }
+
+            /// Sets the time before which the key can't be used.
+            pub fn with_not_before(mut self, not_before: DateTime<Utc>) -> Self {
+                self.attributes.not_before = Some(not_before);
+                self
+            }
+
+            /// Sets the expiration time of the key.
+            pub fn with_expires_on(mut self, expires_on: DateTime<Utc>) -> Self {
+                self.attributes.expires_on = Some(expires_on);
+                self
+            }
+
+            /// Sets the application specific metadata of the key.
+            pub fn with_tags(mut self, tags: Map<String, Value>) -> Self {
+                self.tags = Some(tags);
+                self
+            }
+        }
+    };
+}
+
+/// The options of a key creation.
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateKeyOptions {
+    #[serde(rename = "kty")]
+    key_type: KeyType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key_size: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    public_exponent: Option<u32>,
+    #[serde(rename = "crv", skip_serializing_if = "Option::is_none")]
+    curve_name: Option<KeyCurveName>,
+    #[serde(rename = "key_ops", skip_serializing_if = "Option::is_none")]
+    key_operations: Option<Vec<KeyOperation>>,
+    attributes: KeyRequestAttributes,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<Map<String, Value>>,
+}
+
+impl CreateKeyOptions {
+    pub fn new(key_type: KeyType) -> Self {
+        Self {
+            key_type,
+            key_size: None,
+            public_exponent: None,
+            curve_name: None,
+            key_operations: None,
+            attributes: KeyRequestAttributes::default(),
+            tags: None,
+        }
+    }
+
+    /// Sets the size in bits of an RSA or oct key, for example 2048, 3072 or 4096 for RSA.
+    pub fn with_key_size(mut self, key_size: u32) -> Self {
+        self.key_size = Some(key_size);
+        self
+    }
+
+    /// Sets the public exponent of an RSA key.
+    pub fn with_public_exponent(mut self, public_exponent: u32) -> Self {
+        self.public_exponent = Some(public_exponent);
+        self
+    }
+
+    /// Sets the curve of an EC key.
+    pub fn with_curve_name(mut self, curve_name: KeyCurveName) -> Self {
+        self.curve_name = Some(curve_name);
+        self
+    }
+}
+
+key_properties_setters!(CreateKeyOptions);
+
+/// The options of a key import.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportKeyOptions {
+    #[serde(rename = "Hsm", skip_serializing_if = "Option::is_none")]
+    hsm: Option<bool>,
+    #[serde(skip)]
+    key_operations: Option<Vec<KeyOperation>>,
+    attributes: KeyRequestAttributes,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<Map<String, Value>>,
+}
+
+impl ImportKeyOptions {
+    pub fn new() -> Self {
+        Self {
+            hsm: None,
+            key_operations: None,
+            attributes: KeyRequestAttributes::default(),
+            tags: None,
+        }
+    }
+
+    /// Sets whether the key is imported into a hardware security module.
+    pub fn with_hsm(mut self, hsm: bool) -> Self {
+        self.hsm = Some(hsm);
+        self
+    }
+}
+
+impl Default for ImportKeyOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+key_properties_setters!(ImportKeyOptions);
+
+/// The properties to update on a key version, the others are left unchanged.
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateKeyPropertiesOptions {
+    #[serde(rename = "key_ops", skip_serializing_if = "Option::is_none")]
+    key_operations: Option<Vec<KeyOperation>>,
+    attributes: KeyRequestAttributes,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<Map<String, Value>>,
+}
+
+impl UpdateKeyPropertiesOptions {
+    pub fn new() -> Self {
+        Self {
+            key_operations: None,
+            attributes: KeyRequestAttributes::default(),
+            tags: None,
+        }
+    }
+}
+
+impl Default for UpdateKeyPropertiesOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+key_properties_setters!(UpdateKeyPropertiesOptions);
+
+/// The policy rotating a key, and notifying of its upcoming expiry.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Getters)]
+#[getset(get = "pub")]
+#[serde(rename_all = "camelCase")]
+pub struct KeyRotationPolicy {
+    /// The policy identifier.
+    #[serde(skip_serializing)]
+    id: Option<String>,
+    /// The actions performed on the key along its lifetime.
+    #[serde(default)]
+    lifetime_actions: Vec<KeyRotationLifetimeAction>,
+    #[serde(default)]
+    attributes: KeyRotationPolicyAttributes,
+}
+
+impl KeyRotationPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the expiry of the new key versions, as an ISO 8601 duration such as `P90D`.
+    pub fn with_expiry_time(mut self, expiry_time: &str) -> Self {
+        self.attributes.expiry_time = Some(expiry_time.to_owned());
+        self
+    }
+
+    /// Adds an action performed when `trigger` fires.
+    pub fn with_lifetime_action(
+        mut self,
+        action: KeyRotationPolicyAction,
+        trigger: KeyRotationTrigger,
+    ) -> Self {
+        self.lifetime_actions
+            .push(KeyRotationLifetimeAction { trigger, action });
+        self
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Getters)]
+#[getset(get = "pub")]
+#[serde(rename_all = "camelCase")]
+pub struct KeyRotationPolicyAttributes {
+    /// The expiry of the new key versions, as an ISO 8601 duration.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expiry_time: Option<String>,
+    /// Creation time in UTC.
+    #[serde(with = "ts_seconds_option", default, skip_serializing)]
+    created: Option<DateTime<Utc>>,
+    /// Last updated time in UTC.
+    #[serde(with = "ts_seconds_option", default, skip_serializing)]
+    updated: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct KeyRotationLifetimeAction {
+    trigger: KeyRotationTrigger,
+    action: KeyRotationPolicyAction,
+}
+
+/// When a lifetime action is performed, as an ISO 8601 duration such as `P30D`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KeyRotationTrigger {
+    /// After the creation of the key version.
+    TimeAfterCreate(String),
+    /// Before the expiry of the key version.
+    TimeBeforeExpiry(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum KeyRotationPolicyAction {
+    /// Rotates the key, creating a new version.
+    #[serde(alias = "rotate")]
+    Rotate,
+    /// Triggers an Event Grid notification.
+    #[serde(alias = "notify")]
+    Notify,
+}
+
+fn key_name_from_id(id: &str) -> &str {
+    // https://{vault}/keys/{name}[/{version}]
+    let mut segments = id.trim_end_matches('/').rsplit('/');
+    let last = segments.next().unwrap_or_default();
+    match segments.next() {
+        Some("keys") | Some("deletedkeys") | None => last,
+        Some(name) => name,
+    }
+}
+
+impl KeyClient {
+    /// Gets the public part of a stored key.
+    /// The get key operation is applicable to all key types.
+    /// If the requested key is symmetric, then no key material is released in the response.
+    /// This operation requires the keys/get permission.
+    ///
+    /// GET {vaultBaseUrl}/keys/{key-name}/{key-version}?api-version=7.1
+    pub async fn get_key(
+        &self,
+        key_name: &str,
+        key_version: Option<&str>,
+    ) -> Result<KeyVaultKey, Error> {
+        let mut uri = self.vault_url.clone();
+        let path = if let Some(ver) = key_version {
+            format!("keys/{}/{}", key_name, ver)
+        } else {
+            format!("keys/{}", key_name)
+        };
+        uri.set_path(&path);
+        uri.set_query(Some(&self.api_version_param()));
+
+        let resp_body = self.get_authed(uri.to_string()).await?;
+        let response = serde_json::from_str::<KeyVaultKey>(&resp_body)?;
+        Ok(response)
+    }
+
+    /// Creates a signature from a digest using the specified key.
+    /// The SIGN operation is applicable to asymmetric and symmetric keys stored in Azure Key Vault since this operation uses the private portion of the key.
+    /// This operation requires the keys/sign permission.
+    pub async fn sign(
+        &self,
+        algorithm: SignatureAlgorithm,
+        key_name: &str,
+        key_version: &str,
+        digest: &str,
+    ) -> Result<SignResult, Error> {
+        // POST {vaultBaseUrl}/keys/{key-name}/{key-version}/sign?api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!("keys/{}/{}/sign", key_name, key_version));
+        uri.set_query(Some(&self.api_version_param()));
+
+        let mut request_body = Map::new();
+        request_body.insert("alg".to_owned(), Value::String(algorithm.to_string()));
+        request_body.insert("value".to_owned(), Value::String(digest.to_owned()));
+
+        let response = self
+            .post_authed(
+                uri.to_string(),
+                Some(Value::Object(request_body).to_string()),
+            )
+            .await?;
+
+        let mut result = serde_json::from_str::<SignResult>(&response)?;
+        result.algorithm = algorithm;
+        Ok(result)
+    }
+
+    /// Verifies a signature using the specified key.
+    /// The VERIFY operation is applicable to symmetric keys stored in Azure Key Vault.
+    /// It is not strictly necessary for asymmetric keys stored in Azure Key Vault since signature verification can be performed using the public portion of the key, see `CryptographyClient`.
+    /// This operation requires the keys/verify permission.
+    pub async fn verify(
+        &self,
+        algorithm: SignatureAlgorithm,
+        key_name: &str,
+        key_version: &str,
+        digest: &[u8],
+        signature: &[u8],
+    ) -> Result<VerifyResult, Error> {
+        // POST {vaultBaseUrl}/keys/{key-name}/{key-version}/verify?api-version=7.1
+
+        let mut request_body = Map::new();
+        request_body.insert("alg".to_owned(), Value::String(algorithm.to_string()));
+        request_body.insert("digest".to_owned(), encode_base64(digest));
+        request_body.insert("value".to_owned(), encode_base64(signature));
+
+        let response = self
+            .key_operation(key_name, key_version, "verify", request_body)
+            .await?;
+
+        let mut result = serde_json::from_str::<VerifyResult>(&response)?;
+        result.algorithm = algorithm;
+        Ok(result)
+    }
+
+    /// Encrypts an arbitrary sequence of bytes using an encryption key that is stored in a key vault.
+    /// The ENCRYPT operation only supports a single block of data, the size of which is dependent on the target key and the encryption algorithm to be used.
+    /// It is only strictly necessary for symmetric keys stored in Azure Key Vault since protection with an asymmetric key can be performed using the public portion of the key, see `CryptographyClient`.
+    /// This operation requires the keys/encrypt permission.
+    pub async fn encrypt(
+        &self,
+        key_name: &str,
+        key_version: &str,
+        parameters: EncryptParameters,
+    ) -> Result<EncryptResult, Error> {
+        // POST {vaultBaseUrl}/keys/{key-name}/{key-version}/encrypt?api-version=7.1
+
+        let mut request_body = Map::new();
+        request_body.insert(
+            "alg".to_owned(),
+            Value::String(parameters.algorithm.to_string()),
+        );
+        request_body.insert("value".to_owned(), encode_base64(&parameters.plaintext));
+        if let Some(iv) = &parameters.iv {
+            request_body.insert("iv".to_owned(), encode_base64(iv));
+        }
+        if let Some(aad) = &parameters.additional_authenticated_data {
+            request_body.insert("aad".to_owned(), encode_base64(aad));
+        }
+
+        let response = self
+            .key_operation(key_name, key_version, "encrypt", request_body)
+            .await?;
+
+        let mut result = serde_json::from_str::<EncryptResult>(&response)?;
+        result.algorithm = parameters.algorithm;
+        Ok(result)
+    }
+
+    /// Decrypts a single block of encrypted data.
+    /// The DECRYPT operation decrypts a well-formed block of ciphertext using the target encryption key and specified algorithm.
+    /// This operation is the reverse of the ENCRYPT operation; only a single block of data may be decrypted, the size of this block is dependent on the target key and the algorithm to be used.
+    /// This operation requires the keys/decrypt permission.
+    pub async fn decrypt(
+        &self,
+        key_name: &str,
+        key_version: &str,
+        parameters: DecryptParameters,
+    ) -> Result<DecryptResult, Error> {
+        // POST {vaultBaseUrl}/keys/{key-name}/{key-version}/decrypt?api-version=7.1
+
+        let mut request_body = Map::new();
+        request_body.insert(
+            "alg".to_owned(),
+            Value::String(parameters.algorithm.to_string()),
+        );
+        request_body.insert("value".to_owned(), encode_base64(&parameters.ciphertext));
+        if let Some(iv) = &parameters.iv {
+            request_body.insert("iv".to_owned(), encode_base64(iv));
+        }
+        if let Some(aad) = &parameters.additional_authenticated_data {
+            request_body.insert("aad".to_owned(), encode_base64(aad));
+        }
+        if let Some(tag) = &parameters.authentication_tag {
+            request_body.insert("tag".to_owned(), encode_base64(tag));
+        }
+
+        let response = self
+            .key_operation(key_name, key_version, "decrypt", request_body)
+            .await?;
+
+        let mut result = serde_json::from_str::<DecryptResult>(&response)?;
+        result.algorithm = parameters.algorithm;
+        Ok(result)
+    }
+
+    /// Wraps a symmetric key using a specified key.
+    /// The WRAP operation supports encryption of a symmetric key using a key encryption key that has previously been stored in an Azure Key Vault.
+    /// It is only strictly necessary for symmetric keys stored in Azure Key Vault since protection with an asymmetric key can be performed using the public portion of the key, see `CryptographyClient`.
+    /// This operation requires the keys/wrapKey permission.
+    pub async fn wrap_key(
+        &self,
+        algorithm: KeyWrapAlgorithm,
+        key_name: &str,
+        key_version: &str,
+        key: &[u8],
+    ) -> Result<WrapResult, Error> {
+        // POST {vaultBaseUrl}/keys/{key-name}/{key-version}/wrapkey?api-version=7.1
+
+        let mut request_body = Map::new();
+        request_body.insert("alg".to_owned(), Value::String(algorithm.to_string()));
+        request_body.insert("value".to_owned(), encode_base64(key));
+
+        let response = self
+            .key_operation(key_name, key_version, "wrapkey", request_body)
+            .await?;
+
+        let mut result = serde_json::from_str::<WrapResult>(&response)?;
+        result.algorithm = algorithm;
+        Ok(result)
+    }
+
+    /// Unwraps a symmetric key using the specified key that was initially used for wrapping that key.
+    /// The UNWRAP operation supports decryption of a symmetric key using the target key encryption key.
+    /// This operation is the reverse of the WRAP operation.
+    /// This operation requires the keys/unwrapKey permission.
+    pub async fn unwrap_key(
+        &self,
+        algorithm: KeyWrapAlgorithm,
+        key_name: &str,
+        key_version: &str,
+        encrypted_key: &[u8],
+    ) -> Result<UnwrapResult, Error> {
+        // POST {vaultBaseUrl}/keys/{key-name}/{key-version}/unwrapkey?api-version=7.1
+
+        let mut request_body = Map::new();
+        request_body.insert("alg".to_owned(), Value::String(algorithm.to_string()));
+        request_body.insert("value".to_owned(), encode_base64(encrypted_key));
+
+        let response = self
+            .key_operation(key_name, key_version, "unwrapkey", request_body)
+            .await?;
+
+        let mut result = serde_json::from_str::<UnwrapResult>(&response)?;
+        result.algorithm = algorithm;
+        Ok(result)
+    }
+
+    /// Creates a new key, or a new version of an existing key.
+    /// This operation requires the keys/create permission.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use azure_key_vault::KeyClient;
+    /// use azure_key_vault::key::{CreateKeyOptions, KeyType};
+    /// use azure_identity::token_credentials::DefaultCredential;
+    /// use std::sync::Arc;
+    /// use tokio::runtime::Runtime;
+    ///
+    /// async fn example() {
+    ///     let creds = DefaultCredential::default();
+    ///     let client = KeyClient::new(&"KEYVAULT_URL", Arc::new(creds)).unwrap();
+    ///     let options = CreateKeyOptions::new(KeyType::Rsa).with_key_size(3072);
+    ///     let key = client.create_key("KEY_NAME", options).await.unwrap();
+    ///     dbg!(&key);
+    /// }
+    ///
+    /// Runtime::new().unwrap().block_on(example());
+    /// ```
+    pub async fn create_key(
+        &self,
+        key_name: &str,
+        options: CreateKeyOptions,
+    ) -> Result<KeyVaultKey, Error> {
+        // POST {vaultBaseUrl}/keys/{key-name}/create?api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!("keys/{}/create", key_name));
+        uri.set_query(Some(&self.api_version_param()));
+
+        let response = self
+            .post_authed(uri.to_string(), Some(serde_json::to_string(&options)?))
+            .await?;
+        parse_response(&response)
+    }
+
+    /// Imports an externally created key, creating a new key or a new version of an existing key.
+    /// This operation requires the keys/import permission.
+    pub async fn import_key(
+        &self,
+        key_name: &str,
+        key: JsonWebKey,
+        options: ImportKeyOptions,
+    ) -> Result<KeyVaultKey, Error> {
+        // PUT {vaultBaseUrl}/keys/{key-name}?api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!("keys/{}", key_name));
+        uri.set_query(Some(&self.api_version_param()));
+
+        let mut key = key;
+        if let Some(key_operations) = &options.key_operations {
+            key.key_ops = Some(key_operations.iter().map(ToString::to_string).collect());
+        }
+        let mut request_body = match serde_json::to_value(&options)? {
+            Value::Object(request_body) => request_body,
+            _ => Map::new(),
+        };
+        request_body.insert("key".to_owned(), serde_json::to_value(&key)?);
+
+        let response = self
+            .put_authed(uri.to_string(), Value::Object(request_body).to_string())
+            .await?;
+        parse_response(&response)
+    }
+
+    /// Creates a new version of a key following its rotation policy.
+    /// This operation requires the keys/rotate permission.
+    pub async fn rotate_key(&self, key_name: &str) -> Result<KeyVaultKey, Error> {
+        // POST {vaultBaseUrl}/keys/{key-name}/rotate?api-version=7.3
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!("keys/{}/rotate", key_name));
+        uri.set_query(Some(&self.api_version_param()));
+
+        let response = self.post_authed(uri.to_string(), None).await?;
+        parse_response(&response)
+    }
+
+    /// Gets the rotation policy of a key.
+    /// This operation requires the keys/get permission.
+    pub async fn get_key_rotation_policy(
+        &self,
+        key_name: &str,
+    ) -> Result<KeyRotationPolicy, Error> {
+        // GET {vaultBaseUrl}/keys/{key-name}/rotationpolicy?api-version=7.3
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!("keys/{}/rotationpolicy", key_name));
+        uri.set_query(Some(&self.api_version_param()));
+
+        let response = self.get_authed(uri.to_string()).await?;
+        parse_response(&response)
+    }
+
+    /// Sets the rotation policy of a key.
+    /// This operation requires the keys/update permission.
+    pub async fn update_key_rotation_policy(
+        &self,
+        key_name: &str,
+        policy: &KeyRotationPolicy,
+    ) -> Result<KeyRotationPolicy, Error> {
+        // PUT {vaultBaseUrl}/keys/{key-name}/rotationpolicy?api-version=7.3
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!("keys/{}/rotationpolicy", key_name));
+        uri.set_query(Some(&self.api_version_param()));
+
+        let response = self
+            .put_authed(uri.to_string(), serde_json::to_string(policy)?)
+            .await?;
+        parse_response(&response)
+    }
+
+    /// Lists the keys in the Key Vault, one page at a time.
+    /// Only the key identifiers and properties are returned, not the key material.
+    /// This operation requires the keys/list permission.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use azure_key_vault::KeyClient;
+    /// use azure_identity::token_credentials::DefaultCredential;
+    /// use std::sync::Arc;
+    /// use futures::stream::StreamExt;
+    /// use tokio::runtime::Runtime;
+    ///
+    /// async fn example() {
+    ///     let creds = DefaultCredential::default();
+    ///     let client = KeyClient::new(&"KEYVAULT_URL", Arc::new(creds)).unwrap();
+    ///     let mut pages = client.list_keys();
+    ///     while let Some(page) = pages.next().await {
+    ///         for key in page.unwrap().items() {
+    ///             println!("{}", key.name());
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// Runtime::new().unwrap().block_on(example());
+    /// ```
+    pub fn list_keys(&self) -> PageStream<'_, KeyItem> {
+        // GET {vaultBaseUrl}/keys?maxresults=25&api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path("keys");
+        uri.set_query(Some(&self.api_version_max_results_param()));
+        self.list_pages(uri)
+    }
+
+    /// Lists the versions of a key, one page at a time.
+    /// This operation requires the keys/list permission.
+    pub fn list_key_versions(&self, key_name: &str) -> PageStream<'_, KeyItem> {
+        // GET {vaultBaseUrl}/keys/{key-name}/versions?maxresults=25&api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!("keys/{}/versions", key_name));
+        uri.set_query(Some(&self.api_version_max_results_param()));
+        self.list_pages(uri)
+    }
+
+    /// Updates the operations, attributes and tags of a key version.
+    /// The key material itself can't be changed.
+    /// This operation requires the keys/update permission.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_name` - Name of the key
+    /// * `key_version` - Version of the key. Use an empty string for the latest version
+    /// * `options` - The properties to update
+    pub async fn update_key_properties(
+        &self,
+        key_name: &str,
+        key_version: &str,
+        options: UpdateKeyPropertiesOptions,
+    ) -> Result<KeyVaultKey, Error> {
+        // PATCH {vaultBaseUrl}/keys/{key-name}/{key-version}?api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!("keys/{}/{}", key_name, key_version));
+        uri.set_query(Some(&self.api_version_param()));
+
+        let response = self
+            .patch_authed(uri.to_string(), serde_json::to_string(&options)?)
+            .await?;
+        parse_response(&response)
+    }
+
+    /// Deletes a key and all its versions.
+    /// When soft delete is enabled the key can be recovered until its scheduled purge date.
+    /// This operation requires the keys/delete permission.
+    pub async fn delete_key(&self, key_name: &str) -> Result<DeletedKey, Error> {
+        // DELETE {vaultBaseUrl}/keys/{key-name}?api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!("keys/{}", key_name));
+        uri.set_query(Some(&self.api_version_param()));
+
+        let response = self.delete_authed(uri.to_string()).await?;
+        parse_response(&response)
+    }
+
+    /// Gets a deleted key.
+    /// This operation requires the keys/get permission.
+    pub async fn get_deleted_key(&self, key_name: &str) -> Result<DeletedKey, Error> {
+        // GET {vaultBaseUrl}/deletedkeys/{key-name}?api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!("deletedkeys/{}", key_name));
+        uri.set_query(Some(&self.api_version_param()));
+
+        let response = self.get_authed(uri.to_string()).await?;
+        parse_response(&response)
+    }
+
+    /// Lists the deleted keys, one page at a time.
+    /// This operation requires the keys/list permission.
+    pub fn list_deleted_keys(&self) -> PageStream<'_, DeletedKeyItem> {
+        // GET {vaultBaseUrl}/deletedkeys?maxresults=25&api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path("deletedkeys");
+        uri.set_query(Some(&self.api_version_max_results_param()));
+        self.list_pages(uri)
+    }
+
+    /// Permanently deletes a deleted key, which can't be recovered afterwards.
+    /// This operation requires the keys/purge permission.
+    pub async fn purge_deleted_key(&self, key_name: &str) -> Result<(), Error> {
+        // DELETE {vaultBaseUrl}/deletedkeys/{key-name}?api-version=7.1
 
-impl Default for SignatureAlgorithm {
-    fn default() -> Self {
-        SignatureAlgorithm::Custom("".to_string())
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!("deletedkeys/{}", key_name));
+        uri.set_query(Some(&self.api_version_param()));
+
+        let response = self.delete_authed(uri.to_string()).await?;
+        if response.is_empty() {
+            return Ok(());
+        }
+        parse_response::<Value>(&response)?;
+        Ok(())
     }
-}
 
-impl Display for SignatureAlgorithm {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        Debug::fmt(self, f)
+    /// Recovers a deleted key to its latest version.
+    /// This operation requires the keys/recover permission.
+    pub async fn recover_deleted_key(&self, key_name: &str) -> Result<KeyVaultKey, Error> {
+        // POST {vaultBaseUrl}/deletedkeys/{key-name}/recover?api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!("deletedkeys/{}/recover", key_name));
+        uri.set_query(Some(&self.api_version_param()));
+
+        let response = self.post_authed(uri.to_string(), None).await?;
+        parse_response(&response)
     }
-}
 
-impl<'a, T: TokenCredential> KeyClient<'a, T> {
-    /// Gets the public part of a stored key.
-    /// The get key operation is applicable to all key types.
-    /// If the requested key is symmetric, then no key material is released in the response.
-    /// This operation requires the keys/get permission.
-    ///
-    /// GET {vaultBaseUrl}/keys/{key-name}/{key-version}?api-version=7.1
-    pub async fn get_key(
-        &mut self,
-        key_name: &str,
-        key_version: Option<&str>,
-    ) -> Result<KeyVaultKey, Error> {
+    /// Backs up a key and all its versions, in a form that can only be restored in Azure Key Vault.
+    /// This operation requires the keys/backup permission.
+    pub async fn backup_key(&self, key_name: &str) -> Result<KeyBackupBlob, Error> {
+        // POST {vaultBaseUrl}/keys/{key-name}/backup?api-version=7.1
+
         let mut uri = self.vault_url.clone();
-        let path = if let Some(ver) = key_version {
-            format!("keys/{}/{}", key_name, ver)
-        } else {
-            format!("keys/{}", key_name)
-        };
-        uri.set_path(&path);
-        uri.set_query(Some(API_VERSION_PARAM));
+        uri.set_path(&format!("keys/{}/backup", key_name));
+        uri.set_query(Some(&self.api_version_param()));
 
-        let resp_body = self.get_authed(uri.to_string()).await?;
-        let response = serde_json::from_str::<KeyVaultKey>(&resp_body)?;
-        Ok(response)
+        let response = self.post_authed(uri.to_string(), None).await?;
+        parse_response(&response)
     }
 
-    /// Creates a signature from a digest using the specified key.
-    /// The SIGN operation is applicable to asymmetric and symmetric keys stored in Azure Key Vault since this operation uses the private portion of the key.
-    /// This operation requires the keys/sign permission.
-    pub async fn sign(
-        &mut self,
-        algorithm: SignatureAlgorithm,
-        key_name: &str,
-        key_version: &str,
-        digest: &str,
-    ) -> Result<SignResult, Error> {
-        // POST {vaultBaseUrl}/keys/{key-name}/{key-version}/sign?api-version=7.1
+    /// Restores a backed up key and all its versions.
+    /// This operation requires the keys/restore permission.
+    pub async fn restore_key(&self, backup_blob: &[u8]) -> Result<KeyVaultKey, Error> {
+        // POST {vaultBaseUrl}/keys/restore?api-version=7.1
 
         let mut uri = self.vault_url.clone();
-        uri.set_path(&format!("keys/{}/{}/sign", key_name, key_version));
-        uri.set_query(Some(API_VERSION_PARAM));
+        uri.set_path("keys/restore");
+        uri.set_query(Some(&self.api_version_param()));
 
         let mut request_body = Map::new();
-        request_body.insert("alg".to_owned(), Value::String(algorithm.to_string()));
-        request_body.insert("value".to_owned(), Value::String(digest.to_owned()));
+        request_body.insert("value".to_owned(), encode_base64(backup_blob));
 
         let response = self
             .post_authed(
@@ -291,10 +1376,25 @@ impl<'a, T: TokenCredential> KeyClient<'a, T> {
                 Some(Value::Object(request_body).to_string()),
             )
             .await?;
+        parse_response(&response)
+    }
 
-        let mut result = serde_json::from_str::<SignResult>(&response)?;
-        result.algorithm = algorithm;
-        Ok(result)
+    async fn key_operation(
+        &self,
+        key_name: &str,
+        key_version: &str,
+        operation: &str,
+        request_body: Map<String, Value>,
+    ) -> Result<String, Error> {
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!("keys/{}/{}/{}", key_name, key_version, operation));
+        uri.set_query(Some(&self.api_version_param()));
+
+        self.post_authed(
+            uri.to_string(),
+            Some(Value::Object(request_body).to_string()),
+        )
+        .await
     }
 }
 
@@ -309,6 +1409,8 @@ mod tests {
     use crate::client::API_VERSION;
     use crate::mock_client;
     use crate::tests::MockCredential;
+    use crate::Page;
+    use futures::stream::TryStreamExt;
 
     fn diff(first: DateTime<Utc>, second: DateTime<Utc>) -> Duration {
         if first > second {
@@ -359,7 +1461,7 @@ mod tests {
             .create();
 
         let creds = MockCredential;
-        let mut client = mock_client!(&"test-keyvault", &creds,);
+        let client = mock_client!(&"test-keyvault", creds,);
 
         let key = client
             .get_key("test-key", Some("78deebed173b48e48f55abf87ed4cf71"))
@@ -411,7 +1513,7 @@ mod tests {
             .create();
 
         let creds = MockCredential;
-        let mut client = mock_client!(&"test-keyvault", &creds,);
+        let client = mock_client!(&"test-keyvault", creds,);
 
         let res = client
             .sign(
@@ -435,4 +1537,441 @@ mod tests {
         assert_eq!(expected_sig, sig.to_owned());
         assert!(matches!(alg, SignatureAlgorithm::RS512));
     }
+
+    #[tokio::test]
+    async fn can_encrypt_and_decrypt() {
+        let _encrypt = mock("POST", "/keys/test-key/78deebed173b48e48f55abf87ed4cf71/encrypt")
+            .match_query(Matcher::UrlEncoded("api-version".into(), API_VERSION.into()))
+            .match_body(Matcher::Json(json!({
+                "alg": "A256GCM",
+                "value": "cGxhaW50ZXh0",
+                "aad": "YWFk"
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "kid": "https://myvault.managedhsm.azure.net/keys/test-key/78deebed173b48e48f55abf87ed4cf71",
+                    "value": "Y2lwaGVydGV4dA",
+                    "iv": "aXY",
+                    "tag": "dGFn",
+                    "aad": "YWFk"
+                })
+                .to_string(),
+            )
+            .with_status(200)
+            .create();
+        let _decrypt = mock("POST", "/keys/test-key/78deebed173b48e48f55abf87ed4cf71/decrypt")
+            .match_query(Matcher::UrlEncoded("api-version".into(), API_VERSION.into()))
+            .match_body(Matcher::Json(json!({
+                "alg": "A256GCM",
+                "value": "Y2lwaGVydGV4dA",
+                "iv": "aXY",
+                "tag": "dGFn",
+                "aad": "YWFk"
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "kid": "https://myvault.managedhsm.azure.net/keys/test-key/78deebed173b48e48f55abf87ed4cf71",
+                    "value": "cGxhaW50ZXh0"
+                })
+                .to_string(),
+            )
+            .with_status(200)
+            .create();
+
+        let creds = MockCredential;
+        let client = mock_client!(&"test-keyvault", creds,);
+
+        let encrypted = client
+            .encrypt(
+                "test-key",
+                "78deebed173b48e48f55abf87ed4cf71",
+                EncryptParameters::new(EncryptionAlgorithm::A256Gcm, "plaintext")
+                    .with_additional_authenticated_data("aad"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(encrypted.ciphertext(), b"ciphertext");
+        assert_eq!(encrypted.iv().as_deref(), Some(&b"iv"[..]));
+        assert_eq!(encrypted.authentication_tag().as_deref(), Some(&b"tag"[..]));
+        assert_eq!(encrypted.algorithm(), &EncryptionAlgorithm::A256Gcm);
+
+        let decrypted = client
+            .decrypt(
+                "test-key",
+                "78deebed173b48e48f55abf87ed4cf71",
+                DecryptParameters::new(EncryptionAlgorithm::A256Gcm, "ciphertext")
+                    .with_iv("iv")
+                    .with_authentication_tag("tag")
+                    .with_additional_authenticated_data("aad"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(decrypted.plaintext(), b"plaintext");
+    }
+
+    #[tokio::test]
+    async fn can_wrap_and_unwrap_key() {
+        let _wrap = mock("POST", "/keys/test-key/78deebed173b48e48f55abf87ed4cf71/wrapkey")
+            .match_query(Matcher::UrlEncoded("api-version".into(), API_VERSION.into()))
+            .match_body(Matcher::Json(json!({"alg": "RSA-OAEP-256", "value": "a2V5"})))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "kid": "https://myvault.vault.azure.net/keys/test-key/78deebed173b48e48f55abf87ed4cf71",
+                    "value": "d3JhcHBlZA"
+                })
+                .to_string(),
+            )
+            .with_status(200)
+            .create();
+        let _unwrap = mock("POST", "/keys/test-key/78deebed173b48e48f55abf87ed4cf71/unwrapkey")
+            .match_query(Matcher::UrlEncoded("api-version".into(), API_VERSION.into()))
+            .match_body(Matcher::Json(json!({"alg": "RSA-OAEP-256", "value": "d3JhcHBlZA"})))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "kid": "https://myvault.vault.azure.net/keys/test-key/78deebed173b48e48f55abf87ed4cf71",
+                    "value": "a2V5"
+                })
+                .to_string(),
+            )
+            .with_status(200)
+            .create();
+
+        let creds = MockCredential;
+        let client = mock_client!(&"test-keyvault", creds,);
+
+        let wrapped = client
+            .wrap_key(
+                KeyWrapAlgorithm::RsaOaep256,
+                "test-key",
+                "78deebed173b48e48f55abf87ed4cf71",
+                b"key",
+            )
+            .await
+            .unwrap();
+        assert_eq!(wrapped.encrypted_key(), b"wrapped");
+
+        let unwrapped = client
+            .unwrap_key(
+                KeyWrapAlgorithm::RsaOaep256,
+                "test-key",
+                "78deebed173b48e48f55abf87ed4cf71",
+                wrapped.encrypted_key(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(unwrapped.key(), b"key");
+        assert_eq!(unwrapped.algorithm(), &KeyWrapAlgorithm::RsaOaep256);
+    }
+
+    #[tokio::test]
+    async fn can_verify() {
+        let _m = mock(
+            "POST",
+            "/keys/test-key/78deebed173b48e48f55abf87ed4cf71/verify",
+        )
+        .match_query(Matcher::UrlEncoded(
+            "api-version".into(),
+            API_VERSION.into(),
+        ))
+        .match_body(Matcher::Json(
+            json!({"alg": "ES256", "digest": "ZGlnZXN0", "value": "c2lnbmF0dXJl"}),
+        ))
+        .with_header("content-type", "application/json")
+        .with_body(json!({ "value": true }).to_string())
+        .with_status(200)
+        .create();
+
+        let creds = MockCredential;
+        let client = mock_client!(&"test-keyvault", creds,);
+
+        let res = client
+            .verify(
+                SignatureAlgorithm::ES256,
+                "test-key",
+                "78deebed173b48e48f55abf87ed4cf71",
+                b"digest",
+                b"signature",
+            )
+            .await
+            .unwrap();
+        assert!(res.is_valid());
+    }
+
+    fn key_bundle(kid: &str) -> serde_json::Value {
+        json!({
+            "key": {
+                "kid": kid,
+                "kty": "RSA",
+                "key_ops": ["encrypt", "decrypt"],
+                "n": "2HJAE5fU3Cw2Rt9hEuq-F6XjINKGa-zskfISVqopqUy60GOs2eyhxbWbJBeUXNor_gf-tXtNeuqeBgitLeVa640UDvnEjYTKWjCniTxZRaU7ewY8BfTSk-7KxoDdLsPSpX_MX4rwlAx-_1UGk5t4sQgTbm9T6Fm2oqFd37dsz5-Gj27UP2GTAShfJPFD7MqU_zIgOI0pfqsbNL5xTQVM29K6rX4jSPtylZV3uWJtkoQIQnrIHhk1d0SC0KwlBV3V7R_LVYjiXLyIXsFzSNYgQ68ZjAwt8iL7I8Osa-ehQLM13DVvLASaf7Jnu3sC3CWl3Gyirgded6cfMmswJzY87w",
+                "e": "AQAB"
+            },
+            "attributes": {
+                "enabled": true,
+                "created": 1493938289,
+                "updated": 1493938289,
+                "recoveryLevel": "Recoverable+Purgeable"
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn can_create_key() {
+        let _m = mock("POST", "/keys/created-key/create")
+            .match_query(Matcher::UrlEncoded(
+                "api-version".into(),
+                API_VERSION.into(),
+            ))
+            .match_body(Matcher::Json(json!({
+                "kty": "RSA",
+                "key_size": 2048,
+                "key_ops": ["encrypt", "decrypt"],
+                "attributes": { "enabled": true, "exp": 1893456000 }
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(key_bundle("https://myvault.vault.azure.net/keys/created-key/1").to_string())
+            .with_status(200)
+            .create();
+
+        let creds = MockCredential;
+        let client = mock_client!(&"test-keyvault", creds,);
+
+        let expires_on = DateTime::parse_from_rfc3339("2030-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let options = CreateKeyOptions::new(KeyType::Rsa)
+            .with_key_size(2048)
+            .with_key_operations(vec![KeyOperation::Encrypt, KeyOperation::Decrypt])
+            .with_enabled(true)
+            .with_expires_on(expires_on);
+        let key = client.create_key("created-key", options).await.unwrap();
+
+        assert_eq!(
+            key.key().id().as_deref(),
+            Some("https://myvault.vault.azure.net/keys/created-key/1")
+        );
+        assert_eq!(key.properties().attributes().enabled(), &Some(true));
+    }
+
+    #[tokio::test]
+    async fn can_list_keys() {
+        let _m1 = mock("GET", "/keys")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("api-version".into(), API_VERSION.into()),
+                Matcher::UrlEncoded("maxresults".into(), "25".into()),
+            ]))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "value": [{
+                        "kid": "https://myvault.vault.azure.net/keys/first-key",
+                        "attributes": { "enabled": true, "created": 1493938289, "updated": 1493938289 }
+                    }],
+                    "nextLink": format!("{}/keys?api-version={}&maxresults=25&$skiptoken=SKIP_TOKEN_MOCK", mockito::server_url(), API_VERSION)
+                })
+                .to_string(),
+            )
+            .with_status(200)
+            .create();
+        let _m2 = mock("GET", "/keys")
+            .match_query(Matcher::UrlEncoded(
+                "$skiptoken".into(),
+                "SKIP_TOKEN_MOCK".into(),
+            ))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "value": [{
+                        "kid": "https://myvault.vault.azure.net/keys/second-key",
+                        "attributes": { "enabled": false },
+                        "managed": true
+                    }],
+                    "nextLink": null
+                })
+                .to_string(),
+            )
+            .with_status(200)
+            .create();
+
+        let creds = MockCredential;
+        let client = mock_client!(&"test-keyvault", creds,);
+
+        let pages: Vec<Page<KeyItem>> = client.list_keys().try_collect().await.unwrap();
+
+        assert_eq!(pages.len(), 2);
+        let names: Vec<&str> = pages
+            .iter()
+            .flat_map(|page| page.items())
+            .map(|key| key.name())
+            .collect();
+        assert_eq!(names, vec!["first-key", "second-key"]);
+        assert_eq!(pages[1].items()[0].properties().managed(), &Some(true));
+    }
+
+    #[tokio::test]
+    async fn can_delete_and_recover_key() {
+        let mut deleted_key = key_bundle("https://myvault.vault.azure.net/keys/deleted-key/1");
+        deleted_key["recoveryId"] =
+            json!("https://myvault.vault.azure.net/deletedkeys/deleted-key");
+        deleted_key["deletedDate"] = json!(1493938433);
+        deleted_key["scheduledPurgeDate"] = json!(1501714433);
+        let _delete = mock("DELETE", "/keys/deleted-key")
+            .match_query(Matcher::UrlEncoded(
+                "api-version".into(),
+                API_VERSION.into(),
+            ))
+            .with_header("content-type", "application/json")
+            .with_body(deleted_key.to_string())
+            .with_status(200)
+            .create();
+        let _recover = mock("POST", "/deletedkeys/deleted-key/recover")
+            .match_query(Matcher::UrlEncoded(
+                "api-version".into(),
+                API_VERSION.into(),
+            ))
+            .with_header("content-type", "application/json")
+            .with_body(key_bundle("https://myvault.vault.azure.net/keys/deleted-key/1").to_string())
+            .with_status(200)
+            .create();
+        let _purge = mock("DELETE", "/deletedkeys/deleted-key")
+            .match_query(Matcher::UrlEncoded(
+                "api-version".into(),
+                API_VERSION.into(),
+            ))
+            .with_status(204)
+            .create();
+
+        let creds = MockCredential;
+        let client = mock_client!(&"test-keyvault", creds,);
+
+        let deleted = client.delete_key("deleted-key").await.unwrap();
+        assert_eq!(
+            deleted.recovery_id().as_deref(),
+            Some("https://myvault.vault.azure.net/deletedkeys/deleted-key")
+        );
+        assert_eq!(
+            deleted.scheduled_purge_date().unwrap().timestamp(),
+            1501714433
+        );
+        assert!(deleted.key().key().n().is_some());
+
+        let recovered = client.recover_deleted_key("deleted-key").await.unwrap();
+        assert_eq!(
+            recovered.key().id().as_deref(),
+            Some("https://myvault.vault.azure.net/keys/deleted-key/1")
+        );
+
+        client.purge_deleted_key("deleted-key").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn can_backup_and_restore_key() {
+        let _backup = mock("POST", "/keys/backup-key/backup")
+            .match_query(Matcher::UrlEncoded(
+                "api-version".into(),
+                API_VERSION.into(),
+            ))
+            .with_header("content-type", "application/json")
+            .with_body(json!({ "value": "YmFja3Vw" }).to_string())
+            .with_status(200)
+            .create();
+        let _restore = mock("POST", "/keys/restore")
+            .match_query(Matcher::UrlEncoded(
+                "api-version".into(),
+                API_VERSION.into(),
+            ))
+            .match_body(Matcher::Json(json!({ "value": "YmFja3Vw" })))
+            .with_header("content-type", "application/json")
+            .with_body(key_bundle("https://myvault.vault.azure.net/keys/backup-key/1").to_string())
+            .with_status(200)
+            .create();
+
+        let creds = MockCredential;
+        let client = mock_client!(&"test-keyvault", creds,);
+
+        let backup = client.backup_key("backup-key").await.unwrap();
+        assert_eq!(backup.value(), b"backup");
+        client.restore_key(backup.value()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn can_update_rotation_policy() {
+        let _m = mock("PUT", "/keys/rotated-key/rotationpolicy")
+            .match_query(Matcher::UrlEncoded("api-version".into(), "7.3".into()))
+            .match_body(Matcher::Json(json!({
+                "lifetimeActions": [
+                    { "trigger": { "timeAfterCreate": "P90D" }, "action": { "type": "Rotate" } },
+                    { "trigger": { "timeBeforeExpiry": "P30D" }, "action": { "type": "Notify" } }
+                ],
+                "attributes": { "expiryTime": "P1Y" }
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "id": "https://myvault.vault.azure.net/keys/rotated-key/rotationpolicy",
+                    "lifetimeActions": [
+                        { "trigger": { "timeAfterCreate": "P90D" }, "action": { "type": "rotate" } },
+                        { "trigger": { "timeBeforeExpiry": "P30D" }, "action": { "type": "notify" } }
+                    ],
+                    "attributes": { "expiryTime": "P1Y", "created": 1649797765, "updated": 1649797765 }
+                })
+                .to_string(),
+            )
+            .with_status(200)
+            .create();
+
+        let creds = MockCredential;
+        let client = mock_client!(&"test-keyvault", creds,);
+
+        let policy = KeyRotationPolicy::new()
+            .with_expiry_time("P1Y")
+            .with_lifetime_action(
+                KeyRotationPolicyAction::Rotate,
+                KeyRotationTrigger::TimeAfterCreate("P90D".to_owned()),
+            )
+            .with_lifetime_action(
+                KeyRotationPolicyAction::Notify,
+                KeyRotationTrigger::TimeBeforeExpiry("P30D".to_owned()),
+            );
+        let policy = client
+            .update_key_rotation_policy("rotated-key", &policy)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            policy.id().as_deref(),
+            Some("https://myvault.vault.azure.net/keys/rotated-key/rotationpolicy")
+        );
+        assert_eq!(
+            policy.lifetime_actions()[0].action(),
+            &KeyRotationPolicyAction::Rotate
+        );
+        assert_eq!(
+            policy.lifetime_actions()[1].trigger(),
+            &KeyRotationTrigger::TimeBeforeExpiry("P30D".to_owned())
+        );
+        assert_eq!(policy.attributes().expiry_time().as_deref(), Some("P1Y"));
+    }
+
+    #[test]
+    fn key_names_from_ids() {
+        assert_eq!(
+            key_name_from_id("https://myvault.vault.azure.net/keys/my-key"),
+            "my-key"
+        );
+        assert_eq!(
+            key_name_from_id(
+                "https://myvault.vault.azure.net/keys/my-key/78deebed173b48e48f55abf87ed4cf71"
+            ),
+            "my-key"
+        );
+        assert_eq!(
+            key_name_from_id("https://myvault.vault.azure.net/deletedkeys/my-key"),
+            "my-key"
+        );
+    }
 }
@@ -1,8 +1,11 @@
+pub mod certificate;
 mod client;
+mod cryptography;
 pub mod key;
 pub mod secret;
 
-pub use client::KeyClient;
+pub use client::{KeyClient, KeyClientOptions, Page, PageStream};
+pub use cryptography::CryptographyClient;
 pub use secret::RecoveryLevel;
 
 #[non_exhaustive]
@@ -20,6 +23,9 @@ pub enum Error {
     #[error(transparent)]
     Reqwest(#[from] reqwest::Error),
 
+    #[error(transparent)]
+    Core(#[from] azure_core::Error),
+
     #[error("Key Vault Error: {0}")]
     General(String),
 
@@ -29,9 +35,18 @@ pub enum Error {
     #[error("Could not get vault domain")]
     DomainParse,
 
+    #[error("Cryptography error: {0}")]
+    Cryptography(#[from] openssl::error::ErrorStack),
+
+    #[error("Unexpected certificate content: {0}")]
+    CertificateContent(String),
+
     #[error("URL parse error: {0}")]
     UrlParseError(#[from] url::ParseError),
 
+    #[error("The operation did not complete within {0:?}")]
+    OperationTimedOut(std::time::Duration),
+
     #[error("Failed to parse response from Key Vault when backing up secret {}, response body: {}, error: {}", secret_name, response_body, error)]
     BackupSecretParseError {
         error: serde_json::Error,
@@ -42,19 +57,14 @@ pub enum Error {
 
 #[cfg(test)]
 mod tests {
-    use azure_core::{TokenCredential, TokenResponse};
+    use azure_core::{TokenCredential, TokenRequestOptions, TokenResponse};
     use chrono::{Duration, Utc};
     use oauth2::AccessToken;
 
     #[macro_export]
     macro_rules! mock_client {
         ($keyvault_name:expr, $creds:expr, ) => {{
-            KeyClient {
-                vault_url: url::Url::parse(&mockito::server_url()).unwrap(),
-                endpoint: "".to_string(),
-                token_credential: $creds,
-                token: None,
-            }
+            KeyClient::new(&mockito::server_url(), std::sync::Arc::new($creds)).unwrap()
         }};
     }
 
@@ -62,7 +72,11 @@ mod tests {
 
     #[async_trait::async_trait]
     impl TokenCredential for MockCredential {
-        async fn get_token(&self, _resource: &str) -> Result<TokenResponse, azure_core::Error> {
+        async fn get_token_with_options(
+            &self,
+            _scopes: &[&str],
+            _options: &TokenRequestOptions,
+        ) -> Result<TokenResponse, azure_core::Error> {
             Ok(TokenResponse::new(
                 AccessToken::new("TOKEN".to_owned()),
                 Utc::now() + Duration::days(14),
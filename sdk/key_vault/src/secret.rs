@@ -1,21 +1,15 @@
-use crate::client::API_VERSION_PARAM;
+use crate::client::parse_response;
 use crate::Error;
-use crate::KeyClient;
+use crate::{KeyClient, Page, PageStream};
 
-use azure_core::TokenCredential;
 use chrono::serde::{ts_seconds, ts_seconds_option};
 use chrono::{DateTime, Utc};
-use const_format::formatcp;
+use futures::TryStreamExt;
 use getset::Getters;
-use reqwest::Url;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use std::fmt;
-
-const DEFAULT_MAX_RESULTS: usize = 25;
-
-const API_VERSION_MAX_RESULTS_PARAM: &str =
-    formatcp!("{}&maxresults={}", API_VERSION_PARAM, DEFAULT_MAX_RESULTS);
+use std::time::{Duration, Instant};
 
 /// Reflects the deletion recovery level currently in effect for keys in the current Key Vault.
 /// If it contains 'Purgeable' the key can be permanently deleted by a privileged user;
@@ -55,13 +49,6 @@ pub(crate) struct KeyVaultSecretBaseIdentifierRaw {
     attributes: KeyVaultSecretBaseIdentifierAttributedRaw,
 }
 
-#[derive(Deserialize, Debug)]
-pub(crate) struct KeyVaultGetSecretsResponse {
-    value: Vec<KeyVaultSecretBaseIdentifierRaw>,
-    #[serde(rename = "nextLink")]
-    next_link: Option<String>,
-}
-
 #[derive(Deserialize, Debug)]
 pub(crate) struct KeyVaultGetSecretResponse {
     value: String,
@@ -94,8 +81,9 @@ pub struct KeyVaultSecretBackupBlob {
     value: String,
 }
 
-#[derive(Debug, Getters)]
+#[derive(Debug, Deserialize, Getters)]
 #[getset(get = "pub")]
+#[serde(from = "KeyVaultSecretBaseIdentifierRaw")]
 pub struct KeyVaultSecretBaseIdentifier {
     id: String,
     name: String,
@@ -104,6 +92,18 @@ pub struct KeyVaultSecretBaseIdentifier {
     time_updated: DateTime<Utc>,
 }
 
+impl From<KeyVaultSecretBaseIdentifierRaw> for KeyVaultSecretBaseIdentifier {
+    fn from(s: KeyVaultSecretBaseIdentifierRaw) -> Self {
+        Self {
+            name: s.id.rsplit('/').next().unwrap_or_default().to_owned(),
+            id: s.id,
+            enabled: s.attributes.enabled,
+            time_created: s.attributes.created,
+            time_updated: s.attributes.updated,
+        }
+    }
+}
+
 #[derive(Debug, Getters)]
 #[getset(get = "pub")]
 pub struct KeyVaultSecret {
@@ -115,7 +115,210 @@ pub struct KeyVaultSecret {
     time_updated: DateTime<Utc>,
 }
 
-impl<'a, T: TokenCredential> KeyClient<'a, T> {
+#[derive(Debug, Deserialize, Getters)]
+#[getset(get = "pub")]
+#[serde(rename_all = "camelCase")]
+pub struct SecretAttributes {
+    /// Creation time in UTC.
+    #[serde(rename = "created", with = "ts_seconds_option", default)]
+    created_on: Option<DateTime<Utc>>,
+    /// Determines whether the object is enabled.
+    enabled: Option<bool>,
+    /// Expiry date in UTC.
+    #[serde(rename = "exp", with = "ts_seconds_option", default)]
+    expires_on: Option<DateTime<Utc>>,
+    /// Not before date in UTC.
+    #[serde(rename = "nbf", with = "ts_seconds_option", default)]
+    not_before: Option<DateTime<Utc>>,
+    /// softDelete data retention days. Value should be >=7 and <=90 when softDelete enabled, otherwise 0.
+    recoverable_days: Option<u8>,
+    /// Reflects the deletion recovery level currently in effect for secrets in the current vault.
+    recovery_level: Option<String>,
+    /// Last updated time in UTC.
+    #[serde(rename = "updated", with = "ts_seconds_option", default)]
+    updated_on: Option<DateTime<Utc>>,
+}
+
+/// A deleted secret, which can be recovered or purged.
+#[derive(Debug, Deserialize, Getters)]
+#[getset(get = "pub")]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedSecret {
+    /// The secret identifier.
+    id: String,
+    /// The value of the secret.
+    value: Option<String>,
+    /// The type of the secret value, such as a password.
+    content_type: Option<String>,
+    attributes: SecretAttributes,
+    /// Application specific metadata in the form of key-value pairs.
+    tags: Option<Map<String, Value>>,
+    /// The url of the recovery object, used to recover the deleted secret.
+    recovery_id: Option<String>,
+    /// The time when the secret is scheduled to be purged, in UTC.
+    #[serde(with = "ts_seconds_option", default)]
+    scheduled_purge_date: Option<DateTime<Utc>>,
+    /// The time when the secret was deleted, in UTC.
+    #[serde(with = "ts_seconds_option", default)]
+    deleted_date: Option<DateTime<Utc>>,
+}
+
+impl DeletedSecret {
+    /// The name of the secret, taken from its identifier.
+    pub fn name(&self) -> &str {
+        secret_name_from_id(&self.id)
+    }
+}
+
+/// A deleted secret, as listed by `list_deleted_secrets`.
+#[derive(Debug, Deserialize, Getters)]
+#[getset(get = "pub")]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedSecretItem {
+    /// The secret identifier.
+    id: String,
+    /// The type of the secret value, such as a password.
+    content_type: Option<String>,
+    attributes: SecretAttributes,
+    /// Application specific metadata in the form of key-value pairs.
+    tags: Option<Map<String, Value>>,
+    /// The url of the recovery object, used to recover the deleted secret.
+    recovery_id: Option<String>,
+    /// The time when the secret is scheduled to be purged, in UTC.
+    #[serde(with = "ts_seconds_option", default)]
+    scheduled_purge_date: Option<DateTime<Utc>>,
+    /// The time when the secret was deleted, in UTC.
+    #[serde(with = "ts_seconds_option", default)]
+    deleted_date: Option<DateTime<Utc>>,
+}
+
+impl DeletedSecretItem {
+    /// The name of the secret, taken from its identifier.
+    pub fn name(&self) -> &str {
+        secret_name_from_id(&self.id)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+struct SecretRequestAttributes {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enabled: Option<bool>,
+    #[serde(
+        rename = "nbf",
+        with = "ts_seconds_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    not_before: Option<DateTime<Utc>>,
+    #[serde(
+        rename = "exp",
+        with = "ts_seconds_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    expires_on: Option<DateTime<Utc>>,
+}
+
+/// The properties of a secret version to update, the other ones being left unchanged.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSecretOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_type: Option<String>,
+    attributes: SecretRequestAttributes,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<Map<String, Value>>,
+}
+
+impl UpdateSecretOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the type of the secret value, such as a password.
+    pub fn with_content_type(mut self, content_type: &str) -> Self {
+        self.content_type = Some(content_type.to_owned());
+        self
+    }
+
+    /// Sets whether the secret is enabled.
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.attributes.enabled = Some(enabled);
+        self
+    }
+
+    /// Sets the time before which the secret can't be used.
+    pub fn with_not_before(mut self, not_before: DateTime<Utc>) -> Self {
+        self.attributes.not_before = Some(not_before);
+        self
+    }
+
+    /// Sets the expiration time of the secret.
+    pub fn with_expires_on(mut self, expires_on: DateTime<Utc>) -> Self {
+        self.attributes.expires_on = Some(expires_on);
+        self
+    }
+
+    /// Sets the application specific metadata of the secret, replacing the existing one.
+    pub fn with_tags(mut self, tags: Map<String, Value>) -> Self {
+        self.tags = Some(tags);
+        self
+    }
+}
+
+/// The recovery of a deleted secret, started by `recover_deleted_secret`.
+///
+/// The recovery completes asynchronously: the secret can't be read until it does.
+#[derive(Debug, Clone)]
+pub struct RecoverDeletedSecretPoller {
+    client: KeyClient,
+    secret_name: String,
+}
+
+impl RecoverDeletedSecretPoller {
+    /// Whether the recovery has completed.
+    pub async fn is_done(&self) -> Result<bool, Error> {
+        let mut uri = self.client.vault_url.clone();
+        uri.set_path(&format!("secrets/{}/", self.secret_name));
+        uri.set_query(Some(&self.client.api_version_param()));
+
+        match self.client.get_authed_optional(uri.to_string()).await? {
+            Some(response) => {
+                parse_response::<Value>(&response)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Polls the secret every `poll_interval` until the recovery completes, returning it, or
+    /// fails with `Error::OperationTimedOut` once `max_duration` has elapsed.
+    pub async fn wait(
+        self,
+        poll_interval: Duration,
+        max_duration: Duration,
+    ) -> Result<KeyVaultSecret, Error> {
+        let deadline = Instant::now() + max_duration;
+        while !self.is_done().await? {
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(Error::OperationTimedOut(max_duration));
+            }
+            azure_core::sleep(poll_interval.min(deadline - now)).await;
+        }
+        self.client.get_secret(&self.secret_name).await
+    }
+}
+
+fn secret_name_from_id(id: &str) -> &str {
+    // https://{vault}/secrets/{name}[/{version}] or https://{vault}/deletedsecrets/{name}
+    let mut segments = id.trim_end_matches('/').rsplit('/');
+    let last = segments.next().unwrap_or_default();
+    match segments.next() {
+        Some("secrets") | Some("deletedsecrets") | None => last,
+        Some(name) => name,
+    }
+}
+
+impl KeyClient {
     /// Gets a secret from the Key Vault.
     /// Note that the latest version is fetched. For a specific version, use `get_version_with_version`.
     ///
@@ -124,13 +327,14 @@ impl<'a, T: TokenCredential> KeyClient<'a, T> {
     /// ```no_run
     /// use azure_key_vault::KeyClient;
     /// use azure_identity::token_credentials::DefaultCredential;
+    /// use std::sync::Arc;
     /// use tokio::runtime::Runtime;
     ///
     /// async fn example() {
     ///     let creds = DefaultCredential::default();
-    ///     let mut client = KeyClient::new(
+    ///     let client = KeyClient::new(
     ///     &"KEYVAULT_URL",
-    ///     &creds,
+    ///     Arc::new(creds),
     ///     ).unwrap();
     ///     let secret = client.get_secret(&"SECRET_NAME").await.unwrap();
     ///     dbg!(&secret);
@@ -138,7 +342,7 @@ impl<'a, T: TokenCredential> KeyClient<'a, T> {
     ///
     /// Runtime::new().unwrap().block_on(example());
     /// ```
-    pub async fn get_secret(&mut self, secret_name: &'a str) -> Result<KeyVaultSecret, Error> {
+    pub async fn get_secret(&self, secret_name: &str) -> Result<KeyVaultSecret, Error> {
         Ok(self.get_secret_with_version(secret_name, "").await?)
     }
 
@@ -150,13 +354,14 @@ impl<'a, T: TokenCredential> KeyClient<'a, T> {
     /// ```no_run
     /// use azure_key_vault::KeyClient;
     /// use azure_identity::token_credentials::DefaultCredential;
+    /// use std::sync::Arc;
     /// use tokio::runtime::Runtime;
     ///
     /// async fn example() {
     ///     let creds = DefaultCredential::default();
-    /// let mut client = KeyClient::new(
+    /// let client = KeyClient::new(
     ///     &"KEYVAULT_URL",
-    ///     &creds,
+    ///     Arc::new(creds),
     ///     ).unwrap();
     ///     let secret = client.get_secret_with_version(&"SECRET_NAME", &"SECRET_VERSION").await.unwrap();
     ///     dbg!(&secret);
@@ -165,13 +370,13 @@ impl<'a, T: TokenCredential> KeyClient<'a, T> {
     /// Runtime::new().unwrap().block_on(example());
     /// ```
     pub async fn get_secret_with_version(
-        &mut self,
-        secret_name: &'a str,
-        secret_version_name: &'a str,
+        &self,
+        secret_name: &str,
+        secret_version_name: &str,
     ) -> Result<KeyVaultSecret, Error> {
         let mut uri = self.vault_url.clone();
         uri.set_path(&format!("secrets/{}/{}", secret_name, secret_version_name));
-        uri.set_query(Some(API_VERSION_PARAM));
+        uri.set_query(Some(&self.api_version_param()));
 
         let response_body = self.get_authed(uri.to_string()).await?;
         let response =
@@ -192,57 +397,47 @@ impl<'a, T: TokenCredential> KeyClient<'a, T> {
         })
     }
 
-    /// Lists all the secrets in the Key Vault.
+    /// Lists the secrets in the Key Vault, one page at a time.
+    /// Use `KeyClientOptions::max_results` to set the size of the pages.
     ///
     /// ```no_run
     /// use azure_key_vault::KeyClient;
     /// use azure_identity::token_credentials::DefaultCredential;
+    /// use futures::TryStreamExt;
+    /// use std::sync::Arc;
     /// use tokio::runtime::Runtime;
     ///
     /// async fn example() {
     ///     let creds = DefaultCredential::default();
-    ///     let mut client = KeyClient::new(
+    ///     let client = KeyClient::new(
     ///     &"KEYVAULT_URL",
-    ///     &creds,
+    ///     Arc::new(creds),
     ///     ).unwrap();
-    ///     let secrets = client.list_secrets().await.unwrap();
-    ///     dbg!(&secrets);
+    ///     let mut pages = client.list_secrets();
+    ///     while let Some(page) = pages.try_next().await.unwrap() {
+    ///         dbg!(page.items());
+    ///     }
     /// }
     ///
     /// Runtime::new().unwrap().block_on(example());
     /// ```
-    pub async fn list_secrets(&mut self) -> Result<Vec<KeyVaultSecretBaseIdentifier>, Error> {
-        let mut secrets = Vec::<KeyVaultSecretBaseIdentifier>::new();
-
+    pub fn list_secrets(&self) -> PageStream<'_, KeyVaultSecretBaseIdentifier> {
         let mut uri = self.vault_url.clone();
         uri.set_path("secrets");
-        uri.set_query(Some(API_VERSION_MAX_RESULTS_PARAM));
-
-        loop {
-            let resp_body = self.get_authed(uri.to_string()).await?;
-            let response = serde_json::from_str::<KeyVaultGetSecretsResponse>(&resp_body).unwrap();
-
-            secrets.extend(
-                response
-                    .value
-                    .into_iter()
-                    .map(|s| KeyVaultSecretBaseIdentifier {
-                        id: s.id.to_owned(),
-                        name: s.id.split('/').last().unwrap().to_owned(),
-                        enabled: s.attributes.enabled,
-                        time_created: s.attributes.created,
-                        time_updated: s.attributes.updated,
-                    })
-                    .collect::<Vec<KeyVaultSecretBaseIdentifier>>(),
-            );
-
-            match response.next_link {
-                None => break,
-                Some(u) => uri = Url::parse(&u).unwrap(),
-            }
-        }
+        uri.set_query(Some(&self.api_version_max_results_param()));
+        self.list_pages(uri)
+    }
 
-        Ok(secrets)
+    /// Lists the versions of a secret, one page at a time.
+    /// This operation requires the secrets/list permission.
+    pub fn list_secret_versions(
+        &self,
+        secret_name: &str,
+    ) -> PageStream<'_, KeyVaultSecretBaseIdentifier> {
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!("secrets/{}/versions", secret_name));
+        uri.set_query(Some(&self.api_version_max_results_param()));
+        self.list_pages(uri)
     }
 
     /// Gets all the versions for a secret in the Key Vault.
@@ -252,13 +447,14 @@ impl<'a, T: TokenCredential> KeyClient<'a, T> {
     /// ```no_run
     /// use azure_key_vault::KeyClient;
     /// use azure_identity::token_credentials::DefaultCredential;
+    /// use std::sync::Arc;
     /// use tokio::runtime::Runtime;
     ///
     /// async fn example() {
     ///     let creds = DefaultCredential::default();
-    ///     let mut client = KeyClient::new(
+    ///     let client = KeyClient::new(
     ///     &"KEYVAULT_URL",
-    ///     &creds,
+    ///     Arc::new(creds),
     ///     ).unwrap();
     ///     let secret_versions = client.get_secret_versions(&"SECRET_NAME").await.unwrap();
     ///     dbg!(&secret_versions);
@@ -267,37 +463,16 @@ impl<'a, T: TokenCredential> KeyClient<'a, T> {
     /// Runtime::new().unwrap().block_on(example());
     /// ```
     pub async fn get_secret_versions(
-        &mut self,
-        secret_name: &'a str,
+        &self,
+        secret_name: &str,
     ) -> Result<Vec<KeyVaultSecretBaseIdentifier>, Error> {
-        let mut secret_versions = Vec::<KeyVaultSecretBaseIdentifier>::new();
-
-        let mut uri = self.vault_url.clone();
-        uri.set_path(&format!("secrets/{}/versions", secret_name));
-        uri.set_query(Some(API_VERSION_MAX_RESULTS_PARAM));
-
-        loop {
-            let resp_body = self.get_authed(uri.to_string()).await?;
-            let response = serde_json::from_str::<KeyVaultGetSecretsResponse>(&resp_body).unwrap();
-
-            secret_versions.extend(
-                response
-                    .value
-                    .into_iter()
-                    .map(|s| KeyVaultSecretBaseIdentifier {
-                        id: s.id.to_owned(),
-                        name: s.id.split('/').last().unwrap().to_owned(),
-                        enabled: s.attributes.enabled,
-                        time_created: s.attributes.created,
-                        time_updated: s.attributes.updated,
-                    })
-                    .collect::<Vec<KeyVaultSecretBaseIdentifier>>(),
-            );
-            match response.next_link {
-                None => break,
-                Some(u) => uri = Url::parse(&u).unwrap(),
-            }
-        }
+        let mut secret_versions = self
+            .list_secret_versions(secret_name)
+            .try_collect::<Vec<_>>()
+            .await?
+            .into_iter()
+            .flat_map(Page::into_items)
+            .collect::<Vec<KeyVaultSecretBaseIdentifier>>();
 
         // Return the secret versions sorted by the time modified in descending order.
         secret_versions.sort_by(|a, b| {
@@ -317,27 +492,24 @@ impl<'a, T: TokenCredential> KeyClient<'a, T> {
     /// ```no_run
     /// use azure_key_vault::KeyClient;
     /// use azure_identity::token_credentials::DefaultCredential;
+    /// use std::sync::Arc;
     /// use tokio::runtime::Runtime;
     ///
     /// async fn example() {
     ///     let creds = DefaultCredential::default();
-    ///     let mut client = KeyClient::new(
+    ///     let client = KeyClient::new(
     ///     &"KEYVAULT_URL",
-    ///     &creds,
+    ///     Arc::new(creds),
     ///     ).unwrap();
     ///     client.set_secret(&"SECRET_NAME", &"NEW_VALUE").await.unwrap();
     /// }
     ///
     /// Runtime::new().unwrap().block_on(example());
     /// ```
-    pub async fn set_secret(
-        &mut self,
-        secret_name: &'a str,
-        new_secret_value: &'a str,
-    ) -> Result<(), Error> {
+    pub async fn set_secret(&self, secret_name: &str, new_secret_value: &str) -> Result<(), Error> {
         let mut uri = self.vault_url.clone();
         uri.set_path(&format!("secrets/{}", secret_name));
-        uri.set_query(Some(API_VERSION_PARAM));
+        uri.set_query(Some(&self.api_version_param()));
 
         let mut request_body = Map::new();
         request_body.insert(
@@ -364,13 +536,14 @@ impl<'a, T: TokenCredential> KeyClient<'a, T> {
     /// ```no_run
     /// use azure_key_vault::KeyClient;
     /// use azure_identity::token_credentials::DefaultCredential;
+    /// use std::sync::Arc;
     /// use tokio::runtime::Runtime;
     ///
     /// async fn example() {
     ///     let creds = DefaultCredential::default();
-    ///     let mut client = KeyClient::new(
+    ///     let client = KeyClient::new(
     ///     &"KEYVAULT_URL",
-    ///     &creds,
+    ///     Arc::new(creds),
     ///     ).unwrap();
     ///     client.update_secret_enabled(&"SECRET_NAME", &"", true).await.unwrap();
     /// }
@@ -378,15 +551,15 @@ impl<'a, T: TokenCredential> KeyClient<'a, T> {
     /// Runtime::new().unwrap().block_on(example());
     /// ```
     pub async fn update_secret_enabled(
-        &mut self,
-        secret_name: &'a str,
-        secret_version: &'a str,
+        &self,
+        secret_name: &str,
+        secret_version: &str,
         enabled: bool,
     ) -> Result<(), Error> {
         let mut attributes = Map::new();
         attributes.insert("enabled".to_owned(), Value::Bool(enabled));
 
-        self.update_secret(secret_name, secret_version, attributes)
+        self.update_secret_attributes(secret_name, secret_version, attributes)
             .await?;
 
         Ok(())
@@ -405,13 +578,14 @@ impl<'a, T: TokenCredential> KeyClient<'a, T> {
     /// ```no_run
     /// use azure_key_vault::{KeyClient, RecoveryLevel};
     /// use azure_identity::token_credentials::DefaultCredential;
+    /// use std::sync::Arc;
     /// use tokio::runtime::Runtime;
     ///
     /// async fn example() {
     ///     let creds = DefaultCredential::default();
-    ///     let mut client = KeyClient::new(
+    ///     let client = KeyClient::new(
     ///     &"KEYVAULT_URL",
-    ///     &creds,
+    ///     Arc::new(creds),
     ///     ).unwrap();
     ///     client.update_secret_recovery_level(&"SECRET_NAME", &"", RecoveryLevel::Purgeable).await.unwrap();
     /// }
@@ -419,9 +593,9 @@ impl<'a, T: TokenCredential> KeyClient<'a, T> {
     /// Runtime::new().unwrap().block_on(example());
     /// ```
     pub async fn update_secret_recovery_level(
-        &mut self,
-        secret_name: &'a str,
-        secret_version: &'a str,
+        &self,
+        secret_name: &str,
+        secret_version: &str,
         recovery_level: RecoveryLevel,
     ) -> Result<(), Error> {
         let mut attributes = Map::new();
@@ -430,7 +604,7 @@ impl<'a, T: TokenCredential> KeyClient<'a, T> {
             Value::String(recovery_level.to_string()),
         );
 
-        self.update_secret(secret_name, secret_version, attributes)
+        self.update_secret_attributes(secret_name, secret_version, attributes)
             .await?;
 
         Ok(())
@@ -449,14 +623,15 @@ impl<'a, T: TokenCredential> KeyClient<'a, T> {
     /// ```no_run
     /// use azure_key_vault::{KeyClient, RecoveryLevel};
     /// use azure_identity::token_credentials::DefaultCredential;
+    /// use std::sync::Arc;
     /// use tokio::runtime::Runtime;
     /// use chrono::{Utc, Duration};
     ///
     /// async fn example() {
     ///     let creds = DefaultCredential::default();
-    ///     let mut client = KeyClient::new(
+    ///     let client = KeyClient::new(
     ///     &"KEYVAULT_URL",
-    ///     &creds,
+    ///     Arc::new(creds),
     ///     ).unwrap();
     ///     client.update_secret_expiration_time(&"SECRET_NAME", &"", Utc::now() + Duration::days(14)).await.unwrap();
     /// }
@@ -464,9 +639,9 @@ impl<'a, T: TokenCredential> KeyClient<'a, T> {
     /// Runtime::new().unwrap().block_on(example());
     /// ```
     pub async fn update_secret_expiration_time(
-        &mut self,
-        secret_name: &'a str,
-        secret_version: &'a str,
+        &self,
+        secret_name: &str,
+        secret_version: &str,
         expiration_time: DateTime<Utc>,
     ) -> Result<(), Error> {
         let mut attributes = Map::new();
@@ -475,21 +650,71 @@ impl<'a, T: TokenCredential> KeyClient<'a, T> {
             Value::Number(serde_json::Number::from(expiration_time.timestamp())),
         );
 
-        self.update_secret(secret_name, secret_version, attributes)
+        self.update_secret_attributes(secret_name, secret_version, attributes)
             .await?;
 
         Ok(())
     }
 
-    async fn update_secret(
-        &mut self,
-        secret_name: &'a str,
-        secret_version: &'a str,
+    /// Updates the content type, attributes and tags of a secret version in a single call.
+    /// Its value can't be updated: use `set_secret` to create a new version instead.
+    /// This operation requires the secrets/set permission.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret_name` - Name of the secret
+    /// * `secret_version` - Version of the secret. Use an empty string for the latest version
+    /// * `options` - The properties to update
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use azure_key_vault::KeyClient;
+    /// use azure_key_vault::secret::UpdateSecretOptions;
+    /// use azure_identity::token_credentials::DefaultCredential;
+    /// use chrono::{Duration, Utc};
+    /// use std::sync::Arc;
+    /// use tokio::runtime::Runtime;
+    ///
+    /// async fn example() {
+    ///     let creds = DefaultCredential::default();
+    ///     let client = KeyClient::new(&"KEYVAULT_URL", Arc::new(creds)).unwrap();
+    ///     let options = UpdateSecretOptions::new()
+    ///         .with_content_type("password")
+    ///         .with_enabled(true)
+    ///         .with_expires_on(Utc::now() + Duration::days(90));
+    ///     client.update_secret(&"SECRET_NAME", &"", options).await.unwrap();
+    /// }
+    ///
+    /// Runtime::new().unwrap().block_on(example());
+    /// ```
+    pub async fn update_secret(
+        &self,
+        secret_name: &str,
+        secret_version: &str,
+        options: UpdateSecretOptions,
+    ) -> Result<(), Error> {
+        // PATCH {vaultBaseUrl}/secrets/{secret-name}/{secret-version}?api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!("secrets/{}/{}", secret_name, secret_version));
+        uri.set_query(Some(&self.api_version_param()));
+
+        self.patch_authed(uri.to_string(), serde_json::to_string(&options)?)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn update_secret_attributes(
+        &self,
+        secret_name: &str,
+        secret_version: &str,
         attributes: Map<String, Value>,
     ) -> Result<(), Error> {
         let mut uri = self.vault_url.clone();
         uri.set_path(&format!("secrets/{}/{}", secret_name, secret_version));
-        uri.set_query(Some(API_VERSION_PARAM));
+        uri.set_query(Some(&self.api_version_param()));
 
         let mut request_body = Map::new();
         request_body.insert("attributes".to_owned(), Value::Object(attributes));
@@ -508,23 +733,24 @@ impl<'a, T: TokenCredential> KeyClient<'a, T> {
     /// ```no_run
     /// use azure_key_vault::KeyClient;
     /// use azure_identity::token_credentials::DefaultCredential;
+    /// use std::sync::Arc;
     /// use tokio::runtime::Runtime;
     ///
     /// async fn example() {
     ///     let creds = DefaultCredential::default();
-    ///     let mut client = KeyClient::new(
+    ///     let client = KeyClient::new(
     ///     &"KEYVAULT_URL",
-    ///     &creds,
+    ///     Arc::new(creds),
     ///     ).unwrap();
     ///     client.restore_secret(&"KUF6dXJlS2V5VmF1bHRTZWNyZXRCYWNrdXBWMS5taW").await.unwrap();
     /// }
     ///
     /// Runtime::new().unwrap().block_on(example());
     /// ```
-    pub async fn restore_secret(&mut self, backup_blob: &'a str) -> Result<(), Error> {
+    pub async fn restore_secret(&self, backup_blob: &str) -> Result<(), Error> {
         let mut uri = self.vault_url.clone();
         uri.set_path("secrets/restore");
-        uri.set_query(Some(API_VERSION_PARAM));
+        uri.set_query(Some(&self.api_version_param()));
 
         let mut request_body = Map::new();
         request_body.insert("value".to_owned(), Value::String(backup_blob.to_owned()));
@@ -546,13 +772,14 @@ impl<'a, T: TokenCredential> KeyClient<'a, T> {
     /// ```no_run
     /// use azure_key_vault::KeyClient;
     /// use azure_identity::token_credentials::DefaultCredential;
+    /// use std::sync::Arc;
     /// use tokio::runtime::Runtime;
     ///
     /// async fn example() {
     ///     let creds = DefaultCredential::default();
-    ///     let mut client = KeyClient::new(
+    ///     let client = KeyClient::new(
     ///     &"KEYVAULT_URL",
-    ///     &creds,
+    ///     Arc::new(creds),
     ///     ).unwrap();
     ///     client.backup_secret(&"SECRET_NAME").await.unwrap();
     /// }
@@ -560,12 +787,12 @@ impl<'a, T: TokenCredential> KeyClient<'a, T> {
     /// Runtime::new().unwrap().block_on(example());
     /// ```
     pub async fn backup_secret(
-        &mut self,
-        secret_name: &'a str,
+        &self,
+        secret_name: &str,
     ) -> Result<KeyVaultSecretBackupBlob, Error> {
         let mut uri = self.vault_url.clone();
         uri.set_path(&format!("secrets/{}/backup", secret_name));
-        uri.set_query(Some(API_VERSION_PARAM));
+        uri.set_query(Some(&self.api_version_param()));
 
         let response_body = self.post_authed(uri.to_string(), None).await?;
         let backup_blob = serde_json::from_str::<KeyVaultSecretBackupResponseRaw>(&response_body)
@@ -591,28 +818,116 @@ impl<'a, T: TokenCredential> KeyClient<'a, T> {
     /// ```no_run
     /// use azure_key_vault::{KeyClient, RecoveryLevel};
     /// use azure_identity::token_credentials::DefaultCredential;
+    /// use std::sync::Arc;
     /// use tokio::runtime::Runtime;
     ///
     /// async fn example() {
     ///     let creds = DefaultCredential::default();
-    ///     let mut client = KeyClient::new(
+    ///     let client = KeyClient::new(
     ///     &"KEYVAULT_URL",
-    ///     &creds,
+    ///     Arc::new(creds),
     ///     ).unwrap();
     ///     client.delete_secret(&"SECRET_NAME").await.unwrap();
     /// }
     ///
     /// Runtime::new().unwrap().block_on(example());
     /// ```
-    pub async fn delete_secret(&mut self, secret_name: &'a str) -> Result<(), Error> {
+    pub async fn delete_secret(&self, secret_name: &str) -> Result<(), Error> {
         let mut uri = self.vault_url.clone();
         uri.set_path(&format!("secrets/{}", secret_name));
-        uri.set_query(Some(API_VERSION_PARAM));
+        uri.set_query(Some(&self.api_version_param()));
 
         self.delete_authed(uri.to_string()).await?;
 
         Ok(())
     }
+
+    /// Gets a deleted secret.
+    /// This operation requires the secrets/get permission.
+    pub async fn get_deleted_secret(&self, secret_name: &str) -> Result<DeletedSecret, Error> {
+        // GET {vaultBaseUrl}/deletedsecrets/{secret-name}?api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!("deletedsecrets/{}", secret_name));
+        uri.set_query(Some(&self.api_version_param()));
+
+        let response = self.get_authed(uri.to_string()).await?;
+        parse_response(&response)
+    }
+
+    /// Lists the deleted secrets, one page at a time.
+    /// This operation requires the secrets/list permission.
+    pub fn list_deleted_secrets(&self) -> PageStream<'_, DeletedSecretItem> {
+        // GET {vaultBaseUrl}/deletedsecrets?maxresults=25&api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path("deletedsecrets");
+        uri.set_query(Some(&self.api_version_max_results_param()));
+        self.list_pages(uri)
+    }
+
+    /// Permanently deletes a deleted secret, which can't be recovered afterwards.
+    /// This operation requires the secrets/purge permission.
+    pub async fn purge_deleted_secret(&self, secret_name: &str) -> Result<(), Error> {
+        // DELETE {vaultBaseUrl}/deletedsecrets/{secret-name}?api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!("deletedsecrets/{}", secret_name));
+        uri.set_query(Some(&self.api_version_param()));
+
+        let response = self.delete_authed(uri.to_string()).await?;
+        if response.is_empty() {
+            return Ok(());
+        }
+        parse_response::<Value>(&response)?;
+        Ok(())
+    }
+
+    /// Recovers a deleted secret to its latest version.
+    /// The recovery completes asynchronously: use the returned poller to wait for it.
+    /// This operation requires the secrets/recover permission.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use azure_key_vault::KeyClient;
+    /// use azure_identity::token_credentials::DefaultCredential;
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    /// use tokio::runtime::Runtime;
+    ///
+    /// async fn example() {
+    ///     let creds = DefaultCredential::default();
+    ///     let client = KeyClient::new(&"KEYVAULT_URL", Arc::new(creds)).unwrap();
+    ///     let secret = client
+    ///         .recover_deleted_secret(&"SECRET_NAME")
+    ///         .await
+    ///         .unwrap()
+    ///         .wait(Duration::from_secs(2), Duration::from_secs(60))
+    ///         .await
+    ///         .unwrap();
+    ///     dbg!(&secret);
+    /// }
+    ///
+    /// Runtime::new().unwrap().block_on(example());
+    /// ```
+    pub async fn recover_deleted_secret(
+        &self,
+        secret_name: &str,
+    ) -> Result<RecoverDeletedSecretPoller, Error> {
+        // POST {vaultBaseUrl}/deletedsecrets/{secret-name}/recover?api-version=7.1
+
+        let mut uri = self.vault_url.clone();
+        uri.set_path(&format!("deletedsecrets/{}/recover", secret_name));
+        uri.set_query(Some(&self.api_version_param()));
+
+        self.post_authed(uri.to_string(), None).await?;
+
+        Ok(RecoverDeletedSecretPoller {
+            client: self.clone(),
+            secret_name: secret_name.to_owned(),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -620,11 +935,11 @@ impl<'a, T: TokenCredential> KeyClient<'a, T> {
 mod tests {
     use super::*;
 
-    use chrono::{Duration, Utc};
+    use chrono::{Duration, TimeZone, Utc};
     use mockito::{mock, Matcher};
     use serde_json::json;
 
-    use crate::client::API_VERSION;
+    use crate::client::{API_VERSION, DEFAULT_MAX_RESULTS};
     use crate::mock_client;
     use crate::tests::MockCredential;
 
@@ -661,9 +976,9 @@ mod tests {
 
         let creds = MockCredential;
         dbg!(mockito::server_url());
-        let mut client = mock_client!(&"test-keyvault", &creds,);
+        let client = mock_client!(&"test-keyvault", creds,);
 
-        let secret: KeyVaultSecret = client.get_secret(&"test-secret").await.unwrap();
+        let secret: KeyVaultSecret = client.get_secret("test-secret").await.unwrap();
 
         assert_eq!("secret-value", secret.value());
         assert_eq!(
@@ -730,9 +1045,9 @@ mod tests {
             .create();
 
         let creds = MockCredential;
-        let mut client = mock_client!(&"test-keyvault", &creds,);
+        let client = mock_client!(&"test-keyvault", creds,);
 
-        let secret_versions = client.get_secret_versions(&"test-secret").await.unwrap();
+        let secret_versions = client.get_secret_versions("test-secret").await.unwrap();
 
         let secret_1 = &secret_versions[0];
         assert_eq!(
@@ -750,4 +1065,298 @@ mod tests {
         assert!(diff(time_created_2, *secret_2.time_created()) < Duration::seconds(1));
         assert!(diff(time_updated_2, *secret_2.time_updated()) < Duration::seconds(1));
     }
+
+    #[tokio::test]
+    async fn list_secrets_one_page_at_a_time() {
+        let _m1 = mock("GET", "/secrets")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("api-version".into(), API_VERSION.into()),
+                Matcher::UrlEncoded("maxresults".into(), "1".into()),
+            ]))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "value": [{
+                        "id": "https://test-keyvault.vault.azure.net/secrets/secret-1",
+                        "attributes": { "enabled": true, "created": 1482188947, "updated": 1482188947 }
+                    }],
+                    "nextLink": format!("{}/secrets?api-version={}&maxresults=1&$skiptoken=SKIP_TOKEN_MOCK", mockito::server_url(), API_VERSION)
+                })
+                .to_string(),
+            )
+            .with_status(200)
+            .create();
+        let _m2 = mock("GET", "/secrets")
+            .match_query(Matcher::UrlEncoded("$skiptoken".into(), "SKIP_TOKEN_MOCK".into()))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "value": [{
+                        "id": "https://test-keyvault.vault.azure.net/secrets/secret-2",
+                        "attributes": { "enabled": false, "created": 1482188947, "updated": 1482188947 }
+                    }],
+                    "nextLink": null
+                })
+                .to_string(),
+            )
+            .with_status(200)
+            .create();
+
+        let client = KeyClient::new_with_options(
+            &mockito::server_url(),
+            std::sync::Arc::new(MockCredential),
+            crate::KeyClientOptions::default().max_results(1usize),
+        )
+        .unwrap();
+
+        let pages = client.list_secrets().try_collect::<Vec<_>>().await.unwrap();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].items()[0].name(), "secret-1");
+        assert_eq!(pages[1].items()[0].name(), "secret-2");
+        assert!(!*pages[1].items()[0].enabled());
+    }
+
+    #[tokio::test]
+    async fn update_secret() {
+        let _m = mock("PATCH", "/secrets/test-secret/")
+            .match_query(Matcher::UrlEncoded("api-version".into(), API_VERSION.into()))
+            .match_body(Matcher::Json(json!({
+                "contentType": "password",
+                "attributes": { "enabled": false, "exp": 1482188947 },
+                "tags": { "environment": "test" }
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "id": "https://test-keyvault.vault.azure.net/secrets/test-secret/1",
+                    "contentType": "password",
+                    "attributes": { "enabled": false, "exp": 1482188947, "created": 1482188947, "updated": 1482188947 },
+                    "tags": { "environment": "test" }
+                })
+                .to_string(),
+            )
+            .with_status(200)
+            .create();
+
+        let creds = MockCredential;
+        let client = mock_client!(&"test-keyvault", creds,);
+
+        let mut tags = Map::new();
+        tags.insert("environment".to_owned(), json!("test"));
+        let options = UpdateSecretOptions::new()
+            .with_content_type("password")
+            .with_enabled(false)
+            .with_expires_on(Utc.timestamp_opt(1482188947, 0).unwrap())
+            .with_tags(tags);
+        client
+            .update_secret("test-secret", "", options)
+            .await
+            .unwrap();
+        _m.assert();
+    }
+
+    #[tokio::test]
+    async fn set_secret_fails_when_forbidden() {
+        let _m = mock("PUT", "/secrets/test-secret")
+            .match_query(Matcher::UrlEncoded(
+                "api-version".into(),
+                API_VERSION.into(),
+            ))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "error": {
+                        "code": "Forbidden",
+                        "message": "The user does not have secrets set permission"
+                    }
+                })
+                .to_string(),
+            )
+            .with_status(403)
+            .create();
+
+        let creds = MockCredential;
+        let client = mock_client!(&"test-keyvault", creds,);
+
+        match client.set_secret("test-secret", "secret-value").await {
+            Err(Error::General(message)) => {
+                assert_eq!(message, "The user does not have secrets set permission")
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn delete_secret_fails_when_not_found() {
+        let _m = mock("DELETE", "/secrets/missing-secret")
+            .match_query(Matcher::UrlEncoded("api-version".into(), API_VERSION.into()))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "error": { "code": "SecretNotFound", "message": "Secret not found: missing-secret" }
+                })
+                .to_string(),
+            )
+            .with_status(404)
+            .create();
+
+        let creds = MockCredential;
+        let client = mock_client!(&"test-keyvault", creds,);
+
+        match client.delete_secret("missing-secret").await {
+            Err(Error::General(message)) => assert_eq!(message, "Secret not found: missing-secret"),
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn get_and_list_deleted_secrets() {
+        let deleted_secret = json!({
+            "id": "https://test-keyvault.vault.azure.net/secrets/deleted-secret/1",
+            "recoveryId": "https://test-keyvault.vault.azure.net/deletedsecrets/deleted-secret",
+            "scheduledPurgeDate": 1493938410,
+            "deletedDate": 1493938410,
+            "attributes": {
+                "enabled": true,
+                "created": 1493938410,
+                "updated": 1493938410,
+                "recoveryLevel": "Recoverable+Purgeable"
+            }
+        });
+        let _get = mock("GET", "/deletedsecrets/deleted-secret")
+            .match_query(Matcher::UrlEncoded(
+                "api-version".into(),
+                API_VERSION.into(),
+            ))
+            .with_header("content-type", "application/json")
+            .with_body(deleted_secret.to_string())
+            .with_status(200)
+            .create();
+        let _list = mock("GET", "/deletedsecrets")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("api-version".into(), API_VERSION.into()),
+                Matcher::UrlEncoded("maxresults".into(), DEFAULT_MAX_RESULTS.to_string()),
+            ]))
+            .with_header("content-type", "application/json")
+            .with_body(json!({ "value": [deleted_secret], "nextLink": null }).to_string())
+            .with_status(200)
+            .create();
+
+        let creds = MockCredential;
+        let client = mock_client!(&"test-keyvault", creds,);
+
+        let secret = client.get_deleted_secret("deleted-secret").await.unwrap();
+        assert_eq!(secret.name(), "deleted-secret");
+        assert_eq!(
+            secret.recovery_id().as_deref(),
+            Some("https://test-keyvault.vault.azure.net/deletedsecrets/deleted-secret")
+        );
+        assert_eq!(
+            secret.attributes().recovery_level().as_deref(),
+            Some("Recoverable+Purgeable")
+        );
+
+        let pages = client
+            .list_deleted_secrets()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].items()[0].name(), "deleted-secret");
+        assert_eq!(
+            *pages[0].items()[0].deleted_date(),
+            Some(Utc.timestamp_opt(1493938410, 0).unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn recover_and_purge_deleted_secret() {
+        let _recover = mock("POST", "/deletedsecrets/recovered-secret/recover")
+            .match_query(Matcher::UrlEncoded(
+                "api-version".into(),
+                API_VERSION.into(),
+            ))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "id": "https://test-keyvault.vault.azure.net/secrets/recovered-secret/1",
+                    "attributes": { "enabled": true, "created": 1493938410, "updated": 1493938410 }
+                })
+                .to_string(),
+            )
+            .with_status(200)
+            .create();
+        let _get = mock("GET", "/secrets/recovered-secret/")
+            .match_query(Matcher::UrlEncoded(
+                "api-version".into(),
+                API_VERSION.into(),
+            ))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "value": "secret-value",
+                    "id": "https://test-keyvault.vault.azure.net/secrets/recovered-secret/1",
+                    "attributes": {
+                        "enabled": true,
+                        "created": 1493938410,
+                        "updated": 1493938410,
+                        "recoveryLevel": "Recoverable+Purgeable"
+                    }
+                })
+                .to_string(),
+            )
+            .with_status(200)
+            .create();
+        let _missing = mock("GET", "/secrets/purged-secret/")
+            .match_query(Matcher::UrlEncoded("api-version".into(), API_VERSION.into()))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "error": { "code": "SecretNotFound", "message": "Secret not found: purged-secret" }
+                })
+                .to_string(),
+            )
+            .with_status(404)
+            .create();
+        let _purge = mock("DELETE", "/deletedsecrets/purged-secret")
+            .match_query(Matcher::UrlEncoded(
+                "api-version".into(),
+                API_VERSION.into(),
+            ))
+            .with_status(204)
+            .create();
+
+        let creds = MockCredential;
+        let client = mock_client!(&"test-keyvault", creds,);
+
+        let poller = client
+            .recover_deleted_secret("recovered-secret")
+            .await
+            .unwrap();
+        let secret = poller
+            .wait(
+                Duration::milliseconds(10).to_std().unwrap(),
+                Duration::seconds(5).to_std().unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(secret.value(), "secret-value");
+
+        let poller = RecoverDeletedSecretPoller {
+            client: client.clone(),
+            secret_name: "purged-secret".to_owned(),
+        };
+        assert!(!poller.is_done().await.unwrap());
+        let max_duration = Duration::milliseconds(30).to_std().unwrap();
+        match poller
+            .wait(Duration::milliseconds(10).to_std().unwrap(), max_duration)
+            .await
+        {
+            Err(Error::OperationTimedOut(duration)) => assert_eq!(duration, max_duration),
+            other => panic!("expected a timeout, got {:?}", other.map(|_| ())),
+        }
+
+        client.purge_deleted_secret("purged-secret").await.unwrap();
+        _purge.assert();
+    }
 }
@@ -0,0 +1,70 @@
+use azure_service_bus::event_hubs::{
+    CreateBatchOptions, EventData, EventHubClientOptions, EventHubConsumerClient,
+    EventHubProducerClient, EventPosition, ReceiveOptions, DEFAULT_CONSUMER_GROUP,
+};
+use std::env;
+use std::time::Duration;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let namespace =
+        env::var("EVENT_HUB_NAMESPACE").expect("Set env variable EVENT_HUB_NAMESPACE first!");
+    let event_hub = env::var("EVENT_HUB_NAME").expect("Set env variable EVENT_HUB_NAME first!");
+    let policy_name =
+        env::var("EVENT_HUB_POLICY_NAME").expect("Set env variable EVENT_HUB_POLICY_NAME first!");
+    let policy_key =
+        env::var("EVENT_HUB_POLICY_KEY").expect("Set env variable EVENT_HUB_POLICY_KEY first!");
+
+    let producer = EventHubProducerClient::new(
+        &namespace,
+        &event_hub,
+        &policy_name,
+        &policy_key,
+        EventHubClientOptions::default(),
+    )?;
+    let partition_id = producer.get_partition_ids().await?.remove(0);
+    let start = producer.get_partition_properties(&partition_id).await?;
+
+    let mut batch = producer
+        .create_batch(CreateBatchOptions::default().partition_id(partition_id.clone()))
+        .await?;
+    for i in 0..10 {
+        if !batch.try_add(&EventData::new(format!("event {}", i))) {
+            break;
+        }
+    }
+    println!(
+        "sending {} events to partition {}",
+        batch.len(),
+        partition_id
+    );
+    producer.send_batch(batch).await?;
+    producer.close().await?;
+
+    let consumer = EventHubConsumerClient::new(
+        &namespace,
+        &event_hub,
+        DEFAULT_CONSUMER_GROUP,
+        &policy_name,
+        &policy_key,
+        EventHubClientOptions::default(),
+    )?;
+    let mut receiver = consumer
+        .receive_from_partition(
+            &partition_id,
+            EventPosition::from_sequence_number(start.last_enqueued_sequence_number(), false),
+            ReceiveOptions::default(),
+        )
+        .await?;
+    for event in receiver.receive_batch(10, Duration::from_secs(10)).await? {
+        println!(
+            "{}: {:?}",
+            event.sequence_number(),
+            std::str::from_utf8(event.body())?
+        );
+    }
+    receiver.close().await?;
+    consumer.close().await?;
+
+    Ok(())
+}
@@ -1,65 +1,51 @@
-// use azure_service_bus::prelude::*;
-// use chrono::Duration;
+use azure_service_bus::prelude::*;
 use std::error::Error;
-
-// async fn send(
-//     s: String,
-//     service_bus_namespace: String,
-//     event_hub_name: String,
-//     policy_name: String,
-//     policy_key: String,
-// ) -> Result<(), Box<dyn Error>> {
-//     let mut client = Client::new(
-//         service_bus_namespace.to_owned(),
-//         event_hub_name.to_owned(),
-//         policy_name.to_owned(),
-//         policy_key.to_owned(),
-//     )?;
-
-//     println!("before {:?} message send!", s);
-//     match client.send_event(&s, Duration::days(1)).await {
-//         Ok(_) => println!("{:?} message sent!", s),
-
-//         Err(error) => println!("{:?} failed to send message", error),
-//     }
-
-//     Ok(())
-// }
+use std::time::Duration;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
-    // // First we retrieve the account name and master key from environment variables.
-    // // We expect master keys (ie, not resource constrained)
-    // let service_bus_namespace = std::env::var("AZURE_SERVICE_BUS_NAMESPACE")
-    //     .expect("Set env variable AZURE_SERVICE_BUS_NAMESPACE first!");
-    // let event_hub_name = std::env::var("AZURE_EVENT_HUB_NAME")
-    //     .expect("Set env variable AZURE_EVENT_HUB_NAME first!");
-    // let policy_name =
-    //     std::env::var("AZURE_POLICY_NAME").expect("Set env variable AZURE_POLICY_NAME first!");
-    // let policy_key =
-    //     std::env::var("AZURE_POLICY_KEY").expect("Set env variable AZURE_POLICY_KEY first!");
-
-    // let messages = vec![
-    //     "These", "are", "useless", "messages", "provided", "for", "free", "with", "love",
-    // ];
-    // println!(
-    //     "Sending the following messages: {:?}. \
-    //      Please note they will be sent out of order!",
-    //     messages
-    // );
-
-    // let mut v = Vec::new();
-    // for s in messages.into_iter() {
-    //     v.push(send(
-    //         s.to_owned(),
-    //         service_bus_namespace.to_owned(),
-    //         event_hub_name.to_owned(),
-    //         policy_name.to_owned(),
-    //         policy_key.to_owned(),
-    //     ))
-    // }
-
-    // futures::future::join_all(v).await;
+    // First we retrieve the namespace, the queue and the shared access policy from environment
+    // variables.
+    let service_bus_namespace = std::env::var("AZURE_SERVICE_BUS_NAMESPACE")
+        .expect("Set env variable AZURE_SERVICE_BUS_NAMESPACE first!");
+    let queue_name =
+        std::env::var("AZURE_QUEUE_NAME").expect("Set env variable AZURE_QUEUE_NAME first!");
+    let policy_name =
+        std::env::var("AZURE_POLICY_NAME").expect("Set env variable AZURE_POLICY_NAME first!");
+    let policy_key =
+        std::env::var("AZURE_POLICY_KEY").expect("Set env variable AZURE_POLICY_KEY first!");
+
+    let client = ServiceBusClient::new(
+        azure_core::new_http_client(),
+        service_bus_namespace,
+        policy_name,
+        policy_key,
+        ServiceBusClientOptions::default(),
+    )?;
+
+    let messages = vec![
+        "These", "are", "useless", "messages", "provided", "for", "free", "with", "love",
+    ];
+    println!("Sending the following messages: {:?}.", messages);
+
+    let sender = client.queue_sender(&queue_name);
+    let batch = messages
+        .iter()
+        .enumerate()
+        .map(|(index, text)| ServiceBusMessage::new(*text).with_property("index", index))
+        .collect::<Vec<_>>();
+    sender.send_messages(&batch).await?;
+
+    let receiver = client.queue_receiver(&queue_name);
+    while let Some(locked) = receiver.peek_lock(Some(Duration::from_secs(5))).await? {
+        let message = locked.message();
+        println!(
+            "received {:?} (sequence number {:?})",
+            std::str::from_utf8(message.body())?,
+            message.broker_properties().sequence_number
+        );
+        locked.complete().await?;
+    }
 
     Ok(())
 }
@@ -0,0 +1,369 @@
+//! The ATOM entries and feeds exchanged with the management endpoint, and the XML values of
+//! the entity descriptions they carry.
+use crate::Error;
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::time::Duration;
+use xml::escape::escape_str_pcdata;
+use xml::reader::{EventReader, XmlEvent};
+
+const ATOM_NAMESPACE: &str = "http://www.w3.org/2005/Atom";
+const SERVICE_BUS_NAMESPACE: &str =
+    "http://schemas.microsoft.com/netservices/2010/10/servicebus/connect";
+pub(crate) const INSTANCE_NAMESPACE: &str = "http://www.w3.org/2001/XMLSchema-instance";
+pub(crate) const SCHEMA_NAMESPACE: &str = "http://www.w3.org/2001/XMLSchema";
+
+/// A parsed XML element. Namespaces are dropped: elements and attributes go by their local
+/// names, which are unambiguous in the documents of the management endpoint.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Element {
+    pub(crate) name: String,
+    pub(crate) attributes: Vec<(String, String)>,
+    pub(crate) children: Vec<Element>,
+    pub(crate) text: String,
+}
+
+impl Element {
+    pub(crate) fn parse(xml: &[u8]) -> Result<Self, Error> {
+        let mut stack: Vec<Element> = Vec::new();
+        for event in EventReader::new(xml) {
+            match event.map_err(|error| Error::Xml(error.to_string()))? {
+                XmlEvent::StartElement {
+                    name, attributes, ..
+                } => stack.push(Element {
+                    name: name.local_name,
+                    attributes: attributes
+                        .into_iter()
+                        .map(|attribute| (attribute.name.local_name, attribute.value))
+                        .collect(),
+                    ..Default::default()
+                }),
+                XmlEvent::EndElement { .. } => {
+                    let element = stack
+                        .pop()
+                        .ok_or_else(|| Error::Xml("unbalanced element".to_owned()))?;
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(element),
+                        None => return Ok(element),
+                    }
+                }
+                XmlEvent::Characters(text) | XmlEvent::CData(text) => {
+                    if let Some(element) = stack.last_mut() {
+                        element.text.push_str(&text);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Err(Error::Xml("the document has no root element".to_owned()))
+    }
+
+    pub(crate) fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    pub(crate) fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    pub(crate) fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The value of the child element `name`, `None` when missing or nil.
+    pub(crate) fn value<T: XmlValue>(&self, name: &str) -> Result<Option<T>, Error> {
+        self.child(name)
+            .filter(|child| child.attribute("nil") != Some("true"))
+            .map(|child| T::from_xml(&child.text))
+            .transpose()
+    }
+}
+
+/// A value of an element of an entity description.
+pub(crate) trait XmlValue: Sized {
+    fn to_xml(&self) -> String;
+    fn from_xml(text: &str) -> Result<Self, Error>;
+}
+
+impl XmlValue for String {
+    fn to_xml(&self) -> String {
+        self.clone()
+    }
+
+    fn from_xml(text: &str) -> Result<Self, Error> {
+        Ok(text.to_owned())
+    }
+}
+
+macro_rules! parsed_xml_value {
+    ($($type:ty),*) => {
+        $(
+            impl XmlValue for $type {
+                fn to_xml(&self) -> String {
+                    self.to_string()
+                }
+
+                fn from_xml(text: &str) -> Result<Self, Error> {
+                    text.trim()
+                        .parse()
+                        .map_err(|_| Error::Xml(format!("invalid {}: {}", stringify!($type), text)))
+                }
+            }
+        )*
+    };
+}
+
+parsed_xml_value!(bool, u32, u64, i64);
+
+impl XmlValue for DateTime<Utc> {
+    fn to_xml(&self) -> String {
+        self.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+    }
+
+    fn from_xml(text: &str) -> Result<Self, Error> {
+        DateTime::parse_from_rfc3339(text.trim())
+            .map(|time| time.with_timezone(&Utc))
+            .map_err(|_| Error::Xml(format!("invalid time: {}", text)))
+    }
+}
+
+/// Durations are written in the ISO 8601 format, such as `P14D` or `PT1M30S`.
+impl XmlValue for Duration {
+    fn to_xml(&self) -> String {
+        let seconds = self.as_secs();
+        let (days, hours, minutes, seconds) = (
+            seconds / 86_400,
+            seconds % 86_400 / 3_600,
+            seconds % 3_600 / 60,
+            seconds % 60,
+        );
+        let nanos = self.subsec_nanos();
+        let mut text = String::from("P");
+        if days > 0 {
+            text.push_str(&format!("{}D", days));
+        }
+        if days == 0 || hours > 0 || minutes > 0 || seconds > 0 || nanos > 0 {
+            text.push('T');
+            if hours > 0 {
+                text.push_str(&format!("{}H", hours));
+            }
+            if minutes > 0 {
+                text.push_str(&format!("{}M", minutes));
+            }
+            if nanos > 0 {
+                let fraction = format!("{:09}", nanos);
+                text.push_str(&format!("{}.{}S", seconds, fraction.trim_end_matches('0')));
+            } else if seconds > 0 || text == "PT" {
+                text.push_str(&format!("{}S", seconds));
+            }
+        }
+        text
+    }
+
+    fn from_xml(text: &str) -> Result<Self, Error> {
+        let invalid = || Error::Xml(format!("invalid duration: {}", text));
+        let designators = text.trim().strip_prefix('P').ok_or_else(invalid)?;
+        let mut duration = Duration::default();
+        let mut in_time = false;
+        let mut number = String::new();
+        for c in designators.chars() {
+            let unit = match c {
+                '0'..='9' | '.' => {
+                    number.push(c);
+                    continue;
+                }
+                'T' if !in_time && number.is_empty() => {
+                    in_time = true;
+                    continue;
+                }
+                'S' if in_time => {
+                    let (seconds, fraction) = match number.split_once('.') {
+                        Some((seconds, fraction)) => (seconds, fraction),
+                        None => (number.as_str(), ""),
+                    };
+                    let nanos = format!("{:0<9}", fraction)
+                        .get(..9)
+                        .and_then(|nanos| nanos.parse().ok())
+                        .ok_or_else(invalid)?;
+                    duration += Duration::new(seconds.parse().map_err(|_| invalid())?, nanos);
+                    number.clear();
+                    continue;
+                }
+                'M' if in_time => 60,
+                'H' if in_time => 3_600,
+                'D' if !in_time => 86_400,
+                'W' if !in_time => 7 * 86_400,
+                'M' if !in_time => 30 * 86_400,
+                'Y' if !in_time => 365 * 86_400,
+                _ => return Err(invalid()),
+            };
+            let count: u64 = number.parse().map_err(|_| invalid())?;
+            duration += Duration::from_secs(count * unit);
+            number.clear();
+        }
+        if number.is_empty() {
+            Ok(duration)
+        } else {
+            Err(invalid())
+        }
+    }
+}
+
+/// Writes the elements of an entity description. The service expects them in the order of
+/// its schema, which is the order they are written in.
+#[derive(Debug, Default)]
+pub(crate) struct ContentWriter {
+    xml: String,
+}
+
+impl ContentWriter {
+    /// Writes the element `name` when `value` is set.
+    pub(crate) fn field<T: XmlValue>(&mut self, name: &str, value: &Option<T>) {
+        if let Some(value) = value {
+            self.element(name, "", &value.to_xml());
+        }
+    }
+
+    /// Writes the element `name`, with its `attributes` written as is and its `text` escaped.
+    pub(crate) fn element(&mut self, name: &str, attributes: &str, text: &str) {
+        self.xml.push_str(&format!(
+            "<{0}{1}>{2}</{0}>",
+            name,
+            attributes,
+            escape_str_pcdata(text)
+        ));
+    }
+
+    /// Writes the element `name` around the elements written by `content`.
+    pub(crate) fn nested(&mut self, name: &str, attributes: &str, content: impl FnOnce(&mut Self)) {
+        self.xml.push_str(&format!("<{}{}>", name, attributes));
+        content(self);
+        self.xml.push_str(&format!("</{}>", name));
+    }
+}
+
+/// A description of an entity, read from the content of an ATOM entry.
+pub(crate) trait AtomContent: Sized {
+    /// The element of the content, such as `QueueDescription`.
+    const ELEMENT: &'static str;
+
+    /// Reads the description of the entity `name`, the title of the entry.
+    fn read_content(name: &str, content: &Element) -> Result<Self, Error>;
+}
+
+/// An ATOM entry whose content is the `element` description written by `content`.
+pub(crate) fn entry(element: &str, content: impl FnOnce(&mut ContentWriter)) -> String {
+    let mut writer = ContentWriter::default();
+    writer.nested(
+        element,
+        &format!(
+            r#" xmlns="{}" xmlns:i="{}""#,
+            SERVICE_BUS_NAMESPACE, INSTANCE_NAMESPACE
+        ),
+        content,
+    );
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?><entry xmlns="{}"><content type="application/xml">{}</content></entry>"#,
+        ATOM_NAMESPACE, writer.xml
+    )
+}
+
+pub(crate) fn read_entry<T: AtomContent>(entry: &Element) -> Result<T, Error> {
+    let name = entry
+        .child("title")
+        .map(|title| title.text.trim())
+        .unwrap_or_default();
+    let content = entry
+        .child("content")
+        .and_then(|content| content.child(T::ELEMENT))
+        .ok_or_else(|| Error::Xml(format!("the entry of {} has no {}", name, T::ELEMENT)))?;
+    T::read_content(name, content)
+}
+
+/// Reads the entries of an ATOM feed; a feed without entries lists no entity.
+pub(crate) fn read_feed<T: AtomContent>(feed: &Element) -> Result<Vec<T>, Error> {
+    feed.children("entry").map(read_entry).collect()
+}
+
+/// The message counts of a queue, topic or subscription, in its `CountDetails`.
+#[derive(Debug, Default)]
+pub(crate) struct CountDetails {
+    pub(crate) active_message_count: i64,
+    pub(crate) dead_letter_message_count: i64,
+    pub(crate) scheduled_message_count: i64,
+    pub(crate) transfer_message_count: i64,
+    pub(crate) transfer_dead_letter_message_count: i64,
+}
+
+impl CountDetails {
+    pub(crate) fn read(content: &Element) -> Result<Self, Error> {
+        let details = match content.child("CountDetails") {
+            Some(details) => details,
+            None => return Ok(Self::default()),
+        };
+        let count = |name| details.value(name).map(Option::unwrap_or_default);
+        Ok(Self {
+            active_message_count: count("ActiveMessageCount")?,
+            dead_letter_message_count: count("DeadLetterMessageCount")?,
+            scheduled_message_count: count("ScheduledMessageCount")?,
+            transfer_message_count: count("TransferMessageCount")?,
+            transfer_dead_letter_message_count: count("TransferDeadLetterMessageCount")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations_round_trip_iso_8601() {
+        for (duration, text) in [
+            (Duration::from_secs(0), "PT0S"),
+            (Duration::from_secs(60), "PT1M"),
+            (Duration::from_secs(90), "PT1M30S"),
+            (Duration::from_secs(14 * 86_400), "P14D"),
+            (Duration::from_secs(86_400 + 3_600), "P1DT1H"),
+            (Duration::from_millis(1_500), "PT1.5S"),
+            (
+                Duration::new(10_675_199 * 86_400 + 2 * 3_600 + 48 * 60 + 5, 477_580_700),
+                "P10675199DT2H48M5.4775807S",
+            ),
+        ] {
+            assert_eq!(duration.to_xml(), text);
+            assert_eq!(Duration::from_xml(text).unwrap(), duration);
+        }
+        assert_eq!(
+            Duration::from_xml("P1W").unwrap(),
+            Duration::from_secs(7 * 86_400)
+        );
+        assert!(Duration::from_xml("1M").is_err());
+        assert!(Duration::from_xml("P1H").is_err());
+        assert!(Duration::from_xml("PT1").is_err());
+    }
+
+    #[test]
+    fn entries_are_parsed_without_namespaces() {
+        let feed = Element::parse(
+            br#"<feed xmlns="http://www.w3.org/2005/Atom">
+                <entry><title type="text">orders</title><content type="application/xml">
+                <QueueDescription xmlns="http://schemas.microsoft.com/netservices/2010/10/servicebus/connect" xmlns:i="http://www.w3.org/2001/XMLSchema-instance">
+                    <MaxDeliveryCount>5</MaxDeliveryCount>
+                    <ForwardTo i:nil="true"/>
+                </QueueDescription></content></entry></feed>"#,
+        )
+        .unwrap();
+        let entry = feed.child("entry").unwrap();
+        assert_eq!(entry.child("title").unwrap().text, "orders");
+        let content = entry
+            .child("content")
+            .and_then(|content| content.child("QueueDescription"))
+            .unwrap();
+        assert_eq!(content.value::<u32>("MaxDeliveryCount").unwrap(), Some(5));
+        assert_eq!(content.value::<String>("ForwardTo").unwrap(), None);
+        assert_eq!(content.value::<String>("UserMetadata").unwrap(), None);
+        assert!(Element::parse(b"<feed>").is_err());
+    }
+}
@@ -0,0 +1,480 @@
+use super::atom::{entry, read_entry, read_feed, AtomContent, Element};
+use super::event_hub::EventHubDescription;
+use super::queue::{QueueDescription, QueueRuntimeProperties};
+use super::rule::RuleDescription;
+use super::subscription::{SubscriptionDescription, SubscriptionRuntimeProperties};
+use super::topic::{TopicDescription, TopicRuntimeProperties};
+use crate::service_bus::{ServiceBusClientOptions, ServiceBusConnection};
+use crate::Error;
+use azure_core::HttpClient;
+use bytes::Bytes;
+use http::header::{CONTENT_TYPE, IF_MATCH};
+use http::{Method, StatusCode};
+use std::sync::Arc;
+use url::Url;
+
+const API_VERSION: &str = "2021-05";
+const ENTRY_CONTENT_TYPE: &str = "application/atom+xml;type=entry;charset=utf-8";
+/// The entities listed by each request of a listing.
+const PAGE_SIZE: usize = 100;
+/// The headers authorizing the forwarding of messages to another entity.
+const FORWARD_AUTHORIZATION: &str = "ServiceBusSupplementaryAuthorization";
+const FORWARD_DEAD_LETTER_AUTHORIZATION: &str = "ServiceBusDlqSupplementaryAuthorization";
+
+/// A client creating, reading, updating and deleting the queues, topics, subscriptions, rules
+/// and event hubs of a namespace, and reading the message counts of its entities.
+///
+/// The client uses the ATOM management endpoint of the namespace, authorized with a Shared
+/// Access Signature of a policy with the Manage right.
+#[derive(Debug, Clone)]
+pub struct ServiceBusAdministrationClient {
+    connection: Arc<ServiceBusConnection>,
+}
+
+impl ServiceBusAdministrationClient {
+    pub fn new<N, P, K>(
+        http_client: Arc<dyn HttpClient>,
+        namespace: N,
+        policy_name: P,
+        key: K,
+        options: ServiceBusClientOptions,
+    ) -> Result<Self, Error>
+    where
+        N: AsRef<str>,
+        P: Into<String>,
+        K: AsRef<str>,
+    {
+        Ok(Self {
+            connection: Arc::new(ServiceBusConnection::new(
+                http_client,
+                namespace.as_ref(),
+                policy_name.into(),
+                key.as_ref(),
+                options,
+            )?),
+        })
+    }
+
+    fn url(&self, path: &str) -> Result<Url, Error> {
+        let mut url = self.connection.url(path)?;
+        url.query_pairs_mut()
+            .append_pair("api-version", API_VERSION);
+        Ok(url)
+    }
+
+    /// Reads the entity at `path`. The service answers with an empty feed rather than an error
+    /// when the entity does not exist.
+    async fn get_entity<T: AtomContent>(&self, path: &str) -> Result<T, Error> {
+        let url = self.url(path)?;
+        let request = self
+            .connection
+            .request(Method::GET, &url)
+            .body(Bytes::new())?;
+        let response = self.connection.execute(request, &[StatusCode::OK]).await?;
+        let root = Element::parse(response.body())?;
+        if root.name == "entry" {
+            read_entry(&root)
+        } else {
+            Err(Error::EntityNotFound(path.to_owned()))
+        }
+    }
+
+    /// Creates the entity at `path`, or replaces it when `update`, and returns it as stored by
+    /// the service.
+    async fn put_entity<T: AtomContent>(
+        &self,
+        path: &str,
+        body: String,
+        update: bool,
+        headers: Vec<(&'static str, String)>,
+    ) -> Result<T, Error> {
+        let url = self.url(path)?;
+        let mut request = self
+            .connection
+            .request(Method::PUT, &url)
+            .header(CONTENT_TYPE, ENTRY_CONTENT_TYPE);
+        if update {
+            request = request.header(IF_MATCH, "*");
+        }
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        let response = self
+            .connection
+            .execute(
+                request.body(body.into())?,
+                &[StatusCode::OK, StatusCode::CREATED],
+            )
+            .await?;
+        read_entry(&Element::parse(response.body())?)
+    }
+
+    async fn delete_entity(&self, path: &str) -> Result<(), Error> {
+        let url = self.url(path)?;
+        let request = self
+            .connection
+            .request(Method::DELETE, &url)
+            .body(Bytes::new())?;
+        self.connection.execute(request, &[StatusCode::OK]).await?;
+        Ok(())
+    }
+
+    /// Lists the entities of the feed at `path`, page by page.
+    async fn list_entities<T: AtomContent>(&self, path: &str) -> Result<Vec<T>, Error> {
+        let mut entities = Vec::new();
+        loop {
+            let mut url = self.url(path)?;
+            url.query_pairs_mut()
+                .append_pair("$skip", &entities.len().to_string())
+                .append_pair("$top", &PAGE_SIZE.to_string());
+            let request = self
+                .connection
+                .request(Method::GET, &url)
+                .body(Bytes::new())?;
+            let response = self.connection.execute(request, &[StatusCode::OK]).await?;
+            let page = read_feed(&Element::parse(response.body())?)?;
+            let last = page.len() < PAGE_SIZE;
+            entities.extend(page);
+            if last {
+                return Ok(entities);
+            }
+        }
+    }
+
+    /// Replaces the names of the entities messages are forwarded to by their URLs, which the
+    /// service requires, and authorizes the forwarding to each of them.
+    fn forwarding(
+        &self,
+        forward_to: &mut Option<String>,
+        forward_dead_lettered_messages_to: &mut Option<String>,
+    ) -> Result<Vec<(&'static str, String)>, Error> {
+        let mut headers = Vec::new();
+        for (target, header) in [
+            (forward_to, FORWARD_AUTHORIZATION),
+            (
+                forward_dead_lettered_messages_to,
+                FORWARD_DEAD_LETTER_AUTHORIZATION,
+            ),
+        ] {
+            if let Some(target) = target {
+                let url = match Url::parse(target) {
+                    Ok(url) => url,
+                    Err(_) => self.connection.url(target)?,
+                };
+                headers.push((header, self.connection.signature(&url)));
+                *target = url.to_string();
+            }
+        }
+        Ok(headers)
+    }
+
+    async fn put_queue(
+        &self,
+        queue: &QueueDescription,
+        update: bool,
+    ) -> Result<QueueDescription, Error> {
+        let mut queue = queue.clone();
+        let headers = self.forwarding(
+            &mut queue.forward_to,
+            &mut queue.forward_dead_lettered_messages_to,
+        )?;
+        let body = entry(QueueDescription::ELEMENT, |writer| {
+            queue.write_content(writer)
+        });
+        self.put_entity(&queue.name, body, update, headers).await
+    }
+
+    pub async fn create_queue(&self, queue: &QueueDescription) -> Result<QueueDescription, Error> {
+        self.put_queue(queue, false).await
+    }
+
+    /// Replaces the settings of the queue: the settings left to `None` are reset to their
+    /// defaults, so the description is best read with [`get_queue`](Self::get_queue) first.
+    pub async fn update_queue(&self, queue: &QueueDescription) -> Result<QueueDescription, Error> {
+        self.put_queue(queue, true).await
+    }
+
+    pub async fn get_queue(&self, name: &str) -> Result<QueueDescription, Error> {
+        self.get_entity(name).await
+    }
+
+    pub async fn get_queue_runtime_properties(
+        &self,
+        name: &str,
+    ) -> Result<QueueRuntimeProperties, Error> {
+        self.get_entity(name).await
+    }
+
+    pub async fn queue_exists(&self, name: &str) -> Result<bool, Error> {
+        exists(self.get_queue(name).await)
+    }
+
+    pub async fn delete_queue(&self, name: &str) -> Result<(), Error> {
+        self.delete_entity(name).await
+    }
+
+    pub async fn list_queues(&self) -> Result<Vec<QueueDescription>, Error> {
+        self.list_entities("$Resources/Queues").await
+    }
+
+    pub async fn list_queues_runtime_properties(
+        &self,
+    ) -> Result<Vec<QueueRuntimeProperties>, Error> {
+        self.list_entities("$Resources/Queues").await
+    }
+
+    async fn put_topic(
+        &self,
+        topic: &TopicDescription,
+        update: bool,
+    ) -> Result<TopicDescription, Error> {
+        let body = entry(TopicDescription::ELEMENT, |writer| {
+            topic.write_content(writer)
+        });
+        self.put_entity(&topic.name, body, update, Vec::new()).await
+    }
+
+    pub async fn create_topic(&self, topic: &TopicDescription) -> Result<TopicDescription, Error> {
+        self.put_topic(topic, false).await
+    }
+
+    /// Replaces the settings of the topic: the settings left to `None` are reset to their
+    /// defaults, so the description is best read with [`get_topic`](Self::get_topic) first.
+    pub async fn update_topic(&self, topic: &TopicDescription) -> Result<TopicDescription, Error> {
+        self.put_topic(topic, true).await
+    }
+
+    pub async fn get_topic(&self, name: &str) -> Result<TopicDescription, Error> {
+        self.get_entity(name).await
+    }
+
+    pub async fn get_topic_runtime_properties(
+        &self,
+        name: &str,
+    ) -> Result<TopicRuntimeProperties, Error> {
+        self.get_entity(name).await
+    }
+
+    pub async fn topic_exists(&self, name: &str) -> Result<bool, Error> {
+        exists(self.get_topic(name).await)
+    }
+
+    /// Deletes the topic, with its subscriptions.
+    pub async fn delete_topic(&self, name: &str) -> Result<(), Error> {
+        self.delete_entity(name).await
+    }
+
+    pub async fn list_topics(&self) -> Result<Vec<TopicDescription>, Error> {
+        self.list_entities("$Resources/Topics").await
+    }
+
+    pub async fn list_topics_runtime_properties(
+        &self,
+    ) -> Result<Vec<TopicRuntimeProperties>, Error> {
+        self.list_entities("$Resources/Topics").await
+    }
+
+    async fn put_subscription(
+        &self,
+        topic: &str,
+        subscription: &SubscriptionDescription,
+        update: bool,
+    ) -> Result<SubscriptionDescription, Error> {
+        let mut subscription = subscription.clone();
+        let headers = self.forwarding(
+            &mut subscription.forward_to,
+            &mut subscription.forward_dead_lettered_messages_to,
+        )?;
+        let body = entry(SubscriptionDescription::ELEMENT, |writer| {
+            subscription.write_content(writer)
+        });
+        self.put_entity(
+            &subscription_path(topic, &subscription.name),
+            body,
+            update,
+            headers,
+        )
+        .await
+    }
+
+    pub async fn create_subscription(
+        &self,
+        topic: &str,
+        subscription: &SubscriptionDescription,
+    ) -> Result<SubscriptionDescription, Error> {
+        self.put_subscription(topic, subscription, false).await
+    }
+
+    /// Replaces the settings of the subscription: the settings left to `None` are reset to
+    /// their defaults, so the description is best read with
+    /// [`get_subscription`](Self::get_subscription) first.
+    pub async fn update_subscription(
+        &self,
+        topic: &str,
+        subscription: &SubscriptionDescription,
+    ) -> Result<SubscriptionDescription, Error> {
+        self.put_subscription(topic, subscription, true).await
+    }
+
+    pub async fn get_subscription(
+        &self,
+        topic: &str,
+        name: &str,
+    ) -> Result<SubscriptionDescription, Error> {
+        self.get_entity(&subscription_path(topic, name)).await
+    }
+
+    pub async fn get_subscription_runtime_properties(
+        &self,
+        topic: &str,
+        name: &str,
+    ) -> Result<SubscriptionRuntimeProperties, Error> {
+        self.get_entity(&subscription_path(topic, name)).await
+    }
+
+    pub async fn subscription_exists(&self, topic: &str, name: &str) -> Result<bool, Error> {
+        exists(self.get_subscription(topic, name).await)
+    }
+
+    pub async fn delete_subscription(&self, topic: &str, name: &str) -> Result<(), Error> {
+        self.delete_entity(&subscription_path(topic, name)).await
+    }
+
+    pub async fn list_subscriptions(
+        &self,
+        topic: &str,
+    ) -> Result<Vec<SubscriptionDescription>, Error> {
+        self.list_entities(&format!("{}/Subscriptions", topic))
+            .await
+    }
+
+    pub async fn list_subscriptions_runtime_properties(
+        &self,
+        topic: &str,
+    ) -> Result<Vec<SubscriptionRuntimeProperties>, Error> {
+        self.list_entities(&format!("{}/Subscriptions", topic))
+            .await
+    }
+
+    async fn put_rule(
+        &self,
+        topic: &str,
+        subscription: &str,
+        rule: &RuleDescription,
+        update: bool,
+    ) -> Result<RuleDescription, Error> {
+        let body = entry(RuleDescription::ELEMENT, |writer| {
+            rule.write_content(writer)
+        });
+        self.put_entity(
+            &rule_path(topic, subscription, &rule.name),
+            body,
+            update,
+            Vec::new(),
+        )
+        .await
+    }
+
+    pub async fn create_rule(
+        &self,
+        topic: &str,
+        subscription: &str,
+        rule: &RuleDescription,
+    ) -> Result<RuleDescription, Error> {
+        self.put_rule(topic, subscription, rule, false).await
+    }
+
+    pub async fn update_rule(
+        &self,
+        topic: &str,
+        subscription: &str,
+        rule: &RuleDescription,
+    ) -> Result<RuleDescription, Error> {
+        self.put_rule(topic, subscription, rule, true).await
+    }
+
+    pub async fn get_rule(
+        &self,
+        topic: &str,
+        subscription: &str,
+        name: &str,
+    ) -> Result<RuleDescription, Error> {
+        self.get_entity(&rule_path(topic, subscription, name)).await
+    }
+
+    /// Deletes the rule. A subscription without rules receives no message: deleting its
+    /// `$Default` rule, which matches all the messages, is how a subscription is narrowed to
+    /// the rules created for it.
+    pub async fn delete_rule(
+        &self,
+        topic: &str,
+        subscription: &str,
+        name: &str,
+    ) -> Result<(), Error> {
+        self.delete_entity(&rule_path(topic, subscription, name))
+            .await
+    }
+
+    pub async fn list_rules(
+        &self,
+        topic: &str,
+        subscription: &str,
+    ) -> Result<Vec<RuleDescription>, Error> {
+        self.list_entities(&format!("{}/Rules", subscription_path(topic, subscription)))
+            .await
+    }
+
+    async fn put_event_hub(
+        &self,
+        event_hub: &EventHubDescription,
+        update: bool,
+    ) -> Result<EventHubDescription, Error> {
+        let body = entry(EventHubDescription::ELEMENT, |writer| {
+            event_hub.write_content(writer)
+        });
+        self.put_entity(&event_hub.name, body, update, Vec::new())
+            .await
+    }
+
+    pub async fn create_event_hub(
+        &self,
+        event_hub: &EventHubDescription,
+    ) -> Result<EventHubDescription, Error> {
+        self.put_event_hub(event_hub, false).await
+    }
+
+    pub async fn update_event_hub(
+        &self,
+        event_hub: &EventHubDescription,
+    ) -> Result<EventHubDescription, Error> {
+        self.put_event_hub(event_hub, true).await
+    }
+
+    pub async fn get_event_hub(&self, name: &str) -> Result<EventHubDescription, Error> {
+        self.get_entity(name).await
+    }
+
+    pub async fn delete_event_hub(&self, name: &str) -> Result<(), Error> {
+        self.delete_entity(name).await
+    }
+
+    pub async fn list_event_hubs(&self) -> Result<Vec<EventHubDescription>, Error> {
+        self.list_entities("$Resources/EventHubs").await
+    }
+}
+
+fn subscription_path(topic: &str, subscription: &str) -> String {
+    format!("{}/Subscriptions/{}", topic, subscription)
+}
+
+fn rule_path(topic: &str, subscription: &str, rule: &str) -> String {
+    format!("{}/Rules/{}", subscription_path(topic, subscription), rule)
+}
+
+fn exists<T>(result: Result<T, Error>) -> Result<bool, Error> {
+    match result {
+        Ok(_) => Ok(true),
+        Err(Error::EntityNotFound(_)) => Ok(false),
+        Err(error) => Err(error),
+    }
+}
@@ -0,0 +1,56 @@
+use super::atom::{AtomContent, ContentWriter, Element};
+use super::status::EntityStatus;
+use crate::Error;
+use chrono::{DateTime, Utc};
+
+/// The settings of an event hub.
+///
+/// The settings left to `None` take the defaults of the service when the event hub is created.
+/// The partition count cannot be changed once the event hub is created.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EventHubDescription {
+    pub name: String,
+    pub message_retention_in_days: Option<u32>,
+    pub partition_count: Option<u32>,
+    pub status: Option<EntityStatus>,
+    /// The partitions of the event hub, set by the service.
+    pub partition_ids: Vec<String>,
+    /// Set by the service.
+    pub created_at: Option<DateTime<Utc>>,
+    /// Set by the service.
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl EventHubDescription {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn write_content(&self, writer: &mut ContentWriter) {
+        writer.field("MessageRetentionInDays", &self.message_retention_in_days);
+        writer.field("Status", &self.status);
+        writer.field("PartitionCount", &self.partition_count);
+    }
+}
+
+impl AtomContent for EventHubDescription {
+    const ELEMENT: &'static str = "EventHubDescription";
+
+    fn read_content(name: &str, content: &Element) -> Result<Self, Error> {
+        Ok(Self {
+            name: name.to_owned(),
+            message_retention_in_days: content.value("MessageRetentionInDays")?,
+            partition_count: content.value("PartitionCount")?,
+            status: content.value("Status")?,
+            partition_ids: content
+                .child("PartitionIds")
+                .map(|ids| ids.children.iter().map(|id| id.text.clone()).collect())
+                .unwrap_or_default(),
+            created_at: content.value("CreatedAt")?,
+            updated_at: content.value("UpdatedAt")?,
+        })
+    }
+}
@@ -0,0 +1,383 @@
+//! The administration of the entities of a Service Bus or Event Hubs namespace.
+//!
+//! A [`ServiceBusAdministrationClient`] creates, reads, updates and deletes the queues, topics,
+//! subscriptions and rules of a Service Bus namespace, and the event hubs of an Event Hubs
+//! namespace, through the ATOM management endpoint shared by both services. The entities are
+//! described by [`QueueDescription`]s, [`TopicDescription`]s, [`SubscriptionDescription`]s,
+//! [`RuleDescription`]s and [`EventHubDescription`]s; the runtime properties of the queues,
+//! topics and subscriptions give their sizes and message counts.
+mod atom;
+mod client;
+mod event_hub;
+mod queue;
+mod rule;
+mod status;
+mod subscription;
+mod topic;
+
+pub use client::ServiceBusAdministrationClient;
+pub use event_hub::EventHubDescription;
+pub use queue::{QueueDescription, QueueRuntimeProperties};
+pub use rule::{CorrelationFilter, RuleDescription, RuleFilter};
+pub use status::EntityStatus;
+pub use subscription::{SubscriptionDescription, SubscriptionRuntimeProperties};
+pub use topic::{TopicDescription, TopicRuntimeProperties};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service_bus::ServiceBusClientOptions;
+    use crate::Error;
+    use chrono::{TimeZone, Utc};
+    use mockito::{mock, Matcher};
+    use std::time::Duration;
+    use url::Url;
+
+    const DESCRIPTION_NAMESPACES: &str = r#"xmlns="http://schemas.microsoft.com/netservices/2010/10/servicebus/connect" xmlns:i="http://www.w3.org/2001/XMLSchema-instance""#;
+
+    fn client() -> ServiceBusAdministrationClient {
+        ServiceBusAdministrationClient::new(
+            azure_core::new_http_client(),
+            "namespace",
+            "policy",
+            "key",
+            ServiceBusClientOptions::default()
+                .endpoint(Url::parse(&mockito::server_url()).unwrap()),
+        )
+        .unwrap()
+    }
+
+    /// An ATOM entry as returned by the service, describing the entity `title`.
+    fn entry(title: &str, element: &str, content: &str) -> String {
+        format!(
+            r#"<entry xmlns="http://www.w3.org/2005/Atom"><id>https://namespace.servicebus.windows.net/{0}</id><title type="text">{0}</title><content type="application/xml"><{1} {2}>{3}</{1}></content></entry>"#,
+            title, element, DESCRIPTION_NAMESPACES, content
+        )
+    }
+
+    fn feed(entries: &[String]) -> String {
+        format!(
+            r#"<feed xmlns="http://www.w3.org/2005/Atom"><title type="text">Entities</title>{}</feed>"#,
+            entries.concat()
+        )
+    }
+
+    /// The body of a request creating or updating an entity.
+    fn request_body(element: &str, content: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="utf-8"?><entry xmlns="http://www.w3.org/2005/Atom"><content type="application/xml"><{0} {1}>{2}</{0}></content></entry>"#,
+            element, DESCRIPTION_NAMESPACES, content
+        )
+    }
+
+    fn api_version() -> Matcher {
+        Matcher::UrlEncoded("api-version".into(), "2021-05".into())
+    }
+
+    fn signature(path: &str) -> Matcher {
+        let resource: String = url::form_urlencoded::byte_serialize(
+            format!("{}{}", mockito::server_url(), path).as_bytes(),
+        )
+        .collect();
+        Matcher::Regex(format!(
+            "^SharedAccessSignature sr={}&sig=.+&se=[0-9]+&skn=policy$",
+            regex_escape(&resource)
+        ))
+    }
+
+    fn regex_escape(text: &str) -> String {
+        text.chars()
+            .flat_map(|c| match c {
+                '.' | '%' | '$' | '/' | '?' | '*' | '+' => vec!['\\', c],
+                c => vec![c],
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn queues_are_created_read_updated_and_listed() {
+        let archive = format!("{}/archive", mockito::server_url());
+        let stored = entry(
+            "orders",
+            "QueueDescription",
+            &format!(
+                "<LockDuration>PT1M</LockDuration><MaxSizeInMegabytes>1024</MaxSizeInMegabytes>\
+                 <MaxDeliveryCount>5</MaxDeliveryCount><Status>Active</Status>\
+                 <ForwardTo>{}</ForwardTo><CreatedAt>2021-03-02T10:20:30.1234567Z</CreatedAt>\
+                 <SizeInBytes>2048</SizeInBytes><MessageCount>7</MessageCount>\
+                 <CountDetails xmlns:d2p1=\"http://schemas.microsoft.com/netservices/2011/06/servicebus\">\
+                 <d2p1:ActiveMessageCount>4</d2p1:ActiveMessageCount>\
+                 <d2p1:DeadLetterMessageCount>2</d2p1:DeadLetterMessageCount>\
+                 <d2p1:ScheduledMessageCount>1</d2p1:ScheduledMessageCount>\
+                 <d2p1:TransferMessageCount>0</d2p1:TransferMessageCount>\
+                 <d2p1:TransferDeadLetterMessageCount>0</d2p1:TransferDeadLetterMessageCount>\
+                 </CountDetails><EnablePartitioning>false</EnablePartitioning>",
+                archive
+            ),
+        );
+        let created = mock("PUT", "/orders")
+            .match_query(api_version())
+            .match_header("authorization", signature("/orders"))
+            .match_header(
+                "content-type",
+                "application/atom+xml;type=entry;charset=utf-8",
+            )
+            .match_header("if-match", Matcher::Missing)
+            .match_header(
+                "servicebussupplementaryauthorization",
+                signature("/archive"),
+            )
+            .match_body(
+                request_body(
+                    "QueueDescription",
+                    &format!(
+                        "<LockDuration>PT1M</LockDuration><MaxDeliveryCount>5</MaxDeliveryCount>\
+                         <ForwardTo>{}</ForwardTo>",
+                        archive
+                    ),
+                )
+                .as_str(),
+            )
+            .with_status(201)
+            .with_body(&stored)
+            .create();
+        let updated = mock("PUT", "/orders")
+            .match_query(api_version())
+            .match_header("if-match", "*")
+            .match_body(Matcher::Regex(
+                "<MaxDeliveryCount>10</MaxDeliveryCount>".to_owned(),
+            ))
+            .with_status(200)
+            .with_body(&stored)
+            .create();
+        let read = mock("GET", "/orders")
+            .match_query(api_version())
+            .match_header("authorization", signature("/orders"))
+            .with_status(200)
+            .with_body(&stored)
+            .expect(2)
+            .create();
+        let missing = mock("GET", "/missing")
+            .match_query(api_version())
+            .with_status(200)
+            .with_body(feed(&[]))
+            .expect(2)
+            .create();
+        let listed = mock("GET", "/$Resources/Queues")
+            .match_query(Matcher::AllOf(vec![
+                api_version(),
+                Matcher::UrlEncoded("$skip".into(), "0".into()),
+                Matcher::UrlEncoded("$top".into(), "100".into()),
+            ]))
+            .with_status(200)
+            .with_body(feed(&[
+                stored.clone(),
+                entry(
+                    "jobs",
+                    "QueueDescription",
+                    "<RequiresSession>true</RequiresSession>",
+                ),
+            ]))
+            .create();
+        let deleted = mock("DELETE", "/orders")
+            .match_query(api_version())
+            .match_header("authorization", signature("/orders"))
+            .with_status(200)
+            .create();
+
+        let client = client();
+        let mut queue = QueueDescription::new("orders");
+        queue.lock_duration = Some(Duration::from_secs(60));
+        queue.max_delivery_count = Some(5);
+        queue.forward_to = Some("archive".to_owned());
+        let queue = client.create_queue(&queue).await.unwrap();
+        assert_eq!(queue.name, "orders");
+        assert_eq!(queue.max_size_in_megabytes, Some(1024));
+        assert_eq!(queue.status, Some(EntityStatus::Active));
+        assert_eq!(queue.forward_to.as_deref(), Some(archive.as_str()));
+        assert_eq!(queue.requires_session, None);
+
+        let mut queue = client.get_queue("orders").await.unwrap();
+        queue.forward_to = None;
+        queue.max_delivery_count = Some(10);
+        client.update_queue(&queue).await.unwrap();
+
+        let properties = client.get_queue_runtime_properties("orders").await.unwrap();
+        assert_eq!(properties.size_in_bytes, 2048);
+        assert_eq!(properties.total_message_count, 7);
+        assert_eq!(properties.active_message_count, 4);
+        assert_eq!(properties.dead_letter_message_count, 2);
+        assert_eq!(properties.scheduled_message_count, 1);
+        assert_eq!(
+            properties.created_at,
+            Some(Utc.timestamp_opt(1_614_680_430, 123_456_700).unwrap())
+        );
+        assert_eq!(properties.accessed_at, None);
+
+        let error = client.get_queue("missing").await.unwrap_err();
+        assert!(matches!(error, Error::EntityNotFound(name) if name == "missing"));
+        assert!(!client.queue_exists("missing").await.unwrap());
+
+        let queues = client.list_queues().await.unwrap();
+        assert_eq!(queues.len(), 2);
+        assert_eq!(queues[1].name, "jobs");
+        assert_eq!(queues[1].requires_session, Some(true));
+
+        client.delete_queue("orders").await.unwrap();
+
+        created.assert();
+        updated.assert();
+        read.assert();
+        missing.assert();
+        listed.assert();
+        deleted.assert();
+    }
+
+    #[tokio::test]
+    async fn subscriptions_rules_and_event_hubs_are_managed() {
+        let subscription = entry(
+            "audit",
+            "SubscriptionDescription",
+            "<LockDuration>PT30S</LockDuration><MaxDeliveryCount>3</MaxDeliveryCount>\
+             <MessageCount>5</MessageCount><CountDetails>\
+             <ActiveMessageCount>3</ActiveMessageCount>\
+             <DeadLetterMessageCount>2</DeadLetterMessageCount></CountDetails>\
+             <AutoDeleteOnIdle>P10675199DT2H48M5.4775807S</AutoDeleteOnIdle>",
+        );
+        let subscription_created = mock("PUT", "/events/Subscriptions/audit")
+            .match_query(api_version())
+            .match_header("authorization", signature("/events/Subscriptions/audit"))
+            .match_body(
+                request_body(
+                    "SubscriptionDescription",
+                    "<LockDuration>PT30S</LockDuration><MaxDeliveryCount>3</MaxDeliveryCount>",
+                )
+                .as_str(),
+            )
+            .with_status(201)
+            .with_body(&subscription)
+            .create();
+        let subscription_read = mock("GET", "/events/Subscriptions/audit")
+            .match_query(api_version())
+            .with_status(200)
+            .with_body(&subscription)
+            .create();
+
+        let correlation_rule = "<Filter i:type=\"CorrelationFilter\">\
+             <CorrelationId>order-1</CorrelationId><Label>created</Label>\
+             <Properties><KeyValueOfstringanyType><Key>region</Key>\
+             <Value i:type=\"d6p1:string\" xmlns:d6p1=\"http://www.w3.org/2001/XMLSchema\">emea</Value>\
+             </KeyValueOfstringanyType></Properties></Filter>\
+             <Action i:type=\"EmptyRuleAction\"></Action><Name>emea</Name>";
+        let rule_created = mock("PUT", "/events/Subscriptions/audit/Rules/emea")
+            .match_query(api_version())
+            .match_header("if-match", Matcher::Missing)
+            .match_body(request_body("RuleDescription", correlation_rule).as_str())
+            .with_status(201)
+            .with_body(entry("emea", "RuleDescription", correlation_rule))
+            .create();
+        let rules_listed = mock("GET", "/events/Subscriptions/audit/Rules")
+            .match_query(Matcher::AllOf(vec![
+                api_version(),
+                Matcher::UrlEncoded("$skip".into(), "0".into()),
+                Matcher::UrlEncoded("$top".into(), "100".into()),
+            ]))
+            .with_status(200)
+            .with_body(feed(&[
+                entry(
+                    "$Default",
+                    "RuleDescription",
+                    "<Filter i:type=\"TrueFilter\"><SqlExpression>1=1</SqlExpression>\
+                     <CompatibilityLevel>20</CompatibilityLevel></Filter>\
+                     <Action i:type=\"EmptyRuleAction\"/><Name>$Default</Name>",
+                ),
+                entry(
+                    "urgent",
+                    "RuleDescription",
+                    "<Filter i:type=\"SqlFilter\"><SqlExpression>priority &gt; 5</SqlExpression>\
+                     <CompatibilityLevel>20</CompatibilityLevel></Filter>\
+                     <Action i:type=\"SqlRuleAction\"><SqlExpression>SET urgent = TRUE</SqlExpression>\
+                     <CompatibilityLevel>20</CompatibilityLevel></Action><Name>urgent</Name>",
+                ),
+            ]))
+            .create();
+        let default_rule_deleted = mock("DELETE", "/events/Subscriptions/audit/Rules/$Default")
+            .match_query(api_version())
+            .with_status(200)
+            .create();
+        let event_hub_read = mock("GET", "/telemetry")
+            .match_query(api_version())
+            .with_status(200)
+            .with_body(entry(
+                "telemetry",
+                "EventHubDescription",
+                "<MessageRetentionInDays>7</MessageRetentionInDays><Status>Active</Status>\
+                 <PartitionCount>2</PartitionCount>\
+                 <PartitionIds xmlns:d2p1=\"http://schemas.microsoft.com/2003/10/Serialization/Arrays\">\
+                 <d2p1:string>0</d2p1:string><d2p1:string>1</d2p1:string></PartitionIds>",
+            ))
+            .create();
+
+        let client = client();
+        let mut description = SubscriptionDescription::new("audit");
+        description.lock_duration = Some(Duration::from_secs(30));
+        description.max_delivery_count = Some(3);
+        let created = client
+            .create_subscription("events", &description)
+            .await
+            .unwrap();
+        assert_eq!(created.name, "audit");
+        assert_eq!(created.lock_duration, Some(Duration::from_secs(30)));
+        assert_eq!(
+            created.auto_delete_on_idle,
+            Some(Duration::new(922_337_203_685, 477_580_700))
+        );
+
+        let properties = client
+            .get_subscription_runtime_properties("events", "audit")
+            .await
+            .unwrap();
+        assert_eq!(properties.total_message_count, 5);
+        assert_eq!(properties.active_message_count, 3);
+        assert_eq!(properties.dead_letter_message_count, 2);
+        assert_eq!(properties.transfer_message_count, 0);
+
+        let filter = CorrelationFilter {
+            correlation_id: Some("order-1".to_owned()),
+            label: Some("created".to_owned()),
+            ..Default::default()
+        }
+        .with_property("region", "emea");
+        let rule = RuleDescription::new("emea", RuleFilter::Correlation(filter));
+        assert_eq!(
+            client.create_rule("events", "audit", &rule).await.unwrap(),
+            rule
+        );
+
+        let rules = client.list_rules("events", "audit").await.unwrap();
+        assert_eq!(
+            rules,
+            vec![
+                RuleDescription::new("$Default", RuleFilter::True),
+                RuleDescription::new("urgent", RuleFilter::Sql("priority > 5".to_owned()))
+                    .with_action("SET urgent = TRUE"),
+            ]
+        );
+        client
+            .delete_rule("events", "audit", "$Default")
+            .await
+            .unwrap();
+
+        let event_hub = client.get_event_hub("telemetry").await.unwrap();
+        assert_eq!(event_hub.message_retention_in_days, Some(7));
+        assert_eq!(event_hub.partition_count, Some(2));
+        assert_eq!(event_hub.partition_ids, vec!["0", "1"]);
+
+        subscription_created.assert();
+        subscription_read.assert();
+        rule_created.assert();
+        rules_listed.assert();
+        default_rule_deleted.assert();
+        event_hub_read.assert();
+    }
+}
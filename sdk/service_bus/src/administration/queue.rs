@@ -0,0 +1,140 @@
+use super::atom::{AtomContent, ContentWriter, CountDetails, Element};
+use super::status::EntityStatus;
+use crate::Error;
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+/// The settings of a queue.
+///
+/// The settings left to `None` take the defaults of the service when the queue is created.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueueDescription {
+    pub name: String,
+    /// How long a received message stays locked, at most 5 minutes.
+    pub lock_duration: Option<Duration>,
+    pub max_size_in_megabytes: Option<u64>,
+    pub requires_duplicate_detection: Option<bool>,
+    pub requires_session: Option<bool>,
+    pub default_message_time_to_live: Option<Duration>,
+    pub dead_lettering_on_message_expiration: Option<bool>,
+    pub duplicate_detection_history_time_window: Option<Duration>,
+    /// How many times a message is delivered before being dead-lettered.
+    pub max_delivery_count: Option<u32>,
+    pub enable_batched_operations: Option<bool>,
+    pub status: Option<EntityStatus>,
+    /// The queue or topic the messages are forwarded to, by name or URL.
+    pub forward_to: Option<String>,
+    pub user_metadata: Option<String>,
+    /// How long the queue stays idle before being deleted.
+    pub auto_delete_on_idle: Option<Duration>,
+    pub enable_partitioning: Option<bool>,
+    /// The queue or topic the dead-lettered messages are forwarded to, by name or URL.
+    pub forward_dead_lettered_messages_to: Option<String>,
+}
+
+impl QueueDescription {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn write_content(&self, writer: &mut ContentWriter) {
+        writer.field("LockDuration", &self.lock_duration);
+        writer.field("MaxSizeInMegabytes", &self.max_size_in_megabytes);
+        writer.field(
+            "RequiresDuplicateDetection",
+            &self.requires_duplicate_detection,
+        );
+        writer.field("RequiresSession", &self.requires_session);
+        writer.field(
+            "DefaultMessageTimeToLive",
+            &self.default_message_time_to_live,
+        );
+        writer.field(
+            "DeadLetteringOnMessageExpiration",
+            &self.dead_lettering_on_message_expiration,
+        );
+        writer.field(
+            "DuplicateDetectionHistoryTimeWindow",
+            &self.duplicate_detection_history_time_window,
+        );
+        writer.field("MaxDeliveryCount", &self.max_delivery_count);
+        writer.field("EnableBatchedOperations", &self.enable_batched_operations);
+        writer.field("Status", &self.status);
+        writer.field("ForwardTo", &self.forward_to);
+        writer.field("UserMetadata", &self.user_metadata);
+        writer.field("AutoDeleteOnIdle", &self.auto_delete_on_idle);
+        writer.field("EnablePartitioning", &self.enable_partitioning);
+        writer.field(
+            "ForwardDeadLetteredMessagesTo",
+            &self.forward_dead_lettered_messages_to,
+        );
+    }
+}
+
+impl AtomContent for QueueDescription {
+    const ELEMENT: &'static str = "QueueDescription";
+
+    fn read_content(name: &str, content: &Element) -> Result<Self, Error> {
+        Ok(Self {
+            name: name.to_owned(),
+            lock_duration: content.value("LockDuration")?,
+            max_size_in_megabytes: content.value("MaxSizeInMegabytes")?,
+            requires_duplicate_detection: content.value("RequiresDuplicateDetection")?,
+            requires_session: content.value("RequiresSession")?,
+            default_message_time_to_live: content.value("DefaultMessageTimeToLive")?,
+            dead_lettering_on_message_expiration: content
+                .value("DeadLetteringOnMessageExpiration")?,
+            duplicate_detection_history_time_window: content
+                .value("DuplicateDetectionHistoryTimeWindow")?,
+            max_delivery_count: content.value("MaxDeliveryCount")?,
+            enable_batched_operations: content.value("EnableBatchedOperations")?,
+            status: content.value("Status")?,
+            forward_to: content.value("ForwardTo")?,
+            user_metadata: content.value("UserMetadata")?,
+            auto_delete_on_idle: content.value("AutoDeleteOnIdle")?,
+            enable_partitioning: content.value("EnablePartitioning")?,
+            forward_dead_lettered_messages_to: content.value("ForwardDeadLetteredMessagesTo")?,
+        })
+    }
+}
+
+/// The size and message counts of a queue.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueueRuntimeProperties {
+    pub name: String,
+    pub size_in_bytes: i64,
+    /// The messages of the queue and of its dead-letter queue.
+    pub total_message_count: i64,
+    pub active_message_count: i64,
+    pub dead_letter_message_count: i64,
+    pub scheduled_message_count: i64,
+    pub transfer_message_count: i64,
+    pub transfer_dead_letter_message_count: i64,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub accessed_at: Option<DateTime<Utc>>,
+}
+
+impl AtomContent for QueueRuntimeProperties {
+    const ELEMENT: &'static str = "QueueDescription";
+
+    fn read_content(name: &str, content: &Element) -> Result<Self, Error> {
+        let counts = CountDetails::read(content)?;
+        Ok(Self {
+            name: name.to_owned(),
+            size_in_bytes: content.value("SizeInBytes")?.unwrap_or_default(),
+            total_message_count: content.value("MessageCount")?.unwrap_or_default(),
+            active_message_count: counts.active_message_count,
+            dead_letter_message_count: counts.dead_letter_message_count,
+            scheduled_message_count: counts.scheduled_message_count,
+            transfer_message_count: counts.transfer_message_count,
+            transfer_dead_letter_message_count: counts.transfer_dead_letter_message_count,
+            created_at: content.value("CreatedAt")?,
+            updated_at: content.value("UpdatedAt")?,
+            accessed_at: content.value("AccessedAt")?,
+        })
+    }
+}
@@ -0,0 +1,200 @@
+use super::atom::{AtomContent, ContentWriter, Element, SCHEMA_NAMESPACE};
+use crate::Error;
+use std::collections::BTreeMap;
+
+/// The compatibility level of the SQL expressions of the filters and actions.
+const SQL_COMPATIBILITY_LEVEL: &str = "20";
+
+/// A rule of a subscription: the messages of the topic matching its filter are copied to the
+/// subscription, after being modified by its action.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleDescription {
+    pub name: String,
+    pub filter: RuleFilter,
+    /// The SQL action modifying the properties of the matching messages, such as
+    /// `SET priority = 'high'`.
+    pub action: Option<String>,
+}
+
+impl RuleDescription {
+    pub fn new(name: impl Into<String>, filter: RuleFilter) -> Self {
+        Self {
+            name: name.into(),
+            filter,
+            action: None,
+        }
+    }
+
+    pub fn with_action(self, action: impl Into<String>) -> Self {
+        Self {
+            action: Some(action.into()),
+            ..self
+        }
+    }
+
+    pub(crate) fn write_content(&self, writer: &mut ContentWriter) {
+        writer.nested(
+            "Filter",
+            &format!(r#" i:type="{}""#, self.filter.type_name()),
+            |writer| match &self.filter {
+                RuleFilter::Sql(expression) => write_sql_expression(writer, expression),
+                RuleFilter::Correlation(filter) => filter.write_content(writer),
+                RuleFilter::True => write_sql_expression(writer, "1=1"),
+                RuleFilter::False => write_sql_expression(writer, "1=0"),
+            },
+        );
+        match &self.action {
+            Some(action) => writer.nested("Action", r#" i:type="SqlRuleAction""#, |writer| {
+                write_sql_expression(writer, action)
+            }),
+            None => writer.nested("Action", r#" i:type="EmptyRuleAction""#, |_| {}),
+        }
+        writer.element("Name", "", &self.name);
+    }
+}
+
+fn write_sql_expression(writer: &mut ContentWriter, expression: &str) {
+    writer.element("SqlExpression", "", expression);
+    writer.element("CompatibilityLevel", "", SQL_COMPATIBILITY_LEVEL);
+}
+
+/// The type of an element, given by its `i:type` attribute.
+fn element_type(element: &Element) -> Option<&str> {
+    element
+        .attribute("type")
+        .map(|element_type| element_type.rsplit(':').next().unwrap_or(element_type))
+}
+
+impl AtomContent for RuleDescription {
+    const ELEMENT: &'static str = "RuleDescription";
+
+    fn read_content(name: &str, content: &Element) -> Result<Self, Error> {
+        let filter = content
+            .child("Filter")
+            .ok_or_else(|| Error::Xml(format!("the rule {} has no filter", name)))?;
+        let sql_expression = |element: &Element| -> Result<String, Error> {
+            Ok(element.value("SqlExpression")?.unwrap_or_default())
+        };
+        let filter = match element_type(filter) {
+            Some("SqlFilter") => RuleFilter::Sql(sql_expression(filter)?),
+            Some("CorrelationFilter") => {
+                RuleFilter::Correlation(CorrelationFilter::read_content(filter)?)
+            }
+            Some("TrueFilter") => RuleFilter::True,
+            Some("FalseFilter") => RuleFilter::False,
+            filter_type => {
+                return Err(Error::Xml(format!(
+                    "unsupported filter type: {}",
+                    filter_type.unwrap_or_default()
+                )))
+            }
+        };
+        let action = match content.child("Action") {
+            Some(action) if element_type(action) == Some("SqlRuleAction") => {
+                Some(sql_expression(action)?)
+            }
+            _ => None,
+        };
+        Ok(Self {
+            name: name.to_owned(),
+            filter,
+            action,
+        })
+    }
+}
+
+/// The filter of a rule.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleFilter {
+    /// A SQL expression over the properties of the messages, such as `region = 'emea'`.
+    Sql(String),
+    /// Matches the messages by equality with some of their properties, which is cheaper to
+    /// evaluate than a SQL expression.
+    Correlation(CorrelationFilter),
+    /// Matches all the messages.
+    True,
+    /// Matches no message.
+    False,
+}
+
+impl RuleFilter {
+    fn type_name(&self) -> &'static str {
+        match self {
+            RuleFilter::Sql(_) => "SqlFilter",
+            RuleFilter::Correlation(_) => "CorrelationFilter",
+            RuleFilter::True => "TrueFilter",
+            RuleFilter::False => "FalseFilter",
+        }
+    }
+}
+
+/// Matches the messages whose properties are equal to all the properties set in the filter.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CorrelationFilter {
+    pub correlation_id: Option<String>,
+    pub message_id: Option<String>,
+    pub to: Option<String>,
+    pub reply_to: Option<String>,
+    pub label: Option<String>,
+    pub session_id: Option<String>,
+    pub reply_to_session_id: Option<String>,
+    pub content_type: Option<String>,
+    /// The application properties of the messages. The values of other types than strings
+    /// are read as their text.
+    pub properties: BTreeMap<String, String>,
+}
+
+impl CorrelationFilter {
+    pub fn with_property(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.properties.insert(name.into(), value.into());
+        self
+    }
+
+    fn write_content(&self, writer: &mut ContentWriter) {
+        writer.field("CorrelationId", &self.correlation_id);
+        writer.field("MessageId", &self.message_id);
+        writer.field("To", &self.to);
+        writer.field("ReplyTo", &self.reply_to);
+        writer.field("Label", &self.label);
+        writer.field("SessionId", &self.session_id);
+        writer.field("ReplyToSessionId", &self.reply_to_session_id);
+        writer.field("ContentType", &self.content_type);
+        if !self.properties.is_empty() {
+            writer.nested("Properties", "", |writer| {
+                for (key, value) in &self.properties {
+                    writer.nested("KeyValueOfstringanyType", "", |writer| {
+                        writer.element("Key", "", key);
+                        writer.element(
+                            "Value",
+                            &format!(r#" i:type="d6p1:string" xmlns:d6p1="{}""#, SCHEMA_NAMESPACE),
+                            value,
+                        );
+                    });
+                }
+            });
+        }
+    }
+
+    fn read_content(filter: &Element) -> Result<Self, Error> {
+        let mut properties = BTreeMap::new();
+        if let Some(elements) = filter.child("Properties") {
+            for property in elements.children("KeyValueOfstringanyType") {
+                if let (Some(key), Some(value)) = (property.value("Key")?, property.value("Value")?)
+                {
+                    properties.insert(key, value);
+                }
+            }
+        }
+        Ok(Self {
+            correlation_id: filter.value("CorrelationId")?,
+            message_id: filter.value("MessageId")?,
+            to: filter.value("To")?,
+            reply_to: filter.value("ReplyTo")?,
+            label: filter.value("Label")?,
+            session_id: filter.value("SessionId")?,
+            reply_to_session_id: filter.value("ReplyToSessionId")?,
+            content_type: filter.value("ContentType")?,
+            properties,
+        })
+    }
+}
@@ -0,0 +1,52 @@
+use super::atom::XmlValue;
+use crate::Error;
+
+/// The status of an entity, which can be disabled to stop sending or receiving messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntityStatus {
+    Active,
+    Disabled,
+    SendDisabled,
+    ReceiveDisabled,
+    Creating,
+    Deleting,
+    Renaming,
+    Restoring,
+    Unknown,
+}
+
+impl EntityStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntityStatus::Active => "Active",
+            EntityStatus::Disabled => "Disabled",
+            EntityStatus::SendDisabled => "SendDisabled",
+            EntityStatus::ReceiveDisabled => "ReceiveDisabled",
+            EntityStatus::Creating => "Creating",
+            EntityStatus::Deleting => "Deleting",
+            EntityStatus::Renaming => "Renaming",
+            EntityStatus::Restoring => "Restoring",
+            EntityStatus::Unknown => "Unknown",
+        }
+    }
+}
+
+impl XmlValue for EntityStatus {
+    fn to_xml(&self) -> String {
+        self.as_str().to_owned()
+    }
+
+    fn from_xml(text: &str) -> Result<Self, Error> {
+        Ok(match text.trim() {
+            "Active" => EntityStatus::Active,
+            "Disabled" => EntityStatus::Disabled,
+            "SendDisabled" => EntityStatus::SendDisabled,
+            "ReceiveDisabled" => EntityStatus::ReceiveDisabled,
+            "Creating" => EntityStatus::Creating,
+            "Deleting" => EntityStatus::Deleting,
+            "Renaming" => EntityStatus::Renaming,
+            "Restoring" => EntityStatus::Restoring,
+            _ => EntityStatus::Unknown,
+        })
+    }
+}
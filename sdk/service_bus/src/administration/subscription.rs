@@ -0,0 +1,127 @@
+use super::atom::{AtomContent, ContentWriter, CountDetails, Element};
+use super::status::EntityStatus;
+use crate::Error;
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+/// The settings of a subscription to a topic.
+///
+/// The settings left to `None` take the defaults of the service when the subscription is
+/// created. A new subscription receives all the messages of the topic until its default rule
+/// is replaced.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SubscriptionDescription {
+    pub name: String,
+    /// How long a received message stays locked, at most 5 minutes.
+    pub lock_duration: Option<Duration>,
+    pub requires_session: Option<bool>,
+    pub default_message_time_to_live: Option<Duration>,
+    pub dead_lettering_on_message_expiration: Option<bool>,
+    /// Whether the messages whose rules fail to be evaluated are dead-lettered.
+    pub dead_lettering_on_filter_evaluation_exceptions: Option<bool>,
+    /// How many times a message is delivered before being dead-lettered.
+    pub max_delivery_count: Option<u32>,
+    pub enable_batched_operations: Option<bool>,
+    pub status: Option<EntityStatus>,
+    /// The queue or topic the messages are forwarded to, by name or URL.
+    pub forward_to: Option<String>,
+    pub user_metadata: Option<String>,
+    /// The queue or topic the dead-lettered messages are forwarded to, by name or URL.
+    pub forward_dead_lettered_messages_to: Option<String>,
+    /// How long the subscription stays idle before being deleted.
+    pub auto_delete_on_idle: Option<Duration>,
+}
+
+impl SubscriptionDescription {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn write_content(&self, writer: &mut ContentWriter) {
+        writer.field("LockDuration", &self.lock_duration);
+        writer.field("RequiresSession", &self.requires_session);
+        writer.field(
+            "DefaultMessageTimeToLive",
+            &self.default_message_time_to_live,
+        );
+        writer.field(
+            "DeadLetteringOnMessageExpiration",
+            &self.dead_lettering_on_message_expiration,
+        );
+        writer.field(
+            "DeadLetteringOnFilterEvaluationExceptions",
+            &self.dead_lettering_on_filter_evaluation_exceptions,
+        );
+        writer.field("MaxDeliveryCount", &self.max_delivery_count);
+        writer.field("EnableBatchedOperations", &self.enable_batched_operations);
+        writer.field("Status", &self.status);
+        writer.field("ForwardTo", &self.forward_to);
+        writer.field("UserMetadata", &self.user_metadata);
+        writer.field(
+            "ForwardDeadLetteredMessagesTo",
+            &self.forward_dead_lettered_messages_to,
+        );
+        writer.field("AutoDeleteOnIdle", &self.auto_delete_on_idle);
+    }
+}
+
+impl AtomContent for SubscriptionDescription {
+    const ELEMENT: &'static str = "SubscriptionDescription";
+
+    fn read_content(name: &str, content: &Element) -> Result<Self, Error> {
+        Ok(Self {
+            name: name.to_owned(),
+            lock_duration: content.value("LockDuration")?,
+            requires_session: content.value("RequiresSession")?,
+            default_message_time_to_live: content.value("DefaultMessageTimeToLive")?,
+            dead_lettering_on_message_expiration: content
+                .value("DeadLetteringOnMessageExpiration")?,
+            dead_lettering_on_filter_evaluation_exceptions: content
+                .value("DeadLetteringOnFilterEvaluationExceptions")?,
+            max_delivery_count: content.value("MaxDeliveryCount")?,
+            enable_batched_operations: content.value("EnableBatchedOperations")?,
+            status: content.value("Status")?,
+            forward_to: content.value("ForwardTo")?,
+            user_metadata: content.value("UserMetadata")?,
+            forward_dead_lettered_messages_to: content.value("ForwardDeadLetteredMessagesTo")?,
+            auto_delete_on_idle: content.value("AutoDeleteOnIdle")?,
+        })
+    }
+}
+
+/// The message counts of a subscription.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubscriptionRuntimeProperties {
+    pub name: String,
+    /// The messages of the subscription and of its dead-letter queue.
+    pub total_message_count: i64,
+    pub active_message_count: i64,
+    pub dead_letter_message_count: i64,
+    pub transfer_message_count: i64,
+    pub transfer_dead_letter_message_count: i64,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub accessed_at: Option<DateTime<Utc>>,
+}
+
+impl AtomContent for SubscriptionRuntimeProperties {
+    const ELEMENT: &'static str = "SubscriptionDescription";
+
+    fn read_content(name: &str, content: &Element) -> Result<Self, Error> {
+        let counts = CountDetails::read(content)?;
+        Ok(Self {
+            name: name.to_owned(),
+            total_message_count: content.value("MessageCount")?.unwrap_or_default(),
+            active_message_count: counts.active_message_count,
+            dead_letter_message_count: counts.dead_letter_message_count,
+            transfer_message_count: counts.transfer_message_count,
+            transfer_dead_letter_message_count: counts.transfer_dead_letter_message_count,
+            created_at: content.value("CreatedAt")?,
+            updated_at: content.value("UpdatedAt")?,
+            accessed_at: content.value("AccessedAt")?,
+        })
+    }
+}
@@ -0,0 +1,105 @@
+use super::atom::{AtomContent, ContentWriter, CountDetails, Element};
+use super::status::EntityStatus;
+use crate::Error;
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+/// The settings of a topic.
+///
+/// The settings left to `None` take the defaults of the service when the topic is created.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TopicDescription {
+    pub name: String,
+    pub default_message_time_to_live: Option<Duration>,
+    pub max_size_in_megabytes: Option<u64>,
+    pub requires_duplicate_detection: Option<bool>,
+    pub duplicate_detection_history_time_window: Option<Duration>,
+    pub enable_batched_operations: Option<bool>,
+    pub status: Option<EntityStatus>,
+    /// Whether the messages are delivered to the subscriptions in the order they were sent.
+    pub support_ordering: Option<bool>,
+    /// How long the topic stays idle before being deleted.
+    pub auto_delete_on_idle: Option<Duration>,
+    pub enable_partitioning: Option<bool>,
+    pub user_metadata: Option<String>,
+}
+
+impl TopicDescription {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn write_content(&self, writer: &mut ContentWriter) {
+        writer.field(
+            "DefaultMessageTimeToLive",
+            &self.default_message_time_to_live,
+        );
+        writer.field("MaxSizeInMegabytes", &self.max_size_in_megabytes);
+        writer.field(
+            "RequiresDuplicateDetection",
+            &self.requires_duplicate_detection,
+        );
+        writer.field(
+            "DuplicateDetectionHistoryTimeWindow",
+            &self.duplicate_detection_history_time_window,
+        );
+        writer.field("EnableBatchedOperations", &self.enable_batched_operations);
+        writer.field("Status", &self.status);
+        writer.field("SupportOrdering", &self.support_ordering);
+        writer.field("AutoDeleteOnIdle", &self.auto_delete_on_idle);
+        writer.field("EnablePartitioning", &self.enable_partitioning);
+        writer.field("UserMetadata", &self.user_metadata);
+    }
+}
+
+impl AtomContent for TopicDescription {
+    const ELEMENT: &'static str = "TopicDescription";
+
+    fn read_content(name: &str, content: &Element) -> Result<Self, Error> {
+        Ok(Self {
+            name: name.to_owned(),
+            default_message_time_to_live: content.value("DefaultMessageTimeToLive")?,
+            max_size_in_megabytes: content.value("MaxSizeInMegabytes")?,
+            requires_duplicate_detection: content.value("RequiresDuplicateDetection")?,
+            duplicate_detection_history_time_window: content
+                .value("DuplicateDetectionHistoryTimeWindow")?,
+            enable_batched_operations: content.value("EnableBatchedOperations")?,
+            status: content.value("Status")?,
+            support_ordering: content.value("SupportOrdering")?,
+            auto_delete_on_idle: content.value("AutoDeleteOnIdle")?,
+            enable_partitioning: content.value("EnablePartitioning")?,
+            user_metadata: content.value("UserMetadata")?,
+        })
+    }
+}
+
+/// The size, message counts and subscription count of a topic.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopicRuntimeProperties {
+    pub name: String,
+    pub size_in_bytes: i64,
+    pub subscription_count: i64,
+    pub scheduled_message_count: i64,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub accessed_at: Option<DateTime<Utc>>,
+}
+
+impl AtomContent for TopicRuntimeProperties {
+    const ELEMENT: &'static str = "TopicDescription";
+
+    fn read_content(name: &str, content: &Element) -> Result<Self, Error> {
+        Ok(Self {
+            name: name.to_owned(),
+            size_in_bytes: content.value("SizeInBytes")?.unwrap_or_default(),
+            subscription_count: content.value("SubscriptionCount")?.unwrap_or_default(),
+            scheduled_message_count: CountDetails::read(content)?.scheduled_message_count,
+            created_at: content.value("CreatedAt")?,
+            updated_at: content.value("UpdatedAt")?,
+            accessed_at: content.value("AccessedAt")?,
+        })
+    }
+}
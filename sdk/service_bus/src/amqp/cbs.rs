@@ -0,0 +1,36 @@
+use super::codec::Value;
+use super::message::Message;
+use super::request_response::{response_status, RequestResponseLink};
+use crate::Error;
+use chrono::{DateTime, Utc};
+
+/// The address of the claims-based security node.
+pub(crate) const CBS_NODE: &str = "$cbs";
+const SAS_TOKEN_TYPE: &str = "servicebus.windows.net:sastoken";
+
+/// Authorizes the connection of `link` to access `audience` with a Shared Access Signature.
+pub(crate) async fn put_token(
+    link: &RequestResponseLink,
+    audience: &str,
+    token: &str,
+    expires_on: DateTime<Utc>,
+) -> Result<(), Error> {
+    let mut request = Message::with_value(Value::String(token.to_owned()));
+    request.application_properties = vec![
+        ("operation".into(), "put-token".into()),
+        ("type".into(), SAS_TOKEN_TYPE.into()),
+        ("name".into(), audience.into()),
+        (
+            "expiration".into(),
+            Value::Timestamp(expires_on.timestamp_millis()),
+        ),
+    ];
+    let response = link.call(request).await?;
+    match response_status(&response) {
+        (200..=299, _) => Ok(()),
+        (status_code, description) => Err(Error::Authorization {
+            status_code,
+            description,
+        }),
+    }
+}
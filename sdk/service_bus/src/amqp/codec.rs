@@ -0,0 +1,606 @@
+use crate::Error;
+use bytes::Bytes;
+use uuid::Uuid;
+
+/// An AMQP 1.0 value, as defined by the AMQP type system.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Ubyte(u8),
+    Ushort(u16),
+    Uint(u32),
+    Ulong(u64),
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    Char(char),
+    /// Milliseconds since the unix epoch.
+    Timestamp(i64),
+    Uuid(Uuid),
+    Binary(Bytes),
+    String(String),
+    Symbol(String),
+    List(Vec<Value>),
+    Map(Vec<(Value, Value)>),
+    /// An array of values sharing the same type.
+    Array(Vec<Value>),
+    Described(Box<Value>, Box<Value>),
+}
+
+impl Value {
+    /// Creates a symbol value.
+    pub fn symbol(symbol: impl Into<String>) -> Self {
+        Value::Symbol(symbol.into())
+    }
+
+    pub(crate) fn described(code: u64, value: Value) -> Self {
+        Value::Described(Box::new(Value::Ulong(code)), Box::new(value))
+    }
+
+    /// Returns `true` for [`Value::Null`].
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    /// Returns the value of a string or a symbol.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) | Value::Symbol(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of any unsigned integer type fitting an `u32`.
+    pub fn as_u32(&self) -> Option<u32> {
+        match self {
+            Value::Ubyte(v) => Some(*v as u32),
+            Value::Ushort(v) => Some(*v as u32),
+            Value::Uint(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of any unsigned integer type.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::Ulong(v) => Some(*v),
+            v => v.as_u32().map(u64::from),
+        }
+    }
+
+    /// Returns the value of any integer type fitting an `i64`.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Byte(v) => Some(*v as i64),
+            Value::Short(v) => Some(*v as i64),
+            Value::Int(v) => Some(*v as i64),
+            Value::Long(v) => Some(*v),
+            Value::Ulong(v) if *v <= i64::MAX as u64 => Some(*v as i64),
+            v => v.as_u32().map(i64::from),
+        }
+    }
+
+    pub fn as_timestamp(&self) -> Option<i64> {
+        match self {
+            Value::Timestamp(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_binary(&self) -> Option<&Bytes> {
+        match self {
+            Value::Binary(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn as_map(&self) -> Option<&[(Value, Value)]> {
+        match self {
+            Value::Map(m) => Some(m),
+            _ => None,
+        }
+    }
+
+    /// Looks up the entry of a map value whose key is the string or symbol `key`.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.as_map().and_then(|map| map_get(map, key))
+    }
+
+    /// Returns the descriptor code and the value of a described value.
+    pub(crate) fn into_described(self) -> Option<(u64, Value)> {
+        match self {
+            Value::Described(descriptor, value) => descriptor.as_u64().map(|code| (code, *value)),
+            _ => None,
+        }
+    }
+
+    /// Returns the fields of a list value; `null` is considered an empty list.
+    pub(crate) fn into_list(self) -> Option<Vec<Value>> {
+        match self {
+            Value::List(list) => Some(list),
+            Value::Null => Some(Vec::new()),
+            _ => None,
+        }
+    }
+
+    /// Returns the elements of an array, or the value itself as a single element array.
+    pub(crate) fn into_multiple(self) -> Vec<Value> {
+        match self {
+            Value::Array(items) => items,
+            Value::Null => Vec::new(),
+            value => vec![value],
+        }
+    }
+
+    /// The number of bytes [`Value::encode`] writes.
+    pub fn encoded_size(&self) -> usize {
+        let mut buf = Vec::new();
+        self.encode(&mut buf);
+        buf.len()
+    }
+
+    /// Appends the most compact encoding of this value to `buf`.
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        match self {
+            Value::Null => buf.push(0x40),
+            Value::Bool(true) => buf.push(0x41),
+            Value::Bool(false) => buf.push(0x42),
+            Value::Uint(0) => buf.push(0x43),
+            Value::Uint(v) if *v < 256 => buf.extend_from_slice(&[0x52, *v as u8]),
+            Value::Ulong(0) => buf.push(0x44),
+            Value::Ulong(v) if *v < 256 => buf.extend_from_slice(&[0x53, *v as u8]),
+            Value::Int(v) if i8::MIN as i32 <= *v && *v <= i8::MAX as i32 => {
+                buf.extend_from_slice(&[0x54, *v as i8 as u8])
+            }
+            Value::Long(v) if i8::MIN as i64 <= *v && *v <= i8::MAX as i64 => {
+                buf.extend_from_slice(&[0x55, *v as i8 as u8])
+            }
+            Value::Binary(b) if b.len() < 256 => {
+                buf.extend_from_slice(&[0xa0, b.len() as u8]);
+                buf.extend_from_slice(b);
+            }
+            Value::String(s) if s.len() < 256 => {
+                buf.extend_from_slice(&[0xa1, s.len() as u8]);
+                buf.extend_from_slice(s.as_bytes());
+            }
+            Value::Symbol(s) if s.len() < 256 => {
+                buf.extend_from_slice(&[0xa3, s.len() as u8]);
+                buf.extend_from_slice(s.as_bytes());
+            }
+            Value::List(items) if items.is_empty() => buf.push(0x45),
+            Value::List(items) => encode_compound(buf, 0xc0, 0xd0, items.len(), |body| {
+                items.iter().for_each(|item| item.encode(body))
+            }),
+            Value::Map(entries) => encode_compound(buf, 0xc1, 0xd1, entries.len() * 2, |body| {
+                for (key, value) in entries {
+                    key.encode(body);
+                    value.encode(body);
+                }
+            }),
+            Value::Described(descriptor, value) => {
+                buf.push(0x00);
+                descriptor.encode(buf);
+                value.encode(buf);
+            }
+            value => {
+                value.encode_constructor(buf);
+                value.encode_wide_body(buf);
+            }
+        }
+    }
+
+    /// Writes the constructor of the wide (fixed width) encoding of this value, as used for
+    /// array elements.
+    fn encode_constructor(&self, buf: &mut Vec<u8>) {
+        let code = match self {
+            Value::Null => 0x40,
+            Value::Bool(_) => 0x56,
+            Value::Ubyte(_) => 0x50,
+            Value::Ushort(_) => 0x60,
+            Value::Uint(_) => 0x70,
+            Value::Ulong(_) => 0x80,
+            Value::Byte(_) => 0x51,
+            Value::Short(_) => 0x61,
+            Value::Int(_) => 0x71,
+            Value::Long(_) => 0x81,
+            Value::Float(_) => 0x72,
+            Value::Double(_) => 0x82,
+            Value::Char(_) => 0x73,
+            Value::Timestamp(_) => 0x83,
+            Value::Uuid(_) => 0x98,
+            Value::Binary(_) => 0xb0,
+            Value::String(_) => 0xb1,
+            Value::Symbol(_) => 0xb3,
+            Value::List(_) => 0xd0,
+            Value::Map(_) => 0xd1,
+            Value::Array(_) => 0xf0,
+            Value::Described(descriptor, value) => {
+                buf.push(0x00);
+                descriptor.encode(buf);
+                return value.encode_constructor(buf);
+            }
+        };
+        buf.push(code);
+    }
+
+    /// Writes the body of the wide encoding of this value, without its constructor.
+    fn encode_wide_body(&self, buf: &mut Vec<u8>) {
+        match self {
+            Value::Null => {}
+            Value::Bool(b) => buf.push(*b as u8),
+            Value::Ubyte(v) => buf.push(*v),
+            Value::Ushort(v) => buf.extend_from_slice(&v.to_be_bytes()),
+            Value::Uint(v) => buf.extend_from_slice(&v.to_be_bytes()),
+            Value::Ulong(v) => buf.extend_from_slice(&v.to_be_bytes()),
+            Value::Byte(v) => buf.push(*v as u8),
+            Value::Short(v) => buf.extend_from_slice(&v.to_be_bytes()),
+            Value::Int(v) => buf.extend_from_slice(&v.to_be_bytes()),
+            Value::Long(v) | Value::Timestamp(v) => buf.extend_from_slice(&v.to_be_bytes()),
+            Value::Float(v) => buf.extend_from_slice(&v.to_be_bytes()),
+            Value::Double(v) => buf.extend_from_slice(&v.to_be_bytes()),
+            Value::Char(c) => buf.extend_from_slice(&(*c as u32).to_be_bytes()),
+            Value::Uuid(u) => buf.extend_from_slice(u.as_bytes()),
+            Value::Binary(b) => {
+                buf.extend_from_slice(&(b.len() as u32).to_be_bytes());
+                buf.extend_from_slice(b);
+            }
+            Value::String(s) | Value::Symbol(s) => {
+                buf.extend_from_slice(&(s.len() as u32).to_be_bytes());
+                buf.extend_from_slice(s.as_bytes());
+            }
+            Value::List(items) => encode_wide_compound(buf, items.len(), |body| {
+                items.iter().for_each(|item| item.encode(body))
+            }),
+            Value::Map(entries) => encode_wide_compound(buf, entries.len() * 2, |body| {
+                for (key, value) in entries {
+                    key.encode(body);
+                    value.encode(body);
+                }
+            }),
+            Value::Array(items) => encode_wide_compound(buf, items.len(), |body| {
+                match items.first() {
+                    Some(first) => first.encode_constructor(body),
+                    None => body.push(0x40),
+                }
+                items.iter().for_each(|item| item.encode_element_body(body))
+            }),
+            Value::Described(_, value) => value.encode_wide_body(buf),
+        }
+    }
+
+    fn encode_element_body(&self, buf: &mut Vec<u8>) {
+        match self {
+            Value::Described(_, value) => value.encode_element_body(buf),
+            value => value.encode_wide_body(buf),
+        }
+    }
+
+    /// Decodes the value at the start of `buf`, advancing it past the value.
+    pub fn decode(buf: &mut &[u8]) -> Result<Value, Error> {
+        let code = take_u8(buf)?;
+        if code == 0x00 {
+            let descriptor = Value::decode(buf)?;
+            let value = Value::decode(buf)?;
+            return Ok(Value::Described(Box::new(descriptor), Box::new(value)));
+        }
+        decode_body(code, buf)
+    }
+}
+
+fn encode_compound(
+    buf: &mut Vec<u8>,
+    small_code: u8,
+    large_code: u8,
+    count: usize,
+    write_items: impl FnOnce(&mut Vec<u8>),
+) {
+    let mut body = Vec::new();
+    write_items(&mut body);
+    if body.len() < 255 && count < 256 {
+        buf.extend_from_slice(&[small_code, body.len() as u8 + 1, count as u8]);
+    } else {
+        buf.push(large_code);
+        buf.extend_from_slice(&(body.len() as u32 + 4).to_be_bytes());
+        buf.extend_from_slice(&(count as u32).to_be_bytes());
+    }
+    buf.extend_from_slice(&body);
+}
+
+fn encode_wide_compound(buf: &mut Vec<u8>, count: usize, write_items: impl FnOnce(&mut Vec<u8>)) {
+    let mut body = Vec::new();
+    write_items(&mut body);
+    buf.extend_from_slice(&(body.len() as u32 + 4).to_be_bytes());
+    buf.extend_from_slice(&(count as u32).to_be_bytes());
+    buf.extend_from_slice(&body);
+}
+
+fn decode_body(code: u8, buf: &mut &[u8]) -> Result<Value, Error> {
+    Ok(match code {
+        0x40 => Value::Null,
+        0x41 => Value::Bool(true),
+        0x42 => Value::Bool(false),
+        0x56 => Value::Bool(take_u8(buf)? != 0),
+        0x50 => Value::Ubyte(take_u8(buf)?),
+        0x60 => Value::Ushort(u16::from_be_bytes(take_array(buf)?)),
+        0x70 => Value::Uint(u32::from_be_bytes(take_array(buf)?)),
+        0x52 => Value::Uint(take_u8(buf)? as u32),
+        0x43 => Value::Uint(0),
+        0x80 => Value::Ulong(u64::from_be_bytes(take_array(buf)?)),
+        0x53 => Value::Ulong(take_u8(buf)? as u64),
+        0x44 => Value::Ulong(0),
+        0x51 => Value::Byte(take_u8(buf)? as i8),
+        0x61 => Value::Short(i16::from_be_bytes(take_array(buf)?)),
+        0x71 => Value::Int(i32::from_be_bytes(take_array(buf)?)),
+        0x54 => Value::Int(take_u8(buf)? as i8 as i32),
+        0x81 => Value::Long(i64::from_be_bytes(take_array(buf)?)),
+        0x55 => Value::Long(take_u8(buf)? as i8 as i64),
+        0x72 => Value::Float(f32::from_be_bytes(take_array(buf)?)),
+        0x82 => Value::Double(f64::from_be_bytes(take_array(buf)?)),
+        0x73 => {
+            let c = u32::from_be_bytes(take_array(buf)?);
+            Value::Char(
+                std::char::from_u32(c)
+                    .ok_or_else(|| Error::Decode(format!("invalid char {:#x}", c)))?,
+            )
+        }
+        0x83 => Value::Timestamp(i64::from_be_bytes(take_array(buf)?)),
+        0x98 => Value::Uuid(Uuid::from_bytes(take_array(buf)?)),
+        0xa0 | 0xb0 => {
+            let len = take_len(code, buf)?;
+            Value::Binary(Bytes::copy_from_slice(take(buf, len)?))
+        }
+        0xa1 | 0xb1 | 0xa3 | 0xb3 => {
+            let len = take_len(code, buf)?;
+            let s = std::str::from_utf8(take(buf, len)?)
+                .map_err(|e| Error::Decode(e.to_string()))?
+                .to_owned();
+            if code & 0x0f == 0x01 {
+                Value::String(s)
+            } else {
+                Value::Symbol(s)
+            }
+        }
+        0x45 => Value::List(Vec::new()),
+        0xc0 | 0xd0 | 0xc1 | 0xd1 => {
+            let (mut body, count) = take_compound(code, buf)?;
+            let items = (0..count)
+                .map(|_| Value::decode(&mut body))
+                .collect::<Result<Vec<_>, _>>()?;
+            if code & 0x0f == 0x00 {
+                Value::List(items)
+            } else {
+                if count % 2 != 0 {
+                    return Err(Error::Decode("map with an odd element count".to_owned()));
+                }
+                let mut items = items.into_iter();
+                let mut entries = Vec::with_capacity(count / 2);
+                while let (Some(key), Some(value)) = (items.next(), items.next()) {
+                    entries.push((key, value));
+                }
+                Value::Map(entries)
+            }
+        }
+        0xe0 | 0xf0 => {
+            let (mut body, count) = take_compound(code, buf)?;
+            let mut element_code = take_u8(&mut body)?;
+            let descriptor = if element_code == 0x00 {
+                let descriptor = Value::decode(&mut body)?;
+                element_code = take_u8(&mut body)?;
+                Some(descriptor)
+            } else {
+                None
+            };
+            let items = (0..count)
+                .map(|_| {
+                    let value = decode_body(element_code, &mut body)?;
+                    Ok(match &descriptor {
+                        Some(descriptor) => {
+                            Value::Described(Box::new(descriptor.clone()), Box::new(value))
+                        }
+                        None => value,
+                    })
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            Value::Array(items)
+        }
+        code => {
+            return Err(Error::Decode(format!(
+                "unsupported type constructor {:#04x}",
+                code
+            )))
+        }
+    })
+}
+
+fn take<'a>(buf: &mut &'a [u8], len: usize) -> Result<&'a [u8], Error> {
+    if buf.len() < len {
+        return Err(Error::Decode(format!(
+            "expected {} more bytes, found {}",
+            len,
+            buf.len()
+        )));
+    }
+    let (head, tail) = buf.split_at(len);
+    *buf = tail;
+    Ok(head)
+}
+
+fn take_u8(buf: &mut &[u8]) -> Result<u8, Error> {
+    Ok(take(buf, 1)?[0])
+}
+
+fn take_array<const N: usize>(buf: &mut &[u8]) -> Result<[u8; N], Error> {
+    let mut array = [0; N];
+    array.copy_from_slice(take(buf, N)?);
+    Ok(array)
+}
+
+/// Reads the one byte (`0xa?`, `0xc?`, `0xe?`) or four bytes (`0xb?`, `0xd?`, `0xf?`) length.
+fn take_len(code: u8, buf: &mut &[u8]) -> Result<usize, Error> {
+    if matches!(code >> 4, 0xa | 0xc | 0xe) {
+        Ok(take_u8(buf)? as usize)
+    } else {
+        Ok(u32::from_be_bytes(take_array(buf)?) as usize)
+    }
+}
+
+/// Reads the size and count of a compound or array value, returning the remaining body and the
+/// element count.
+fn take_compound<'a>(code: u8, buf: &mut &'a [u8]) -> Result<(&'a [u8], usize), Error> {
+    let size = take_len(code, buf)?;
+    let mut body = take(buf, size)?;
+    let count = take_len(code, &mut body)?;
+    Ok((body, count))
+}
+
+/// Looks up the entry of `map` whose key is the string or symbol `key`.
+pub(crate) fn map_get<'a>(map: &'a [(Value, Value)], key: &str) -> Option<&'a Value> {
+    map.iter()
+        .find(|(k, _)| k.as_str() == Some(key))
+        .map(|(_, v)| v)
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<u32> for Value {
+    fn from(value: u32) -> Self {
+        Value::Uint(value)
+    }
+}
+
+impl From<u64> for Value {
+    fn from(value: u64) -> Self {
+        Value::Ulong(value)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(value: i32) -> Self {
+        Value::Int(value)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Long(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Double(value)
+    }
+}
+
+impl From<Uuid> for Value {
+    fn from(value: Uuid) -> Self {
+        Value::Uuid(value)
+    }
+}
+
+impl From<Bytes> for Value {
+    fn from(value: Bytes) -> Self {
+        Value::Binary(value)
+    }
+}
+
+impl From<Vec<u8>> for Value {
+    fn from(value: Vec<u8>) -> Self {
+        Value::Binary(value.into())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_owned())
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map(Into::into).unwrap_or(Value::Null)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(value: Value) -> Vec<u8> {
+        let mut buf = Vec::new();
+        value.encode(&mut buf);
+        let mut slice = buf.as_slice();
+        assert_eq!(Value::decode(&mut slice).unwrap(), value);
+        assert!(slice.is_empty());
+        buf
+    }
+
+    #[test]
+    fn primitives_use_compact_encodings() {
+        assert_eq!(roundtrip(Value::Uint(0)), vec![0x43]);
+        assert_eq!(roundtrip(Value::Uint(7)), vec![0x52, 7]);
+        assert_eq!(roundtrip(Value::Uint(70000)), vec![0x70, 0, 1, 0x11, 0x70]);
+        assert_eq!(roundtrip(Value::Ulong(0x24)), vec![0x53, 0x24]);
+        assert_eq!(roundtrip(Value::Long(-1)), vec![0x55, 0xff]);
+        assert_eq!(roundtrip(Value::symbol("ab")), vec![0xa3, 2, b'a', b'b']);
+        assert_eq!(roundtrip(Value::List(Vec::new())), vec![0x45]);
+        roundtrip(Value::Timestamp(1_600_000_000_000));
+        roundtrip(Value::Uuid(Uuid::new_v4()));
+        roundtrip(Value::Double(1.5));
+        roundtrip(Value::String("x".repeat(300)));
+        roundtrip(Value::Binary(Bytes::from(vec![1u8; 300])));
+    }
+
+    #[test]
+    fn compound_values() {
+        let value = Value::described(
+            0x12,
+            Value::List(vec![
+                "link".into(),
+                Value::Uint(1),
+                Value::Null,
+                Value::Map(vec![(Value::symbol("key"), Value::Int(-5))]),
+                Value::Array(vec![Value::symbol("a"), Value::symbol("bc")]),
+                Value::Array(Vec::new()),
+            ]),
+        );
+        let buf = roundtrip(value.clone());
+        assert_eq!(&buf[..4], &[0x00, 0x53, 0x12, 0xc0]);
+        assert_eq!(value.into_described().unwrap().0, 0x12);
+
+        let large = Value::List(
+            (0..100)
+                .map(|i| Value::String(format!("{:05}", i)))
+                .collect(),
+        );
+        assert_eq!(roundtrip(large)[0], 0xd0);
+    }
+
+    #[test]
+    fn truncated_input_is_an_error() {
+        let mut slice: &[u8] = &[0xa1, 5, b'a'];
+        assert!(matches!(Value::decode(&mut slice), Err(Error::Decode(_))));
+    }
+}
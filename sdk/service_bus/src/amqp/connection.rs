@@ -0,0 +1,1001 @@
+use super::codec::Value;
+use super::message::Message;
+use super::performatives::*;
+use crate::Error;
+use bytes::Bytes;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadHalf, WriteHalf};
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, oneshot, Notify};
+use url::Url;
+use uuid::Uuid;
+
+pub(crate) const AMQP_HEADER: [u8; 8] = *b"AMQP\x00\x01\x00\x00";
+pub(crate) const SASL_HEADER: [u8; 8] = *b"AMQP\x03\x01\x00\x00";
+pub(crate) const FRAME_TYPE_AMQP: u8 = 0;
+pub(crate) const FRAME_TYPE_SASL: u8 = 1;
+/// The largest frame this side accepts.
+pub(crate) const MAX_FRAME_SIZE: u32 = 64 * 1024;
+/// The number of transfer frames the session accepts before the window is renewed.
+const SESSION_WINDOW: u32 = 5000;
+const EMPTY_FRAME: [u8; 8] = [0, 0, 0, 8, 2, FRAME_TYPE_AMQP, 0, 0];
+
+pub(crate) trait Transport: AsyncRead + AsyncWrite + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> Transport for T {}
+
+type BoxedTransport = Box<dyn Transport>;
+
+/// A frame as read from the wire, before its body is decoded.
+#[derive(Debug)]
+pub(crate) struct RawFrame {
+    pub(crate) frame_type: u8,
+    /// The encoded performative followed by the payload; empty for heartbeats.
+    pub(crate) body: Bytes,
+}
+
+impl RawFrame {
+    /// Decodes the performative of an AMQP frame, returning it with the payload following it.
+    pub(crate) fn performative(&self) -> Result<(Performative, Bytes), Error> {
+        let mut body = &self.body[..];
+        let performative = Performative::from_value(Value::decode(&mut body)?)?;
+        let payload = self.body.slice(self.body.len() - body.len()..);
+        Ok((performative, payload))
+    }
+
+    pub(crate) fn sasl(&self) -> Result<SaslFrame, Error> {
+        if self.frame_type != FRAME_TYPE_SASL {
+            return Err(Error::Protocol("expected a SASL frame".to_owned()));
+        }
+        SaslFrame::from_value(Value::decode(&mut &self.body[..])?)
+    }
+}
+
+pub(crate) async fn read_frame<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    max_frame_size: u32,
+) -> Result<RawFrame, Error> {
+    let mut header = [0u8; 8];
+    reader.read_exact(&mut header).await?;
+    let size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+    let data_offset = header[4] as u32 * 4;
+    if size < 8 || data_offset < 8 || data_offset > size || size > max_frame_size {
+        return Err(Error::Protocol(format!(
+            "invalid frame header {:?}",
+            header
+        )));
+    }
+    let mut rest = vec![0u8; size as usize - 8];
+    reader.read_exact(&mut rest).await?;
+    let body = Bytes::from(rest).slice(data_offset as usize - 8..);
+    Ok(RawFrame {
+        frame_type: header[5],
+        body,
+    })
+}
+
+pub(crate) fn encode_frame(
+    buf: &mut Vec<u8>,
+    frame_type: u8,
+    channel: u16,
+    body: &Value,
+    payload: &[u8],
+) {
+    let start = buf.len();
+    buf.extend_from_slice(&[0, 0, 0, 0, 2, frame_type]);
+    buf.extend_from_slice(&channel.to_be_bytes());
+    body.encode(buf);
+    buf.extend_from_slice(payload);
+    let size = (buf.len() - start) as u32;
+    buf[start..start + 4].copy_from_slice(&size.to_be_bytes());
+}
+
+async fn expect_header<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    expected: &[u8; 8],
+) -> Result<(), Error> {
+    let mut header = [0u8; 8];
+    reader.read_exact(&mut header).await?;
+    if &header != expected {
+        return Err(Error::Protocol(format!(
+            "unexpected protocol header {:?}",
+            header
+        )));
+    }
+    Ok(())
+}
+
+/// A delivery received on a receiver link.
+#[derive(Debug, Clone)]
+pub(crate) struct Delivery {
+    pub(crate) delivery_id: u32,
+    pub(crate) delivery_tag: Bytes,
+    pub(crate) settled: bool,
+    pub(crate) message: Message,
+}
+
+enum LinkKind {
+    Sender {
+        credit: u32,
+        delivery_count: u32,
+        credit_available: Arc<Notify>,
+    },
+    Receiver {
+        credit: u32,
+        delivery_count: u32,
+        deliveries: Option<mpsc::UnboundedSender<Result<Delivery, Error>>>,
+        partial: Option<(Transfer, Vec<u8>)>,
+    },
+}
+
+struct Link {
+    name: String,
+    attached: Option<oneshot::Sender<Result<Attach, Error>>>,
+    /// Set once the remote peer detached the link, with the error it reported.
+    detached: Option<Option<AmqpError>>,
+    closing: bool,
+    kind: LinkKind,
+}
+
+impl Link {
+    fn detached_error(&self) -> Option<Error> {
+        self.detached.as_ref().map(|error| match error {
+            Some(error) => error.clone().into(),
+            None => Error::Protocol(format!("the link {} was detached", self.name)),
+        })
+    }
+}
+
+#[derive(Default)]
+struct State {
+    closed: bool,
+    close_error: Option<AmqpError>,
+    next_handle: u32,
+    next_outgoing_id: u32,
+    next_incoming_id: u32,
+    incoming_window: u32,
+    links: HashMap<u32, Link>,
+    /// Maps the handles chosen by the remote peer to the local ones.
+    remote_handles: HashMap<u32, u32>,
+    unsettled: HashMap<u32, oneshot::Sender<Result<DeliveryState, Error>>>,
+}
+
+impl State {
+    fn closed_error(&self) -> Error {
+        self.close_error
+            .clone()
+            .map(Into::into)
+            .unwrap_or(Error::ConnectionClosed)
+    }
+
+    fn session_flow(&self) -> Flow {
+        Flow {
+            next_incoming_id: Some(self.next_incoming_id),
+            incoming_window: self.incoming_window,
+            next_outgoing_id: self.next_outgoing_id,
+            outgoing_window: SESSION_WINDOW,
+            ..Default::default()
+        }
+    }
+
+    fn local_handle(&self, remote_handle: u32) -> Result<u32, Error> {
+        self.remote_handles
+            .get(&remote_handle)
+            .copied()
+            .ok_or_else(|| Error::Protocol(format!("unknown link handle {}", remote_handle)))
+    }
+}
+
+struct Inner {
+    writer: tokio::sync::Mutex<WriteHalf<BoxedTransport>>,
+    state: Mutex<State>,
+    /// The largest frame the remote peer accepts.
+    max_frame_size: usize,
+}
+
+/// An AMQP 1.0 connection with a single session.
+///
+/// Frames are read by a background task, so links are driven as long as any handle to the
+/// connection is alive.
+#[derive(Clone)]
+pub(crate) struct Connection {
+    inner: Arc<Inner>,
+}
+
+impl std::fmt::Debug for Connection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Connection")
+            .field("closed", &self.is_closed())
+            .finish()
+    }
+}
+
+impl Connection {
+    /// Opens a connection to `endpoint`, whose scheme is either `amqps` (TLS) or `amqp`.
+    pub(crate) async fn open(endpoint: &Url) -> Result<Self, Error> {
+        let host = endpoint
+            .host_str()
+            .ok_or_else(|| Error::InvalidEndpoint(endpoint.to_string()))?;
+        let tls = match endpoint.scheme() {
+            "amqps" => true,
+            "amqp" => false,
+            _ => return Err(Error::InvalidEndpoint(endpoint.to_string())),
+        };
+        let port = endpoint.port().unwrap_or(if tls { 5671 } else { 5672 });
+        let stream = TcpStream::connect((host, port)).await?;
+        let transport: BoxedTransport = if tls {
+            let connector = tokio_native_tls::TlsConnector::from(native_tls::TlsConnector::new()?);
+            Box::new(connector.connect(host, stream).await?)
+        } else {
+            Box::new(stream)
+        };
+        Self::handshake(transport, host).await
+    }
+
+    async fn handshake(mut transport: BoxedTransport, hostname: &str) -> Result<Self, Error> {
+        let mut buf = SASL_HEADER.to_vec();
+        transport.write_all(&buf).await?;
+        expect_header(&mut transport, &SASL_HEADER).await?;
+        match read_frame(&mut transport, MAX_FRAME_SIZE).await?.sasl()? {
+            SaslFrame::Mechanisms(mechanisms) if mechanisms.iter().any(|m| m == "ANONYMOUS") => {}
+            frame => {
+                return Err(Error::Protocol(format!(
+                    "the ANONYMOUS SASL mechanism is not offered: {:?}",
+                    frame
+                )))
+            }
+        }
+        buf.clear();
+        let init = SaslFrame::Init {
+            mechanism: "ANONYMOUS".to_owned(),
+            initial_response: None,
+            hostname: Some(hostname.to_owned()),
+        };
+        encode_frame(&mut buf, FRAME_TYPE_SASL, 0, &init.to_value(), &[]);
+        transport.write_all(&buf).await?;
+        match read_frame(&mut transport, MAX_FRAME_SIZE).await?.sasl()? {
+            SaslFrame::Outcome(0) => {}
+            SaslFrame::Outcome(code) => return Err(Error::SaslAuthentication(code)),
+            frame => {
+                return Err(Error::Protocol(format!(
+                    "expected a SASL outcome, received {:?}",
+                    frame
+                )))
+            }
+        }
+
+        buf.clear();
+        buf.extend_from_slice(&AMQP_HEADER);
+        let open = Performative::Open(Open {
+            container_id: Uuid::new_v4().to_string(),
+            hostname: Some(hostname.to_owned()),
+            max_frame_size: Some(MAX_FRAME_SIZE),
+            channel_max: Some(0),
+            idle_time_out: None,
+        });
+        encode_frame(&mut buf, FRAME_TYPE_AMQP, 0, &open.to_value(), &[]);
+        let begin = Performative::Begin(Begin {
+            remote_channel: None,
+            next_outgoing_id: 0,
+            incoming_window: SESSION_WINDOW,
+            outgoing_window: SESSION_WINDOW,
+            handle_max: None,
+        });
+        encode_frame(&mut buf, FRAME_TYPE_AMQP, 0, &begin.to_value(), &[]);
+        transport.write_all(&buf).await?;
+        expect_header(&mut transport, &AMQP_HEADER).await?;
+
+        let mut remote_open = None;
+        let remote_begin = loop {
+            let frame = read_frame(&mut transport, MAX_FRAME_SIZE).await?;
+            if frame.body.is_empty() {
+                continue;
+            }
+            match (frame.performative()?.0, &remote_open) {
+                (Performative::Open(open), None) => remote_open = Some(open),
+                (Performative::Begin(begin), Some(_)) => break begin,
+                (Performative::Close(error), _) | (Performative::End(error), _) => {
+                    return Err(error.map(Into::into).unwrap_or(Error::ConnectionClosed))
+                }
+                (performative, _) => {
+                    return Err(Error::Protocol(format!(
+                        "unexpected frame while opening the connection: {:?}",
+                        performative
+                    )))
+                }
+            }
+        };
+        let remote_open = remote_open.expect("the open frame precedes the begin frame");
+
+        let (reader, writer) = tokio::io::split(transport);
+        let inner = Arc::new(Inner {
+            writer: tokio::sync::Mutex::new(writer),
+            state: Mutex::new(State {
+                next_incoming_id: remote_begin.next_outgoing_id,
+                incoming_window: SESSION_WINDOW,
+                ..Default::default()
+            }),
+            max_frame_size: remote_open.max_frame_size.unwrap_or(u32::MAX).max(512) as usize,
+        });
+        tokio::spawn(run_reader(reader, Arc::downgrade(&inner)));
+        if let Some(idle_time_out) = remote_open.idle_time_out.filter(|timeout| *timeout > 0) {
+            tokio::spawn(run_heartbeat(
+                Arc::downgrade(&inner),
+                Duration::from_millis(idle_time_out as u64 / 2),
+            ));
+        }
+        Ok(Self { inner })
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.inner.state.lock().unwrap().closed
+    }
+
+    /// Attaches a link sending to `attach.target`. The handle and role are set by the connection.
+    pub(crate) async fn attach_sender(&self, mut attach: Attach) -> Result<SenderLink, Error> {
+        let credit_available = Arc::new(Notify::new());
+        attach.role = Role::Sender;
+        attach.initial_delivery_count = Some(0);
+        let settled = attach.snd_settle_mode == Some(SenderSettleMode::Settled);
+        let (handle, remote) = self
+            .attach(
+                attach,
+                LinkKind::Sender {
+                    credit: 0,
+                    delivery_count: 0,
+                    credit_available: credit_available.clone(),
+                },
+            )
+            .await?;
+        Ok(SenderLink {
+            connection: self.clone(),
+            handle,
+            settled,
+            max_message_size: remote.max_message_size.filter(|size| *size > 0),
+            credit_available,
+        })
+    }
+
+    /// Attaches a link receiving from `attach.source`, granting it `credit` deliveries at a time.
+    pub(crate) async fn attach_receiver(
+        &self,
+        mut attach: Attach,
+        credit: u32,
+    ) -> Result<ReceiverLink, Error> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        attach.role = Role::Receiver;
+        let (handle, remote) = self
+            .attach(
+                attach,
+                LinkKind::Receiver {
+                    credit: 0,
+                    delivery_count: 0,
+                    deliveries: Some(tx),
+                    partial: None,
+                },
+            )
+            .await?;
+        let receiver = ReceiverLink {
+            connection: self.clone(),
+            handle,
+            credit,
+            remote,
+            deliveries: rx,
+        };
+        receiver.issue_credit(true).await?;
+        Ok(receiver)
+    }
+
+    async fn attach(&self, mut attach: Attach, kind: LinkKind) -> Result<(u32, Attach), Error> {
+        let (tx, rx) = oneshot::channel();
+        let handle = {
+            let mut state = self.inner.state.lock().unwrap();
+            if state.closed {
+                return Err(state.closed_error());
+            }
+            let handle = state.next_handle;
+            state.next_handle += 1;
+            state.links.insert(
+                handle,
+                Link {
+                    name: attach.name.clone(),
+                    attached: Some(tx),
+                    detached: None,
+                    closing: false,
+                    kind,
+                },
+            );
+            handle
+        };
+        attach.handle = handle;
+        self.write(&Performative::Attach(attach), &[]).await?;
+        let remote = match rx.await {
+            Ok(remote) => remote?,
+            Err(_) => return Err(self.inner.state.lock().unwrap().closed_error()),
+        };
+        Ok((handle, remote))
+    }
+
+    async fn write(&self, performative: &Performative, payload: &[u8]) -> Result<(), Error> {
+        self.inner.write(performative, payload).await
+    }
+
+    async fn detach(&self, handle: u32) -> Result<(), Error> {
+        {
+            let mut state = self.inner.state.lock().unwrap();
+            if state.closed {
+                return Ok(());
+            }
+            match state.links.get_mut(&handle) {
+                Some(link) if link.detached.is_none() => link.closing = true,
+                _ => {
+                    state.links.remove(&handle);
+                    return Ok(());
+                }
+            }
+        }
+        self.write(
+            &Performative::Detach(Detach {
+                handle,
+                closed: true,
+                error: None,
+            }),
+            &[],
+        )
+        .await
+    }
+
+    /// Closes the connection; links attached to it fail from now on.
+    pub(crate) async fn close(&self) -> Result<(), Error> {
+        {
+            let mut state = self.inner.state.lock().unwrap();
+            if state.closed {
+                return Ok(());
+            }
+            state.closed = true;
+        }
+        let result = self.write(&Performative::Close(None), &[]).await;
+        self.inner.shutdown(None);
+        result
+    }
+}
+
+impl Inner {
+    async fn write(&self, performative: &Performative, payload: &[u8]) -> Result<(), Error> {
+        let mut buf = Vec::new();
+        encode_frame(
+            &mut buf,
+            FRAME_TYPE_AMQP,
+            0,
+            &performative.to_value(),
+            payload,
+        );
+        self.writer.lock().await.write_all(&buf).await?;
+        Ok(())
+    }
+
+    /// Handles a frame received from the remote peer, returning `false` once the connection is
+    /// closed.
+    async fn handle_frame(&self, frame: RawFrame) -> Result<bool, Error> {
+        if frame.body.is_empty() {
+            return Ok(true);
+        }
+        if frame.frame_type != FRAME_TYPE_AMQP {
+            return Err(Error::Protocol("unexpected SASL frame".to_owned()));
+        }
+        let (performative, payload) = frame.performative()?;
+        log::trace!("received {:?}", performative);
+        let reply = match performative {
+            Performative::Attach(attach) => self.on_attach(attach)?,
+            Performative::Flow(flow) => self.on_flow(flow)?,
+            Performative::Transfer(transfer) => self.on_transfer(transfer, payload)?,
+            Performative::Disposition(disposition) => self.on_disposition(disposition),
+            Performative::Detach(detach) => self.on_detach(detach)?,
+            Performative::End(error) | Performative::Close(error) => {
+                let reply = {
+                    let mut state = self.state.lock().unwrap();
+                    state.close_error = error;
+                    !std::mem::replace(&mut state.closed, true)
+                };
+                if reply {
+                    self.write(&Performative::Close(None), &[]).await?;
+                }
+                return Ok(false);
+            }
+            Performative::Open(_) | Performative::Begin(_) => None,
+        };
+        if let Some(reply) = reply {
+            self.write(&reply, &[]).await?;
+        }
+        Ok(true)
+    }
+
+    fn on_attach(&self, attach: Attach) -> Result<Option<Performative>, Error> {
+        let mut state = self.state.lock().unwrap();
+        let (handle, link) = state
+            .links
+            .iter_mut()
+            .find(|(_, link)| link.name == attach.name && link.attached.is_some())
+            .ok_or_else(|| Error::Protocol(format!("unexpected attach of {}", attach.name)))?;
+        let handle = *handle;
+        let refused = match &mut link.kind {
+            LinkKind::Sender { .. } => attach.target.is_none(),
+            LinkKind::Receiver { delivery_count, .. } => {
+                *delivery_count = attach.initial_delivery_count.unwrap_or(0);
+                attach.source.is_none()
+            }
+        };
+        // A refused link is followed by a detach carrying the error.
+        if !refused {
+            if let Some(attached) = link.attached.take() {
+                let _ = attached.send(Ok(attach.clone()));
+            }
+        }
+        state.remote_handles.insert(attach.handle, handle);
+        Ok(None)
+    }
+
+    fn on_flow(&self, flow: Flow) -> Result<Option<Performative>, Error> {
+        let remote_handle = match flow.handle {
+            Some(handle) => handle,
+            None => return Ok(None),
+        };
+        let mut state = self.state.lock().unwrap();
+        let handle = state.local_handle(remote_handle)?;
+        if let Some(Link {
+            kind:
+                LinkKind::Sender {
+                    credit,
+                    delivery_count,
+                    credit_available,
+                },
+            ..
+        }) = state.links.get_mut(&handle)
+        {
+            *credit = flow
+                .delivery_count
+                .unwrap_or(0)
+                .wrapping_add(flow.link_credit.unwrap_or(0))
+                .wrapping_sub(*delivery_count);
+            if *credit > 0 {
+                credit_available.notify_one();
+            }
+        }
+        Ok(None)
+    }
+
+    fn on_transfer(
+        &self,
+        transfer: Transfer,
+        payload: Bytes,
+    ) -> Result<Option<Performative>, Error> {
+        let mut state = self.state.lock().unwrap();
+        state.next_incoming_id = state.next_incoming_id.wrapping_add(1);
+        state.incoming_window = state.incoming_window.saturating_sub(1);
+        let handle = state.local_handle(transfer.handle)?;
+        if let Some(Link {
+            kind:
+                LinkKind::Receiver {
+                    credit,
+                    delivery_count,
+                    deliveries,
+                    partial,
+                },
+            ..
+        }) = state.links.get_mut(&handle)
+        {
+            let more = transfer.more;
+            let (first, mut data) = partial
+                .take()
+                .unwrap_or_else(|| (transfer, Vec::with_capacity(payload.len())));
+            data.extend_from_slice(&payload);
+            if more {
+                *partial = Some((first, data));
+            } else {
+                *credit = credit.saturating_sub(1);
+                *delivery_count = delivery_count.wrapping_add(1);
+                let delivery = Message::decode(&data).map(|message| Delivery {
+                    delivery_id: first.delivery_id.unwrap_or_default(),
+                    delivery_tag: first.delivery_tag.clone().unwrap_or_default(),
+                    settled: first.settled.unwrap_or(false),
+                    message,
+                });
+                if let Some(deliveries) = deliveries {
+                    let _ = deliveries.send(delivery);
+                }
+            }
+        }
+        if state.incoming_window < SESSION_WINDOW / 2 {
+            state.incoming_window = SESSION_WINDOW;
+            return Ok(Some(Performative::Flow(state.session_flow())));
+        }
+        Ok(None)
+    }
+
+    fn on_disposition(&self, disposition: Disposition) -> Option<Performative> {
+        if disposition.role != Role::Receiver {
+            return None;
+        }
+        let mut state = self.state.lock().unwrap();
+        let last = disposition.last.unwrap_or(disposition.first);
+        let mut id = disposition.first;
+        loop {
+            if let Some(outcome) = state.unsettled.remove(&id) {
+                let _ = outcome.send(Ok(disposition
+                    .state
+                    .clone()
+                    .unwrap_or(DeliveryState::Accepted)));
+            }
+            if id == last {
+                break;
+            }
+            id = id.wrapping_add(1);
+        }
+        None
+    }
+
+    fn on_detach(&self, detach: Detach) -> Result<Option<Performative>, Error> {
+        let mut state = self.state.lock().unwrap();
+        let handle = state.local_handle(detach.handle)?;
+        state.remote_handles.remove(&detach.handle);
+        let link = match state.links.get_mut(&handle) {
+            Some(link) => link,
+            None => return Ok(None),
+        };
+        if link.closing {
+            state.links.remove(&handle);
+            return Ok(None);
+        }
+        if let Some(attached) = link.attached.take() {
+            let error = detach.error.map(Into::into).unwrap_or_else(|| {
+                Error::Protocol(format!("the attach of {} was refused", link.name))
+            });
+            let _ = attached.send(Err(error));
+            state.links.remove(&handle);
+        } else {
+            link.detached = Some(detach.error.clone());
+            match &mut link.kind {
+                LinkKind::Sender {
+                    credit_available, ..
+                } => credit_available.notify_one(),
+                LinkKind::Receiver { deliveries, .. } => {
+                    if let (Some(deliveries), Some(error)) = (deliveries.take(), detach.error) {
+                        let _ = deliveries.send(Err(error.into()));
+                    }
+                }
+            }
+        }
+        Ok(Some(Performative::Detach(Detach {
+            handle,
+            closed: true,
+            error: None,
+        })))
+    }
+
+    /// Fails every pending operation once the connection is closed.
+    fn shutdown(&self, error: Option<Error>) {
+        if let Some(error) = &error {
+            log::warn!("AMQP connection failed: {}", error);
+        }
+        let mut state = self.state.lock().unwrap();
+        state.closed = true;
+        for (_, link) in state.links.drain() {
+            if let LinkKind::Sender {
+                credit_available, ..
+            } = link.kind
+            {
+                credit_available.notify_one();
+            }
+        }
+        state.unsettled.clear();
+    }
+}
+
+async fn run_reader(mut reader: ReadHalf<BoxedTransport>, inner: Weak<Inner>) {
+    let error = loop {
+        let frame = match read_frame(&mut reader, MAX_FRAME_SIZE).await {
+            Ok(frame) => frame,
+            Err(error) => break Some(error),
+        };
+        let inner = match inner.upgrade() {
+            Some(inner) => inner,
+            None => return,
+        };
+        match inner.handle_frame(frame).await {
+            Ok(true) => {}
+            Ok(false) => break None,
+            Err(error) => break Some(error),
+        }
+    };
+    if let Some(inner) = inner.upgrade() {
+        let closed = inner.state.lock().unwrap().closed;
+        inner.shutdown(if closed { None } else { error });
+    }
+}
+
+async fn run_heartbeat(inner: Weak<Inner>, period: Duration) {
+    loop {
+        tokio::time::sleep(period).await;
+        let inner = match inner.upgrade() {
+            Some(inner) if !inner.state.lock().unwrap().closed => inner,
+            _ => return,
+        };
+        let mut writer = inner.writer.lock().await;
+        if writer.write_all(&EMPTY_FRAME).await.is_err() {
+            return;
+        }
+    }
+}
+
+/// A link sending messages to a node of the remote peer.
+#[derive(Debug)]
+pub(crate) struct SenderLink {
+    connection: Connection,
+    handle: u32,
+    settled: bool,
+    max_message_size: Option<u64>,
+    credit_available: Arc<Notify>,
+}
+
+impl SenderLink {
+    /// The largest message the remote peer accepts, when it advertised one.
+    pub(crate) fn max_message_size(&self) -> Option<u64> {
+        self.max_message_size
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        let state = self.connection.inner.state.lock().unwrap();
+        state.closed
+            || state
+                .links
+                .get(&self.handle)
+                .map(|link| link.detached.is_some())
+                .unwrap_or(true)
+    }
+
+    /// Sends an encoded message, waiting for the remote peer to accept it unless the link sends
+    /// settled deliveries.
+    pub(crate) async fn send(&self, message_format: u32, message: Bytes) -> Result<(), Error> {
+        if let Some(max_message_size) = self.max_message_size {
+            if message.len() as u64 > max_message_size {
+                return Err(Error::MessageTooLarge {
+                    size: message.len(),
+                    max_size: max_message_size as usize,
+                });
+            }
+        }
+        self.acquire_credit().await?;
+
+        let inner = &self.connection.inner;
+        let outcome = {
+            let mut writer = inner.writer.lock().await;
+            let (delivery_id, outcome) = {
+                let mut state = inner.state.lock().unwrap();
+                if state.closed {
+                    return Err(state.closed_error());
+                }
+                let delivery_id = state.next_outgoing_id;
+                state.next_outgoing_id = delivery_id.wrapping_add(1);
+                let outcome = if self.settled {
+                    None
+                } else {
+                    let (tx, rx) = oneshot::channel();
+                    state.unsettled.insert(delivery_id, tx);
+                    Some(rx)
+                };
+                (delivery_id, outcome)
+            };
+
+            let mut transfer = Transfer {
+                handle: self.handle,
+                delivery_id: Some(delivery_id),
+                delivery_tag: Some(Bytes::copy_from_slice(&delivery_id.to_be_bytes())),
+                message_format: Some(message_format),
+                settled: Some(self.settled),
+                more: false,
+            };
+            // Leaves room for the frame header and the transfer performative.
+            let chunk_size = inner.max_frame_size.saturating_sub(128).max(256);
+            let mut buf = Vec::new();
+            let mut chunks = message.chunks(chunk_size).peekable();
+            loop {
+                let chunk = chunks.next().unwrap_or(&[]);
+                transfer.more = chunks.peek().is_some();
+                let performative = Performative::Transfer(transfer.clone());
+                encode_frame(
+                    &mut buf,
+                    FRAME_TYPE_AMQP,
+                    0,
+                    &performative.to_value(),
+                    chunk,
+                );
+                if !transfer.more {
+                    break;
+                }
+                transfer = Transfer {
+                    handle: self.handle,
+                    ..Default::default()
+                };
+            }
+            writer.write_all(&buf).await?;
+            outcome
+        };
+
+        let outcome = match outcome {
+            Some(outcome) => outcome,
+            None => return Ok(()),
+        };
+        match outcome.await {
+            Ok(Ok(DeliveryState::Accepted)) => Ok(()),
+            Ok(Ok(DeliveryState::Rejected(Some(error)))) => Err(error.into()),
+            Ok(Ok(state)) => Err(Error::Protocol(format!(
+                "the message was not accepted: {:?}",
+                state
+            ))),
+            Ok(Err(error)) => Err(error),
+            Err(_) => Err(inner.state.lock().unwrap().closed_error()),
+        }
+    }
+
+    async fn acquire_credit(&self) -> Result<(), Error> {
+        loop {
+            {
+                let mut state = self.connection.inner.state.lock().unwrap();
+                if state.closed {
+                    return Err(state.closed_error());
+                }
+                let link = state
+                    .links
+                    .get_mut(&self.handle)
+                    .ok_or_else(|| Error::Protocol("the link is closed".to_owned()))?;
+                if let Some(error) = link.detached_error() {
+                    return Err(error);
+                }
+                if let LinkKind::Sender {
+                    credit,
+                    delivery_count,
+                    credit_available,
+                } = &mut link.kind
+                {
+                    if *credit > 0 {
+                        *credit -= 1;
+                        *delivery_count = delivery_count.wrapping_add(1);
+                        if *credit > 0 {
+                            credit_available.notify_one();
+                        }
+                        return Ok(());
+                    }
+                }
+            }
+            self.credit_available.notified().await;
+        }
+    }
+
+    pub(crate) async fn close(&self) -> Result<(), Error> {
+        self.connection.detach(self.handle).await
+    }
+}
+
+/// A link receiving messages from a node of the remote peer.
+#[derive(Debug)]
+pub(crate) struct ReceiverLink {
+    connection: Connection,
+    handle: u32,
+    credit: u32,
+    /// The attach frame the remote peer answered with.
+    remote: Attach,
+    deliveries: mpsc::UnboundedReceiver<Result<Delivery, Error>>,
+}
+
+impl ReceiverLink {
+    pub(crate) fn remote_attach(&self) -> &Attach {
+        &self.remote
+    }
+
+    /// Waits for the next delivery; `None` means the link was closed by the remote peer.
+    pub(crate) async fn recv(&mut self) -> Result<Option<Delivery>, Error> {
+        match self.deliveries.recv().await {
+            Some(Ok(delivery)) => {
+                self.issue_credit(false).await?;
+                Ok(Some(delivery))
+            }
+            Some(Err(error)) => Err(error),
+            None => {
+                let state = self.connection.inner.state.lock().unwrap();
+                if state.closed {
+                    Err(state.closed_error())
+                } else {
+                    Ok(None)
+                }
+            }
+        }
+    }
+
+    /// Renews the credit of the link once half of it is consumed, or unconditionally.
+    async fn issue_credit(&self, force: bool) -> Result<(), Error> {
+        let flow = {
+            let mut state = self.connection.inner.state.lock().unwrap();
+            if state.closed {
+                return Err(state.closed_error());
+            }
+            let mut flow = state.session_flow();
+            match state.links.get_mut(&self.handle) {
+                Some(Link {
+                    kind:
+                        LinkKind::Receiver {
+                            credit,
+                            delivery_count,
+                            ..
+                        },
+                    ..
+                }) if force || *credit <= self.credit / 2 => {
+                    *credit = self.credit;
+                    flow.handle = Some(self.handle);
+                    flow.delivery_count = Some(*delivery_count);
+                    flow.link_credit = Some(self.credit);
+                    flow
+                }
+                _ => return Ok(()),
+            }
+        };
+        self.connection.write(&Performative::Flow(flow), &[]).await
+    }
+
+    /// Settles a delivery received unsettled with the given outcome.
+    pub(crate) async fn settle(&self, delivery_id: u32, state: DeliveryState) -> Result<(), Error> {
+        self.settler().settle(delivery_id, state).await
+    }
+
+    /// Returns a handle settling the deliveries of the link without borrowing it, so that they
+    /// can be settled while the link waits for the next one.
+    pub(crate) fn settler(&self) -> DeliverySettler {
+        DeliverySettler {
+            connection: self.connection.clone(),
+            handle: self.handle,
+        }
+    }
+
+    pub(crate) async fn close(&self) -> Result<(), Error> {
+        self.connection.detach(self.handle).await
+    }
+}
+
+/// Settles the deliveries received on a receiver link.
+#[derive(Debug, Clone)]
+pub(crate) struct DeliverySettler {
+    connection: Connection,
+    handle: u32,
+}
+
+impl DeliverySettler {
+    pub(crate) async fn settle(&self, delivery_id: u32, state: DeliveryState) -> Result<(), Error> {
+        {
+            let connection_state = self.connection.inner.state.lock().unwrap();
+            if connection_state.closed {
+                return Err(connection_state.closed_error());
+            }
+            match connection_state.links.get(&self.handle) {
+                Some(link) => {
+                    if let Some(error) = link.detached_error() {
+                        return Err(error);
+                    }
+                }
+                None => return Err(Error::Protocol("the link is closed".to_owned())),
+            }
+        }
+        self.connection
+            .write(
+                &Performative::Disposition(Disposition {
+                    role: Role::Receiver,
+                    first: delivery_id,
+                    last: None,
+                    settled: true,
+                    state: Some(state),
+                }),
+                &[],
+            )
+            .await
+    }
+}
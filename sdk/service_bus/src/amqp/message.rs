@@ -0,0 +1,289 @@
+use super::codec::{map_get, Value};
+use crate::Error;
+use bytes::Bytes;
+
+const HEADER: u64 = 0x70;
+const DELIVERY_ANNOTATIONS: u64 = 0x71;
+const MESSAGE_ANNOTATIONS: u64 = 0x72;
+const PROPERTIES: u64 = 0x73;
+const APPLICATION_PROPERTIES: u64 = 0x74;
+const DATA: u64 = 0x75;
+const AMQP_SEQUENCE: u64 = 0x76;
+const AMQP_VALUE: u64 = 0x77;
+const FOOTER: u64 = 0x78;
+
+/// The transport headers of a message.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Header {
+    pub(crate) durable: bool,
+    pub(crate) priority: Option<u8>,
+    /// Time to live in milliseconds.
+    pub(crate) ttl: Option<u32>,
+    pub(crate) first_acquirer: bool,
+    pub(crate) delivery_count: u32,
+}
+
+/// The immutable properties of a message.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Properties {
+    pub(crate) message_id: Option<Value>,
+    pub(crate) user_id: Option<Bytes>,
+    pub(crate) to: Option<String>,
+    pub(crate) subject: Option<String>,
+    pub(crate) reply_to: Option<String>,
+    pub(crate) correlation_id: Option<Value>,
+    pub(crate) content_type: Option<String>,
+    pub(crate) content_encoding: Option<String>,
+    pub(crate) absolute_expiry_time: Option<i64>,
+    pub(crate) creation_time: Option<i64>,
+    pub(crate) group_id: Option<String>,
+    pub(crate) group_sequence: Option<u32>,
+    pub(crate) reply_to_group_id: Option<String>,
+}
+
+impl Properties {
+    fn is_empty(&self) -> bool {
+        self == &Properties::default()
+    }
+}
+
+/// The body of a message.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Body {
+    Data(Vec<Bytes>),
+    Sequence(Vec<Value>),
+    Value(Value),
+}
+
+impl Default for Body {
+    fn default() -> Self {
+        Body::Data(Vec::new())
+    }
+}
+
+/// An AMQP message, made of its sections.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Message {
+    pub(crate) header: Option<Header>,
+    pub(crate) delivery_annotations: Vec<(Value, Value)>,
+    pub(crate) message_annotations: Vec<(Value, Value)>,
+    pub(crate) properties: Properties,
+    pub(crate) application_properties: Vec<(Value, Value)>,
+    pub(crate) body: Body,
+}
+
+impl Message {
+    pub(crate) fn with_data(data: impl Into<Bytes>) -> Self {
+        Self {
+            body: Body::Data(vec![data.into()]),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn with_value(value: Value) -> Self {
+        Self {
+            body: Body::Value(value),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the message annotation `key`.
+    pub(crate) fn annotation(&self, key: &str) -> Option<&Value> {
+        map_get(&self.message_annotations, key)
+    }
+
+    /// Returns the application property `key`.
+    pub(crate) fn application_property(&self, key: &str) -> Option<&Value> {
+        map_get(&self.application_properties, key)
+    }
+
+    /// Returns the concatenation of the data sections of the body.
+    pub(crate) fn data(&self) -> Bytes {
+        match &self.body {
+            Body::Data(sections) if sections.len() == 1 => sections[0].clone(),
+            Body::Data(sections) => sections.concat().into(),
+            _ => Bytes::new(),
+        }
+    }
+
+    pub(crate) fn encode(&self, buf: &mut Vec<u8>) {
+        if let Some(header) = &self.header {
+            let mut fields = vec![
+                Value::Bool(header.durable),
+                header.priority.map(Value::Ubyte).into(),
+                header.ttl.into(),
+                Value::Bool(header.first_acquirer),
+                Value::Uint(header.delivery_count),
+            ];
+            while fields.last().map(Value::is_null).unwrap_or(false) {
+                fields.pop();
+            }
+            Value::described(HEADER, Value::List(fields)).encode(buf);
+        }
+        if !self.delivery_annotations.is_empty() {
+            Value::described(
+                DELIVERY_ANNOTATIONS,
+                Value::Map(self.delivery_annotations.clone()),
+            )
+            .encode(buf);
+        }
+        if !self.message_annotations.is_empty() {
+            Value::described(
+                MESSAGE_ANNOTATIONS,
+                Value::Map(self.message_annotations.clone()),
+            )
+            .encode(buf);
+        }
+        if !self.properties.is_empty() {
+            let properties = &self.properties;
+            let mut fields = vec![
+                properties.message_id.clone().into(),
+                properties.user_id.clone().into(),
+                properties.to.clone().into(),
+                properties.subject.clone().into(),
+                properties.reply_to.clone().into(),
+                properties.correlation_id.clone().into(),
+                properties.content_type.clone().map(Value::Symbol).into(),
+                properties
+                    .content_encoding
+                    .clone()
+                    .map(Value::Symbol)
+                    .into(),
+                properties.absolute_expiry_time.map(Value::Timestamp).into(),
+                properties.creation_time.map(Value::Timestamp).into(),
+                properties.group_id.clone().into(),
+                properties.group_sequence.into(),
+                properties.reply_to_group_id.clone().into(),
+            ];
+            while fields.last().map(Value::is_null).unwrap_or(false) {
+                fields.pop();
+            }
+            Value::described(PROPERTIES, Value::List(fields)).encode(buf);
+        }
+        if !self.application_properties.is_empty() {
+            Value::described(
+                APPLICATION_PROPERTIES,
+                Value::Map(self.application_properties.clone()),
+            )
+            .encode(buf);
+        }
+        match &self.body {
+            Body::Data(sections) => sections
+                .iter()
+                .for_each(|data| Value::described(DATA, data.clone().into()).encode(buf)),
+            Body::Sequence(values) => {
+                Value::described(AMQP_SEQUENCE, Value::List(values.clone())).encode(buf)
+            }
+            Body::Value(value) => Value::described(AMQP_VALUE, value.clone()).encode(buf),
+        }
+    }
+
+    pub(crate) fn to_bytes(&self) -> Bytes {
+        let mut buf = Vec::new();
+        self.encode(&mut buf);
+        buf.into()
+    }
+
+    pub(crate) fn decode(mut buf: &[u8]) -> Result<Self, Error> {
+        let mut message = Message::default();
+        let mut data = Vec::new();
+        while !buf.is_empty() {
+            let (code, value) = Value::decode(&mut buf)?
+                .into_described()
+                .ok_or_else(|| Error::Decode("expected a message section".to_owned()))?;
+            match (code, value) {
+                (HEADER, value) => {
+                    let mut fields = value.into_list().unwrap_or_default().into_iter();
+                    let mut next = || fields.next().unwrap_or(Value::Null);
+                    message.header = Some(Header {
+                        durable: next().as_bool().unwrap_or(false),
+                        priority: next().as_u32().map(|priority| priority as u8),
+                        ttl: next().as_u32(),
+                        first_acquirer: next().as_bool().unwrap_or(false),
+                        delivery_count: next().as_u32().unwrap_or(0),
+                    });
+                }
+                (DELIVERY_ANNOTATIONS, Value::Map(map)) => message.delivery_annotations = map,
+                (MESSAGE_ANNOTATIONS, Value::Map(map)) => message.message_annotations = map,
+                (PROPERTIES, value) => {
+                    let mut fields = value.into_list().unwrap_or_default().into_iter();
+                    let mut next = || fields.next().unwrap_or(Value::Null);
+                    let string = |value: Value| value.as_str().map(ToOwned::to_owned);
+                    let optional = |value: Value| Some(value).filter(|value| !value.is_null());
+                    message.properties = Properties {
+                        message_id: optional(next()),
+                        user_id: next().as_binary().cloned(),
+                        to: string(next()),
+                        subject: string(next()),
+                        reply_to: string(next()),
+                        correlation_id: optional(next()),
+                        content_type: string(next()),
+                        content_encoding: string(next()),
+                        absolute_expiry_time: next().as_timestamp(),
+                        creation_time: next().as_timestamp(),
+                        group_id: string(next()),
+                        group_sequence: next().as_u32(),
+                        reply_to_group_id: string(next()),
+                    };
+                }
+                (APPLICATION_PROPERTIES, Value::Map(map)) => message.application_properties = map,
+                (DATA, Value::Binary(bytes)) => data.push(bytes),
+                (AMQP_SEQUENCE, Value::List(values)) => match &mut message.body {
+                    Body::Sequence(sequence) => sequence.extend(values),
+                    body => *body = Body::Sequence(values),
+                },
+                (AMQP_VALUE, value) => message.body = Body::Value(value),
+                (DELIVERY_ANNOTATIONS, _)
+                | (MESSAGE_ANNOTATIONS, _)
+                | (APPLICATION_PROPERTIES, _)
+                | (FOOTER, _) => {}
+                (code, _) => {
+                    return Err(Error::Decode(format!(
+                        "unexpected message section {:#x}",
+                        code
+                    )))
+                }
+            }
+        }
+        if !data.is_empty() {
+            message.body = Body::Data(data);
+        }
+        Ok(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_roundtrip() {
+        let mut message = Message::with_data(&b"hello"[..]);
+        message.header = Some(Header {
+            durable: true,
+            ttl: Some(1000),
+            ..Default::default()
+        });
+        message.message_annotations = vec![(Value::symbol("x-opt-partition-key"), "pk".into())];
+        message.properties.message_id = Some("id".into());
+        message.properties.content_type = Some("text/plain".to_owned());
+        message.properties.creation_time = Some(1_600_000_000_000);
+        message.application_properties = vec![("count".into(), Value::Int(3))];
+
+        let decoded = Message::decode(&message.to_bytes()).unwrap();
+        assert_eq!(decoded, message);
+        assert_eq!(decoded.data(), Bytes::from_static(b"hello"));
+        assert_eq!(
+            decoded
+                .annotation("x-opt-partition-key")
+                .and_then(Value::as_str),
+            Some("pk")
+        );
+        assert_eq!(
+            decoded
+                .application_property("count")
+                .and_then(Value::as_i64),
+            Some(3)
+        );
+    }
+}
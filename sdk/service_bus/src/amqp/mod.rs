@@ -0,0 +1,13 @@
+//! A minimal AMQP 1.0 client: the type system, framing, a connection with a single session,
+//! sender and receiver links, and the request/response pattern used by the `$cbs` and
+//! `$management` nodes of Service Bus and Event Hubs.
+pub(crate) mod cbs;
+mod codec;
+pub(crate) mod connection;
+pub(crate) mod message;
+pub(crate) mod performatives;
+pub(crate) mod request_response;
+#[cfg(test)]
+pub(crate) mod test_broker;
+
+pub use codec::Value;
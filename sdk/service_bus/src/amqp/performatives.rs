@@ -0,0 +1,702 @@
+use super::codec::Value;
+use crate::Error;
+use bytes::Bytes;
+
+pub(crate) const OPEN: u64 = 0x10;
+pub(crate) const BEGIN: u64 = 0x11;
+pub(crate) const ATTACH: u64 = 0x12;
+pub(crate) const FLOW: u64 = 0x13;
+pub(crate) const TRANSFER: u64 = 0x14;
+pub(crate) const DISPOSITION: u64 = 0x15;
+pub(crate) const DETACH: u64 = 0x16;
+pub(crate) const END: u64 = 0x17;
+pub(crate) const CLOSE: u64 = 0x18;
+const ERROR: u64 = 0x1d;
+const RECEIVED: u64 = 0x23;
+const ACCEPTED: u64 = 0x24;
+const REJECTED: u64 = 0x25;
+const RELEASED: u64 = 0x26;
+const MODIFIED: u64 = 0x27;
+const SOURCE: u64 = 0x28;
+const TARGET: u64 = 0x29;
+
+pub(crate) const SASL_MECHANISMS: u64 = 0x40;
+pub(crate) const SASL_INIT: u64 = 0x41;
+pub(crate) const SASL_OUTCOME: u64 = 0x44;
+
+/// Reads the fields of a performative or of a composite type out of a list.
+struct Fields(std::vec::IntoIter<Value>);
+
+impl Fields {
+    fn new(value: Value) -> Result<Self, Error> {
+        value
+            .into_list()
+            .map(|list| Fields(list.into_iter()))
+            .ok_or_else(|| Error::Decode("expected a list of fields".to_owned()))
+    }
+
+    fn next(&mut self) -> Value {
+        self.0.next().unwrap_or(Value::Null)
+    }
+
+    fn string(&mut self) -> Option<String> {
+        self.next().as_str().map(ToOwned::to_owned)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.next().as_u32()
+    }
+
+    fn bool(&mut self) -> Option<bool> {
+        self.next().as_bool()
+    }
+}
+
+/// Builds the list of a composite type, dropping trailing `null` fields.
+fn composite(code: u64, mut fields: Vec<Value>) -> Value {
+    while fields.last().map(Value::is_null).unwrap_or(false) {
+        fields.pop();
+    }
+    Value::described(code, Value::List(fields))
+}
+
+/// An AMQP error condition, as carried by detach, end, close and rejected outcomes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct AmqpError {
+    pub(crate) condition: String,
+    pub(crate) description: Option<String>,
+    pub(crate) info: Vec<(Value, Value)>,
+}
+
+impl AmqpError {
+    fn to_value(&self) -> Value {
+        composite(
+            ERROR,
+            vec![
+                Value::symbol(self.condition.clone()),
+                self.description.clone().into(),
+                if self.info.is_empty() {
+                    Value::Null
+                } else {
+                    Value::Map(self.info.clone())
+                },
+            ],
+        )
+    }
+
+    fn from_value(value: Value) -> Result<Option<Self>, Error> {
+        if value.is_null() {
+            return Ok(None);
+        }
+        let (_, fields) = value
+            .into_described()
+            .ok_or_else(|| Error::Decode("expected an error".to_owned()))?;
+        let mut fields = Fields::new(fields)?;
+        Ok(Some(Self {
+            condition: fields.string().unwrap_or_default(),
+            description: fields.string(),
+            info: match fields.next() {
+                Value::Map(info) => info,
+                _ => Vec::new(),
+            },
+        }))
+    }
+}
+
+impl From<AmqpError> for Error {
+    fn from(error: AmqpError) -> Self {
+        Error::Amqp {
+            condition: error.condition,
+            description: error.description,
+        }
+    }
+}
+
+/// The state of a delivery, as set by a disposition or a transfer.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum DeliveryState {
+    Received,
+    Accepted,
+    Rejected(Option<AmqpError>),
+    Released,
+    Modified {
+        delivery_failed: bool,
+        undeliverable_here: bool,
+        message_annotations: Vec<(Value, Value)>,
+    },
+}
+
+impl DeliveryState {
+    pub(crate) fn to_value(&self) -> Value {
+        match self {
+            DeliveryState::Received => composite(RECEIVED, vec![Value::Uint(0), Value::Ulong(0)]),
+            DeliveryState::Accepted => composite(ACCEPTED, Vec::new()),
+            DeliveryState::Rejected(error) => composite(
+                REJECTED,
+                vec![error
+                    .as_ref()
+                    .map(AmqpError::to_value)
+                    .unwrap_or(Value::Null)],
+            ),
+            DeliveryState::Released => composite(RELEASED, Vec::new()),
+            DeliveryState::Modified {
+                delivery_failed,
+                undeliverable_here,
+                message_annotations,
+            } => composite(
+                MODIFIED,
+                vec![
+                    Value::Bool(*delivery_failed),
+                    Value::Bool(*undeliverable_here),
+                    if message_annotations.is_empty() {
+                        Value::Null
+                    } else {
+                        Value::Map(message_annotations.clone())
+                    },
+                ],
+            ),
+        }
+    }
+
+    fn from_value(value: Value) -> Result<Option<Self>, Error> {
+        if value.is_null() {
+            return Ok(None);
+        }
+        let (code, fields) = value
+            .into_described()
+            .ok_or_else(|| Error::Decode("expected a delivery state".to_owned()))?;
+        let mut fields = Fields::new(fields)?;
+        Ok(Some(match code {
+            RECEIVED => DeliveryState::Received,
+            ACCEPTED => DeliveryState::Accepted,
+            REJECTED => DeliveryState::Rejected(AmqpError::from_value(fields.next())?),
+            RELEASED => DeliveryState::Released,
+            MODIFIED => DeliveryState::Modified {
+                delivery_failed: fields.bool().unwrap_or(false),
+                undeliverable_here: fields.bool().unwrap_or(false),
+                message_annotations: match fields.next() {
+                    Value::Map(annotations) => annotations,
+                    _ => Vec::new(),
+                },
+            },
+            code => return Err(Error::Decode(format!("unknown delivery state {:#x}", code))),
+        }))
+    }
+}
+
+/// The source or target terminus of a link.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Terminus {
+    pub(crate) address: Option<String>,
+    /// The filter set of a source.
+    pub(crate) filter: Vec<(Value, Value)>,
+}
+
+impl Terminus {
+    pub(crate) fn new(address: impl Into<String>) -> Self {
+        Self {
+            address: Some(address.into()),
+            filter: Vec::new(),
+        }
+    }
+
+    fn to_value(&self, code: u64) -> Value {
+        let mut fields = vec![self.address.clone().into()];
+        if !self.filter.is_empty() {
+            fields.resize(7, Value::Null);
+            fields.push(Value::Map(self.filter.clone()));
+        }
+        composite(code, fields)
+    }
+
+    fn from_value(value: Value) -> Result<Option<Self>, Error> {
+        if value.is_null() {
+            return Ok(None);
+        }
+        let (code, fields) = value
+            .into_described()
+            .ok_or_else(|| Error::Decode("expected a terminus".to_owned()))?;
+        let mut fields = Fields::new(fields)?;
+        let address = fields.string();
+        let filter = if code == SOURCE {
+            (0..6).for_each(|_| {
+                fields.next();
+            });
+            match fields.next() {
+                Value::Map(filter) => filter,
+                _ => Vec::new(),
+            }
+        } else {
+            Vec::new()
+        };
+        Ok(Some(Self { address, filter }))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Open {
+    pub(crate) container_id: String,
+    pub(crate) hostname: Option<String>,
+    pub(crate) max_frame_size: Option<u32>,
+    pub(crate) channel_max: Option<u16>,
+    pub(crate) idle_time_out: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Begin {
+    pub(crate) remote_channel: Option<u16>,
+    pub(crate) next_outgoing_id: u32,
+    pub(crate) incoming_window: u32,
+    pub(crate) outgoing_window: u32,
+    pub(crate) handle_max: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Role {
+    Sender,
+    Receiver,
+}
+
+impl Role {
+    fn from_bool(role: bool) -> Self {
+        if role {
+            Role::Receiver
+        } else {
+            Role::Sender
+        }
+    }
+
+    fn to_bool(self) -> bool {
+        self == Role::Receiver
+    }
+}
+
+/// Whether the sender of a link settles deliveries before sending them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum SenderSettleMode {
+    Unsettled = 0,
+    Settled = 1,
+    Mixed = 2,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Attach {
+    pub(crate) name: String,
+    pub(crate) handle: u32,
+    pub(crate) role: Role,
+    pub(crate) snd_settle_mode: Option<SenderSettleMode>,
+    pub(crate) source: Option<Terminus>,
+    pub(crate) target: Option<Terminus>,
+    pub(crate) initial_delivery_count: Option<u32>,
+    pub(crate) max_message_size: Option<u64>,
+    pub(crate) properties: Vec<(Value, Value)>,
+}
+
+impl Attach {
+    pub(crate) fn new(name: impl Into<String>, handle: u32, role: Role) -> Self {
+        Self {
+            name: name.into(),
+            handle,
+            role,
+            snd_settle_mode: None,
+            source: None,
+            target: None,
+            initial_delivery_count: None,
+            max_message_size: None,
+            properties: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Flow {
+    pub(crate) next_incoming_id: Option<u32>,
+    pub(crate) incoming_window: u32,
+    pub(crate) next_outgoing_id: u32,
+    pub(crate) outgoing_window: u32,
+    pub(crate) handle: Option<u32>,
+    pub(crate) delivery_count: Option<u32>,
+    pub(crate) link_credit: Option<u32>,
+    pub(crate) drain: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Transfer {
+    pub(crate) handle: u32,
+    pub(crate) delivery_id: Option<u32>,
+    pub(crate) delivery_tag: Option<Bytes>,
+    pub(crate) message_format: Option<u32>,
+    pub(crate) settled: Option<bool>,
+    pub(crate) more: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Disposition {
+    pub(crate) role: Role,
+    pub(crate) first: u32,
+    pub(crate) last: Option<u32>,
+    pub(crate) settled: bool,
+    pub(crate) state: Option<DeliveryState>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Detach {
+    pub(crate) handle: u32,
+    pub(crate) closed: bool,
+    pub(crate) error: Option<AmqpError>,
+}
+
+/// The frame bodies exchanged once a connection is open.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Performative {
+    Open(Open),
+    Begin(Begin),
+    Attach(Attach),
+    Flow(Flow),
+    Transfer(Transfer),
+    Disposition(Disposition),
+    Detach(Detach),
+    End(Option<AmqpError>),
+    Close(Option<AmqpError>),
+}
+
+impl Performative {
+    pub(crate) fn to_value(&self) -> Value {
+        fn error_value(error: &Option<AmqpError>) -> Value {
+            error
+                .as_ref()
+                .map(AmqpError::to_value)
+                .unwrap_or(Value::Null)
+        }
+
+        match self {
+            Performative::Open(open) => composite(
+                OPEN,
+                vec![
+                    open.container_id.clone().into(),
+                    open.hostname.clone().into(),
+                    open.max_frame_size.into(),
+                    open.channel_max.map(Value::Ushort).into(),
+                    open.idle_time_out.into(),
+                ],
+            ),
+            Performative::Begin(begin) => composite(
+                BEGIN,
+                vec![
+                    begin.remote_channel.map(Value::Ushort).into(),
+                    begin.next_outgoing_id.into(),
+                    begin.incoming_window.into(),
+                    begin.outgoing_window.into(),
+                    begin.handle_max.into(),
+                ],
+            ),
+            Performative::Attach(attach) => composite(
+                ATTACH,
+                vec![
+                    attach.name.clone().into(),
+                    attach.handle.into(),
+                    attach.role.to_bool().into(),
+                    attach
+                        .snd_settle_mode
+                        .map(|mode| Value::Ubyte(mode as u8))
+                        .into(),
+                    Value::Null,
+                    attach
+                        .source
+                        .as_ref()
+                        .map(|source| source.to_value(SOURCE))
+                        .into(),
+                    attach
+                        .target
+                        .as_ref()
+                        .map(|target| target.to_value(TARGET))
+                        .into(),
+                    Value::Null,
+                    Value::Null,
+                    attach.initial_delivery_count.into(),
+                    attach.max_message_size.into(),
+                    Value::Null,
+                    Value::Null,
+                    if attach.properties.is_empty() {
+                        Value::Null
+                    } else {
+                        Value::Map(attach.properties.clone())
+                    },
+                ],
+            ),
+            Performative::Flow(flow) => composite(
+                FLOW,
+                vec![
+                    flow.next_incoming_id.into(),
+                    flow.incoming_window.into(),
+                    flow.next_outgoing_id.into(),
+                    flow.outgoing_window.into(),
+                    flow.handle.into(),
+                    flow.delivery_count.into(),
+                    flow.link_credit.into(),
+                    Value::Null,
+                    if flow.drain {
+                        Value::Bool(true)
+                    } else {
+                        Value::Null
+                    },
+                ],
+            ),
+            Performative::Transfer(transfer) => composite(
+                TRANSFER,
+                vec![
+                    transfer.handle.into(),
+                    transfer.delivery_id.into(),
+                    transfer.delivery_tag.clone().into(),
+                    transfer.message_format.into(),
+                    transfer.settled.into(),
+                    if transfer.more {
+                        Value::Bool(true)
+                    } else {
+                        Value::Null
+                    },
+                ],
+            ),
+            Performative::Disposition(disposition) => composite(
+                DISPOSITION,
+                vec![
+                    disposition.role.to_bool().into(),
+                    disposition.first.into(),
+                    disposition.last.into(),
+                    disposition.settled.into(),
+                    disposition
+                        .state
+                        .as_ref()
+                        .map(DeliveryState::to_value)
+                        .into(),
+                ],
+            ),
+            Performative::Detach(detach) => composite(
+                DETACH,
+                vec![
+                    detach.handle.into(),
+                    detach.closed.into(),
+                    error_value(&detach.error),
+                ],
+            ),
+            Performative::End(error) => composite(END, vec![error_value(error)]),
+            Performative::Close(error) => composite(CLOSE, vec![error_value(error)]),
+        }
+    }
+
+    pub(crate) fn from_value(value: Value) -> Result<Self, Error> {
+        let (code, fields) = value
+            .into_described()
+            .ok_or_else(|| Error::Decode("expected a performative".to_owned()))?;
+        let mut fields = Fields::new(fields)?;
+        let missing = |field: &str| Error::Decode(format!("missing mandatory field {}", field));
+        Ok(match code {
+            OPEN => Performative::Open(Open {
+                container_id: fields.string().ok_or_else(|| missing("container-id"))?,
+                hostname: fields.string(),
+                max_frame_size: fields.u32(),
+                channel_max: fields.u32().map(|max| max as u16),
+                idle_time_out: fields.u32(),
+            }),
+            BEGIN => Performative::Begin(Begin {
+                remote_channel: fields.u32().map(|channel| channel as u16),
+                next_outgoing_id: fields.u32().ok_or_else(|| missing("next-outgoing-id"))?,
+                incoming_window: fields.u32().unwrap_or(0),
+                outgoing_window: fields.u32().unwrap_or(0),
+                handle_max: fields.u32(),
+            }),
+            ATTACH => {
+                let name = fields.string().ok_or_else(|| missing("name"))?;
+                let handle = fields.u32().ok_or_else(|| missing("handle"))?;
+                let role = Role::from_bool(fields.bool().ok_or_else(|| missing("role"))?);
+                let snd_settle_mode = fields.u32().map(|mode| match mode {
+                    0 => SenderSettleMode::Unsettled,
+                    1 => SenderSettleMode::Settled,
+                    _ => SenderSettleMode::Mixed,
+                });
+                fields.next();
+                let source = Terminus::from_value(fields.next())?;
+                let target = Terminus::from_value(fields.next())?;
+                fields.next();
+                fields.next();
+                let initial_delivery_count = fields.u32();
+                let max_message_size = fields.next().as_u64();
+                fields.next();
+                fields.next();
+                let properties = match fields.next() {
+                    Value::Map(properties) => properties,
+                    _ => Vec::new(),
+                };
+                Performative::Attach(Attach {
+                    name,
+                    handle,
+                    role,
+                    snd_settle_mode,
+                    source,
+                    target,
+                    initial_delivery_count,
+                    max_message_size,
+                    properties,
+                })
+            }
+            FLOW => Performative::Flow(Flow {
+                next_incoming_id: fields.u32(),
+                incoming_window: fields.u32().unwrap_or(0),
+                next_outgoing_id: fields.u32().unwrap_or(0),
+                outgoing_window: fields.u32().unwrap_or(0),
+                handle: fields.u32(),
+                delivery_count: fields.u32(),
+                link_credit: fields.u32(),
+                drain: {
+                    fields.next();
+                    fields.bool().unwrap_or(false)
+                },
+            }),
+            TRANSFER => Performative::Transfer(Transfer {
+                handle: fields.u32().ok_or_else(|| missing("handle"))?,
+                delivery_id: fields.u32(),
+                delivery_tag: fields.next().as_binary().cloned(),
+                message_format: fields.u32(),
+                settled: fields.bool(),
+                more: fields.bool().unwrap_or(false),
+            }),
+            DISPOSITION => Performative::Disposition(Disposition {
+                role: Role::from_bool(fields.bool().ok_or_else(|| missing("role"))?),
+                first: fields.u32().ok_or_else(|| missing("first"))?,
+                last: fields.u32(),
+                settled: fields.bool().unwrap_or(false),
+                state: DeliveryState::from_value(fields.next())?,
+            }),
+            DETACH => Performative::Detach(Detach {
+                handle: fields.u32().ok_or_else(|| missing("handle"))?,
+                closed: fields.bool().unwrap_or(false),
+                error: AmqpError::from_value(fields.next())?,
+            }),
+            END => Performative::End(AmqpError::from_value(fields.next())?),
+            CLOSE => Performative::Close(AmqpError::from_value(fields.next())?),
+            code => return Err(Error::Decode(format!("unknown performative {:#x}", code))),
+        })
+    }
+}
+
+/// The frame bodies of the SASL negotiation.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SaslFrame {
+    Mechanisms(Vec<String>),
+    Init {
+        mechanism: String,
+        initial_response: Option<Bytes>,
+        hostname: Option<String>,
+    },
+    Outcome(u8),
+}
+
+impl SaslFrame {
+    pub(crate) fn to_value(&self) -> Value {
+        match self {
+            SaslFrame::Mechanisms(mechanisms) => composite(
+                SASL_MECHANISMS,
+                vec![Value::Array(
+                    mechanisms.iter().cloned().map(Value::Symbol).collect(),
+                )],
+            ),
+            SaslFrame::Init {
+                mechanism,
+                initial_response,
+                hostname,
+            } => composite(
+                SASL_INIT,
+                vec![
+                    Value::symbol(mechanism.clone()),
+                    initial_response.clone().into(),
+                    hostname.clone().into(),
+                ],
+            ),
+            SaslFrame::Outcome(code) => composite(SASL_OUTCOME, vec![Value::Ubyte(*code)]),
+        }
+    }
+
+    pub(crate) fn from_value(value: Value) -> Result<Self, Error> {
+        let (code, fields) = value
+            .into_described()
+            .ok_or_else(|| Error::Decode("expected a SASL frame".to_owned()))?;
+        let mut fields = Fields::new(fields)?;
+        Ok(match code {
+            SASL_MECHANISMS => SaslFrame::Mechanisms(
+                fields
+                    .next()
+                    .into_multiple()
+                    .iter()
+                    .filter_map(|mechanism| mechanism.as_str().map(ToOwned::to_owned))
+                    .collect(),
+            ),
+            SASL_INIT => SaslFrame::Init {
+                mechanism: fields.string().unwrap_or_default(),
+                initial_response: fields.next().as_binary().cloned(),
+                hostname: fields.string(),
+            },
+            SASL_OUTCOME => SaslFrame::Outcome(fields.u32().unwrap_or(1) as u8),
+            code => return Err(Error::Decode(format!("unknown SASL frame {:#x}", code))),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(performative: Performative) {
+        let mut buf = Vec::new();
+        performative.to_value().encode(&mut buf);
+        let decoded = Performative::from_value(Value::decode(&mut buf.as_slice()).unwrap());
+        assert_eq!(decoded.unwrap(), performative);
+    }
+
+    #[test]
+    fn performatives_roundtrip() {
+        roundtrip(Performative::Open(Open {
+            container_id: "container".to_owned(),
+            hostname: Some("localhost".to_owned()),
+            max_frame_size: Some(65536),
+            channel_max: None,
+            idle_time_out: Some(120_000),
+        }));
+        let mut attach = Attach::new("receiver", 3, Role::Receiver);
+        attach.snd_settle_mode = Some(SenderSettleMode::Settled);
+        attach.source = Some(Terminus {
+            address: Some("hub/ConsumerGroups/$default/Partitions/0".to_owned()),
+            filter: vec![(
+                Value::symbol("filter"),
+                Value::described(0x1, Value::String("x > 1".to_owned())),
+            )],
+        });
+        attach.target = Some(Terminus::new("receiver"));
+        attach.max_message_size = Some(1 << 20);
+        roundtrip(Performative::Attach(attach));
+        roundtrip(Performative::Transfer(Transfer {
+            handle: 1,
+            delivery_id: Some(5),
+            delivery_tag: Some(Bytes::from_static(b"tag")),
+            message_format: Some(0),
+            settled: Some(false),
+            more: true,
+        }));
+        roundtrip(Performative::Disposition(Disposition {
+            role: Role::Receiver,
+            first: 5,
+            last: Some(7),
+            settled: true,
+            state: Some(DeliveryState::Rejected(Some(AmqpError {
+                condition: "amqp:internal-error".to_owned(),
+                description: Some("boom".to_owned()),
+                info: vec![(Value::symbol("reason"), "test".into())],
+            }))),
+        }));
+        roundtrip(Performative::Detach(Detach {
+            handle: 1,
+            closed: true,
+            error: None,
+        }));
+        roundtrip(Performative::Close(None));
+    }
+}
@@ -0,0 +1,82 @@
+use super::codec::Value;
+use super::connection::{Connection, ReceiverLink, SenderLink};
+use super::message::Message;
+use super::performatives::{Attach, DeliveryState, Role, SenderSettleMode, Terminus};
+use crate::Error;
+use uuid::Uuid;
+
+/// A pair of links used to exchange requests and responses with a node of the remote peer,
+/// such as `$cbs` or `$management`.
+#[derive(Debug)]
+pub(crate) struct RequestResponseLink {
+    sender: SenderLink,
+    receiver: tokio::sync::Mutex<ReceiverLink>,
+    reply_to: String,
+}
+
+impl RequestResponseLink {
+    pub(crate) async fn attach(connection: &Connection, node: &str) -> Result<Self, Error> {
+        let id = Uuid::new_v4();
+        let reply_to = format!("{}-client-reply-to-{}", node.trim_start_matches('$'), id);
+
+        let mut attach = Attach::new(format!("{}-sender-{}", node, id), 0, Role::Sender);
+        attach.source = Some(Terminus::new(reply_to.clone()));
+        attach.target = Some(Terminus::new(node));
+        let sender = connection.attach_sender(attach).await?;
+
+        let mut attach = Attach::new(format!("{}-receiver-{}", node, id), 0, Role::Receiver);
+        attach.snd_settle_mode = Some(SenderSettleMode::Settled);
+        attach.source = Some(Terminus::new(node));
+        attach.target = Some(Terminus::new(reply_to.clone()));
+        let receiver = connection.attach_receiver(attach, 10).await?;
+
+        Ok(Self {
+            sender,
+            receiver: tokio::sync::Mutex::new(receiver),
+            reply_to,
+        })
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+
+    /// Sends `request` and waits for the response correlated with it.
+    pub(crate) async fn call(&self, mut request: Message) -> Result<Message, Error> {
+        // Requests are sent one at a time, so responses cannot be consumed by another caller.
+        let mut receiver = self.receiver.lock().await;
+        let message_id = Uuid::new_v4().to_string();
+        request.properties.message_id = Some(Value::String(message_id.clone()));
+        request.properties.reply_to = Some(self.reply_to.clone());
+        self.sender.send(0, request.to_bytes()).await?;
+        loop {
+            let delivery = receiver.recv().await?.ok_or(Error::ConnectionClosed)?;
+            if !delivery.settled {
+                receiver
+                    .settle(delivery.delivery_id, DeliveryState::Accepted)
+                    .await?;
+            }
+            let correlation_id = delivery.message.properties.correlation_id.as_ref();
+            if correlation_id.and_then(Value::as_str) == Some(message_id.as_str()) {
+                return Ok(delivery.message);
+            }
+            log::debug!("ignoring uncorrelated response {:?}", correlation_id);
+        }
+    }
+}
+
+/// Returns the status code and description of a response of the `$cbs` or `$management` nodes.
+pub(crate) fn response_status(response: &Message) -> (i32, String) {
+    let property = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| response.application_property(key))
+            .cloned()
+    };
+    let status_code = property(&["status-code", "statusCode"])
+        .and_then(|code| code.as_i64())
+        .unwrap_or(0) as i32;
+    let description = property(&["status-description", "statusDescription"])
+        .and_then(|description| description.as_str().map(ToOwned::to_owned))
+        .unwrap_or_default();
+    (status_code, description)
+}
@@ -0,0 +1,371 @@
+//! An in-process AMQP 1.0 broker standing in for the service in tests.
+//!
+//! The broker accepts any number of links: messages sent to a target address are recorded and,
+//! when a responder is registered for the address, answered on the links receiving from it.
+//! Messages queued for a source address are delivered to the links receiving from it.
+//!
+//! Like Service Bus, the broker delivers a message carrying an `x-opt-lock-token` annotation
+//! with its lock token as delivery tag, and answers the attach of a link filtering the next
+//! available session with the session of the first message queued for the address.
+use super::codec::Value;
+use super::connection::{
+    encode_frame, read_frame, RawFrame, AMQP_HEADER, FRAME_TYPE_AMQP, FRAME_TYPE_SASL,
+    MAX_FRAME_SIZE, SASL_HEADER,
+};
+use super::message::Message;
+use super::performatives::*;
+use crate::Error;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use url::Url;
+
+const LOCK_TOKEN_ANNOTATION: &str = "x-opt-lock-token";
+const SESSION_FILTER: &str = "com.microsoft:session-filter";
+
+type Responder = Box<dyn Fn(&Message) -> Message + Send>;
+
+#[derive(Default)]
+pub(crate) struct BrokerState {
+    /// The messages received on each target address, with their message format.
+    pub(crate) received: Vec<(String, u32, Message)>,
+    /// The messages waiting to be delivered to the links receiving from each source address.
+    pub(crate) queues: HashMap<String, VecDeque<Message>>,
+    /// The attach frames sent by the clients.
+    pub(crate) attaches: Vec<Attach>,
+    /// The dispositions sent by the clients.
+    pub(crate) dispositions: Vec<Disposition>,
+    responders: HashMap<String, Responder>,
+}
+
+pub(crate) struct TestBroker {
+    pub(crate) url: Url,
+    pub(crate) state: Arc<Mutex<BrokerState>>,
+}
+
+impl TestBroker {
+    pub(crate) async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("amqp://{}", listener.local_addr().unwrap())).unwrap();
+        let state = Arc::new(Mutex::new(BrokerState::default()));
+        let broker_state = state.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let state = broker_state.clone();
+                tokio::spawn(async move {
+                    if let Err(error) = serve(stream, state).await {
+                        log::debug!("test broker connection failed: {}", error);
+                    }
+                });
+            }
+        });
+        Self { url, state }
+    }
+
+    /// Answers the messages sent to `address` with the message returned by `responder`.
+    pub(crate) fn respond(
+        &self,
+        address: &str,
+        responder: impl Fn(&Message) -> Message + Send + 'static,
+    ) {
+        self.state
+            .lock()
+            .unwrap()
+            .responders
+            .insert(address.to_owned(), Box::new(responder));
+    }
+
+    /// Queues a message for the links receiving from `address`.
+    pub(crate) fn enqueue(&self, address: &str, message: Message) {
+        self.state
+            .lock()
+            .unwrap()
+            .queues
+            .entry(address.to_owned())
+            .or_default()
+            .push_back(message);
+    }
+
+    pub(crate) fn received(&self) -> Vec<(String, u32, Message)> {
+        self.state.lock().unwrap().received.clone()
+    }
+
+    pub(crate) fn attaches(&self) -> Vec<Attach> {
+        self.state.lock().unwrap().attaches.clone()
+    }
+}
+
+struct OutgoingLink {
+    address: String,
+    credit: u32,
+    delivery_count: u32,
+    settled: bool,
+}
+
+struct IncomingLink {
+    address: String,
+    partial: Vec<u8>,
+    first: Option<Transfer>,
+}
+
+async fn read_performative(
+    stream: &mut TcpStream,
+) -> Result<Option<(Performative, RawFrame)>, Error> {
+    loop {
+        let frame = read_frame(stream, MAX_FRAME_SIZE).await?;
+        if frame.body.is_empty() {
+            continue;
+        }
+        let (performative, _) = frame.performative()?;
+        return Ok(Some((performative, frame)));
+    }
+}
+
+async fn serve(mut stream: TcpStream, state: Arc<Mutex<BrokerState>>) -> Result<(), Error> {
+    let mut header = [0u8; 8];
+    stream.read_exact(&mut header).await?;
+    assert_eq!(header, SASL_HEADER);
+    let mut buf = SASL_HEADER.to_vec();
+    let mechanisms = SaslFrame::Mechanisms(vec!["ANONYMOUS".to_owned()]);
+    encode_frame(&mut buf, FRAME_TYPE_SASL, 0, &mechanisms.to_value(), &[]);
+    stream.write_all(&buf).await?;
+    read_frame(&mut stream, MAX_FRAME_SIZE).await?.sasl()?;
+    buf.clear();
+    encode_frame(
+        &mut buf,
+        FRAME_TYPE_SASL,
+        0,
+        &SaslFrame::Outcome(0).to_value(),
+        &[],
+    );
+    stream.write_all(&buf).await?;
+    stream.read_exact(&mut header).await?;
+    assert_eq!(header, AMQP_HEADER);
+
+    let mut outgoing: HashMap<u32, OutgoingLink> = HashMap::new();
+    let mut incoming: HashMap<u32, IncomingLink> = HashMap::new();
+    let mut next_delivery_id = 0u32;
+    buf.clear();
+    buf.extend_from_slice(&AMQP_HEADER);
+
+    while let Some((performative, frame)) = read_performative(&mut stream).await? {
+        let mut replies = Vec::new();
+        match performative {
+            Performative::Open(_) => replies.push(Performative::Open(Open {
+                container_id: "test-broker".to_owned(),
+                max_frame_size: Some(MAX_FRAME_SIZE),
+                ..Default::default()
+            })),
+            Performative::Begin(begin) => replies.push(Performative::Begin(Begin {
+                remote_channel: Some(0),
+                next_outgoing_id: 0,
+                incoming_window: begin.outgoing_window,
+                outgoing_window: begin.incoming_window,
+                handle_max: None,
+            })),
+            Performative::Attach(attach) => {
+                state.lock().unwrap().attaches.push(attach.clone());
+                let mut reply = attach.clone();
+                match attach.role {
+                    Role::Sender => {
+                        let address = attach
+                            .target
+                            .as_ref()
+                            .and_then(|target| target.address.clone())
+                            .unwrap_or_default();
+                        incoming.insert(
+                            attach.handle,
+                            IncomingLink {
+                                address,
+                                partial: Vec::new(),
+                                first: None,
+                            },
+                        );
+                        reply.role = Role::Receiver;
+                        reply.max_message_size = Some(1024 * 1024);
+                        replies.push(Performative::Attach(reply));
+                        replies.push(Performative::Flow(Flow {
+                            next_incoming_id: Some(0),
+                            incoming_window: 5000,
+                            next_outgoing_id: next_delivery_id,
+                            outgoing_window: 5000,
+                            handle: Some(attach.handle),
+                            delivery_count: Some(0),
+                            link_credit: Some(100),
+                            drain: false,
+                        }));
+                    }
+                    Role::Receiver => {
+                        let address = attach
+                            .source
+                            .as_ref()
+                            .and_then(|source| source.address.clone())
+                            .unwrap_or_default();
+                        outgoing.insert(
+                            attach.handle,
+                            OutgoingLink {
+                                address,
+                                credit: 0,
+                                delivery_count: 0,
+                                settled: attach.snd_settle_mode == Some(SenderSettleMode::Settled),
+                            },
+                        );
+                        reply.role = Role::Sender;
+                        reply.initial_delivery_count = Some(0);
+                        if let Some(source) = &mut reply.source {
+                            fill_session_filter(source, &state.lock().unwrap());
+                        }
+                        replies.push(Performative::Attach(reply));
+                    }
+                }
+            }
+            Performative::Flow(flow) => {
+                if let Some(link) = flow.handle.and_then(|handle| outgoing.get_mut(&handle)) {
+                    link.credit = flow
+                        .delivery_count
+                        .unwrap_or(0)
+                        .wrapping_add(flow.link_credit.unwrap_or(0))
+                        .wrapping_sub(link.delivery_count);
+                }
+            }
+            Performative::Transfer(transfer) => {
+                let (_, payload) = frame.performative()?;
+                let link = incoming.get_mut(&transfer.handle).unwrap();
+                link.partial.extend_from_slice(&payload);
+                let more = transfer.more;
+                if link.first.is_none() {
+                    link.first = Some(transfer);
+                }
+                if !more {
+                    let first = link.first.take().unwrap();
+                    let message = Message::decode(&std::mem::take(&mut link.partial))?;
+                    let mut state = state.lock().unwrap();
+                    state.received.push((
+                        link.address.clone(),
+                        first.message_format.unwrap_or(0),
+                        message.clone(),
+                    ));
+                    if first.settled != Some(true) {
+                        replies.push(Performative::Disposition(Disposition {
+                            role: Role::Receiver,
+                            first: first.delivery_id.unwrap_or(0),
+                            last: None,
+                            settled: true,
+                            state: Some(DeliveryState::Accepted),
+                        }));
+                    }
+                    if let Some(responder) = state.responders.get(&link.address) {
+                        let mut response = responder(&message);
+                        response.properties.correlation_id = message.properties.message_id.clone();
+                        let address = link.address.clone();
+                        state.queues.entry(address).or_default().push_back(response);
+                    }
+                }
+            }
+            Performative::Disposition(disposition) => {
+                state.lock().unwrap().dispositions.push(disposition);
+            }
+            Performative::Detach(detach) => {
+                outgoing.remove(&detach.handle);
+                incoming.remove(&detach.handle);
+                replies.push(Performative::Detach(Detach {
+                    handle: detach.handle,
+                    closed: true,
+                    error: None,
+                }));
+            }
+            Performative::End(_) | Performative::Close(_) => {
+                replies.push(Performative::Close(None));
+                for reply in replies {
+                    encode_frame(&mut buf, FRAME_TYPE_AMQP, 0, &reply.to_value(), &[]);
+                }
+                stream.write_all(&buf).await?;
+                return Ok(());
+            }
+        }
+        for reply in replies {
+            encode_frame(&mut buf, FRAME_TYPE_AMQP, 0, &reply.to_value(), &[]);
+        }
+
+        // Delivers the queued messages to the links with credit.
+        {
+            let mut state = state.lock().unwrap();
+            for (handle, link) in outgoing.iter_mut() {
+                while link.credit > 0 {
+                    let message = match state
+                        .queues
+                        .get_mut(&link.address)
+                        .and_then(VecDeque::pop_front)
+                    {
+                        Some(message) => message,
+                        None => break,
+                    };
+                    let delivery_tag = match message.annotation(LOCK_TOKEN_ANNOTATION) {
+                        Some(Value::Uuid(lock_token)) => {
+                            let (d1, d2, d3, d4) = lock_token.as_fields();
+                            [
+                                &d1.to_le_bytes()[..],
+                                &d2.to_le_bytes(),
+                                &d3.to_le_bytes(),
+                                d4,
+                            ]
+                            .concat()
+                        }
+                        _ => next_delivery_id.to_be_bytes().to_vec(),
+                    };
+                    let transfer = Performative::Transfer(Transfer {
+                        handle: *handle,
+                        delivery_id: Some(next_delivery_id),
+                        delivery_tag: Some(delivery_tag.into()),
+                        message_format: Some(0),
+                        settled: Some(link.settled),
+                        more: false,
+                    });
+                    encode_frame(
+                        &mut buf,
+                        FRAME_TYPE_AMQP,
+                        0,
+                        &transfer.to_value(),
+                        &message.to_bytes(),
+                    );
+                    next_delivery_id = next_delivery_id.wrapping_add(1);
+                    link.credit -= 1;
+                    link.delivery_count = link.delivery_count.wrapping_add(1);
+                }
+            }
+        }
+        stream.write_all(&buf).await?;
+        buf.clear();
+    }
+    Ok(())
+}
+
+/// Replaces the `null` session filter of `source`, which accepts the next available session,
+/// with the session of the first message queued for its address.
+fn fill_session_filter(source: &mut Terminus, state: &BrokerState) {
+    let session_id = source
+        .address
+        .as_ref()
+        .and_then(|address| state.queues.get(address))
+        .and_then(|queue| queue.front())
+        .and_then(|message| message.properties.group_id.clone());
+    for (key, value) in source.filter.iter_mut() {
+        if key.as_str() == Some(SESSION_FILTER) && value.is_null() {
+            if let Some(session_id) = &session_id {
+                *value = Value::String(session_id.clone());
+            }
+        }
+    }
+}
+
+/// Returns a response to a request of the `$cbs` or `$management` nodes with the given status
+/// and body.
+pub(crate) fn status_response(status_code: i32, body: Value) -> Message {
+    let mut response = Message::with_value(body);
+    response.application_properties = vec![
+        ("status-code".into(), Value::Int(status_code)),
+        ("status-description".into(), "OK".into()),
+    ];
+    response
+}
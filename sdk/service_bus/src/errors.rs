@@ -0,0 +1,57 @@
+/// An error raised by the clients of this crate.
+#[allow(missing_docs)]
+#[non_exhaustive]
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("tls error: {0}")]
+    Tls(#[from] native_tls::Error),
+    #[error("url parse error: {0}")]
+    Url(#[from] url::ParseError),
+    #[error("http error: {0}")]
+    Http(#[from] azure_core::HttpError),
+    #[error("failed to build the request: {0}")]
+    Request(#[from] http::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error("invalid endpoint: {0}")]
+    InvalidEndpoint(String),
+    #[error("cannot decode AMQP data: {0}")]
+    Decode(String),
+    #[error("AMQP protocol error: {0}")]
+    Protocol(String),
+    #[error("SASL authentication failed with outcome code {0}")]
+    SaslAuthentication(u8),
+    #[error("the remote peer reported {condition}: {}", description.as_deref().unwrap_or(""))]
+    Amqp {
+        condition: String,
+        description: Option<String>,
+    },
+    #[error("the AMQP connection is closed")]
+    ConnectionClosed,
+    #[error("authorization failed with status code {status_code}: {description}")]
+    Authorization {
+        status_code: i32,
+        description: String,
+    },
+    #[error("management request failed with status code {status_code}: {description}")]
+    Management {
+        status_code: i32,
+        description: String,
+    },
+    #[error("the message size of {size} bytes exceeds the maximum of {max_size} bytes")]
+    MessageTooLarge { size: usize, max_size: usize },
+    #[error("the operation timed out")]
+    Timeout,
+    #[error("cannot decode XML data: {0}")]
+    Xml(String),
+    #[error("the entity {0} does not exist")]
+    EntityNotFound(String),
+    #[error("checkpoint store error: {0}")]
+    CheckpointStore(Box<dyn std::error::Error + Send + Sync>),
+    #[error("event handler error: {0}")]
+    Handler(Box<dyn std::error::Error + Send + Sync>),
+}
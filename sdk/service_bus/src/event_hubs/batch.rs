@@ -0,0 +1,131 @@
+use super::event::{EventData, PARTITION_KEY_ANNOTATION};
+use crate::amqp::message::{Body, Message};
+use crate::amqp::Value;
+use azure_core::setters;
+use bytes::Bytes;
+
+/// The message format of an AMQP message whose data sections are each an encoded event.
+pub(crate) const BATCH_MESSAGE_FORMAT: u32 = 0x8001_3700;
+
+/// Options of [`EventHubProducerClient::create_batch`](super::EventHubProducerClient::create_batch).
+///
+/// Events are routed to `partition_id` when set, otherwise to the partition the service derives
+/// from `partition_key`, otherwise to a partition chosen by the service.
+#[derive(Debug, Clone, Default)]
+pub struct CreateBatchOptions {
+    pub(crate) partition_id: Option<String>,
+    pub(crate) partition_key: Option<String>,
+    pub(crate) max_size_in_bytes: Option<usize>,
+}
+
+impl CreateBatchOptions {
+    setters! {
+        partition_id: String => Some(partition_id),
+        partition_key: String => Some(partition_key),
+        max_size_in_bytes: usize => Some(max_size_in_bytes),
+    }
+}
+
+/// A set of events sent to an Event Hub in a single message, whose size cannot exceed the size
+/// limit of the link used to send it.
+#[derive(Debug, Clone)]
+pub struct EventDataBatch {
+    partition_id: Option<String>,
+    partition_key: Option<String>,
+    max_size_in_bytes: usize,
+    size_in_bytes: usize,
+    events: Vec<Bytes>,
+}
+
+impl EventDataBatch {
+    pub(crate) fn new(
+        partition_id: Option<String>,
+        partition_key: Option<String>,
+        max_size_in_bytes: usize,
+    ) -> Self {
+        let mut batch = Self {
+            partition_id,
+            partition_key,
+            max_size_in_bytes,
+            size_in_bytes: 0,
+            events: Vec::new(),
+        };
+        batch.size_in_bytes = batch.to_message().to_bytes().len();
+        batch
+    }
+
+    /// Adds `event` to the batch, returning `false` when the batch would exceed its maximum size.
+    pub fn try_add(&mut self, event: &EventData) -> bool {
+        let encoded = event.to_message(self.partition_key.as_deref()).to_bytes();
+        let size = self.size_in_bytes + data_section_size(encoded.len());
+        if size > self.max_size_in_bytes {
+            return false;
+        }
+        self.size_in_bytes = size;
+        self.events.push(encoded);
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// The size of the message sending the batch.
+    pub fn size_in_bytes(&self) -> usize {
+        self.size_in_bytes
+    }
+
+    pub fn max_size_in_bytes(&self) -> usize {
+        self.max_size_in_bytes
+    }
+
+    pub fn partition_id(&self) -> Option<&str> {
+        self.partition_id.as_deref()
+    }
+
+    pub fn partition_key(&self) -> Option<&str> {
+        self.partition_key.as_deref()
+    }
+
+    pub(crate) fn to_message(&self) -> Message {
+        let mut message = Message {
+            body: Body::Data(self.events.clone()),
+            ..Default::default()
+        };
+        if let Some(partition_key) = &self.partition_key {
+            message.message_annotations = vec![(
+                Value::symbol(PARTITION_KEY_ANNOTATION),
+                partition_key.as_str().into(),
+            )];
+        }
+        message
+    }
+}
+
+/// The encoded size of a data section holding `len` bytes.
+fn data_section_size(len: usize) -> usize {
+    // The descriptor takes 3 bytes, then the binary constructor and its length.
+    3 + if len < 256 { 2 } else { 5 } + len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_size_matches_encoding() {
+        let mut batch = EventDataBatch::new(None, Some("key".to_owned()), 1024);
+        assert!(batch.try_add(&EventData::new(&b"small"[..])));
+        assert!(batch.try_add(&EventData::new(vec![7u8; 300]).with_property("p", 1)));
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch.size_in_bytes(), batch.to_message().to_bytes().len());
+
+        assert!(!batch.try_add(&EventData::new(vec![0u8; 1024])));
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch.size_in_bytes(), batch.to_message().to_bytes().len());
+    }
+}
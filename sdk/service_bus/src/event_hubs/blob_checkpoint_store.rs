@@ -0,0 +1,283 @@
+use super::checkpoint_store::{Checkpoint, CheckpointStore, PartitionOwnership};
+use crate::Error;
+use async_trait::async_trait;
+use azure_core::prelude::*;
+use azure_storage::blob::prelude::*;
+use azure_storage::StorageErrorCode;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const OWNER_ID: &str = "ownerid";
+const OFFSET: &str = "offset";
+const SEQUENCE_NUMBER: &str = "sequencenumber";
+
+type StorageResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// A [`CheckpointStore`] keeping one blob per partition ownership and per checkpoint in a
+/// storage container, shared by the processors of every process.
+///
+/// The blobs are named `{namespace}/{event hub}/{consumer group}/ownership/{partition}` and
+/// `{namespace}/{event hub}/{consumer group}/checkpoint/{partition}`, and hold their state in
+/// their metadata. Ownership claims are conditioned on the etag of the blob, so that only one of
+/// the processors racing for a partition succeeds.
+#[derive(Debug, Clone)]
+pub struct BlobCheckpointStore {
+    container_client: Arc<ContainerClient>,
+}
+
+impl BlobCheckpointStore {
+    /// Creates a store in an existing container.
+    pub fn new(container_client: Arc<ContainerClient>) -> Self {
+        Self { container_client }
+    }
+
+    /// Returns the name and the properties, metadata included, of the blobs under `prefix`.
+    async fn list(&self, prefix: &str) -> StorageResult<Vec<Blob>> {
+        let mut blobs = Vec::new();
+        let mut next_marker = None;
+        loop {
+            let mut request = self
+                .container_client
+                .list_blobs()
+                .prefix(prefix)
+                .include_metadata(true);
+            if let Some(next_marker) = next_marker {
+                request = request.next_marker(next_marker);
+            }
+            let response = request.execute().await?;
+            blobs.extend(response.blobs.blobs);
+            next_marker = response.next_marker;
+            if next_marker.is_none() {
+                break;
+            }
+        }
+        Ok(blobs)
+    }
+
+    async fn claim(&self, ownership: &mut PartitionOwnership) -> StorageResult<()> {
+        let blob_client = self
+            .container_client
+            .as_blob_client(ownership_blob_name(ownership));
+        let mut metadata = Metadata::new();
+        metadata.insert(OWNER_ID, ownership.owner_id.clone());
+        let (etag, last_modified) = match &ownership.etag {
+            Some(etag) => {
+                let response = blob_client
+                    .set_metadata(&metadata)
+                    .if_match_condition(IfMatchCondition::Match(etag))
+                    .execute()
+                    .await?;
+                (response.etag, response.last_modified)
+            }
+            None => {
+                let response = blob_client
+                    .put_block_blob(Vec::new())
+                    .metadata(&metadata)
+                    .if_match_condition(IfMatchCondition::NotMatch("*"))
+                    .execute()
+                    .await?;
+                (response.etag, response.last_modified)
+            }
+        };
+        ownership.etag = Some(etag);
+        ownership.last_modified_time = Some(last_modified);
+        Ok(())
+    }
+}
+
+fn prefix(fully_qualified_namespace: &str, event_hub_name: &str, consumer_group: &str) -> String {
+    format!(
+        "{}/{}/{}",
+        fully_qualified_namespace, event_hub_name, consumer_group
+    )
+    .to_lowercase()
+}
+
+fn ownership_blob_name(ownership: &PartitionOwnership) -> String {
+    format!(
+        "{}/ownership/{}",
+        prefix(
+            &ownership.fully_qualified_namespace,
+            &ownership.event_hub_name,
+            &ownership.consumer_group
+        ),
+        ownership.partition_id
+    )
+}
+
+fn checkpoint_blob_name(checkpoint: &Checkpoint) -> String {
+    format!(
+        "{}/checkpoint/{}",
+        prefix(
+            &checkpoint.fully_qualified_namespace,
+            &checkpoint.event_hub_name,
+            &checkpoint.consumer_group
+        ),
+        checkpoint.partition_id
+    )
+}
+
+/// The partition of a blob named `{prefix}{partition}`.
+fn partition_id<'a>(blob: &'a Blob, prefix: &str) -> Option<&'a str> {
+    blob.name
+        .strip_prefix(prefix)
+        .filter(|partition_id| !partition_id.is_empty())
+}
+
+fn metadata<'a>(blob: &'a Blob, name: &str) -> Option<&'a str> {
+    blob.properties
+        .metadata
+        .as_ref()
+        .and_then(|metadata: &HashMap<String, String>| metadata.get(name))
+        .map(String::as_str)
+}
+
+fn has_error_code(
+    error: &(dyn std::error::Error + Send + Sync + 'static),
+    code: &StorageErrorCode,
+) -> bool {
+    error
+        .downcast_ref::<azure_storage::Error>()
+        .and_then(azure_storage::Error::storage_error_code)
+        == Some(code)
+}
+
+#[async_trait]
+impl CheckpointStore for BlobCheckpointStore {
+    async fn list_ownership(
+        &self,
+        fully_qualified_namespace: &str,
+        event_hub_name: &str,
+        consumer_group: &str,
+    ) -> Result<Vec<PartitionOwnership>, Error> {
+        let prefix = format!(
+            "{}/ownership/",
+            prefix(fully_qualified_namespace, event_hub_name, consumer_group)
+        );
+        let blobs = self.list(&prefix).await.map_err(Error::CheckpointStore)?;
+        Ok(blobs
+            .iter()
+            .filter_map(|blob| {
+                Some(PartitionOwnership {
+                    fully_qualified_namespace: fully_qualified_namespace.to_owned(),
+                    event_hub_name: event_hub_name.to_owned(),
+                    consumer_group: consumer_group.to_owned(),
+                    partition_id: partition_id(blob, &prefix)?.to_owned(),
+                    owner_id: metadata(blob, OWNER_ID).unwrap_or_default().to_owned(),
+                    last_modified_time: Some(blob.properties.last_modified),
+                    etag: Some(blob.properties.etag.to_string()),
+                })
+            })
+            .collect())
+    }
+
+    async fn claim_ownership(
+        &self,
+        ownership: Vec<PartitionOwnership>,
+    ) -> Result<Vec<PartitionOwnership>, Error> {
+        let mut claimed = Vec::with_capacity(ownership.len());
+        for mut claim in ownership {
+            match self.claim(&mut claim).await {
+                Ok(()) => claimed.push(claim),
+                Err(error)
+                    if has_error_code(&*error, &StorageErrorCode::ConditionNotMet)
+                        || has_error_code(&*error, &StorageErrorCode::BlobAlreadyExists)
+                        || has_error_code(&*error, &StorageErrorCode::BlobNotFound) =>
+                {
+                    log::debug!(
+                        "partition {} was claimed by another processor",
+                        claim.partition_id
+                    );
+                }
+                Err(error) => return Err(Error::CheckpointStore(error)),
+            }
+        }
+        Ok(claimed)
+    }
+
+    async fn list_checkpoints(
+        &self,
+        fully_qualified_namespace: &str,
+        event_hub_name: &str,
+        consumer_group: &str,
+    ) -> Result<Vec<Checkpoint>, Error> {
+        let prefix = format!(
+            "{}/checkpoint/",
+            prefix(fully_qualified_namespace, event_hub_name, consumer_group)
+        );
+        let blobs = self.list(&prefix).await.map_err(Error::CheckpointStore)?;
+        let number = |blob, name| metadata(blob, name).and_then(|value| value.parse().ok());
+        Ok(blobs
+            .iter()
+            .filter_map(|blob| {
+                Some(Checkpoint {
+                    fully_qualified_namespace: fully_qualified_namespace.to_owned(),
+                    event_hub_name: event_hub_name.to_owned(),
+                    consumer_group: consumer_group.to_owned(),
+                    partition_id: partition_id(blob, &prefix)?.to_owned(),
+                    offset: number(blob, OFFSET),
+                    sequence_number: number(blob, SEQUENCE_NUMBER),
+                })
+            })
+            .collect())
+    }
+
+    async fn update_checkpoint(&self, checkpoint: Checkpoint) -> Result<(), Error> {
+        let blob_client = self
+            .container_client
+            .as_blob_client(checkpoint_blob_name(&checkpoint));
+        let mut metadata = Metadata::new();
+        if let Some(offset) = checkpoint.offset {
+            metadata.insert(OFFSET, offset.to_string());
+        }
+        if let Some(sequence_number) = checkpoint.sequence_number {
+            metadata.insert(SEQUENCE_NUMBER, sequence_number.to_string());
+        }
+        match blob_client.set_metadata(&metadata).execute().await {
+            Ok(_) => Ok(()),
+            Err(error) if has_error_code(&*error, &StorageErrorCode::BlobNotFound) => blob_client
+                .put_block_blob(Vec::new())
+                .metadata(&metadata)
+                .execute()
+                .await
+                .map(|_| ())
+                .map_err(Error::CheckpointStore),
+            Err(error) => Err(Error::CheckpointStore(error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blob_names() {
+        let ownership = PartitionOwnership {
+            fully_qualified_namespace: "NS.servicebus.windows.net".to_owned(),
+            event_hub_name: "Hub".to_owned(),
+            consumer_group: "$Default".to_owned(),
+            partition_id: "3".to_owned(),
+            owner_id: "owner".to_owned(),
+            last_modified_time: None,
+            etag: None,
+        };
+        assert_eq!(
+            ownership_blob_name(&ownership),
+            "ns.servicebus.windows.net/hub/$default/ownership/3"
+        );
+
+        let checkpoint = Checkpoint {
+            fully_qualified_namespace: ownership.fully_qualified_namespace,
+            event_hub_name: ownership.event_hub_name,
+            consumer_group: ownership.consumer_group,
+            partition_id: ownership.partition_id,
+            offset: Some(100),
+            sequence_number: Some(5),
+        };
+        assert_eq!(
+            checkpoint_blob_name(&checkpoint),
+            "ns.servicebus.windows.net/hub/$default/checkpoint/3"
+        );
+    }
+}
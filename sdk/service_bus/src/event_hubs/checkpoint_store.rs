@@ -0,0 +1,183 @@
+use crate::Error;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// The claim of an event processor on a partition of an Event Hub, for a consumer group.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartitionOwnership {
+    pub fully_qualified_namespace: String,
+    pub event_hub_name: String,
+    pub consumer_group: String,
+    pub partition_id: String,
+    /// The identifier of the owning processor; empty once the ownership is relinquished.
+    pub owner_id: String,
+    /// When the claim was last renewed, set by the store.
+    pub last_modified_time: Option<DateTime<Utc>>,
+    /// The version of the claim, set by the store. A claim only succeeds if it still matches
+    /// the stored one, `None` meaning the partition was never claimed.
+    pub etag: Option<String>,
+}
+
+/// The position in a partition up to which events were processed for a consumer group.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checkpoint {
+    pub fully_qualified_namespace: String,
+    pub event_hub_name: String,
+    pub consumer_group: String,
+    pub partition_id: String,
+    pub offset: Option<i64>,
+    pub sequence_number: Option<i64>,
+}
+
+/// Persists the partition ownerships and checkpoints shared by the event processors of a
+/// consumer group.
+#[async_trait]
+pub trait CheckpointStore: Send + Sync {
+    async fn list_ownership(
+        &self,
+        fully_qualified_namespace: &str,
+        event_hub_name: &str,
+        consumer_group: &str,
+    ) -> Result<Vec<PartitionOwnership>, Error>;
+
+    /// Claims the given partitions, returning the ownerships successfully claimed with their
+    /// new `etag` and `last_modified_time`.
+    ///
+    /// Claims whose `etag` no longer matches the stored ownership are left out, since another
+    /// processor claimed the partition in the meantime. Any other failure fails the whole call.
+    async fn claim_ownership(
+        &self,
+        ownership: Vec<PartitionOwnership>,
+    ) -> Result<Vec<PartitionOwnership>, Error>;
+
+    async fn list_checkpoints(
+        &self,
+        fully_qualified_namespace: &str,
+        event_hub_name: &str,
+        consumer_group: &str,
+    ) -> Result<Vec<Checkpoint>, Error>;
+
+    async fn update_checkpoint(&self, checkpoint: Checkpoint) -> Result<(), Error>;
+}
+
+type PartitionKey = (String, String, String, String);
+
+/// A [`CheckpointStore`] keeping its state in memory, for processors of a single process.
+#[derive(Debug, Default)]
+pub struct InMemoryCheckpointStore {
+    ownership: Mutex<HashMap<PartitionKey, PartitionOwnership>>,
+    checkpoints: Mutex<HashMap<PartitionKey, Checkpoint>>,
+}
+
+impl InMemoryCheckpointStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+fn matches(namespace: &str, event_hub: &str, consumer_group: &str, key: &PartitionKey) -> bool {
+    key.0.eq_ignore_ascii_case(namespace)
+        && key.1.eq_ignore_ascii_case(event_hub)
+        && key.2.eq_ignore_ascii_case(consumer_group)
+}
+
+fn partition_key(namespace: &str, event_hub: &str, consumer_group: &str, id: &str) -> PartitionKey {
+    (
+        namespace.to_lowercase(),
+        event_hub.to_lowercase(),
+        consumer_group.to_lowercase(),
+        id.to_owned(),
+    )
+}
+
+#[async_trait]
+impl CheckpointStore for InMemoryCheckpointStore {
+    async fn list_ownership(
+        &self,
+        fully_qualified_namespace: &str,
+        event_hub_name: &str,
+        consumer_group: &str,
+    ) -> Result<Vec<PartitionOwnership>, Error> {
+        Ok(self
+            .ownership
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(key, _)| {
+                matches(
+                    fully_qualified_namespace,
+                    event_hub_name,
+                    consumer_group,
+                    key,
+                )
+            })
+            .map(|(_, ownership)| ownership.clone())
+            .collect())
+    }
+
+    async fn claim_ownership(
+        &self,
+        ownership: Vec<PartitionOwnership>,
+    ) -> Result<Vec<PartitionOwnership>, Error> {
+        let mut stored = self.ownership.lock().unwrap();
+        let mut claimed = Vec::new();
+        for mut claim in ownership {
+            let key = partition_key(
+                &claim.fully_qualified_namespace,
+                &claim.event_hub_name,
+                &claim.consumer_group,
+                &claim.partition_id,
+            );
+            let current_etag = stored.get(&key).and_then(|current| current.etag.clone());
+            if current_etag != claim.etag {
+                log::debug!(
+                    "partition {} was claimed by another processor",
+                    claim.partition_id
+                );
+                continue;
+            }
+            claim.etag = Some(Uuid::new_v4().to_string());
+            claim.last_modified_time = Some(Utc::now());
+            stored.insert(key, claim.clone());
+            claimed.push(claim);
+        }
+        Ok(claimed)
+    }
+
+    async fn list_checkpoints(
+        &self,
+        fully_qualified_namespace: &str,
+        event_hub_name: &str,
+        consumer_group: &str,
+    ) -> Result<Vec<Checkpoint>, Error> {
+        Ok(self
+            .checkpoints
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(key, _)| {
+                matches(
+                    fully_qualified_namespace,
+                    event_hub_name,
+                    consumer_group,
+                    key,
+                )
+            })
+            .map(|(_, checkpoint)| checkpoint.clone())
+            .collect())
+    }
+
+    async fn update_checkpoint(&self, checkpoint: Checkpoint) -> Result<(), Error> {
+        let key = partition_key(
+            &checkpoint.fully_qualified_namespace,
+            &checkpoint.event_hub_name,
+            &checkpoint.consumer_group,
+            &checkpoint.partition_id,
+        );
+        self.checkpoints.lock().unwrap().insert(key, checkpoint);
+        Ok(())
+    }
+}
@@ -0,0 +1,195 @@
+use crate::amqp::cbs::{self, CBS_NODE};
+use crate::amqp::connection::Connection;
+use crate::amqp::message::Message;
+use crate::amqp::request_response::{response_status, RequestResponseLink};
+use crate::amqp::Value;
+use crate::sas::generate_signature;
+use crate::Error;
+use azure_core::setters;
+use chrono::{DateTime, Duration, Utc};
+use ring::hmac;
+use std::sync::Arc;
+use url::Url;
+
+const MANAGEMENT_NODE: &str = "$management";
+
+/// Options shared by the Event Hubs clients.
+#[derive(Debug, Clone)]
+pub struct EventHubClientOptions {
+    endpoint: Option<Url>,
+    token_ttl: Duration,
+    prefetch_count: u32,
+}
+
+impl Default for EventHubClientOptions {
+    fn default() -> Self {
+        Self {
+            endpoint: None,
+            token_ttl: Duration::hours(1),
+            prefetch_count: 300,
+        }
+    }
+}
+
+impl EventHubClientOptions {
+    setters! {
+        endpoint: Url => Some(endpoint),
+        token_ttl: Duration => token_ttl,
+        prefetch_count: u32 => prefetch_count.max(1),
+    }
+}
+
+struct Active {
+    connection: Connection,
+    cbs: RequestResponseLink,
+    management: Option<Arc<RequestResponseLink>>,
+    token_expires_on: DateTime<Utc>,
+}
+
+/// The connection shared by the links of a client, authorized with a Shared Access Signature
+/// for the Event Hub.
+pub(crate) struct EventHubConnection {
+    endpoint: Url,
+    event_hub: String,
+    policy_name: String,
+    signing_key: hmac::Key,
+    options: EventHubClientOptions,
+    active: tokio::sync::Mutex<Option<Active>>,
+}
+
+impl std::fmt::Debug for EventHubConnection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventHubConnection")
+            .field("endpoint", &self.endpoint)
+            .field("event_hub", &self.event_hub)
+            .field("policy_name", &self.policy_name)
+            .finish()
+    }
+}
+
+impl EventHubConnection {
+    pub(crate) fn new(
+        namespace: &str,
+        event_hub: String,
+        policy_name: String,
+        key: &str,
+        options: EventHubClientOptions,
+    ) -> Result<Self, Error> {
+        let endpoint = match &options.endpoint {
+            Some(endpoint) => endpoint.clone(),
+            None => Url::parse(&format!("amqps://{}.servicebus.windows.net", namespace))?,
+        };
+        Ok(Self {
+            endpoint,
+            event_hub,
+            policy_name,
+            signing_key: hmac::Key::new(hmac::HMAC_SHA256, key.as_bytes()),
+            options,
+            active: tokio::sync::Mutex::new(None),
+        })
+    }
+
+    /// The host name of the namespace, such as `{namespace}.servicebus.windows.net`.
+    pub(crate) fn fully_qualified_namespace(&self) -> &str {
+        self.endpoint.host_str().unwrap_or_default()
+    }
+
+    pub(crate) fn event_hub(&self) -> &str {
+        &self.event_hub
+    }
+
+    pub(crate) fn prefetch_count(&self) -> u32 {
+        self.options.prefetch_count
+    }
+
+    fn audience(&self) -> String {
+        format!(
+            "sb://{}/{}",
+            self.fully_qualified_namespace(),
+            self.event_hub
+        )
+    }
+
+    fn token(&self, audience: &str) -> (String, DateTime<Utc>) {
+        let expires_on = Utc::now() + self.options.token_ttl;
+        let token = generate_signature(
+            &self.policy_name,
+            &self.signing_key,
+            audience,
+            self.options.token_ttl,
+        );
+        (token, expires_on)
+    }
+
+    /// Returns the connection, opening it if needed, once authorized to access the Event Hub.
+    ///
+    /// The token is renewed once half of its lifetime is elapsed.
+    pub(crate) async fn authorize(&self) -> Result<Connection, Error> {
+        let mut active = self.active.lock().await;
+        let audience = self.audience();
+        let expired = |active: &Active| {
+            active.connection.is_closed()
+                || active.cbs.is_closed()
+                || active.token_expires_on - Utc::now() < self.options.token_ttl / 2
+        };
+        match active.as_mut() {
+            Some(current) if !expired(current) => return Ok(current.connection.clone()),
+            Some(current) if !current.connection.is_closed() && !current.cbs.is_closed() => {
+                let (token, expires_on) = self.token(&audience);
+                cbs::put_token(&current.cbs, &audience, &token, expires_on).await?;
+                current.token_expires_on = expires_on;
+                return Ok(current.connection.clone());
+            }
+            _ => {}
+        }
+
+        let connection = Connection::open(&self.endpoint).await?;
+        let cbs = RequestResponseLink::attach(&connection, CBS_NODE).await?;
+        let (token, expires_on) = self.token(&audience);
+        cbs::put_token(&cbs, &audience, &token, expires_on).await?;
+        *active = Some(Active {
+            connection: connection.clone(),
+            cbs,
+            management: None,
+            token_expires_on: expires_on,
+        });
+        Ok(connection)
+    }
+
+    /// Sends a request to the `$management` node, authorized by a token in its properties.
+    pub(crate) async fn management_request(&self, mut request: Message) -> Result<Message, Error> {
+        let connection = self.authorize().await?;
+        let management = {
+            let mut active = self.active.lock().await;
+            let active = active.as_mut().ok_or(Error::ConnectionClosed)?;
+            match &active.management {
+                Some(management) if !management.is_closed() => management.clone(),
+                _ => {
+                    let management =
+                        Arc::new(RequestResponseLink::attach(&connection, MANAGEMENT_NODE).await?);
+                    active.management = Some(management.clone());
+                    management
+                }
+            }
+        };
+        let (token, _) = self.token(&self.audience());
+        request
+            .application_properties
+            .push(("security_token".into(), Value::String(token)));
+        let response = management.call(request).await?;
+        match response_status(&response) {
+            (200..=299, _) => Ok(response),
+            (status_code, description) => Err(Error::Management {
+                status_code,
+                description,
+            }),
+        }
+    }
+
+    pub(crate) async fn close(&self) -> Result<(), Error> {
+        match self.active.lock().await.take() {
+            Some(active) => active.connection.close().await,
+            None => Ok(()),
+        }
+    }
+}
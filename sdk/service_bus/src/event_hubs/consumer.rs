@@ -0,0 +1,282 @@
+use super::connection::{EventHubClientOptions, EventHubConnection};
+use super::event::ReceivedEventData;
+use super::properties::{
+    get_event_hub_properties, get_partition_properties, EventHubProperties, PartitionProperties,
+};
+use crate::amqp::connection::ReceiverLink;
+use crate::amqp::performatives::{Attach, Role, SenderSettleMode, Terminus};
+use crate::amqp::Value;
+use crate::Error;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// The name of the consumer group every Event Hub has.
+pub const DEFAULT_CONSUMER_GROUP: &str = "$Default";
+
+const SELECTOR_FILTER_NAME: &str = "apache.org:selector-filter:string";
+const SELECTOR_FILTER_CODE: u64 = 0x0000_468C_0000_0004;
+const EPOCH_PROPERTY: &str = "com.microsoft:epoch";
+
+/// The position in a partition from which events are read.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventPosition {
+    /// The first event still retained in the partition.
+    Earliest,
+    /// The events enqueued after the receiver is opened.
+    Latest,
+    Offset {
+        offset: i64,
+        inclusive: bool,
+    },
+    SequenceNumber {
+        sequence_number: i64,
+        inclusive: bool,
+    },
+    /// The first event enqueued after the given time.
+    EnqueuedTime(DateTime<Utc>),
+}
+
+impl EventPosition {
+    pub fn earliest() -> Self {
+        EventPosition::Earliest
+    }
+
+    pub fn latest() -> Self {
+        EventPosition::Latest
+    }
+
+    pub fn from_offset(offset: i64, inclusive: bool) -> Self {
+        EventPosition::Offset { offset, inclusive }
+    }
+
+    pub fn from_sequence_number(sequence_number: i64, inclusive: bool) -> Self {
+        EventPosition::SequenceNumber {
+            sequence_number,
+            inclusive,
+        }
+    }
+
+    pub fn from_enqueued_time(enqueued_time: DateTime<Utc>) -> Self {
+        EventPosition::EnqueuedTime(enqueued_time)
+    }
+
+    /// The selector filter expression of the position.
+    pub(crate) fn filter_expression(&self) -> String {
+        let operator = |inclusive: bool| if inclusive { ">=" } else { ">" };
+        match self {
+            EventPosition::Earliest => "amqp.annotation.x-opt-offset > '-1'".to_owned(),
+            EventPosition::Latest => "amqp.annotation.x-opt-offset > '@latest'".to_owned(),
+            EventPosition::Offset { offset, inclusive } => format!(
+                "amqp.annotation.x-opt-offset {} '{}'",
+                operator(*inclusive),
+                offset
+            ),
+            EventPosition::SequenceNumber {
+                sequence_number,
+                inclusive,
+            } => format!(
+                "amqp.annotation.x-opt-sequence-number {} '{}'",
+                operator(*inclusive),
+                sequence_number
+            ),
+            EventPosition::EnqueuedTime(enqueued_time) => format!(
+                "amqp.annotation.x-opt-enqueued-time > '{}'",
+                enqueued_time.timestamp_millis()
+            ),
+        }
+    }
+}
+
+/// Options of [`EventHubConsumerClient::receive_from_partition`].
+#[derive(Debug, Clone, Default)]
+pub struct ReceiveOptions {
+    /// Receivers with a higher owner level disconnect the receivers of the same partition and
+    /// consumer group with a lower one.
+    owner_level: Option<i64>,
+}
+
+impl ReceiveOptions {
+    pub fn owner_level(self, owner_level: i64) -> Self {
+        Self {
+            owner_level: Some(owner_level),
+        }
+    }
+}
+
+/// A client reading events from the partitions of an Event Hub, as a member of a consumer group.
+#[derive(Debug)]
+pub struct EventHubConsumerClient {
+    connection: Arc<EventHubConnection>,
+    consumer_group: String,
+}
+
+impl EventHubConsumerClient {
+    pub fn new<N, E, C, P, K>(
+        namespace: N,
+        event_hub: E,
+        consumer_group: C,
+        policy_name: P,
+        key: K,
+        options: EventHubClientOptions,
+    ) -> Result<Self, Error>
+    where
+        N: AsRef<str>,
+        E: Into<String>,
+        C: Into<String>,
+        P: Into<String>,
+        K: AsRef<str>,
+    {
+        Ok(Self {
+            connection: Arc::new(EventHubConnection::new(
+                namespace.as_ref(),
+                event_hub.into(),
+                policy_name.into(),
+                key.as_ref(),
+                options,
+            )?),
+            consumer_group: consumer_group.into(),
+        })
+    }
+
+    pub fn fully_qualified_namespace(&self) -> &str {
+        self.connection.fully_qualified_namespace()
+    }
+
+    pub fn event_hub(&self) -> &str {
+        self.connection.event_hub()
+    }
+
+    pub fn consumer_group(&self) -> &str {
+        &self.consumer_group
+    }
+
+    pub async fn get_event_hub_properties(&self) -> Result<EventHubProperties, Error> {
+        get_event_hub_properties(&self.connection).await
+    }
+
+    pub async fn get_partition_ids(&self) -> Result<Vec<String>, Error> {
+        Ok(self
+            .get_event_hub_properties()
+            .await?
+            .partition_ids()
+            .to_vec())
+    }
+
+    pub async fn get_partition_properties(
+        &self,
+        partition_id: &str,
+    ) -> Result<PartitionProperties, Error> {
+        get_partition_properties(&self.connection, partition_id).await
+    }
+
+    /// Opens a receiver reading the events of `partition_id` from `position`.
+    pub async fn receive_from_partition(
+        &self,
+        partition_id: &str,
+        position: EventPosition,
+        options: ReceiveOptions,
+    ) -> Result<PartitionReceiver, Error> {
+        let connection = self.connection.authorize().await?;
+        let address = format!(
+            "{}/ConsumerGroups/{}/Partitions/{}",
+            self.connection.event_hub(),
+            self.consumer_group,
+            partition_id
+        );
+        let mut attach = Attach::new(
+            format!("{}-receiver-{}", address, Uuid::new_v4()),
+            0,
+            Role::Receiver,
+        );
+        attach.snd_settle_mode = Some(SenderSettleMode::Settled);
+        attach.source = Some(Terminus {
+            address: Some(address),
+            filter: vec![(
+                Value::symbol(SELECTOR_FILTER_NAME),
+                Value::described(
+                    SELECTOR_FILTER_CODE,
+                    Value::String(position.filter_expression()),
+                ),
+            )],
+        });
+        attach.target = Some(Terminus::new(Uuid::new_v4().to_string()));
+        if let Some(owner_level) = options.owner_level {
+            attach.properties = vec![(Value::symbol(EPOCH_PROPERTY), Value::Long(owner_level))];
+        }
+        let link = connection
+            .attach_receiver(attach, self.connection.prefetch_count())
+            .await?;
+        Ok(PartitionReceiver {
+            connection: self.connection.clone(),
+            partition_id: partition_id.to_owned(),
+            link,
+            last_received: None,
+        })
+    }
+
+    /// Closes the connection of the client, and with it the receivers it opened.
+    pub async fn close(&self) -> Result<(), Error> {
+        self.connection.close().await
+    }
+}
+
+/// Reads the events of a single partition, in order.
+#[derive(Debug)]
+pub struct PartitionReceiver {
+    connection: Arc<EventHubConnection>,
+    partition_id: String,
+    link: ReceiverLink,
+    last_received: Option<(i64, i64)>,
+}
+
+impl PartitionReceiver {
+    pub fn partition_id(&self) -> &str {
+        &self.partition_id
+    }
+
+    /// The offset and sequence number of the last event received.
+    pub fn last_received(&self) -> Option<(i64, i64)> {
+        self.last_received
+    }
+
+    /// Waits for the next event; `None` means the service closed the receiver.
+    pub async fn receive(&mut self) -> Result<Option<ReceivedEventData>, Error> {
+        // Renews the authorization of the connection when its token is about to expire.
+        self.connection.authorize().await?;
+        let delivery = match self.link.recv().await? {
+            Some(delivery) => delivery,
+            None => return Ok(None),
+        };
+        let event = ReceivedEventData::from_message(&delivery.message);
+        self.last_received = Some((event.offset(), event.sequence_number()));
+        Ok(Some(event))
+    }
+
+    /// Receives up to `max_events` events, waiting at most `max_wait` for them.
+    pub async fn receive_batch(
+        &mut self,
+        max_events: usize,
+        max_wait: Duration,
+    ) -> Result<Vec<ReceivedEventData>, Error> {
+        let deadline = tokio::time::Instant::now() + max_wait;
+        let mut events = Vec::new();
+        while events.len() < max_events {
+            match tokio::time::timeout_at(deadline, self.receive()).await {
+                Ok(Ok(Some(event))) => events.push(event),
+                Ok(Ok(None)) | Err(_) => break,
+                Ok(Err(error)) if events.is_empty() => return Err(error),
+                Ok(Err(error)) => {
+                    log::warn!("receive failed after {} events: {}", events.len(), error);
+                    break;
+                }
+            }
+        }
+        Ok(events)
+    }
+
+    pub async fn close(self) -> Result<(), Error> {
+        self.link.close().await
+    }
+}
@@ -0,0 +1,186 @@
+use crate::amqp::message::Message;
+use crate::amqp::Value;
+use bytes::Bytes;
+use chrono::{DateTime, TimeZone, Utc};
+use std::collections::HashMap;
+
+pub(crate) const PARTITION_KEY_ANNOTATION: &str = "x-opt-partition-key";
+const SEQUENCE_NUMBER_ANNOTATION: &str = "x-opt-sequence-number";
+const OFFSET_ANNOTATION: &str = "x-opt-offset";
+const ENQUEUED_TIME_ANNOTATION: &str = "x-opt-enqueued-time";
+
+/// An event sent to an Event Hub.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EventData {
+    body: Bytes,
+    content_type: Option<String>,
+    correlation_id: Option<String>,
+    message_id: Option<String>,
+    properties: HashMap<String, Value>,
+}
+
+impl EventData {
+    pub fn new(body: impl Into<Bytes>) -> Self {
+        Self {
+            body: body.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
+    pub fn with_correlation_id(mut self, correlation_id: impl Into<String>) -> Self {
+        self.correlation_id = Some(correlation_id.into());
+        self
+    }
+
+    pub fn with_message_id(mut self, message_id: impl Into<String>) -> Self {
+        self.message_id = Some(message_id.into());
+        self
+    }
+
+    /// Adds an application property to the event.
+    pub fn with_property(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.properties.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
+    pub fn correlation_id(&self) -> Option<&str> {
+        self.correlation_id.as_deref()
+    }
+
+    pub fn message_id(&self) -> Option<&str> {
+        self.message_id.as_deref()
+    }
+
+    pub fn properties(&self) -> &HashMap<String, Value> {
+        &self.properties
+    }
+
+    pub(crate) fn to_message(&self, partition_key: Option<&str>) -> Message {
+        let mut message = Message::with_data(self.body.clone());
+        message.properties.content_type = self.content_type.clone();
+        message.properties.correlation_id = self.correlation_id.clone().map(Value::String);
+        message.properties.message_id = self.message_id.clone().map(Value::String);
+        message.application_properties = self
+            .properties
+            .iter()
+            .map(|(name, value)| (Value::String(name.clone()), value.clone()))
+            .collect();
+        if let Some(partition_key) = partition_key {
+            message.message_annotations = vec![(
+                Value::symbol(PARTITION_KEY_ANNOTATION),
+                partition_key.into(),
+            )];
+        }
+        message
+    }
+
+    fn from_message(message: &Message) -> Self {
+        let string = |value: &Option<Value>| {
+            value
+                .as_ref()
+                .and_then(|value| value.as_str().map(ToOwned::to_owned))
+        };
+        Self {
+            body: message.data(),
+            content_type: message.properties.content_type.clone(),
+            correlation_id: string(&message.properties.correlation_id),
+            message_id: string(&message.properties.message_id),
+            properties: message
+                .application_properties
+                .iter()
+                .filter_map(|(name, value)| Some((name.as_str()?.to_owned(), value.clone())))
+                .collect(),
+        }
+    }
+}
+
+/// An event received from a partition of an Event Hub, with the properties set by the service.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReceivedEventData {
+    event: EventData,
+    sequence_number: i64,
+    offset: i64,
+    enqueued_time: DateTime<Utc>,
+    partition_key: Option<String>,
+    system_properties: HashMap<String, Value>,
+}
+
+impl ReceivedEventData {
+    pub(crate) fn from_message(message: &Message) -> Self {
+        let annotation = |name: &str| message.annotation(name);
+        Self {
+            event: EventData::from_message(message),
+            sequence_number: annotation(SEQUENCE_NUMBER_ANNOTATION)
+                .and_then(Value::as_i64)
+                .unwrap_or_default(),
+            offset: annotation(OFFSET_ANNOTATION)
+                .and_then(|offset| match offset {
+                    Value::String(offset) => offset.parse().ok(),
+                    offset => offset.as_i64(),
+                })
+                .unwrap_or_default(),
+            enqueued_time: annotation(ENQUEUED_TIME_ANNOTATION)
+                .and_then(|time| time.as_timestamp().or_else(|| time.as_i64()))
+                .and_then(|millis| Utc.timestamp_millis_opt(millis).single())
+                .unwrap_or_else(|| Utc.timestamp_opt(0, 0).unwrap()),
+            partition_key: annotation(PARTITION_KEY_ANNOTATION)
+                .and_then(Value::as_str)
+                .map(ToOwned::to_owned),
+            system_properties: message
+                .message_annotations
+                .iter()
+                .filter_map(|(name, value)| Some((name.as_str()?.to_owned(), value.clone())))
+                .collect(),
+        }
+    }
+
+    pub fn event(&self) -> &EventData {
+        &self.event
+    }
+
+    pub fn into_event(self) -> EventData {
+        self.event
+    }
+
+    pub fn body(&self) -> &Bytes {
+        self.event.body()
+    }
+
+    pub fn properties(&self) -> &HashMap<String, Value> {
+        self.event.properties()
+    }
+
+    pub fn sequence_number(&self) -> i64 {
+        self.sequence_number
+    }
+
+    pub fn offset(&self) -> i64 {
+        self.offset
+    }
+
+    pub fn enqueued_time(&self) -> DateTime<Utc> {
+        self.enqueued_time
+    }
+
+    pub fn partition_key(&self) -> Option<&str> {
+        self.partition_key.as_deref()
+    }
+
+    /// The message annotations set by the service.
+    pub fn system_properties(&self) -> &HashMap<String, Value> {
+        &self.system_properties
+    }
+}
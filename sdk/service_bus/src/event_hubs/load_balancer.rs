@@ -0,0 +1,174 @@
+use super::checkpoint_store::PartitionOwnership;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+/// Distributes the partitions of an Event Hub evenly among the processors of a consumer group.
+///
+/// Each processor runs the balancing on its own, from the ownerships in the checkpoint store:
+/// until it owns its share of the partitions, it claims one more partition per cycle, first
+/// among the partitions nobody owns, then among those of the processor owning the most.
+#[derive(Debug, Clone)]
+pub(crate) struct LoadBalancer {
+    owner_id: String,
+    ownership_expiration: Duration,
+}
+
+impl LoadBalancer {
+    pub(crate) fn new(owner_id: String, ownership_expiration: Duration) -> Self {
+        Self {
+            owner_id,
+            ownership_expiration,
+        }
+    }
+
+    /// Returns the partitions to claim in this cycle: those already owned, to renew their
+    /// claim, and at most one more.
+    pub(crate) fn claims(
+        &self,
+        partition_ids: &[String],
+        ownership: &[PartitionOwnership],
+        now: DateTime<Utc>,
+    ) -> Vec<String> {
+        let partition_ids: HashSet<&str> = partition_ids.iter().map(String::as_str).collect();
+        let active = ownership
+            .iter()
+            .filter(|ownership| partition_ids.contains(ownership.partition_id.as_str()))
+            .filter(|ownership| !ownership.owner_id.is_empty())
+            .filter(|ownership| {
+                ownership
+                    .last_modified_time
+                    .map(|last_modified_time| {
+                        // A claim renewed after `now` by a processor whose clock is ahead is
+                        // still active.
+                        (now - last_modified_time)
+                            .to_std()
+                            .map(|age| age < self.ownership_expiration)
+                            .unwrap_or(true)
+                    })
+                    .unwrap_or(false)
+            })
+            .collect::<Vec<_>>();
+
+        let mut owned: HashMap<&str, Vec<&str>> = HashMap::new();
+        owned.entry(self.owner_id.as_str()).or_default();
+        for ownership in &active {
+            owned
+                .entry(ownership.owner_id.as_str())
+                .or_default()
+                .push(ownership.partition_id.as_str());
+        }
+        let mine = owned[self.owner_id.as_str()].clone();
+        let mut claims: Vec<String> = mine.iter().map(|id| id.to_string()).collect();
+
+        let minimum = partition_ids.len() / owned.len();
+        let with_extra = partition_ids.len() % owned.len();
+        let over_minimum = owned
+            .values()
+            .filter(|partitions| partitions.len() > minimum)
+            .count();
+        let balanced =
+            mine.len() > minimum || (mine.len() == minimum && over_minimum >= with_extra);
+        if balanced {
+            return claims;
+        }
+
+        let mut unowned = partition_ids
+            .iter()
+            .filter(|id| {
+                !active
+                    .iter()
+                    .any(|ownership| ownership.partition_id == **id)
+            })
+            .copied()
+            .collect::<Vec<_>>();
+        unowned.sort_unstable();
+        if let Some(partition_id) = unowned.first() {
+            claims.push(partition_id.to_string());
+            return claims;
+        }
+
+        // Every partition is owned: takes one from a processor owning more than its share.
+        let maximum = if with_extra > 0 { minimum + 1 } else { minimum };
+        let stolen = owned
+            .iter()
+            .filter(|(owner_id, _)| **owner_id != self.owner_id)
+            .filter(|(_, partitions)| {
+                partitions.len() > maximum || (partitions.len() == maximum && mine.len() < minimum)
+            })
+            .max_by_key(|(owner_id, partitions)| (partitions.len(), **owner_id))
+            .and_then(|(_, partitions)| partitions.iter().min());
+        if let Some(partition_id) = stolen {
+            claims.push(partition_id.to_string());
+        }
+        claims
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ownership(partition_id: &str, owner_id: &str, age: i64) -> PartitionOwnership {
+        PartitionOwnership {
+            fully_qualified_namespace: "ns".to_owned(),
+            event_hub_name: "hub".to_owned(),
+            consumer_group: "$Default".to_owned(),
+            partition_id: partition_id.to_owned(),
+            owner_id: owner_id.to_owned(),
+            last_modified_time: Some(Utc::now() - chrono::Duration::seconds(age)),
+            etag: Some("etag".to_owned()),
+        }
+    }
+
+    #[test]
+    fn claims_one_partition_per_cycle() {
+        let balancer = LoadBalancer::new("a".to_owned(), Duration::from_secs(30));
+        let partitions = ["0", "1", "2", "3"].map(String::from).to_vec();
+
+        // Unowned and expired partitions are claimed first.
+        let current = vec![ownership("0", "a", 5), ownership("1", "b", 60)];
+        assert_eq!(
+            balancer.claims(&partitions, &current, Utc::now()),
+            ["0", "1"]
+        );
+
+        // Two processors sharing four partitions are balanced at two each.
+        let current = vec![
+            ownership("0", "a", 5),
+            ownership("1", "a", 5),
+            ownership("2", "b", 5),
+        ];
+        assert_eq!(
+            balancer.claims(&partitions, &current, Utc::now()),
+            ["0", "1"]
+        );
+
+        // A partition is taken from a processor owning more than its share.
+        let current = vec![
+            ownership("0", "b", 5),
+            ownership("1", "b", 5),
+            ownership("2", "b", 5),
+            ownership("3", "c", 5),
+        ];
+        assert_eq!(balancer.claims(&partitions, &current, Utc::now()), ["0"]);
+    }
+
+    #[test]
+    fn keeps_balanced_ownership() {
+        let balancer = LoadBalancer::new("a".to_owned(), Duration::from_secs(30));
+        let partitions = ["0", "1", "2"].map(String::from).to_vec();
+
+        // With three partitions for two processors, the one owning a single partition is done.
+        let current = vec![
+            ownership("0", "a", 5),
+            ownership("1", "b", 5),
+            ownership("2", "b", 5),
+        ];
+        assert_eq!(balancer.claims(&partitions, &current, Utc::now()), ["0"]);
+
+        // A new processor takes a partition from the processor with the most.
+        let balancer = LoadBalancer::new("c".to_owned(), Duration::from_secs(30));
+        assert_eq!(balancer.claims(&partitions, &current, Utc::now()), ["1"]);
+    }
+}
@@ -0,0 +1,443 @@
+//! Event Hubs clients communicating with the service over AMQP 1.0.
+//!
+//! The [`EventHubProducerClient`] publishes events, one at a time or in size-limited
+//! [`EventDataBatch`]es, and the [`EventHubConsumerClient`] reads the events of a partition from
+//! an [`EventPosition`]. Both authorize their connection with a Shared Access Signature of the
+//! namespace and query the properties of the Event Hub and of its partitions.
+//!
+//! The [`EventProcessor`] reads every partition of an Event Hub on top of a consumer client,
+//! sharing the partitions with the other processors of its consumer group through a
+//! [`CheckpointStore`], such as the `BlobCheckpointStore` of the `blob_checkpoint_store`
+//! feature.
+mod batch;
+#[cfg(feature = "blob_checkpoint_store")]
+mod blob_checkpoint_store;
+mod checkpoint_store;
+mod connection;
+mod consumer;
+mod event;
+mod load_balancer;
+mod processor;
+mod producer;
+mod properties;
+
+pub use crate::amqp::Value as AmqpValue;
+pub use batch::{CreateBatchOptions, EventDataBatch};
+#[cfg(feature = "blob_checkpoint_store")]
+pub use blob_checkpoint_store::BlobCheckpointStore;
+pub use checkpoint_store::{
+    Checkpoint, CheckpointStore, InMemoryCheckpointStore, PartitionOwnership,
+};
+pub use connection::EventHubClientOptions;
+pub use consumer::{
+    EventHubConsumerClient, EventPosition, PartitionReceiver, ReceiveOptions,
+    DEFAULT_CONSUMER_GROUP,
+};
+pub use event::{EventData, ReceivedEventData};
+pub use processor::{
+    CloseReason, EventProcessor, EventProcessorOptions, HandlerResult, PartitionContext,
+    PartitionEventHandler,
+};
+pub use producer::{EventHubProducerClient, SendEventOptions};
+pub use properties::{EventHubProperties, PartitionProperties};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amqp::message::{Body, Message};
+    use crate::amqp::test_broker::{status_response, TestBroker};
+    use crate::amqp::Value;
+    use crate::Error;
+    use async_trait::async_trait;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    async fn broker() -> TestBroker {
+        let broker = TestBroker::start().await;
+        broker.respond("$cbs", |_| status_response(200, Value::Null));
+        broker.respond("$management", |request| {
+            let property = |name: &str| request.application_property(name).cloned().unwrap();
+            let body = if property("type") == Value::from("com.microsoft:eventhub") {
+                Value::Map(vec![
+                    ("name".into(), property("name")),
+                    ("created_at".into(), Value::Timestamp(1_600_000_000_000)),
+                    ("partition_count".into(), Value::Int(2)),
+                    (
+                        "partition_ids".into(),
+                        Value::Array(vec!["0".into(), "1".into()]),
+                    ),
+                ])
+            } else {
+                Value::Map(vec![
+                    ("name".into(), property("name")),
+                    ("partition".into(), property("partition")),
+                    ("begin_sequence_number".into(), Value::Long(3)),
+                    ("last_enqueued_sequence_number".into(), Value::Long(41)),
+                    ("last_enqueued_offset".into(), "8192".into()),
+                    (
+                        "last_enqueued_time_utc".into(),
+                        Value::Timestamp(1_600_000_000_000),
+                    ),
+                    ("is_partition_empty".into(), Value::Bool(false)),
+                ])
+            };
+            status_response(200, body)
+        });
+        broker
+    }
+
+    fn event_message(sequence_number: i64) -> Message {
+        let mut message = Message::with_data(format!("event {}", sequence_number));
+        message.message_annotations = vec![
+            (
+                Value::symbol("x-opt-sequence-number"),
+                Value::Long(sequence_number),
+            ),
+            (
+                Value::symbol("x-opt-offset"),
+                (sequence_number * 100).to_string().into(),
+            ),
+            (
+                Value::symbol("x-opt-enqueued-time"),
+                Value::Timestamp(1_600_000_000_000),
+            ),
+        ];
+        message
+    }
+
+    fn options(broker: &TestBroker) -> EventHubClientOptions {
+        EventHubClientOptions::default()
+            .endpoint(broker.url.clone())
+            .prefetch_count(10u32)
+    }
+
+    #[tokio::test]
+    async fn producer_routes_events_and_batches() {
+        let broker = broker().await;
+        let producer =
+            EventHubProducerClient::new("ns", "hub", "policy", "key", options(&broker)).unwrap();
+
+        producer
+            .send_event(
+                EventData::new("to partition 1").with_property("kind", "test"),
+                SendEventOptions::default().partition_id("1"),
+            )
+            .await
+            .unwrap();
+
+        let mut batch = producer
+            .create_batch(CreateBatchOptions::default().partition_key("device-1"))
+            .await
+            .unwrap();
+        assert_eq!(batch.max_size_in_bytes(), 1024 * 1024);
+        assert!(batch.try_add(&EventData::new("first")));
+        assert!(batch.try_add(&EventData::new("second")));
+        producer.send_batch(batch).await.unwrap();
+
+        let error = producer
+            .send_event(
+                EventData::new("both"),
+                SendEventOptions::default()
+                    .partition_id("0")
+                    .partition_key("key"),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::InvalidArgument(_)));
+
+        let received = broker.received();
+        let (address, _, message) = &received[0];
+        assert_eq!(address, "$cbs");
+        let token = match &message.body {
+            Body::Value(Value::String(token)) => token,
+            body => panic!("unexpected token body {:?}", body),
+        };
+        assert!(token.starts_with("SharedAccessSignature sr=sb%3A%2F%2F127.0.0.1%2Fhub&sig="));
+
+        let (address, format, message) = &received[1];
+        assert_eq!(address, "hub/Partitions/1");
+        assert_eq!(*format, 0);
+        assert_eq!(message.data(), "to partition 1");
+        assert_eq!(
+            message.application_property("kind"),
+            Some(&Value::from("test"))
+        );
+
+        let (address, format, message) = &received[2];
+        assert_eq!(address, "hub");
+        assert_eq!(*format, 0x8001_3700);
+        assert_eq!(
+            message.annotation("x-opt-partition-key"),
+            Some(&Value::from("device-1"))
+        );
+        let events = match &message.body {
+            Body::Data(sections) => sections
+                .iter()
+                .map(|section| Message::decode(section).unwrap())
+                .collect::<Vec<_>>(),
+            body => panic!("unexpected batch body {:?}", body),
+        };
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].data(), "second");
+        assert_eq!(
+            events[1].annotation("x-opt-partition-key"),
+            Some(&Value::from("device-1"))
+        );
+
+        producer.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn consumer_reads_partition_from_position() {
+        let broker = broker().await;
+        for sequence_number in 5..8 {
+            broker.enqueue(
+                "hub/ConsumerGroups/$Default/Partitions/0",
+                event_message(sequence_number),
+            );
+        }
+
+        let consumer = EventHubConsumerClient::new(
+            "ns",
+            "hub",
+            DEFAULT_CONSUMER_GROUP,
+            "policy",
+            "key",
+            options(&broker),
+        )
+        .unwrap();
+        let mut receiver = consumer
+            .receive_from_partition(
+                "0",
+                EventPosition::from_sequence_number(4, false),
+                ReceiveOptions::default().owner_level(2),
+            )
+            .await
+            .unwrap();
+
+        let event = receiver.receive().await.unwrap().unwrap();
+        assert_eq!(event.body(), "event 5");
+        assert_eq!(event.sequence_number(), 5);
+        assert_eq!(event.offset(), 500);
+        assert_eq!(event.enqueued_time().timestamp(), 1_600_000_000);
+
+        let events = receiver
+            .receive_batch(10, std::time::Duration::from_millis(200))
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(receiver.last_received(), Some((700, 7)));
+
+        let attach = broker
+            .attaches()
+            .into_iter()
+            .find(|attach| attach.name.starts_with("hub/ConsumerGroups"))
+            .unwrap();
+        let source = attach.source.unwrap();
+        assert_eq!(
+            source.filter[0].1,
+            Value::described(
+                0x0000_468C_0000_0004,
+                "amqp.annotation.x-opt-sequence-number > '4'".into()
+            )
+        );
+        assert_eq!(
+            attach.properties,
+            vec![(Value::symbol("com.microsoft:epoch"), Value::Long(2))]
+        );
+
+        receiver.close().await.unwrap();
+        consumer.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn management_queries() {
+        let broker = broker().await;
+        let consumer = EventHubConsumerClient::new(
+            "ns",
+            "hub",
+            DEFAULT_CONSUMER_GROUP,
+            "policy",
+            "key",
+            options(&broker),
+        )
+        .unwrap();
+
+        let properties = consumer.get_event_hub_properties().await.unwrap();
+        assert_eq!(properties.name(), "hub");
+        assert_eq!(properties.partition_ids(), ["0", "1"]);
+        assert_eq!(consumer.get_partition_ids().await.unwrap(), ["0", "1"]);
+
+        let partition = consumer.get_partition_properties("1").await.unwrap();
+        assert_eq!(partition.id(), "1");
+        assert_eq!(partition.event_hub_name(), "hub");
+        assert_eq!(partition.beginning_sequence_number(), 3);
+        assert_eq!(partition.last_enqueued_sequence_number(), 41);
+        assert_eq!(partition.last_enqueued_offset(), 8192);
+        assert!(!partition.is_empty());
+
+        let request = broker
+            .received()
+            .into_iter()
+            .find(|(address, _, _)| address == "$management")
+            .unwrap()
+            .2;
+        assert!(request
+            .application_property("security_token")
+            .and_then(Value::as_str)
+            .unwrap()
+            .starts_with("SharedAccessSignature "));
+    }
+
+    #[derive(Default)]
+    struct RecordingHandler {
+        calls: Mutex<Vec<String>>,
+        events: Mutex<Vec<(String, i64)>>,
+    }
+
+    #[async_trait]
+    impl PartitionEventHandler for RecordingHandler {
+        async fn initialize(&self, context: &PartitionContext) -> HandlerResult {
+            let call = format!("initialize {}", context.partition_id());
+            self.calls.lock().unwrap().push(call);
+            Ok(())
+        }
+
+        async fn process_event(
+            &self,
+            context: &PartitionContext,
+            event: ReceivedEventData,
+        ) -> HandlerResult {
+            context.update_checkpoint(&event).await?;
+            let event = (context.partition_id().to_owned(), event.sequence_number());
+            self.events.lock().unwrap().push(event);
+            Ok(())
+        }
+
+        async fn close(&self, context: &PartitionContext, reason: CloseReason) -> HandlerResult {
+            let call = format!("close {} {:?}", context.partition_id(), reason);
+            self.calls.lock().unwrap().push(call);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn processor_claims_partitions_and_resumes_from_checkpoints() {
+        let broker = broker().await;
+        broker.enqueue("hub/ConsumerGroups/$Default/Partitions/0", event_message(5));
+        broker.enqueue("hub/ConsumerGroups/$Default/Partitions/0", event_message(6));
+        broker.enqueue("hub/ConsumerGroups/$Default/Partitions/1", event_message(1));
+
+        let store = Arc::new(InMemoryCheckpointStore::new());
+        let checkpoint = |partition_id: &str, sequence_number: i64| Checkpoint {
+            fully_qualified_namespace: "127.0.0.1".to_owned(),
+            event_hub_name: "hub".to_owned(),
+            consumer_group: DEFAULT_CONSUMER_GROUP.to_owned(),
+            partition_id: partition_id.to_owned(),
+            offset: Some(sequence_number * 100),
+            sequence_number: Some(sequence_number),
+        };
+        store.update_checkpoint(checkpoint("0", 4)).await.unwrap();
+
+        let consumer = EventHubConsumerClient::new(
+            "ns",
+            "hub",
+            DEFAULT_CONSUMER_GROUP,
+            "policy",
+            "key",
+            options(&broker),
+        )
+        .unwrap();
+        let handler = Arc::new(RecordingHandler::default());
+        let processor = EventProcessor::new(
+            consumer,
+            store.clone(),
+            handler.clone(),
+            EventProcessorOptions::default()
+                .owner_id("processor-1")
+                .load_balancing_interval(Duration::from_millis(20))
+                .start_position(EventPosition::earliest()),
+        );
+        processor.start().await;
+        assert!(processor.is_running().await);
+
+        for _ in 0..250 {
+            if handler.events.lock().unwrap().len() == 3 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        let ownership = store
+            .list_ownership("127.0.0.1", "hub", DEFAULT_CONSUMER_GROUP)
+            .await
+            .unwrap();
+        assert_eq!(ownership.len(), 2);
+        assert!(ownership.iter().all(|o| o.owner_id == "processor-1"));
+
+        processor.stop().await.unwrap();
+        assert!(!processor.is_running().await);
+
+        let mut events = handler.events.lock().unwrap().clone();
+        events.sort();
+        assert_eq!(
+            events,
+            [
+                ("0".to_owned(), 5),
+                ("0".to_owned(), 6),
+                ("1".to_owned(), 1)
+            ]
+        );
+        let mut calls = handler.calls.lock().unwrap().clone();
+        calls.sort();
+        assert_eq!(
+            calls,
+            [
+                "close 0 Shutdown",
+                "close 1 Shutdown",
+                "initialize 0",
+                "initialize 1"
+            ]
+        );
+
+        let mut checkpoints = store
+            .list_checkpoints("127.0.0.1", "hub", DEFAULT_CONSUMER_GROUP)
+            .await
+            .unwrap();
+        checkpoints.sort_by(|a, b| a.partition_id.cmp(&b.partition_id));
+        assert_eq!(checkpoints, [checkpoint("0", 6), checkpoint("1", 1)]);
+
+        let ownership = store
+            .list_ownership("127.0.0.1", "hub", DEFAULT_CONSUMER_GROUP)
+            .await
+            .unwrap();
+        assert!(ownership.iter().all(|o| o.owner_id.is_empty()));
+
+        let filter = |partition_id: &str| {
+            let attach = broker
+                .attaches()
+                .into_iter()
+                .find(|attach| {
+                    attach.name.starts_with(&format!(
+                        "hub/ConsumerGroups/$Default/Partitions/{}-",
+                        partition_id
+                    ))
+                })
+                .unwrap();
+            assert_eq!(
+                attach.properties,
+                vec![(Value::symbol("com.microsoft:epoch"), Value::Long(0))]
+            );
+            attach.source.unwrap().filter[0].1.clone()
+        };
+        let expression =
+            |expression: &str| Value::described(0x0000_468C_0000_0004, expression.into());
+        assert_eq!(
+            filter("0"),
+            expression("amqp.annotation.x-opt-offset > '400'")
+        );
+        assert_eq!(
+            filter("1"),
+            expression("amqp.annotation.x-opt-offset > '-1'")
+        );
+    }
+}
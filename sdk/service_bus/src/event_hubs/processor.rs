@@ -0,0 +1,454 @@
+use super::checkpoint_store::{Checkpoint, CheckpointStore, PartitionOwnership};
+use super::consumer::{EventHubConsumerClient, EventPosition, ReceiveOptions};
+use super::event::ReceivedEventData;
+use super::load_balancer::LoadBalancer;
+use crate::Error;
+use async_trait::async_trait;
+use azure_core::setters;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+/// The result of the callbacks of a [`PartitionEventHandler`].
+pub type HandlerResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+/// Why the processing of a partition stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseReason {
+    /// Another processor claimed the partition, or the service closed its receiver.
+    OwnershipLost,
+    /// The processor was stopped.
+    Shutdown,
+}
+
+/// The partition whose events are handled, through which their processing is checkpointed.
+pub struct PartitionContext {
+    fully_qualified_namespace: String,
+    event_hub_name: String,
+    consumer_group: String,
+    partition_id: String,
+    checkpoint_store: Arc<dyn CheckpointStore>,
+}
+
+impl std::fmt::Debug for PartitionContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PartitionContext")
+            .field("fully_qualified_namespace", &self.fully_qualified_namespace)
+            .field("event_hub_name", &self.event_hub_name)
+            .field("consumer_group", &self.consumer_group)
+            .field("partition_id", &self.partition_id)
+            .finish()
+    }
+}
+
+impl PartitionContext {
+    pub fn fully_qualified_namespace(&self) -> &str {
+        &self.fully_qualified_namespace
+    }
+
+    pub fn event_hub_name(&self) -> &str {
+        &self.event_hub_name
+    }
+
+    pub fn consumer_group(&self) -> &str {
+        &self.consumer_group
+    }
+
+    pub fn partition_id(&self) -> &str {
+        &self.partition_id
+    }
+
+    /// Records `event` as the last event processed in the partition: the processor claiming the
+    /// partition next resumes after it.
+    pub async fn update_checkpoint(&self, event: &ReceivedEventData) -> Result<(), Error> {
+        self.checkpoint_store
+            .update_checkpoint(Checkpoint {
+                fully_qualified_namespace: self.fully_qualified_namespace.clone(),
+                event_hub_name: self.event_hub_name.clone(),
+                consumer_group: self.consumer_group.clone(),
+                partition_id: self.partition_id.clone(),
+                offset: Some(event.offset()),
+                sequence_number: Some(event.sequence_number()),
+            })
+            .await
+    }
+}
+
+/// The callbacks of an [`EventProcessor`], called concurrently for the partitions it owns and
+/// in order for the events of each partition.
+#[async_trait]
+pub trait PartitionEventHandler: Send + Sync {
+    /// Called once a partition is claimed, before its events are read.
+    async fn initialize(&self, _context: &PartitionContext) -> HandlerResult {
+        Ok(())
+    }
+
+    async fn process_event(
+        &self,
+        context: &PartitionContext,
+        event: ReceivedEventData,
+    ) -> HandlerResult;
+
+    /// Called with the errors of the processor, and with those returned by the other callbacks
+    /// as [`Error::Handler`]. `partition_id` is `None` for the errors of the load balancing.
+    async fn process_error(&self, partition_id: Option<&str>, error: Error) {
+        match partition_id {
+            Some(partition_id) => {
+                log::warn!("error processing partition {}: {}", partition_id, error)
+            }
+            None => log::warn!("error balancing the partitions: {}", error),
+        }
+    }
+
+    /// Called once the events of a partition are no longer read.
+    async fn close(&self, _context: &PartitionContext, _reason: CloseReason) -> HandlerResult {
+        Ok(())
+    }
+}
+
+/// Options of an [`EventProcessor`].
+#[derive(Debug, Clone)]
+pub struct EventProcessorOptions {
+    owner_id: Option<String>,
+    load_balancing_interval: Duration,
+    ownership_expiration_interval: Duration,
+    start_position: EventPosition,
+    owner_level: Option<i64>,
+}
+
+impl Default for EventProcessorOptions {
+    fn default() -> Self {
+        Self {
+            owner_id: None,
+            load_balancing_interval: Duration::from_secs(10),
+            ownership_expiration_interval: Duration::from_secs(30),
+            start_position: EventPosition::Latest,
+            owner_level: Some(0),
+        }
+    }
+}
+
+impl EventProcessorOptions {
+    setters! {
+        owner_id: String => Some(owner_id),
+        load_balancing_interval: Duration => load_balancing_interval,
+        ownership_expiration_interval: Duration => ownership_expiration_interval,
+        start_position: EventPosition => start_position,
+        owner_level: i64 => Some(owner_level),
+    }
+}
+
+struct Shared {
+    consumer: EventHubConsumerClient,
+    checkpoint_store: Arc<dyn CheckpointStore>,
+    handler: Arc<dyn PartitionEventHandler>,
+    balancer: LoadBalancer,
+    owner_id: String,
+    options: EventProcessorOptions,
+}
+
+struct Running {
+    shutdown: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+/// Reads the events of every partition of an Event Hub, sharing the partitions with the other
+/// processors of the consumer group.
+///
+/// The processors claim the partitions through the ownerships of a [`CheckpointStore`] and
+/// balance them evenly among themselves, an ownership expiring unless its owner renews it.
+/// The events of a claimed partition are read from its last checkpoint, or from the start
+/// position of the options without one, and passed to the [`PartitionEventHandler`].
+pub struct EventProcessor {
+    shared: Arc<Shared>,
+    running: tokio::sync::Mutex<Option<Running>>,
+}
+
+impl std::fmt::Debug for EventProcessor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventProcessor")
+            .field("consumer", &self.shared.consumer)
+            .field("owner_id", &self.shared.owner_id)
+            .field("options", &self.shared.options)
+            .finish()
+    }
+}
+
+impl EventProcessor {
+    pub fn new(
+        consumer: EventHubConsumerClient,
+        checkpoint_store: Arc<dyn CheckpointStore>,
+        handler: Arc<dyn PartitionEventHandler>,
+        options: EventProcessorOptions,
+    ) -> Self {
+        let owner_id = options
+            .owner_id
+            .clone()
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        Self {
+            shared: Arc::new(Shared {
+                consumer,
+                checkpoint_store,
+                handler,
+                balancer: LoadBalancer::new(
+                    owner_id.clone(),
+                    options.ownership_expiration_interval,
+                ),
+                owner_id,
+                options,
+            }),
+            running: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    /// The identifier of the processor in the partition ownerships.
+    pub fn owner_id(&self) -> &str {
+        &self.shared.owner_id
+    }
+
+    pub async fn is_running(&self) -> bool {
+        self.running.lock().await.is_some()
+    }
+
+    /// Starts balancing the partitions in the background, at every load balancing interval.
+    pub async fn start(&self) {
+        let mut running = self.running.lock().await;
+        if running.is_none() {
+            let (shutdown, stopped) = oneshot::channel();
+            let task = tokio::spawn(self.shared.clone().run(stopped));
+            *running = Some(Running { shutdown, task });
+        }
+    }
+
+    /// Stops reading the partitions, relinquishes their ownership and closes the consumer.
+    pub async fn stop(&self) -> Result<(), Error> {
+        let running = self.running.lock().await.take();
+        if let Some(running) = running {
+            let _ = running.shutdown.send(());
+            if let Err(error) = running.task.await {
+                log::warn!("the event processor task failed: {}", error);
+            }
+        }
+        self.shared.consumer.close().await
+    }
+}
+
+struct PartitionTask {
+    stop: oneshot::Sender<CloseReason>,
+    task: JoinHandle<()>,
+}
+
+impl PartitionTask {
+    fn start(shared: Arc<Shared>, partition_id: String) -> Self {
+        let (stop, stopped) = oneshot::channel();
+        let task =
+            tokio::spawn(async move { shared.process_partition(partition_id, stopped).await });
+        Self { stop, task }
+    }
+
+    /// Whether the task ended on its own, on an error or when the service closed its receiver.
+    fn is_finished(&self) -> bool {
+        self.stop.is_closed()
+    }
+
+    async fn stop(self, reason: CloseReason) {
+        let _ = self.stop.send(reason);
+        if let Err(error) = self.task.await {
+            log::warn!("the partition processing task failed: {}", error);
+        }
+    }
+}
+
+impl Shared {
+    async fn run(self: Arc<Self>, mut shutdown: oneshot::Receiver<()>) {
+        let mut tasks = HashMap::new();
+        let mut owned = Vec::new();
+        loop {
+            if let Err(error) = self.balance(&mut tasks, &mut owned).await {
+                self.handler.process_error(None, error).await;
+            }
+            tokio::select! {
+                _ = &mut shutdown => break,
+                _ = tokio::time::sleep(self.options.load_balancing_interval) => {}
+            }
+        }
+
+        for (_, task) in tasks.drain() {
+            task.stop(CloseReason::Shutdown).await;
+        }
+        let relinquished = owned
+            .into_iter()
+            .map(|ownership| PartitionOwnership {
+                owner_id: String::new(),
+                ..ownership
+            })
+            .collect::<Vec<_>>();
+        if !relinquished.is_empty() {
+            if let Err(error) = self.checkpoint_store.claim_ownership(relinquished).await {
+                self.handler.process_error(None, error).await;
+            }
+        }
+    }
+
+    /// Runs a load balancing cycle: renews the ownerships of the processor and claims a new
+    /// partition if it owns less than its share, then starts and stops reading the partitions
+    /// accordingly.
+    async fn balance(
+        self: &Arc<Self>,
+        tasks: &mut HashMap<String, PartitionTask>,
+        owned: &mut Vec<PartitionOwnership>,
+    ) -> Result<(), Error> {
+        let partition_ids = self.consumer.get_partition_ids().await?;
+        let current = self
+            .checkpoint_store
+            .list_ownership(
+                self.consumer.fully_qualified_namespace(),
+                self.consumer.event_hub(),
+                self.consumer.consumer_group(),
+            )
+            .await?;
+
+        let claims = self
+            .balancer
+            .claims(&partition_ids, &current, Utc::now())
+            .into_iter()
+            .map(|partition_id| {
+                let stored = current
+                    .iter()
+                    .find(|ownership| ownership.partition_id == partition_id);
+                PartitionOwnership {
+                    fully_qualified_namespace: self.consumer.fully_qualified_namespace().to_owned(),
+                    event_hub_name: self.consumer.event_hub().to_owned(),
+                    consumer_group: self.consumer.consumer_group().to_owned(),
+                    partition_id,
+                    owner_id: self.owner_id.clone(),
+                    last_modified_time: stored.and_then(|ownership| ownership.last_modified_time),
+                    etag: stored.and_then(|ownership| ownership.etag.clone()),
+                }
+            })
+            .collect();
+        *owned = self.checkpoint_store.claim_ownership(claims).await?;
+
+        let lost = tasks
+            .keys()
+            .filter(|partition_id| {
+                !owned
+                    .iter()
+                    .any(|ownership| &ownership.partition_id == *partition_id)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        for partition_id in lost {
+            if let Some(task) = tasks.remove(&partition_id) {
+                task.stop(CloseReason::OwnershipLost).await;
+            }
+        }
+        // The partitions whose reading ended on its own are read again while still owned.
+        tasks.retain(|_, task| !task.is_finished());
+        for ownership in owned.iter() {
+            if !tasks.contains_key(&ownership.partition_id) {
+                let task = PartitionTask::start(self.clone(), ownership.partition_id.clone());
+                tasks.insert(ownership.partition_id.clone(), task);
+            }
+        }
+        Ok(())
+    }
+
+    async fn process_partition(
+        &self,
+        partition_id: String,
+        mut stopped: oneshot::Receiver<CloseReason>,
+    ) {
+        let context = PartitionContext {
+            fully_qualified_namespace: self.consumer.fully_qualified_namespace().to_owned(),
+            event_hub_name: self.consumer.event_hub().to_owned(),
+            consumer_group: self.consumer.consumer_group().to_owned(),
+            partition_id,
+            checkpoint_store: self.checkpoint_store.clone(),
+        };
+        if let Err(error) = self.handler.initialize(&context).await {
+            self.handler
+                .process_error(Some(&context.partition_id), Error::Handler(error))
+                .await;
+        }
+        let reason = match self.read_partition(&context, &mut stopped).await {
+            Ok(reason) => reason,
+            Err(error) => {
+                self.handler
+                    .process_error(Some(&context.partition_id), error)
+                    .await;
+                CloseReason::OwnershipLost
+            }
+        };
+        if let Err(error) = self.handler.close(&context, reason).await {
+            self.handler
+                .process_error(Some(&context.partition_id), Error::Handler(error))
+                .await;
+        }
+    }
+
+    async fn read_partition(
+        &self,
+        context: &PartitionContext,
+        stopped: &mut oneshot::Receiver<CloseReason>,
+    ) -> Result<CloseReason, Error> {
+        let position = self.start_position(&context.partition_id).await?;
+        let mut options = ReceiveOptions::default();
+        if let Some(owner_level) = self.options.owner_level {
+            options = options.owner_level(owner_level);
+        }
+        let mut receiver = self
+            .consumer
+            .receive_from_partition(&context.partition_id, position, options)
+            .await?;
+        let reason = loop {
+            tokio::select! {
+                reason = &mut *stopped => break reason.unwrap_or(CloseReason::Shutdown),
+                received = receiver.receive() => match received? {
+                    Some(event) => {
+                        if let Err(error) = self.handler.process_event(context, event).await {
+                            self.handler
+                                .process_error(Some(&context.partition_id), Error::Handler(error))
+                                .await;
+                        }
+                    }
+                    None => break CloseReason::OwnershipLost,
+                },
+            }
+        };
+        if let Err(error) = receiver.close().await {
+            log::debug!("cannot close the receiver of the partition: {}", error);
+        }
+        Ok(reason)
+    }
+
+    /// The position following the checkpoint of the partition, if any.
+    async fn start_position(&self, partition_id: &str) -> Result<EventPosition, Error> {
+        let checkpoint = self
+            .checkpoint_store
+            .list_checkpoints(
+                self.consumer.fully_qualified_namespace(),
+                self.consumer.event_hub(),
+                self.consumer.consumer_group(),
+            )
+            .await?
+            .into_iter()
+            .find(|checkpoint| checkpoint.partition_id == partition_id);
+        let position = match checkpoint {
+            Some(Checkpoint {
+                offset: Some(offset),
+                ..
+            }) => EventPosition::from_offset(offset, false),
+            Some(Checkpoint {
+                sequence_number: Some(sequence_number),
+                ..
+            }) => EventPosition::from_sequence_number(sequence_number, false),
+            _ => self.options.start_position.clone(),
+        };
+        Ok(position)
+    }
+}
@@ -0,0 +1,187 @@
+use super::batch::{CreateBatchOptions, EventDataBatch, BATCH_MESSAGE_FORMAT};
+use super::connection::{EventHubClientOptions, EventHubConnection};
+use super::event::EventData;
+use super::properties::{
+    get_event_hub_properties, get_partition_properties, EventHubProperties, PartitionProperties,
+};
+use crate::amqp::connection::SenderLink;
+use crate::amqp::performatives::{Attach, Role, Terminus};
+use crate::Error;
+use azure_core::setters;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// The message size limit used when the service does not advertise one.
+const DEFAULT_MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+
+/// Options of [`EventHubProducerClient::send_event`].
+#[derive(Debug, Clone, Default)]
+pub struct SendEventOptions {
+    partition_id: Option<String>,
+    partition_key: Option<String>,
+}
+
+impl SendEventOptions {
+    setters! {
+        partition_id: String => Some(partition_id),
+        partition_key: String => Some(partition_key),
+    }
+}
+
+/// A client publishing events to an Event Hub over AMQP.
+///
+/// Events are sent to the partition chosen by the service, to an explicit partition, or to the
+/// partition derived from a partition key.
+#[derive(Debug)]
+pub struct EventHubProducerClient {
+    connection: EventHubConnection,
+    senders: tokio::sync::Mutex<HashMap<Option<String>, Arc<SenderLink>>>,
+}
+
+impl EventHubProducerClient {
+    pub fn new<N, E, P, K>(
+        namespace: N,
+        event_hub: E,
+        policy_name: P,
+        key: K,
+        options: EventHubClientOptions,
+    ) -> Result<Self, Error>
+    where
+        N: AsRef<str>,
+        E: Into<String>,
+        P: Into<String>,
+        K: AsRef<str>,
+    {
+        Ok(Self {
+            connection: EventHubConnection::new(
+                namespace.as_ref(),
+                event_hub.into(),
+                policy_name.into(),
+                key.as_ref(),
+                options,
+            )?,
+            senders: tokio::sync::Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub fn event_hub(&self) -> &str {
+        self.connection.event_hub()
+    }
+
+    pub async fn get_event_hub_properties(&self) -> Result<EventHubProperties, Error> {
+        get_event_hub_properties(&self.connection).await
+    }
+
+    pub async fn get_partition_ids(&self) -> Result<Vec<String>, Error> {
+        Ok(self
+            .get_event_hub_properties()
+            .await?
+            .partition_ids()
+            .to_vec())
+    }
+
+    pub async fn get_partition_properties(
+        &self,
+        partition_id: &str,
+    ) -> Result<PartitionProperties, Error> {
+        get_partition_properties(&self.connection, partition_id).await
+    }
+
+    /// Creates an empty batch, limited to the maximum message size of the Event Hub unless a
+    /// smaller size is requested.
+    pub async fn create_batch(&self, options: CreateBatchOptions) -> Result<EventDataBatch, Error> {
+        check_routing(&options.partition_id, &options.partition_key)?;
+        let sender = self.sender(options.partition_id.as_deref()).await?;
+        let link_max_size = sender
+            .max_message_size()
+            .map(|size| size as usize)
+            .unwrap_or(DEFAULT_MAX_MESSAGE_SIZE);
+        let max_size_in_bytes = match options.max_size_in_bytes {
+            Some(size) if size > link_max_size => {
+                return Err(Error::InvalidArgument(format!(
+                    "the batch size of {} bytes exceeds the maximum message size of {} bytes",
+                    size, link_max_size
+                )))
+            }
+            Some(size) => size,
+            None => link_max_size,
+        };
+        Ok(EventDataBatch::new(
+            options.partition_id,
+            options.partition_key,
+            max_size_in_bytes,
+        ))
+    }
+
+    /// Sends the events of `batch` in a single message.
+    pub async fn send_batch(&self, batch: EventDataBatch) -> Result<(), Error> {
+        if batch.is_empty() {
+            return Ok(());
+        }
+        let sender = self.sender(batch.partition_id()).await?;
+        sender
+            .send(BATCH_MESSAGE_FORMAT, batch.to_message().to_bytes())
+            .await
+    }
+
+    pub async fn send_event(
+        &self,
+        event: EventData,
+        options: SendEventOptions,
+    ) -> Result<(), Error> {
+        check_routing(&options.partition_id, &options.partition_key)?;
+        let sender = self.sender(options.partition_id.as_deref()).await?;
+        let message = event.to_message(options.partition_key.as_deref());
+        sender.send(0, message.to_bytes()).await
+    }
+
+    /// Returns the link sending to the Event Hub or to one of its partitions, attaching it on
+    /// first use or once the previous one was closed.
+    async fn sender(&self, partition_id: Option<&str>) -> Result<Arc<SenderLink>, Error> {
+        let connection = self.connection.authorize().await?;
+        let mut senders = self.senders.lock().await;
+        let key = partition_id.map(ToOwned::to_owned);
+        if let Some(sender) = senders.get(&key).filter(|sender| !sender.is_closed()) {
+            return Ok(sender.clone());
+        }
+        let address = match partition_id {
+            Some(partition_id) => format!(
+                "{}/Partitions/{}",
+                self.connection.event_hub(),
+                partition_id
+            ),
+            None => self.connection.event_hub().to_owned(),
+        };
+        let mut attach = Attach::new(
+            format!("{}-sender-{}", address, Uuid::new_v4()),
+            0,
+            Role::Sender,
+        );
+        attach.source = Some(Terminus::new(Uuid::new_v4().to_string()));
+        attach.target = Some(Terminus::new(address));
+        let sender = Arc::new(connection.attach_sender(attach).await?);
+        senders.insert(key, sender.clone());
+        Ok(sender)
+    }
+
+    /// Closes the links and the connection of the client.
+    pub async fn close(&self) -> Result<(), Error> {
+        for (_, sender) in self.senders.lock().await.drain() {
+            let _ = sender.close().await;
+        }
+        self.connection.close().await
+    }
+}
+
+fn check_routing(
+    partition_id: &Option<String>,
+    partition_key: &Option<String>,
+) -> Result<(), Error> {
+    if partition_id.is_some() && partition_key.is_some() {
+        return Err(Error::InvalidArgument(
+            "an event cannot be sent with both a partition id and a partition key".to_owned(),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use super::connection::EventHubConnection;
+use crate::amqp::message::{Body, Message};
+use crate::amqp::Value;
+use crate::Error;
+use chrono::{DateTime, TimeZone, Utc};
+
+const EVENT_HUB_ENTITY_TYPE: &str = "com.microsoft:eventhub";
+const PARTITION_ENTITY_TYPE: &str = "com.microsoft:partition";
+
+/// The properties of an Event Hub, as returned by the management node.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventHubProperties {
+    name: String,
+    created_on: DateTime<Utc>,
+    partition_ids: Vec<String>,
+}
+
+impl EventHubProperties {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn created_on(&self) -> DateTime<Utc> {
+        self.created_on
+    }
+
+    pub fn partition_ids(&self) -> &[String] {
+        &self.partition_ids
+    }
+}
+
+/// The runtime properties of a partition of an Event Hub.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartitionProperties {
+    event_hub_name: String,
+    id: String,
+    beginning_sequence_number: i64,
+    last_enqueued_sequence_number: i64,
+    last_enqueued_offset: i64,
+    last_enqueued_time: DateTime<Utc>,
+    is_empty: bool,
+}
+
+impl PartitionProperties {
+    pub fn event_hub_name(&self) -> &str {
+        &self.event_hub_name
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn beginning_sequence_number(&self) -> i64 {
+        self.beginning_sequence_number
+    }
+
+    pub fn last_enqueued_sequence_number(&self) -> i64 {
+        self.last_enqueued_sequence_number
+    }
+
+    pub fn last_enqueued_offset(&self) -> i64 {
+        self.last_enqueued_offset
+    }
+
+    pub fn last_enqueued_time(&self) -> DateTime<Utc> {
+        self.last_enqueued_time
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.is_empty
+    }
+}
+
+/// Builds a `READ` request of the management node for the entity `name` of `entity_type`.
+fn read_request(entity_type: &str, name: &str, partition_id: Option<&str>) -> Message {
+    let mut application_properties = vec![
+        ("operation".into(), "READ".into()),
+        ("name".into(), name.into()),
+        ("type".into(), entity_type.into()),
+    ];
+    if let Some(partition_id) = partition_id {
+        application_properties.push(("partition".into(), partition_id.into()));
+    }
+    Message {
+        application_properties,
+        ..Default::default()
+    }
+}
+
+fn response_map(response: &Message) -> Result<&Value, Error> {
+    match &response.body {
+        Body::Value(value @ Value::Map(_)) => Ok(value),
+        body => Err(Error::Decode(format!(
+            "unexpected management response body {:?}",
+            body
+        ))),
+    }
+}
+
+fn string(map: &Value, key: &str) -> Result<String, Error> {
+    map.get(key)
+        .and_then(Value::as_str)
+        .map(ToOwned::to_owned)
+        .ok_or_else(|| Error::Decode(format!("missing {} in the management response", key)))
+}
+
+fn integer(map: &Value, key: &str) -> Result<i64, Error> {
+    map.get(key)
+        .and_then(|value| match value {
+            Value::String(value) => value.parse().ok(),
+            value => value.as_i64(),
+        })
+        .ok_or_else(|| Error::Decode(format!("missing {} in the management response", key)))
+}
+
+fn timestamp(map: &Value, key: &str) -> DateTime<Utc> {
+    map.get(key)
+        .and_then(Value::as_timestamp)
+        .and_then(|millis| Utc.timestamp_millis_opt(millis).single())
+        .unwrap_or_else(|| Utc.timestamp_opt(0, 0).unwrap())
+}
+
+impl EventHubProperties {
+    pub(crate) fn from_response(response: &Message) -> Result<Self, Error> {
+        let map = response_map(response)?;
+        Ok(Self {
+            name: string(map, "name")?,
+            created_on: timestamp(map, "created_at"),
+            partition_ids: map
+                .get("partition_ids")
+                .cloned()
+                .map(Value::into_multiple)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|id| id.as_str().map(ToOwned::to_owned))
+                .collect(),
+        })
+    }
+}
+
+impl PartitionProperties {
+    pub(crate) fn from_response(response: &Message) -> Result<Self, Error> {
+        let map = response_map(response)?;
+        Ok(Self {
+            event_hub_name: string(map, "name")?,
+            id: string(map, "partition")?,
+            beginning_sequence_number: integer(map, "begin_sequence_number")?,
+            last_enqueued_sequence_number: integer(map, "last_enqueued_sequence_number")?,
+            last_enqueued_offset: integer(map, "last_enqueued_offset")?,
+            last_enqueued_time: timestamp(map, "last_enqueued_time_utc"),
+            is_empty: map
+                .get("is_partition_empty")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+        })
+    }
+}
+
+pub(crate) async fn get_event_hub_properties(
+    connection: &EventHubConnection,
+) -> Result<EventHubProperties, Error> {
+    let request = read_request(EVENT_HUB_ENTITY_TYPE, connection.event_hub(), None);
+    EventHubProperties::from_response(&connection.management_request(request).await?)
+}
+
+pub(crate) async fn get_partition_properties(
+    connection: &EventHubConnection,
+    partition_id: &str,
+) -> Result<PartitionProperties, Error> {
+    let request = read_request(
+        PARTITION_ENTITY_TYPE,
+        connection.event_hub(),
+        Some(partition_id),
+    );
+    PartitionProperties::from_response(&connection.management_request(request).await?)
+}
@@ -1,8 +1,11 @@
 #![recursion_limit = "128"]
 
-// #[macro_use]
-// extern crate log;
+pub mod administration;
+mod amqp;
+mod errors;
+pub mod event_hubs;
+pub mod prelude;
+mod sas;
+pub mod service_bus;
 
-// // TODO update service_bus to use HttpClient https://github.com/Azure/azure-sdk-for-rust/issues/255
-// pub mod event_hub;
-// pub mod prelude;
+pub use errors::Error;
@@ -1 +1,6 @@
-pub use crate::event_hub::Client;
+pub use crate::administration::ServiceBusAdministrationClient;
+pub use crate::event_hubs::{EventHubConsumerClient, EventHubProducerClient};
+pub use crate::service_bus::{
+    AutoLockRenewer, ServiceBusClient, ServiceBusClientOptions, ServiceBusMessage,
+    ServiceBusReceiver, ServiceBusSender, ServiceBusSessionReceiver,
+};
@@ -0,0 +1,60 @@
+use chrono::Duration;
+use ring::hmac;
+use std::ops::Add;
+use url::form_urlencoded::{self, Serializer};
+
+/// Generates a Shared Access Signature for `url`, valid for `ttl`, signed with the key of the
+/// shared access policy `policy_name`.
+pub(crate) fn generate_signature(
+    policy_name: &str,
+    signing_key: &hmac::Key,
+    url: &str,
+    ttl: Duration,
+) -> String {
+    let expiry = ::chrono::Utc::now().add(ttl).timestamp();
+    log::debug!("expiry == {:?}", expiry);
+
+    let url_encoded: String = form_urlencoded::byte_serialize(url.as_bytes()).collect();
+    log::debug!("url_encoded == {:?}", url_encoded);
+
+    let str_to_sign = format!("{}\n{}", url_encoded, expiry);
+    log::debug!("str_to_sign == {:?}", str_to_sign);
+
+    let sig = hmac::sign(signing_key, str_to_sign.as_bytes());
+    let sig = {
+        let sig = ::base64::encode(sig.as_ref());
+        log::debug!("sig == {}", sig);
+        let mut ser = Serializer::new(String::new());
+        ser.append_pair("sig", &sig);
+        let sig = ser.finish();
+        log::debug!("sig == {}", sig);
+        sig
+    };
+
+    log::debug!("sig == {:?}", sig);
+
+    format!(
+        "SharedAccessSignature sr={}&{}&se={}&skn={}",
+        &url_encoded, sig, expiry, policy_name
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_format() {
+        let key = hmac::Key::new(hmac::HMAC_SHA256, b"secret");
+        let sas = generate_signature(
+            "RootManageSharedAccessKey",
+            &key,
+            "amqps://ns.servicebus.windows.net/hub",
+            Duration::hours(1),
+        );
+        assert!(sas.starts_with(
+            "SharedAccessSignature sr=amqps%3A%2F%2Fns.servicebus.windows.net%2Fhub&sig="
+        ));
+        assert!(sas.ends_with("&skn=RootManageSharedAccessKey"));
+    }
+}
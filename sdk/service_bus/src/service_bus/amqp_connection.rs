@@ -0,0 +1,161 @@
+use crate::amqp::cbs::{self, CBS_NODE};
+use crate::amqp::connection::{Connection, ReceiverLink};
+use crate::amqp::message::{Body, Message};
+use crate::amqp::performatives::Attach;
+use crate::amqp::request_response::{response_status, RequestResponseLink};
+use crate::amqp::Value;
+use crate::sas::generate_signature;
+use crate::Error;
+use chrono::{DateTime, Duration, Utc};
+use ring::hmac;
+use std::collections::HashMap;
+use std::sync::Arc;
+use url::Url;
+
+struct Active {
+    connection: Connection,
+    cbs: RequestResponseLink,
+    /// When the token authorizing each audience expires.
+    tokens: HashMap<String, DateTime<Utc>>,
+    /// The links to the management node of each entity.
+    management: HashMap<String, Arc<RequestResponseLink>>,
+}
+
+impl Active {
+    fn is_closed(&self) -> bool {
+        self.connection.is_closed() || self.cbs.is_closed()
+    }
+}
+
+/// The AMQP connection of a client, opened on first use, for the operations the REST API does
+/// not offer: sessions, deferral and dead-lettering.
+///
+/// Each entity is authorized separately with a Shared Access Signature, so that policies
+/// defined on the entities work as well as the policies of the namespace.
+pub(crate) struct AmqpConnection {
+    endpoint: Url,
+    policy_name: String,
+    signing_key: hmac::Key,
+    token_ttl: Duration,
+    active: tokio::sync::Mutex<Option<Active>>,
+}
+
+impl AmqpConnection {
+    pub(crate) fn new(
+        endpoint: Url,
+        policy_name: String,
+        signing_key: hmac::Key,
+        token_ttl: Duration,
+    ) -> Self {
+        Self {
+            endpoint,
+            policy_name,
+            signing_key,
+            token_ttl,
+            active: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    fn audience(&self, entity_path: &str) -> String {
+        format!(
+            "sb://{}/{}",
+            self.endpoint.host_str().unwrap_or_default(),
+            entity_path
+        )
+    }
+
+    /// Returns the connection, opening it if needed, once authorized to access `entity_path`.
+    ///
+    /// The token of the entity is renewed once half of its lifetime is elapsed.
+    pub(crate) async fn authorize(&self, entity_path: &str) -> Result<Connection, Error> {
+        let mut active = self.active.lock().await;
+        if active.as_ref().map(Active::is_closed).unwrap_or(true) {
+            let connection = Connection::open(&self.endpoint).await?;
+            let cbs = RequestResponseLink::attach(&connection, CBS_NODE).await?;
+            *active = Some(Active {
+                connection,
+                cbs,
+                tokens: HashMap::new(),
+                management: HashMap::new(),
+            });
+        }
+        let active = active.as_mut().ok_or(Error::ConnectionClosed)?;
+
+        let audience = self.audience(entity_path);
+        let expired = active
+            .tokens
+            .get(&audience)
+            .map(|expires_on| *expires_on - Utc::now() < self.token_ttl / 2)
+            .unwrap_or(true);
+        if expired {
+            let expires_on = Utc::now() + self.token_ttl;
+            let token = generate_signature(
+                &self.policy_name,
+                &self.signing_key,
+                &audience,
+                self.token_ttl,
+            );
+            cbs::put_token(&active.cbs, &audience, &token, expires_on).await?;
+            active.tokens.insert(audience, expires_on);
+        }
+        Ok(active.connection.clone())
+    }
+
+    /// Attaches a link receiving from `entity_path`.
+    pub(crate) async fn attach_receiver(
+        &self,
+        entity_path: &str,
+        attach: Attach,
+        credit: u32,
+    ) -> Result<ReceiverLink, Error> {
+        let connection = self.authorize(entity_path).await?;
+        connection.attach_receiver(attach, credit).await
+    }
+
+    /// Sends the `operation` request to the management node of `entity_path` and returns the
+    /// body of the response.
+    pub(crate) async fn management_request(
+        &self,
+        entity_path: &str,
+        operation: &str,
+        body: Vec<(Value, Value)>,
+    ) -> Result<Value, Error> {
+        let connection = self.authorize(entity_path).await?;
+        let management = {
+            let mut active = self.active.lock().await;
+            let active = active.as_mut().ok_or(Error::ConnectionClosed)?;
+            match active.management.get(entity_path) {
+                Some(management) if !management.is_closed() => management.clone(),
+                _ => {
+                    let node = format!("{}/$management", entity_path);
+                    let management =
+                        Arc::new(RequestResponseLink::attach(&connection, &node).await?);
+                    active
+                        .management
+                        .insert(entity_path.to_owned(), management.clone());
+                    management
+                }
+            }
+        };
+        let mut request = Message::with_value(Value::Map(body));
+        request.application_properties = vec![("operation".into(), operation.into())];
+        let response = management.call(request).await?;
+        match response_status(&response) {
+            (200..=299, _) => Ok(match response.body {
+                Body::Value(value) => value,
+                _ => Value::Null,
+            }),
+            (status_code, description) => Err(Error::Management {
+                status_code,
+                description,
+            }),
+        }
+    }
+
+    pub(crate) async fn close(&self) -> Result<(), Error> {
+        match self.active.lock().await.take() {
+            Some(active) => active.connection.close().await,
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use super::amqp_connection::AmqpConnection;
+use super::receiver::ServiceBusReceiver;
+use super::sender::ServiceBusSender;
+use crate::sas::generate_signature;
+use crate::Error;
+use azure_core::{setters, HttpClient};
+use bytes::Bytes;
+use chrono::Duration;
+use http::header::AUTHORIZATION;
+use http::{Method, Request, Response, StatusCode};
+use ring::hmac;
+use std::sync::Arc;
+use url::Url;
+
+/// Options of a [`ServiceBusClient`].
+#[derive(Debug, Clone)]
+pub struct ServiceBusClientOptions {
+    endpoint: Option<Url>,
+    /// The AMQP endpoint, used for sessions, deferral and dead-lettering.
+    amqp_endpoint: Option<Url>,
+    token_ttl: Duration,
+}
+
+impl Default for ServiceBusClientOptions {
+    fn default() -> Self {
+        Self {
+            endpoint: None,
+            amqp_endpoint: None,
+            token_ttl: Duration::hours(1),
+        }
+    }
+}
+
+impl ServiceBusClientOptions {
+    setters! {
+        endpoint: Url => Some(endpoint),
+        amqp_endpoint: Url => Some(amqp_endpoint),
+        token_ttl: Duration => token_ttl,
+    }
+}
+
+/// The state shared by a client and the senders and receivers it creates.
+pub(crate) struct ServiceBusConnection {
+    http_client: Arc<dyn HttpClient>,
+    endpoint: Url,
+    policy_name: String,
+    signing_key: hmac::Key,
+    token_ttl: Duration,
+    amqp: AmqpConnection,
+}
+
+impl std::fmt::Debug for ServiceBusConnection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServiceBusConnection")
+            .field("endpoint", &self.endpoint)
+            .field("policy_name", &self.policy_name)
+            .finish()
+    }
+}
+
+impl ServiceBusConnection {
+    pub(crate) fn new(
+        http_client: Arc<dyn HttpClient>,
+        namespace: &str,
+        policy_name: String,
+        key: &str,
+        options: ServiceBusClientOptions,
+    ) -> Result<Self, Error> {
+        let endpoint = match options.endpoint {
+            Some(endpoint) => endpoint,
+            None => Url::parse(&format!("https://{}.servicebus.windows.net", namespace))?,
+        };
+        let amqp_endpoint = match options.amqp_endpoint {
+            Some(amqp_endpoint) => amqp_endpoint,
+            None => Url::parse(&format!("amqps://{}.servicebus.windows.net", namespace))?,
+        };
+        let signing_key = hmac::Key::new(hmac::HMAC_SHA256, key.as_bytes());
+        Ok(Self {
+            http_client,
+            endpoint,
+            amqp: AmqpConnection::new(
+                amqp_endpoint,
+                policy_name.clone(),
+                signing_key.clone(),
+                options.token_ttl,
+            ),
+            policy_name,
+            signing_key,
+            token_ttl: options.token_ttl,
+        })
+    }
+
+    pub(crate) fn amqp(&self) -> &AmqpConnection {
+        &self.amqp
+    }
+
+    /// The URL of `path`, relative to the namespace.
+    pub(crate) fn url(&self, path: &str) -> Result<Url, Error> {
+        Ok(Url::parse(&format!(
+            "{}/{}",
+            self.endpoint.as_str().trim_end_matches('/'),
+            path
+        ))?)
+    }
+
+    /// A Shared Access Signature for the resource of `url`.
+    pub(crate) fn signature(&self, url: &Url) -> String {
+        let mut resource = url.clone();
+        resource.set_query(None);
+        generate_signature(
+            &self.policy_name,
+            &self.signing_key,
+            resource.as_str(),
+            self.token_ttl,
+        )
+    }
+
+    /// Starts a request to `url`, authorized with a Shared Access Signature for the resource.
+    pub(crate) fn request(&self, method: Method, url: &Url) -> http::request::Builder {
+        Request::builder()
+            .method(method)
+            .uri(url.as_str())
+            .header(AUTHORIZATION, self.signature(url))
+    }
+
+    pub(crate) async fn execute(
+        &self,
+        request: Request<Bytes>,
+        expected_statuses: &[StatusCode],
+    ) -> Result<Response<Bytes>, Error> {
+        log::debug!("{} {}", request.method(), request.uri());
+        Ok(self
+            .http_client
+            .execute_request_check_statuses(request, expected_statuses)
+            .await?)
+    }
+}
+
+/// A client of the queues and topics of a Service Bus namespace, using its REST API and
+/// authorized with a Shared Access Signature.
+///
+/// The client creates the senders and receivers of the entities of the namespace, which share
+/// its HTTP client and credentials.
+#[derive(Debug, Clone)]
+pub struct ServiceBusClient {
+    connection: Arc<ServiceBusConnection>,
+}
+
+impl ServiceBusClient {
+    pub fn new<N, P, K>(
+        http_client: Arc<dyn HttpClient>,
+        namespace: N,
+        policy_name: P,
+        key: K,
+        options: ServiceBusClientOptions,
+    ) -> Result<Self, Error>
+    where
+        N: AsRef<str>,
+        P: Into<String>,
+        K: AsRef<str>,
+    {
+        Ok(Self {
+            connection: Arc::new(ServiceBusConnection::new(
+                http_client,
+                namespace.as_ref(),
+                policy_name.into(),
+                key.as_ref(),
+                options,
+            )?),
+        })
+    }
+
+    /// Creates a sender of messages to `queue`.
+    pub fn queue_sender(&self, queue: impl Into<String>) -> ServiceBusSender {
+        ServiceBusSender::new(self.connection.clone(), queue.into())
+    }
+
+    /// Creates a sender publishing messages to `topic`.
+    pub fn topic_sender(&self, topic: impl Into<String>) -> ServiceBusSender {
+        ServiceBusSender::new(self.connection.clone(), topic.into())
+    }
+
+    /// Creates a receiver of the messages of `queue`.
+    pub fn queue_receiver(&self, queue: impl AsRef<str>) -> ServiceBusReceiver {
+        ServiceBusReceiver::new(self.connection.clone(), queue.as_ref().to_owned())
+    }
+
+    /// Creates a receiver of the messages of `subscription` to `topic`.
+    pub fn subscription_receiver(
+        &self,
+        topic: impl AsRef<str>,
+        subscription: impl AsRef<str>,
+    ) -> ServiceBusReceiver {
+        ServiceBusReceiver::new(
+            self.connection.clone(),
+            subscription_path(topic.as_ref(), subscription.as_ref()),
+        )
+    }
+
+    /// Creates a receiver of the dead-letter queue of `queue`, holding the messages which
+    /// expired or could not be processed.
+    pub fn dead_letter_queue_receiver(&self, queue: impl AsRef<str>) -> ServiceBusReceiver {
+        ServiceBusReceiver::new(self.connection.clone(), dead_letter_path(queue.as_ref()))
+    }
+
+    /// Creates a receiver of the dead-letter queue of `subscription` to `topic`.
+    pub fn dead_letter_subscription_receiver(
+        &self,
+        topic: impl AsRef<str>,
+        subscription: impl AsRef<str>,
+    ) -> ServiceBusReceiver {
+        ServiceBusReceiver::new(
+            self.connection.clone(),
+            dead_letter_path(&subscription_path(topic.as_ref(), subscription.as_ref())),
+        )
+    }
+
+    /// Closes the AMQP connection of the client, and with it the session receivers it opened.
+    pub async fn close(&self) -> Result<(), Error> {
+        self.connection.amqp().close().await
+    }
+}
+
+pub(crate) fn subscription_path(topic: &str, subscription: &str) -> String {
+    format!("{}/subscriptions/{}", topic, subscription)
+}
+
+fn dead_letter_path(entity_path: &str) -> String {
+    format!("{}/$DeadLetterQueue", entity_path)
+}
@@ -0,0 +1,109 @@
+use super::receiver::PeekLockResponse;
+use crate::Error;
+use azure_core::setters;
+use chrono::Utc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+/// Keeps the locks of messages alive in the background while they are processed.
+///
+/// A message whose processing outlasts the lock duration of its entity is otherwise delivered
+/// again to another receiver.
+///
+/// The renewals run on a task spawned with `tokio::spawn`: `register` must be called from within
+/// a Tokio runtime, like the AMQP connections of this crate.
+#[derive(Debug, Clone)]
+pub struct AutoLockRenewer {
+    /// How often the lock is renewed; shorter than the lock duration of the entity. The first
+    /// renewal happens at the latest this long before the current lock expires.
+    renew_interval: Duration,
+    /// How long the lock is kept alive at most, after which it expires as usual.
+    max_lock_renewal_duration: Duration,
+}
+
+impl Default for AutoLockRenewer {
+    fn default() -> Self {
+        Self {
+            renew_interval: Duration::from_secs(10),
+            max_lock_renewal_duration: Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+impl AutoLockRenewer {
+    setters! {
+        renew_interval: Duration => renew_interval,
+        max_lock_renewal_duration: Duration => max_lock_renewal_duration,
+    }
+
+    /// Starts renewing the lock of `message` until the returned [`LockRenewal`] is stopped or
+    /// dropped, the maximum renewal duration elapsed or a renewal failed.
+    ///
+    /// # Panics
+    ///
+    /// Panics when called outside of a Tokio runtime.
+    pub fn register(&self, message: &PeekLockResponse) -> LockRenewal {
+        let message = message.clone();
+        let renew_interval = self.renew_interval;
+        let now = Instant::now();
+        let deadline = now + self.max_lock_renewal_duration;
+        // a message received a while ago may have little of its lock left
+        let mut next_renewal = now + renew_interval;
+        if let Some(locked_until) = message.locked_until() {
+            let lock_left = (locked_until - Utc::now()).to_std().unwrap_or_default();
+            next_renewal = next_renewal.min(now + lock_left.saturating_sub(renew_interval));
+        }
+        let task = tokio::spawn(async move {
+            loop {
+                if next_renewal >= deadline {
+                    return Ok(());
+                }
+                tokio::time::sleep_until(next_renewal).await;
+                if let Err(error) = message.renew_lock().await {
+                    log::warn!(
+                        "cannot renew the lock of message {:?}: {}",
+                        message.message().broker_properties().message_id,
+                        error
+                    );
+                    return Err(error);
+                }
+                next_renewal = Instant::now() + renew_interval;
+            }
+        });
+        LockRenewal { task: Some(task) }
+    }
+}
+
+/// The renewal of the lock of a message, stopped once the message is settled.
+///
+/// Dropping the renewal stops it as well.
+#[derive(Debug)]
+pub struct LockRenewal {
+    task: Option<JoinHandle<Result<(), Error>>>,
+}
+
+impl LockRenewal {
+    /// Stops renewing the lock. Returns the error which stopped the renewal early, when the
+    /// lock could not be renewed.
+    pub async fn stop(mut self) -> Result<(), Error> {
+        let task = match self.task.take() {
+            Some(task) => task,
+            None => return Ok(()),
+        };
+        task.abort();
+        match task.await {
+            Ok(result) => result,
+            Err(error) if error.is_cancelled() => Ok(()),
+            Err(error) => std::panic::resume_unwind(error.into_panic()),
+        }
+    }
+}
+
+impl Drop for LockRenewal {
+    fn drop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+    }
+}
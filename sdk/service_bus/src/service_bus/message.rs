@@ -0,0 +1,463 @@
+use crate::amqp::message::Message;
+use crate::amqp::Value;
+use crate::Error;
+use bytes::Bytes;
+use chrono::{DateTime, TimeZone, Utc};
+use http::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::time::Duration;
+use uuid::Uuid;
+
+pub(crate) const BROKER_PROPERTIES: &str = "brokerproperties";
+
+/// The headers of a message which are not custom properties.
+const STANDARD_HEADERS: &[&str] = &[
+    BROKER_PROPERTIES,
+    "cache-control",
+    "connection",
+    "content-length",
+    "content-type",
+    "date",
+    "expires",
+    "location",
+    "pragma",
+    "server",
+    "strict-transport-security",
+    "transfer-encoding",
+    "x-content-type-options",
+];
+
+/// The properties of a message handled by the broker, sent and received in the
+/// `BrokerProperties` header as JSON.
+///
+/// The properties set by the broker, such as `SequenceNumber` or `LockToken`, are ignored when
+/// sending a message.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct BrokerProperties {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply_to: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply_to_session_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub partition_key: Option<String>,
+    /// The time to live of the message, after which it expires.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "time_to_live_format"
+    )]
+    pub time_to_live: Option<Duration>,
+    /// When the message becomes available to the receivers.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "rfc1123_time_format"
+    )]
+    pub scheduled_enqueue_time_utc: Option<DateTime<Utc>>,
+    #[serde(skip_serializing)]
+    pub sequence_number: Option<i64>,
+    #[serde(skip_serializing)]
+    pub enqueued_sequence_number: Option<i64>,
+    #[serde(default, skip_serializing, with = "rfc1123_time_format")]
+    pub enqueued_time_utc: Option<DateTime<Utc>>,
+    #[serde(skip_serializing)]
+    pub delivery_count: Option<u32>,
+    #[serde(skip_serializing)]
+    pub lock_token: Option<String>,
+    #[serde(default, skip_serializing, with = "rfc1123_time_format")]
+    pub locked_until_utc: Option<DateTime<Utc>>,
+    #[serde(skip_serializing)]
+    pub dead_letter_source: Option<String>,
+    #[serde(skip_serializing)]
+    pub state: Option<String>,
+}
+
+/// A Service Bus message: a binary body, the properties handled by the broker and custom
+/// properties, sent as HTTP headers.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ServiceBusMessage {
+    body: Bytes,
+    content_type: Option<String>,
+    broker_properties: BrokerProperties,
+    properties: HashMap<String, serde_json::Value>,
+}
+
+impl ServiceBusMessage {
+    pub fn new(body: impl Into<Bytes>) -> Self {
+        Self {
+            body: body.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
+    pub fn with_message_id(mut self, message_id: impl Into<String>) -> Self {
+        self.broker_properties.message_id = Some(message_id.into());
+        self
+    }
+
+    pub fn with_session_id(mut self, session_id: impl Into<String>) -> Self {
+        self.broker_properties.session_id = Some(session_id.into());
+        self
+    }
+
+    pub fn with_correlation_id(mut self, correlation_id: impl Into<String>) -> Self {
+        self.broker_properties.correlation_id = Some(correlation_id.into());
+        self
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.broker_properties.label = Some(label.into());
+        self
+    }
+
+    pub fn with_time_to_live(mut self, time_to_live: Duration) -> Self {
+        self.broker_properties.time_to_live = Some(time_to_live);
+        self
+    }
+
+    pub fn with_scheduled_enqueue_time(mut self, enqueue_time: DateTime<Utc>) -> Self {
+        self.broker_properties.scheduled_enqueue_time_utc = Some(enqueue_time);
+        self
+    }
+
+    pub fn with_broker_properties(mut self, broker_properties: BrokerProperties) -> Self {
+        self.broker_properties = broker_properties;
+        self
+    }
+
+    /// Sets the custom property `name`, whose value is a string, a number or a boolean.
+    pub fn with_property(
+        mut self,
+        name: impl Into<String>,
+        value: impl Into<serde_json::Value>,
+    ) -> Self {
+        self.properties.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    pub fn into_body(self) -> Bytes {
+        self.body
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
+    pub fn broker_properties(&self) -> &BrokerProperties {
+        &self.broker_properties
+    }
+
+    pub fn properties(&self) -> &HashMap<String, serde_json::Value> {
+        &self.properties
+    }
+
+    pub fn property(&self, name: &str) -> Option<&serde_json::Value> {
+        self.properties.get(name)
+    }
+
+    /// Adds the content type, the broker properties and the custom properties to `headers`.
+    pub(crate) fn add_headers(&self, headers: &mut HeaderMap) -> Result<(), Error> {
+        if let Some(content_type) = &self.content_type {
+            headers.insert(CONTENT_TYPE, header_value(content_type)?);
+        }
+        let broker_properties = serde_json::to_string(&self.broker_properties)?;
+        if broker_properties != "{}" {
+            headers.insert(
+                HeaderName::from_static(BROKER_PROPERTIES),
+                header_value(&broker_properties)?,
+            );
+        }
+        for (name, value) in &self.properties {
+            let name = HeaderName::try_from(name.as_str()).map_err(|_| {
+                Error::InvalidArgument(format!("invalid custom property name {:?}", name))
+            })?;
+            headers.insert(name, header_value(&value.to_string())?);
+        }
+        Ok(())
+    }
+
+    /// The message as an element of a batch.
+    pub(crate) fn to_batch_entry(&self) -> Result<serde_json::Value, Error> {
+        let body = std::str::from_utf8(&self.body).map_err(|_| {
+            Error::InvalidArgument("the body of a batched message must be UTF-8 text".to_owned())
+        })?;
+        Ok(serde_json::json!({
+            "Body": body,
+            "BrokerProperties": self.broker_properties,
+            "UserProperties": self.properties,
+        }))
+    }
+
+    /// Reads a received message from the headers and the body of the response.
+    pub(crate) fn from_response(headers: &HeaderMap, body: Bytes) -> Result<Self, Error> {
+        let broker_properties = match headers.get(BROKER_PROPERTIES) {
+            Some(value) => serde_json::from_slice(value.as_bytes())?,
+            None => BrokerProperties::default(),
+        };
+        let content_type = headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(ToOwned::to_owned);
+        let properties = headers
+            .iter()
+            .filter(|(name, _)| !STANDARD_HEADERS.contains(&name.as_str()))
+            .filter_map(|(name, value)| {
+                let value = value.to_str().ok()?;
+                let value = serde_json::from_str(value)
+                    .unwrap_or_else(|_| serde_json::Value::String(value.to_owned()));
+                Some((name.as_str().to_owned(), value))
+            })
+            .collect();
+        Ok(Self {
+            body,
+            content_type,
+            broker_properties,
+            properties,
+        })
+    }
+}
+
+impl ServiceBusMessage {
+    /// Reads a message received over AMQP, locked by `lock_token`.
+    pub(crate) fn from_amqp(message: &Message, lock_token: Option<Uuid>) -> Self {
+        let string = |key: &str| {
+            message
+                .annotation(key)
+                .and_then(Value::as_str)
+                .map(ToOwned::to_owned)
+        };
+        let time = |key: &str| {
+            message
+                .annotation(key)
+                .and_then(Value::as_timestamp)
+                .and_then(|millis| Utc.timestamp_millis_opt(millis).single())
+        };
+        let properties = &message.properties;
+        let broker_properties = BrokerProperties {
+            message_id: properties.message_id.as_ref().and_then(id_to_string),
+            session_id: properties.group_id.clone(),
+            correlation_id: properties.correlation_id.as_ref().and_then(id_to_string),
+            label: properties.subject.clone(),
+            to: properties.to.clone(),
+            reply_to: properties.reply_to.clone(),
+            reply_to_session_id: properties.reply_to_group_id.clone(),
+            partition_key: string("x-opt-partition-key"),
+            time_to_live: message
+                .header
+                .as_ref()
+                .and_then(|header| header.ttl)
+                .map(|ttl| Duration::from_millis(ttl.into())),
+            scheduled_enqueue_time_utc: time("x-opt-scheduled-enqueue-time"),
+            sequence_number: message
+                .annotation("x-opt-sequence-number")
+                .and_then(Value::as_i64),
+            enqueued_sequence_number: message
+                .annotation("x-opt-enqueue-sequence-number")
+                .and_then(Value::as_i64),
+            enqueued_time_utc: time("x-opt-enqueued-time"),
+            delivery_count: message.header.as_ref().map(|header| header.delivery_count),
+            lock_token: lock_token.map(|lock_token| lock_token.to_string()),
+            locked_until_utc: time("x-opt-locked-until"),
+            dead_letter_source: string("x-opt-deadletter-source"),
+            state: message
+                .annotation("x-opt-message-state")
+                .and_then(Value::as_i64)
+                .map(|state| match state {
+                    1 => "Deferred",
+                    2 => "Scheduled",
+                    _ => "Active",
+                })
+                .map(ToOwned::to_owned),
+        };
+        let properties = message
+            .application_properties
+            .iter()
+            .filter_map(|(name, value)| Some((name.as_str()?.to_owned(), to_json(value)?)))
+            .collect();
+        Self {
+            body: message.data(),
+            content_type: message.properties.content_type.clone(),
+            broker_properties,
+            properties,
+        }
+    }
+}
+
+/// Message and correlation ids are strings, UUIDs, unsigned integers or binary.
+fn id_to_string(id: &Value) -> Option<String> {
+    match id {
+        Value::String(id) | Value::Symbol(id) => Some(id.clone()),
+        Value::Uuid(id) => Some(id.to_string()),
+        Value::Ulong(id) => Some(id.to_string()),
+        Value::Binary(id) => Some(String::from_utf8_lossy(id).into_owned()),
+        _ => None,
+    }
+}
+
+/// Converts an application property to the JSON value of a custom property; properties of
+/// other types are ignored.
+fn to_json(value: &Value) -> Option<serde_json::Value> {
+    Some(match value {
+        Value::Null => serde_json::Value::Null,
+        Value::Bool(value) => (*value).into(),
+        Value::Ubyte(value) => (*value).into(),
+        Value::Ushort(value) => (*value).into(),
+        Value::Uint(value) => (*value).into(),
+        Value::Ulong(value) => (*value).into(),
+        Value::Byte(value) => (*value).into(),
+        Value::Short(value) => (*value).into(),
+        Value::Int(value) => (*value).into(),
+        Value::Long(value) | Value::Timestamp(value) => (*value).into(),
+        Value::Float(value) => (*value).into(),
+        Value::Double(value) => (*value).into(),
+        Value::Char(value) => value.to_string().into(),
+        Value::Uuid(value) => value.to_string().into(),
+        Value::String(value) | Value::Symbol(value) => value.clone().into(),
+        _ => return None,
+    })
+}
+
+fn header_value(value: &str) -> Result<HeaderValue, Error> {
+    HeaderValue::from_str(value)
+        .map_err(|_| Error::InvalidArgument(format!("invalid header value {:?}", value)))
+}
+
+/// Dates in the RFC 1123 format used by the broker properties, such as
+/// `Wed, 25 Sep 2013 17:36:25 GMT`.
+mod rfc1123_time_format {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S>(
+        date: &Option<DateTime<Utc>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match date {
+            Some(date) => {
+                serializer.serialize_str(&date.format("%a, %d %b %Y %H:%M:%S GMT").to_string())
+            }
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<String>::deserialize(deserializer)?
+            .map(|date| {
+                DateTime::parse_from_rfc2822(&date)
+                    .map(|date| date.with_timezone(&Utc))
+                    .map_err(serde::de::Error::custom)
+            })
+            .transpose()
+    }
+}
+
+/// Durations as a number of seconds.
+mod time_to_live_format {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub(crate) fn serialize<S>(
+        duration: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match duration {
+            Some(duration) => serializer.serialize_f64(duration.as_secs_f64()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Option::<f64>::deserialize(deserializer)?
+            .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+            .map(Duration::from_secs_f64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn broker_properties_format() {
+        let message = ServiceBusMessage::new("body")
+            .with_message_id("id")
+            .with_time_to_live(Duration::from_secs(90))
+            .with_scheduled_enqueue_time(Utc.timestamp_opt(1_600_000_000, 0).unwrap())
+            .with_property("count", 3)
+            .with_property("kind", "test");
+        let mut headers = HeaderMap::new();
+        message.add_headers(&mut headers).unwrap();
+        assert_eq!(
+            headers[BROKER_PROPERTIES],
+            r#"{"MessageId":"id","TimeToLive":90.0,"ScheduledEnqueueTimeUtc":"Sun, 13 Sep 2020 12:26:40 GMT"}"#
+        );
+        assert_eq!(headers["count"], "3");
+        assert_eq!(headers["kind"], "\"test\"");
+
+        headers.insert(
+            BROKER_PROPERTIES,
+            HeaderValue::from_static(
+                r#"{"DeliveryCount":1,"EnqueuedTimeUtc":"Sun, 13 Sep 2020 12:26:40 GMT","LockToken":"token","MessageId":"id","SequenceNumber":12,"TimeToLive":90}"#,
+            ),
+        );
+        headers.insert(
+            "date",
+            HeaderValue::from_static("Sun, 13 Sep 2020 12:26:41 GMT"),
+        );
+        headers.insert("raw", HeaderValue::from_static("not json"));
+        let received = ServiceBusMessage::from_response(&headers, Bytes::from("body")).unwrap();
+        let broker_properties = received.broker_properties();
+        assert_eq!(broker_properties.sequence_number, Some(12));
+        assert_eq!(broker_properties.lock_token.as_deref(), Some("token"));
+        assert_eq!(
+            broker_properties.time_to_live,
+            Some(Duration::from_secs(90))
+        );
+        assert_eq!(
+            broker_properties.enqueued_time_utc,
+            Some(Utc.timestamp_opt(1_600_000_000, 0).unwrap())
+        );
+        assert_eq!(received.property("count"), Some(&serde_json::json!(3)));
+        assert_eq!(received.property("kind"), Some(&serde_json::json!("test")));
+        assert_eq!(
+            received.property("raw"),
+            Some(&serde_json::json!("not json"))
+        );
+        assert_eq!(received.property("date"), None);
+    }
+}
@@ -0,0 +1,531 @@
+//! Service Bus clients communicating with the service over its REST API.
+//!
+//! A [`ServiceBusClient`] creates the [`ServiceBusSender`]s sending [`ServiceBusMessage`]s to
+//! the queues and topics of a namespace, and the [`ServiceBusReceiver`]s reading the messages of
+//! its queues, subscriptions and dead-letter queues. Received messages are either removed at
+//! once or locked, as a [`PeekLockResponse`], until they are settled.
+//!
+//! The operations the REST API does not offer go through an AMQP connection, opened on first
+//! use: the [`ServiceBusSessionReceiver`]s of session-enabled entities, the deferral of
+//! messages and their dead-lettering. An [`AutoLockRenewer`] keeps the locks of messages alive
+//! while they are processed.
+mod amqp_connection;
+mod client;
+mod lock_renewer;
+mod message;
+mod receiver;
+mod sender;
+mod session;
+
+pub(crate) use client::ServiceBusConnection;
+pub use client::{ServiceBusClient, ServiceBusClientOptions};
+pub use lock_renewer::{AutoLockRenewer, LockRenewal};
+pub use message::{BrokerProperties, ServiceBusMessage};
+pub use receiver::{PeekLockResponse, ServiceBusReceiver};
+pub use sender::ServiceBusSender;
+pub use session::ServiceBusSessionReceiver;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amqp::message::{Body, Message};
+    use crate::amqp::performatives::DeliveryState;
+    use crate::amqp::test_broker::{status_response, TestBroker};
+    use crate::amqp::Value;
+    use crate::Error;
+    use bytes::Bytes;
+    use chrono::{TimeZone, Utc};
+    use mockito::{mock, Matcher};
+    use serde_json::json;
+    use std::time::Duration;
+    use url::Url;
+    use uuid::Uuid;
+
+    fn client() -> ServiceBusClient {
+        ServiceBusClient::new(
+            azure_core::new_http_client(),
+            "namespace",
+            "policy",
+            "key",
+            ServiceBusClientOptions::default()
+                .endpoint(Url::parse(&mockito::server_url()).unwrap()),
+        )
+        .unwrap()
+    }
+
+    async fn amqp_client() -> (ServiceBusClient, TestBroker) {
+        let broker = TestBroker::start().await;
+        broker.respond("$cbs", |_| status_response(200, Value::Null));
+        let client = ServiceBusClient::new(
+            azure_core::new_http_client(),
+            "namespace",
+            "policy",
+            "key",
+            ServiceBusClientOptions::default()
+                .endpoint(Url::parse(&mockito::server_url()).unwrap())
+                .amqp_endpoint(broker.url.clone()),
+        )
+        .unwrap();
+        (client, broker)
+    }
+
+    /// The bodies of the requests of `operation` received by the management node of `entity`.
+    fn management_requests(broker: &TestBroker, entity: &str, operation: &str) -> Vec<Value> {
+        broker
+            .received()
+            .into_iter()
+            .filter(|(address, _, _)| *address == format!("{}/$management", entity))
+            .filter(|(_, _, request)| {
+                request
+                    .application_property("operation")
+                    .and_then(Value::as_str)
+                    == Some(operation)
+            })
+            .filter_map(|(_, _, request)| match request.body {
+                Body::Value(body) => Some(body),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn session_message(body: &str, sequence_number: i64, lock_token: Uuid) -> Message {
+        let mut message = Message::with_data(body.to_owned());
+        message.properties.group_id = Some("s1".to_owned());
+        message.message_annotations = vec![
+            (Value::symbol("x-opt-lock-token"), Value::Uuid(lock_token)),
+            (
+                Value::symbol("x-opt-sequence-number"),
+                Value::Long(sequence_number),
+            ),
+            (
+                Value::symbol("x-opt-locked-until"),
+                Value::Timestamp(1_600_000_060_000),
+            ),
+        ];
+        message.application_properties = vec![("attempt".into(), Value::Int(1))];
+        message
+    }
+
+    fn signature(path: &str) -> Matcher {
+        let resource: String = url::form_urlencoded::byte_serialize(
+            format!("{}{}", mockito::server_url(), path).as_bytes(),
+        )
+        .collect();
+        Matcher::Regex(format!(
+            "^SharedAccessSignature sr={}&sig=.+&se=[0-9]+&skn=policy$",
+            regex_escape(&resource)
+        ))
+    }
+
+    fn regex_escape(text: &str) -> String {
+        text.chars()
+            .flat_map(|c| match c {
+                '.' | '%' | '$' | '/' | '?' | '*' | '+' => vec!['\\', c],
+                c => vec![c],
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn sender_sends_messages_and_batches() {
+        let single = mock("POST", "/orders/messages")
+            .match_header("authorization", signature("/orders/messages"))
+            .match_header("content-type", "application/octet-stream")
+            .match_header(
+                "brokerproperties",
+                r#"{"MessageId":"m1","SessionId":"s1","CorrelationId":"c1","TimeToLive":60.0}"#,
+            )
+            .match_header("priority", "2")
+            .match_header("region", "\"emea\"")
+            .match_body(vec![0u8, 159, 146, 150])
+            .with_status(201)
+            .create();
+        let scheduled = mock("POST", "/orders/messages")
+            .match_header(
+                "brokerproperties",
+                r#"{"ScheduledEnqueueTimeUtc":"Sun, 13 Sep 2020 12:26:40 GMT"}"#,
+            )
+            .with_status(201)
+            .create();
+        let batch = mock("POST", "/notifications/messages")
+            .match_header("content-type", "application/vnd.microsoft.servicebus.json")
+            .match_body(Matcher::Json(json!([
+                {"Body": "first", "BrokerProperties": {"Label": "a"}, "UserProperties": {}},
+                {"Body": "second", "BrokerProperties": {}, "UserProperties": {"n": 1}},
+            ])))
+            .with_status(201)
+            .create();
+
+        let client = client();
+        let sender = client.queue_sender("orders");
+        sender
+            .send_message(
+                &ServiceBusMessage::new(vec![0u8, 159, 146, 150])
+                    .with_content_type("application/octet-stream")
+                    .with_message_id("m1")
+                    .with_session_id("s1")
+                    .with_correlation_id("c1")
+                    .with_time_to_live(Duration::from_secs(60))
+                    .with_property("priority", 2)
+                    .with_property("region", "emea"),
+            )
+            .await
+            .unwrap();
+        sender
+            .schedule_message(
+                &ServiceBusMessage::new("later"),
+                Utc.timestamp_opt(1_600_000_000, 0).unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let topic = client.topic_sender("notifications");
+        topic
+            .send_messages(&[
+                ServiceBusMessage::new("first").with_label("a"),
+                ServiceBusMessage::new("second").with_property("n", 1),
+            ])
+            .await
+            .unwrap();
+        let error = topic
+            .send_messages(&[ServiceBusMessage::new(vec![0xffu8])])
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::InvalidArgument(_)));
+
+        single.assert();
+        scheduled.assert();
+        batch.assert();
+    }
+
+    #[tokio::test]
+    async fn receiver_locks_and_settles_messages() {
+        let lock_path = "/jobs/messages/m1/3c5a2f1e";
+        let locked = mock("POST", "/jobs/messages/head")
+            .match_query(Matcher::UrlEncoded("timeout".into(), "5".into()))
+            .match_header("authorization", signature("/jobs/messages/head"))
+            .with_status(201)
+            .with_header(
+                "brokerproperties",
+                r#"{"DeliveryCount":1,"LockToken":"3c5a2f1e","LockedUntilUtc":"Sun, 13 Sep 2020 12:27:40 GMT","MessageId":"m1","SequenceNumber":7,"State":"Active","TimeToLive":922337203685.47754}"#,
+            )
+            .with_header("location", &format!("{}{}", mockito::server_url(), lock_path))
+            .with_header("content-type", "text/plain")
+            .with_header("attempt", "3")
+            .with_body("job")
+            .create();
+        let renewed = mock("POST", lock_path)
+            .match_header("authorization", signature(lock_path))
+            .with_status(200)
+            .create();
+        let abandoned = mock("PUT", lock_path).with_status(200).create();
+        let completed = mock("DELETE", lock_path).with_status(200).create();
+
+        let receiver = client().queue_receiver("jobs");
+        let response = receiver
+            .peek_lock(Some(Duration::from_secs(5)))
+            .await
+            .unwrap()
+            .unwrap();
+        let message = response.message();
+        assert_eq!(message.body(), "job");
+        assert_eq!(message.content_type(), Some("text/plain"));
+        assert_eq!(message.property("attempt"), Some(&json!(3)));
+        assert_eq!(message.broker_properties().sequence_number, Some(7));
+        assert_eq!(
+            response.locked_until(),
+            Some(Utc.timestamp_opt(1_600_000_060, 0).unwrap())
+        );
+        response.renew_lock().await.unwrap();
+        response.abandon().await.unwrap();
+        response.complete().await.unwrap();
+
+        locked.assert();
+        renewed.assert();
+        abandoned.assert();
+        completed.assert();
+    }
+
+    #[tokio::test]
+    async fn receiver_reads_subscriptions_and_dead_letter_queues() {
+        let received = mock("DELETE", "/events/subscriptions/audit/messages/head")
+            .with_status(200)
+            .with_header("brokerproperties", r#"{"SequenceNumber":3}"#)
+            .with_body("event")
+            .create();
+        let empty = mock(
+            "DELETE",
+            "/events/subscriptions/audit/$DeadLetterQueue/messages/head",
+        )
+        .with_status(204)
+        .create();
+
+        let client = client();
+        let message = client
+            .subscription_receiver("events", "audit")
+            .receive_and_delete(None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(message.body(), "event");
+        assert_eq!(message.broker_properties().sequence_number, Some(3));
+
+        let receiver = client.dead_letter_subscription_receiver("events", "audit");
+        assert_eq!(
+            receiver.entity_path(),
+            "events/subscriptions/audit/$DeadLetterQueue"
+        );
+        assert!(receiver.receive_and_delete(None).await.unwrap().is_none());
+
+        received.assert();
+        empty.assert();
+    }
+
+    #[tokio::test]
+    async fn session_receiver_settles_messages_and_manages_state() {
+        let (client, broker) = amqp_client().await;
+        let (first_lock, second_lock, deferred_lock) =
+            (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        broker.respond("orders/$management", move |request| {
+            let operation = request
+                .application_property("operation")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let body = match operation {
+                "com.microsoft:get-session-state" => Value::Map(vec![(
+                    "session-state".into(),
+                    Bytes::from_static(b"step 2").into(),
+                )]),
+                "com.microsoft:renew-session-lock" => Value::Map(vec![(
+                    "expiration".into(),
+                    Value::Timestamp(1_600_000_120_000),
+                )]),
+                "com.microsoft:receive-by-sequence-number" => {
+                    let deferred = session_message("deferred", 7, deferred_lock);
+                    Value::Map(vec![(
+                        "messages".into(),
+                        Value::List(vec![Value::Map(vec![
+                            ("message".into(), deferred.to_bytes().into()),
+                            ("lock-token".into(), deferred_lock.into()),
+                        ])]),
+                    )])
+                }
+                _ => Value::Null,
+            };
+            status_response(200, body)
+        });
+        broker.enqueue("orders", session_message("first", 7, first_lock));
+        broker.enqueue("orders", session_message("second", 8, second_lock));
+
+        let session = client
+            .queue_receiver("orders")
+            .accept_next_session()
+            .await
+            .unwrap();
+        assert_eq!(session.session_id(), "s1");
+        let attach = broker
+            .attaches()
+            .into_iter()
+            .find(|attach| attach.name.starts_with("orders-session-receiver"))
+            .unwrap();
+        assert_eq!(
+            attach.source.unwrap().filter,
+            vec![(Value::symbol("com.microsoft:session-filter"), Value::Null)]
+        );
+
+        let first = session
+            .receive_message(Duration::from_secs(5))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(first.message().body(), "first");
+        assert_eq!(first.session_id(), Some("s1"));
+        let broker_properties = first.message().broker_properties();
+        assert_eq!(broker_properties.sequence_number, Some(7));
+        assert_eq!(broker_properties.session_id.as_deref(), Some("s1"));
+        assert_eq!(broker_properties.lock_token, Some(first_lock.to_string()));
+        assert_eq!(
+            first.locked_until(),
+            Some(Utc.timestamp_opt(1_600_000_060, 0).unwrap())
+        );
+        assert_eq!(first.message().property("attempt"), Some(&json!(1)));
+        first.defer().await.unwrap();
+
+        let second = session
+            .receive_message(Duration::from_secs(5))
+            .await
+            .unwrap()
+            .unwrap();
+        second
+            .dead_letter(Some("invalid"), Some("cannot parse the order"))
+            .await
+            .unwrap();
+        assert!(session
+            .receive_message(Duration::from_millis(100))
+            .await
+            .unwrap()
+            .is_none());
+
+        assert_eq!(
+            session.get_session_state().await.unwrap(),
+            Some(Bytes::from_static(b"step 2"))
+        );
+        session
+            .set_session_state(Some(Bytes::from_static(b"step 3")))
+            .await
+            .unwrap();
+        let locked_until = Utc.timestamp_opt(1_600_000_120, 0).unwrap();
+        assert_eq!(session.renew_session_lock().await.unwrap(), locked_until);
+        assert_eq!(session.locked_until(), Some(locked_until));
+
+        let deferred = session.receive_deferred_messages(&[7]).await.unwrap();
+        assert_eq!(deferred.len(), 1);
+        assert_eq!(deferred[0].message().body(), "deferred");
+        assert!(deferred[0].lock_location().is_none());
+        deferred[0].complete().await.unwrap();
+
+        let dispositions = broker.state.lock().unwrap().dispositions.clone();
+        assert_eq!(dispositions.len(), 2);
+        assert_eq!(
+            dispositions[0].state,
+            Some(DeliveryState::Modified {
+                delivery_failed: false,
+                undeliverable_here: true,
+                message_annotations: Vec::new(),
+            })
+        );
+        match &dispositions[1].state {
+            Some(DeliveryState::Rejected(Some(error))) => {
+                assert_eq!(error.condition, "com.microsoft:dead-letter");
+                assert_eq!(
+                    error.info,
+                    vec![
+                        ("DeadLetterReason".into(), "invalid".into()),
+                        (
+                            "DeadLetterErrorDescription".into(),
+                            "cannot parse the order".into()
+                        ),
+                    ]
+                );
+            }
+            state => panic!("unexpected disposition {:?}", state),
+        }
+
+        let set_state = management_requests(&broker, "orders", "com.microsoft:set-session-state");
+        assert_eq!(
+            set_state,
+            vec![Value::Map(vec![
+                ("session-state".into(), Bytes::from_static(b"step 3").into()),
+                ("session-id".into(), "s1".into()),
+            ])]
+        );
+        let received = management_requests(
+            &broker,
+            "orders",
+            "com.microsoft:receive-by-sequence-number",
+        );
+        assert_eq!(
+            received[0].get("sequence-numbers"),
+            Some(&Value::Array(vec![Value::Long(7)]))
+        );
+        let completed = management_requests(&broker, "orders", "com.microsoft:update-disposition");
+        assert_eq!(
+            completed,
+            vec![Value::Map(vec![
+                ("disposition-status".into(), "completed".into()),
+                (
+                    "lock-tokens".into(),
+                    Value::Array(vec![deferred_lock.into()])
+                ),
+                ("session-id".into(), "s1".into()),
+            ])]
+        );
+        session.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn lock_renewer_keeps_locks_and_messages_are_dead_lettered() {
+        let (client, broker) = amqp_client().await;
+        broker.respond("tasks/$management", |_| status_response(200, Value::Null));
+        let lock_token = Uuid::new_v4();
+        let lock_path = format!("/tasks/messages/m1/{}", lock_token);
+        let locked = mock("POST", "/tasks/messages/head")
+            .with_status(201)
+            .with_header(
+                "brokerproperties",
+                &format!(r#"{{"LockToken":"{}","MessageId":"m1"}}"#, lock_token),
+            )
+            .with_body("task")
+            .create();
+        let renewed = mock("POST", lock_path.as_str())
+            .with_status(200)
+            .expect_at_least(2)
+            .create();
+
+        let response = client
+            .queue_receiver("tasks")
+            .peek_lock(None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            response.lock_location().map(Url::path),
+            Some(lock_path.as_str())
+        );
+        let renewal = AutoLockRenewer::default()
+            .renew_interval(Duration::from_millis(50))
+            .register(&response);
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        renewal.stop().await.unwrap();
+
+        response.dead_letter(Some("expired"), None).await.unwrap();
+        let requests = management_requests(&broker, "tasks", "com.microsoft:update-disposition");
+        assert_eq!(
+            requests,
+            vec![Value::Map(vec![
+                ("deadletter-reason".into(), "expired".into()),
+                ("disposition-status".into(), "suspended".into()),
+                ("lock-tokens".into(), Value::Array(vec![lock_token.into()])),
+            ])]
+        );
+
+        locked.assert();
+        renewed.assert();
+    }
+
+    #[tokio::test]
+    async fn lock_renewer_renews_expiring_locks_first() {
+        let (client, _broker) = amqp_client().await;
+        let lock_token = Uuid::new_v4();
+        let lock_path = format!("/tasks/messages/m2/{}", lock_token);
+        let _locked = mock("POST", "/tasks/messages/head")
+            .with_status(201)
+            .with_header(
+                "brokerproperties",
+                &format!(
+                    r#"{{"LockToken":"{}","LockedUntilUtc":"Sun, 13 Sep 2020 12:27:40 GMT","MessageId":"m2"}}"#,
+                    lock_token
+                ),
+            )
+            .with_body("task")
+            .create();
+        let renewed = mock("POST", lock_path.as_str())
+            .with_status(200)
+            .expect(1)
+            .create();
+
+        let response = client
+            .queue_receiver("tasks")
+            .peek_lock(None)
+            .await
+            .unwrap()
+            .unwrap();
+        let renewal = AutoLockRenewer::default()
+            .renew_interval(Duration::from_secs(10))
+            .register(&response);
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        renewal.stop().await.unwrap();
+
+        renewed.assert();
+    }
+}
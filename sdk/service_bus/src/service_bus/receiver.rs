@@ -0,0 +1,456 @@
+use super::client::ServiceBusConnection;
+use super::message::ServiceBusMessage;
+use super::session::ServiceBusSessionReceiver;
+use crate::amqp::connection::DeliverySettler;
+use crate::amqp::message::Message;
+use crate::amqp::performatives::{AmqpError, DeliveryState};
+use crate::amqp::Value;
+use crate::Error;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use http::header::{CONTENT_LENGTH, LOCATION};
+use http::{Method, StatusCode};
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+use uuid::Uuid;
+
+const UPDATE_DISPOSITION_OPERATION: &str = "com.microsoft:update-disposition";
+const RENEW_LOCK_OPERATION: &str = "com.microsoft:renew-lock";
+const RECEIVE_BY_SEQUENCE_NUMBER_OPERATION: &str = "com.microsoft:receive-by-sequence-number";
+const DEAD_LETTER_CONDITION: &str = "com.microsoft:dead-letter";
+
+/// Receives the messages of a queue, of a subscription or of their dead-letter queue.
+#[derive(Debug, Clone)]
+pub struct ServiceBusReceiver {
+    connection: Arc<ServiceBusConnection>,
+    entity_path: String,
+}
+
+impl ServiceBusReceiver {
+    pub(crate) fn new(connection: Arc<ServiceBusConnection>, entity_path: String) -> Self {
+        Self {
+            connection,
+            entity_path,
+        }
+    }
+
+    /// The path of the entity the messages are received from, relative to the namespace.
+    pub fn entity_path(&self) -> &str {
+        &self.entity_path
+    }
+
+    fn head_url(&self, timeout: Option<Duration>) -> Result<Url, Error> {
+        let mut url = self
+            .connection
+            .url(&format!("{}/messages/head", self.entity_path))?;
+        if let Some(timeout) = timeout {
+            url.query_pairs_mut()
+                .append_pair("timeout", &timeout.as_secs().to_string());
+        }
+        Ok(url)
+    }
+
+    /// Receives the next message and removes it from the entity, waiting at most `timeout` for
+    /// one. `None` means that no message is available.
+    pub async fn receive_and_delete(
+        &self,
+        timeout: Option<Duration>,
+    ) -> Result<Option<ServiceBusMessage>, Error> {
+        let url = self.head_url(timeout)?;
+        let request = self
+            .connection
+            .request(Method::DELETE, &url)
+            .body(Bytes::new())?;
+        let response = self
+            .connection
+            .execute(request, &[StatusCode::OK, StatusCode::NO_CONTENT])
+            .await?;
+        if response.status() == StatusCode::NO_CONTENT {
+            return Ok(None);
+        }
+        let (parts, body) = response.into_parts();
+        Ok(Some(ServiceBusMessage::from_response(
+            &parts.headers,
+            body,
+        )?))
+    }
+
+    /// Receives the next message and locks it, waiting at most `timeout` for one. The message
+    /// stays in the entity, invisible to the other receivers, until it is completed or abandoned
+    /// or its lock expires. `None` means that no message is available.
+    pub async fn peek_lock(
+        &self,
+        timeout: Option<Duration>,
+    ) -> Result<Option<PeekLockResponse>, Error> {
+        let url = self.head_url(timeout)?;
+        let request = self
+            .connection
+            .request(Method::POST, &url)
+            .header(CONTENT_LENGTH, 0)
+            .body(Bytes::new())?;
+        let response = self
+            .connection
+            .execute(request, &[StatusCode::CREATED, StatusCode::NO_CONTENT])
+            .await?;
+        if response.status() == StatusCode::NO_CONTENT {
+            return Ok(None);
+        }
+        let (parts, body) = response.into_parts();
+        let message = ServiceBusMessage::from_response(&parts.headers, body)?;
+        let lock_location = match parts
+            .headers
+            .get(LOCATION)
+            .and_then(|location| location.to_str().ok())
+        {
+            Some(location) => Url::parse(location)?,
+            None => {
+                let broker_properties = message.broker_properties();
+                match (&broker_properties.message_id, &broker_properties.lock_token) {
+                    (Some(message_id), Some(lock_token)) => self.connection.url(&format!(
+                        "{}/messages/{}/{}",
+                        self.entity_path, message_id, lock_token
+                    ))?,
+                    _ => {
+                        return Err(Error::Protocol(
+                            "the locked message has no location".to_owned(),
+                        ))
+                    }
+                }
+            }
+        };
+        Ok(Some(PeekLockResponse {
+            connection: self.connection.clone(),
+            entity_path: self.entity_path.clone(),
+            session_id: None,
+            message,
+            lock: Lock::Location(lock_location),
+        }))
+    }
+
+    /// Receives the messages deferred with [`PeekLockResponse::defer`], which can only be
+    /// received by sequence number. The messages are locked, like those of `peek_lock`.
+    pub async fn receive_deferred_messages(
+        &self,
+        sequence_numbers: &[i64],
+    ) -> Result<Vec<PeekLockResponse>, Error> {
+        receive_deferred_messages(&self.connection, &self.entity_path, None, sequence_numbers).await
+    }
+
+    /// Accepts the session `session_id` of the entity, whose messages only the returned
+    /// receiver receives until it is closed or its lock expires.
+    pub async fn accept_session(
+        &self,
+        session_id: impl Into<String>,
+    ) -> Result<ServiceBusSessionReceiver, Error> {
+        ServiceBusSessionReceiver::accept(
+            self.connection.clone(),
+            self.entity_path.clone(),
+            Some(session_id.into()),
+        )
+        .await
+    }
+
+    /// Accepts the next session of the entity which has messages and is not locked by another
+    /// receiver.
+    pub async fn accept_next_session(&self) -> Result<ServiceBusSessionReceiver, Error> {
+        ServiceBusSessionReceiver::accept(self.connection.clone(), self.entity_path.clone(), None)
+            .await
+    }
+}
+
+/// How the lock of a message is released.
+#[derive(Debug, Clone)]
+enum Lock {
+    /// A message locked by the REST API, settled through the location of its lock.
+    Location(Url),
+    /// A message delivered on an AMQP link, settled on the link.
+    Delivery {
+        settler: DeliverySettler,
+        delivery_id: u32,
+    },
+    /// A deferred message, settled through the management node of the entity.
+    Management,
+}
+
+/// A locked message, received by [`ServiceBusReceiver::peek_lock`], by a session receiver or
+/// by sequence number once deferred.
+///
+/// The message is settled once processed, by completing, abandoning, deferring or
+/// dead-lettering it.
+#[derive(Debug, Clone)]
+pub struct PeekLockResponse {
+    connection: Arc<ServiceBusConnection>,
+    entity_path: String,
+    session_id: Option<String>,
+    message: ServiceBusMessage,
+    lock: Lock,
+}
+
+impl PeekLockResponse {
+    pub(crate) fn delivered(
+        connection: Arc<ServiceBusConnection>,
+        entity_path: String,
+        session_id: String,
+        message: ServiceBusMessage,
+        settler: DeliverySettler,
+        delivery_id: u32,
+    ) -> Self {
+        Self {
+            connection,
+            entity_path,
+            session_id: Some(session_id),
+            message,
+            lock: Lock::Delivery {
+                settler,
+                delivery_id,
+            },
+        }
+    }
+
+    pub fn message(&self) -> &ServiceBusMessage {
+        &self.message
+    }
+
+    pub fn into_message(self) -> ServiceBusMessage {
+        self.message
+    }
+
+    /// The location of the lock of a message locked by the REST API.
+    pub fn lock_location(&self) -> Option<&Url> {
+        match &self.lock {
+            Lock::Location(lock_location) => Some(lock_location),
+            _ => None,
+        }
+    }
+
+    /// The session of the message, when received by a session receiver.
+    pub fn session_id(&self) -> Option<&str> {
+        self.session_id.as_deref()
+    }
+
+    /// When the lock expires, unless renewed.
+    pub fn locked_until(&self) -> Option<DateTime<Utc>> {
+        self.message.broker_properties().locked_until_utc
+    }
+
+    fn lock_token(&self) -> Result<Uuid, Error> {
+        self.message
+            .broker_properties()
+            .lock_token
+            .as_deref()
+            .and_then(|lock_token| Uuid::parse_str(lock_token).ok())
+            .ok_or_else(|| Error::Protocol("the message has no lock token".to_owned()))
+    }
+
+    async fn lock_request(&self, method: Method, lock_location: &Url) -> Result<(), Error> {
+        let request = self
+            .connection
+            .request(method, lock_location)
+            .header(CONTENT_LENGTH, 0)
+            .body(Bytes::new())?;
+        self.connection.execute(request, &[StatusCode::OK]).await?;
+        Ok(())
+    }
+
+    /// Settles the message through the management node of the entity.
+    async fn update_disposition(
+        &self,
+        status: &str,
+        mut body: Vec<(Value, Value)>,
+    ) -> Result<(), Error> {
+        body.push(("disposition-status".into(), status.into()));
+        body.push((
+            "lock-tokens".into(),
+            Value::Array(vec![self.lock_token()?.into()]),
+        ));
+        if let Some(session_id) = &self.session_id {
+            body.push(("session-id".into(), session_id.as_str().into()));
+        }
+        self.connection
+            .amqp()
+            .management_request(&self.entity_path, UPDATE_DISPOSITION_OPERATION, body)
+            .await?;
+        Ok(())
+    }
+
+    /// Removes the message from the entity, once processed.
+    pub async fn complete(&self) -> Result<(), Error> {
+        match &self.lock {
+            Lock::Location(lock_location) => self.lock_request(Method::DELETE, lock_location).await,
+            Lock::Delivery {
+                settler,
+                delivery_id,
+            } => settler.settle(*delivery_id, DeliveryState::Accepted).await,
+            Lock::Management => self.update_disposition("completed", Vec::new()).await,
+        }
+    }
+
+    /// Releases the lock, making the message available to the receivers again.
+    pub async fn abandon(&self) -> Result<(), Error> {
+        match &self.lock {
+            Lock::Location(lock_location) => self.lock_request(Method::PUT, lock_location).await,
+            Lock::Delivery {
+                settler,
+                delivery_id,
+            } => {
+                let state = DeliveryState::Modified {
+                    delivery_failed: false,
+                    undeliverable_here: false,
+                    message_annotations: Vec::new(),
+                };
+                settler.settle(*delivery_id, state).await
+            }
+            Lock::Management => self.update_disposition("abandoned", Vec::new()).await,
+        }
+    }
+
+    /// Extends the lock by the lock duration of the entity.
+    ///
+    /// The messages of a session are locked with it: renew the lock of the session instead.
+    pub async fn renew_lock(&self) -> Result<(), Error> {
+        match &self.lock {
+            Lock::Location(lock_location) => self.lock_request(Method::POST, lock_location).await,
+            Lock::Delivery { .. } | Lock::Management => {
+                let body = vec![(
+                    "lock-tokens".into(),
+                    Value::Array(vec![self.lock_token()?.into()]),
+                )];
+                self.connection
+                    .amqp()
+                    .management_request(&self.entity_path, RENEW_LOCK_OPERATION, body)
+                    .await?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Sets the message aside, to be received later by its sequence number with
+    /// `receive_deferred_messages`.
+    pub async fn defer(&self) -> Result<(), Error> {
+        match &self.lock {
+            Lock::Delivery {
+                settler,
+                delivery_id,
+            } => {
+                let state = DeliveryState::Modified {
+                    delivery_failed: false,
+                    undeliverable_here: true,
+                    message_annotations: Vec::new(),
+                };
+                settler.settle(*delivery_id, state).await
+            }
+            Lock::Location(_) | Lock::Management => {
+                self.update_disposition("defered", Vec::new()).await
+            }
+        }
+    }
+
+    /// Moves the message to the dead-letter queue of the entity, recording why it could not be
+    /// processed in its `DeadLetterReason` and `DeadLetterErrorDescription` properties.
+    pub async fn dead_letter(
+        &self,
+        reason: Option<&str>,
+        description: Option<&str>,
+    ) -> Result<(), Error> {
+        match &self.lock {
+            Lock::Delivery {
+                settler,
+                delivery_id,
+            } => {
+                let mut info = Vec::new();
+                if let Some(reason) = reason {
+                    info.push(("DeadLetterReason".into(), reason.into()));
+                }
+                if let Some(description) = description {
+                    info.push(("DeadLetterErrorDescription".into(), description.into()));
+                }
+                let error = AmqpError {
+                    condition: DEAD_LETTER_CONDITION.to_owned(),
+                    description: None,
+                    info,
+                };
+                settler
+                    .settle(*delivery_id, DeliveryState::Rejected(Some(error)))
+                    .await
+            }
+            Lock::Location(_) | Lock::Management => {
+                let mut body = Vec::new();
+                if let Some(reason) = reason {
+                    body.push(("deadletter-reason".into(), reason.into()));
+                }
+                if let Some(description) = description {
+                    body.push(("deadletter-description".into(), description.into()));
+                }
+                self.update_disposition("suspended", body).await
+            }
+        }
+    }
+}
+
+/// Reads the lock token of a delivery from its tag, a GUID in the byte order of .NET.
+pub(crate) fn lock_token_from_delivery_tag(tag: &[u8]) -> Option<Uuid> {
+    if tag.len() != 16 {
+        return None;
+    }
+    Uuid::from_fields(
+        u32::from_le_bytes([tag[0], tag[1], tag[2], tag[3]]),
+        u16::from_le_bytes([tag[4], tag[5]]),
+        u16::from_le_bytes([tag[6], tag[7]]),
+        &tag[8..],
+    )
+    .ok()
+}
+
+/// Receives the deferred messages of `entity_path`, or of its session `session_id`, through
+/// the management node of the entity.
+pub(crate) async fn receive_deferred_messages(
+    connection: &Arc<ServiceBusConnection>,
+    entity_path: &str,
+    session_id: Option<&str>,
+    sequence_numbers: &[i64],
+) -> Result<Vec<PeekLockResponse>, Error> {
+    if sequence_numbers.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut body = vec![
+        (
+            "sequence-numbers".into(),
+            Value::Array(sequence_numbers.iter().copied().map(Value::Long).collect()),
+        ),
+        // Peek-lock: the messages stay locked until settled.
+        ("receiver-settle-mode".into(), Value::Uint(1)),
+    ];
+    if let Some(session_id) = session_id {
+        body.push(("session-id".into(), session_id.into()));
+    }
+    let response = connection
+        .amqp()
+        .management_request(entity_path, RECEIVE_BY_SEQUENCE_NUMBER_OPERATION, body)
+        .await?;
+    let entries = match response.get("messages") {
+        Some(Value::List(entries)) | Some(Value::Array(entries)) => entries.clone(),
+        _ => Vec::new(),
+    };
+    entries
+        .iter()
+        .map(|entry| {
+            let message = entry
+                .get("message")
+                .and_then(Value::as_binary)
+                .ok_or_else(|| Error::Decode("the deferred message has no body".to_owned()))?;
+            let lock_token = match entry.get("lock-token") {
+                Some(Value::Uuid(lock_token)) => Some(*lock_token),
+                _ => None,
+            };
+            let message = Message::decode(message)?;
+            Ok(PeekLockResponse {
+                connection: connection.clone(),
+                entity_path: entity_path.to_owned(),
+                session_id: session_id.map(ToOwned::to_owned),
+                message: ServiceBusMessage::from_amqp(&message, lock_token),
+                lock: Lock::Management,
+            })
+        })
+        .collect()
+}
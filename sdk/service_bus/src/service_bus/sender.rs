@@ -0,0 +1,77 @@
+use super::client::ServiceBusConnection;
+use super::message::ServiceBusMessage;
+use crate::Error;
+use chrono::{DateTime, Utc};
+use http::header::CONTENT_TYPE;
+use http::{Method, StatusCode};
+use std::sync::Arc;
+
+const BATCH_CONTENT_TYPE: &str = "application/vnd.microsoft.servicebus.json";
+
+/// Sends messages to a queue or publishes them to a topic.
+#[derive(Debug, Clone)]
+pub struct ServiceBusSender {
+    connection: Arc<ServiceBusConnection>,
+    entity_path: String,
+}
+
+impl ServiceBusSender {
+    pub(crate) fn new(connection: Arc<ServiceBusConnection>, entity_path: String) -> Self {
+        Self {
+            connection,
+            entity_path,
+        }
+    }
+
+    /// The queue or topic the messages are sent to.
+    pub fn entity_path(&self) -> &str {
+        &self.entity_path
+    }
+
+    pub async fn send_message(&self, message: &ServiceBusMessage) -> Result<(), Error> {
+        let url = self
+            .connection
+            .url(&format!("{}/messages", self.entity_path))?;
+        let mut request = self
+            .connection
+            .request(Method::POST, &url)
+            .body(message.body().clone())?;
+        message.add_headers(request.headers_mut())?;
+        self.connection
+            .execute(request, &[StatusCode::CREATED])
+            .await?;
+        Ok(())
+    }
+
+    /// Sends `messages` in a single request.
+    ///
+    /// The messages of a batch are sent as JSON, so their bodies must be UTF-8 text.
+    pub async fn send_messages(&self, messages: &[ServiceBusMessage]) -> Result<(), Error> {
+        let batch = messages
+            .iter()
+            .map(ServiceBusMessage::to_batch_entry)
+            .collect::<Result<Vec<_>, _>>()?;
+        let url = self
+            .connection
+            .url(&format!("{}/messages", self.entity_path))?;
+        let request = self
+            .connection
+            .request(Method::POST, &url)
+            .header(CONTENT_TYPE, BATCH_CONTENT_TYPE)
+            .body(serde_json::to_vec(&batch)?.into())?;
+        self.connection
+            .execute(request, &[StatusCode::CREATED])
+            .await?;
+        Ok(())
+    }
+
+    /// Sends `message`, to be enqueued at `enqueue_time` only.
+    pub async fn schedule_message(
+        &self,
+        message: &ServiceBusMessage,
+        enqueue_time: DateTime<Utc>,
+    ) -> Result<(), Error> {
+        let message = message.clone().with_scheduled_enqueue_time(enqueue_time);
+        self.send_message(&message).await
+    }
+}
@@ -0,0 +1,212 @@
+use super::client::ServiceBusConnection;
+use super::message::ServiceBusMessage;
+use super::receiver::{lock_token_from_delivery_tag, receive_deferred_messages, PeekLockResponse};
+use crate::amqp::connection::{DeliverySettler, ReceiverLink};
+use crate::amqp::performatives::{Attach, Role, SenderSettleMode, Terminus};
+use crate::amqp::Value;
+use crate::Error;
+use bytes::Bytes;
+use chrono::{DateTime, TimeZone, Utc};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uuid::Uuid;
+
+const SESSION_FILTER: &str = "com.microsoft:session-filter";
+const LOCKED_UNTIL_PROPERTY: &str = "com.microsoft:locked-until-utc";
+const GET_SESSION_STATE_OPERATION: &str = "com.microsoft:get-session-state";
+const SET_SESSION_STATE_OPERATION: &str = "com.microsoft:set-session-state";
+const RENEW_SESSION_LOCK_OPERATION: &str = "com.microsoft:renew-session-lock";
+
+/// The messages the service delivers ahead of `receive_message`. Each of them is locked as soon
+/// as delivered, so a single message is prefetched.
+const PREFETCH_COUNT: u32 = 1;
+
+/// Receives the messages of a session of a session-enabled queue or subscription, in order.
+///
+/// The session is locked by the receiver: no other receiver gets its messages until the
+/// receiver is closed or the lock expires, so it must be renewed while the messages are
+/// processed. The receiver also reads and writes the state of the session, which keeps track
+/// of its processing across receivers.
+#[derive(Debug)]
+pub struct ServiceBusSessionReceiver {
+    connection: Arc<ServiceBusConnection>,
+    entity_path: String,
+    session_id: String,
+    locked_until: Mutex<Option<DateTime<Utc>>>,
+    link: tokio::sync::Mutex<ReceiverLink>,
+    settler: DeliverySettler,
+}
+
+impl ServiceBusSessionReceiver {
+    /// Accepts `session_id`, or the next available session when `None`.
+    pub(crate) async fn accept(
+        connection: Arc<ServiceBusConnection>,
+        entity_path: String,
+        session_id: Option<String>,
+    ) -> Result<Self, Error> {
+        let mut attach = Attach::new(
+            format!("{}-session-receiver-{}", entity_path, Uuid::new_v4()),
+            0,
+            Role::Receiver,
+        );
+        attach.snd_settle_mode = Some(SenderSettleMode::Unsettled);
+        attach.source = Some(Terminus {
+            address: Some(entity_path.clone()),
+            filter: vec![(
+                Value::symbol(SESSION_FILTER),
+                session_id.map(Value::String).unwrap_or(Value::Null),
+            )],
+        });
+        attach.target = Some(Terminus::new(Uuid::new_v4().to_string()));
+        let link = connection
+            .amqp()
+            .attach_receiver(&entity_path, attach, PREFETCH_COUNT)
+            .await?;
+
+        // The service answers with the session it locked, and until when.
+        let remote = link.remote_attach();
+        let session_id = remote
+            .source
+            .iter()
+            .flat_map(|source| source.filter.iter())
+            .find(|(key, _)| key.as_str() == Some(SESSION_FILTER))
+            .and_then(|(_, session_id)| session_id.as_str())
+            .map(ToOwned::to_owned);
+        let session_id = match session_id {
+            Some(session_id) => session_id,
+            None => {
+                link.close().await?;
+                return Err(Error::Protocol("no session was accepted".to_owned()));
+            }
+        };
+        let locked_until = remote
+            .properties
+            .iter()
+            .find(|(key, _)| key.as_str() == Some(LOCKED_UNTIL_PROPERTY))
+            .and_then(|(_, ticks)| ticks.as_i64())
+            .and_then(from_ticks);
+        Ok(Self {
+            connection,
+            entity_path,
+            session_id,
+            locked_until: Mutex::new(locked_until),
+            settler: link.settler(),
+            link: tokio::sync::Mutex::new(link),
+        })
+    }
+
+    pub fn entity_path(&self) -> &str {
+        &self.entity_path
+    }
+
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// When the lock of the session expires, unless renewed.
+    pub fn locked_until(&self) -> Option<DateTime<Utc>> {
+        *self.locked_until.lock().unwrap()
+    }
+
+    /// Receives the next message of the session, waiting at most `max_wait` for one. `None`
+    /// means that no message is available, or that the service closed the receiver.
+    pub async fn receive_message(
+        &self,
+        max_wait: Duration,
+    ) -> Result<Option<PeekLockResponse>, Error> {
+        // Renews the authorization of the connection when its token is about to expire.
+        self.connection.amqp().authorize(&self.entity_path).await?;
+        let mut link = self.link.lock().await;
+        let delivery = match tokio::time::timeout(max_wait, link.recv()).await {
+            Ok(delivery) => match delivery? {
+                Some(delivery) => delivery,
+                None => return Ok(None),
+            },
+            Err(_) => return Ok(None),
+        };
+        let lock_token = lock_token_from_delivery_tag(&delivery.delivery_tag);
+        Ok(Some(PeekLockResponse::delivered(
+            self.connection.clone(),
+            self.entity_path.clone(),
+            self.session_id.clone(),
+            ServiceBusMessage::from_amqp(&delivery.message, lock_token),
+            self.settler.clone(),
+            delivery.delivery_id,
+        )))
+    }
+
+    /// Receives the deferred messages of the session by sequence number.
+    pub async fn receive_deferred_messages(
+        &self,
+        sequence_numbers: &[i64],
+    ) -> Result<Vec<PeekLockResponse>, Error> {
+        receive_deferred_messages(
+            &self.connection,
+            &self.entity_path,
+            Some(&self.session_id),
+            sequence_numbers,
+        )
+        .await
+    }
+
+    async fn session_request(
+        &self,
+        operation: &str,
+        mut body: Vec<(Value, Value)>,
+    ) -> Result<Value, Error> {
+        body.push(("session-id".into(), self.session_id.as_str().into()));
+        self.connection
+            .amqp()
+            .management_request(&self.entity_path, operation, body)
+            .await
+    }
+
+    /// Reads the state of the session, `None` if it was never set.
+    pub async fn get_session_state(&self) -> Result<Option<Bytes>, Error> {
+        let response = self
+            .session_request(GET_SESSION_STATE_OPERATION, Vec::new())
+            .await?;
+        Ok(response
+            .get("session-state")
+            .and_then(Value::as_binary)
+            .cloned())
+    }
+
+    /// Replaces the state of the session; `None` clears it.
+    pub async fn set_session_state(&self, state: Option<Bytes>) -> Result<(), Error> {
+        let state = state.map(Value::Binary).unwrap_or(Value::Null);
+        self.session_request(
+            SET_SESSION_STATE_OPERATION,
+            vec![("session-state".into(), state)],
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Extends the lock of the session, and of its messages, by the lock duration of the
+    /// entity. Returns when the lock now expires.
+    pub async fn renew_session_lock(&self) -> Result<DateTime<Utc>, Error> {
+        let response = self
+            .session_request(RENEW_SESSION_LOCK_OPERATION, Vec::new())
+            .await?;
+        let locked_until = response
+            .get("expiration")
+            .and_then(Value::as_timestamp)
+            .and_then(|millis| Utc.timestamp_millis_opt(millis).single())
+            .ok_or_else(|| Error::Decode("the renewed lock has no expiration".to_owned()))?;
+        *self.locked_until.lock().unwrap() = Some(locked_until);
+        Ok(locked_until)
+    }
+
+    /// Releases the session, making it available to the other receivers.
+    pub async fn close(self) -> Result<(), Error> {
+        self.link.into_inner().close().await
+    }
+}
+
+/// Converts .NET ticks, the 100 nanosecond intervals elapsed since 0001-01-01, to a time.
+fn from_ticks(ticks: i64) -> Option<DateTime<Utc>> {
+    const UNIX_EPOCH_TICKS: i64 = 621_355_968_000_000_000;
+    Utc.timestamp_millis_opt((ticks - UNIX_EPOCH_TICKS) / 10_000)
+        .single()
+}
@@ -0,0 +1,59 @@
+#![cfg(all(test, feature = "test_e2e"))]
+use azure_service_bus::service_bus::{
+    ServiceBusClient, ServiceBusClientOptions, ServiceBusMessage,
+};
+use std::time::Duration;
+
+#[tokio::test]
+async fn send_and_receive_queue_messages() {
+    let queue = std::env::var("AZURE_SERVICE_BUS_QUEUE")
+        .expect("Please set AZURE_SERVICE_BUS_QUEUE env variable first!");
+    let client = create_client();
+
+    let sender = client.queue_sender(&queue);
+    for i in 0..2 {
+        sender
+            .send_message(
+                &ServiceBusMessage::new(format!("{{ numero: {}, testo: \"sample\" }}", i))
+                    .with_property("index", i),
+            )
+            .await
+            .unwrap();
+    }
+
+    let receiver = client.queue_receiver(&queue);
+    let locked = receiver
+        .peek_lock(Some(Duration::from_secs(10)))
+        .await
+        .unwrap()
+        .expect("the queue should hold a message");
+    locked.renew_lock().await.unwrap();
+    locked.complete().await.unwrap();
+
+    let message = receiver
+        .receive_and_delete(Some(Duration::from_secs(10)))
+        .await
+        .unwrap()
+        .expect("the queue should hold a message");
+    assert!(message.property("index").is_some());
+}
+
+fn create_client() -> ServiceBusClient {
+    let policy_name = std::env::var("AZURE_POLICY_NAME")
+        .expect("Please set AZURE_POLICY_NAME env variable first!");
+
+    let policy_key =
+        std::env::var("AZURE_POLICY_KEY").expect("Please set AZURE_POLICY_KEY env variable first!");
+
+    let service_bus_namespace = std::env::var("AZURE_SERVICE_BUS_NAMESPACE")
+        .expect("Please set AZURE_SERVICE_BUS_NAMESPACE env variable first!");
+
+    ServiceBusClient::new(
+        azure_core::new_http_client(),
+        service_bus_namespace,
+        policy_name,
+        policy_key,
+        ServiceBusClientOptions::default(),
+    )
+    .unwrap()
+}
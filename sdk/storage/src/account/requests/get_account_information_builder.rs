@@ -1,5 +1,6 @@
 use crate::account::responses::GetAccountInformationResponse;
 use crate::core::prelude::*;
+use crate::core::StorageHttpClient;
 
 #[derive(Debug, Clone)]
 pub struct GetAccountInformationBuilder<'a> {
@@ -37,7 +38,7 @@ impl<'a> GetAccountInformationBuilder<'a> {
         let response = self
             .storage_client
             .http_client()
-            .execute_request_check_status(request, http::StatusCode::OK)
+            .execute_storage_request_check_status(request, http::StatusCode::OK)
             .await?;
 
         Ok(GetAccountInformationResponse::from_headers(
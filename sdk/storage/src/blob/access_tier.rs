@@ -5,7 +5,18 @@ create_enum!(
     AccessTier,
     (Hot, "Hot"),
     (Cool, "Cool"),
-    (Archive, "Archive")
+    (Archive, "Archive"),
+    (P4, "P4"),
+    (P6, "P6"),
+    (P10, "P10"),
+    (P15, "P15"),
+    (P20, "P20"),
+    (P30, "P30"),
+    (P40, "P40"),
+    (P50, "P50"),
+    (P60, "P60"),
+    (P70, "P70"),
+    (P80, "P80")
 );
 
 impl AddAsHeader for AccessTier {
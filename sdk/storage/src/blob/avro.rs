@@ -0,0 +1,738 @@
+//! Minimal decoder for the Avro object container format.
+//!
+//! The blob service uses Avro to frame the results of a quick query and to
+//! store the change feed segments. Only the features needed by those payloads
+//! are supported: uncompressed (`null` codec) containers and the primitive and
+//! complex types of the Avro 1.8 specification.
+//! See [https://avro.apache.org/docs/1.8.2/spec.html](https://avro.apache.org/docs/1.8.2/spec.html).
+use bytes::Bytes;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::convert::TryInto;
+
+const MAGIC: &[u8] = b"Obj\x01";
+const SYNC_MARKER_LENGTH: usize = 16;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Schema {
+    Null,
+    Boolean,
+    Int,
+    Long,
+    Float,
+    Double,
+    Bytes,
+    String,
+    Record {
+        name: String,
+        fields: Vec<(String, Schema)>,
+    },
+    Enum {
+        symbols: Vec<String>,
+    },
+    Array(Box<Schema>),
+    Map(Box<Schema>),
+    Union(Vec<Schema>),
+    Fixed(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Boolean(bool),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    Bytes(Bytes),
+    String(String),
+    /// A record carries its (unqualified) schema name so the variants of a
+    /// union of records can be told apart.
+    Record(String, Vec<(String, Value)>),
+    Enum(String),
+    Array(Vec<Value>),
+    Map(HashMap<String, Value>),
+    Fixed(Bytes),
+}
+
+impl Value {
+    /// Returns the field `name` of a record value.
+    pub(crate) fn field(&self, name: &str) -> Option<&Value> {
+        match self {
+            Value::Record(_, fields) => fields.iter().find(|(n, _)| n == name).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Int(i) => Some(*i as i64),
+            Value::Long(l) => Some(*l),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) | Value::Enum(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_bytes(&self) -> Option<&Bytes> {
+        match self {
+            Value::Bytes(b) | Value::Fixed(b) => Some(b),
+            _ => None,
+        }
+    }
+}
+
+fn avro_error(text: impl Into<String>) -> crate::Error {
+    crate::Error::AvroError(text.into())
+}
+
+impl Schema {
+    pub(crate) fn parse(json: &str) -> Result<Schema, crate::Error> {
+        let json: JsonValue = serde_json::from_str(json)?;
+        Self::from_json(&json, None, &mut HashMap::new())
+    }
+
+    fn from_json(
+        json: &JsonValue,
+        namespace: Option<&str>,
+        named: &mut HashMap<String, Schema>,
+    ) -> Result<Schema, crate::Error> {
+        match json {
+            JsonValue::String(name) => Self::from_name(name, namespace, named),
+            JsonValue::Array(branches) => Ok(Schema::Union(
+                branches
+                    .iter()
+                    .map(|branch| Self::from_json(branch, namespace, named))
+                    .collect::<Result<_, _>>()?,
+            )),
+            JsonValue::Object(object) => {
+                let schema_type = object
+                    .get("type")
+                    .ok_or_else(|| avro_error("schema object without type"))?;
+                let schema_type = match schema_type {
+                    JsonValue::String(schema_type) => schema_type.as_str(),
+                    // {"type": {...}} is just a wrapped schema
+                    other => return Self::from_json(other, namespace, named),
+                };
+                let name = object.get("name").and_then(JsonValue::as_str);
+                let namespace = object
+                    .get("namespace")
+                    .and_then(JsonValue::as_str)
+                    .or(namespace);
+
+                let schema = match schema_type {
+                    "record" | "error" => {
+                        let name = name.ok_or_else(|| avro_error("record without name"))?;
+                        let fields = object
+                            .get("fields")
+                            .and_then(JsonValue::as_array)
+                            .ok_or_else(|| avro_error("record without fields"))?
+                            .iter()
+                            .map(|field| {
+                                let field_name = field
+                                    .get("name")
+                                    .and_then(JsonValue::as_str)
+                                    .ok_or_else(|| avro_error("field without name"))?;
+                                let field_type = field
+                                    .get("type")
+                                    .ok_or_else(|| avro_error("field without type"))?;
+                                Ok((
+                                    field_name.to_owned(),
+                                    Self::from_json(field_type, namespace, named)?,
+                                ))
+                            })
+                            .collect::<Result<_, crate::Error>>()?;
+                        Schema::Record {
+                            name: name.rsplit('.').next().unwrap_or(name).to_owned(),
+                            fields,
+                        }
+                    }
+                    "enum" => Schema::Enum {
+                        symbols: object
+                            .get("symbols")
+                            .and_then(JsonValue::as_array)
+                            .ok_or_else(|| avro_error("enum without symbols"))?
+                            .iter()
+                            .map(|s| s.as_str().map(ToOwned::to_owned))
+                            .collect::<Option<_>>()
+                            .ok_or_else(|| avro_error("invalid enum symbol"))?,
+                    },
+                    "array" => Schema::Array(Box::new(Self::from_json(
+                        object
+                            .get("items")
+                            .ok_or_else(|| avro_error("array without items"))?,
+                        namespace,
+                        named,
+                    )?)),
+                    "map" => Schema::Map(Box::new(Self::from_json(
+                        object
+                            .get("values")
+                            .ok_or_else(|| avro_error("map without values"))?,
+                        namespace,
+                        named,
+                    )?)),
+                    "fixed" => Schema::Fixed(
+                        object
+                            .get("size")
+                            .and_then(JsonValue::as_u64)
+                            .ok_or_else(|| avro_error("fixed without size"))?
+                            as usize,
+                    ),
+                    primitive => return Self::from_name(primitive, namespace, named),
+                };
+
+                if let Some(name) = name {
+                    named.insert(name.to_owned(), schema.clone());
+                    if let Some(namespace) = namespace {
+                        named.insert(format!("{}.{}", namespace, name), schema.clone());
+                    }
+                }
+
+                Ok(schema)
+            }
+            other => Err(avro_error(format!("invalid schema: {}", other))),
+        }
+    }
+
+    fn from_name(
+        name: &str,
+        namespace: Option<&str>,
+        named: &HashMap<String, Schema>,
+    ) -> Result<Schema, crate::Error> {
+        Ok(match name {
+            "null" => Schema::Null,
+            "boolean" => Schema::Boolean,
+            "int" => Schema::Int,
+            "long" => Schema::Long,
+            "float" => Schema::Float,
+            "double" => Schema::Double,
+            "bytes" => Schema::Bytes,
+            "string" => Schema::String,
+            name => namespace
+                .and_then(|namespace| named.get(&format!("{}.{}", namespace, name)))
+                .or_else(|| named.get(name))
+                .cloned()
+                .ok_or_else(|| avro_error(format!("unknown type {}", name)))?,
+        })
+    }
+}
+
+/// Cursor over an in-memory Avro encoded buffer.
+struct Decoder<'a> {
+    data: &'a [u8],
+    position: usize,
+    /// Set when a read ran past the end of the buffer: the length the buffer needs to have
+    /// for the read to succeed.
+    needed: Option<usize>,
+}
+
+impl<'a> Decoder<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            position: 0,
+            needed: None,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.position >= self.data.len()
+    }
+
+    fn read_exact(&mut self, length: usize) -> Result<&'a [u8], crate::Error> {
+        let end = self
+            .position
+            .checked_add(length)
+            .ok_or_else(|| avro_error("invalid length"))?;
+        if end > self.data.len() {
+            self.needed = Some(end);
+            return Err(avro_error("unexpected end of data"));
+        }
+        let slice = &self.data[self.position..end];
+        self.position = end;
+        Ok(slice)
+    }
+
+    fn read_long(&mut self) -> Result<i64, crate::Error> {
+        let mut raw: u64 = 0;
+        let mut shift = 0;
+        loop {
+            if shift >= 64 {
+                return Err(avro_error("variable length integer overflow"));
+            }
+            let byte = self.read_exact(1)?[0];
+            raw |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                break;
+            }
+            shift += 7;
+        }
+        // zig-zag decoding
+        Ok((raw >> 1) as i64 ^ -((raw & 1) as i64))
+    }
+
+    fn read_length(&mut self) -> Result<usize, crate::Error> {
+        let length = self.read_long()?;
+        if length < 0 {
+            return Err(avro_error(format!("negative length {}", length)));
+        }
+        Ok(length as usize)
+    }
+
+    fn read_bytes(&mut self) -> Result<&'a [u8], crate::Error> {
+        let length = self.read_length()?;
+        self.read_exact(length)
+    }
+
+    fn read_string(&mut self) -> Result<String, crate::Error> {
+        Ok(std::str::from_utf8(self.read_bytes()?)?.to_owned())
+    }
+
+    /// Reads the item count of an array or map block. Negative counts are
+    /// followed by the block size in bytes, which we do not need.
+    fn read_block_count(&mut self) -> Result<usize, crate::Error> {
+        let count = self.read_long()?;
+        if count < 0 {
+            self.read_long()?;
+        }
+        Ok(count.unsigned_abs() as usize)
+    }
+
+    fn read_value(&mut self, schema: &Schema, source: &Bytes) -> Result<Value, crate::Error> {
+        Ok(match schema {
+            Schema::Null => Value::Null,
+            Schema::Boolean => Value::Boolean(self.read_exact(1)?[0] != 0),
+            Schema::Int => Value::Int(self.read_long()? as i32),
+            Schema::Long => Value::Long(self.read_long()?),
+            Schema::Float => {
+                Value::Float(f32::from_le_bytes(self.read_exact(4)?.try_into().unwrap()))
+            }
+            Schema::Double => {
+                Value::Double(f64::from_le_bytes(self.read_exact(8)?.try_into().unwrap()))
+            }
+            Schema::Bytes => Value::Bytes(source.slice_ref(self.read_bytes()?)),
+            Schema::String => Value::String(self.read_string()?),
+            Schema::Record { name, fields } => Value::Record(
+                name.clone(),
+                fields
+                    .iter()
+                    .map(|(field_name, field_schema)| {
+                        Ok((field_name.clone(), self.read_value(field_schema, source)?))
+                    })
+                    .collect::<Result<_, crate::Error>>()?,
+            ),
+            Schema::Enum { symbols } => {
+                let index = self.read_length()?;
+                Value::Enum(
+                    symbols
+                        .get(index)
+                        .ok_or_else(|| avro_error(format!("invalid enum index {}", index)))?
+                        .clone(),
+                )
+            }
+            Schema::Array(items) => {
+                let mut values = Vec::new();
+                loop {
+                    let count = self.read_block_count()?;
+                    if count == 0 {
+                        break;
+                    }
+                    for _ in 0..count {
+                        values.push(self.read_value(items, source)?);
+                    }
+                }
+                Value::Array(values)
+            }
+            Schema::Map(values_schema) => {
+                let mut values = HashMap::new();
+                loop {
+                    let count = self.read_block_count()?;
+                    if count == 0 {
+                        break;
+                    }
+                    for _ in 0..count {
+                        let key = self.read_string()?;
+                        values.insert(key, self.read_value(values_schema, source)?);
+                    }
+                }
+                Value::Map(values)
+            }
+            Schema::Union(branches) => {
+                let index = self.read_length()?;
+                let branch = branches
+                    .get(index)
+                    .ok_or_else(|| avro_error(format!("invalid union index {}", index)))?;
+                self.read_value(branch, source)?
+            }
+            Schema::Fixed(size) => Value::Fixed(source.slice_ref(self.read_exact(*size)?)),
+        })
+    }
+}
+
+/// Reads the records of an Avro object container file.
+#[derive(Debug, Clone)]
+pub(crate) struct AvroReader {
+    data: Bytes,
+    position: usize,
+    schema: Schema,
+    sync_marker: Bytes,
+    remaining_in_block: usize,
+}
+
+impl AvroReader {
+    pub(crate) fn new(data: Bytes) -> Result<Self, crate::Error> {
+        let mut decoder = Decoder::new(&data);
+        let (schema, sync_marker) = read_header(&mut decoder, &data)?;
+        let position = decoder.position;
+
+        Ok(Self {
+            data,
+            position,
+            schema,
+            sync_marker,
+            remaining_in_block: 0,
+        })
+    }
+
+    fn next_value(&mut self) -> Result<Option<Value>, crate::Error> {
+        let mut decoder = Decoder::new(&self.data);
+        decoder.position = self.position;
+
+        let value = read_object(
+            &mut decoder,
+            &self.data,
+            &self.schema,
+            &self.sync_marker,
+            &mut self.remaining_in_block,
+        )?;
+        self.position = decoder.position;
+
+        Ok(value)
+    }
+}
+
+/// Reads the header of an object container: the schema of its objects and the sync marker
+/// ending its blocks.
+fn read_header(decoder: &mut Decoder, data: &Bytes) -> Result<(Schema, Bytes), crate::Error> {
+    if decoder.read_exact(MAGIC.len())? != MAGIC {
+        return Err(avro_error("invalid magic bytes"));
+    }
+
+    let metadata = match decoder.read_value(&Schema::Map(Box::new(Schema::Bytes)), data)? {
+        Value::Map(metadata) => metadata,
+        _ => unreachable!(),
+    };
+
+    match metadata.get("avro.codec").and_then(Value::as_bytes) {
+        None => {}
+        Some(codec) if codec.as_ref() == b"null" => {}
+        Some(codec) => {
+            return Err(avro_error(format!(
+                "unsupported codec {}",
+                String::from_utf8_lossy(codec)
+            )))
+        }
+    }
+
+    let schema = metadata
+        .get("avro.schema")
+        .and_then(Value::as_bytes)
+        .ok_or_else(|| avro_error("missing avro.schema"))?;
+    let schema = Schema::parse(std::str::from_utf8(schema)?)?;
+
+    let sync_marker = data.slice_ref(decoder.read_exact(SYNC_MARKER_LENGTH)?);
+
+    Ok((schema, sync_marker))
+}
+
+/// Reads the next object of a container, and the block header before it when the previous
+/// block is over. `None` when the data ends between two blocks.
+fn read_object(
+    decoder: &mut Decoder,
+    data: &Bytes,
+    schema: &Schema,
+    sync_marker: &[u8],
+    remaining_in_block: &mut usize,
+) -> Result<Option<Value>, crate::Error> {
+    while *remaining_in_block == 0 {
+        if decoder.is_empty() {
+            return Ok(None);
+        }
+        *remaining_in_block = decoder.read_length()?;
+        // block size in bytes, not needed since we decode every object
+        decoder.read_long()?;
+        if *remaining_in_block == 0 {
+            // an empty block is still terminated by the sync marker
+            check_sync_marker(decoder, sync_marker)?;
+        }
+    }
+
+    let value = decoder.read_value(schema, data)?;
+    *remaining_in_block -= 1;
+    if *remaining_in_block == 0 {
+        check_sync_marker(decoder, sync_marker)?;
+    }
+
+    Ok(Some(value))
+}
+
+fn check_sync_marker(decoder: &mut Decoder, sync_marker: &[u8]) -> Result<(), crate::Error> {
+    if decoder.read_exact(SYNC_MARKER_LENGTH)? != sync_marker {
+        return Err(avro_error("sync marker mismatch"));
+    }
+    Ok(())
+}
+
+impl Iterator for AvroReader {
+    type Item = Result<Value, crate::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_value() {
+            Ok(Some(value)) => Some(Ok(value)),
+            Ok(None) => None,
+            Err(err) => {
+                // do not loop over a corrupted buffer
+                self.position = self.data.len();
+                self.remaining_in_block = 0;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Reads the records of an Avro object container received in chunks, returning every object
+/// as soon as its last byte is received.
+#[derive(Debug, Default)]
+pub(crate) struct AvroStreamReader {
+    /// The data received and not decoded yet, concatenated only once it holds the next
+    /// header or object.
+    chunks: Vec<Bytes>,
+    length: usize,
+    /// How much data is needed before the next decoding attempt.
+    needed: usize,
+    header: Option<(Schema, Bytes)>,
+    remaining_in_block: usize,
+}
+
+impl AvroStreamReader {
+    pub(crate) fn push(&mut self, chunk: Bytes) {
+        if !chunk.is_empty() {
+            self.length += chunk.len();
+            self.chunks.push(chunk);
+        }
+    }
+
+    /// Returns the next object, or `None` until more data is pushed.
+    pub(crate) fn next_value(&mut self) -> Result<Option<Value>, crate::Error> {
+        loop {
+            if self.length == 0 || self.length < self.needed {
+                return Ok(None);
+            }
+            let data = self.data();
+            let mut decoder = Decoder::new(&data);
+
+            let mut header = None;
+            let mut remaining_in_block = self.remaining_in_block;
+            let result = match &self.header {
+                Some((schema, sync_marker)) => read_object(
+                    &mut decoder,
+                    &data,
+                    schema,
+                    sync_marker,
+                    &mut remaining_in_block,
+                ),
+                None => read_header(&mut decoder, &data).map(|read| {
+                    header = Some(read);
+                    None
+                }),
+            };
+            let value = match (result, decoder.needed) {
+                (Ok(value), _) => value,
+                (Err(_), Some(needed)) => {
+                    self.needed = needed;
+                    return Ok(None);
+                }
+                (Err(err), None) => return Err(err),
+            };
+
+            if header.is_some() {
+                self.header = header;
+            }
+            self.remaining_in_block = remaining_in_block;
+            self.chunks = vec![data.slice(decoder.position..)];
+            self.length -= decoder.position;
+            self.needed = 0;
+
+            if value.is_some() {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// Checks, once all the data was pushed, that it held a whole container.
+    pub(crate) fn finish(&self) -> Result<(), crate::Error> {
+        if self.header.is_none() || self.length > 0 || self.remaining_in_block > 0 {
+            return Err(avro_error("unexpected end of data"));
+        }
+        Ok(())
+    }
+
+    fn data(&mut self) -> Bytes {
+        if self.chunks.len() > 1 {
+            let mut data = bytes::BytesMut::with_capacity(self.length);
+            for chunk in self.chunks.drain(..) {
+                data.extend_from_slice(&chunk);
+            }
+            self.chunks.push(data.freeze());
+        }
+        self.chunks[0].clone()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn encode_long(value: i64, out: &mut Vec<u8>) {
+        let mut n = ((value << 1) ^ (value >> 63)) as u64;
+        loop {
+            if n & !0x7f == 0 {
+                out.push(n as u8);
+                break;
+            }
+            out.push(((n & 0x7f) | 0x80) as u8);
+            n >>= 7;
+        }
+    }
+
+    pub(crate) fn encode_bytes(value: &[u8], out: &mut Vec<u8>) {
+        encode_long(value.len() as i64, out);
+        out.extend_from_slice(value);
+    }
+
+    /// Builds an object container with a single block holding the given,
+    /// already encoded, objects.
+    pub(crate) fn container(schema: &str, objects: &[Vec<u8>]) -> Bytes {
+        let sync_marker = b"0123456789abcdef";
+        let mut out = MAGIC.to_vec();
+        encode_long(2, &mut out);
+        encode_bytes(b"avro.schema", &mut out);
+        encode_bytes(schema.as_bytes(), &mut out);
+        encode_bytes(b"avro.codec", &mut out);
+        encode_bytes(b"null", &mut out);
+        encode_long(0, &mut out);
+        out.extend_from_slice(sync_marker);
+
+        let block: Vec<u8> = objects.iter().flatten().copied().collect();
+        encode_long(objects.len() as i64, &mut out);
+        encode_long(block.len() as i64, &mut out);
+        out.extend_from_slice(&block);
+        out.extend_from_slice(sync_marker);
+        Bytes::from(out)
+    }
+
+    #[test]
+    fn zig_zag() {
+        for value in &[0i64, -1, 1, -64, 64, 1 << 40, i64::MIN, i64::MAX] {
+            let mut out = Vec::new();
+            encode_long(*value, &mut out);
+            assert_eq!(Decoder::new(&out).read_long().unwrap(), *value);
+        }
+    }
+
+    #[test]
+    fn read_records() {
+        let schema = r#"{"type":"record","name":"com.example.item","fields":[
+            {"name":"id","type":"long"},
+            {"name":"label","type":["null","string"]},
+            {"name":"tags","type":{"type":"map","values":"string"}}]}"#;
+
+        let mut first = Vec::new();
+        encode_long(42, &mut first);
+        encode_long(1, &mut first);
+        encode_bytes(b"hello", &mut first);
+        encode_long(1, &mut first);
+        encode_bytes(b"k", &mut first);
+        encode_bytes(b"v", &mut first);
+        encode_long(0, &mut first);
+
+        let mut second = Vec::new();
+        encode_long(-7, &mut second);
+        encode_long(0, &mut second);
+        encode_long(0, &mut second);
+
+        let reader = AvroReader::new(container(schema, &[first, second])).unwrap();
+        let values = reader.collect::<Result<Vec<_>, _>>().unwrap();
+
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0].field("id").and_then(Value::as_i64), Some(42));
+        assert_eq!(
+            values[0].field("label").and_then(Value::as_str),
+            Some("hello")
+        );
+        match values[0].field("tags") {
+            Some(Value::Map(tags)) => assert_eq!(tags.get("k"), Some(&Value::String("v".into()))),
+            other => panic!("unexpected tags {:?}", other),
+        }
+        assert_eq!(values[1].field("id").and_then(Value::as_i64), Some(-7));
+        assert_eq!(values[1].field("label"), Some(&Value::Null));
+    }
+
+    #[test]
+    fn read_records_in_chunks() {
+        let schema = r#"{"type":"record","name":"com.example.item","fields":[
+            {"name":"id","type":"long"},
+            {"name":"payload","type":"bytes"}]}"#;
+
+        let objects: Vec<Vec<u8>> = (0..3)
+            .map(|id| {
+                let mut object = Vec::new();
+                encode_long(id, &mut object);
+                encode_bytes(&[id as u8; 100], &mut object);
+                object
+            })
+            .collect();
+        let data = container(schema, &objects);
+
+        let mut reader = AvroStreamReader::default();
+        let mut values = Vec::new();
+        for chunk in data.chunks(7) {
+            reader.push(Bytes::copy_from_slice(chunk));
+            while let Some(value) = reader.next_value().unwrap() {
+                values.push(value);
+            }
+        }
+        reader.finish().unwrap();
+
+        assert_eq!(values.len(), 3);
+        for (id, value) in values.iter().enumerate() {
+            assert_eq!(value.field("id").and_then(Value::as_i64), Some(id as i64));
+            assert_eq!(
+                value.field("payload").and_then(Value::as_bytes),
+                Some(&Bytes::from(vec![id as u8; 100]))
+            );
+        }
+
+        let mut truncated = AvroStreamReader::default();
+        truncated.push(data.slice(..data.len() - 1));
+        while truncated.next_value().unwrap().is_some() {}
+        assert!(truncated.finish().is_err());
+    }
+
+    #[test]
+    fn reject_bad_magic() {
+        assert!(AvroReader::new(Bytes::from_static(b"nope")).is_err());
+    }
+}
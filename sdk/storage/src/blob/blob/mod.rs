@@ -10,18 +10,30 @@ mod block_with_size_list;
 pub use self::block_with_size_list::BlockWithSizeList;
 mod block_list;
 pub use self::block_list::BlockList;
+mod query_serialization;
+pub use self::query_serialization::{
+    ArrowConfiguration, ArrowField, ArrowFieldType, DelimitedTextConfiguration,
+    JsonTextConfiguration, QuerySerialization,
+};
 pub mod requests;
 pub mod responses;
+use crate::blob::headers::{
+    ACCESS_TIER_CHANGE_TIME, ACCESS_TIER_INFERRED, ARCHIVE_STATUS, ENCRYPTION_KEY_SHA256,
+    ENCRYPTION_SCOPE, IMMUTABILITY_POLICY_MODE, IMMUTABILITY_POLICY_UNTIL_DATE, IS_CURRENT_VERSION,
+    LEGAL_HOLD, REHYDRATE_PRIORITY, VERSION_ID,
+};
 use crate::headers::CONTENT_CRC64;
 use crate::{
     headers::{CONTENT_MD5, COPY_ID},
     ConsistencyCRC64, ConsistencyMD5,
 };
-use crate::{AccessTier, CopyId, CopyProgress};
+use crate::{AccessTier, CopyId, CopyProgress, ImmutabilityPolicyMode};
+// the enum moved to the blob module, shared with the requests setting it
+pub use crate::RehydratePriority;
 use azure_core::headers::{
     BLOB_SEQUENCE_NUMBER, BLOB_TYPE, COPY_COMPLETION_TIME, COPY_PROGRESS, COPY_SOURCE, COPY_STATUS,
     COPY_STATUS_DESCRIPTION, CREATION_TIME, LEASE_DURATION, LEASE_STATE, LEASE_STATUS, META_PREFIX,
-    SERVER_ENCRYPTED,
+    SERVER_ENCRYPTED, STORAGE_ACCESS_TIER,
 };
 use azure_core::{
     lease::{LeaseDuration, LeaseState, LeaseStatus},
@@ -62,7 +74,11 @@ create_enum!(
     (Failed, "failed")
 );
 
-create_enum!(RehydratePriority, (High, "High"), (Standard, "Standard"));
+create_enum!(
+    ArchiveStatus,
+    (RehydratePendingToHot, "rehydrate-pending-to-hot"),
+    (RehydratePendingToCool, "rehydrate-pending-to-cool")
+);
 
 create_enum!(PageWriteType, (Update, "update"), (Clear, "clear"));
 
@@ -81,6 +97,33 @@ where
         .map_err(serde::de::Error::custom)?)
 }
 
+fn deserialize_lenient_optional<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    let s: Option<String> = Option::deserialize(deserializer)?;
+
+    Ok(s.as_deref().and_then(parse_lenient))
+}
+
+/// Parses the value of an enumeration, like an access tier or an archive status, ignoring
+/// the values added to the service after this crate.
+fn parse_lenient<T>(s: &str) -> Option<T>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    match s.parse() {
+        Ok(value) => Some(value),
+        Err(error) => {
+            warn!("ignoring unknown value {}: {}", s, error);
+            None
+        }
+    }
+}
+
 fn deserialize_md5_optional<'de, D>(
     deserializer: D,
 ) -> Result<Option<crate::ConsistencyMD5>, D::Error>
@@ -103,7 +146,11 @@ pub struct Blob {
     pub version_id: Option<String>,
     pub is_current_version: Option<bool>,
     pub deleted: Option<bool>,
+    pub has_versions_only: Option<bool>,
     pub properties: BlobProperties,
+    /// The metadata listed with `include_metadata`, moved into the properties once parsed.
+    #[serde(rename = "Metadata", default)]
+    listed_metadata: Option<HashMap<String, String>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
@@ -147,6 +194,8 @@ pub struct BlobProperties {
     #[serde(rename = "x-ms-blob-sequence-number")]
     pub blob_sequence_number: Option<u64>,
     pub blob_type: BlobType,
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_lenient_optional")]
     pub access_tier: Option<AccessTier>,
     #[serde(default)]
     #[serde(with = "azure_core::parsing::rfc2822_time_format_optional")]
@@ -168,11 +217,19 @@ pub struct BlobProperties {
     pub incremental_copy: Option<bool>,
     pub access_tier_inferred: Option<bool>,
     #[serde(default)]
+    #[serde(deserialize_with = "deserialize_lenient_optional")]
+    pub archive_status: Option<ArchiveStatus>,
+    #[serde(default)]
     #[serde(with = "azure_core::parsing::rfc2822_time_format_optional")]
     pub deleted_time: Option<DateTime<Utc>>,
     pub remaining_retention_days: Option<u32>,
     pub tag_count: Option<u32>,
     pub rehydrate_priority: Option<RehydratePriority>,
+    #[serde(default)]
+    #[serde(with = "azure_core::parsing::rfc2822_time_format_optional")]
+    pub immutability_policy_until_date: Option<DateTime<Utc>>,
+    pub immutability_policy_mode: Option<ImmutabilityPolicyMode>,
+    pub legal_hold: Option<bool>,
     pub metadata: Option<HashMap<String, String>>,
     #[serde(flatten)]
     extra: HashMap<String, String>, // For debug purposes, should be compiled out in the future
@@ -306,6 +363,66 @@ impl Blob {
             .ok_or_else(|| crate::Error::HeaderNotFound(SERVER_ENCRYPTED.to_owned()))?
             .parse::<bool>()?;
 
+        let customer_provided_key_sha256 = h.get_as_string(ENCRYPTION_KEY_SHA256);
+        trace!(
+            "customer_provided_key_sha256 == {:?}",
+            customer_provided_key_sha256
+        );
+
+        let encryption_scope = h.get_as_string(ENCRYPTION_SCOPE);
+        trace!("encryption_scope == {:?}", encryption_scope);
+
+        let access_tier = h.get_as_str(STORAGE_ACCESS_TIER).and_then(parse_lenient);
+        trace!("access_tier == {:?}", access_tier);
+
+        let access_tier_inferred = h
+            .get_as_str(ACCESS_TIER_INFERRED)
+            .map(|s| s.parse::<bool>())
+            .transpose()?;
+        trace!("access_tier_inferred == {:?}", access_tier_inferred);
+
+        let access_tier_change_time = h
+            .get_as_str(ACCESS_TIER_CHANGE_TIME)
+            .map(from_azure_time)
+            .transpose()?;
+        trace!("access_tier_change_time == {:?}", access_tier_change_time);
+
+        let archive_status = h.get_as_str(ARCHIVE_STATUS).and_then(parse_lenient);
+        trace!("archive_status == {:?}", archive_status);
+
+        let rehydrate_priority = h.get_as_enum(REHYDRATE_PRIORITY)?;
+        trace!("rehydrate_priority == {:?}", rehydrate_priority);
+
+        let immutability_policy_until_date = h
+            .get_as_str(IMMUTABILITY_POLICY_UNTIL_DATE)
+            .map(from_azure_time)
+            .transpose()?;
+        trace!(
+            "immutability_policy_until_date == {:?}",
+            immutability_policy_until_date
+        );
+
+        let immutability_policy_mode = h
+            .get_as_str(IMMUTABILITY_POLICY_MODE)
+            .map(ImmutabilityPolicyMode::from_str)
+            .transpose()?;
+        trace!("immutability_policy_mode == {:?}", immutability_policy_mode);
+
+        let legal_hold = h
+            .get_as_str(LEGAL_HOLD)
+            .map(|s| s.parse::<bool>())
+            .transpose()?;
+        trace!("legal_hold == {:?}", legal_hold);
+
+        let version_id = h.get_as_string(VERSION_ID);
+        trace!("version_id == {:?}", version_id);
+
+        let is_current_version = h
+            .get_as_str(IS_CURRENT_VERSION)
+            .map(|s| s.parse::<bool>())
+            .transpose()?;
+        trace!("is_current_version == {:?}", is_current_version);
+
         let mut metadata = HashMap::new();
         for (name, value) in h.iter() {
             let name = name.as_str();
@@ -328,9 +445,10 @@ impl Blob {
         Ok(Blob {
             name: blob_name.into(),
             snapshot,
-            deleted: None,            //TODO
-            is_current_version: None, //TODO
-            version_id: None,         //TODO
+            deleted: None, //TODO
+            is_current_version,
+            version_id,
+            has_versions_only: None,
             properties: BlobProperties {
                 creation_time,
                 last_modified,
@@ -346,7 +464,7 @@ impl Blob {
                 content_disposition,
                 blob_sequence_number,
                 blob_type,
-                access_tier: None,
+                access_tier,
                 lease_status,
                 lease_state,
                 lease_duration,
@@ -358,17 +476,22 @@ impl Blob {
                 copy_status_description,
                 incremental_copy: None, // TODO: Not present or documentation bug?
                 server_encrypted,
-                customer_provided_key_sha256: None, // TODO
-                encryption_scope: None,             // TODO
-                access_tier_inferred: None,         // TODO: Not present
-                access_tier_change_time: None,      // TODO: Not present
-                deleted_time: None,                 // TODO
-                remaining_retention_days: None,     // TODO: Not present or documentation bug?
-                tag_count: None,                    // TODO
-                rehydrate_priority: None,           // TODO
+                customer_provided_key_sha256,
+                encryption_scope,
+                access_tier_inferred,
+                access_tier_change_time,
+                archive_status,
+                deleted_time: None,             // Only returned by List Blobs
+                remaining_retention_days: None, // Only returned by List Blobs
+                tag_count: None,                // TODO
+                rehydrate_priority,
+                immutability_policy_until_date,
+                immutability_policy_mode,
+                legal_hold,
                 metadata,
                 extra: HashMap::new(),
             },
+            listed_metadata: None,
         })
     }
 }
@@ -0,0 +1,188 @@
+use crate::xml::xml_escape;
+
+/// Format of the blob contents (input) or of the query results (output) of
+/// a quick query. Parquet input and Arrow output are only valid on the
+/// respective side.
+/// See [https://docs.microsoft.com/rest/api/storageservices/query-blob-contents](https://docs.microsoft.com/rest/api/storageservices/query-blob-contents).
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuerySerialization {
+    Delimited(DelimitedTextConfiguration),
+    Json(JsonTextConfiguration),
+    Arrow(ArrowConfiguration),
+    Parquet,
+}
+
+impl QuerySerialization {
+    pub(crate) fn to_xml(&self) -> String {
+        let format = match self {
+            QuerySerialization::Delimited(configuration) => {
+                format!("<Type>delimited</Type>{}", configuration.to_xml())
+            }
+            QuerySerialization::Json(configuration) => {
+                format!("<Type>json</Type>{}", configuration.to_xml())
+            }
+            QuerySerialization::Arrow(configuration) => {
+                format!("<Type>arrow</Type>{}", configuration.to_xml())
+            }
+            QuerySerialization::Parquet => "<Type>parquet</Type>".to_owned(),
+        };
+        format!("<Format>{}</Format>", format)
+    }
+}
+
+impl From<DelimitedTextConfiguration> for QuerySerialization {
+    fn from(configuration: DelimitedTextConfiguration) -> Self {
+        QuerySerialization::Delimited(configuration)
+    }
+}
+
+impl From<JsonTextConfiguration> for QuerySerialization {
+    fn from(configuration: JsonTextConfiguration) -> Self {
+        QuerySerialization::Json(configuration)
+    }
+}
+
+impl From<ArrowConfiguration> for QuerySerialization {
+    fn from(configuration: ArrowConfiguration) -> Self {
+        QuerySerialization::Arrow(configuration)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DelimitedTextConfiguration {
+    pub column_separator: String,
+    pub field_quote: String,
+    pub record_separator: String,
+    pub escape_char: String,
+    pub has_headers: bool,
+}
+
+impl Default for DelimitedTextConfiguration {
+    fn default() -> Self {
+        Self {
+            column_separator: ",".to_owned(),
+            field_quote: "\"".to_owned(),
+            record_separator: "\n".to_owned(),
+            escape_char: "".to_owned(),
+            has_headers: false,
+        }
+    }
+}
+
+impl DelimitedTextConfiguration {
+    setters! {
+        column_separator: String => column_separator,
+        field_quote: String => field_quote,
+        record_separator: String => record_separator,
+        escape_char: String => escape_char,
+        has_headers: bool => has_headers,
+    }
+
+    fn to_xml(&self) -> String {
+        format!(
+            "<DelimitedTextConfiguration><ColumnSeparator>{}</ColumnSeparator><FieldQuote>{}</FieldQuote><RecordSeparator>{}</RecordSeparator><EscapeChar>{}</EscapeChar><HasHeaders>{}</HasHeaders></DelimitedTextConfiguration>",
+            xml_escape(&self.column_separator),
+            xml_escape(&self.field_quote),
+            xml_escape(&self.record_separator),
+            xml_escape(&self.escape_char),
+            self.has_headers
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonTextConfiguration {
+    pub record_separator: String,
+}
+
+impl Default for JsonTextConfiguration {
+    fn default() -> Self {
+        Self {
+            record_separator: "\n".to_owned(),
+        }
+    }
+}
+
+impl JsonTextConfiguration {
+    pub fn record_separator<T: Into<String>>(self, record_separator: T) -> Self {
+        Self {
+            record_separator: record_separator.into(),
+        }
+    }
+
+    fn to_xml(&self) -> String {
+        format!(
+            "<JsonTextConfiguration><RecordSeparator>{}</RecordSeparator></JsonTextConfiguration>",
+            xml_escape(&self.record_separator)
+        )
+    }
+}
+
+create_enum!(
+    ArrowFieldType,
+    (Int64, "int64"),
+    (Bool, "bool"),
+    (Timestamp, "timestamp[ms]"),
+    (String, "string"),
+    (Double, "double"),
+    (Decimal, "decimal")
+);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrowField {
+    pub field_type: ArrowFieldType,
+    pub name: Option<String>,
+    pub precision: Option<u32>,
+    pub scale: Option<u32>,
+}
+
+impl ArrowField {
+    pub fn new(field_type: ArrowFieldType) -> Self {
+        Self {
+            field_type,
+            name: None,
+            precision: None,
+            scale: None,
+        }
+    }
+
+    setters! {
+        name: String => Some(name),
+        precision: u32 => Some(precision),
+        scale: u32 => Some(scale),
+    }
+
+    fn to_xml(&self) -> String {
+        let mut s = format!("<Field><Type>{}</Type>", self.field_type);
+        if let Some(name) = &self.name {
+            s.push_str(&format!("<Name>{}</Name>", xml_escape(name)));
+        }
+        if let Some(precision) = self.precision {
+            s.push_str(&format!("<Precision>{}</Precision>", precision));
+        }
+        if let Some(scale) = self.scale {
+            s.push_str(&format!("<Scale>{}</Scale>", scale));
+        }
+        s.push_str("</Field>");
+        s
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ArrowConfiguration {
+    pub schema: Vec<ArrowField>,
+}
+
+impl ArrowConfiguration {
+    pub fn new(schema: Vec<ArrowField>) -> Self {
+        Self { schema }
+    }
+
+    fn to_xml(&self) -> String {
+        let fields: String = self.schema.iter().map(ArrowField::to_xml).collect();
+        format!(
+            "<ArrowConfiguration><Schema>{}</Schema></ArrowConfiguration>",
+            fields
+        )
+    }
+}
@@ -0,0 +1,69 @@
+use crate::blob::blob::responses::AbortCopyBlobResponse;
+use crate::blob::headers::COPY_ACTION;
+use crate::blob::prelude::*;
+use crate::core::CopyId;
+use crate::core::StorageHttpClient;
+use azure_core::headers::{add_optional_header, add_optional_header_ref};
+use azure_core::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct AbortCopyBlobBuilder<'a> {
+    blob_client: &'a BlobClient,
+    copy_id: &'a CopyId,
+    lease_id: Option<&'a LeaseId>,
+    client_request_id: Option<ClientRequestId<'a>>,
+    timeout: Option<Timeout>,
+}
+
+impl<'a> AbortCopyBlobBuilder<'a> {
+    pub(crate) fn new(blob_client: &'a BlobClient, copy_id: &'a CopyId) -> Self {
+        Self {
+            blob_client,
+            copy_id,
+            lease_id: None,
+            client_request_id: None,
+            timeout: None,
+        }
+    }
+
+    setters! {
+        lease_id: &'a LeaseId => Some(lease_id),
+        client_request_id: ClientRequestId<'a> => Some(client_request_id),
+        timeout: Timeout => Some(timeout),
+    }
+
+    pub async fn execute(
+        &self,
+    ) -> Result<AbortCopyBlobResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let mut url = self.blob_client.url_with_segments(None)?;
+
+        url.query_pairs_mut().append_pair("comp", "copy");
+        url.query_pairs_mut()
+            .append_pair("copyid", &self.copy_id.to_string());
+        self.timeout.append_to_url_query(&mut url);
+
+        trace!("url == {:?}", url);
+
+        let (request, _url) = self.blob_client.prepare_request(
+            url.as_str(),
+            &http::Method::PUT,
+            &|mut request| {
+                request = request.header(COPY_ACTION, "abort");
+                request = add_optional_header_ref(&self.lease_id, request);
+                request = add_optional_header(&self.client_request_id, request);
+                request
+            },
+            None,
+        )?;
+
+        let response = self
+            .blob_client
+            .http_client()
+            .execute_storage_request_check_status(request, http::StatusCode::NO_CONTENT)
+            .await?;
+
+        debug!("response.headers() == {:#?}", response.headers());
+
+        Ok(AbortCopyBlobResponse::from_headers(response.headers())?)
+    }
+}
@@ -1,5 +1,6 @@
 use crate::blob::blob::responses::AcquireBlobLeaseResponse;
 use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
 use azure_core::headers::LEASE_ACTION;
 use azure_core::headers::{add_mandatory_header, add_optional_header, add_optional_header_ref};
 use azure_core::prelude::*;
@@ -60,7 +61,7 @@ impl<'a> AcquireLeaseBuilder<'a> {
         let response = self
             .blob_client
             .http_client()
-            .execute_request_check_status(request, http::StatusCode::CREATED)
+            .execute_storage_request_check_status(request, http::StatusCode::CREATED)
             .await?;
 
         Ok(AcquireBlobLeaseResponse::from_headers(response.headers())?)
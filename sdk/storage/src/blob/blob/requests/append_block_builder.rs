@@ -1,5 +1,6 @@
 use crate::blob::blob::responses::PutBlockResponse;
 use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
 use azure_core::headers::{add_optional_header, add_optional_header_ref};
 use azure_core::prelude::*;
 use bytes::Bytes;
@@ -14,6 +15,8 @@ pub struct AppendBlockBuilder<'a> {
     lease_id: Option<&'a LeaseId>,
     client_request_id: Option<ClientRequestId<'a>>,
     timeout: Option<Timeout>,
+    customer_provided_key: Option<&'a CustomerProvidedKey>,
+    encryption_scope: Option<&'a EncryptionScope>,
 }
 
 impl<'a> AppendBlockBuilder<'a> {
@@ -27,6 +30,8 @@ impl<'a> AppendBlockBuilder<'a> {
             lease_id: None,
             client_request_id: None,
             timeout: None,
+            customer_provided_key: None,
+            encryption_scope: None,
         }
     }
 
@@ -37,6 +42,8 @@ impl<'a> AppendBlockBuilder<'a> {
         lease_id: &'a LeaseId => Some(lease_id),
         client_request_id: ClientRequestId<'a> => Some(client_request_id),
         timeout: Timeout => Some(timeout),
+        customer_provided_key: &'a CustomerProvidedKey => Some(customer_provided_key),
+        encryption_scope: &'a EncryptionScope => Some(encryption_scope),
     }
 
     pub async fn execute(
@@ -58,6 +65,8 @@ impl<'a> AppendBlockBuilder<'a> {
                 request = add_optional_header(&self.condition_append_position, request);
                 request = add_optional_header_ref(&self.lease_id, request);
                 request = add_optional_header(&self.client_request_id, request);
+                request = add_optional_header_ref(&self.customer_provided_key, request);
+                request = add_optional_header_ref(&self.encryption_scope, request);
                 request
             },
             Some(self.body.clone()),
@@ -66,7 +75,7 @@ impl<'a> AppendBlockBuilder<'a> {
         let response = self
             .blob_client
             .http_client()
-            .execute_request_check_status(request, http::StatusCode::CREATED)
+            .execute_storage_request_check_status(request, http::StatusCode::CREATED)
             .await?;
 
         debug!("response.headers() == {:#?}", response.headers());
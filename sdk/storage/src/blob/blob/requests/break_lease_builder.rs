@@ -1,5 +1,6 @@
 use crate::blob::blob::responses::BreakBlobLeaseResponse;
 use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
 use azure_core::headers::LEASE_ACTION;
 use azure_core::headers::{add_optional_header, add_optional_header_ref};
 use azure_core::prelude::*;
@@ -57,7 +58,7 @@ impl<'a> BreakLeaseBuilder<'a> {
         let response = self
             .blob_client
             .http_client()
-            .execute_request_check_status(request, http::StatusCode::ACCEPTED)
+            .execute_storage_request_check_status(request, http::StatusCode::ACCEPTED)
             .await?;
 
         Ok(BreakBlobLeaseResponse::from_headers(response.headers())?)
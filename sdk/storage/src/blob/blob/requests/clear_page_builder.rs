@@ -1,5 +1,6 @@
 use crate::blob::blob::responses::ClearPageResponse;
 use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
 use azure_core::headers::{
     add_mandatory_header, add_optional_header, add_optional_header_ref, BLOB_TYPE, PAGE_WRITE,
 };
@@ -72,7 +73,7 @@ impl<'a> ClearPageBuilder<'a> {
         let response = self
             .blob_client
             .http_client()
-            .execute_request_check_status(request, http::StatusCode::CREATED)
+            .execute_storage_request_check_status(request, http::StatusCode::CREATED)
             .await?;
 
         debug!("response.headers() == {:#?}", response.headers());
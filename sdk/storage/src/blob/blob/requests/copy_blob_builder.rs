@@ -1,5 +1,6 @@
 use crate::blob::blob::responses::CopyBlobResponse;
 use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
 use crate::RehydratePriority;
 use azure_core::headers::COPY_SOURCE;
 use azure_core::headers::{add_mandatory_header, add_optional_header, add_optional_header_ref};
@@ -92,7 +93,7 @@ impl<'a> CopyBlobBuilder<'a> {
         let response = self
             .blob_client
             .http_client()
-            .execute_request_check_status(request, http::StatusCode::ACCEPTED)
+            .execute_storage_request_check_status(request, http::StatusCode::ACCEPTED)
             .await?;
 
         debug!("response.headers() == {:#?}", response.headers());
@@ -1,6 +1,7 @@
 use super::SourceContentMD5;
 use crate::blob::blob::responses::CopyBlobFromUrlResponse;
 use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
 use azure_core::headers::{
     add_optional_header, add_optional_header_ref, COPY_SOURCE, REQUIRES_SYNC,
 };
@@ -20,6 +21,8 @@ pub struct CopyBlobFromUrlBuilder<'a> {
     if_source_since_condition: Option<IfSourceModifiedSinceCondition>,
     if_source_match_condition: Option<IfSourceMatchCondition<'a>>,
     source_content_md5: Option<&'a SourceContentMD5>,
+    customer_provided_key: Option<&'a CustomerProvidedKey>,
+    encryption_scope: Option<&'a EncryptionScope>,
 }
 
 impl<'a> CopyBlobFromUrlBuilder<'a> {
@@ -37,6 +40,8 @@ impl<'a> CopyBlobFromUrlBuilder<'a> {
             if_source_since_condition: None,
             if_source_match_condition: None,
             source_content_md5: None,
+            customer_provided_key: None,
+            encryption_scope: None,
         }
     }
 
@@ -51,6 +56,8 @@ impl<'a> CopyBlobFromUrlBuilder<'a> {
         if_source_since_condition: IfSourceModifiedSinceCondition => Some(if_source_since_condition),
         if_source_match_condition: IfSourceMatchCondition<'a> => Some(if_source_match_condition),
         source_content_md5: &'a SourceContentMD5 => Some(source_content_md5),
+        customer_provided_key: &'a CustomerProvidedKey => Some(customer_provided_key),
+        encryption_scope: &'a EncryptionScope => Some(encryption_scope),
     }
 
     pub async fn execute(
@@ -76,6 +83,8 @@ impl<'a> CopyBlobFromUrlBuilder<'a> {
                 request = add_optional_header(&self.if_source_since_condition, request);
                 request = add_optional_header(&self.if_source_match_condition, request);
                 request = add_optional_header_ref(&self.source_content_md5, request);
+                request = add_optional_header_ref(&self.customer_provided_key, request);
+                request = add_optional_header_ref(&self.encryption_scope, request);
                 request
             },
             None,
@@ -84,7 +93,7 @@ impl<'a> CopyBlobFromUrlBuilder<'a> {
         let response = self
             .blob_client
             .http_client()
-            .execute_request_check_status(request, http::StatusCode::ACCEPTED)
+            .execute_storage_request_check_status(request, http::StatusCode::ACCEPTED)
             .await?;
 
         debug!("response.headers() == {:#?}", response.headers());
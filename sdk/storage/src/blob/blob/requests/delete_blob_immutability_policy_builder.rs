@@ -0,0 +1,68 @@
+use crate::blob::blob::responses::DeleteBlobImmutabilityPolicyResponse;
+use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
+use azure_core::headers::add_optional_header;
+use azure_core::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct DeleteBlobImmutabilityPolicyBuilder<'a> {
+    blob_client: &'a BlobClient,
+    blob_versioning: Option<&'a BlobVersioning>,
+    client_request_id: Option<ClientRequestId<'a>>,
+    timeout: Option<Timeout>,
+}
+
+impl<'a> DeleteBlobImmutabilityPolicyBuilder<'a> {
+    pub(crate) fn new(blob_client: &'a BlobClient) -> Self {
+        Self {
+            blob_client,
+            blob_versioning: None,
+            client_request_id: None,
+            timeout: None,
+        }
+    }
+
+    setters! {
+        blob_versioning: &'a BlobVersioning => Some(blob_versioning),
+        client_request_id: ClientRequestId<'a> => Some(client_request_id),
+        timeout: Timeout => Some(timeout),
+    }
+
+    /// Removes an unlocked immutability policy. Locked policies cannot be
+    /// deleted.
+    pub async fn execute(
+        &self,
+    ) -> Result<DeleteBlobImmutabilityPolicyResponse, Box<dyn std::error::Error + Send + Sync>>
+    {
+        let mut url = self.blob_client.url_with_segments(None)?;
+
+        url.query_pairs_mut()
+            .append_pair("comp", "immutabilityPolicies");
+        self.blob_versioning.append_to_url_query(&mut url);
+        self.timeout.append_to_url_query(&mut url);
+
+        trace!("url == {:?}", url);
+
+        let (request, _url) = self.blob_client.prepare_request(
+            url.as_str(),
+            &http::Method::DELETE,
+            &|mut request| {
+                request = add_optional_header(&self.client_request_id, request);
+                request
+            },
+            None,
+        )?;
+
+        let response = self
+            .blob_client
+            .http_client()
+            .execute_storage_request_check_status(request, http::StatusCode::OK)
+            .await?;
+
+        debug!("response.headers() == {:#?}", response.headers());
+
+        Ok(DeleteBlobImmutabilityPolicyResponse::from_headers(
+            response.headers(),
+        )?)
+    }
+}
@@ -1,5 +1,6 @@
 use crate::blob::blob::responses::DeleteBlobResponse;
 use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
 use azure_core::headers::{add_optional_header, add_optional_header_ref};
 use azure_core::prelude::*;
 
@@ -59,7 +60,7 @@ impl<'a> DeleteBlobSnapshotBuilder<'a> {
         let response = self
             .blob_client
             .http_client()
-            .execute_request_check_status(request, http::StatusCode::ACCEPTED)
+            .execute_storage_request_check_status(request, http::StatusCode::ACCEPTED)
             .await?;
 
         debug!("response.headers() == {:#?}", response.headers());
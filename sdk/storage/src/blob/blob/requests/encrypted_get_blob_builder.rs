@@ -0,0 +1,179 @@
+use crate::blob::blob::requests::GetBlobBuilder;
+use crate::blob::blob::responses::GetBlobResponse;
+use crate::blob::blob::Blob;
+use crate::blob::clients::EncryptedBlobClient;
+use crate::blob::prelude::*;
+use crate::core::{
+    decrypt_regions, encrypted_range, plaintext_length, unwrap_content_key, EncryptionData,
+    ENCRYPTION_DATA_METADATA,
+};
+use azure_core::prelude::*;
+
+#[derive(Debug, Clone, Copy)]
+pub struct EncryptedGetBlobBuilder<'a> {
+    encrypted_blob_client: &'a EncryptedBlobClient,
+    range: Option<Range>,
+    blob_versioning: Option<&'a BlobVersioning>,
+    client_request_id: Option<ClientRequestId<'a>>,
+    timeout: Option<Timeout>,
+    lease_id: Option<&'a LeaseId>,
+}
+
+impl<'a> EncryptedGetBlobBuilder<'a> {
+    pub(crate) fn new(encrypted_blob_client: &'a EncryptedBlobClient) -> Self {
+        Self {
+            encrypted_blob_client,
+            range: None,
+            blob_versioning: None,
+            client_request_id: None,
+            timeout: None,
+            lease_id: None,
+        }
+    }
+
+    setters! {
+        range: Range => Some(range),
+        blob_versioning: &'a BlobVersioning => Some(blob_versioning),
+        client_request_id: ClientRequestId<'a> => Some(client_request_id),
+        timeout: Timeout => Some(timeout),
+        lease_id: &'a LeaseId => Some(lease_id),
+    }
+
+    fn get_blob_builder(&self) -> GetBlobBuilder<'a> {
+        let mut builder = self.encrypted_blob_client.blob_client().get();
+        if let Some(blob_versioning) = self.blob_versioning {
+            builder = builder.blob_versioning(blob_versioning);
+        }
+        if let Some(client_request_id) = self.client_request_id {
+            builder = builder.client_request_id(client_request_id);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(lease_id) = self.lease_id {
+            builder = builder.lease_id(lease_id);
+        }
+        builder
+    }
+
+    /// Downloads and decrypts the blob. When a range is specified only the
+    /// encrypted regions covering it are downloaded, after reading the
+    /// encryption metadata with a `Get Blob Properties` call. The ranged
+    /// download fails if the blob changed in between.
+    pub async fn execute(
+        &self,
+    ) -> Result<GetBlobResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let key_encryption_key = self.encrypted_blob_client.key_encryption_key();
+
+        let range = match self.range {
+            Some(range) => range,
+            None => {
+                let mut response = self.get_blob_builder().execute().await?;
+
+                let encryption_data = encryption_data(&response.blob)?;
+                let content_key = unwrap_content_key(key_encryption_key, &encryption_data).await?;
+                let data = decrypt_regions(
+                    &content_key,
+                    &response.data,
+                    &encryption_data.encrypted_region_info,
+                )?;
+
+                response.blob.properties.content_length = data.len() as u64;
+                response.data = data.into();
+                return Ok(response);
+            }
+        };
+
+        let mut properties_builder = self.encrypted_blob_client.blob_client().get_properties();
+        if let Some(blob_versioning) = self.blob_versioning {
+            properties_builder = properties_builder.blob_versioning(blob_versioning);
+        }
+        if let Some(client_request_id) = self.client_request_id {
+            properties_builder = properties_builder.client_request_id(client_request_id);
+        }
+        if let Some(lease_id) = self.lease_id {
+            properties_builder = properties_builder.lease_id(lease_id);
+        }
+        let properties = properties_builder.execute().await?;
+
+        let encryption_data = encryption_data(&properties.blob)?;
+        let region_info = encryption_data.encrypted_region_info;
+
+        let encrypted_length = properties.blob.properties.content_length;
+        let total_length = plaintext_length(encrypted_length, &region_info);
+        let range = Range::new(range.start, range.end.min(total_length));
+        if range.start >= range.end {
+            return Err(crate::Error::ClientSideEncryptionError(format!(
+                "range {}..{} is outside of the {} bytes of the blob",
+                range.start, range.end, total_length
+            ))
+            .into());
+        }
+
+        let (encrypted_range, offset) = encrypted_range(range, &region_info);
+        let encrypted_range = Range::new(
+            encrypted_range.start,
+            encrypted_range.end.min(encrypted_length),
+        );
+
+        // the encryption metadata is only valid for the version of the blob it was read from
+        let mut response = self
+            .get_blob_builder()
+            .range(encrypted_range)
+            .if_match_condition(IfMatchCondition::Match(
+                properties.blob.properties.etag.as_ref(),
+            ))
+            .execute()
+            .await
+            .map_err(changed_blob_error)?;
+
+        let content_key = unwrap_content_key(key_encryption_key, &encryption_data).await?;
+        let data = decrypt_regions(&content_key, &response.data, &region_info)?;
+        let data = data
+            .get(offset as usize..(offset + range.len()) as usize)
+            .ok_or_else(|| {
+                crate::Error::ClientSideEncryptionError(
+                    "the downloaded regions are shorter than expected".to_owned(),
+                )
+            })?
+            .to_vec();
+
+        response.blob.properties.content_length = data.len() as u64;
+        response.content_range = Some(ContentRange::new(range.start, range.end - 1, total_length));
+        response.data = data.into();
+        Ok(response)
+    }
+}
+
+fn changed_blob_error(
+    error: Box<dyn std::error::Error + Send + Sync>,
+) -> Box<dyn std::error::Error + Send + Sync> {
+    match error.downcast_ref::<crate::Error>() {
+        Some(crate::Error::StorageError(storage_error))
+            if storage_error.status == http::StatusCode::PRECONDITION_FAILED =>
+        {
+            crate::Error::ClientSideEncryptionError(
+                "the blob changed after its encryption metadata was read, retry the download"
+                    .to_owned(),
+            )
+            .into()
+        }
+        _ => error,
+    }
+}
+
+fn encryption_data(blob: &Blob) -> Result<EncryptionData, crate::Error> {
+    let encryption_data = blob
+        .properties
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get(ENCRYPTION_DATA_METADATA))
+        .ok_or_else(|| {
+            crate::Error::ClientSideEncryptionError(format!(
+                "blob {} has no {} metadata",
+                blob.name, ENCRYPTION_DATA_METADATA
+            ))
+        })?;
+
+    Ok(serde_json::from_str(encryption_data)?)
+}
@@ -0,0 +1,100 @@
+use crate::blob::blob::responses::PutBlockBlobResponse;
+use crate::blob::clients::EncryptedBlobClient;
+use crate::blob::prelude::*;
+use crate::core::ENCRYPTION_DATA_METADATA;
+use azure_core::prelude::*;
+use bytes::Bytes;
+
+#[derive(Debug, Clone)]
+pub struct EncryptedPutBlockBlobBuilder<'a> {
+    encrypted_blob_client: &'a EncryptedBlobClient,
+    body: Bytes,
+    content_type: Option<ContentType<'a>>,
+    content_encoding: Option<ContentEncoding<'a>>,
+    content_language: Option<ContentLanguage<'a>>,
+    content_disposition: Option<ContentDisposition<'a>>,
+    metadata: Option<&'a Metadata>,
+    access_tier: Option<AccessTier>,
+    lease_id: Option<&'a LeaseId>,
+    client_request_id: Option<ClientRequestId<'a>>,
+    timeout: Option<Timeout>,
+}
+
+impl<'a> EncryptedPutBlockBlobBuilder<'a> {
+    pub(crate) fn new(encrypted_blob_client: &'a EncryptedBlobClient, body: Bytes) -> Self {
+        Self {
+            encrypted_blob_client,
+            body,
+            content_type: None,
+            content_encoding: None,
+            content_language: None,
+            content_disposition: None,
+            metadata: None,
+            access_tier: None,
+            lease_id: None,
+            client_request_id: None,
+            timeout: None,
+        }
+    }
+
+    setters! {
+        content_type: ContentType<'a> => Some(content_type),
+        content_encoding: ContentEncoding<'a> => Some(content_encoding),
+        content_language: ContentLanguage<'a> => Some(content_language),
+        content_disposition: ContentDisposition<'a> => Some(content_disposition),
+        metadata: &'a Metadata => Some(metadata),
+        access_tier: AccessTier => Some(access_tier),
+        lease_id: &'a LeaseId => Some(lease_id),
+        client_request_id: ClientRequestId<'a> => Some(client_request_id),
+        timeout: Timeout => Some(timeout),
+    }
+
+    pub async fn execute(
+        &self,
+    ) -> Result<PutBlockBlobResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let (encrypted, encryption_data) = crate::core::encrypt(
+            self.encrypted_blob_client.key_encryption_key(),
+            &self.body,
+            Some("FullBlob"),
+        )
+        .await?;
+
+        let mut metadata = self.metadata.cloned().unwrap_or_default();
+        metadata.insert(
+            ENCRYPTION_DATA_METADATA,
+            serde_json::to_string(&encryption_data)?,
+        );
+
+        let mut builder = self
+            .encrypted_blob_client
+            .blob_client()
+            .put_block_blob(encrypted)
+            .metadata(&metadata);
+        if let Some(content_type) = self.content_type {
+            builder = builder.content_type(content_type);
+        }
+        if let Some(content_encoding) = self.content_encoding {
+            builder = builder.content_encoding(content_encoding);
+        }
+        if let Some(content_language) = self.content_language {
+            builder = builder.content_language(content_language);
+        }
+        if let Some(content_disposition) = self.content_disposition {
+            builder = builder.content_disposition(content_disposition);
+        }
+        if let Some(access_tier) = self.access_tier {
+            builder = builder.access_tier(access_tier);
+        }
+        if let Some(lease_id) = self.lease_id {
+            builder = builder.lease_id(lease_id);
+        }
+        if let Some(client_request_id) = self.client_request_id {
+            builder = builder.client_request_id(client_request_id);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        builder.execute().await
+    }
+}
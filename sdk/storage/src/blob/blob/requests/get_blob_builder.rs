@@ -1,5 +1,6 @@
 use crate::blob::blob::responses::GetBlobResponse;
 use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
 use azure_core::headers::{add_optional_header, add_optional_header_ref};
 use azure_core::prelude::*;
 use futures::stream::Stream;
@@ -13,6 +14,8 @@ pub struct GetBlobBuilder<'a> {
     client_request_id: Option<ClientRequestId<'a>>,
     timeout: Option<Timeout>,
     lease_id: Option<&'a LeaseId>,
+    if_match_condition: Option<IfMatchCondition<'a>>,
+    customer_provided_key: Option<&'a CustomerProvidedKey>,
 }
 
 impl<'a> GetBlobBuilder<'a> {
@@ -24,6 +27,8 @@ impl<'a> GetBlobBuilder<'a> {
             range: None,
             lease_id: None,
             client_request_id: None,
+            if_match_condition: None,
+            customer_provided_key: None,
         }
     }
 
@@ -33,6 +38,8 @@ impl<'a> GetBlobBuilder<'a> {
         client_request_id: ClientRequestId<'a> => Some(client_request_id),
         timeout: Timeout => Some(timeout),
         lease_id: &'a LeaseId => Some(lease_id),
+        if_match_condition: IfMatchCondition<'a> => Some(if_match_condition),
+        customer_provided_key: &'a CustomerProvidedKey => Some(customer_provided_key),
     }
 
     pub async fn execute(
@@ -52,6 +59,8 @@ impl<'a> GetBlobBuilder<'a> {
                 request = add_optional_header(&self.range, request);
                 request = add_optional_header(&self.client_request_id, request);
                 request = add_optional_header_ref(&self.lease_id, request);
+                request = add_optional_header(&self.if_match_condition, request);
+                request = add_optional_header_ref(&self.customer_provided_key, request);
                 request
             },
             None,
@@ -68,7 +77,7 @@ impl<'a> GetBlobBuilder<'a> {
         let response = self
             .blob_client
             .http_client()
-            .execute_request_check_status(request, expected_status_code)
+            .execute_storage_request_check_status(request, expected_status_code)
             .await?;
 
         Ok((self.blob_client.blob_name(), response).try_into()?)
@@ -1,5 +1,6 @@
 use crate::blob::blob::responses::GetBlobMetadataResponse;
 use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
 use azure_core::headers::{add_optional_header, add_optional_header_ref};
 use azure_core::prelude::*;
 use std::convert::TryInto;
@@ -11,6 +12,7 @@ pub struct GetBlobMetadataBuilder<'a> {
     lease_id: Option<&'a LeaseId>,
     client_request_id: Option<ClientRequestId<'a>>,
     timeout: Option<Timeout>,
+    customer_provided_key: Option<&'a CustomerProvidedKey>,
 }
 
 impl<'a> GetBlobMetadataBuilder<'a> {
@@ -21,6 +23,7 @@ impl<'a> GetBlobMetadataBuilder<'a> {
             lease_id: None,
             client_request_id: None,
             timeout: None,
+            customer_provided_key: None,
         }
     }
 
@@ -29,6 +32,7 @@ impl<'a> GetBlobMetadataBuilder<'a> {
         lease_id: &'a LeaseId => Some(lease_id),
         timeout: Timeout => Some(timeout),
         client_request_id: ClientRequestId<'a> => Some(client_request_id),
+        customer_provided_key: &'a CustomerProvidedKey => Some(customer_provided_key),
     }
 
     pub async fn execute(
@@ -48,6 +52,7 @@ impl<'a> GetBlobMetadataBuilder<'a> {
             &|mut request| {
                 request = add_optional_header(&self.client_request_id, request);
                 request = add_optional_header_ref(&self.lease_id, request);
+                request = add_optional_header_ref(&self.customer_provided_key, request);
                 request
             },
             None,
@@ -56,7 +61,7 @@ impl<'a> GetBlobMetadataBuilder<'a> {
         let response = self
             .blob_client
             .http_client()
-            .execute_request_check_status(request, http::StatusCode::OK)
+            .execute_storage_request_check_status(request, http::StatusCode::OK)
             .await?;
 
         Ok(response.headers().try_into()?)
@@ -1,5 +1,6 @@
 use crate::blob::blob::responses::GetBlobPropertiesResponse;
 use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
 use azure_core::headers::{add_optional_header, add_optional_header_ref};
 use azure_core::prelude::*;
 
@@ -10,6 +11,7 @@ pub struct GetBlobPropertiesBuilder<'a> {
     timeout: Option<Timeout>,
     lease_id: Option<&'a LeaseId>,
     client_request_id: Option<ClientRequestId<'a>>,
+    customer_provided_key: Option<&'a CustomerProvidedKey>,
 }
 
 impl<'a> GetBlobPropertiesBuilder<'a> {
@@ -20,6 +22,7 @@ impl<'a> GetBlobPropertiesBuilder<'a> {
             timeout: None,
             lease_id: None,
             client_request_id: None,
+            customer_provided_key: None,
         }
     }
 
@@ -28,6 +31,7 @@ impl<'a> GetBlobPropertiesBuilder<'a> {
         timeout: Timeout => Some(timeout),
         lease_id: &'a LeaseId => Some(lease_id),
         client_request_id: ClientRequestId<'a> => Some(client_request_id),
+        customer_provided_key: &'a CustomerProvidedKey => Some(customer_provided_key),
     }
 
     pub async fn execute(
@@ -46,6 +50,7 @@ impl<'a> GetBlobPropertiesBuilder<'a> {
             &|mut request| {
                 request = add_optional_header_ref(&self.lease_id, request);
                 request = add_optional_header(&self.client_request_id, request);
+                request = add_optional_header_ref(&self.customer_provided_key, request);
                 request
             },
             None,
@@ -54,7 +59,7 @@ impl<'a> GetBlobPropertiesBuilder<'a> {
         let response = self
             .blob_client
             .http_client()
-            .execute_request_check_status(request, http::StatusCode::OK)
+            .execute_storage_request_check_status(request, http::StatusCode::OK)
             .await?;
 
         debug!("response.headers() == {:#?}", response.headers());
@@ -1,6 +1,7 @@
 use crate::blob::blob::responses::GetBlockListResponse;
 use crate::blob::blob::BlockListType;
 use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
 use azure_core::headers::{add_optional_header, add_optional_header_ref};
 use azure_core::prelude::*;
 
@@ -59,7 +60,7 @@ impl<'a> GetBlockListBuilder<'a> {
         let response = self
             .blob_client
             .http_client()
-            .execute_request_check_status(request, http::StatusCode::OK)
+            .execute_storage_request_check_status(request, http::StatusCode::OK)
             .await?;
 
         debug!("response.headers() == {:#?}", response.headers());
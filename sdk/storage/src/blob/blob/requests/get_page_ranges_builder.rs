@@ -0,0 +1,91 @@
+use crate::blob::blob::responses::GetPageRangesResponse;
+use crate::blob::headers::PREVIOUS_SNAPSHOT_URL;
+use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
+use azure_core::headers::{add_optional_header, add_optional_header_ref};
+use azure_core::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct GetPageRangesBuilder<'a> {
+    blob_client: &'a BlobClient,
+    blob_versioning: Option<&'a BlobVersioning>,
+    previous_snapshot: Option<&'a Snapshot>,
+    previous_snapshot_url: Option<&'a str>,
+    range: Option<BA512Range>,
+    lease_id: Option<&'a LeaseId>,
+    client_request_id: Option<ClientRequestId<'a>>,
+    timeout: Option<Timeout>,
+}
+
+impl<'a> GetPageRangesBuilder<'a> {
+    pub(crate) fn new(blob_client: &'a BlobClient) -> Self {
+        Self {
+            blob_client,
+            blob_versioning: None,
+            previous_snapshot: None,
+            previous_snapshot_url: None,
+            range: None,
+            lease_id: None,
+            client_request_id: None,
+            timeout: None,
+        }
+    }
+
+    setters! {
+        blob_versioning: &'a BlobVersioning => Some(blob_versioning),
+        previous_snapshot: &'a Snapshot => Some(previous_snapshot),
+        previous_snapshot_url: &'a str => Some(previous_snapshot_url),
+        range: BA512Range => Some(range),
+        lease_id: &'a LeaseId => Some(lease_id),
+        client_request_id: ClientRequestId<'a> => Some(client_request_id),
+        timeout: Timeout => Some(timeout),
+    }
+
+    /// Lists the valid page ranges. When `previous_snapshot` (or
+    /// `previous_snapshot_url` for managed disks) is specified, only the
+    /// pages changed since that snapshot are returned, along with the
+    /// cleared ones.
+    pub async fn execute(
+        &self,
+    ) -> Result<GetPageRangesResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let mut url = self.blob_client.url_with_segments(None)?;
+
+        url.query_pairs_mut().append_pair("comp", "pagelist");
+        self.blob_versioning.append_to_url_query(&mut url);
+        if let Some(previous_snapshot) = self.previous_snapshot {
+            url.query_pairs_mut()
+                .append_pair("prevsnapshot", previous_snapshot.as_str());
+        }
+        self.timeout.append_to_url_query(&mut url);
+
+        trace!("url == {:?}", url);
+
+        let (request, _url) = self.blob_client.prepare_request(
+            url.as_str(),
+            &http::Method::GET,
+            &|mut request| {
+                if let Some(previous_snapshot_url) = self.previous_snapshot_url {
+                    request = request.header(PREVIOUS_SNAPSHOT_URL, previous_snapshot_url);
+                }
+                request = add_optional_header(&self.range, request);
+                request = add_optional_header_ref(&self.lease_id, request);
+                request = add_optional_header(&self.client_request_id, request);
+                request
+            },
+            None,
+        )?;
+
+        let response = self
+            .blob_client
+            .http_client()
+            .execute_storage_request_check_status(request, http::StatusCode::OK)
+            .await?;
+
+        debug!("response.headers() == {:#?}", response.headers());
+
+        Ok(GetPageRangesResponse::from_response(
+            response.headers(),
+            response.body(),
+        )?)
+    }
+}
@@ -0,0 +1,74 @@
+use crate::blob::blob::responses::IncrementalCopyBlobResponse;
+use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
+use azure_core::headers::add_optional_header;
+use azure_core::headers::COPY_SOURCE;
+use azure_core::prelude::*;
+use std::convert::TryInto;
+
+#[derive(Debug, Clone)]
+pub struct IncrementalCopyBlobBuilder<'a> {
+    blob_client: &'a BlobClient,
+    source_url: &'a str,
+    if_modified_since_condition: Option<IfModifiedSinceCondition>,
+    if_match_condition: Option<IfMatchCondition<'a>>,
+    client_request_id: Option<ClientRequestId<'a>>,
+    timeout: Option<Timeout>,
+}
+
+impl<'a> IncrementalCopyBlobBuilder<'a> {
+    pub(crate) fn new(blob_client: &'a BlobClient, source_url: &'a str) -> Self {
+        Self {
+            blob_client,
+            source_url,
+            if_modified_since_condition: None,
+            if_match_condition: None,
+            client_request_id: None,
+            timeout: None,
+        }
+    }
+
+    setters! {
+        if_modified_since_condition: IfModifiedSinceCondition => Some(if_modified_since_condition),
+        if_match_condition: IfMatchCondition<'a> => Some(if_match_condition),
+        client_request_id: ClientRequestId<'a> => Some(client_request_id),
+        timeout: Timeout => Some(timeout),
+    }
+
+    /// Starts copying the differences between the source page blob
+    /// snapshot and the previously copied one. The source url must
+    /// reference a snapshot and be public or authorized by a SAS token.
+    pub async fn execute(
+        &self,
+    ) -> Result<IncrementalCopyBlobResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let mut url = self.blob_client.url_with_segments(None)?;
+
+        url.query_pairs_mut().append_pair("comp", "incrementalcopy");
+        self.timeout.append_to_url_query(&mut url);
+
+        trace!("url == {:?}", url);
+
+        let (request, _url) = self.blob_client.prepare_request(
+            url.as_str(),
+            &http::Method::PUT,
+            &|mut request| {
+                request = request.header(COPY_SOURCE, self.source_url);
+                request = add_optional_header(&self.if_modified_since_condition, request);
+                request = add_optional_header(&self.if_match_condition, request);
+                request = add_optional_header(&self.client_request_id, request);
+                request
+            },
+            None,
+        )?;
+
+        let response = self
+            .blob_client
+            .http_client()
+            .execute_storage_request_check_status(request, http::StatusCode::ACCEPTED)
+            .await?;
+
+        debug!("response.headers() == {:#?}", response.headers());
+
+        Ok(response.headers().try_into()?)
+    }
+}
@@ -1,3 +1,4 @@
+mod abort_copy_blob_builder;
 mod acquire_lease_builder;
 mod append_block_builder;
 mod break_lease_builder;
@@ -6,40 +7,73 @@ mod clear_page_builder;
 mod copy_blob_builder;
 mod copy_blob_from_url_builder;
 mod delete_blob_builder;
+mod delete_blob_immutability_policy_builder;
 mod delete_blob_snapshot_builder;
 mod delete_blob_version_builder;
+#[cfg(feature = "client_side_encryption")]
+mod encrypted_get_blob_builder;
+#[cfg(feature = "client_side_encryption")]
+mod encrypted_put_block_blob_builder;
 mod get_blob_builder;
 mod get_blob_metadata_builder;
 mod get_blob_properties_builder;
 mod get_block_list_builder;
+mod get_page_ranges_builder;
+mod incremental_copy_blob_builder;
 mod put_append_blob_builder;
 mod put_block_blob_builder;
 mod put_block_builder;
 mod put_block_list_builder;
 mod put_page_blob_builder;
+mod query_blob_builder;
 mod release_lease_builder;
 mod renew_lease_builder;
+mod seal_blob_builder;
+mod set_blob_immutability_policy_builder;
+mod set_blob_legal_hold_builder;
+mod set_blob_metadata_builder;
+mod set_blob_properties_builder;
+mod set_blob_tier_builder;
+mod snapshot_blob_builder;
 mod source_content_md5;
+mod undelete_blob_builder;
 mod update_page_builder;
+pub use self::abort_copy_blob_builder::AbortCopyBlobBuilder;
 pub use self::acquire_lease_builder::AcquireLeaseBuilder;
 pub use self::append_block_builder::AppendBlockBuilder;
 pub use self::break_lease_builder::BreakLeaseBuilder;
 pub use self::change_lease_builder::ChangeLeaseBuilder;
 pub use self::clear_page_builder::ClearPageBuilder;
 pub use self::delete_blob_builder::DeleteBlobBuilder;
+pub use self::delete_blob_immutability_policy_builder::DeleteBlobImmutabilityPolicyBuilder;
 pub use self::delete_blob_snapshot_builder::DeleteBlobSnapshotBuilder;
 pub use self::delete_blob_version_builder::DeleteBlobVersionBuilder;
+#[cfg(feature = "client_side_encryption")]
+pub use self::encrypted_get_blob_builder::EncryptedGetBlobBuilder;
+#[cfg(feature = "client_side_encryption")]
+pub use self::encrypted_put_block_blob_builder::EncryptedPutBlockBlobBuilder;
 pub use self::get_blob_builder::GetBlobBuilder;
 pub use self::get_blob_metadata_builder::GetBlobMetadataBuilder;
 pub use self::get_blob_properties_builder::GetBlobPropertiesBuilder;
 pub use self::get_block_list_builder::GetBlockListBuilder;
+pub use self::get_page_ranges_builder::GetPageRangesBuilder;
+pub use self::incremental_copy_blob_builder::IncrementalCopyBlobBuilder;
 pub use self::put_append_blob_builder::PutAppendBlobBuilder;
 pub use self::put_block_blob_builder::PutBlockBlobBuilder;
 pub use self::put_block_builder::PutBlockBuilder;
 pub use self::put_block_list_builder::PutBlockListBuilder;
 pub use self::put_page_blob_builder::PutPageBlobBuilder;
+pub use self::query_blob_builder::QueryBlobBuilder;
 pub use self::release_lease_builder::ReleaseLeaseBuilder;
 pub use self::renew_lease_builder::RenewLeaseBuilder;
+pub use self::seal_blob_builder::SealBlobBuilder;
+pub use self::set_blob_immutability_policy_builder::SetBlobImmutabilityPolicyBuilder;
+pub use self::set_blob_legal_hold_builder::SetBlobLegalHoldBuilder;
+pub use self::set_blob_metadata_builder::SetBlobMetadataBuilder;
+pub use self::set_blob_properties_builder::SetBlobPropertiesBuilder;
+pub use self::set_blob_tier_builder::SetBlobTierBuilder;
+pub use self::snapshot_blob_builder::SnapshotBlobBuilder;
+pub use self::undelete_blob_builder::UndeleteBlobBuilder;
 pub use self::update_page_builder::UpdatePageBuilder;
 pub use copy_blob_builder::CopyBlobBuilder;
 pub use copy_blob_from_url_builder::CopyBlobFromUrlBuilder;
@@ -1,5 +1,6 @@
 use crate::blob::blob::responses::PutBlobResponse;
 use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
 use azure_core::headers::{add_optional_header, add_optional_header_ref, BLOB_TYPE};
 use azure_core::prelude::*;
 
@@ -15,6 +16,8 @@ pub struct PutAppendBlobBuilder<'a> {
     lease_id: Option<&'a LeaseId>,
     client_request_id: Option<ClientRequestId<'a>>,
     timeout: Option<Timeout>,
+    customer_provided_key: Option<&'a CustomerProvidedKey>,
+    encryption_scope: Option<&'a EncryptionScope>,
 }
 
 impl<'a> PutAppendBlobBuilder<'a> {
@@ -29,6 +32,8 @@ impl<'a> PutAppendBlobBuilder<'a> {
             lease_id: None,
             client_request_id: None,
             timeout: None,
+            customer_provided_key: None,
+            encryption_scope: None,
         }
     }
 
@@ -41,6 +46,8 @@ impl<'a> PutAppendBlobBuilder<'a> {
         lease_id: &'a LeaseId => Some(lease_id),
         client_request_id: ClientRequestId<'a> => Some(client_request_id),
         timeout: Timeout => Some(timeout),
+        customer_provided_key: &'a CustomerProvidedKey => Some(customer_provided_key),
+        encryption_scope: &'a EncryptionScope => Some(encryption_scope),
     }
 
     pub async fn execute(
@@ -64,6 +71,8 @@ impl<'a> PutAppendBlobBuilder<'a> {
                 request = add_optional_header(&self.metadata, request);
                 request = add_optional_header_ref(&self.lease_id, request);
                 request = add_optional_header(&self.client_request_id, request);
+                request = add_optional_header_ref(&self.customer_provided_key, request);
+                request = add_optional_header_ref(&self.encryption_scope, request);
                 request
             },
             None,
@@ -72,7 +81,7 @@ impl<'a> PutAppendBlobBuilder<'a> {
         let response = self
             .blob_client
             .http_client()
-            .execute_request_check_status(request, http::StatusCode::CREATED)
+            .execute_storage_request_check_status(request, http::StatusCode::CREATED)
             .await?;
 
         debug!("response.headers() == {:#?}", response.headers());
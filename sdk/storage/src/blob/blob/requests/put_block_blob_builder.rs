@@ -1,5 +1,6 @@
 use crate::blob::blob::responses::PutBlockBlobResponse;
 use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
 use azure_core::headers::BLOB_TYPE;
 use azure_core::headers::{add_optional_header, add_optional_header_ref};
 use azure_core::prelude::*;
@@ -18,8 +19,11 @@ pub struct PutBlockBlobBuilder<'a> {
     access_tier: Option<AccessTier>,
     // TODO: Support tags
     lease_id: Option<&'a LeaseId>,
+    if_match_condition: Option<IfMatchCondition<'a>>,
     client_request_id: Option<ClientRequestId<'a>>,
     timeout: Option<Timeout>,
+    customer_provided_key: Option<&'a CustomerProvidedKey>,
+    encryption_scope: Option<&'a EncryptionScope>,
 }
 
 impl<'a> PutBlockBlobBuilder<'a> {
@@ -35,8 +39,11 @@ impl<'a> PutBlockBlobBuilder<'a> {
             metadata: None,
             access_tier: None,
             lease_id: None,
+            if_match_condition: None,
             client_request_id: None,
             timeout: None,
+            customer_provided_key: None,
+            encryption_scope: None,
         }
     }
 
@@ -49,8 +56,11 @@ impl<'a> PutBlockBlobBuilder<'a> {
         metadata: &'a Metadata => Some(metadata),
         access_tier: AccessTier => Some(access_tier),
         lease_id: &'a LeaseId => Some(lease_id),
+        if_match_condition: IfMatchCondition<'a> => Some(if_match_condition),
         client_request_id: ClientRequestId<'a> => Some(client_request_id),
         timeout: Timeout => Some(timeout),
+        customer_provided_key: &'a CustomerProvidedKey => Some(customer_provided_key),
+        encryption_scope: &'a EncryptionScope => Some(encryption_scope),
     }
 
     pub async fn execute(
@@ -75,7 +85,10 @@ impl<'a> PutBlockBlobBuilder<'a> {
                 request = add_optional_header(&self.metadata, request);
                 request = add_optional_header(&self.access_tier, request);
                 request = add_optional_header_ref(&self.lease_id, request);
+                request = add_optional_header(&self.if_match_condition, request);
                 request = add_optional_header(&self.client_request_id, request);
+                request = add_optional_header_ref(&self.customer_provided_key, request);
+                request = add_optional_header_ref(&self.encryption_scope, request);
                 request
             },
             Some(self.body.clone()),
@@ -84,7 +97,7 @@ impl<'a> PutBlockBlobBuilder<'a> {
         let response = self
             .blob_client
             .http_client()
-            .execute_request_check_status(request, http::StatusCode::CREATED)
+            .execute_storage_request_check_status(request, http::StatusCode::CREATED)
             .await?;
 
         debug!("response.headers() == {:#?}", response.headers());
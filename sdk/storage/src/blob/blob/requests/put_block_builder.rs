@@ -1,5 +1,6 @@
 use crate::blob::blob::responses::PutBlockResponse;
 use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
 use azure_core::headers::{add_optional_header, add_optional_header_ref};
 use azure_core::prelude::*;
 use bytes::Bytes;
@@ -13,6 +14,8 @@ pub struct PutBlockBuilder<'a> {
     client_request_id: Option<ClientRequestId<'a>>,
     timeout: Option<Timeout>,
     lease_id: Option<&'a LeaseId>,
+    customer_provided_key: Option<&'a CustomerProvidedKey>,
+    encryption_scope: Option<&'a EncryptionScope>,
 }
 
 impl<'a> PutBlockBuilder<'a> {
@@ -29,6 +32,8 @@ impl<'a> PutBlockBuilder<'a> {
             client_request_id: None,
             timeout: None,
             lease_id: None,
+            customer_provided_key: None,
+            encryption_scope: None,
         }
     }
 
@@ -37,6 +42,8 @@ impl<'a> PutBlockBuilder<'a> {
         client_request_id: ClientRequestId<'a> => Some(client_request_id),
         timeout: Timeout => Some(timeout),
         lease_id: &'a LeaseId => Some(lease_id),
+        customer_provided_key: &'a CustomerProvidedKey => Some(customer_provided_key),
+        encryption_scope: &'a EncryptionScope => Some(encryption_scope),
     }
 
     pub async fn execute(
@@ -54,6 +61,8 @@ impl<'a> PutBlockBuilder<'a> {
             &|mut request| {
                 request = add_optional_header(&self.client_request_id, request);
                 request = add_optional_header_ref(&self.lease_id, request);
+                request = add_optional_header_ref(&self.customer_provided_key, request);
+                request = add_optional_header_ref(&self.encryption_scope, request);
                 request
             },
             Some(self.body.clone()),
@@ -64,7 +73,7 @@ impl<'a> PutBlockBuilder<'a> {
         let response = self
             .blob_client
             .http_client()
-            .execute_request_check_status(request, http::StatusCode::CREATED)
+            .execute_storage_request_check_status(request, http::StatusCode::CREATED)
             .await?;
 
         debug!("response.headers() == {:#?}", response.headers());
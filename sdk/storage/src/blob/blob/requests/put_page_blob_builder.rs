@@ -1,5 +1,6 @@
 use crate::blob::blob::responses::PutBlobResponse;
 use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
 use azure_core::headers::{
     add_optional_header, add_optional_header_ref, BLOB_CONTENT_LENGTH, BLOB_TYPE,
 };
@@ -19,6 +20,8 @@ pub struct PutPageBlobBuilder<'a> {
     sequence_number: Option<SequenceNumber>,
     client_request_id: Option<ClientRequestId<'a>>,
     timeout: Option<Timeout>,
+    customer_provided_key: Option<&'a CustomerProvidedKey>,
+    encryption_scope: Option<&'a EncryptionScope>,
 }
 
 impl<'a> PutPageBlobBuilder<'a> {
@@ -35,6 +38,8 @@ impl<'a> PutPageBlobBuilder<'a> {
             sequence_number: None,
             client_request_id: None,
             timeout: None,
+            customer_provided_key: None,
+            encryption_scope: None,
         }
     }
 
@@ -48,6 +53,8 @@ impl<'a> PutPageBlobBuilder<'a> {
         sequence_number: SequenceNumber => Some(sequence_number),
         client_request_id: ClientRequestId<'a> => Some(client_request_id),
         timeout: Timeout => Some(timeout),
+        customer_provided_key: &'a CustomerProvidedKey => Some(customer_provided_key),
+        encryption_scope: &'a EncryptionScope => Some(encryption_scope),
     }
 
     pub async fn execute(
@@ -73,6 +80,8 @@ impl<'a> PutPageBlobBuilder<'a> {
                 request = add_optional_header_ref(&self.lease_id, request);
                 request = add_optional_header(&self.sequence_number, request);
                 request = add_optional_header(&self.client_request_id, request);
+                request = add_optional_header_ref(&self.customer_provided_key, request);
+                request = add_optional_header_ref(&self.encryption_scope, request);
                 request
             },
             None,
@@ -81,7 +90,7 @@ impl<'a> PutPageBlobBuilder<'a> {
         let response = self
             .blob_client
             .http_client()
-            .execute_request_check_status(request, http::StatusCode::CREATED)
+            .execute_storage_request_check_status(request, http::StatusCode::CREATED)
             .await?;
 
         debug!("response.headers() == {:#?}", response.headers());
@@ -0,0 +1,184 @@
+use crate::blob::blob::responses::{query_events, QueryBlobResponse, QueryEvent};
+use crate::blob::blob::QuerySerialization;
+use crate::blob::prelude::*;
+use crate::core::StorageError;
+use crate::xml::xml_escape;
+use azure_core::headers::{add_optional_header, add_optional_header_ref};
+use azure_core::prelude::*;
+use azure_core::{collect_pinned_stream, Response};
+use futures::stream::{Stream, StreamExt, TryStreamExt};
+
+#[derive(Debug, Clone)]
+pub struct QueryBlobBuilder<'a> {
+    blob_client: &'a BlobClient,
+    expression: &'a str,
+    input_serialization: Option<QuerySerialization>,
+    output_serialization: Option<QuerySerialization>,
+    snapshot: Option<Snapshot>,
+    timeout: Option<Timeout>,
+    lease_id: Option<&'a LeaseId>,
+    client_request_id: Option<ClientRequestId<'a>>,
+    customer_provided_key: Option<&'a CustomerProvidedKey>,
+}
+
+impl<'a> QueryBlobBuilder<'a> {
+    pub(crate) fn new(blob_client: &'a BlobClient, expression: &'a str) -> Self {
+        Self {
+            blob_client,
+            expression,
+            input_serialization: None,
+            output_serialization: None,
+            snapshot: None,
+            timeout: None,
+            lease_id: None,
+            client_request_id: None,
+            customer_provided_key: None,
+        }
+    }
+
+    setters! {
+        input_serialization: QuerySerialization => Some(input_serialization),
+        output_serialization: QuerySerialization => Some(output_serialization),
+        snapshot: Snapshot => Some(snapshot),
+        timeout: Timeout => Some(timeout),
+        lease_id: &'a LeaseId => Some(lease_id),
+        client_request_id: ClientRequestId<'a> => Some(client_request_id),
+        customer_provided_key: &'a CustomerProvidedKey => Some(customer_provided_key),
+    }
+
+    fn to_xml(&self) -> String {
+        let mut s = String::new();
+        s.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<QueryRequest>");
+        s.push_str("<QueryType>SQL</QueryType>");
+        s.push_str(&format!(
+            "<Expression>{}</Expression>",
+            xml_escape(self.expression)
+        ));
+        if let Some(input_serialization) = &self.input_serialization {
+            s.push_str(&format!(
+                "<InputSerialization>{}</InputSerialization>",
+                input_serialization.to_xml()
+            ));
+        }
+        if let Some(output_serialization) = &self.output_serialization {
+            s.push_str(&format!(
+                "<OutputSerialization>{}</OutputSerialization>",
+                output_serialization.to_xml()
+            ));
+        }
+        s.push_str("</QueryRequest>");
+        s
+    }
+
+    /// Sends the query, returning the response once its status is checked, before its body is
+    /// received.
+    async fn send(&self) -> Result<Response, Box<dyn std::error::Error + Send + Sync>> {
+        let mut url = self.blob_client.url_with_segments(None)?;
+
+        url.query_pairs_mut().append_pair("comp", "query");
+        if let Some(snapshot) = &self.snapshot {
+            snapshot.append_to_url_query(&mut url);
+        }
+        self.timeout.append_to_url_query(&mut url);
+
+        trace!("url == {:?}", url);
+
+        let body = self.to_xml();
+        debug!("xml about to be sent == {}", body);
+
+        let (request, _url) = self.blob_client.prepare_request(
+            url.as_str(),
+            &http::Method::POST,
+            &|mut request| {
+                request = request.header(http::header::CONTENT_TYPE, "application/xml");
+                request = add_optional_header_ref(&self.lease_id, request);
+                request = add_optional_header(&self.client_request_id, request);
+                request = add_optional_header_ref(&self.customer_provided_key, request);
+                request
+            },
+            Some(body.into()),
+        )?;
+
+        let response = self
+            .blob_client
+            .http_client()
+            .execute_request2(&request.into())
+            .await
+            .map_err(azure_core::Error::from)?;
+
+        if response.status() != http::StatusCode::OK {
+            let (status, headers, body) = response.deconstruct();
+            let body = collect_pinned_stream(body)
+                .await
+                .map_err(azure_core::Error::from)?;
+            return Err(
+                crate::Error::from(StorageError::from_response(status, &headers, &body)).into(),
+            );
+        }
+
+        Ok(response)
+    }
+
+    /// Executes the query and collects all its records. Use `stream` to
+    /// process large results as they are received instead.
+    pub async fn execute(
+        &self,
+    ) -> Result<QueryBlobResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let (_, headers, body) = self.send().await?.deconstruct();
+        let events = query_events(body).try_collect().await?;
+
+        Ok(QueryBlobResponse::from_headers(&headers, events)?)
+    }
+
+    /// Executes the query and yields the records as they are received and
+    /// decoded: the result chunks as `QueryEvent::Data` followed or
+    /// interleaved by the progress, error and end records.
+    pub fn stream(
+        self,
+    ) -> impl Stream<Item = Result<QueryEvent, Box<dyn std::error::Error + Send + Sync>>> + 'a {
+        futures::stream::once(async move { self.send().await })
+            .map(|response| match response {
+                Ok(response) => query_events(response.deconstruct().2)
+                    .map_err(Into::into)
+                    .left_stream(),
+                Err(err) => futures::stream::iter(std::iter::once(Err(err))).right_stream(),
+            })
+            .flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blob::blob::{DelimitedTextConfiguration, JsonTextConfiguration};
+    use crate::core::prelude::*;
+
+    #[test]
+    fn query_request_xml() {
+        let storage_account = StorageAccountClient::new_emulator_default().as_storage_client();
+        let blob_client = storage_account
+            .as_container_client("container")
+            .as_blob_client("logs.csv");
+
+        let builder = QueryBlobBuilder::new(&blob_client, "SELECT * FROM BlobStorage WHERE _1 > 3")
+            .input_serialization(QuerySerialization::Delimited(
+                DelimitedTextConfiguration::default()
+                    .column_separator(";")
+                    .has_headers(true),
+            ))
+            .output_serialization(QuerySerialization::Json(JsonTextConfiguration::default()));
+
+        assert_eq!(
+            builder.to_xml(),
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<QueryRequest><QueryType>SQL</QueryType>\
+             <Expression>SELECT * FROM BlobStorage WHERE _1 &gt; 3</Expression>\
+             <InputSerialization><Format><Type>delimited</Type><DelimitedTextConfiguration>\
+             <ColumnSeparator>;</ColumnSeparator><FieldQuote>&quot;</FieldQuote>\
+             <RecordSeparator>\n</RecordSeparator><EscapeChar></EscapeChar><HasHeaders>true</HasHeaders>\
+             </DelimitedTextConfiguration></Format></InputSerialization>\
+             <OutputSerialization><Format><Type>json</Type><JsonTextConfiguration>\
+             <RecordSeparator>\n</RecordSeparator></JsonTextConfiguration></Format></OutputSerialization>\
+             </QueryRequest>"
+        );
+    }
+}
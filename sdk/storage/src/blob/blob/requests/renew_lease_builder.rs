@@ -1,5 +1,6 @@
 use crate::blob::blob::responses::RenewBlobLeaseResponse;
 use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
 use azure_core::headers::{add_mandatory_header, add_optional_header, LEASE_ACTION};
 use azure_core::prelude::*;
 
@@ -49,7 +50,7 @@ impl<'a> RenewLeaseBuilder<'a> {
         let response = self
             .blob_lease_client
             .http_client()
-            .execute_request_check_status(request, http::StatusCode::OK)
+            .execute_storage_request_check_status(request, http::StatusCode::OK)
             .await?;
 
         Ok(RenewBlobLeaseResponse::from_headers(response.headers())?)
@@ -0,0 +1,71 @@
+use crate::blob::blob::responses::SealBlobResponse;
+use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
+use azure_core::headers::{add_optional_header, add_optional_header_ref};
+use azure_core::prelude::*;
+use std::convert::TryInto;
+
+#[derive(Debug, Clone)]
+pub struct SealBlobBuilder<'a> {
+    blob_client: &'a BlobClient,
+    condition_append_position: Option<ConditionAppendPosition>,
+    if_match_condition: Option<IfMatchCondition<'a>>,
+    lease_id: Option<&'a LeaseId>,
+    client_request_id: Option<ClientRequestId<'a>>,
+    timeout: Option<Timeout>,
+}
+
+impl<'a> SealBlobBuilder<'a> {
+    pub(crate) fn new(blob_client: &'a BlobClient) -> Self {
+        Self {
+            blob_client,
+            condition_append_position: None,
+            if_match_condition: None,
+            lease_id: None,
+            client_request_id: None,
+            timeout: None,
+        }
+    }
+
+    setters! {
+        condition_append_position: ConditionAppendPosition => Some(condition_append_position),
+        if_match_condition: IfMatchCondition<'a> => Some(if_match_condition),
+        lease_id: &'a LeaseId => Some(lease_id),
+        client_request_id: ClientRequestId<'a> => Some(client_request_id),
+        timeout: Timeout => Some(timeout),
+    }
+
+    pub async fn execute(
+        &self,
+    ) -> Result<SealBlobResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let mut url = self.blob_client.url_with_segments(None)?;
+
+        url.query_pairs_mut().append_pair("comp", "seal");
+        self.timeout.append_to_url_query(&mut url);
+
+        trace!("url == {:?}", url);
+
+        let (request, _url) = self.blob_client.prepare_request(
+            url.as_str(),
+            &http::Method::PUT,
+            &|mut request| {
+                request = add_optional_header(&self.condition_append_position, request);
+                request = add_optional_header(&self.if_match_condition, request);
+                request = add_optional_header_ref(&self.lease_id, request);
+                request = add_optional_header(&self.client_request_id, request);
+                request
+            },
+            None,
+        )?;
+
+        let response = self
+            .blob_client
+            .http_client()
+            .execute_storage_request_check_status(request, http::StatusCode::OK)
+            .await?;
+
+        debug!("response.headers() == {:#?}", response.headers());
+
+        Ok(response.headers().try_into()?)
+    }
+}
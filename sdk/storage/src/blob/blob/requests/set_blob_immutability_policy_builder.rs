@@ -0,0 +1,74 @@
+use crate::blob::blob::responses::SetBlobImmutabilityPolicyResponse;
+use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
+use azure_core::headers::{add_mandatory_header, add_optional_header};
+use azure_core::prelude::*;
+use std::convert::TryInto;
+
+#[derive(Debug, Clone)]
+pub struct SetBlobImmutabilityPolicyBuilder<'a> {
+    blob_client: &'a BlobClient,
+    immutability_policy: &'a ImmutabilityPolicy,
+    blob_versioning: Option<&'a BlobVersioning>,
+    if_unmodified_since: Option<IfModifiedSinceCondition>,
+    client_request_id: Option<ClientRequestId<'a>>,
+    timeout: Option<Timeout>,
+}
+
+impl<'a> SetBlobImmutabilityPolicyBuilder<'a> {
+    pub(crate) fn new(
+        blob_client: &'a BlobClient,
+        immutability_policy: &'a ImmutabilityPolicy,
+    ) -> Self {
+        Self {
+            blob_client,
+            immutability_policy,
+            blob_versioning: None,
+            if_unmodified_since: None,
+            client_request_id: None,
+            timeout: None,
+        }
+    }
+
+    setters! {
+        blob_versioning: &'a BlobVersioning => Some(blob_versioning),
+        if_unmodified_since: IfModifiedSinceCondition => Some(if_unmodified_since),
+        client_request_id: ClientRequestId<'a> => Some(client_request_id),
+        timeout: Timeout => Some(timeout),
+    }
+
+    pub async fn execute(
+        &self,
+    ) -> Result<SetBlobImmutabilityPolicyResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let mut url = self.blob_client.url_with_segments(None)?;
+
+        url.query_pairs_mut()
+            .append_pair("comp", "immutabilityPolicies");
+        self.blob_versioning.append_to_url_query(&mut url);
+        self.timeout.append_to_url_query(&mut url);
+
+        trace!("url == {:?}", url);
+
+        let (request, _url) = self.blob_client.prepare_request(
+            url.as_str(),
+            &http::Method::PUT,
+            &|mut request| {
+                request = add_mandatory_header(&self.immutability_policy, request);
+                request = add_optional_header(&self.if_unmodified_since, request);
+                request = add_optional_header(&self.client_request_id, request);
+                request
+            },
+            None,
+        )?;
+
+        let response = self
+            .blob_client
+            .http_client()
+            .execute_storage_request_check_status(request, http::StatusCode::OK)
+            .await?;
+
+        debug!("response.headers() == {:#?}", response.headers());
+
+        Ok(response.headers().try_into()?)
+    }
+}
@@ -0,0 +1,67 @@
+use crate::blob::blob::responses::SetBlobLegalHoldResponse;
+use crate::blob::headers::LEGAL_HOLD;
+use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
+use azure_core::headers::add_optional_header;
+use azure_core::prelude::*;
+use std::convert::TryInto;
+
+#[derive(Debug, Clone)]
+pub struct SetBlobLegalHoldBuilder<'a> {
+    blob_client: &'a BlobClient,
+    legal_hold: bool,
+    blob_versioning: Option<&'a BlobVersioning>,
+    client_request_id: Option<ClientRequestId<'a>>,
+    timeout: Option<Timeout>,
+}
+
+impl<'a> SetBlobLegalHoldBuilder<'a> {
+    pub(crate) fn new(blob_client: &'a BlobClient, legal_hold: bool) -> Self {
+        Self {
+            blob_client,
+            legal_hold,
+            blob_versioning: None,
+            client_request_id: None,
+            timeout: None,
+        }
+    }
+
+    setters! {
+        blob_versioning: &'a BlobVersioning => Some(blob_versioning),
+        client_request_id: ClientRequestId<'a> => Some(client_request_id),
+        timeout: Timeout => Some(timeout),
+    }
+
+    pub async fn execute(
+        &self,
+    ) -> Result<SetBlobLegalHoldResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let mut url = self.blob_client.url_with_segments(None)?;
+
+        url.query_pairs_mut().append_pair("comp", "legalhold");
+        self.blob_versioning.append_to_url_query(&mut url);
+        self.timeout.append_to_url_query(&mut url);
+
+        trace!("url == {:?}", url);
+
+        let (request, _url) = self.blob_client.prepare_request(
+            url.as_str(),
+            &http::Method::PUT,
+            &|mut request| {
+                request = request.header(LEGAL_HOLD, &self.legal_hold.to_string());
+                request = add_optional_header(&self.client_request_id, request);
+                request
+            },
+            None,
+        )?;
+
+        let response = self
+            .blob_client
+            .http_client()
+            .execute_storage_request_check_status(request, http::StatusCode::OK)
+            .await?;
+
+        debug!("response.headers() == {:#?}", response.headers());
+
+        Ok(response.headers().try_into()?)
+    }
+}
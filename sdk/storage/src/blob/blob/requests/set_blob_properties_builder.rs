@@ -0,0 +1,83 @@
+use crate::blob::blob::responses::SetBlobPropertiesResponse;
+use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
+use azure_core::headers::{
+    add_optional_header, add_optional_header_ref, BLOB_CONTENT_LENGTH, BLOB_SEQUENCE_NUMBER,
+};
+use azure_core::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct SetBlobPropertiesBuilder<'a> {
+    blob_client: &'a BlobClient,
+    blob_http_headers: Option<&'a BlobHttpHeaders>,
+    content_length: Option<u64>,
+    sequence_number: Option<u64>,
+    lease_id: Option<&'a LeaseId>,
+    client_request_id: Option<ClientRequestId<'a>>,
+    timeout: Option<Timeout>,
+}
+
+impl<'a> SetBlobPropertiesBuilder<'a> {
+    pub(crate) fn new(blob_client: &'a BlobClient) -> Self {
+        Self {
+            blob_client,
+            blob_http_headers: None,
+            content_length: None,
+            sequence_number: None,
+            lease_id: None,
+            client_request_id: None,
+            timeout: None,
+        }
+    }
+
+    setters! {
+        blob_http_headers: &'a BlobHttpHeaders => Some(blob_http_headers),
+        content_length: u64 => Some(content_length),
+        sequence_number: u64 => Some(sequence_number),
+        lease_id: &'a LeaseId => Some(lease_id),
+        client_request_id: ClientRequestId<'a> => Some(client_request_id),
+        timeout: Timeout => Some(timeout),
+    }
+
+    pub async fn execute(
+        &self,
+    ) -> Result<SetBlobPropertiesResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let mut url = self.blob_client.url_with_segments(None)?;
+
+        url.query_pairs_mut().append_pair("comp", "properties");
+        self.timeout.append_to_url_query(&mut url);
+
+        trace!("url == {:?}", url);
+
+        let (request, _url) = self.blob_client.prepare_request(
+            url.as_str(),
+            &http::Method::PUT,
+            &|mut request| {
+                request = add_optional_header_ref(&self.blob_http_headers, request);
+                // only valid for page blobs
+                if let Some(content_length) = self.content_length {
+                    request = request.header(BLOB_CONTENT_LENGTH, &content_length.to_string());
+                }
+                if let Some(sequence_number) = self.sequence_number {
+                    request = request
+                        .header("x-ms-sequence-number-action", "update")
+                        .header(BLOB_SEQUENCE_NUMBER, &sequence_number.to_string());
+                }
+                request = add_optional_header_ref(&self.lease_id, request);
+                request = add_optional_header(&self.client_request_id, request);
+                request
+            },
+            None,
+        )?;
+
+        let response = self
+            .blob_client
+            .http_client()
+            .execute_storage_request_check_status(request, http::StatusCode::OK)
+            .await?;
+
+        debug!("response.headers() == {:#?}", response.headers());
+
+        Ok(SetBlobPropertiesResponse::from_headers(response.headers())?)
+    }
+}
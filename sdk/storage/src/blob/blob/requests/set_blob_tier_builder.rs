@@ -0,0 +1,78 @@
+use crate::blob::blob::responses::SetBlobTierResponse;
+use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
+use azure_core::headers::{add_mandatory_header, add_optional_header, add_optional_header_ref};
+use azure_core::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct SetBlobTierBuilder<'a> {
+    blob_client: &'a BlobClient,
+    access_tier: AccessTier,
+    rehydrate_priority: Option<RehydratePriority>,
+    blob_versioning: Option<&'a BlobVersioning>,
+    timeout: Option<Timeout>,
+    lease_id: Option<&'a LeaseId>,
+    client_request_id: Option<ClientRequestId<'a>>,
+}
+
+impl<'a> SetBlobTierBuilder<'a> {
+    pub(crate) fn new(blob_client: &'a BlobClient, access_tier: AccessTier) -> Self {
+        Self {
+            blob_client,
+            access_tier,
+            rehydrate_priority: None,
+            blob_versioning: None,
+            timeout: None,
+            lease_id: None,
+            client_request_id: None,
+        }
+    }
+
+    setters! {
+        rehydrate_priority: RehydratePriority => Some(rehydrate_priority),
+        blob_versioning: &'a BlobVersioning => Some(blob_versioning),
+        timeout: Timeout => Some(timeout),
+        lease_id: &'a LeaseId => Some(lease_id),
+        client_request_id: ClientRequestId<'a> => Some(client_request_id),
+    }
+
+    pub async fn execute(
+        &self,
+    ) -> Result<SetBlobTierResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let mut url = self.blob_client.url_with_segments(None)?;
+
+        url.query_pairs_mut().append_pair("comp", "tier");
+        self.blob_versioning.append_to_url_query(&mut url);
+        self.timeout.append_to_url_query(&mut url);
+
+        trace!("url == {:?}", url);
+
+        let (request, _url) = self.blob_client.prepare_request(
+            url.as_str(),
+            &http::Method::PUT,
+            &|mut request| {
+                request = add_mandatory_header(&self.access_tier, request);
+                request = add_optional_header(&self.rehydrate_priority, request);
+                request = add_optional_header_ref(&self.lease_id, request);
+                request = add_optional_header(&self.client_request_id, request);
+                request
+            },
+            None,
+        )?;
+
+        // The service answers 200 if the tier change is immediate and 202 if
+        // the blob has to be rehydrated from the archive tier first.
+        let response = self
+            .blob_client
+            .http_client()
+            .execute_storage_request_check_statuses(
+                request,
+                &[http::StatusCode::OK, http::StatusCode::ACCEPTED],
+            )
+            .await?;
+
+        debug!("response.headers() == {:#?}", response.headers());
+
+        Ok(SetBlobTierResponse::from_headers(response.headers())?)
+    }
+}
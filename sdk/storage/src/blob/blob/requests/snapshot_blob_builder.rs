@@ -0,0 +1,75 @@
+use crate::blob::blob::responses::SnapshotBlobResponse;
+use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
+use azure_core::headers::{add_optional_header, add_optional_header_ref};
+use azure_core::prelude::*;
+use std::convert::TryInto;
+
+#[derive(Debug, Clone)]
+pub struct SnapshotBlobBuilder<'a> {
+    blob_client: &'a BlobClient,
+    metadata: Option<&'a Metadata>,
+    lease_id: Option<&'a LeaseId>,
+    client_request_id: Option<ClientRequestId<'a>>,
+    timeout: Option<Timeout>,
+    customer_provided_key: Option<&'a CustomerProvidedKey>,
+    encryption_scope: Option<&'a EncryptionScope>,
+}
+
+impl<'a> SnapshotBlobBuilder<'a> {
+    pub(crate) fn new(blob_client: &'a BlobClient) -> Self {
+        Self {
+            blob_client,
+            metadata: None,
+            lease_id: None,
+            client_request_id: None,
+            timeout: None,
+            customer_provided_key: None,
+            encryption_scope: None,
+        }
+    }
+
+    setters! {
+        metadata: &'a Metadata => Some(metadata),
+        lease_id: &'a LeaseId => Some(lease_id),
+        client_request_id: ClientRequestId<'a> => Some(client_request_id),
+        timeout: Timeout => Some(timeout),
+        customer_provided_key: &'a CustomerProvidedKey => Some(customer_provided_key),
+        encryption_scope: &'a EncryptionScope => Some(encryption_scope),
+    }
+
+    pub async fn execute(
+        &self,
+    ) -> Result<SnapshotBlobResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let mut url = self.blob_client.url_with_segments(None)?;
+
+        url.query_pairs_mut().append_pair("comp", "snapshot");
+        self.timeout.append_to_url_query(&mut url);
+
+        trace!("url == {:?}", url);
+
+        let (request, _url) = self.blob_client.prepare_request(
+            url.as_str(),
+            &http::Method::PUT,
+            &|mut request| {
+                request = add_optional_header(&self.metadata, request);
+                request = add_optional_header_ref(&self.lease_id, request);
+                request = add_optional_header(&self.client_request_id, request);
+                request = add_optional_header_ref(&self.customer_provided_key, request);
+                request = add_optional_header_ref(&self.encryption_scope, request);
+                request
+            },
+            None,
+        )?;
+
+        let response = self
+            .blob_client
+            .http_client()
+            .execute_storage_request_check_status(request, http::StatusCode::CREATED)
+            .await?;
+
+        debug!("response.headers() == {:#?}", response.headers());
+
+        Ok(response.headers().try_into()?)
+    }
+}
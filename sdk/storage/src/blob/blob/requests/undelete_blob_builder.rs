@@ -0,0 +1,58 @@
+use crate::blob::blob::responses::UndeleteBlobResponse;
+use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
+use azure_core::headers::add_optional_header;
+use azure_core::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct UndeleteBlobBuilder<'a> {
+    blob_client: &'a BlobClient,
+    timeout: Option<Timeout>,
+    client_request_id: Option<ClientRequestId<'a>>,
+}
+
+impl<'a> UndeleteBlobBuilder<'a> {
+    pub(crate) fn new(blob_client: &'a BlobClient) -> Self {
+        Self {
+            blob_client,
+            timeout: None,
+            client_request_id: None,
+        }
+    }
+
+    setters! {
+        timeout: Timeout => Some(timeout),
+        client_request_id: ClientRequestId<'a> => Some(client_request_id),
+    }
+
+    pub async fn execute(
+        &self,
+    ) -> Result<UndeleteBlobResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let mut url = self.blob_client.url_with_segments(None)?;
+
+        url.query_pairs_mut().append_pair("comp", "undelete");
+        self.timeout.append_to_url_query(&mut url);
+
+        trace!("url == {:?}", url);
+
+        let (request, _url) = self.blob_client.prepare_request(
+            url.as_str(),
+            &http::Method::PUT,
+            &|mut request| {
+                request = add_optional_header(&self.client_request_id, request);
+                request
+            },
+            None,
+        )?;
+
+        let response = self
+            .blob_client
+            .http_client()
+            .execute_storage_request_check_status(request, http::StatusCode::OK)
+            .await?;
+
+        debug!("response.headers() == {:#?}", response.headers());
+
+        Ok(UndeleteBlobResponse::from_headers(response.headers())?)
+    }
+}
@@ -1,5 +1,6 @@
 use crate::blob::blob::responses::UpdatePageResponse;
 use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
 use azure_core::headers::{add_mandatory_header, add_optional_header, add_optional_header_ref};
 use azure_core::headers::{BLOB_TYPE, PAGE_WRITE};
 use azure_core::prelude::*;
@@ -17,6 +18,8 @@ pub struct UpdatePageBuilder<'a> {
     client_request_id: Option<ClientRequestId<'a>>,
     timeout: Option<Timeout>,
     lease_id: Option<&'a LeaseId>,
+    customer_provided_key: Option<&'a CustomerProvidedKey>,
+    encryption_scope: Option<&'a EncryptionScope>,
 }
 
 impl<'a> UpdatePageBuilder<'a> {
@@ -36,6 +39,8 @@ impl<'a> UpdatePageBuilder<'a> {
             client_request_id: None,
             timeout: None,
             lease_id: None,
+            customer_provided_key: None,
+            encryption_scope: None,
         }
     }
 
@@ -47,6 +52,8 @@ impl<'a> UpdatePageBuilder<'a> {
         client_request_id: ClientRequestId<'a> => Some(client_request_id),
         timeout: Timeout => Some(timeout),
         lease_id: &'a LeaseId => Some(lease_id),
+        customer_provided_key: &'a CustomerProvidedKey => Some(customer_provided_key),
+        encryption_scope: &'a EncryptionScope => Some(encryption_scope),
     }
 
     pub async fn execute(
@@ -72,6 +79,8 @@ impl<'a> UpdatePageBuilder<'a> {
                 request = add_optional_header(&self.if_match_condition, request);
                 request = add_optional_header(&self.client_request_id, request);
                 request = add_optional_header_ref(&self.lease_id, request);
+                request = add_optional_header_ref(&self.customer_provided_key, request);
+                request = add_optional_header_ref(&self.encryption_scope, request);
                 request
             },
             Some(self.content.clone()),
@@ -82,7 +91,7 @@ impl<'a> UpdatePageBuilder<'a> {
         let response = self
             .blob_client
             .http_client()
-            .execute_request_check_status(request, http::StatusCode::CREATED)
+            .execute_storage_request_check_status(request, http::StatusCode::CREATED)
             .await?;
 
         debug!("response.headers() == {:#?}", response.headers());
@@ -1,4 +1,5 @@
 use crate::blob::blob::{copy_status_from_headers, CopyStatus};
+use crate::blob::headers::encryption_key_sha256_from_headers_optional;
 use crate::core::CopyId;
 use crate::ConsistencyMD5;
 use crate::{core::copy_id_from_headers, headers::content_md5_from_headers_optional};
@@ -21,6 +22,7 @@ pub struct CopyBlobFromUrlResponse {
     pub version: String,
     pub copy_id: CopyId,
     pub copy_status: CopyStatus,
+    pub encryption_key_sha256: Option<String>,
     pub date: DateTime<Utc>,
 }
 
@@ -37,6 +39,7 @@ impl TryFrom<&HeaderMap> for CopyBlobFromUrlResponse {
             version: version_from_headers(headers)?.to_owned(),
             copy_id: copy_id_from_headers(headers)?,
             copy_status: copy_status_from_headers(headers)?,
+            encryption_key_sha256: encryption_key_sha256_from_headers_optional(headers)?,
             date: date_from_headers(headers)?,
         })
     }
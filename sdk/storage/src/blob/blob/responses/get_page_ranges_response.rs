@@ -0,0 +1,98 @@
+use crate::parsing_xml::{cast_must, find_subnodes};
+use azure_core::headers::{
+    date_from_headers, etag_from_headers, last_modified_from_headers, request_id_from_headers,
+};
+use azure_core::prelude::BA512Range;
+use azure_core::RequestId;
+use chrono::{DateTime, Utc};
+use http::HeaderMap;
+use std::str::from_utf8;
+use xml::Element;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetPageRangesResponse {
+    pub etag: String,
+    pub last_modified: DateTime<Utc>,
+    pub blob_content_length: u64,
+    pub request_id: RequestId,
+    pub date: DateTime<Utc>,
+    pub page_ranges: Vec<BA512Range>,
+    /// Only populated when diffing against a previous snapshot.
+    pub clear_ranges: Vec<BA512Range>,
+}
+
+impl GetPageRangesResponse {
+    pub(crate) fn from_response(
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Result<GetPageRangesResponse, crate::Error> {
+        let blob_content_length = headers
+            .get("x-ms-blob-content-length")
+            .ok_or_else(|| crate::Error::HeaderNotFound("x-ms-blob-content-length".to_owned()))?
+            .to_str()?
+            .parse::<u64>()?;
+
+        let (page_ranges, clear_ranges) = parse_page_list(from_utf8(body)?)?;
+
+        Ok(GetPageRangesResponse {
+            etag: etag_from_headers(headers)?,
+            last_modified: last_modified_from_headers(headers)?,
+            blob_content_length,
+            request_id: request_id_from_headers(headers)?,
+            date: date_from_headers(headers)?,
+            page_ranges,
+            clear_ranges,
+        })
+    }
+}
+
+fn parse_page_list(body: &str) -> Result<(Vec<BA512Range>, Vec<BA512Range>), crate::Error> {
+    let elem: Element = body.trim_start_matches('\u{FEFF}').parse()?;
+
+    let parse_ranges = |name: &str| -> Result<Vec<BA512Range>, crate::Error> {
+        find_subnodes(&elem, name)
+            .into_iter()
+            .map(|node| {
+                let start: u64 = cast_must(node, &["Start"])?;
+                let end: u64 = cast_must(node, &["End"])?;
+                BA512Range::new(start, end)
+                    .map_err(|err| crate::Error::UnexpectedXMLError(err.to_string()))
+            })
+            .collect()
+    };
+
+    Ok((parse_ranges("PageRange")?, parse_ranges("ClearRange")?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_page_list_diff() {
+        let body = "<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+            <PageList>\
+                <PageRange><Start>0</Start><End>511</End></PageRange>\
+                <ClearRange><Start>512</Start><End>1023</End></ClearRange>\
+                <PageRange><Start>1024</Start><End>4095</End></PageRange>\
+            </PageList>";
+
+        let (page_ranges, clear_ranges) = parse_page_list(body).unwrap();
+
+        assert_eq!(
+            page_ranges,
+            vec![
+                BA512Range::new(0, 511).unwrap(),
+                BA512Range::new(1024, 4095).unwrap()
+            ]
+        );
+        assert_eq!(clear_ranges, vec![BA512Range::new(512, 1023).unwrap()]);
+    }
+
+    #[test]
+    fn parse_empty_page_list() {
+        let (page_ranges, clear_ranges) = parse_page_list("<PageList />").unwrap();
+        assert!(page_ranges.is_empty());
+        assert!(clear_ranges.is_empty());
+    }
+}
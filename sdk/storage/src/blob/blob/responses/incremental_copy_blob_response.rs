@@ -0,0 +1,35 @@
+use crate::blob::blob::{copy_status_from_headers, CopyStatus};
+use crate::core::{copy_id_from_headers, CopyId};
+use azure_core::headers::{
+    date_from_headers, etag_from_headers, last_modified_from_headers, request_id_from_headers,
+};
+use azure_core::RequestId;
+use chrono::{DateTime, Utc};
+use http::HeaderMap;
+use std::convert::TryFrom;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncrementalCopyBlobResponse {
+    pub etag: String,
+    pub last_modified: DateTime<Utc>,
+    pub request_id: RequestId,
+    pub date: DateTime<Utc>,
+    pub copy_id: CopyId,
+    pub copy_status: CopyStatus,
+}
+
+impl TryFrom<&HeaderMap> for IncrementalCopyBlobResponse {
+    type Error = crate::Error;
+
+    fn try_from(headers: &HeaderMap) -> Result<Self, Self::Error> {
+        trace!("IncrementalCopyBlobResponse headers == {:#?}", headers);
+        Ok(Self {
+            etag: etag_from_headers(headers)?,
+            last_modified: last_modified_from_headers(headers)?,
+            request_id: request_id_from_headers(headers)?,
+            date: date_from_headers(headers)?,
+            copy_id: copy_id_from_headers(headers)?,
+            copy_status: copy_status_from_headers(headers)?,
+        })
+    }
+}
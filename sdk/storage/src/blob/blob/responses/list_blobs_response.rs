@@ -49,7 +49,12 @@ impl TryFrom<&http::Response<Bytes>> for ListBlobsResponse {
         let body = response.body();
 
         trace!("body == {:?}", body);
-        let list_blobs_response_internal: ListBlobsResponseInternal = read_xml(body)?;
+        let mut list_blobs_response_internal: ListBlobsResponseInternal = read_xml(body)?;
+        list_blobs_response_internal
+            .blobs
+            .blobs
+            .iter_mut()
+            .for_each(move_listed_metadata);
 
         Ok(Self {
             request_id: request_id_from_headers(response.headers())?,
@@ -65,6 +70,15 @@ impl TryFrom<&http::Response<Bytes>> for ListBlobsResponse {
     }
 }
 
+// The listing puts the metadata next to the properties, `get_properties` puts it in them.
+fn move_listed_metadata(blob: &mut Blob) {
+    if let Some(metadata) = blob.listed_metadata.take() {
+        if !metadata.is_empty() {
+            blob.properties.metadata = Some(metadata);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -221,4 +235,147 @@ mod tests {
         let bytes = Bytes::from(S);
         let _list_blobs_response_internal: ListBlobsResponseInternal = read_xml(&bytes).unwrap();
     }
+
+    #[test]
+    fn deserde_deleted_and_archived() {
+        const S: &'static str = "<?xml version=\"1.0\" encoding=\"utf-8\"?>
+<EnumerationResults ServiceEndpoint=\"https://azureskdforrust.blob.core.windows.net/\" ContainerName=\"osa2\">
+    <Blobs>
+        <Blob>
+            <Name>blob0.txt</Name>
+            <Deleted>true</Deleted>
+            <HasVersionsOnly>true</HasVersionsOnly>
+            <Properties>
+                <Creation-Time>Thu, 01 Jul 2021 10:44:59 GMT</Creation-Time>
+                <Last-Modified>Thu, 01 Jul 2021 10:44:59 GMT</Last-Modified>
+                <Etag>0x8D93C7D4629C227</Etag>
+                <Content-Length>8</Content-Length>
+                <Content-Type>text/plain</Content-Type>
+                <BlobType>BlockBlob</BlobType>
+                <AccessTier>Archive</AccessTier>
+                <ArchiveStatus>rehydrate-pending-to-hot</ArchiveStatus>
+                <RehydratePriority>High</RehydratePriority>
+                <LeaseStatus>unlocked</LeaseStatus>
+                <LeaseState>available</LeaseState>
+                <ServerEncrypted>true</ServerEncrypted>
+                <DeletedTime>Fri, 02 Jul 2021 08:12:01 GMT</DeletedTime>
+                <RemainingRetentionDays>6</RemainingRetentionDays>
+            </Properties>
+        </Blob>
+    </Blobs>
+    <NextMarker />
+</EnumerationResults>";
+
+        let bytes = Bytes::from(S);
+        let list_blobs_response_internal: ListBlobsResponseInternal = read_xml(&bytes).unwrap();
+        let blob = &list_blobs_response_internal.blobs.blobs[0];
+        assert_eq!(blob.deleted, Some(true));
+        assert_eq!(blob.has_versions_only, Some(true));
+        assert_eq!(
+            blob.properties.access_tier,
+            Some(crate::AccessTier::Archive)
+        );
+        assert_eq!(
+            blob.properties.archive_status,
+            Some(crate::blob::blob::ArchiveStatus::RehydratePendingToHot)
+        );
+        assert_eq!(
+            blob.properties.rehydrate_priority,
+            Some(crate::RehydratePriority::High)
+        );
+        assert!(blob.properties.deleted_time.is_some());
+        assert_eq!(blob.properties.remaining_retention_days, Some(6));
+    }
+
+    #[test]
+    fn deserde_metadata() {
+        const S: &'static str = "<?xml version=\"1.0\" encoding=\"utf-8\"?>
+<EnumerationResults ServiceEndpoint=\"https://azureskdforrust.blob.core.windows.net/\" ContainerName=\"checkpoints\">
+    <Prefix>hub/ownership/</Prefix>
+    <Blobs>
+        <Blob>
+            <Name>hub/ownership/0</Name>
+            <Properties>
+                <Creation-Time>Thu, 01 Jul 2021 10:44:59 GMT</Creation-Time>
+                <Last-Modified>Thu, 01 Jul 2021 10:44:59 GMT</Last-Modified>
+                <Etag>0x8D93C7D4629C227</Etag>
+                <Content-Length>0</Content-Length>
+                <Content-Type>application/octet-stream</Content-Type>
+                <BlobType>BlockBlob</BlobType>
+                <LeaseStatus>unlocked</LeaseStatus>
+                <LeaseState>available</LeaseState>
+                <ServerEncrypted>true</ServerEncrypted>
+            </Properties>
+            <Metadata>
+                <ownerid>processor-1</ownerid>
+            </Metadata>
+        </Blob>
+        <Blob>
+            <Name>hub/ownership/1</Name>
+            <Properties>
+                <Creation-Time>Thu, 01 Jul 2021 10:44:59 GMT</Creation-Time>
+                <Last-Modified>Thu, 01 Jul 2021 10:44:59 GMT</Last-Modified>
+                <Etag>0x8D93C7D463004D6</Etag>
+                <Content-Length>0</Content-Length>
+                <Content-Type>application/octet-stream</Content-Type>
+                <BlobType>BlockBlob</BlobType>
+                <LeaseStatus>unlocked</LeaseStatus>
+                <LeaseState>available</LeaseState>
+                <ServerEncrypted>true</ServerEncrypted>
+            </Properties>
+            <Metadata />
+        </Blob>
+    </Blobs>
+    <NextMarker />
+</EnumerationResults>";
+
+        let bytes = Bytes::from(S);
+        let mut list_blobs_response_internal: ListBlobsResponseInternal = read_xml(&bytes).unwrap();
+        let blobs = &mut list_blobs_response_internal.blobs.blobs;
+        blobs.iter_mut().for_each(move_listed_metadata);
+
+        assert_eq!(
+            blobs[0]
+                .properties
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.get("ownerid"))
+                .map(String::as_str),
+            Some("processor-1")
+        );
+        assert_eq!(blobs[1].properties.metadata, None);
+    }
+
+    #[test]
+    fn deserde_unknown_access_tier_and_archive_status() {
+        const S: &'static str = "<?xml version=\"1.0\" encoding=\"utf-8\"?>
+<EnumerationResults ServiceEndpoint=\"https://azureskdforrust.blob.core.windows.net/\" ContainerName=\"osa2\">
+    <Blobs>
+        <Blob>
+            <Name>blob0.txt</Name>
+            <Properties>
+                <Creation-Time>Thu, 01 Jul 2021 10:44:59 GMT</Creation-Time>
+                <Last-Modified>Thu, 01 Jul 2021 10:44:59 GMT</Last-Modified>
+                <Etag>0x8D93C7D4629C227</Etag>
+                <Content-Length>8</Content-Length>
+                <Content-Type>text/plain</Content-Type>
+                <BlobType>BlockBlob</BlobType>
+                <AccessTier>Frozen</AccessTier>
+                <ArchiveStatus>rehydrate-pending-to-frozen</ArchiveStatus>
+                <LeaseStatus>unlocked</LeaseStatus>
+                <LeaseState>available</LeaseState>
+                <ServerEncrypted>true</ServerEncrypted>
+            </Properties>
+        </Blob>
+    </Blobs>
+    <NextMarker />
+</EnumerationResults>";
+
+        let bytes = Bytes::from(S);
+        let list_blobs_response_internal: ListBlobsResponseInternal = read_xml(&bytes).unwrap();
+        let blob = &list_blobs_response_internal.blobs.blobs[0];
+        assert_eq!(blob.properties.access_tier, None);
+        assert_eq!(blob.properties.archive_status, None);
+        assert_eq!(blob.properties.content_length, 8);
+    }
 }
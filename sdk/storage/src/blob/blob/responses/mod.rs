@@ -36,3 +36,30 @@ mod copy_blob_response;
 pub use copy_blob_response::CopyBlobResponse;
 mod get_blob_metadata_response;
 pub use get_blob_metadata_response::GetBlobMetadataResponse;
+mod set_blob_tier_response;
+pub use set_blob_tier_response::SetBlobTierResponse;
+mod undelete_blob_response;
+pub use undelete_blob_response::UndeleteBlobResponse;
+mod query_blob_response;
+pub(crate) use query_blob_response::query_events;
+pub use query_blob_response::{QueryBlobResponse, QueryEvent};
+mod set_blob_metadata_response;
+pub use set_blob_metadata_response::SetBlobMetadataResponse;
+mod set_blob_properties_response;
+pub use set_blob_properties_response::SetBlobPropertiesResponse;
+mod snapshot_blob_response;
+pub use snapshot_blob_response::SnapshotBlobResponse;
+mod get_page_ranges_response;
+pub use get_page_ranges_response::GetPageRangesResponse;
+mod abort_copy_blob_response;
+pub use abort_copy_blob_response::AbortCopyBlobResponse;
+mod incremental_copy_blob_response;
+pub use incremental_copy_blob_response::IncrementalCopyBlobResponse;
+mod seal_blob_response;
+pub use seal_blob_response::SealBlobResponse;
+mod set_blob_immutability_policy_response;
+pub use set_blob_immutability_policy_response::SetBlobImmutabilityPolicyResponse;
+mod delete_blob_immutability_policy_response;
+pub use delete_blob_immutability_policy_response::DeleteBlobImmutabilityPolicyResponse;
+mod set_blob_legal_hold_response;
+pub use set_blob_legal_hold_response::SetBlobLegalHoldResponse;
@@ -1,3 +1,4 @@
+use crate::blob::headers::encryption_key_sha256_from_headers_optional;
 use azure_core::headers::{
     date_from_headers, etag_from_headers, last_modified_from_headers, request_id_from_headers,
     request_server_encrypted_from_headers,
@@ -13,6 +14,7 @@ pub struct PutBlobResponse {
     pub request_id: RequestId,
     pub date: DateTime<Utc>,
     pub request_server_encrypted: bool,
+    pub encryption_key_sha256: Option<String>,
 }
 
 impl PutBlobResponse {
@@ -24,6 +26,7 @@ impl PutBlobResponse {
         let request_id = request_id_from_headers(headers)?;
         let date = date_from_headers(headers)?;
         let request_server_encrypted = request_server_encrypted_from_headers(headers)?;
+        let encryption_key_sha256 = encryption_key_sha256_from_headers_optional(headers)?;
 
         Ok(PutBlobResponse {
             etag,
@@ -31,6 +34,7 @@ impl PutBlobResponse {
             request_id,
             date,
             request_server_encrypted,
+            encryption_key_sha256,
         })
     }
 }
@@ -1,3 +1,4 @@
+use crate::blob::headers::encryption_key_sha256_from_headers_optional;
 use crate::headers::content_md5_from_headers;
 use crate::ConsistencyMD5;
 use azure_core::headers::{
@@ -16,6 +17,7 @@ pub struct PutBlockListResponse {
     pub request_id: RequestId,
     pub date: DateTime<Utc>,
     pub request_server_encrypted: bool,
+    pub encryption_key_sha256: Option<String>,
 }
 
 impl PutBlockListResponse {
@@ -28,6 +30,7 @@ impl PutBlockListResponse {
         let request_id = request_id_from_headers(headers)?;
         let date = date_from_headers(headers)?;
         let request_server_encrypted = request_server_encrypted_from_headers(headers)?;
+        let encryption_key_sha256 = encryption_key_sha256_from_headers_optional(headers)?;
 
         Ok(PutBlockListResponse {
             etag,
@@ -36,6 +39,7 @@ impl PutBlockListResponse {
             request_id,
             date,
             request_server_encrypted,
+            encryption_key_sha256,
         })
     }
 }
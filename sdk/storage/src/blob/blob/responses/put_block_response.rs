@@ -1,3 +1,4 @@
+use crate::blob::headers::encryption_key_sha256_from_headers_optional;
 use crate::{headers::consistency_from_headers, ConsistencyCRC64, ConsistencyMD5};
 use azure_core::headers::{
     date_from_headers, request_id_from_headers, request_server_encrypted_from_headers,
@@ -13,6 +14,7 @@ pub struct PutBlockResponse {
     pub request_id: RequestId,
     pub date: DateTime<Utc>,
     pub request_server_encrypted: bool,
+    pub encryption_key_sha256: Option<String>,
 }
 
 impl PutBlockResponse {
@@ -23,6 +25,7 @@ impl PutBlockResponse {
         let request_id = request_id_from_headers(headers)?;
         let date = date_from_headers(headers)?;
         let request_server_encrypted = request_server_encrypted_from_headers(headers)?;
+        let encryption_key_sha256 = encryption_key_sha256_from_headers_optional(headers)?;
 
         Ok(PutBlockResponse {
             content_md5,
@@ -30,6 +33,7 @@ impl PutBlockResponse {
             request_id,
             date,
             request_server_encrypted,
+            encryption_key_sha256,
         })
     }
 }
@@ -0,0 +1,243 @@
+use crate::blob::avro::{AvroStreamReader, Value};
+use azure_core::headers::{date_from_headers, request_id_from_headers};
+use azure_core::{RequestId, StreamError};
+use bytes::{Bytes, BytesMut};
+use chrono::{DateTime, Utc};
+use futures::stream::{Stream, StreamExt};
+use http::HeaderMap;
+
+/// A record of the Avro stream returned by a quick query.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryEvent {
+    /// A chunk of the query results, in the requested output format.
+    Data(Bytes),
+    Progress {
+        bytes_scanned: i64,
+        total_bytes: i64,
+    },
+    /// Reported by the service when a record cannot be processed. Non fatal
+    /// errors are followed by further results.
+    Error {
+        fatal: bool,
+        name: String,
+        description: String,
+        position: i64,
+    },
+    End {
+        total_bytes: i64,
+    },
+}
+
+impl QueryEvent {
+    fn from_avro(value: Value) -> Result<Self, crate::Error> {
+        fn missing(record: &str, field: &str) -> crate::Error {
+            crate::Error::AvroError(format!("{} record without {}", record, field))
+        }
+
+        let name = match &value {
+            Value::Record(name, _) => name.as_str(),
+            _ => {
+                return Err(crate::Error::AvroError(format!(
+                    "unexpected query record {:?}",
+                    value
+                )))
+            }
+        };
+
+        Ok(match name {
+            "resultData" => QueryEvent::Data(
+                value
+                    .field("data")
+                    .and_then(Value::as_bytes)
+                    .ok_or_else(|| missing(name, "data"))?
+                    .clone(),
+            ),
+            "progress" => QueryEvent::Progress {
+                bytes_scanned: value
+                    .field("bytesScanned")
+                    .and_then(Value::as_i64)
+                    .ok_or_else(|| missing(name, "bytesScanned"))?,
+                total_bytes: value
+                    .field("totalBytes")
+                    .and_then(Value::as_i64)
+                    .ok_or_else(|| missing(name, "totalBytes"))?,
+            },
+            "error" => QueryEvent::Error {
+                fatal: value
+                    .field("fatal")
+                    .and_then(Value::as_bool)
+                    .ok_or_else(|| missing(name, "fatal"))?,
+                name: value
+                    .field("name")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| missing(name, "name"))?
+                    .to_owned(),
+                description: value
+                    .field("description")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| missing(name, "description"))?
+                    .to_owned(),
+                position: value
+                    .field("position")
+                    .and_then(Value::as_i64)
+                    .ok_or_else(|| missing(name, "position"))?,
+            },
+            "end" => QueryEvent::End {
+                total_bytes: value
+                    .field("totalBytes")
+                    .and_then(Value::as_i64)
+                    .ok_or_else(|| missing(name, "totalBytes"))?,
+            },
+            other => {
+                return Err(crate::Error::AvroError(format!(
+                    "unknown query record {}",
+                    other
+                )))
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct QueryBlobResponse {
+    pub request_id: RequestId,
+    pub date: DateTime<Utc>,
+    pub events: Vec<QueryEvent>,
+}
+
+impl QueryBlobResponse {
+    /// Concatenates the data records, that is the query results without
+    /// the progress and error records.
+    pub fn data(&self) -> Bytes {
+        let mut data = BytesMut::new();
+        for event in &self.events {
+            if let QueryEvent::Data(chunk) = event {
+                data.extend_from_slice(chunk);
+            }
+        }
+        data.freeze()
+    }
+
+    /// Returns the first fatal error reported by the service, if any.
+    pub fn fatal_error(&self) -> Option<&QueryEvent> {
+        self.events
+            .iter()
+            .find(|event| matches!(event, QueryEvent::Error { fatal: true, .. }))
+    }
+}
+
+impl QueryBlobResponse {
+    pub(crate) fn from_headers(
+        headers: &HeaderMap,
+        events: Vec<QueryEvent>,
+    ) -> Result<QueryBlobResponse, crate::Error> {
+        debug!("headers == {:#?}", headers);
+
+        Ok(QueryBlobResponse {
+            request_id: request_id_from_headers(headers)?,
+            date: date_from_headers(headers)?,
+            events,
+        })
+    }
+}
+
+/// Decodes the records of a query response body as its chunks are received.
+pub(crate) fn query_events<S>(body: S) -> impl Stream<Item = Result<QueryEvent, crate::Error>>
+where
+    S: Stream<Item = Result<Bytes, StreamError>> + Unpin,
+{
+    futures::stream::unfold(
+        Some((body, AvroStreamReader::default())),
+        |state| async move {
+            let (mut body, mut reader) = state?;
+            loop {
+                match reader.next_value() {
+                    Ok(Some(value)) => {
+                        return Some((QueryEvent::from_avro(value), Some((body, reader))))
+                    }
+                    Ok(None) => {}
+                    Err(err) => return Some((Err(err), None)),
+                }
+                match body.next().await {
+                    Some(Ok(chunk)) => reader.push(chunk),
+                    Some(Err(err)) => {
+                        return Some((Err(azure_core::Error::from(err).into()), None))
+                    }
+                    None => return reader.finish().err().map(|err| (Err(err), None)),
+                }
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blob::avro::tests::{container, encode_bytes, encode_long};
+
+    const SCHEMA: &str = r#"[
+    {"type":"record","name":"com.microsoft.azure.storage.queryBlobContents.resultData","fields":[{"name":"data","type":"bytes"}]},
+    {"type":"record","name":"com.microsoft.azure.storage.queryBlobContents.error","fields":[{"name":"fatal","type":"boolean"},{"name":"name","type":"string"},{"name":"description","type":"string"},{"name":"position","type":"long"}]},
+    {"type":"record","name":"com.microsoft.azure.storage.queryBlobContents.progress","fields":[{"name":"bytesScanned","type":"long"},{"name":"totalBytes","type":"long"}]},
+    {"type":"record","name":"com.microsoft.azure.storage.queryBlobContents.end","fields":[{"name":"totalBytes","type":"long"}]}
+]"#;
+
+    #[tokio::test]
+    async fn decode_events() {
+        let mut data = Vec::new();
+        encode_long(0, &mut data);
+        encode_bytes(b"a,1\n", &mut data);
+
+        let mut error = Vec::new();
+        encode_long(1, &mut error);
+        error.push(0);
+        encode_bytes(b"InvalidColumnOrdinal", &mut error);
+        encode_bytes(b"Column ordinal out of range", &mut error);
+        encode_long(12, &mut error);
+
+        let mut progress = Vec::new();
+        encode_long(2, &mut progress);
+        encode_long(40, &mut progress);
+        encode_long(40, &mut progress);
+
+        let mut more_data = Vec::new();
+        encode_long(0, &mut more_data);
+        encode_bytes(b"b,2\n", &mut more_data);
+
+        let mut end = Vec::new();
+        encode_long(3, &mut end);
+        encode_long(40, &mut end);
+
+        // the body is received in chunks splitting the records
+        let body = container(SCHEMA, &[data, error, progress, more_data, end]);
+        let chunks = body
+            .chunks(5)
+            .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+            .collect::<Vec<_>>();
+        let events = query_events(futures::stream::iter(chunks))
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(
+            events,
+            vec![
+                QueryEvent::Data(Bytes::from_static(b"a,1\n")),
+                QueryEvent::Error {
+                    fatal: false,
+                    name: "InvalidColumnOrdinal".to_owned(),
+                    description: "Column ordinal out of range".to_owned(),
+                    position: 12,
+                },
+                QueryEvent::Progress {
+                    bytes_scanned: 40,
+                    total_bytes: 40
+                },
+                QueryEvent::Data(Bytes::from_static(b"b,2\n")),
+                QueryEvent::End { total_bytes: 40 },
+            ]
+        );
+    }
+}
@@ -0,0 +1,37 @@
+use crate::blob::headers::BLOB_SEALED;
+use azure_core::headers::{
+    date_from_headers, etag_from_headers, last_modified_from_headers, request_id_from_headers,
+};
+use azure_core::RequestId;
+use chrono::{DateTime, Utc};
+use http::HeaderMap;
+use std::convert::TryFrom;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SealBlobResponse {
+    pub etag: String,
+    pub last_modified: DateTime<Utc>,
+    pub request_id: RequestId,
+    pub date: DateTime<Utc>,
+    pub sealed: bool,
+}
+
+impl TryFrom<&HeaderMap> for SealBlobResponse {
+    type Error = crate::Error;
+
+    fn try_from(headers: &HeaderMap) -> Result<Self, Self::Error> {
+        let sealed = headers
+            .get(BLOB_SEALED)
+            .ok_or_else(|| crate::Error::HeaderNotFound(BLOB_SEALED.to_owned()))?
+            .to_str()?
+            .parse::<bool>()?;
+
+        Ok(Self {
+            etag: etag_from_headers(headers)?,
+            last_modified: last_modified_from_headers(headers)?,
+            request_id: request_id_from_headers(headers)?,
+            date: date_from_headers(headers)?,
+            sealed,
+        })
+    }
+}
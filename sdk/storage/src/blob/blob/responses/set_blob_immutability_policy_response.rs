@@ -0,0 +1,42 @@
+use crate::blob::headers::{IMMUTABILITY_POLICY_MODE, IMMUTABILITY_POLICY_UNTIL_DATE};
+use crate::blob::prelude::ImmutabilityPolicyMode;
+use azure_core::headers::{date_from_headers, request_id_from_headers};
+use azure_core::RequestId;
+use chrono::{DateTime, Utc};
+use http::HeaderMap;
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetBlobImmutabilityPolicyResponse {
+    pub request_id: RequestId,
+    pub date: DateTime<Utc>,
+    pub immutability_policy_until_date: DateTime<Utc>,
+    pub immutability_policy_mode: ImmutabilityPolicyMode,
+}
+
+impl TryFrom<&HeaderMap> for SetBlobImmutabilityPolicyResponse {
+    type Error = crate::Error;
+
+    fn try_from(headers: &HeaderMap) -> Result<Self, Self::Error> {
+        let immutability_policy_until_date = headers
+            .get(IMMUTABILITY_POLICY_UNTIL_DATE)
+            .ok_or_else(|| crate::Error::HeaderNotFound(IMMUTABILITY_POLICY_UNTIL_DATE.to_owned()))?
+            .to_str()?;
+        let immutability_policy_until_date =
+            DateTime::parse_from_rfc2822(immutability_policy_until_date)?.with_timezone(&Utc);
+
+        let immutability_policy_mode = headers
+            .get(IMMUTABILITY_POLICY_MODE)
+            .ok_or_else(|| crate::Error::HeaderNotFound(IMMUTABILITY_POLICY_MODE.to_owned()))?
+            .to_str()?;
+        let immutability_policy_mode = ImmutabilityPolicyMode::from_str(immutability_policy_mode)?;
+
+        Ok(Self {
+            request_id: request_id_from_headers(headers)?,
+            date: date_from_headers(headers)?,
+            immutability_policy_until_date,
+            immutability_policy_mode,
+        })
+    }
+}
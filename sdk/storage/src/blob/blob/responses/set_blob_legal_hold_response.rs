@@ -0,0 +1,31 @@
+use crate::blob::headers::LEGAL_HOLD;
+use azure_core::headers::{date_from_headers, request_id_from_headers};
+use azure_core::RequestId;
+use chrono::{DateTime, Utc};
+use http::HeaderMap;
+use std::convert::TryFrom;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetBlobLegalHoldResponse {
+    pub request_id: RequestId,
+    pub date: DateTime<Utc>,
+    pub legal_hold: bool,
+}
+
+impl TryFrom<&HeaderMap> for SetBlobLegalHoldResponse {
+    type Error = crate::Error;
+
+    fn try_from(headers: &HeaderMap) -> Result<Self, Self::Error> {
+        let legal_hold = headers
+            .get(LEGAL_HOLD)
+            .ok_or_else(|| crate::Error::HeaderNotFound(LEGAL_HOLD.to_owned()))?
+            .to_str()?
+            .parse::<bool>()?;
+
+        Ok(Self {
+            request_id: request_id_from_headers(headers)?,
+            date: date_from_headers(headers)?,
+            legal_hold,
+        })
+    }
+}
@@ -0,0 +1,33 @@
+use azure_core::headers::{
+    date_from_headers, etag_from_headers, last_modified_from_headers, request_id_from_headers,
+    BLOB_SEQUENCE_NUMBER,
+};
+use azure_core::RequestId;
+use chrono::{DateTime, Utc};
+use http::HeaderMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetBlobPropertiesResponse {
+    pub etag: String,
+    pub last_modified: DateTime<Utc>,
+    pub sequence_number: Option<u64>,
+    pub request_id: RequestId,
+    pub date: DateTime<Utc>,
+}
+
+impl SetBlobPropertiesResponse {
+    pub(crate) fn from_headers(
+        headers: &HeaderMap,
+    ) -> Result<SetBlobPropertiesResponse, crate::Error> {
+        Ok(SetBlobPropertiesResponse {
+            etag: etag_from_headers(headers)?,
+            last_modified: last_modified_from_headers(headers)?,
+            sequence_number: match headers.get(BLOB_SEQUENCE_NUMBER) {
+                Some(value) => Some(value.to_str()?.parse::<u64>()?),
+                None => None,
+            },
+            request_id: request_id_from_headers(headers)?,
+            date: date_from_headers(headers)?,
+        })
+    }
+}
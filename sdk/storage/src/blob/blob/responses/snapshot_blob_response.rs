@@ -0,0 +1,45 @@
+use crate::blob::prelude::Snapshot;
+use azure_core::headers::{
+    date_from_headers, etag_from_headers, last_modified_from_headers, request_id_from_headers,
+};
+use azure_core::RequestId;
+use chrono::{DateTime, Utc};
+use http::HeaderMap;
+use std::convert::TryFrom;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotBlobResponse {
+    pub snapshot: Snapshot,
+    pub etag: String,
+    pub last_modified: DateTime<Utc>,
+    pub request_id: RequestId,
+    pub date: DateTime<Utc>,
+    pub request_server_encrypted: Option<bool>,
+}
+
+impl TryFrom<&HeaderMap> for SnapshotBlobResponse {
+    type Error = crate::Error;
+
+    fn try_from(headers: &HeaderMap) -> Result<Self, Self::Error> {
+        trace!("SnapshotBlobResponse headers == {:#?}", headers);
+
+        let snapshot = headers
+            .get("x-ms-snapshot")
+            .ok_or_else(|| crate::Error::HeaderNotFound("x-ms-snapshot".to_owned()))?
+            .to_str()?;
+
+        let request_server_encrypted = match headers.get("x-ms-request-server-encrypted") {
+            Some(value) => Some(value.to_str()?.parse::<bool>()?),
+            None => None,
+        };
+
+        Ok(Self {
+            snapshot: Snapshot::new(snapshot.to_owned()),
+            etag: etag_from_headers(headers)?,
+            last_modified: last_modified_from_headers(headers)?,
+            request_id: request_id_from_headers(headers)?,
+            date: date_from_headers(headers)?,
+            request_server_encrypted,
+        })
+    }
+}
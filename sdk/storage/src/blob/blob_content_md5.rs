@@ -1,6 +1,8 @@
 use azure_core::AddAsHeader;
 use http::request::Builder;
 
+use crate::blob::headers::BLOB_CONTENT_MD5;
+
 #[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord)]
 pub struct BlobContentMD5([u8; 16]);
 
@@ -10,9 +12,15 @@ impl From<md5::Digest> for BlobContentMD5 {
     }
 }
 
+impl From<&crate::ConsistencyMD5> for BlobContentMD5 {
+    fn from(md5: &crate::ConsistencyMD5) -> Self {
+        BlobContentMD5(*md5.as_slice())
+    }
+}
+
 impl AddAsHeader for BlobContentMD5 {
     fn add_as_header(&self, builder: Builder) -> Builder {
-        builder.header("x-ms-blob-content-md5", base64::encode(self.0))
+        builder.header(BLOB_CONTENT_MD5, base64::encode(self.0))
     }
 
     fn add_as_header2(
@@ -20,7 +28,7 @@ impl AddAsHeader for BlobContentMD5 {
         request: &mut azure_core::Request,
     ) -> Result<(), azure_core::HTTPHeaderError> {
         request.headers_mut().append(
-            "x-ms-blob-content-md5",
+            BLOB_CONTENT_MD5,
             http::header::HeaderValue::from_str(&base64::encode(self.0))?,
         );
 
@@ -0,0 +1,89 @@
+use crate::blob::blob::BlobProperties;
+use crate::blob::headers::{BLOB_CONTENT_ENCODING, BLOB_CONTENT_LANGUAGE, BLOB_CONTENT_TYPE};
+use crate::BlobContentMD5;
+use azure_core::headers::{CACHE_CONTROL, CONTENT_DISPOSITION};
+use azure_core::AddAsHeader;
+use http::request::Builder;
+
+/// The standard HTTP properties of a blob, as updated by
+/// `Set Blob Properties`. The service clears every property not
+/// specified, so start from the current ones (see the `From<&BlobProperties>`
+/// implementation) to change only some of them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BlobHttpHeaders {
+    pub cache_control: Option<String>,
+    pub content_type: Option<String>,
+    pub content_encoding: Option<String>,
+    pub content_language: Option<String>,
+    pub content_disposition: Option<String>,
+    pub content_md5: Option<BlobContentMD5>,
+}
+
+impl BlobHttpHeaders {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    setters! {
+        cache_control: String => Some(cache_control),
+        content_type: String => Some(content_type),
+        content_encoding: String => Some(content_encoding),
+        content_language: String => Some(content_language),
+        content_disposition: String => Some(content_disposition),
+        content_md5: BlobContentMD5 => Some(content_md5),
+    }
+
+    fn headers(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        vec![
+            (CACHE_CONTROL, &self.cache_control),
+            (BLOB_CONTENT_TYPE, &self.content_type),
+            (BLOB_CONTENT_ENCODING, &self.content_encoding),
+            (BLOB_CONTENT_LANGUAGE, &self.content_language),
+            (CONTENT_DISPOSITION, &self.content_disposition),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.as_deref().map(|value| (name, value)))
+    }
+}
+
+impl From<&BlobProperties> for BlobHttpHeaders {
+    fn from(properties: &BlobProperties) -> Self {
+        Self {
+            cache_control: properties.cache_control.clone(),
+            content_type: Some(properties.content_type.clone()),
+            content_encoding: properties.content_encoding.clone(),
+            content_language: properties.content_language.clone(),
+            content_disposition: properties.content_disposition.clone(),
+            content_md5: properties.content_md5.as_ref().map(BlobContentMD5::from),
+        }
+    }
+}
+
+impl AddAsHeader for BlobHttpHeaders {
+    fn add_as_header(&self, builder: Builder) -> Builder {
+        let mut builder = builder;
+        for (name, value) in self.headers() {
+            builder = builder.header(name, value);
+        }
+        if let Some(content_md5) = &self.content_md5 {
+            builder = content_md5.add_as_header(builder);
+        }
+        builder
+    }
+
+    fn add_as_header2(
+        &self,
+        request: &mut azure_core::Request,
+    ) -> Result<(), azure_core::HTTPHeaderError> {
+        for (name, value) in self.headers() {
+            request
+                .headers_mut()
+                .append(name, http::header::HeaderValue::from_str(value)?);
+        }
+        if let Some(content_md5) = &self.content_md5 {
+            content_md5.add_as_header2(request)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,188 @@
+use crate::core::{Cors, Logging, Metrics, RetentionPolicy};
+use crate::xml::xml_escape;
+
+/// Properties of the blob service of a storage account.
+///
+/// Every element is optional: when setting the properties the service leaves
+/// the omitted ones unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct BlobServiceProperties {
+    pub logging: Option<Logging>,
+    pub hour_metrics: Option<Metrics>,
+    pub minute_metrics: Option<Metrics>,
+    pub cors: Option<Cors>,
+    pub default_service_version: Option<String>,
+    pub delete_retention_policy: Option<RetentionPolicy>,
+    pub static_website: Option<StaticWebsite>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct StaticWebsite {
+    pub enabled: bool,
+    pub index_document: Option<String>,
+    #[serde(rename = "ErrorDocument404Path")]
+    pub error_document_404_path: Option<String>,
+}
+
+impl StaticWebsite {
+    fn to_xml(&self) -> String {
+        let mut s = format!("<StaticWebsite><Enabled>{}</Enabled>", self.enabled);
+        if let Some(index_document) = &self.index_document {
+            s.push_str(&format!(
+                "<IndexDocument>{}</IndexDocument>",
+                xml_escape(index_document)
+            ));
+        }
+        if let Some(error_document_404_path) = &self.error_document_404_path {
+            s.push_str(&format!(
+                "<ErrorDocument404Path>{}</ErrorDocument404Path>",
+                xml_escape(error_document_404_path)
+            ));
+        }
+        s.push_str("</StaticWebsite>");
+        s
+    }
+}
+
+impl BlobServiceProperties {
+    pub fn to_xml(&self) -> String {
+        let mut s = String::new();
+        s.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<StorageServiceProperties>");
+        if let Some(logging) = &self.logging {
+            s.push_str(&logging.to_xml());
+        }
+        if let Some(hour_metrics) = &self.hour_metrics {
+            s.push_str(&hour_metrics.to_xml("HourMetrics"));
+        }
+        if let Some(minute_metrics) = &self.minute_metrics {
+            s.push_str(&minute_metrics.to_xml("MinuteMetrics"));
+        }
+        if let Some(cors) = &self.cors {
+            s.push_str(&cors.to_xml());
+        }
+        if let Some(default_service_version) = &self.default_service_version {
+            s.push_str(&format!(
+                "<DefaultServiceVersion>{}</DefaultServiceVersion>",
+                xml_escape(default_service_version)
+            ));
+        }
+        if let Some(delete_retention_policy) = &self.delete_retention_policy {
+            s.push_str(&delete_retention_policy.to_xml("DeleteRetentionPolicy"));
+        }
+        if let Some(static_website) = &self.static_website {
+            s.push_str(&static_website.to_xml());
+        }
+        s.push_str("</StorageServiceProperties>");
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::CorsRule;
+    use crate::xml::read_xml;
+    use bytes::Bytes;
+
+    const S: &str = "<?xml version=\"1.0\" encoding=\"utf-8\"?>
+<StorageServiceProperties>
+    <Logging>
+        <Version>1.0</Version>
+        <Read>false</Read>
+        <Write>true</Write>
+        <Delete>true</Delete>
+        <RetentionPolicy>
+            <Enabled>true</Enabled>
+            <Days>7</Days>
+        </RetentionPolicy>
+    </Logging>
+    <HourMetrics>
+        <Version>1.0</Version>
+        <Enabled>true</Enabled>
+        <IncludeAPIs>false</IncludeAPIs>
+        <RetentionPolicy>
+            <Enabled>true</Enabled>
+            <Days>7</Days>
+        </RetentionPolicy>
+    </HourMetrics>
+    <MinuteMetrics>
+        <Version>1.0</Version>
+        <Enabled>false</Enabled>
+        <RetentionPolicy>
+            <Enabled>false</Enabled>
+        </RetentionPolicy>
+    </MinuteMetrics>
+    <Cors>
+        <CorsRule>
+            <AllowedMethods>GET,PUT</AllowedMethods>
+            <AllowedOrigins>http://www.contoso.com</AllowedOrigins>
+            <AllowedHeaders>x-ms-meta-*</AllowedHeaders>
+            <ExposedHeaders>x-ms-meta-data*</ExposedHeaders>
+            <MaxAgeInSeconds>200</MaxAgeInSeconds>
+        </CorsRule>
+    </Cors>
+    <DefaultServiceVersion>2019-12-12</DefaultServiceVersion>
+    <DeleteRetentionPolicy>
+        <Enabled>true</Enabled>
+        <Days>5</Days>
+    </DeleteRetentionPolicy>
+    <StaticWebsite>
+        <Enabled>true</Enabled>
+        <IndexDocument>index.html</IndexDocument>
+        <ErrorDocument404Path>404.html</ErrorDocument404Path>
+    </StaticWebsite>
+</StorageServiceProperties>";
+
+    #[test]
+    fn deserde_blob_service_properties() {
+        let properties: BlobServiceProperties = read_xml(&Bytes::from(S)).unwrap();
+
+        let cors_rules = properties.cors.unwrap().cors_rule.unwrap();
+        assert_eq!(cors_rules.len(), 1);
+        assert_eq!(cors_rules[0].max_age_in_seconds, 200);
+        assert_eq!(
+            properties.default_service_version.as_deref(),
+            Some("2019-12-12")
+        );
+        assert_eq!(properties.delete_retention_policy.unwrap().days, Some(5));
+        assert_eq!(
+            properties
+                .static_website
+                .unwrap()
+                .error_document_404_path
+                .as_deref(),
+            Some("404.html")
+        );
+    }
+
+    #[test]
+    fn to_xml_round_trip() {
+        let properties = BlobServiceProperties {
+            cors: Some(Cors {
+                cors_rule: Some(vec![CorsRule {
+                    allowed_origins: "https://contoso.com/?a=1&b=<2>".to_owned(),
+                    allowed_methods: "GET".to_owned(),
+                    max_age_in_seconds: 60,
+                    exposed_headers: "*".to_owned(),
+                    allowed_headers: "x-ms-meta-\"quoted\"".to_owned(),
+                }]),
+            }),
+            delete_retention_policy: Some(RetentionPolicy {
+                enabled: true,
+                days: Some(3),
+            }),
+            static_website: Some(StaticWebsite {
+                enabled: true,
+                index_document: Some("index.html?lang=en&region=<eu>".to_owned()),
+                error_document_404_path: None,
+            }),
+            ..Default::default()
+        };
+
+        let xml = properties.to_xml();
+        let round_trip: BlobServiceProperties = read_xml(&Bytes::from(xml)).unwrap();
+        assert_eq!(round_trip, properties);
+    }
+}
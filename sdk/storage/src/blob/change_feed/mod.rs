@@ -0,0 +1,334 @@
+//! Reader for the blob change feed.
+//!
+//! The change feed is stored by the service in the `$blobchangefeed`
+//! container: `meta/segments.json` tells up to when the feed can be
+//! consumed, every hour of changes is a segment described by an
+//! `idx/segments/YYYY/MM/DD/hhmm/meta.json` manifest and each segment is
+//! split into shards, directories of Avro chunk files holding the events.
+//! See [https://docs.microsoft.com/azure/storage/blobs/storage-blob-change-feed](https://docs.microsoft.com/azure/storage/blobs/storage-blob-change-feed).
+pub mod requests;
+
+use crate::blob::avro::Value;
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+pub(crate) const CHANGE_FEED_CONTAINER: &str = "$blobchangefeed";
+pub(crate) const SEGMENTS_META: &str = "meta/segments.json";
+pub(crate) const SEGMENTS_PREFIX: &str = "idx/segments/";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlobChangeFeedEventType {
+    BlobCreated,
+    BlobDeleted,
+    BlobPropertiesUpdated,
+    BlobSnapshotCreated,
+    BlobTierChanged,
+    BlobAsyncOperationInitiated,
+    RestorePointMarkerCreated,
+    /// Event types introduced after this crate was written.
+    Other(String),
+}
+
+impl FromStr for BlobChangeFeedEventType {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "BlobCreated" => BlobChangeFeedEventType::BlobCreated,
+            "BlobDeleted" => BlobChangeFeedEventType::BlobDeleted,
+            "BlobPropertiesUpdated" => BlobChangeFeedEventType::BlobPropertiesUpdated,
+            "BlobSnapshotCreated" => BlobChangeFeedEventType::BlobSnapshotCreated,
+            "BlobTierChanged" => BlobChangeFeedEventType::BlobTierChanged,
+            "BlobAsyncOperationInitiated" => BlobChangeFeedEventType::BlobAsyncOperationInitiated,
+            "RestorePointMarkerCreated" => BlobChangeFeedEventType::RestorePointMarkerCreated,
+            other => BlobChangeFeedEventType::Other(other.to_owned()),
+        })
+    }
+}
+
+/// A change feed record, in the Event Grid schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlobChangeFeedEvent {
+    pub id: String,
+    pub topic: String,
+    pub subject: String,
+    pub event_type: BlobChangeFeedEventType,
+    pub event_time: DateTime<Utc>,
+    pub schema_version: i64,
+    pub data: BlobChangeFeedEventData,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlobChangeFeedEventData {
+    pub api: String,
+    pub client_request_id: Option<String>,
+    pub request_id: Option<String>,
+    pub etag: Option<String>,
+    pub content_type: Option<String>,
+    pub content_length: Option<i64>,
+    pub content_offset: Option<i64>,
+    pub blob_type: Option<String>,
+    pub url: String,
+    pub source_url: Option<String>,
+    pub destination_url: Option<String>,
+    pub recursive: Option<bool>,
+    pub sequencer: Option<String>,
+    pub blob_version: Option<String>,
+    pub container_version: Option<String>,
+    pub snapshot: Option<String>,
+    pub storage_diagnostics: HashMap<String, String>,
+}
+
+impl BlobChangeFeedEvent {
+    pub(crate) fn from_avro(value: &Value) -> Result<Self, crate::Error> {
+        let data = value.field("data").ok_or_else(|| missing_field("data"))?;
+
+        let event_time = must_str(value, "eventTime")?;
+        let event_time = DateTime::parse_from_rfc3339(event_time)?.with_timezone(&Utc);
+        let Ok(event_type) = BlobChangeFeedEventType::from_str(must_str(value, "eventType")?);
+
+        Ok(Self {
+            id: must_str(value, "id")?.to_owned(),
+            topic: must_str(value, "topic")?.to_owned(),
+            subject: must_str(value, "subject")?.to_owned(),
+            event_type,
+            event_time,
+            schema_version: value
+                .field("schemaVersion")
+                .and_then(Value::as_i64)
+                .unwrap_or_default(),
+            data: BlobChangeFeedEventData {
+                api: must_str(data, "api")?.to_owned(),
+                client_request_id: optional_string(data, "clientRequestId"),
+                request_id: optional_string(data, "requestId"),
+                etag: optional_string(data, "etag"),
+                content_type: optional_string(data, "contentType"),
+                content_length: data.field("contentLength").and_then(Value::as_i64),
+                content_offset: data.field("contentOffset").and_then(Value::as_i64),
+                blob_type: optional_string(data, "blobType"),
+                url: must_str(data, "url")?.to_owned(),
+                source_url: optional_string(data, "sourceUrl"),
+                destination_url: optional_string(data, "destinationUrl"),
+                recursive: data.field("recursive").and_then(Value::as_bool),
+                sequencer: optional_string(data, "sequencer"),
+                blob_version: optional_string(data, "blobVersion"),
+                container_version: optional_string(data, "containerVersion"),
+                snapshot: optional_string(data, "snapshot"),
+                storage_diagnostics: match data.field("storageDiagnostics") {
+                    Some(Value::Map(map)) => map
+                        .iter()
+                        .filter_map(|(k, v)| v.as_str().map(|v| (k.clone(), v.to_owned())))
+                        .collect(),
+                    _ => HashMap::new(),
+                },
+            },
+        })
+    }
+}
+
+fn missing_field(field: &str) -> crate::Error {
+    crate::Error::AvroError(format!("change feed record without {}", field))
+}
+
+fn must_str<'a>(value: &'a Value, field: &str) -> Result<&'a str, crate::Error> {
+    value
+        .field(field)
+        .and_then(Value::as_str)
+        .ok_or_else(|| missing_field(field))
+}
+
+fn optional_string(value: &Value, field: &str) -> Option<String> {
+    value.field(field).and_then(Value::as_str).map(String::from)
+}
+
+/// Position in the change feed of the last returned event. It can be
+/// persisted (it implements `Serialize`/`Deserialize`) and handed back to
+/// `GetChangesBuilder::cursor` to resume right after that event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeFeedCursor {
+    pub(crate) segment_path: String,
+    pub(crate) shard_path: String,
+    pub(crate) chunk_path: String,
+    pub(crate) event_index: u64,
+}
+
+impl ChangeFeedCursor {
+    /// The start time of the segment holding the event.
+    pub fn segment_time(&self) -> Option<DateTime<Utc>> {
+        segment_time(&self.segment_path)
+    }
+}
+
+/// An event along with the cursor pointing at it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeFeedRecord {
+    pub event: BlobChangeFeedEvent,
+    pub cursor: ChangeFeedCursor,
+}
+
+/// `meta/segments.json`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SegmentsMeta {
+    pub(crate) last_consumable: String,
+}
+
+/// `idx/segments/YYYY/MM/DD/hhmm/meta.json`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SegmentMeta {
+    pub(crate) chunk_file_paths: Vec<String>,
+}
+
+impl SegmentMeta {
+    /// The shard directories, relative to the change feed container.
+    pub(crate) fn shard_paths(&self) -> Vec<String> {
+        let container_prefix = format!("{}/", CHANGE_FEED_CONTAINER);
+        self.chunk_file_paths
+            .iter()
+            .map(|path| path.trim_start_matches(&container_prefix).to_owned())
+            .collect()
+    }
+}
+
+/// Extracts the start time from a segment manifest path such as
+/// `idx/segments/2019/02/22/1810/meta.json`.
+pub(crate) fn segment_time(segment_path: &str) -> Option<DateTime<Utc>> {
+    let mut parts = segment_path.strip_prefix(SEGMENTS_PREFIX)?.split('/');
+    let year = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse().ok()?;
+    let day = parts.next()?.parse().ok()?;
+    let hhmm = parts.next()?;
+    if hhmm.len() != 4 {
+        return None;
+    }
+    let hour = hhmm[..2].parse().ok()?;
+    let minute = hhmm[2..].parse().ok()?;
+    let naive = NaiveDate::from_ymd_opt(year, month, day)?.and_hms_opt(hour, minute, 0)?;
+    Some(Utc.from_utc_datetime(&naive))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blob::avro::tests::{container, encode_bytes, encode_long};
+    use crate::blob::avro::AvroReader;
+
+    const EVENT_SCHEMA: &str = r#"{"type":"record","name":"BlobChangeEvent","namespace":"com.microsoft.azure.storage.blob","fields":[
+        {"name":"schemaVersion","type":"int"},
+        {"name":"topic","type":"string"},
+        {"name":"subject","type":"string"},
+        {"name":"eventType","type":{"type":"enum","name":"BlobChangeEventType","symbols":["UnspecifiedEventType","BlobCreated","BlobDeleted"]}},
+        {"name":"eventTime","type":"string"},
+        {"name":"id","type":"string"},
+        {"name":"data","type":{"type":"record","name":"BlobChangeEventData","fields":[
+            {"name":"api","type":"string"},
+            {"name":"clientRequestId","type":"string"},
+            {"name":"requestId","type":"string"},
+            {"name":"etag","type":"string"},
+            {"name":"contentType","type":"string"},
+            {"name":"contentLength","type":"long"},
+            {"name":"blobType","type":{"type":"enum","name":"BlobType","symbols":["BlockBlob","PageBlob","AppendBlob"]}},
+            {"name":"url","type":"string"},
+            {"name":"sequencer","type":"string"},
+            {"name":"snapshot","type":["null","string"]},
+            {"name":"storageDiagnostics","type":{"type":"map","values":"string"}}]}}]}"#;
+
+    #[test]
+    fn decode_event() {
+        let mut record = Vec::new();
+        encode_long(1, &mut record);
+        encode_bytes(
+            b"/subscriptions/s/resourceGroups/r/providers/Microsoft.Storage/storageAccounts/a",
+            &mut record,
+        );
+        encode_bytes(b"/blobServices/default/containers/c/blobs/b", &mut record);
+        encode_long(1, &mut record); // BlobCreated
+        encode_bytes(b"2020-02-17T18:11:25.1200000Z", &mut record);
+        encode_bytes(b"62616073-8020-0000-00ff-233467060cc0", &mut record);
+        encode_bytes(b"PutBlob", &mut record);
+        encode_bytes(b"ba1c1b23-a2d4-4e34-a64b-bbd1ef8dafa0", &mut record);
+        encode_bytes(b"62616073-8020-0000-00ff-233467000000", &mut record);
+        encode_bytes(b"0x8D7B3DB9B6C4E6E", &mut record);
+        encode_bytes(b"application/octet-stream", &mut record);
+        encode_long(128, &mut record);
+        encode_long(0, &mut record); // BlockBlob
+        encode_bytes(b"https://a.blob.core.windows.net/c/b", &mut record);
+        encode_bytes(
+            b"000000000000000000000000000017140000000000000a88",
+            &mut record,
+        );
+        encode_long(0, &mut record); // no snapshot
+        encode_long(1, &mut record);
+        encode_bytes(b"bid", &mut record);
+        encode_bytes(b"f5d6d4e8", &mut record);
+        encode_long(0, &mut record);
+
+        let value = AvroReader::new(container(EVENT_SCHEMA, &[record]))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        let event = BlobChangeFeedEvent::from_avro(&value).unwrap();
+
+        assert_eq!(event.event_type, BlobChangeFeedEventType::BlobCreated);
+        assert_eq!(
+            event.event_time,
+            "2020-02-17T18:11:25.120Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(event.schema_version, 1);
+        assert_eq!(event.data.api, "PutBlob");
+        assert_eq!(event.data.content_length, Some(128));
+        assert_eq!(event.data.blob_type.as_deref(), Some("BlockBlob"));
+        assert_eq!(event.data.snapshot, None);
+        assert_eq!(
+            event
+                .data
+                .storage_diagnostics
+                .get("bid")
+                .map(String::as_str),
+            Some("f5d6d4e8")
+        );
+    }
+
+    #[test]
+    fn parse_segment_paths() {
+        assert_eq!(
+            segment_time("idx/segments/2019/02/22/1810/meta.json"),
+            Some("2019-02-22T18:10:00Z".parse::<DateTime<Utc>>().unwrap())
+        );
+        assert!(segment_time("idx/segments/1601/01/01/0000/meta.json").is_some());
+        assert_eq!(segment_time("meta/segments.json"), None);
+
+        let meta: SegmentMeta = serde_json::from_str(
+            r#"{"version":0,"begin":"2019-02-22T18:10:00.000Z","intervalSecs":3600,"status":"Finalized",
+                "chunkFilePaths":["$blobchangefeed/log/00/2019/02/22/1810/","$blobchangefeed/log/01/2019/02/22/1810/"]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            meta.shard_paths(),
+            vec!["log/00/2019/02/22/1810/", "log/01/2019/02/22/1810/"]
+        );
+    }
+
+    #[test]
+    fn cursor_round_trip() {
+        let cursor = ChangeFeedCursor {
+            segment_path: "idx/segments/2019/02/22/1810/meta.json".to_owned(),
+            shard_path: "log/00/2019/02/22/1810/".to_owned(),
+            chunk_path: "log/00/2019/02/22/1810/00000.avro".to_owned(),
+            event_index: 41,
+        };
+        let json = serde_json::to_string(&cursor).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ChangeFeedCursor>(&json).unwrap(),
+            cursor
+        );
+        assert_eq!(
+            cursor.segment_time(),
+            Some("2019-02-22T18:10:00Z".parse::<DateTime<Utc>>().unwrap())
+        );
+    }
+}
@@ -0,0 +1,259 @@
+use crate::blob::avro::AvroReader;
+use crate::blob::change_feed::{
+    segment_time, BlobChangeFeedEvent, ChangeFeedCursor, ChangeFeedRecord, SegmentMeta,
+    SegmentsMeta, SEGMENTS_META, SEGMENTS_PREFIX,
+};
+use crate::blob::prelude::*;
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use futures::stream::{unfold, Stream, StreamExt};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+type BoxedError = Box<dyn std::error::Error + Sync + Send>;
+
+#[derive(Debug, Clone)]
+pub struct GetChangesBuilder<'a> {
+    change_feed_client: &'a ChangeFeedClient,
+    start_time: Option<DateTime<Utc>>,
+    end_time: Option<DateTime<Utc>>,
+    cursor: Option<ChangeFeedCursor>,
+}
+
+impl<'a> GetChangesBuilder<'a> {
+    pub(crate) fn new(change_feed_client: &'a ChangeFeedClient) -> Self {
+        Self {
+            change_feed_client,
+            start_time: None,
+            end_time: None,
+            cursor: None,
+        }
+    }
+
+    setters! {
+        start_time: DateTime<Utc> => Some(start_time),
+        end_time: DateTime<Utc> => Some(end_time),
+        cursor: ChangeFeedCursor => Some(cursor),
+    }
+
+    /// Streams the events of the change feed, oldest segment first. Shards
+    /// are read one after the other so events are only ordered within a
+    /// shard. The feed is read up to the last consumable segment: events
+    /// still being written are returned by later calls.
+    pub fn stream(self) -> impl Stream<Item = Result<ChangeFeedRecord, BoxedError>> + 'a {
+        let state = ChangeFeedState {
+            container_client: self.change_feed_client.container_client.clone(),
+            start_time: self.start_time,
+            end_time: self.end_time,
+            resume_from: self.cursor,
+            segments: None,
+            shards: VecDeque::new(),
+            chunks: VecDeque::new(),
+            events: VecDeque::new(),
+            segment_path: String::new(),
+            shard_path: String::new(),
+            chunk_path: String::new(),
+        };
+
+        unfold(Some(state), |state| async move {
+            let mut state = state?;
+            match state.next_record().await {
+                Ok(Some(record)) => Some((Ok(record), Some(state))),
+                Ok(None) => None,
+                Err(err) => Some((Err(err), None)),
+            }
+        })
+    }
+}
+
+#[derive(Debug)]
+struct ChangeFeedState {
+    container_client: Arc<ContainerClient>,
+    start_time: Option<DateTime<Utc>>,
+    end_time: Option<DateTime<Utc>>,
+    resume_from: Option<ChangeFeedCursor>,
+    segments: Option<VecDeque<String>>,
+    shards: VecDeque<String>,
+    chunks: VecDeque<String>,
+    events: VecDeque<(u64, BlobChangeFeedEvent)>,
+    segment_path: String,
+    shard_path: String,
+    chunk_path: String,
+}
+
+impl ChangeFeedState {
+    async fn next_record(&mut self) -> Result<Option<ChangeFeedRecord>, BoxedError> {
+        loop {
+            if let Some((event_index, event)) = self.events.pop_front() {
+                return Ok(Some(ChangeFeedRecord {
+                    event,
+                    cursor: ChangeFeedCursor {
+                        segment_path: self.segment_path.clone(),
+                        shard_path: self.shard_path.clone(),
+                        chunk_path: self.chunk_path.clone(),
+                        event_index,
+                    },
+                }));
+            }
+
+            if let Some(chunk_path) = self.chunks.pop_front() {
+                self.chunk_path = chunk_path;
+                self.load_chunk().await?;
+                continue;
+            }
+
+            if let Some(shard_path) = self.shards.pop_front() {
+                self.shard_path = shard_path;
+                self.load_shard().await?;
+                continue;
+            }
+
+            match self.segments.as_mut() {
+                Some(segments) => match segments.pop_front() {
+                    Some(segment_path) => {
+                        self.segment_path = segment_path;
+                        self.load_segment().await?;
+                    }
+                    None => return Ok(None),
+                },
+                None => self.load_segments().await?,
+            }
+        }
+    }
+
+    /// Lists the consumable segments in the requested time range.
+    async fn load_segments(&mut self) -> Result<(), BoxedError> {
+        let meta = self
+            .container_client
+            .as_blob_client(SEGMENTS_META)
+            .get()
+            .execute()
+            .await?;
+        let meta: SegmentsMeta = serde_json::from_slice(&meta.data)?;
+        let last_consumable =
+            DateTime::parse_from_rfc3339(&meta.last_consumable)?.with_timezone(&Utc);
+
+        // segments span an hour: events of the start hour are in the
+        // segment starting at its beginning
+        let segment_start_time = self
+            .start_time
+            .map(|start_time| start_time.duration_trunc(Duration::hours(1)))
+            .transpose()?;
+
+        let mut segments = Vec::new();
+        let mut pages = Box::pin(
+            self.container_client
+                .list_blobs()
+                .prefix(SEGMENTS_PREFIX)
+                .stream(),
+        );
+        while let Some(page) = pages.next().await {
+            for blob in page?.blobs.blobs {
+                if !blob.name.ends_with("/meta.json") {
+                    continue;
+                }
+                let time = match segment_time(&blob.name) {
+                    Some(time) => time,
+                    None => continue,
+                };
+                let in_range = time <= last_consumable
+                    && !matches!(segment_start_time, Some(start) if time < start)
+                    && !matches!(self.end_time, Some(end) if time >= end)
+                    && !matches!(&self.resume_from, Some(cursor) if blob.name < cursor.segment_path);
+                if in_range {
+                    segments.push(blob.name);
+                }
+            }
+        }
+        segments.sort();
+
+        self.segments = Some(segments.into());
+        Ok(())
+    }
+
+    async fn load_segment(&mut self) -> Result<(), BoxedError> {
+        let meta = self
+            .container_client
+            .as_blob_client(self.segment_path.as_str())
+            .get()
+            .execute()
+            .await?;
+        let meta: SegmentMeta = serde_json::from_slice(&meta.data)?;
+
+        let mut shards = meta.shard_paths();
+        shards.sort();
+        if let Some(cursor) = self.resuming_segment() {
+            shards.retain(|shard| shard >= &cursor.shard_path);
+        }
+        self.shards = shards.into();
+        Ok(())
+    }
+
+    async fn load_shard(&mut self) -> Result<(), BoxedError> {
+        let mut chunks = Vec::new();
+        let mut pages = Box::pin(
+            self.container_client
+                .list_blobs()
+                .prefix(self.shard_path.as_str())
+                .stream(),
+        );
+        while let Some(page) = pages.next().await {
+            chunks.extend(page?.blobs.blobs.into_iter().map(|blob| blob.name));
+        }
+        chunks.sort();
+
+        if let Some(cursor) = self.resuming_shard() {
+            chunks.retain(|chunk| chunk >= &cursor.chunk_path);
+        }
+        self.chunks = chunks.into();
+        Ok(())
+    }
+
+    async fn load_chunk(&mut self) -> Result<(), BoxedError> {
+        let chunk = self
+            .container_client
+            .as_blob_client(self.chunk_path.as_str())
+            .get()
+            .execute()
+            .await?;
+
+        let skip_up_to = self.resuming_chunk().map(|cursor| cursor.event_index);
+
+        let mut events = VecDeque::new();
+        for (event_index, value) in AvroReader::new(chunk.data)?.enumerate() {
+            let event_index = event_index as u64;
+            if matches!(skip_up_to, Some(skip_up_to) if event_index <= skip_up_to) {
+                continue;
+            }
+            let event = BlobChangeFeedEvent::from_avro(&value?)?;
+            let in_range = !matches!(self.start_time, Some(start) if event.event_time < start)
+                && !matches!(self.end_time, Some(end) if event.event_time >= end);
+            if in_range {
+                events.push_back((event_index, event));
+            }
+        }
+
+        // once the resumed chunk is read the cursor is of no further use
+        if skip_up_to.is_some() {
+            self.resume_from = None;
+        }
+
+        self.events = events;
+        Ok(())
+    }
+
+    fn resuming_segment(&self) -> Option<&ChangeFeedCursor> {
+        self.resume_from
+            .as_ref()
+            .filter(|cursor| cursor.segment_path == self.segment_path)
+    }
+
+    fn resuming_shard(&self) -> Option<&ChangeFeedCursor> {
+        self.resuming_segment()
+            .filter(|cursor| cursor.shard_path == self.shard_path)
+    }
+
+    fn resuming_chunk(&self) -> Option<&ChangeFeedCursor> {
+        self.resuming_shard()
+            .filter(|cursor| cursor.chunk_path == self.chunk_path)
+    }
+}
@@ -0,0 +1,2 @@
+mod get_changes_builder;
+pub use get_changes_builder::GetChangesBuilder;
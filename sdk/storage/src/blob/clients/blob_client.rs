@@ -79,6 +79,26 @@ impl BlobClient {
         GetBlobMetadataBuilder::new(self)
     }
 
+    pub fn set_metadata<'a>(&'a self, metadata: &'a Metadata) -> SetBlobMetadataBuilder<'a> {
+        SetBlobMetadataBuilder::new(self, metadata)
+    }
+
+    pub fn set_properties(&self) -> SetBlobPropertiesBuilder {
+        SetBlobPropertiesBuilder::new(self)
+    }
+
+    pub fn snapshot(&self) -> SnapshotBlobBuilder {
+        SnapshotBlobBuilder::new(self)
+    }
+
+    pub fn get_page_ranges(&self) -> GetPageRangesBuilder {
+        GetPageRangesBuilder::new(self)
+    }
+
+    pub fn query<'a>(&'a self, expression: &'a str) -> QueryBlobBuilder<'a> {
+        QueryBlobBuilder::new(self, expression)
+    }
+
     pub fn update_page<'a>(
         &'a self,
         ba512_range: BA512Range,
@@ -99,6 +119,14 @@ impl BlobClient {
         DeleteBlobVersionBuilder::new(self, version_id)
     }
 
+    pub fn undelete(&self) -> UndeleteBlobBuilder {
+        UndeleteBlobBuilder::new(self)
+    }
+
+    pub fn set_tier(&self, access_tier: AccessTier) -> SetBlobTierBuilder {
+        SetBlobTierBuilder::new(self, access_tier)
+    }
+
     pub fn copy<'a>(&'a self, copy_source: &'a str) -> CopyBlobBuilder<'a> {
         CopyBlobBuilder::new(self, copy_source)
     }
@@ -107,6 +135,33 @@ impl BlobClient {
         CopyBlobFromUrlBuilder::new(self, copy_source)
     }
 
+    pub fn abort_copy<'a>(&'a self, copy_id: &'a CopyId) -> AbortCopyBlobBuilder<'a> {
+        AbortCopyBlobBuilder::new(self, copy_id)
+    }
+
+    pub fn incremental_copy<'a>(&'a self, source_url: &'a str) -> IncrementalCopyBlobBuilder<'a> {
+        IncrementalCopyBlobBuilder::new(self, source_url)
+    }
+
+    pub fn seal(&self) -> SealBlobBuilder {
+        SealBlobBuilder::new(self)
+    }
+
+    pub fn set_immutability_policy<'a>(
+        &'a self,
+        immutability_policy: &'a ImmutabilityPolicy,
+    ) -> SetBlobImmutabilityPolicyBuilder<'a> {
+        SetBlobImmutabilityPolicyBuilder::new(self, immutability_policy)
+    }
+
+    pub fn delete_immutability_policy(&self) -> DeleteBlobImmutabilityPolicyBuilder {
+        DeleteBlobImmutabilityPolicyBuilder::new(self)
+    }
+
+    pub fn set_legal_hold(&self, legal_hold: bool) -> SetBlobLegalHoldBuilder {
+        SetBlobLegalHoldBuilder::new(self, legal_hold)
+    }
+
     pub fn put_page_blob(&self, length: u128) -> PutPageBlobBuilder {
         PutPageBlobBuilder::new(self, length)
     }
@@ -0,0 +1,42 @@
+use crate::blob::change_feed::requests::GetChangesBuilder;
+use crate::blob::change_feed::CHANGE_FEED_CONTAINER;
+use crate::blob::prelude::*;
+use crate::core::clients::{AsStorageClient, StorageAccountClient, StorageClient};
+use std::sync::Arc;
+
+pub trait AsChangeFeedClient {
+    fn as_change_feed_client(&self) -> Arc<ChangeFeedClient>;
+}
+
+impl AsChangeFeedClient for Arc<StorageClient> {
+    fn as_change_feed_client(&self) -> Arc<ChangeFeedClient> {
+        ChangeFeedClient::new(self.as_container_client(CHANGE_FEED_CONTAINER))
+    }
+}
+
+impl AsChangeFeedClient for Arc<StorageAccountClient> {
+    fn as_change_feed_client(&self) -> Arc<ChangeFeedClient> {
+        self.as_storage_client().as_change_feed_client()
+    }
+}
+
+/// Reads the change feed of the account. The change feed must be enabled
+/// in the blob service properties.
+#[derive(Debug, Clone)]
+pub struct ChangeFeedClient {
+    pub(crate) container_client: Arc<ContainerClient>,
+}
+
+impl ChangeFeedClient {
+    pub(crate) fn new(container_client: Arc<ContainerClient>) -> Arc<Self> {
+        Arc::new(Self { container_client })
+    }
+
+    pub fn container_client(&self) -> &ContainerClient {
+        self.container_client.as_ref()
+    }
+
+    pub fn get_changes(&self) -> GetChangesBuilder {
+        GetChangesBuilder::new(self)
+    }
+}
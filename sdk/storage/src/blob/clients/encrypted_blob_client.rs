@@ -0,0 +1,62 @@
+use crate::blob::blob::requests::{EncryptedGetBlobBuilder, EncryptedPutBlockBlobBuilder};
+use crate::blob::prelude::*;
+use crate::core::KeyEncryptionKey;
+use bytes::Bytes;
+use std::sync::Arc;
+
+pub trait AsEncryptedBlobClient {
+    fn as_encrypted_blob_client(
+        &self,
+        key_encryption_key: Arc<dyn KeyEncryptionKey>,
+    ) -> Arc<EncryptedBlobClient>;
+}
+
+impl AsEncryptedBlobClient for Arc<BlobClient> {
+    fn as_encrypted_blob_client(
+        &self,
+        key_encryption_key: Arc<dyn KeyEncryptionKey>,
+    ) -> Arc<EncryptedBlobClient> {
+        EncryptedBlobClient::new(self.clone(), key_encryption_key)
+    }
+}
+
+/// A `BlobClient` encrypting the uploaded contents on the client side.
+/// The service only stores the ciphertext and the wrapped content key,
+/// in the `encryptiondata` metadata. Downloads, including ranged ones,
+/// are decrypted transparently.
+#[derive(Debug, Clone)]
+pub struct EncryptedBlobClient {
+    blob_client: Arc<BlobClient>,
+    key_encryption_key: Arc<dyn KeyEncryptionKey>,
+}
+
+impl EncryptedBlobClient {
+    pub(crate) fn new(
+        blob_client: Arc<BlobClient>,
+        key_encryption_key: Arc<dyn KeyEncryptionKey>,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            blob_client,
+            key_encryption_key,
+        })
+    }
+
+    pub fn blob_client(&self) -> &BlobClient {
+        self.blob_client.as_ref()
+    }
+
+    pub(crate) fn key_encryption_key(&self) -> &dyn KeyEncryptionKey {
+        self.key_encryption_key.as_ref()
+    }
+
+    pub fn put_block_blob<'a>(
+        &'a self,
+        body: impl Into<Bytes>,
+    ) -> EncryptedPutBlockBlobBuilder<'a> {
+        EncryptedPutBlockBlobBuilder::new(self, body.into())
+    }
+
+    pub fn get(&self) -> EncryptedGetBlobBuilder {
+        EncryptedGetBlobBuilder::new(self)
+    }
+}
@@ -6,3 +6,9 @@ mod blob_lease_client;
 pub use blob_lease_client::{AsBlobLeaseClient, BlobLeaseClient};
 mod blob_client;
 pub use blob_client::{AsBlobClient, BlobClient};
+#[cfg(feature = "client_side_encryption")]
+mod encrypted_blob_client;
+#[cfg(feature = "client_side_encryption")]
+pub use encrypted_blob_client::{AsEncryptedBlobClient, EncryptedBlobClient};
+mod change_feed_client;
+pub use change_feed_client::{AsChangeFeedClient, ChangeFeedClient};
@@ -2,6 +2,10 @@ use azure_core::AddAsHeader;
 pub mod requests;
 pub mod responses;
 
+use crate::blob::headers::{
+    DEFAULT_ENCRYPTION_SCOPE, DENY_ENCRYPTION_SCOPE_OVERRIDE,
+    IMMUTABLE_STORAGE_WITH_VERSIONING_ENABLED,
+};
 use crate::parsing_xml::{cast_must, cast_optional, traverse};
 use azure_core::incompletevector::IncompleteVector;
 use azure_core::{
@@ -74,6 +78,9 @@ pub struct Container {
     pub public_access: PublicAccess,
     pub has_immutability_policy: bool,
     pub has_legal_hold: bool,
+    pub default_encryption_scope: Option<String>,
+    pub deny_encryption_scope_override: Option<bool>,
+    pub immutable_storage_with_versioning_enabled: Option<bool>,
     pub metadata: HashMap<String, String>,
 }
 
@@ -95,6 +102,9 @@ impl Container {
             public_access: PublicAccess::None,
             has_immutability_policy: false,
             has_legal_hold: false,
+            default_encryption_scope: None,
+            deny_encryption_scope_override: None,
+            immutable_storage_with_versioning_enabled: None,
             metadata: HashMap::new(),
         }
     }
@@ -158,6 +168,24 @@ impl Container {
             None => return Err(crate::Error::MissingHeaderError(HAS_LEGAL_HOLD.to_owned())),
         };
 
+        let default_encryption_scope = match headers.get(DEFAULT_ENCRYPTION_SCOPE) {
+            Some(default_encryption_scope) => Some(default_encryption_scope.to_str()?.to_owned()),
+            None => None,
+        };
+
+        let deny_encryption_scope_override = match headers.get(DENY_ENCRYPTION_SCOPE_OVERRIDE) {
+            Some(deny_encryption_scope_override) => {
+                Some(bool::from_str(deny_encryption_scope_override.to_str()?)?)
+            }
+            None => None,
+        };
+
+        let immutable_storage_with_versioning_enabled =
+            match headers.get(IMMUTABLE_STORAGE_WITH_VERSIONING_ENABLED) {
+                Some(enabled) => Some(bool::from_str(enabled.to_str()?)?),
+                None => None,
+            };
+
         let mut metadata: HashMap<String, String> = HashMap::new();
         for (key, value) in headers {
             if key.as_str().starts_with(META_PREFIX) {
@@ -175,6 +203,9 @@ impl Container {
             public_access,
             has_immutability_policy,
             has_legal_hold,
+            default_encryption_scope,
+            deny_encryption_scope_override,
+            immutable_storage_with_versioning_enabled,
             metadata,
         })
     }
@@ -200,6 +231,14 @@ impl Container {
         let has_immutability_policy =
             cast_must::<bool>(elem, &["Properties", "HasImmutabilityPolicy"])?;
         let has_legal_hold = cast_must::<bool>(elem, &["Properties", "HasLegalHold"])?;
+        let default_encryption_scope =
+            cast_optional::<String>(elem, &["Properties", "DefaultEncryptionScope"])?;
+        let deny_encryption_scope_override =
+            cast_optional::<bool>(elem, &["Properties", "DenyEncryptionScopeOverride"])?;
+        let immutable_storage_with_versioning_enabled = cast_optional::<bool>(
+            elem,
+            &["Properties", "ImmutableStorageWithVersioningEnabled"],
+        )?;
 
         let metadata = {
             let mut hm = HashMap::new();
@@ -252,6 +291,9 @@ impl Container {
             public_access,
             has_immutability_policy,
             has_legal_hold,
+            default_encryption_scope,
+            deny_encryption_scope_override,
+            immutable_storage_with_versioning_enabled,
             metadata,
         })
     }
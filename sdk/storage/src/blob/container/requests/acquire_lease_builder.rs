@@ -1,5 +1,6 @@
 use crate::blob::prelude::*;
 use crate::container::responses::AcquireLeaseResponse;
+use crate::core::StorageHttpClient;
 use azure_core::headers::{
     add_mandatory_header, add_optional_header, add_optional_header_ref, LEASE_ACTION,
 };
@@ -68,7 +69,7 @@ impl<'a> AcquireLeaseBuilder<'a> {
             .storage_client()
             .storage_account_client()
             .http_client()
-            .execute_request_check_status(request.0, StatusCode::CREATED)
+            .execute_storage_request_check_status(request.0, StatusCode::CREATED)
             .await?;
 
         Ok(AcquireLeaseResponse::from_headers(response.headers())?)
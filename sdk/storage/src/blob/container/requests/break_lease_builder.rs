@@ -1,5 +1,6 @@
 use crate::blob::prelude::*;
 use crate::container::responses::BreakLeaseResponse;
+use crate::core::StorageHttpClient;
 use azure_core::headers::{add_optional_header, add_optional_header_ref, LEASE_ACTION};
 use azure_core::prelude::*;
 use http::method::Method;
@@ -60,7 +61,7 @@ impl<'a> BreakLeaseBuilder<'a> {
             .storage_client()
             .storage_account_client()
             .http_client()
-            .execute_request_check_status(request.0, StatusCode::ACCEPTED)
+            .execute_storage_request_check_status(request.0, StatusCode::ACCEPTED)
             .await?;
 
         Ok(BreakLeaseResponse::from_headers(response.headers())?)
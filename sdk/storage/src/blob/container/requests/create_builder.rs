@@ -1,6 +1,8 @@
+use crate::blob::headers::IMMUTABLE_STORAGE_WITH_VERSIONING_ENABLED;
+use crate::core::StorageHttpClient;
 use crate::{blob::prelude::*, container::PublicAccess};
 use azure_core::{
-    headers::{add_mandatory_header, add_optional_header},
+    headers::{add_mandatory_header, add_optional_header, add_optional_header_ref},
     prelude::*,
 };
 use http::{method::Method, status::StatusCode};
@@ -10,6 +12,8 @@ pub struct CreateBuilder<'a> {
     container_client: &'a ContainerClient,
     public_access: PublicAccess,
     metadata: Option<&'a Metadata>,
+    default_encryption_scope: Option<&'a DefaultEncryptionScope>,
+    immutable_storage_with_versioning: bool,
     client_request_id: Option<ClientRequestId<'a>>,
     timeout: Option<Timeout>,
 }
@@ -20,6 +24,8 @@ impl<'a> CreateBuilder<'a> {
             container_client,
             public_access: PublicAccess::None,
             metadata: None,
+            default_encryption_scope: None,
+            immutable_storage_with_versioning: false,
             client_request_id: None,
             timeout: None,
         }
@@ -28,6 +34,8 @@ impl<'a> CreateBuilder<'a> {
     setters! {
         public_access: PublicAccess => public_access,
         metadata: &'a Metadata => Some(metadata),
+        default_encryption_scope: &'a DefaultEncryptionScope => Some(default_encryption_scope),
+        immutable_storage_with_versioning: bool => immutable_storage_with_versioning,
         client_request_id: ClientRequestId<'a> => Some(client_request_id),
         timeout: Timeout => Some(timeout),
     }
@@ -45,6 +53,11 @@ impl<'a> CreateBuilder<'a> {
             &|mut request| {
                 request = add_mandatory_header(&self.public_access, request);
                 request = add_optional_header(&self.metadata, request);
+                request = add_optional_header_ref(&self.default_encryption_scope, request);
+                // requires versioning to be enabled on the account
+                if self.immutable_storage_with_versioning {
+                    request = request.header(IMMUTABLE_STORAGE_WITH_VERSIONING_ENABLED, "true");
+                }
                 request = add_optional_header(&self.client_request_id, request);
                 request
             },
@@ -56,7 +69,7 @@ impl<'a> CreateBuilder<'a> {
             .storage_client()
             .storage_account_client()
             .http_client()
-            .execute_request_check_status(request.0, StatusCode::CREATED)
+            .execute_storage_request_check_status(request.0, StatusCode::CREATED)
             .await?;
 
         // TODO: Capture and return the response headers
@@ -1,4 +1,5 @@
 use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
 use azure_core::headers::{add_optional_header, add_optional_header_ref};
 use azure_core::prelude::*;
 use http::method::Method;
@@ -49,7 +50,7 @@ impl<'a> DeleteBuilder<'a> {
             .storage_client()
             .storage_account_client()
             .http_client()
-            .execute_request_check_status(request.0, StatusCode::ACCEPTED)
+            .execute_storage_request_check_status(request.0, StatusCode::ACCEPTED)
             .await?;
 
         // TODO: Capture and return the response headers
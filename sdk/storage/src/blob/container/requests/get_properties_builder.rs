@@ -1,5 +1,6 @@
 use crate::blob::prelude::*;
 use crate::container::responses::GetPropertiesResponse;
+use crate::core::StorageHttpClient;
 use azure_core::headers::{add_optional_header, add_optional_header_ref};
 use azure_core::prelude::*;
 use http::method::Method;
@@ -55,7 +56,7 @@ impl<'a> GetPropertiesBuilder<'a> {
             .storage_client()
             .storage_account_client()
             .http_client()
-            .execute_request_check_status(request.0, StatusCode::OK)
+            .execute_storage_request_check_status(request.0, StatusCode::OK)
             .await?;
 
         Ok((self.container_client.container_name(), response.headers()).try_into()?)
@@ -1,5 +1,6 @@
 use crate::blob::blob::responses::ListBlobsResponse;
 use crate::blob::prelude::*;
+use crate::core::StorageHttpClient;
 use azure_core::headers::add_optional_header;
 use azure_core::prelude::*;
 use futures::stream::{unfold, Stream};
@@ -21,6 +22,7 @@ pub struct ListBlobsBuilder<'a> {
     include_deleted: bool,
     include_tags: bool,
     include_versions: bool,
+    include_deleted_with_versions: bool,
     client_request_id: Option<ClientRequestId<'a>>,
     timeout: Option<Timeout>,
 }
@@ -40,6 +42,7 @@ impl<'a> ListBlobsBuilder<'a> {
             include_deleted: false,
             include_tags: false,
             include_versions: false,
+            include_deleted_with_versions: false,
             client_request_id: None,
             timeout: None,
         }
@@ -57,6 +60,7 @@ impl<'a> ListBlobsBuilder<'a> {
         include_deleted: bool => include_deleted,
         include_tags: bool => include_tags,
         include_versions: bool => include_versions,
+        include_deleted_with_versions: bool => include_deleted_with_versions,
         client_request_id: ClientRequestId<'a> => Some(client_request_id),
         timeout: Timeout => Some(timeout),
     }
@@ -99,6 +103,9 @@ impl<'a> ListBlobsBuilder<'a> {
         if self.include_versions {
             optional_includes.push("versions");
         }
+        if self.include_deleted_with_versions {
+            optional_includes.push("deletedwithversions");
+        }
         if !optional_includes.is_empty() {
             url.query_pairs_mut()
                 .append_pair("include", &optional_includes.join(","));
@@ -123,7 +130,7 @@ impl<'a> ListBlobsBuilder<'a> {
             .storage_client()
             .storage_account_client()
             .http_client()
-            .execute_request_check_status(request.0, StatusCode::OK)
+            .execute_storage_request_check_status(request.0, StatusCode::OK)
             .await?;
 
         Ok((&response).try_into()?)
@@ -1,6 +1,7 @@
 use crate::container::incomplete_vector_from_container_response;
 use crate::container::responses::ListContainersResponse;
 use crate::core::prelude::*;
+use crate::core::StorageHttpClient;
 use azure_core::headers::add_optional_header;
 use azure_core::headers::request_id_from_headers;
 use azure_core::prelude::*;
@@ -84,7 +85,7 @@ impl<'a> ListContainersBuilder<'a> {
             .storage_client
             .storage_account_client()
             .http_client()
-            .execute_request_check_status(request, StatusCode::OK)
+            .execute_storage_request_check_status(request, StatusCode::OK)
             .await?;
 
         debug!("response == {:?}", response);
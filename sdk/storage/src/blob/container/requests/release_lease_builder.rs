@@ -1,5 +1,6 @@
 use crate::blob::prelude::*;
 use crate::container::responses::ReleaseLeaseResponse;
+use crate::core::StorageHttpClient;
 use azure_core::headers::{add_mandatory_header, add_optional_header, LEASE_ACTION};
 use azure_core::prelude::*;
 use http::method::Method;
@@ -51,7 +52,7 @@ impl<'a> ReleaseLeaseBuilder<'a> {
         let response = self
             .container_lease_client
             .http_client()
-            .execute_request_check_status(request.0, StatusCode::OK)
+            .execute_storage_request_check_status(request.0, StatusCode::OK)
             .await?;
 
         Ok(ReleaseLeaseResponse::from_headers(response.headers())?)
@@ -1,5 +1,6 @@
 use crate::blob::prelude::*;
 use crate::container::responses::RenewLeaseResponse;
+use crate::core::StorageHttpClient;
 use azure_core::headers::{add_mandatory_header, add_optional_header, LEASE_ACTION};
 use azure_core::prelude::*;
 use http::method::Method;
@@ -46,7 +47,7 @@ impl<'a> RenewLeaseBuilder<'a> {
         let response = self
             .container_lease_client
             .http_client()
-            .execute_request_check_status(request.0, StatusCode::OK)
+            .execute_storage_request_check_status(request.0, StatusCode::OK)
             .await?;
 
         Ok(RenewLeaseResponse::from_headers(response.headers())?)
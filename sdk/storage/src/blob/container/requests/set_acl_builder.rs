@@ -1,4 +1,5 @@
 use crate::container::public_access_from_header;
+use crate::core::StorageHttpClient;
 use crate::{blob::prelude::*, StoredAccessPolicyList};
 use azure_core::headers::{add_mandatory_header, add_optional_header, add_optional_header_ref};
 use azure_core::prelude::*;
@@ -62,7 +63,7 @@ impl<'a> SetACLBuilder<'a> {
             .storage_client()
             .storage_account_client()
             .http_client()
-            .execute_request_check_status(request.0, StatusCode::OK)
+            .execute_storage_request_check_status(request.0, StatusCode::OK)
             .await?;
 
         Ok(public_access_from_header(response.headers())?)
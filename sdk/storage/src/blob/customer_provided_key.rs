@@ -0,0 +1,101 @@
+use crate::blob::headers::{ENCRYPTION_ALGORITHM, ENCRYPTION_KEY, ENCRYPTION_KEY_SHA256};
+use azure_core::AddAsHeader;
+use http::request::Builder;
+
+create_enum!(EncryptionAlgorithm, (AES256, "AES256"));
+
+/// An encryption key supplied by the caller for server side encryption.
+/// The service does not persist the key: the same key must be sent
+/// again to read the blob or its properties.
+/// See [https://docs.microsoft.com/azure/storage/blobs/encryption-customer-provided-keys](https://docs.microsoft.com/azure/storage/blobs/encryption-customer-provided-keys).
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomerProvidedKey {
+    key: String,
+    key_sha256: String,
+    algorithm: EncryptionAlgorithm,
+}
+
+impl CustomerProvidedKey {
+    /// Creates the key from its raw AES-256 bytes, computing the
+    /// base64 encoded key and its SHA-256 hash.
+    pub fn new(key: &[u8; 32]) -> Self {
+        let key_sha256 = ring::digest::digest(&ring::digest::SHA256, key);
+        Self {
+            key: base64::encode(key),
+            key_sha256: base64::encode(key_sha256.as_ref()),
+            algorithm: EncryptionAlgorithm::AES256,
+        }
+    }
+
+    /// Creates the key from its already base64 encoded value and hash.
+    pub fn from_base64(key: impl Into<String>, key_sha256: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            key_sha256: key_sha256.into(),
+            algorithm: EncryptionAlgorithm::AES256,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn key_sha256(&self) -> &str {
+        &self.key_sha256
+    }
+
+    pub fn algorithm(&self) -> EncryptionAlgorithm {
+        self.algorithm
+    }
+}
+
+impl AddAsHeader for CustomerProvidedKey {
+    fn add_as_header(&self, builder: Builder) -> Builder {
+        builder
+            .header(ENCRYPTION_KEY, &self.key)
+            .header(ENCRYPTION_KEY_SHA256, &self.key_sha256)
+            .header(ENCRYPTION_ALGORITHM, self.algorithm.as_ref())
+    }
+
+    fn add_as_header2(
+        &self,
+        request: &mut azure_core::Request,
+    ) -> Result<(), azure_core::HTTPHeaderError> {
+        request.headers_mut().append(
+            ENCRYPTION_KEY,
+            http::header::HeaderValue::from_str(&self.key)?,
+        );
+        request.headers_mut().append(
+            ENCRYPTION_KEY_SHA256,
+            http::header::HeaderValue::from_str(&self.key_sha256)?,
+        );
+        request.headers_mut().append(
+            ENCRYPTION_ALGORITHM,
+            http::header::HeaderValue::from_str(self.algorithm.as_ref())?,
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_sha256() {
+        let key = CustomerProvidedKey::new(&[0u8; 32]);
+        assert_eq!(key.key(), "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=");
+        assert_eq!(
+            key.key_sha256(),
+            "Zmh6rfhivXdsj8GLjp+OIAiXFIVu4jOzkCpZHQ1fKSU="
+        );
+
+        let request = key
+            .add_as_header(http::Request::builder())
+            .body(())
+            .unwrap();
+        assert_eq!(request.headers()[ENCRYPTION_ALGORITHM], "AES256");
+        assert_eq!(request.headers()[ENCRYPTION_KEY], key.key());
+    }
+}
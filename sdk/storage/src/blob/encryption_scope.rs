@@ -0,0 +1,101 @@
+use crate::blob::headers::{
+    DEFAULT_ENCRYPTION_SCOPE, DENY_ENCRYPTION_SCOPE_OVERRIDE, ENCRYPTION_SCOPE,
+};
+use azure_core::AddAsHeader;
+use http::request::Builder;
+
+/// A named encryption scope of the storage account used to encrypt
+/// the blob, instead of the account default key.
+/// See [https://docs.microsoft.com/azure/storage/blobs/encryption-scope-overview](https://docs.microsoft.com/azure/storage/blobs/encryption-scope-overview).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncryptionScope(String);
+
+impl EncryptionScope {
+    pub fn new(encryption_scope: String) -> Self {
+        Self(encryption_scope)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<S> From<S> for EncryptionScope
+where
+    S: Into<String>,
+{
+    fn from(encryption_scope: S) -> Self {
+        Self::new(encryption_scope.into())
+    }
+}
+
+impl AddAsHeader for EncryptionScope {
+    fn add_as_header(&self, builder: Builder) -> Builder {
+        builder.header(ENCRYPTION_SCOPE, &self.0)
+    }
+
+    fn add_as_header2(
+        &self,
+        request: &mut azure_core::Request,
+    ) -> Result<(), azure_core::HTTPHeaderError> {
+        request.headers_mut().append(
+            ENCRYPTION_SCOPE,
+            http::header::HeaderValue::from_str(&self.0)?,
+        );
+
+        Ok(())
+    }
+}
+
+/// The encryption scope applied to every blob written to a container.
+/// Unless `deny_override` is set, single requests can still specify a
+/// different `EncryptionScope`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DefaultEncryptionScope {
+    encryption_scope: EncryptionScope,
+    deny_override: bool,
+}
+
+impl DefaultEncryptionScope {
+    pub fn new(encryption_scope: impl Into<EncryptionScope>) -> Self {
+        Self {
+            encryption_scope: encryption_scope.into(),
+            deny_override: false,
+        }
+    }
+
+    setters! {
+        deny_override: bool => deny_override,
+    }
+
+    pub fn encryption_scope(&self) -> &EncryptionScope {
+        &self.encryption_scope
+    }
+}
+
+impl AddAsHeader for DefaultEncryptionScope {
+    fn add_as_header(&self, builder: Builder) -> Builder {
+        builder
+            .header(DEFAULT_ENCRYPTION_SCOPE, self.encryption_scope.as_str())
+            .header(
+                DENY_ENCRYPTION_SCOPE_OVERRIDE,
+                &self.deny_override.to_string(),
+            )
+    }
+
+    fn add_as_header2(
+        &self,
+        request: &mut azure_core::Request,
+    ) -> Result<(), azure_core::HTTPHeaderError> {
+        request.headers_mut().append(
+            DEFAULT_ENCRYPTION_SCOPE,
+            http::header::HeaderValue::from_str(self.encryption_scope.as_str())?,
+        );
+        request.headers_mut().append(
+            DENY_ENCRYPTION_SCOPE_OVERRIDE,
+            http::header::HeaderValue::from_str(&self.deny_override.to_string())?,
+        );
+
+        Ok(())
+    }
+}
@@ -1 +1,34 @@
 pub const REHYDRATE_PRIORITY: &str = "x-ms-rehydrate-priority";
+pub const ACCESS_TIER_INFERRED: &str = "x-ms-access-tier-inferred";
+pub const ACCESS_TIER_CHANGE_TIME: &str = "x-ms-access-tier-change-time";
+pub const ARCHIVE_STATUS: &str = "x-ms-archive-status";
+pub const ENCRYPTION_KEY: &str = "x-ms-encryption-key";
+pub const ENCRYPTION_KEY_SHA256: &str = "x-ms-encryption-key-sha256";
+pub const ENCRYPTION_ALGORITHM: &str = "x-ms-encryption-algorithm";
+pub const ENCRYPTION_SCOPE: &str = "x-ms-encryption-scope";
+pub const DEFAULT_ENCRYPTION_SCOPE: &str = "x-ms-default-encryption-scope";
+pub const DENY_ENCRYPTION_SCOPE_OVERRIDE: &str = "x-ms-deny-encryption-scope-override";
+
+pub(crate) fn encryption_key_sha256_from_headers_optional(
+    headers: &http::HeaderMap,
+) -> Result<Option<String>, crate::Error> {
+    Ok(headers
+        .get(ENCRYPTION_KEY_SHA256)
+        .map(|value| value.to_str())
+        .transpose()?
+        .map(|value| value.to_owned()))
+}
+pub const BLOB_CONTENT_TYPE: &str = "x-ms-blob-content-type";
+pub const BLOB_CONTENT_ENCODING: &str = "x-ms-blob-content-encoding";
+pub const BLOB_CONTENT_LANGUAGE: &str = "x-ms-blob-content-language";
+pub const BLOB_CONTENT_MD5: &str = "x-ms-blob-content-md5";
+pub const COPY_ACTION: &str = "x-ms-copy-action";
+pub const PREVIOUS_SNAPSHOT_URL: &str = "x-ms-previous-snapshot-url";
+pub const BLOB_SEALED: &str = "x-ms-blob-sealed";
+pub const IMMUTABILITY_POLICY_UNTIL_DATE: &str = "x-ms-immutability-policy-until-date";
+pub const IMMUTABILITY_POLICY_MODE: &str = "x-ms-immutability-policy-mode";
+pub const LEGAL_HOLD: &str = "x-ms-legal-hold";
+pub const VERSION_ID: &str = "x-ms-version-id";
+pub const IS_CURRENT_VERSION: &str = "x-ms-is-current-version";
+pub const IMMUTABLE_STORAGE_WITH_VERSIONING_ENABLED: &str =
+    "x-ms-immutable-storage-with-versioning-enabled";
@@ -0,0 +1,111 @@
+use crate::blob::headers::{IMMUTABILITY_POLICY_MODE, IMMUTABILITY_POLICY_UNTIL_DATE};
+use azure_core::AddAsHeader;
+use chrono::{DateTime, Utc};
+use http::request::Builder;
+
+use std::str::FromStr;
+
+/// The service is not consistent with the casing of the mode (headers
+/// use `Unlocked`, listings `unlocked`) so parsing ignores it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImmutabilityPolicyMode {
+    Unlocked,
+    Locked,
+}
+
+impl AsRef<str> for ImmutabilityPolicyMode {
+    fn as_ref(&self) -> &str {
+        match self {
+            ImmutabilityPolicyMode::Unlocked => "Unlocked",
+            ImmutabilityPolicyMode::Locked => "Locked",
+        }
+    }
+}
+
+impl FromStr for ImmutabilityPolicyMode {
+    type Err = azure_core::ParsingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("unlocked") {
+            Ok(ImmutabilityPolicyMode::Unlocked)
+        } else if s.eq_ignore_ascii_case("locked") {
+            Ok(ImmutabilityPolicyMode::Locked)
+        } else {
+            Err(azure_core::ParsingError::UnknownVariant {
+                item: "ImmutabilityPolicyMode",
+                variant: s.to_owned(),
+            })
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for ImmutabilityPolicyMode {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// A time based retention policy on a blob version. While the policy is
+/// in effect the blob cannot be modified or deleted. An `Unlocked` policy
+/// can still be shortened or removed, a `Locked` one can only be extended.
+/// See [https://docs.microsoft.com/azure/storage/blobs/immutable-policy-configure-version-scope](https://docs.microsoft.com/azure/storage/blobs/immutable-policy-configure-version-scope).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImmutabilityPolicy {
+    pub expiry: DateTime<Utc>,
+    pub mode: ImmutabilityPolicyMode,
+}
+
+impl ImmutabilityPolicy {
+    pub fn new(expiry: DateTime<Utc>, mode: ImmutabilityPolicyMode) -> Self {
+        Self { expiry, mode }
+    }
+}
+
+impl AddAsHeader for &ImmutabilityPolicy {
+    fn add_as_header(&self, builder: Builder) -> Builder {
+        builder
+            .header(
+                IMMUTABILITY_POLICY_UNTIL_DATE,
+                &self.expiry.to_rfc2822() as &str,
+            )
+            .header(IMMUTABILITY_POLICY_MODE, self.mode.as_ref())
+    }
+
+    fn add_as_header2(
+        &self,
+        request: &mut azure_core::Request,
+    ) -> Result<(), azure_core::HTTPHeaderError> {
+        request.headers_mut().append(
+            IMMUTABILITY_POLICY_UNTIL_DATE,
+            http::header::HeaderValue::from_str(&self.expiry.to_rfc2822())?,
+        );
+        request.headers_mut().append(
+            IMMUTABILITY_POLICY_MODE,
+            http::header::HeaderValue::from_str(self.mode.as_ref())?,
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mode_parsing_ignores_case() {
+        assert_eq!(
+            "unlocked".parse::<ImmutabilityPolicyMode>().unwrap(),
+            ImmutabilityPolicyMode::Unlocked
+        );
+        assert_eq!(
+            "Locked".parse::<ImmutabilityPolicyMode>().unwrap(),
+            ImmutabilityPolicyMode::Locked
+        );
+        assert!("Frozen".parse::<ImmutabilityPolicyMode>().is_err());
+    }
+}
@@ -1,28 +1,41 @@
 mod access_tier;
+mod avro;
 #[allow(clippy::module_inception)]
 pub mod blob;
 mod blob_content_md5;
+mod blob_http_headers;
+mod blob_service_properties;
 mod block_id;
+pub mod change_feed;
 mod clients;
 mod condition_append_position;
 mod condition_max_size;
 pub mod container;
+mod customer_provided_key;
 mod delete_snapshot_method;
+mod encryption_scope;
 mod hash;
 mod headers;
+mod immutability_policy;
 pub mod prelude;
+pub mod service;
 mod snapshot;
 mod version_id;
 
 pub use access_tier::AccessTier;
 use azure_core::{AddAsHeader, AppendToUrlQuery};
 pub use blob_content_md5::BlobContentMD5;
+pub use blob_http_headers::BlobHttpHeaders;
+pub use blob_service_properties::{BlobServiceProperties, StaticWebsite};
 pub use block_id::BlockId;
 pub use condition_append_position::ConditionAppendPosition;
 pub use condition_max_size::ConditionMaxSize;
+pub use customer_provided_key::{CustomerProvidedKey, EncryptionAlgorithm};
 pub use delete_snapshot_method::DeleteSnapshotsMethod;
+pub use encryption_scope::{DefaultEncryptionScope, EncryptionScope};
 pub use hash::Hash;
 use http::request::Builder;
+pub use immutability_policy::{ImmutabilityPolicy, ImmutabilityPolicyMode};
 pub use snapshot::Snapshot;
 pub use version_id::VersionId;
 
@@ -1,11 +1,18 @@
 pub use super::blob::{Blob, BlobBlockType, BlockList, BlockListType};
+pub use super::change_feed::{
+    BlobChangeFeedEvent, BlobChangeFeedEventData, BlobChangeFeedEventType, ChangeFeedCursor,
+    ChangeFeedRecord,
+};
 pub use super::container::PublicAccess;
 pub use crate::blob::clients::{
-    AsBlobClient, AsBlobLeaseClient, AsContainerClient, AsContainerLeaseClient, BlobClient,
-    BlobLeaseClient, ContainerClient, ContainerLeaseClient,
+    AsBlobClient, AsBlobLeaseClient, AsChangeFeedClient, AsContainerClient, AsContainerLeaseClient,
+    BlobClient, BlobLeaseClient, ChangeFeedClient, ContainerClient, ContainerLeaseClient,
 };
+#[cfg(feature = "client_side_encryption")]
+pub use crate::blob::clients::{AsEncryptedBlobClient, EncryptedBlobClient};
 pub use crate::{
-    AccessTier, BlobContentMD5, BlobVersioning, BlockId, ConditionAppendPosition, ConditionMaxSize,
-    DeleteSnapshotsMethod, Hash, RehydratePriority, Snapshot, StoredAccessPolicy,
-    StoredAccessPolicyList, VersionId,
+    AccessTier, BlobContentMD5, BlobHttpHeaders, BlobVersioning, BlockId, ConditionAppendPosition,
+    ConditionMaxSize, CustomerProvidedKey, DefaultEncryptionScope, DeleteSnapshotsMethod,
+    EncryptionAlgorithm, EncryptionScope, Hash, ImmutabilityPolicy, ImmutabilityPolicyMode,
+    RehydratePriority, Snapshot, StoredAccessPolicy, StoredAccessPolicyList, VersionId,
 };
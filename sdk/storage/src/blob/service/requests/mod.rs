@@ -0,0 +1,6 @@
+mod get_blob_service_properties_builder;
+mod get_blob_service_stats_builder;
+mod set_blob_service_properties_builder;
+pub use get_blob_service_properties_builder::GetBlobServicePropertiesBuilder;
+pub use get_blob_service_stats_builder::GetBlobServiceStatsBuilder;
+pub use set_blob_service_properties_builder::SetBlobServicePropertiesBuilder;
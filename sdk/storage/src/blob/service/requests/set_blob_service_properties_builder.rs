@@ -0,0 +1,74 @@
+use crate::blob::service::responses::SetBlobServicePropertiesResponse;
+use crate::core::prelude::*;
+use crate::core::StorageHttpClient;
+use crate::BlobServiceProperties;
+use azure_core::headers::add_optional_header;
+use azure_core::prelude::*;
+use http::method::Method;
+use http::status::StatusCode;
+use std::convert::TryInto;
+
+#[derive(Debug, Clone)]
+pub struct SetBlobServicePropertiesBuilder<'a> {
+    storage_client: &'a StorageClient,
+    blob_service_properties: &'a BlobServiceProperties,
+    timeout: Option<Timeout>,
+    client_request_id: Option<ClientRequestId<'a>>,
+}
+
+impl<'a> SetBlobServicePropertiesBuilder<'a> {
+    pub(crate) fn new(
+        storage_client: &'a StorageClient,
+        blob_service_properties: &'a BlobServiceProperties,
+    ) -> Self {
+        Self {
+            storage_client,
+            blob_service_properties,
+            timeout: None,
+            client_request_id: None,
+        }
+    }
+
+    setters! {
+        timeout: Timeout => Some(timeout),
+        client_request_id: ClientRequestId<'a> => Some(client_request_id),
+    }
+
+    pub async fn execute(
+        &self,
+    ) -> Result<SetBlobServicePropertiesResponse, Box<dyn std::error::Error + Sync + Send>> {
+        let mut url = self
+            .storage_client
+            .storage_account_client()
+            .blob_storage_url()
+            .to_owned();
+
+        url.query_pairs_mut().append_pair("restype", "service");
+        url.query_pairs_mut().append_pair("comp", "properties");
+
+        self.timeout.append_to_url_query(&mut url);
+
+        trace!("url == {}", url);
+
+        let xml_body = self.blob_service_properties.to_xml();
+        debug!("xml about to be sent == {}", xml_body);
+
+        let request = self.storage_client.prepare_request(
+            url.as_str(),
+            &Method::PUT,
+            &|mut request| {
+                request = add_optional_header(&self.client_request_id, request);
+                request
+            },
+            Some(xml_body.into()),
+        )?;
+
+        let response = self
+            .storage_client
+            .http_client()
+            .execute_storage_request_check_status(request.0, StatusCode::ACCEPTED)
+            .await?;
+
+        Ok((&response).try_into()?)
+    }
+}
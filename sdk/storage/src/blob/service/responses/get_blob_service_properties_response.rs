@@ -0,0 +1,29 @@
+use crate::{xml::read_xml, BlobServiceProperties};
+use azure_core::headers::CommonStorageResponseHeaders;
+use bytes::Bytes;
+use http::response::Response;
+use std::convert::TryInto;
+
+#[derive(Debug, Clone)]
+pub struct GetBlobServicePropertiesResponse {
+    pub common_storage_response_headers: CommonStorageResponseHeaders,
+    pub blob_service_properties: BlobServiceProperties,
+}
+
+impl std::convert::TryFrom<&Response<Bytes>> for GetBlobServicePropertiesResponse {
+    type Error = crate::Error;
+
+    fn try_from(response: &Response<Bytes>) -> Result<Self, Self::Error> {
+        let headers = response.headers();
+        let body = response.body();
+
+        debug!("headers == {:?}", headers);
+        debug!("body == {:#?}", body);
+        let blob_service_properties: BlobServiceProperties = read_xml(body)?;
+
+        Ok(GetBlobServicePropertiesResponse {
+            common_storage_response_headers: headers.try_into()?,
+            blob_service_properties,
+        })
+    }
+}
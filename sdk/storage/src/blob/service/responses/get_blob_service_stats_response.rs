@@ -0,0 +1,54 @@
+use crate::core::GeoReplicationStatus;
+use crate::xml::read_xml;
+use azure_core::headers::CommonStorageResponseHeaders;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use http::response::Response;
+use std::convert::TryInto;
+
+#[derive(Debug, Clone)]
+pub struct GetBlobServiceStatsResponse {
+    pub common_storage_response_headers: CommonStorageResponseHeaders,
+    pub status: GeoReplicationStatus,
+    pub last_sync_time: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GetBlobServiceStatsResponseInternal {
+    pub geo_replication: GeoReplication,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GeoReplication {
+    pub status: GeoReplicationStatus,
+    pub last_sync_time: Option<String>,
+}
+
+impl std::convert::TryFrom<&Response<Bytes>> for GetBlobServiceStatsResponse {
+    type Error = crate::Error;
+
+    fn try_from(response: &Response<Bytes>) -> Result<Self, Self::Error> {
+        let headers = response.headers();
+        let body = response.body();
+
+        debug!("headers == {:?}", headers);
+        debug!("body == {:#?}", body);
+        let response: GetBlobServiceStatsResponseInternal = read_xml(body)?;
+        debug!("deserde == {:#?}", response);
+
+        Ok(GetBlobServiceStatsResponse {
+            common_storage_response_headers: headers.try_into()?,
+            status: response.geo_replication.status,
+            last_sync_time: response
+                .geo_replication
+                .last_sync_time
+                // the service returns an empty element if no sync happened yet
+                .filter(|t| !t.is_empty())
+                .map(|t| DateTime::parse_from_rfc2822(&t))
+                .transpose()?
+                .map(|t| t.with_timezone(&Utc)),
+        })
+    }
+}
@@ -0,0 +1,6 @@
+mod get_blob_service_properties_response;
+mod get_blob_service_stats_response;
+mod set_blob_service_properties_response;
+pub use get_blob_service_properties_response::GetBlobServicePropertiesResponse;
+pub use get_blob_service_stats_response::GetBlobServiceStatsResponse;
+pub use set_blob_service_properties_response::SetBlobServicePropertiesResponse;
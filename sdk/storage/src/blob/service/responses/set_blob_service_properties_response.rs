@@ -0,0 +1,21 @@
+use azure_core::headers::CommonStorageResponseHeaders;
+use bytes::Bytes;
+use http::response::Response;
+use std::convert::TryInto;
+
+#[derive(Debug, Clone)]
+pub struct SetBlobServicePropertiesResponse {
+    pub common_storage_response_headers: CommonStorageResponseHeaders,
+}
+
+impl std::convert::TryFrom<&Response<Bytes>> for SetBlobServicePropertiesResponse {
+    type Error = crate::Error;
+
+    fn try_from(response: &Response<Bytes>) -> Result<Self, Self::Error> {
+        debug!("response == {:?}", response);
+
+        Ok(SetBlobServicePropertiesResponse {
+            common_storage_response_headers: response.headers().try_into()?,
+        })
+    }
+}
@@ -12,6 +12,10 @@ impl Snapshot {
     pub fn new(snapshot: String) -> Self {
         Self(snapshot)
     }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
 }
 
 impl AppendToUrlQuery for &Snapshot {
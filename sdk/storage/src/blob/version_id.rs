@@ -12,11 +12,15 @@ impl VersionId {
     pub fn new(version_id: String) -> Self {
         Self(version_id)
     }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
 }
 
 impl AppendToUrlQuery for &VersionId {
     fn append_to_url_query(&self, url: &mut url::Url) {
-        url.query_pairs_mut().append_pair("version_id", &self.0);
+        url.query_pairs_mut().append_pair("versionid", &self.0);
     }
 }
 
@@ -28,3 +32,18 @@ where
         Self::new(version_id.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_id_query_parameter() {
+        let mut url = url::Url::parse("https://account.blob.core.windows.net/c/b").unwrap();
+        (&VersionId::new("2021-10-25T05:41:32.5526810Z".to_owned())).append_to_url_query(&mut url);
+        assert_eq!(
+            url.query(),
+            Some("versionid=2021-10-25T05%3A41%3A32.5526810Z")
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use super::KeyWrapAlgorithm;
+
+/// The encryption metadata stored in the `encryptiondata` metadata of a
+/// blob or in the envelope of a queue message. The layout follows the
+/// version 2.0 of the Azure Storage client side encryption protocol.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct EncryptionData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encryption_mode: Option<String>,
+    pub wrapped_content_key: WrappedContentKey,
+    pub encryption_agent: EncryptionAgent,
+    pub encrypted_region_info: EncryptedRegionInfo,
+    #[serde(default)]
+    pub key_wrapping_metadata: std::collections::HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct WrappedContentKey {
+    pub key_id: String,
+    pub encrypted_key: String,
+    pub algorithm: KeyWrapAlgorithm,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct EncryptionAgent {
+    pub protocol: String,
+    pub encryption_algorithm: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct EncryptedRegionInfo {
+    pub data_length: u64,
+    pub nonce_length: u64,
+}
+
+impl EncryptedRegionInfo {
+    /// Length of an encrypted region: nonce, ciphertext and tag.
+    pub fn encrypted_region_length(&self) -> u64 {
+        self.nonce_length + self.data_length + super::TAG_LENGTH as u64
+    }
+}
@@ -0,0 +1,43 @@
+use async_trait::async_trait;
+
+create_enum!(
+    KeyWrapAlgorithm,
+    (RsaOaep, "RSA-OAEP"),
+    (RsaOaep256, "RSA-OAEP-256"),
+    (A256KW, "A256KW")
+);
+
+/// A key encryption key (KEK) wraps the random content encryption key
+/// generated for every encrypted blob or message. The plaintext content
+/// key never leaves the client, only its wrapped form is stored with the
+/// data.
+///
+/// Implement this trait to delegate the wrapping to a remote key store,
+/// or use the local [`RsaKeyEncryptionKey`](crate::RsaKeyEncryptionKey).
+/// This crate does not depend on Azure Key Vault: wrapping with a Key
+/// Vault key means implementing this trait on top of the `wrap_key` and
+/// `unwrap_key` operations of its `CryptographyClient`.
+#[async_trait]
+pub trait KeyEncryptionKey: std::fmt::Debug + Send + Sync {
+    /// The identifier stored along the content keys wrapped by `wrap_key`.
+    fn key_id(&self) -> &str;
+
+    /// The algorithm used to wrap new content keys.
+    fn wrap_algorithm(&self) -> KeyWrapAlgorithm;
+
+    async fn wrap_key(
+        &self,
+        algorithm: KeyWrapAlgorithm,
+        key: &[u8],
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// Unwraps a content key wrapped by the key `key_id`. It is up to the
+    /// implementation to accept or reject the identifier, for instance to
+    /// unwrap content keys wrapped by a previous version of a rotated key.
+    async fn unwrap_key(
+        &self,
+        key_id: &str,
+        algorithm: KeyWrapAlgorithm,
+        encrypted_key: &[u8],
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>>;
+}
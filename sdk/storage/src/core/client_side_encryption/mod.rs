@@ -0,0 +1,306 @@
+//! Client side envelope encryption, following the version 2.0 of the
+//! Azure Storage client side encryption protocol.
+//!
+//! Every blob or message is encrypted with its own random AES-256 content
+//! key. The data is split in regions of 4 MiB, each one encrypted with
+//! AES-GCM and its own nonce, so ranges can be decrypted without
+//! downloading the whole blob. The content key is wrapped by a
+//! [`KeyEncryptionKey`] and stored, with the other encryption settings,
+//! as [`EncryptionData`].
+//! See [https://docs.microsoft.com/azure/storage/common/storage-client-side-encryption](https://docs.microsoft.com/azure/storage/common/storage-client-side-encryption).
+
+mod encryption_data;
+mod key_encryption_key;
+mod rsa_key_encryption_key;
+
+pub use encryption_data::{
+    EncryptedRegionInfo, EncryptionAgent, EncryptionData, WrappedContentKey,
+};
+pub use key_encryption_key::{KeyEncryptionKey, KeyWrapAlgorithm};
+pub use rsa_key_encryption_key::RsaKeyEncryptionKey;
+
+use azure_core::prelude::Range;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM};
+use ring::rand::{SecureRandom, SystemRandom};
+
+/// Name of the blob metadata holding the serialized `EncryptionData`.
+pub(crate) const ENCRYPTION_DATA_METADATA: &str = "encryptiondata";
+
+const PROTOCOL: &str = "2.0";
+const CONTENT_ENCRYPTION_ALGORITHM: &str = "AES_GCM_256";
+const REGION_LENGTH: u64 = 4 * 1024 * 1024;
+const NONCE_LENGTH: usize = 12;
+const TAG_LENGTH: usize = 16;
+const CONTENT_KEY_LENGTH: usize = 32;
+// the protocol version is wrapped together with the content key,
+// padded with zeros to 8 bytes
+const WRAPPED_PROTOCOL_LENGTH: usize = 8;
+
+fn encryption_error(message: impl Into<String>) -> crate::Error {
+    crate::Error::ClientSideEncryptionError(message.into())
+}
+
+/// Encrypts `plaintext` with a new content key, wrapped by
+/// `key_encryption_key`.
+pub(crate) async fn encrypt(
+    key_encryption_key: &dyn KeyEncryptionKey,
+    plaintext: &[u8],
+    encryption_mode: Option<&str>,
+) -> Result<(Vec<u8>, EncryptionData), Box<dyn std::error::Error + Send + Sync>> {
+    let rng = SystemRandom::new();
+
+    let mut content_key = [0u8; CONTENT_KEY_LENGTH];
+    rng.fill(&mut content_key)
+        .map_err(|_| encryption_error("cannot generate the content key"))?;
+
+    let region_info = EncryptedRegionInfo {
+        data_length: REGION_LENGTH,
+        nonce_length: NONCE_LENGTH as u64,
+    };
+    let encrypted = encrypt_regions(&rng, &content_key, plaintext, &region_info)?;
+
+    let mut key_payload = Vec::with_capacity(WRAPPED_PROTOCOL_LENGTH + CONTENT_KEY_LENGTH);
+    key_payload.extend_from_slice(PROTOCOL.as_bytes());
+    key_payload.resize(WRAPPED_PROTOCOL_LENGTH, 0);
+    key_payload.extend_from_slice(&content_key);
+
+    let algorithm = key_encryption_key.wrap_algorithm();
+    let encrypted_key = key_encryption_key.wrap_key(algorithm, &key_payload).await?;
+
+    let mut key_wrapping_metadata = std::collections::HashMap::new();
+    key_wrapping_metadata.insert(
+        "EncryptionLibrary".to_owned(),
+        format!("Rust {}", env!("CARGO_PKG_VERSION")),
+    );
+
+    let encryption_data = EncryptionData {
+        encryption_mode: encryption_mode.map(|mode| mode.to_owned()),
+        wrapped_content_key: WrappedContentKey {
+            key_id: key_encryption_key.key_id().to_owned(),
+            encrypted_key: base64::encode(encrypted_key),
+            algorithm,
+        },
+        encryption_agent: EncryptionAgent {
+            protocol: PROTOCOL.to_owned(),
+            encryption_algorithm: CONTENT_ENCRYPTION_ALGORITHM.to_owned(),
+        },
+        encrypted_region_info: region_info,
+        key_wrapping_metadata,
+    };
+
+    Ok((encrypted, encryption_data))
+}
+
+/// Unwraps the content key described by `encryption_data`.
+pub(crate) async fn unwrap_content_key(
+    key_encryption_key: &dyn KeyEncryptionKey,
+    encryption_data: &EncryptionData,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let agent = &encryption_data.encryption_agent;
+    if agent.protocol != PROTOCOL || agent.encryption_algorithm != CONTENT_ENCRYPTION_ALGORITHM {
+        return Err(encryption_error(format!(
+            "unsupported encryption protocol {} with algorithm {}",
+            agent.protocol, agent.encryption_algorithm
+        ))
+        .into());
+    }
+
+    let wrapped_content_key = &encryption_data.wrapped_content_key;
+    let encrypted_key = base64::decode(&wrapped_content_key.encrypted_key)?;
+    let key_payload = key_encryption_key
+        .unwrap_key(
+            &wrapped_content_key.key_id,
+            wrapped_content_key.algorithm,
+            &encrypted_key,
+        )
+        .await?;
+
+    if key_payload.len() != WRAPPED_PROTOCOL_LENGTH + CONTENT_KEY_LENGTH
+        || !key_payload.starts_with(PROTOCOL.as_bytes())
+    {
+        return Err(encryption_error("the unwrapped content key is malformed").into());
+    }
+
+    Ok(key_payload[WRAPPED_PROTOCOL_LENGTH..].to_vec())
+}
+
+fn content_key(key: &[u8]) -> Result<LessSafeKey, crate::Error> {
+    let key = UnboundKey::new(&AES_256_GCM, key)
+        .map_err(|_| encryption_error("the content key must be 32 bytes long"))?;
+    Ok(LessSafeKey::new(key))
+}
+
+fn encrypt_regions(
+    rng: &dyn SecureRandom,
+    key: &[u8],
+    plaintext: &[u8],
+    region_info: &EncryptedRegionInfo,
+) -> Result<Vec<u8>, crate::Error> {
+    let key = content_key(key)?;
+    let regions = plaintext.chunks(region_info.data_length as usize);
+    let mut encrypted =
+        Vec::with_capacity(plaintext.len() + regions.len() * (NONCE_LENGTH + TAG_LENGTH));
+
+    for region in regions {
+        let mut nonce = [0u8; NONCE_LENGTH];
+        rng.fill(&mut nonce)
+            .map_err(|_| encryption_error("cannot generate the nonce"))?;
+
+        let mut in_out = region.to_vec();
+        key.seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::empty(),
+            &mut in_out,
+        )
+        .map_err(|_| encryption_error("AES-GCM encryption failed"))?;
+
+        encrypted.extend_from_slice(&nonce);
+        encrypted.extend_from_slice(&in_out);
+    }
+
+    Ok(encrypted)
+}
+
+/// Decrypts a sequence of whole encrypted regions.
+pub(crate) fn decrypt_regions(
+    key: &[u8],
+    encrypted: &[u8],
+    region_info: &EncryptedRegionInfo,
+) -> Result<Vec<u8>, crate::Error> {
+    if region_info.nonce_length != NONCE_LENGTH as u64 {
+        return Err(encryption_error(format!(
+            "unsupported nonce length {}",
+            region_info.nonce_length
+        )));
+    }
+
+    let key = content_key(key)?;
+    let mut plaintext = Vec::with_capacity(encrypted.len());
+
+    for region in encrypted.chunks(region_info.encrypted_region_length() as usize) {
+        if region.len() < NONCE_LENGTH + TAG_LENGTH {
+            return Err(encryption_error("truncated encrypted region"));
+        }
+        let (nonce, ciphertext) = region.split_at(NONCE_LENGTH);
+        let nonce = Nonce::try_assume_unique_for_key(nonce)
+            .map_err(|_| encryption_error("invalid nonce"))?;
+
+        let mut in_out = ciphertext.to_vec();
+        let decrypted = key
+            .open_in_place(nonce, Aad::empty(), &mut in_out)
+            .map_err(|_| encryption_error("AES-GCM authentication failed"))?;
+        plaintext.extend_from_slice(decrypted);
+    }
+
+    Ok(plaintext)
+}
+
+/// Returns the range of encrypted regions covering the plaintext `range`,
+/// and the offset of `range.start` in the decrypted regions.
+pub(crate) fn encrypted_range(range: Range, region_info: &EncryptedRegionInfo) -> (Range, u64) {
+    let first_region = range.start / region_info.data_length;
+    let last_region = (range.end.max(range.start + 1) - 1) / region_info.data_length;
+    let encrypted_region_length = region_info.encrypted_region_length();
+
+    (
+        Range::new(
+            first_region * encrypted_region_length,
+            (last_region + 1) * encrypted_region_length,
+        ),
+        range.start - first_region * region_info.data_length,
+    )
+}
+
+/// Returns the plaintext length of `encrypted_length` bytes of regions.
+pub(crate) fn plaintext_length(encrypted_length: u64, region_info: &EncryptedRegionInfo) -> u64 {
+    let encrypted_region_length = region_info.encrypted_region_length();
+    let overhead = encrypted_region_length - region_info.data_length;
+
+    (encrypted_length / encrypted_region_length) * region_info.data_length
+        + (encrypted_length % encrypted_region_length).saturating_sub(overhead)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SMALL_REGIONS: EncryptedRegionInfo = EncryptedRegionInfo {
+        data_length: 10,
+        nonce_length: NONCE_LENGTH as u64,
+    };
+
+    #[test]
+    fn regions_round_trip() {
+        let key = [7u8; CONTENT_KEY_LENGTH];
+        let plaintext = b"the quick brown fox jumps over the lazy dog";
+
+        let encrypted =
+            encrypt_regions(&SystemRandom::new(), &key, plaintext, &SMALL_REGIONS).unwrap();
+        assert_eq!(
+            encrypted.len(),
+            plaintext.len() + 5 * (NONCE_LENGTH + TAG_LENGTH)
+        );
+        assert_eq!(
+            plaintext_length(encrypted.len() as u64, &SMALL_REGIONS),
+            plaintext.len() as u64
+        );
+
+        let decrypted = decrypt_regions(&key, &encrypted, &SMALL_REGIONS).unwrap();
+        assert_eq!(decrypted, plaintext);
+
+        // a range spanning the second and third regions
+        let (range, offset) = encrypted_range(Range::new(16, 24), &SMALL_REGIONS);
+        assert_eq!(range, Range::new(38, 114));
+        assert_eq!(offset, 6);
+        let decrypted = decrypt_regions(
+            &key,
+            &encrypted[range.start as usize..range.end as usize],
+            &SMALL_REGIONS,
+        )
+        .unwrap();
+        assert_eq!(
+            &decrypted[offset as usize..offset as usize + 8],
+            &plaintext[16..24]
+        );
+
+        let mut tampered = encrypted;
+        tampered[NONCE_LENGTH] ^= 1;
+        assert!(decrypt_regions(&key, &tampered, &SMALL_REGIONS).is_err());
+    }
+
+    #[tokio::test]
+    async fn rsa_envelope_round_trip() {
+        let private_key = rsa::RsaPrivateKey::new(&mut rand::rngs::OsRng, 1024).unwrap();
+        let key_encryption_key = RsaKeyEncryptionKey::new("local-key", private_key);
+
+        let (encrypted, encryption_data) =
+            encrypt(&key_encryption_key, b"secret", Some("FullBlob"))
+                .await
+                .unwrap();
+        assert_eq!(encryption_data.wrapped_content_key.key_id, "local-key");
+        assert_eq!(
+            encryption_data.wrapped_content_key.algorithm,
+            KeyWrapAlgorithm::RsaOaep
+        );
+
+        let serialized = serde_json::to_string(&encryption_data).unwrap();
+        let encryption_data: EncryptionData = serde_json::from_str(&serialized).unwrap();
+
+        let content_key = unwrap_content_key(&key_encryption_key, &encryption_data)
+            .await
+            .unwrap();
+        let decrypted = decrypt_regions(
+            &content_key,
+            &encrypted,
+            &encryption_data.encrypted_region_info,
+        )
+        .unwrap();
+        assert_eq!(decrypted, b"secret");
+
+        let mut rotated = encryption_data;
+        rotated.wrapped_content_key.key_id = "other-key".to_owned();
+        assert!(unwrap_content_key(&key_encryption_key, &rotated)
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,87 @@
+use super::{KeyEncryptionKey, KeyWrapAlgorithm};
+use async_trait::async_trait;
+use rsa::pkcs1::DecodeRsaPrivateKey;
+use rsa::pkcs8::DecodePrivateKey;
+use rsa::{PaddingScheme, PublicKey, RsaPrivateKey, RsaPublicKey};
+
+/// A key encryption key backed by a local RSA private key. Content keys
+/// are wrapped with RSA-OAEP (SHA-1) by default, matching the other Azure
+/// SDKs, or with RSA-OAEP-256.
+#[derive(Debug, Clone)]
+pub struct RsaKeyEncryptionKey {
+    key_id: String,
+    private_key: RsaPrivateKey,
+    wrap_algorithm: KeyWrapAlgorithm,
+}
+
+impl RsaKeyEncryptionKey {
+    pub fn new(key_id: impl Into<String>, private_key: RsaPrivateKey) -> Self {
+        Self {
+            key_id: key_id.into(),
+            private_key,
+            wrap_algorithm: KeyWrapAlgorithm::RsaOaep,
+        }
+    }
+
+    /// Loads the private key from a PEM file, either PKCS#1
+    /// (`BEGIN RSA PRIVATE KEY`) or PKCS#8 (`BEGIN PRIVATE KEY`).
+    pub fn from_pem(key_id: impl Into<String>, pem: &str) -> Result<Self, crate::Error> {
+        let private_key = RsaPrivateKey::from_pkcs1_pem(pem)
+            .or_else(|_| RsaPrivateKey::from_pkcs8_pem(pem))
+            .map_err(|err| crate::Error::ClientSideEncryptionError(err.to_string()))?;
+        Ok(Self::new(key_id, private_key))
+    }
+
+    setters! {
+        wrap_algorithm: KeyWrapAlgorithm => wrap_algorithm,
+    }
+
+    fn padding(algorithm: KeyWrapAlgorithm) -> Result<PaddingScheme, crate::Error> {
+        match algorithm {
+            KeyWrapAlgorithm::RsaOaep => Ok(PaddingScheme::new_oaep::<sha1::Sha1>()),
+            KeyWrapAlgorithm::RsaOaep256 => Ok(PaddingScheme::new_oaep::<sha2::Sha256>()),
+            algorithm => Err(crate::Error::ClientSideEncryptionError(format!(
+                "key wrap algorithm {} is not supported by RSA keys",
+                algorithm
+            ))),
+        }
+    }
+}
+
+#[async_trait]
+impl KeyEncryptionKey for RsaKeyEncryptionKey {
+    fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    fn wrap_algorithm(&self) -> KeyWrapAlgorithm {
+        self.wrap_algorithm
+    }
+
+    async fn wrap_key(
+        &self,
+        algorithm: KeyWrapAlgorithm,
+        key: &[u8],
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+        let public_key = RsaPublicKey::from(&self.private_key);
+        Ok(public_key.encrypt(&mut rand::rngs::OsRng, Self::padding(algorithm)?, key)?)
+    }
+
+    async fn unwrap_key(
+        &self,
+        key_id: &str,
+        algorithm: KeyWrapAlgorithm,
+        encrypted_key: &[u8],
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+        if key_id != self.key_id {
+            return Err(crate::Error::ClientSideEncryptionError(format!(
+                "the data was encrypted with key {}, not with {}",
+                key_id, self.key_id
+            ))
+            .into());
+        }
+        Ok(self
+            .private_key
+            .decrypt(Self::padding(algorithm)?, encrypted_key)?)
+    }
+}
@@ -49,10 +49,12 @@ pub struct StorageAccountClient {
     storage_credentials: StorageCredentials,
     http_client: Arc<dyn HttpClient>,
     blob_storage_url: Url,
+    blob_storage_secondary_url: Option<Url>,
     file_storage_url: Url,
     table_storage_url: Url,
+    table_storage_secondary_url: Option<Url>,
     queue_storage_url: Url,
-    queue_storage_secondary_url: Url,
+    queue_storage_secondary_url: Option<Url>,
     filesystem_url: Url,
 }
 
@@ -85,25 +87,38 @@ impl StorageAccountClient {
     {
         let account = account.into();
 
-        Arc::new(Self {
-            blob_storage_url: Url::parse(&format!("https://{}.blob.core.windows.net", &account))
+        Arc::new(
+            Self {
+                blob_storage_url: Url::parse(&format!(
+                    "https://{}.blob.core.windows.net",
+                    &account
+                ))
                 .unwrap(),
-            table_storage_url: Url::parse(&format!("https://{}.table.core.windows.net", &account))
+                blob_storage_secondary_url: None,
+                table_storage_url: Url::parse(&format!(
+                    "https://{}.table.core.windows.net",
+                    &account
+                ))
                 .unwrap(),
-            file_storage_url:  Url::parse(&format!("https://{}.file.core.windows.net", &account))
+                table_storage_secondary_url: None,
+                file_storage_url: Url::parse(&format!(
+                    "https://{}.file.core.windows.net",
+                    &account
+                ))
                 .unwrap(),
-            queue_storage_url: Url::parse(&format!("https://{}.queue.core.windows.net", &account))
+                queue_storage_url: Url::parse(&format!(
+                    "https://{}.queue.core.windows.net",
+                    &account
+                ))
                 .unwrap(),
-            queue_storage_secondary_url: Url::parse(&format!(
-                "https://{}-secondary.queue.core.windows.net",
-                &account
-            ))
-            .unwrap(),
-            filesystem_url: Url::parse(&format!("https://{}.dfs.core.windows.net", &account))
-                .unwrap(),
-            storage_credentials: StorageCredentials::Key(account, key.into()),
-            http_client,
-        })
+                queue_storage_secondary_url: None,
+                filesystem_url: Url::parse(&format!("https://{}.dfs.core.windows.net", &account))
+                    .unwrap(),
+                storage_credentials: StorageCredentials::Key(account.clone(), key.into()),
+                http_client,
+            }
+            .with_secondary_urls(&account),
+        )
     }
 
     /// Create a new client for customized emulator endpoints.
@@ -163,7 +178,7 @@ impl StorageAccountClient {
         let blob_storage_url =
             Url::parse(&format!("{}{}", blob_storage_url.as_str(), account)).unwrap();
         let file_storage_url =
-            Url::parse(&format!("{}{}", file_storage_url.as_str(), account)).unwrap();        
+            Url::parse(&format!("{}{}", file_storage_url.as_str(), account)).unwrap();
         let table_storage_url =
             Url::parse(&format!("{}{}", table_storage_url.as_str(), account)).unwrap();
         let queue_storage_url =
@@ -171,16 +186,21 @@ impl StorageAccountClient {
         let filesystem_url =
             Url::parse(&format!("{}{}", filesystem_url.as_str(), account)).unwrap();
 
-        Arc::new(Self {
-            blob_storage_url,
-            file_storage_url,
-            table_storage_url,
-            queue_storage_url: queue_storage_url.clone(),
-            queue_storage_secondary_url: queue_storage_url,
-            filesystem_url,
-            storage_credentials: StorageCredentials::Key(account, key.into()),
-            http_client,
-        })
+        Arc::new(
+            Self {
+                blob_storage_url,
+                blob_storage_secondary_url: None,
+                file_storage_url,
+                table_storage_url,
+                table_storage_secondary_url: None,
+                queue_storage_url,
+                queue_storage_secondary_url: None,
+                filesystem_url,
+                storage_credentials: StorageCredentials::Key(account.clone(), key.into()),
+                http_client,
+            }
+            .with_secondary_urls(&account),
+        )
     }
 
     pub fn new_sas_token<A, S>(
@@ -194,21 +214,35 @@ impl StorageAccountClient {
     {
         let account = account.into();
 
-        Ok(Arc::new(Self {
-            blob_storage_url: Url::parse(&format!("https://{}.blob.core.windows.net", &account))?,
-            file_storage_url: Url::parse(&format!("https://{}.file.core.windows.net", &account))?,
-            table_storage_url: Url::parse(&format!("https://{}.table.core.windows.net", &account))?,
-            queue_storage_url: Url::parse(&format!("https://{}.queue.core.windows.net", &account))?,
-            queue_storage_secondary_url: Url::parse(&format!(
-                "https://{}-secondary.queue.core.windows.net",
-                &account
-            ))?,
-            filesystem_url: Url::parse(&format!("https://{}.dfs.core.windows.net", &account))?,
-            storage_credentials: StorageCredentials::SASToken(get_sas_token_parms(
-                sas_token.as_ref(),
-            )?),
-            http_client,
-        }))
+        Ok(Arc::new(
+            Self {
+                blob_storage_url: Url::parse(&format!(
+                    "https://{}.blob.core.windows.net",
+                    &account
+                ))?,
+                blob_storage_secondary_url: None,
+                file_storage_url: Url::parse(&format!(
+                    "https://{}.file.core.windows.net",
+                    &account
+                ))?,
+                table_storage_url: Url::parse(&format!(
+                    "https://{}.table.core.windows.net",
+                    &account
+                ))?,
+                table_storage_secondary_url: None,
+                queue_storage_url: Url::parse(&format!(
+                    "https://{}.queue.core.windows.net",
+                    &account
+                ))?,
+                queue_storage_secondary_url: None,
+                filesystem_url: Url::parse(&format!("https://{}.dfs.core.windows.net", &account))?,
+                storage_credentials: StorageCredentials::SASToken(get_sas_token_parms(
+                    sas_token.as_ref(),
+                )?),
+                http_client,
+            }
+            .with_secondary_urls(&account),
+        ))
     }
 
     pub fn new_bearer_token<A, BT>(
@@ -223,25 +257,38 @@ impl StorageAccountClient {
         let account = account.into();
         let bearer_token = bearer_token.into();
 
-        Arc::new(Self {
-            blob_storage_url: Url::parse(&format!("https://{}.blob.core.windows.net", &account))
-                .unwrap(),
-            file_storage_url: Url::parse(&format!("https://{}.file.core.windows.net", &account))
+        Arc::new(
+            Self {
+                blob_storage_url: Url::parse(&format!(
+                    "https://{}.blob.core.windows.net",
+                    &account
+                ))
                 .unwrap(),
-            table_storage_url: Url::parse(&format!("https://{}.table.core.windows.net", &account))
+                blob_storage_secondary_url: None,
+                file_storage_url: Url::parse(&format!(
+                    "https://{}.file.core.windows.net",
+                    &account
+                ))
                 .unwrap(),
-            queue_storage_url: Url::parse(&format!("https://{}.queue.core.windows.net", &account))
+                table_storage_url: Url::parse(&format!(
+                    "https://{}.table.core.windows.net",
+                    &account
+                ))
                 .unwrap(),
-            queue_storage_secondary_url: Url::parse(&format!(
-                "https://{}-secondary.queue.core.windows.net",
-                &account
-            ))
-            .unwrap(),
-            filesystem_url: Url::parse(&format!("https://{}.dfs.core.windows.net", &account))
+                table_storage_secondary_url: None,
+                queue_storage_url: Url::parse(&format!(
+                    "https://{}.queue.core.windows.net",
+                    &account
+                ))
                 .unwrap(),
-            storage_credentials: StorageCredentials::BearerToken(bearer_token),
-            http_client,
-        })
+                queue_storage_secondary_url: None,
+                filesystem_url: Url::parse(&format!("https://{}.dfs.core.windows.net", &account))
+                    .unwrap(),
+                storage_credentials: StorageCredentials::BearerToken(bearer_token),
+                http_client,
+            }
+            .with_secondary_urls(&account),
+        )
     }
 
     pub fn new_connection_string(
@@ -266,13 +313,16 @@ impl StorageAccountClient {
                         sas_token,
                     )?),
                     blob_storage_url: get_endpoint_uri(blob_endpoint, account, "blob")?,
+                    blob_storage_secondary_url: None,
                     file_storage_url: get_endpoint_uri(file_endpoint, account, "file")?,
                     table_storage_url: get_endpoint_uri(table_endpoint, account, "table")?,
+                    table_storage_secondary_url: None,
                     queue_storage_url: get_endpoint_uri(queue_endpoint, account, "queue")?,
-                    queue_storage_secondary_url: get_endpoint_uri(queue_endpoint, &format!("{}-secondary", account), "queue")?,
+                    queue_storage_secondary_url: None,
                     filesystem_url: get_endpoint_uri(file_endpoint, account, "dfs")?,
                     http_client,
-                }))
+                }
+                .with_secondary_urls(account)))
             }
             ConnectionString {
                 account_name: Some(account),
@@ -285,13 +335,16 @@ impl StorageAccountClient {
             } => Ok(Arc::new(Self {
                 storage_credentials: StorageCredentials::SASToken(get_sas_token_parms(sas_token)?),
                 blob_storage_url: get_endpoint_uri(blob_endpoint, account, "blob")?,
+                blob_storage_secondary_url: None,
                 file_storage_url: get_endpoint_uri(file_endpoint, account, "file")?,
                 table_storage_url: get_endpoint_uri(table_endpoint, account, "table")?,
+                table_storage_secondary_url: None,
                 queue_storage_url: get_endpoint_uri(queue_endpoint, account, "queue")?,
-                queue_storage_secondary_url: get_endpoint_uri(queue_endpoint, &format!("{}-secondary", account), "queue")?,
+                queue_storage_secondary_url: None,
                 filesystem_url: get_endpoint_uri(file_endpoint, account, "dfs")?,
                 http_client,
-            })),
+            }
+            .with_secondary_urls(account))),
             ConnectionString {
                 account_name: Some(account),
                 account_key: Some(key),
@@ -303,13 +356,16 @@ impl StorageAccountClient {
             } => Ok(Arc::new(Self {
                 storage_credentials: StorageCredentials::Key(account.to_owned(), key.to_owned()),
                 blob_storage_url: get_endpoint_uri(blob_endpoint, account, "blob")?,
+                blob_storage_secondary_url: None,
                 file_storage_url: get_endpoint_uri(file_endpoint, account, "file")?,
                 table_storage_url: get_endpoint_uri(table_endpoint, account, "table")?,
+                table_storage_secondary_url: None,
                 queue_storage_url: get_endpoint_uri(queue_endpoint, account, "queue")?,
-                queue_storage_secondary_url: get_endpoint_uri(queue_endpoint, &format!("{}-secondary", account), "queue")?,
+                queue_storage_secondary_url: None,
                 filesystem_url: get_endpoint_uri(file_endpoint, account, "dfs")?,
                 http_client,
-            })),
+            }
+            .with_secondary_urls(account))),
            _ => {
                 Err(crate::Error::GenericErrorWithText(
                     "Could not create a storage client from the provided connection string. Please validate that you have specified the account name and means of authentication (key, SAS, etc.)."
@@ -319,6 +375,14 @@ impl StorageAccountClient {
         }
     }
 
+    /// Derives the secondary endpoints from the primary ones.
+    fn with_secondary_urls(mut self, account: &str) -> Self {
+        self.blob_storage_secondary_url = secondary_endpoint_uri(&self.blob_storage_url, account);
+        self.table_storage_secondary_url = secondary_endpoint_uri(&self.table_storage_url, account);
+        self.queue_storage_secondary_url = secondary_endpoint_uri(&self.queue_storage_url, account);
+        self
+    }
+
     pub fn http_client(&self) -> &dyn HttpClient {
         self.http_client.as_ref()
     }
@@ -327,6 +391,20 @@ impl StorageAccountClient {
         &self.blob_storage_url
     }
 
+    /// The read-only secondary endpoint of the blob service, or the primary endpoint when it
+    /// cannot be derived from a custom one. See [`Self::blob_storage_secondary_endpoint`].
+    pub fn blob_storage_secondary_url(&self) -> &Url {
+        self.blob_storage_secondary_url
+            .as_ref()
+            .unwrap_or(&self.blob_storage_url)
+    }
+
+    /// The read-only secondary endpoint of the blob service, `None` when it cannot be derived
+    /// from a custom primary endpoint.
+    pub fn blob_storage_secondary_endpoint(&self) -> Option<&Url> {
+        self.blob_storage_secondary_url.as_ref()
+    }
+
     pub fn file_storage_url(&self) -> &Url {
         &self.file_storage_url
     }
@@ -335,12 +413,36 @@ impl StorageAccountClient {
         &self.table_storage_url
     }
 
+    /// The read-only secondary endpoint of the table service, or the primary endpoint when it
+    /// cannot be derived from a custom one. See [`Self::table_storage_secondary_endpoint`].
+    pub fn table_storage_secondary_url(&self) -> &Url {
+        self.table_storage_secondary_url
+            .as_ref()
+            .unwrap_or(&self.table_storage_url)
+    }
+
+    /// The read-only secondary endpoint of the table service, `None` when it cannot be derived
+    /// from a custom primary endpoint.
+    pub fn table_storage_secondary_endpoint(&self) -> Option<&Url> {
+        self.table_storage_secondary_url.as_ref()
+    }
+
     pub fn queue_storage_url(&self) -> &Url {
         &self.queue_storage_url
     }
 
+    /// The read-only secondary endpoint of the queue service, or the primary endpoint when it
+    /// cannot be derived from a custom one. See [`Self::queue_storage_secondary_endpoint`].
     pub fn queue_storage_secondary_url(&self) -> &Url {
-        &self.queue_storage_secondary_url
+        self.queue_storage_secondary_url
+            .as_ref()
+            .unwrap_or(&self.queue_storage_url)
+    }
+
+    /// The read-only secondary endpoint of the queue service, `None` when it cannot be derived
+    /// from a custom primary endpoint.
+    pub fn queue_storage_secondary_endpoint(&self) -> Option<&Url> {
+        self.queue_storage_secondary_url.as_ref()
     }
 
     pub fn filesystem_url(&self) -> &Url {
@@ -562,7 +664,11 @@ fn canonicalize_header(h: &HeaderMap) -> String {
 
 // For table
 fn canonicalized_resource_table(account: &str, u: &url::Url) -> String {
-    format!("/{}{}", account, u.path())
+    // the comp parameter is the only one to be signed
+    match u.query_pairs().find(|(key, _)| key == "comp") {
+        Some((_, comp)) => format!("/{}{}?comp={}", account, u.path(), comp),
+        None => format!("/{}{}", account, u.path()),
+    }
 }
 
 fn canonicalized_resource(account: &str, u: &url::Url) -> String {
@@ -647,3 +753,115 @@ where
         ))?,
     })
 }
+
+/// The secondary endpoint of `account`, `{account}-secondary` in place of the account in the
+/// host of its primary endpoint, or in the path of the path-style endpoints of the emulator.
+/// `None` when the account is in neither, as with custom domains.
+fn secondary_endpoint_uri(primary: &Url, account: &str) -> Option<Url> {
+    let account = account.to_lowercase();
+    let secondary = format!("{}-secondary", account);
+    let mut url = primary.clone();
+
+    if let Some(domain) = primary
+        .host_str()
+        .and_then(|host| host.strip_prefix(account.as_str()))
+        .and_then(|rest| rest.strip_prefix('.'))
+    {
+        url.set_host(Some(&format!("{}.{}", secondary, domain)))
+            .ok()?;
+        return Some(url);
+    }
+
+    let mut segments = primary.path_segments()?;
+    if segments.next() == Some(account.as_str()) {
+        let rest: Vec<&str> = segments.collect();
+        url.path_segments_mut()
+            .ok()?
+            .clear()
+            .push(&secondary)
+            .extend(rest);
+        return Some(url);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_canonicalized_resource_includes_comp() {
+        let url = Url::parse(
+            "https://myaccount.table.core.windows.net/?restype=service&comp=properties&timeout=30",
+        )
+        .unwrap();
+        assert_eq!(
+            canonicalized_resource_table("myaccount", &url),
+            "/myaccount/?comp=properties"
+        );
+
+        let url = Url::parse("https://myaccount.table.core.windows.net/Tables?$top=5").unwrap();
+        assert_eq!(
+            canonicalized_resource_table("myaccount", &url),
+            "/myaccount/Tables"
+        );
+    }
+
+    #[test]
+    fn secondary_endpoints() {
+        let secondary = |primary: &str, account: &str| {
+            secondary_endpoint_uri(&Url::parse(primary).unwrap(), account)
+                .map(|url| url.to_string())
+        };
+
+        assert_eq!(
+            secondary("https://myaccount.blob.core.windows.net", "myaccount").as_deref(),
+            Some("https://myaccount-secondary.blob.core.windows.net/")
+        );
+        assert_eq!(
+            secondary(
+                "https://myaccount.queue.core.chinacloudapi.cn/",
+                "MyAccount"
+            )
+            .as_deref(),
+            Some("https://myaccount-secondary.queue.core.chinacloudapi.cn/")
+        );
+        assert_eq!(
+            secondary(
+                "http://127.0.0.1:10000/devstoreaccount1",
+                "devstoreaccount1"
+            )
+            .as_deref(),
+            Some("http://127.0.0.1:10000/devstoreaccount1-secondary")
+        );
+        assert_eq!(secondary("https://files.contoso.com", "myaccount"), None);
+        assert_eq!(
+            secondary("https://myaccountant.blob.core.windows.net", "myaccount"),
+            None
+        );
+    }
+
+    #[test]
+    fn secondary_urls_of_custom_endpoints() {
+        let client = StorageAccountClient::new_connection_string(
+            new_http_client(),
+            "AccountName=myaccount;AccountKey=a2V5;BlobEndpoint=https://files.contoso.com",
+        )
+        .unwrap();
+
+        assert_eq!(
+            client.blob_storage_secondary_url().as_str(),
+            "https://files.contoso.com/"
+        );
+        assert_eq!(client.blob_storage_secondary_endpoint(), None);
+        assert_eq!(
+            client.queue_storage_secondary_url().as_str(),
+            "https://myaccount-secondary.queue.core.windows.net/"
+        );
+        assert_eq!(
+            client.queue_storage_secondary_endpoint(),
+            Some(client.queue_storage_secondary_url())
+        );
+    }
+}
@@ -102,6 +102,31 @@ impl StorageClient {
         crate::container::requests::ListContainersBuilder::new(self)
     }
 
+    #[cfg(feature = "blob")]
+    pub fn get_blob_service_properties(
+        &self,
+    ) -> crate::blob::service::requests::GetBlobServicePropertiesBuilder {
+        crate::blob::service::requests::GetBlobServicePropertiesBuilder::new(self)
+    }
+
+    #[cfg(feature = "blob")]
+    pub fn set_blob_service_properties<'a>(
+        &'a self,
+        blob_service_properties: &'a crate::BlobServiceProperties,
+    ) -> crate::blob::service::requests::SetBlobServicePropertiesBuilder<'a> {
+        crate::blob::service::requests::SetBlobServicePropertiesBuilder::new(
+            self,
+            blob_service_properties,
+        )
+    }
+
+    #[cfg(feature = "blob")]
+    pub fn get_blob_service_stats(
+        &self,
+    ) -> crate::blob::service::requests::GetBlobServiceStatsBuilder {
+        crate::blob::service::requests::GetBlobServiceStatsBuilder::new(self)
+    }
+
     #[cfg(feature = "file")]
     pub fn list_shares(&self) -> crate::share::requests::ListSharesBuilder {
         crate::share::requests::ListSharesBuilder::new(self)
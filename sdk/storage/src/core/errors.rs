@@ -67,6 +67,23 @@ pub enum Error {
     CRC64Not8BytesLong(usize),
     #[error("At least one of these headers must be present: {0:?}")]
     HeadersNotFound(Vec<String>),
+    #[error("Avro decoding error: {0}")]
+    AvroError(String),
+    #[cfg(feature = "client_side_encryption")]
+    #[error("Client side encryption error: {0}")]
+    ClientSideEncryptionError(String),
+    #[error("Storage service error: {0}")]
+    StorageError(#[from] super::StorageError),
+}
+
+impl Error {
+    /// The service error code, if this error was returned by the service.
+    pub fn storage_error_code(&self) -> Option<&super::StorageErrorCode> {
+        match self {
+            Error::StorageError(error) => Some(&error.error_code),
+            _ => None,
+        }
+    }
 }
 
 #[non_exhaustive]
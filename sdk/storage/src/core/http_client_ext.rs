@@ -0,0 +1,61 @@
+use crate::core::StorageError;
+use azure_core::HttpClient;
+use bytes::Bytes;
+use futures::future::{BoxFuture, FutureExt};
+use http::{Request, Response, StatusCode};
+
+/// Storage flavored status checks. Unlike the `HttpClient` ones, a failed
+/// check keeps the response headers around so the service error code and
+/// request id end up in `Error::StorageError`.
+pub(crate) trait StorageHttpClient {
+    fn execute_storage_request_check_status(
+        &self,
+        request: Request<Bytes>,
+        expected_status: StatusCode,
+    ) -> BoxFuture<'_, Result<Response<Bytes>, crate::Error>>;
+
+    fn execute_storage_request_check_statuses<'a>(
+        &'a self,
+        request: Request<Bytes>,
+        expected_statuses: &'a [StatusCode],
+    ) -> BoxFuture<'a, Result<Response<Bytes>, crate::Error>>;
+}
+
+impl<'c> StorageHttpClient for dyn HttpClient + 'c {
+    fn execute_storage_request_check_status(
+        &self,
+        request: Request<Bytes>,
+        expected_status: StatusCode,
+    ) -> BoxFuture<'_, Result<Response<Bytes>, crate::Error>> {
+        async move {
+            self.execute_storage_request_check_statuses(request, &[expected_status])
+                .await
+        }
+        .boxed()
+    }
+
+    fn execute_storage_request_check_statuses<'a>(
+        &'a self,
+        request: Request<Bytes>,
+        expected_statuses: &'a [StatusCode],
+    ) -> BoxFuture<'a, Result<Response<Bytes>, crate::Error>> {
+        async move {
+            let response = self
+                .execute_request(request)
+                .await
+                .map_err(azure_core::Error::from)?;
+
+            if expected_statuses.contains(&response.status()) {
+                Ok(response)
+            } else {
+                Err(StorageError::from_response(
+                    response.status(),
+                    response.headers(),
+                    response.body(),
+                )
+                .into())
+            }
+        }
+        .boxed()
+    }
+}
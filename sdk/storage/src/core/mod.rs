@@ -1,14 +1,29 @@
+#[cfg(feature = "client_side_encryption")]
+mod client_side_encryption;
 pub mod clients;
 mod connection_string;
 mod connection_string_builder;
 mod copy_id;
 mod copy_progress;
 mod errors;
+mod http_client_ext;
 mod into_azure_path;
 pub mod prelude;
+mod service_properties;
 pub mod shared_access_signature;
+pub(crate) mod storage_error;
 use std::convert::TryInto;
 
+#[cfg(feature = "client_side_encryption")]
+pub(crate) use self::client_side_encryption::{
+    decrypt_regions, encrypt, encrypted_range, plaintext_length, unwrap_content_key,
+    ENCRYPTION_DATA_METADATA,
+};
+#[cfg(feature = "client_side_encryption")]
+pub use self::client_side_encryption::{
+    EncryptedRegionInfo, EncryptionAgent, EncryptionData, KeyEncryptionKey, KeyWrapAlgorithm,
+    RsaKeyEncryptionKey, WrappedContentKey,
+};
 pub use self::connection_string::{ConnectionString, EndpointProtocol};
 pub use self::connection_string_builder::ConnectionStringBuilder;
 pub use self::into_azure_path::IntoAzurePath;
@@ -19,6 +34,11 @@ pub use copy_progress::CopyProgress;
 pub(crate) mod parsing_xml;
 mod stored_access_policy;
 pub use errors::Error;
+pub(crate) use http_client_ext::StorageHttpClient;
+pub use service_properties::{
+    Cors, CorsRule, GeoReplicationStatus, Logging, Metrics, RetentionPolicy,
+};
+pub use storage_error::{StorageError, StorageErrorCode};
 pub(crate) mod xml;
 
 #[derive(Debug, Clone, Eq, PartialEq, Copy, Serialize, Deserialize)]
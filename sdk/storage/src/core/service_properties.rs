@@ -0,0 +1,123 @@
+//! Settings shared by the `Get/Set Service Properties` operations of the
+//! blob and queue services.
+//! See [https://docs.microsoft.com/rest/api/storageservices/set-blob-service-properties](https://docs.microsoft.com/rest/api/storageservices/set-blob-service-properties).
+
+use crate::xml::xml_escape;
+
+create_enum!(
+    GeoReplicationStatus,
+    (Live, "live"),
+    (Bootstrap, "bootstrap"),
+    (Unavailable, "unavailable")
+);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct RetentionPolicy {
+    pub enabled: bool,
+    pub days: Option<u64>,
+}
+
+impl RetentionPolicy {
+    pub(crate) fn to_xml(&self, tag: &str) -> String {
+        let mut s = format!("<{}><Enabled>{}</Enabled>", tag, self.enabled);
+        if let Some(days) = self.days {
+            s.push_str(&format!("<Days>{}</Days>", days));
+        }
+        s.push_str(&format!("</{}>", tag));
+        s
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Logging {
+    pub version: String,
+    pub delete: bool,
+    pub read: bool,
+    pub write: bool,
+    pub retention_policy: RetentionPolicy,
+}
+
+impl Logging {
+    pub(crate) fn to_xml(&self) -> String {
+        format!(
+            "<Logging><Version>{}</Version><Delete>{}</Delete><Read>{}</Read><Write>{}</Write>{}</Logging>",
+            xml_escape(&self.version),
+            self.delete,
+            self.read,
+            self.write,
+            self.retention_policy.to_xml("RetentionPolicy")
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Metrics {
+    pub version: String,
+    pub enabled: bool,
+    #[serde(rename = "IncludeAPIs")]
+    pub include_apis: Option<bool>,
+    pub retention_policy: RetentionPolicy,
+}
+
+impl Metrics {
+    pub(crate) fn to_xml(&self, tag: &str) -> String {
+        let mut s = format!(
+            "<{}><Version>{}</Version><Enabled>{}</Enabled>",
+            tag,
+            xml_escape(&self.version),
+            self.enabled
+        );
+        // IncludeAPIs must be omitted when metrics are disabled
+        if let Some(include_apis) = self.include_apis {
+            if self.enabled {
+                s.push_str(&format!("<IncludeAPIs>{}</IncludeAPIs>", include_apis));
+            }
+        }
+        s.push_str(&self.retention_policy.to_xml("RetentionPolicy"));
+        s.push_str(&format!("</{}>", tag));
+        s
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Cors {
+    pub cors_rule: Option<Vec<CorsRule>>,
+}
+
+impl Cors {
+    pub(crate) fn to_xml(&self) -> String {
+        let mut s = String::from("<Cors>");
+        for rule in self.cors_rule.iter().flatten() {
+            s.push_str(&rule.to_xml());
+        }
+        s.push_str("</Cors>");
+        s
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CorsRule {
+    pub allowed_origins: String,
+    pub allowed_methods: String,
+    pub max_age_in_seconds: u64,
+    pub exposed_headers: String,
+    pub allowed_headers: String,
+}
+
+impl CorsRule {
+    pub(crate) fn to_xml(&self) -> String {
+        format!(
+            "<CorsRule><AllowedOrigins>{}</AllowedOrigins><AllowedMethods>{}</AllowedMethods><MaxAgeInSeconds>{}</MaxAgeInSeconds><ExposedHeaders>{}</ExposedHeaders><AllowedHeaders>{}</AllowedHeaders></CorsRule>",
+            xml_escape(&self.allowed_origins),
+            xml_escape(&self.allowed_methods),
+            self.max_age_in_seconds,
+            xml_escape(&self.exposed_headers),
+            xml_escape(&self.allowed_headers)
+        )
+    }
+}
@@ -0,0 +1,384 @@
+use crate::parsing_xml::cast_optional;
+use azure_core::RequestId;
+use http::{HeaderMap, StatusCode};
+use std::str::FromStr;
+use xml::Element;
+
+pub(crate) const ERROR_CODE: &str = "x-ms-error-code";
+
+macro_rules! storage_error_codes {
+    ($($variant:ident => $code:literal),* $(,)?) => {
+        /// Error codes returned by the storage services, either in the
+        /// `x-ms-error-code` header or in the error body. Codes this crate
+        /// does not know about are preserved as `Unknown`.
+        ///
+        /// see: https://docs.microsoft.com/rest/api/storageservices/common-rest-api-error-codes
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        #[non_exhaustive]
+        pub enum StorageErrorCode {
+            $($variant,)*
+            Unknown(String),
+        }
+
+        impl StorageErrorCode {
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(StorageErrorCode::$variant => $code,)*
+                    StorageErrorCode::Unknown(code) => code.as_str(),
+                }
+            }
+        }
+
+        impl From<&str> for StorageErrorCode {
+            fn from(code: &str) -> Self {
+                match code {
+                    $($code => StorageErrorCode::$variant,)*
+                    code => StorageErrorCode::Unknown(code.to_owned()),
+                }
+            }
+        }
+    };
+}
+
+storage_error_codes! {
+    // common
+    AccountAlreadyExists => "AccountAlreadyExists",
+    AccountBeingCreated => "AccountBeingCreated",
+    AccountIsDisabled => "AccountIsDisabled",
+    AuthenticationFailed => "AuthenticationFailed",
+    AuthorizationFailure => "AuthorizationFailure",
+    AuthorizationPermissionMismatch => "AuthorizationPermissionMismatch",
+    AuthorizationProtocolMismatch => "AuthorizationProtocolMismatch",
+    AuthorizationResourceTypeMismatch => "AuthorizationResourceTypeMismatch",
+    AuthorizationServiceMismatch => "AuthorizationServiceMismatch",
+    AuthorizationSourceIPMismatch => "AuthorizationSourceIPMismatch",
+    ConditionHeadersNotSupported => "ConditionHeadersNotSupported",
+    ConditionNotMet => "ConditionNotMet",
+    EmptyMetadataKey => "EmptyMetadataKey",
+    InsufficientAccountPermissions => "InsufficientAccountPermissions",
+    InternalError => "InternalError",
+    InvalidAuthenticationInfo => "InvalidAuthenticationInfo",
+    InvalidHeaderValue => "InvalidHeaderValue",
+    InvalidHttpVerb => "InvalidHttpVerb",
+    InvalidInput => "InvalidInput",
+    InvalidMd5 => "InvalidMd5",
+    InvalidMetadata => "InvalidMetadata",
+    InvalidQueryParameterValue => "InvalidQueryParameterValue",
+    InvalidRange => "InvalidRange",
+    InvalidResourceName => "InvalidResourceName",
+    InvalidUri => "InvalidUri",
+    InvalidXmlDocument => "InvalidXmlDocument",
+    InvalidXmlNodeValue => "InvalidXmlNodeValue",
+    Md5Mismatch => "Md5Mismatch",
+    MetadataTooLarge => "MetadataTooLarge",
+    MissingContentLengthHeader => "MissingContentLengthHeader",
+    MissingRequiredQueryParameter => "MissingRequiredQueryParameter",
+    MissingRequiredHeader => "MissingRequiredHeader",
+    MissingRequiredXmlNode => "MissingRequiredXmlNode",
+    MultipleConditionHeadersNotSupported => "MultipleConditionHeadersNotSupported",
+    OperationTimedOut => "OperationTimedOut",
+    OutOfRangeInput => "OutOfRangeInput",
+    OutOfRangeQueryParameterValue => "OutOfRangeQueryParameterValue",
+    RequestBodyTooLarge => "RequestBodyTooLarge",
+    ResourceTypeMismatch => "ResourceTypeMismatch",
+    RequestUrlFailedToParse => "RequestUrlFailedToParse",
+    ResourceAlreadyExists => "ResourceAlreadyExists",
+    ResourceNotFound => "ResourceNotFound",
+    ServerBusy => "ServerBusy",
+    UnsupportedHeader => "UnsupportedHeader",
+    UnsupportedXmlNode => "UnsupportedXmlNode",
+    UnsupportedQueryParameter => "UnsupportedQueryParameter",
+    UnsupportedHttpVerb => "UnsupportedHttpVerb",
+    // blob
+    AppendPositionConditionNotMet => "AppendPositionConditionNotMet",
+    BlobAlreadyExists => "BlobAlreadyExists",
+    BlobArchived => "BlobArchived",
+    BlobBeingRehydrated => "BlobBeingRehydrated",
+    BlobImmutableDueToPolicy => "BlobImmutableDueToPolicy",
+    BlobNotArchived => "BlobNotArchived",
+    BlobNotFound => "BlobNotFound",
+    BlobOverwritten => "BlobOverwritten",
+    BlobTierInadequateForContentLength => "BlobTierInadequateForContentLength",
+    BlockCountExceedsLimit => "BlockCountExceedsLimit",
+    BlockListTooLong => "BlockListTooLong",
+    CannotChangeToLowerTier => "CannotChangeToLowerTier",
+    CannotVerifyCopySource => "CannotVerifyCopySource",
+    ContainerAlreadyExists => "ContainerAlreadyExists",
+    ContainerBeingDeleted => "ContainerBeingDeleted",
+    ContainerDisabled => "ContainerDisabled",
+    ContainerNotFound => "ContainerNotFound",
+    ContentLengthLargerThanTierLimit => "ContentLengthLargerThanTierLimit",
+    CopyAcrossAccountsNotSupported => "CopyAcrossAccountsNotSupported",
+    CopyIdMismatch => "CopyIdMismatch",
+    FeatureVersionMismatch => "FeatureVersionMismatch",
+    IncrementalCopyBlobMismatch => "IncrementalCopyBlobMismatch",
+    IncrementalCopyOfEralierVersionSnapshotNotAllowed => "IncrementalCopyOfEralierVersionSnapshotNotAllowed",
+    IncrementalCopySourceMustBeSnapshot => "IncrementalCopySourceMustBeSnapshot",
+    InfiniteLeaseDurationRequired => "InfiniteLeaseDurationRequired",
+    InvalidBlobOrBlock => "InvalidBlobOrBlock",
+    InvalidBlobTier => "InvalidBlobTier",
+    InvalidBlobType => "InvalidBlobType",
+    InvalidBlockId => "InvalidBlockId",
+    InvalidBlockList => "InvalidBlockList",
+    InvalidOperation => "InvalidOperation",
+    InvalidPageRange => "InvalidPageRange",
+    InvalidSourceBlobType => "InvalidSourceBlobType",
+    InvalidSourceBlobUrl => "InvalidSourceBlobUrl",
+    InvalidVersionForPageBlobOperation => "InvalidVersionForPageBlobOperation",
+    LeaseAlreadyPresent => "LeaseAlreadyPresent",
+    LeaseAlreadyBroken => "LeaseAlreadyBroken",
+    LeaseIdMismatchWithBlobOperation => "LeaseIdMismatchWithBlobOperation",
+    LeaseIdMismatchWithContainerOperation => "LeaseIdMismatchWithContainerOperation",
+    LeaseIdMismatchWithLeaseOperation => "LeaseIdMismatchWithLeaseOperation",
+    LeaseIdMissing => "LeaseIdMissing",
+    LeaseIsBreakingAndCannotBeAcquired => "LeaseIsBreakingAndCannotBeAcquired",
+    LeaseIsBreakingAndCannotBeChanged => "LeaseIsBreakingAndCannotBeChanged",
+    LeaseIsBrokenAndCannotBeRenewed => "LeaseIsBrokenAndCannotBeRenewed",
+    LeaseLost => "LeaseLost",
+    LeaseNotPresentWithBlobOperation => "LeaseNotPresentWithBlobOperation",
+    LeaseNotPresentWithContainerOperation => "LeaseNotPresentWithContainerOperation",
+    LeaseNotPresentWithLeaseOperation => "LeaseNotPresentWithLeaseOperation",
+    MaxBlobSizeConditionNotMet => "MaxBlobSizeConditionNotMet",
+    NoPendingCopyOperation => "NoPendingCopyOperation",
+    OperationNotAllowedOnIncrementalCopyBlob => "OperationNotAllowedOnIncrementalCopyBlob",
+    PendingCopyOperation => "PendingCopyOperation",
+    PreviousSnapshotCannotBeNewer => "PreviousSnapshotCannotBeNewer",
+    PreviousSnapshotNotFound => "PreviousSnapshotNotFound",
+    PreviousSnapshotOperationNotSupported => "PreviousSnapshotOperationNotSupported",
+    SequenceNumberConditionNotMet => "SequenceNumberConditionNotMet",
+    SequenceNumberIncrementTooLarge => "SequenceNumberIncrementTooLarge",
+    SnapshotCountExceeded => "SnapshotCountExceeded",
+    SnapshotOperationRateExceeded => "SnapshotOperationRateExceeded",
+    SnapshotsPresent => "SnapshotsPresent",
+    SourceConditionNotMet => "SourceConditionNotMet",
+    SystemInUse => "SystemInUse",
+    TargetConditionNotMet => "TargetConditionNotMet",
+    UnauthorizedBlobOverwrite => "UnauthorizedBlobOverwrite",
+    // queue
+    InvalidMarker => "InvalidMarker",
+    MessageNotFound => "MessageNotFound",
+    MessageTooLarge => "MessageTooLarge",
+    PopReceiptMismatch => "PopReceiptMismatch",
+    QueueAlreadyExists => "QueueAlreadyExists",
+    QueueBeingDeleted => "QueueBeingDeleted",
+    QueueDisabled => "QueueDisabled",
+    QueueNotEmpty => "QueueNotEmpty",
+    QueueNotFound => "QueueNotFound",
+    // table
+    DuplicatePropertiesSpecified => "DuplicatePropertiesSpecified",
+    EntityAlreadyExists => "EntityAlreadyExists",
+    EntityNotFound => "EntityNotFound",
+    EntityTooLarge => "EntityTooLarge",
+    PropertiesNeedValue => "PropertiesNeedValue",
+    PropertyNameInvalid => "PropertyNameInvalid",
+    PropertyNameTooLong => "PropertyNameTooLong",
+    PropertyValueTooLarge => "PropertyValueTooLarge",
+    TableAlreadyExists => "TableAlreadyExists",
+    TableBeingDeleted => "TableBeingDeleted",
+    TableNotFound => "TableNotFound",
+    TooManyProperties => "TooManyProperties",
+    UpdateConditionNotSatisfied => "UpdateConditionNotSatisfied",
+    // file
+    CannotDeleteFileOrDirectory => "CannotDeleteFileOrDirectory",
+    ClientCacheFlushDelay => "ClientCacheFlushDelay",
+    DeletePending => "DeletePending",
+    DirectoryNotEmpty => "DirectoryNotEmpty",
+    FileLockConflict => "FileLockConflict",
+    InvalidFileOrDirectoryPathName => "InvalidFileOrDirectoryPathName",
+    ParentNotFound => "ParentNotFound",
+    ReadOnlyAttribute => "ReadOnlyAttribute",
+    ShareAlreadyExists => "ShareAlreadyExists",
+    ShareBeingDeleted => "ShareBeingDeleted",
+    ShareDisabled => "ShareDisabled",
+    ShareNotFound => "ShareNotFound",
+    SharingViolation => "SharingViolation",
+    ShareSnapshotInProgress => "ShareSnapshotInProgress",
+    ShareSnapshotCountExceeded => "ShareSnapshotCountExceeded",
+    ShareSnapshotOperationNotSupported => "ShareSnapshotOperationNotSupported",
+    ShareHasSnapshots => "ShareHasSnapshots",
+    // data lake
+    FilesystemAlreadyExists => "FilesystemAlreadyExists",
+    FilesystemBeingDeleted => "FilesystemBeingDeleted",
+    FilesystemNotFound => "FilesystemNotFound",
+    PathAlreadyExists => "PathAlreadyExists",
+    PathNotFound => "PathNotFound",
+    DirectoryIsNotEmpty => "DirectoryIsNotEmpty",
+}
+
+impl FromStr for StorageErrorCode {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(s.into())
+    }
+}
+
+impl std::fmt::Display for StorageErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A failed storage operation, as reported by the service.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StorageError {
+    pub status: StatusCode,
+    pub error_code: StorageErrorCode,
+    pub message: Option<String>,
+    pub request_id: Option<RequestId>,
+    pub authentication_error_detail: Option<String>,
+    pub body: String,
+}
+
+impl StorageError {
+    /// Builds the error from a failed response. The error code in the
+    /// `x-ms-error-code` header takes precedence over the one in the
+    /// body, since HEAD requests do not carry a body at all.
+    pub(crate) fn from_response(status: StatusCode, headers: &HeaderMap, body: &[u8]) -> Self {
+        let body = String::from_utf8_lossy(body).into_owned();
+
+        let (body_code, message, authentication_error_detail) =
+            parse_error_body(&body).unwrap_or((None, None, None));
+
+        let error_code = headers
+            .get(ERROR_CODE)
+            .and_then(|code| code.to_str().ok())
+            .map(StorageErrorCode::from)
+            .or_else(|| body_code.as_deref().map(StorageErrorCode::from))
+            .unwrap_or_else(|| StorageErrorCode::Unknown(status.as_str().to_owned()));
+
+        let request_id = headers
+            .get(azure_core::headers::REQUEST_ID)
+            .and_then(|request_id| request_id.to_str().ok())
+            .and_then(|request_id| request_id.parse().ok());
+
+        Self {
+            status,
+            error_code,
+            message,
+            request_id,
+            authentication_error_detail,
+            body,
+        }
+    }
+}
+
+impl std::fmt::Display for StorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.error_code, self.status)?;
+        if let Some(message) = &self.message {
+            write!(f, ": {}", message.lines().next().unwrap_or_default())?;
+        }
+        if let Some(request_id) = &self.request_id {
+            write!(f, " [request id {}]", request_id)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+type ErrorBody = (Option<String>, Option<String>, Option<String>);
+
+fn parse_error_body(body: &str) -> Option<ErrorBody> {
+    let body = body.trim_start_matches('\u{FEFF}').trim();
+    if body.starts_with('<') {
+        let elem: Element = body.parse().ok()?;
+        Some((
+            cast_optional(&elem, &["Code"]).ok()?,
+            cast_optional(&elem, &["Message"]).ok()?,
+            cast_optional(&elem, &["AuthenticationErrorDetail"]).ok()?,
+        ))
+    } else if body.starts_with('{') {
+        // tables answer with "odata.error", data lake with "error"
+        let json: serde_json::Value = serde_json::from_str(body).ok()?;
+        let error = json.get("odata.error").or_else(|| json.get("error"))?;
+        let code = error.get("code").and_then(|c| c.as_str()).map(String::from);
+        let message = error.get("message").and_then(|message| {
+            message
+                .as_str()
+                .or_else(|| message.get("value").and_then(|v| v.as_str()))
+                .map(String::from)
+        });
+        Some((code, message, None))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_xml_error() {
+        let body = "\u{FEFF}<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+            <Error>\
+                <Code>AuthenticationFailed</Code>\
+                <Message>Server failed to authenticate the request.</Message>\
+                <AuthenticationErrorDetail>Signature did not match.</AuthenticationErrorDetail>\
+            </Error>";
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-ms-request-id",
+            "4a0c3e3a-e01e-0056-6d9b-4d7e0b000000".parse().unwrap(),
+        );
+
+        let error = StorageError::from_response(StatusCode::FORBIDDEN, &headers, body.as_bytes());
+
+        assert_eq!(error.error_code, StorageErrorCode::AuthenticationFailed);
+        assert_eq!(
+            error.message.as_deref(),
+            Some("Server failed to authenticate the request.")
+        );
+        assert_eq!(
+            error.authentication_error_detail.as_deref(),
+            Some("Signature did not match.")
+        );
+        assert_eq!(
+            error.request_id.unwrap().to_string(),
+            "4a0c3e3a-e01e-0056-6d9b-4d7e0b000000"
+        );
+    }
+
+    #[test]
+    fn header_code_without_body() {
+        let mut headers = HeaderMap::new();
+        headers.insert(ERROR_CODE, "BlobNotFound".parse().unwrap());
+
+        let error = StorageError::from_response(StatusCode::NOT_FOUND, &headers, &[]);
+
+        assert_eq!(error.error_code, StorageErrorCode::BlobNotFound);
+        assert_eq!(error.message, None);
+    }
+
+    #[test]
+    fn parse_json_errors() {
+        let table = r#"{"odata.error":{"code":"TableNotFound","message":{"lang":"en-US","value":"The table specified does not exist."}}}"#;
+        let error =
+            StorageError::from_response(StatusCode::NOT_FOUND, &HeaderMap::new(), table.as_bytes());
+        assert_eq!(error.error_code, StorageErrorCode::TableNotFound);
+        assert_eq!(
+            error.message.as_deref(),
+            Some("The table specified does not exist.")
+        );
+
+        let data_lake = r#"{"error":{"code":"FilesystemNotFound","message":"The specified filesystem does not exist."}}"#;
+        let error = StorageError::from_response(
+            StatusCode::NOT_FOUND,
+            &HeaderMap::new(),
+            data_lake.as_bytes(),
+        );
+        assert_eq!(error.error_code, StorageErrorCode::FilesystemNotFound);
+    }
+
+    #[test]
+    fn unknown_code_is_preserved() {
+        let code: StorageErrorCode = "SomeBrandNewCode".parse().unwrap();
+        assert_eq!(
+            code,
+            StorageErrorCode::Unknown("SomeBrandNewCode".to_owned())
+        );
+        assert_eq!(code.as_str(), "SomeBrandNewCode");
+    }
+}
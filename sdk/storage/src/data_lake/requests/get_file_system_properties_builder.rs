@@ -1,3 +1,4 @@
+use crate::core::StorageHttpClient;
 use crate::data_lake::clients::FileSystemClient;
 use crate::data_lake::responses::*;
 use azure_core::prelude::*;
@@ -54,7 +55,7 @@ impl<'a> GetFileSystemPropertiesBuilder<'a> {
         let response = self
             .file_system_client
             .http_client()
-            .execute_request_check_status(request.0, StatusCode::OK)
+            .execute_storage_request_check_status(request.0, StatusCode::OK)
             .await?;
 
         Ok((&response).try_into()?)
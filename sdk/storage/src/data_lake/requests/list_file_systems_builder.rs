@@ -1,3 +1,4 @@
+use crate::core::StorageHttpClient;
 use crate::data_lake::clients::DataLakeClient;
 use crate::data_lake::responses::*;
 use azure_core::prelude::*;
@@ -70,7 +71,7 @@ impl<'a> ListFileSystemsBuilder<'a> {
         let response = self
             .data_lake_client
             .http_client()
-            .execute_request_check_status(request.0, StatusCode::OK)
+            .execute_storage_request_check_status(request.0, StatusCode::OK)
             .await?;
 
         Ok((&response).try_into()?)
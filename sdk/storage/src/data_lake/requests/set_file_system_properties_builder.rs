@@ -1,3 +1,4 @@
+use crate::core::StorageHttpClient;
 use crate::data_lake::responses::*;
 use crate::{data_lake::clients::FileSystemClient, Properties};
 use azure_core::prelude::*;
@@ -66,7 +67,7 @@ impl<'a> SetFileSystemPropertiesBuilder<'a> {
         let response = self
             .file_system_client
             .http_client()
-            .execute_request_check_status(request.0, StatusCode::OK)
+            .execute_storage_request_check_status(request.0, StatusCode::OK)
             .await?;
 
         Ok((&response).try_into()?)
@@ -1,3 +1,4 @@
+use crate::core::StorageHttpClient;
 use crate::file::prelude::*;
 use crate::share::access_tier::AccessTier;
 use azure_core::{
@@ -57,7 +58,7 @@ impl<'a> CreateBuilder<'a> {
             .storage_client()
             .storage_account_client()
             .http_client()
-            .execute_request_check_status(request.0, StatusCode::CREATED)
+            .execute_storage_request_check_status(request.0, StatusCode::CREATED)
             .await?;
 
         // TODO: Capture and return the response headers
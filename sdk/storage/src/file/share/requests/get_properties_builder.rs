@@ -1,3 +1,4 @@
+use crate::core::StorageHttpClient;
 use crate::file::prelude::*;
 use crate::share::responses::GetPropertiesResponse;
 use azure_core::headers::{add_optional_header, add_optional_header_ref};
@@ -55,7 +56,7 @@ impl<'a> GetPropertiesBuilder<'a> {
             .storage_client()
             .storage_account_client()
             .http_client()
-            .execute_request_check_status(request.0, StatusCode::OK)
+            .execute_storage_request_check_status(request.0, StatusCode::OK)
             .await?;
 
         Ok((self.share_client.share_name(), response.headers()).try_into()?)
@@ -1,3 +1,4 @@
+use crate::core::StorageHttpClient;
 use crate::share::incomplete_vector_from_share_response;
 use crate::share::responses::ListSharesResponse;
 use crate::core::prelude::*;
@@ -91,7 +92,7 @@ impl<'a> ListSharesBuilder<'a> {
             .storage_client
             .storage_account_client()
             .http_client()
-            .execute_request_check_status(request, StatusCode::OK)
+            .execute_storage_request_check_status(request, StatusCode::OK)
             .await?;
 
         debug!("response == {:?}", response);
@@ -1,3 +1,4 @@
+use crate::core::StorageHttpClient;
 use crate::{
     StoredAccessPolicyList,
 };
@@ -63,7 +64,7 @@ impl<'a> SetACLBuilder<'a> {
             .storage_client()
             .storage_account_client()
             .http_client()
-            .execute_request_check_status(request.0, StatusCode::OK)
+            .execute_storage_request_check_status(request.0, StatusCode::OK)
             .await?;
 
         Ok((response.body(), response.headers()).try_into()?)
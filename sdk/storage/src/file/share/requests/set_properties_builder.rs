@@ -1,3 +1,4 @@
+use crate::core::StorageHttpClient;
 use crate::file::prelude::*;
 use crate::share::access_tier::AccessTier;
 use crate::share::responses::GetPropertiesResponse;
@@ -62,7 +63,7 @@ impl<'a> GetPropertiesBuilder<'a> {
             .storage_client()
             .storage_account_client()
             .http_client()
-            .execute_request_check_status(request.0, StatusCode::OK)
+            .execute_storage_request_check_status(request.0, StatusCode::OK)
             .await?;
 
         Ok((self.share_client.share_name(), response.headers()).try_into()?)
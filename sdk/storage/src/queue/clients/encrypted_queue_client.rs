@@ -0,0 +1,60 @@
+use crate::core::KeyEncryptionKey;
+use crate::queue::clients::QueueClient;
+use crate::queue::requests::{EncryptedGetMessagesBuilder, EncryptedPutMessageBuilder};
+use std::sync::Arc;
+
+pub trait AsEncryptedQueueClient {
+    fn as_encrypted_queue_client(
+        &self,
+        key_encryption_key: Arc<dyn KeyEncryptionKey>,
+    ) -> Arc<EncryptedQueueClient>;
+}
+
+impl AsEncryptedQueueClient for Arc<QueueClient> {
+    fn as_encrypted_queue_client(
+        &self,
+        key_encryption_key: Arc<dyn KeyEncryptionKey>,
+    ) -> Arc<EncryptedQueueClient> {
+        EncryptedQueueClient::new(self.clone(), key_encryption_key)
+    }
+}
+
+/// A `QueueClient` encrypting the messages on the client side. The
+/// message text is replaced by an envelope holding the ciphertext and
+/// the wrapped content key.
+#[derive(Debug, Clone)]
+pub struct EncryptedQueueClient {
+    queue_client: Arc<QueueClient>,
+    key_encryption_key: Arc<dyn KeyEncryptionKey>,
+}
+
+impl EncryptedQueueClient {
+    pub(crate) fn new(
+        queue_client: Arc<QueueClient>,
+        key_encryption_key: Arc<dyn KeyEncryptionKey>,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            queue_client,
+            key_encryption_key,
+        })
+    }
+
+    pub fn queue_client(&self) -> &QueueClient {
+        self.queue_client.as_ref()
+    }
+
+    pub(crate) fn key_encryption_key(&self) -> &dyn KeyEncryptionKey {
+        self.key_encryption_key.as_ref()
+    }
+
+    /// Encrypts and puts a message in the queue. The body will be passed
+    /// to the `execute` function of the returned struct.
+    pub fn put_message(&self) -> EncryptedPutMessageBuilder {
+        EncryptedPutMessageBuilder::new(self)
+    }
+
+    /// Gets, shadowing them, one or more messages and decrypts them.
+    pub fn get_messages(&self) -> EncryptedGetMessagesBuilder {
+        EncryptedGetMessagesBuilder::new(self)
+    }
+}
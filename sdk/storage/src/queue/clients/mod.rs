@@ -1,4 +1,8 @@
+#[cfg(feature = "client_side_encryption")]
+mod encrypted_queue_client;
 mod pop_receipt_client;
 mod queue_client;
+#[cfg(feature = "client_side_encryption")]
+pub use encrypted_queue_client::{AsEncryptedQueueClient, EncryptedQueueClient};
 pub use pop_receipt_client::{AsPopReceiptClient, PopReceiptClient};
 pub use queue_client::{AsQueueClient, QueueClient};
@@ -0,0 +1,9 @@
+use crate::core::EncryptionData;
+
+/// The envelope replacing the text of a client side encrypted message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct EncryptedMessage {
+    pub encrypted_message_contents: String,
+    pub encryption_data: EncryptionData,
+}
@@ -1,4 +1,6 @@
 mod clients;
+#[cfg(feature = "client_side_encryption")]
+mod encrypted_message;
 mod message_ttl;
 mod number_of_messages;
 mod pop_receipt;
@@ -1,3 +1,5 @@
+#[cfg(feature = "client_side_encryption")]
+pub use crate::queue::clients::AsEncryptedQueueClient;
 pub use crate::queue::clients::{AsPopReceiptClient, AsQueueClient};
 pub use crate::{
     MessageTTL, NumberOfMessages, PopReceipt, QueueClient, QueueStoredAccessPolicy,
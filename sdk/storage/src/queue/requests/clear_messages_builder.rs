@@ -1,3 +1,4 @@
+use crate::core::StorageHttpClient;
 use crate::queue::clients::QueueClient;
 use crate::queue::responses::*;
 use azure_core::headers::add_optional_header;
@@ -49,7 +50,7 @@ impl<'a> ClearMessagesBuilder<'a> {
             .storage_client()
             .storage_account_client()
             .http_client()
-            .execute_request_check_status(request.0, http::status::StatusCode::NO_CONTENT)
+            .execute_storage_request_check_status(request.0, http::status::StatusCode::NO_CONTENT)
             .await?;
 
         Ok((&response).try_into()?)
@@ -1,3 +1,4 @@
+use crate::core::StorageHttpClient;
 use crate::queue::clients::QueueClient;
 use crate::queue::responses::*;
 use azure_core::headers::add_optional_header;
@@ -51,7 +52,7 @@ impl<'a> CreateQueueBuilder<'a> {
             .storage_client()
             .storage_account_client()
             .http_client()
-            .execute_request_check_status(request.0, http::status::StatusCode::CREATED)
+            .execute_storage_request_check_status(request.0, http::status::StatusCode::CREATED)
             .await?;
 
         Ok((&response).try_into()?)
@@ -0,0 +1,71 @@
+use crate::core::{decrypt_regions, unwrap_content_key};
+use crate::queue::clients::EncryptedQueueClient;
+use crate::queue::encrypted_message::EncryptedMessage;
+use crate::queue::prelude::*;
+use crate::queue::responses::*;
+use azure_core::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct EncryptedGetMessagesBuilder<'a> {
+    encrypted_queue_client: &'a EncryptedQueueClient,
+    number_of_messages: Option<NumberOfMessages>,
+    visibility_timeout: Option<VisibilityTimeout>,
+    timeout: Option<Timeout>,
+    client_request_id: Option<ClientRequestId<'a>>,
+}
+
+impl<'a> EncryptedGetMessagesBuilder<'a> {
+    pub(crate) fn new(encrypted_queue_client: &'a EncryptedQueueClient) -> Self {
+        EncryptedGetMessagesBuilder {
+            encrypted_queue_client,
+            number_of_messages: None,
+            visibility_timeout: None,
+            timeout: None,
+            client_request_id: None,
+        }
+    }
+
+    setters! {
+        number_of_messages: NumberOfMessages => Some(number_of_messages),
+        visibility_timeout: VisibilityTimeout => Some(visibility_timeout),
+        timeout: Timeout => Some(timeout),
+        client_request_id: ClientRequestId<'a> => Some(client_request_id),
+    }
+
+    pub async fn execute(
+        &self,
+    ) -> Result<GetMessagesResponse, Box<dyn std::error::Error + Sync + Send>> {
+        let mut builder = self.encrypted_queue_client.queue_client().get_messages();
+        if let Some(number_of_messages) = self.number_of_messages.clone() {
+            builder = builder.number_of_messages(number_of_messages);
+        }
+        if let Some(visibility_timeout) = self.visibility_timeout.clone() {
+            builder = builder.visibility_timeout(visibility_timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(client_request_id) = self.client_request_id {
+            builder = builder.client_request_id(client_request_id);
+        }
+
+        let mut response = builder.execute().await?;
+
+        for message in response.messages.iter_mut() {
+            let envelope: EncryptedMessage = serde_json::from_str(&message.message_text)?;
+            let content_key = unwrap_content_key(
+                self.encrypted_queue_client.key_encryption_key(),
+                &envelope.encryption_data,
+            )
+            .await?;
+            let plaintext = decrypt_regions(
+                &content_key,
+                &base64::decode(&envelope.encrypted_message_contents)?,
+                &envelope.encryption_data.encrypted_region_info,
+            )?;
+            message.message_text = String::from_utf8(plaintext)?;
+        }
+
+        Ok(response)
+    }
+}
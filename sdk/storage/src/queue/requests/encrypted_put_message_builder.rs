@@ -0,0 +1,71 @@
+use crate::queue::clients::EncryptedQueueClient;
+use crate::queue::encrypted_message::EncryptedMessage;
+use crate::queue::responses::*;
+use crate::queue::*;
+use azure_core::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct EncryptedPutMessageBuilder<'a> {
+    encrypted_queue_client: &'a EncryptedQueueClient,
+    visibility_timeout: Option<VisibilityTimeout>,
+    ttl: Option<MessageTTL>,
+    timeout: Option<Timeout>,
+    client_request_id: Option<ClientRequestId<'a>>,
+}
+
+impl<'a> EncryptedPutMessageBuilder<'a> {
+    pub(crate) fn new(encrypted_queue_client: &'a EncryptedQueueClient) -> Self {
+        EncryptedPutMessageBuilder {
+            encrypted_queue_client,
+            visibility_timeout: None,
+            ttl: None,
+            timeout: None,
+            client_request_id: None,
+        }
+    }
+
+    setters! {
+        visibility_timeout: VisibilityTimeout => Some(visibility_timeout),
+        ttl: MessageTTL => Some(ttl),
+        timeout: Timeout => Some(timeout),
+        client_request_id: ClientRequestId<'a> => Some(client_request_id),
+    }
+
+    pub async fn execute(
+        &self,
+        body: impl AsRef<str>,
+    ) -> Result<PutMessageResponse, Box<dyn std::error::Error + Sync + Send>> {
+        let (encrypted, encryption_data) = crate::core::encrypt(
+            self.encrypted_queue_client.key_encryption_key(),
+            body.as_ref().as_bytes(),
+            None,
+        )
+        .await?;
+
+        let envelope = serde_json::to_string(&EncryptedMessage {
+            encrypted_message_contents: base64::encode(encrypted),
+            encryption_data,
+        })?;
+        // the envelope ends up in the XML body as is
+        let envelope = envelope
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;");
+
+        let mut builder = self.encrypted_queue_client.queue_client().put_message();
+        if let Some(visibility_timeout) = self.visibility_timeout.clone() {
+            builder = builder.visibility_timeout(visibility_timeout);
+        }
+        if let Some(ttl) = self.ttl.clone() {
+            builder = builder.ttl(ttl);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(client_request_id) = self.client_request_id {
+            builder = builder.client_request_id(client_request_id);
+        }
+
+        builder.execute(envelope).await
+    }
+}
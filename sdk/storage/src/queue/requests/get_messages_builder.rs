@@ -1,3 +1,4 @@
+use crate::core::StorageHttpClient;
 use crate::queue::clients::QueueClient;
 use crate::queue::prelude::*;
 use crate::queue::responses::*;
@@ -58,7 +59,7 @@ impl<'a> GetMessagesBuilder<'a> {
             .storage_client()
             .storage_account_client()
             .http_client()
-            .execute_request_check_status(request.0, http::status::StatusCode::OK)
+            .execute_storage_request_check_status(request.0, http::status::StatusCode::OK)
             .await?;
 
         Ok((&response).try_into()?)
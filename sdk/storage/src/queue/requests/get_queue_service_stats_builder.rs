@@ -1,4 +1,5 @@
 use crate::core::prelude::*;
+use crate::core::StorageHttpClient;
 use crate::queue::responses::*;
 use azure_core::headers::add_optional_header;
 use azure_core::prelude::*;
@@ -33,7 +34,13 @@ impl<'a> GetQueueServiceStatsBuilder<'a> {
         let mut url = self
             .storage_client
             .storage_account_client()
-            .queue_storage_secondary_url()
+            .queue_storage_secondary_endpoint()
+            .ok_or_else(|| {
+                crate::Error::OperationNotSupported(
+                    "Get queue service stats".to_owned(),
+                    "the secondary endpoint cannot be derived from a custom endpoint".to_owned(),
+                )
+            })?
             .to_owned();
 
         url.query_pairs_mut().append_pair("restype", "service");
@@ -57,7 +64,7 @@ impl<'a> GetQueueServiceStatsBuilder<'a> {
             .storage_client
             .storage_account_client()
             .http_client()
-            .execute_request_check_status(request.0, StatusCode::OK)
+            .execute_storage_request_check_status(request.0, StatusCode::OK)
             .await?;
 
         Ok((&response).try_into()?)
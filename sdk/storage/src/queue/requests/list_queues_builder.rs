@@ -1,4 +1,5 @@
 use crate::core::prelude::*;
+use crate::core::StorageHttpClient;
 use crate::queue::responses::*;
 use azure_core::headers::add_optional_header;
 use azure_core::prelude::*;
@@ -78,7 +79,7 @@ impl<'a> ListQueuesBuilder<'a> {
             .storage_client
             .storage_account_client()
             .http_client()
-            .execute_request_check_status(request.0, StatusCode::OK)
+            .execute_storage_request_check_status(request.0, StatusCode::OK)
             .await?;
 
         Ok((&response).try_into()?)
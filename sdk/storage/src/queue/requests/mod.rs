@@ -2,6 +2,10 @@ mod clear_messages_builder;
 mod create_queue_builder;
 mod delete_message_builder;
 mod delete_queue_builder;
+#[cfg(feature = "client_side_encryption")]
+mod encrypted_get_messages_builder;
+#[cfg(feature = "client_side_encryption")]
+mod encrypted_put_message_builder;
 mod get_messages_builder;
 mod get_queue_acl_builder;
 mod get_queue_metadata_builder;
@@ -18,6 +22,10 @@ pub use clear_messages_builder::ClearMessagesBuilder;
 pub use create_queue_builder::CreateQueueBuilder;
 pub use delete_message_builder::DeleteMessageBuilder;
 pub use delete_queue_builder::DeleteQueueBuilder;
+#[cfg(feature = "client_side_encryption")]
+pub use encrypted_get_messages_builder::EncryptedGetMessagesBuilder;
+#[cfg(feature = "client_side_encryption")]
+pub use encrypted_put_message_builder::EncryptedPutMessageBuilder;
 pub use get_messages_builder::GetMessagesBuilder;
 pub use get_queue_acl_builder::GetQueueACLBuilder;
 pub use get_queue_metadata_builder::GetQueueMetadataBuilder;
@@ -1,3 +1,4 @@
+use crate::core::StorageHttpClient;
 use crate::queue::clients::QueueClient;
 use crate::queue::prelude::*;
 use crate::queue::responses::*;
@@ -55,7 +56,7 @@ impl<'a> PeekMessagesBuilder<'a> {
             .storage_client()
             .storage_account_client()
             .http_client()
-            .execute_request_check_status(request.0, http::status::StatusCode::OK)
+            .execute_storage_request_check_status(request.0, http::status::StatusCode::OK)
             .await?;
 
         Ok((&response).try_into()?)
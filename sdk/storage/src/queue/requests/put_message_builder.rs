@@ -1,3 +1,4 @@
+use crate::core::StorageHttpClient;
 use crate::queue::responses::*;
 use crate::queue::*;
 use azure_core::headers::add_optional_header;
@@ -68,7 +69,7 @@ impl<'a> PutMessageBuilder<'a> {
             .storage_client()
             .storage_account_client()
             .http_client()
-            .execute_request_check_status(request.0, http::status::StatusCode::CREATED)
+            .execute_storage_request_check_status(request.0, http::status::StatusCode::CREATED)
             .await?;
 
         Ok((&response).try_into()?)
@@ -1,3 +1,4 @@
+use crate::core::StorageHttpClient;
 use crate::queue::responses::*;
 use crate::QueueStoredAccessPolicy;
 use crate::{queue::clients::QueueClient, StoredAccessPolicyList};
@@ -70,7 +71,7 @@ impl<'a> SetQueueACLBuilder<'a> {
             .storage_client()
             .storage_account_client()
             .http_client()
-            .execute_request_check_status(request.0, http::status::StatusCode::NO_CONTENT)
+            .execute_storage_request_check_status(request.0, http::status::StatusCode::NO_CONTENT)
             .await?;
 
         Ok((&response).try_into()?)
@@ -1,4 +1,5 @@
 use crate::core::clients::StorageClient;
+use crate::core::StorageHttpClient;
 use crate::queue::responses::*;
 use crate::QueueServiceProperties;
 use azure_core::headers::add_optional_header;
@@ -60,7 +61,7 @@ impl<'a> SetQueueServicePropertiesBuilder<'a> {
             .storage_client
             .storage_account_client()
             .http_client()
-            .execute_request_check_status(request.0, http::status::StatusCode::ACCEPTED)
+            .execute_storage_request_check_status(request.0, http::status::StatusCode::ACCEPTED)
             .await?;
 
         Ok((&response).try_into()?)
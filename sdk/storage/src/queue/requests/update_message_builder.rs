@@ -1,3 +1,4 @@
+use crate::core::StorageHttpClient;
 use crate::queue::clients::PopReceiptClient;
 use crate::queue::responses::*;
 use crate::queue::*;
@@ -65,7 +66,7 @@ impl<'a> UpdateMessageBuilder<'a> {
         let response = self
             .pop_receipt_client
             .http_client()
-            .execute_request_check_status(request.0, http::status::StatusCode::NO_CONTENT)
+            .execute_storage_request_check_status(request.0, http::status::StatusCode::NO_CONTENT)
             .await?;
 
         Ok((&response).try_into()?)
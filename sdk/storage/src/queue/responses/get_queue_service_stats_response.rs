@@ -53,7 +53,7 @@ impl std::convert::TryFrom<&Response<Bytes>> for GetQueueServiceStatsResponse {
                 .last_sync_time
                 .map(|t| DateTime::parse_from_rfc2822(&t))
                 .transpose()?
-                .map(|t| DateTime::from_utc(t.naive_utc(), Utc)),
+                .map(|t| t.with_timezone(&Utc)),
         })
     }
 }
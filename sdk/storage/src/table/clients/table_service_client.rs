@@ -1,5 +1,9 @@
 use crate::core::clients::{StorageAccountClient, StorageClient};
-use crate::table::requests::ListTablesBuilder;
+use crate::table::requests::{
+    GetTableServicePropertiesBuilder, GetTableServiceStatsBuilder, ListTablesBuilder,
+    SetTableServicePropertiesBuilder,
+};
+use crate::TableServiceProperties;
 use bytes::Bytes;
 use http::method::Method;
 use http::request::{Builder, Request};
@@ -42,6 +46,21 @@ impl TableServiceClient {
         ListTablesBuilder::new(self)
     }
 
+    pub fn get_properties(&self) -> GetTableServicePropertiesBuilder {
+        GetTableServicePropertiesBuilder::new(self)
+    }
+
+    pub fn set_properties<'a>(
+        &'a self,
+        table_service_properties: &'a TableServiceProperties,
+    ) -> SetTableServicePropertiesBuilder<'a> {
+        SetTableServicePropertiesBuilder::new(self, table_service_properties)
+    }
+
+    pub fn get_stats(&self) -> GetTableServiceStatsBuilder {
+        GetTableServiceStatsBuilder::new(self)
+    }
+
     pub(crate) fn url(&self) -> &Url {
         &self.url
     }
@@ -11,6 +11,7 @@ pub mod requests;
 pub mod responses;
 mod return_entity;
 mod select;
+mod table_service_properties;
 mod top;
 mod transaction;
 mod transaction_operation;
@@ -24,6 +25,7 @@ pub use model::Table;
 use once_cell::sync::Lazy;
 pub use return_entity::ReturnEntity;
 pub use select::Select;
+pub use table_service_properties::TableServiceProperties;
 pub use top::Top;
 pub use transaction::Transaction;
 pub use transaction_operation::TransactionOperation;
@@ -1,3 +1,4 @@
+use crate::core::StorageHttpClient;
 use crate::table::prelude::*;
 use crate::table::responses::*;
 use azure_core::headers::add_optional_header;
@@ -59,7 +60,7 @@ impl<'a> CreateTableBuilder<'a> {
         let response = self
             .table_client
             .http_client()
-            .execute_request_check_status(request.0, StatusCode::CREATED)
+            .execute_storage_request_check_status(request.0, StatusCode::CREATED)
             .await?;
 
         Ok((&response).try_into()?)
@@ -1,3 +1,4 @@
+use crate::core::StorageHttpClient;
 use crate::table::prelude::IfMatchCondition;
 use crate::table::prelude::*;
 use crate::table::responses::*;
@@ -56,7 +57,7 @@ impl<'a> DeleteEntityBuilder<'a> {
         let response = self
             .entity_client
             .http_client()
-            .execute_request_check_status(request.0, StatusCode::NO_CONTENT)
+            .execute_storage_request_check_status(request.0, StatusCode::NO_CONTENT)
             .await?;
 
         Ok((&response).try_into()?)
@@ -1,3 +1,4 @@
+use crate::core::StorageHttpClient;
 use crate::table::prelude::*;
 use crate::table::responses::*;
 use azure_core::headers::add_optional_header;
@@ -53,7 +54,7 @@ impl<'a> DeleteTableBuilder<'a> {
         let response = self
             .table_client
             .http_client()
-            .execute_request_check_status(request.0, StatusCode::NO_CONTENT)
+            .execute_storage_request_check_status(request.0, StatusCode::NO_CONTENT)
             .await?;
 
         Ok((&response).try_into()?)
@@ -1,3 +1,4 @@
+use crate::core::StorageHttpClient;
 use crate::table::prelude::*;
 use crate::table::responses::*;
 use azure_core::prelude::*;
@@ -56,7 +57,7 @@ impl<'a> GetEntityBuilder<'a> {
         let response = self
             .entity_client
             .http_client()
-            .execute_request_check_status(request.0, StatusCode::OK)
+            .execute_storage_request_check_status(request.0, StatusCode::OK)
             .await?;
 
         Ok((&response).try_into()?)
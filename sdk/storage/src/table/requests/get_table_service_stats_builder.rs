@@ -0,0 +1,77 @@
+use crate::core::StorageHttpClient;
+use crate::table::prelude::*;
+use crate::table::responses::*;
+use azure_core::headers::add_optional_header;
+use azure_core::prelude::*;
+use http::method::Method;
+use http::status::StatusCode;
+use std::convert::TryInto;
+
+#[cfg(test)]
+use std::println as debug;
+
+#[derive(Debug, Clone)]
+pub struct GetTableServiceStatsBuilder<'a> {
+    table_service_client: &'a TableServiceClient,
+    timeout: Option<Timeout>,
+    client_request_id: Option<ClientRequestId<'a>>,
+}
+
+impl<'a> GetTableServiceStatsBuilder<'a> {
+    pub(crate) fn new(table_service_client: &'a TableServiceClient) -> Self {
+        Self {
+            table_service_client,
+            timeout: None,
+            client_request_id: None,
+        }
+    }
+
+    setters! {
+        timeout: Timeout => Some(timeout),
+        client_request_id: ClientRequestId<'a> => Some(client_request_id),
+    }
+
+    /// Retrieves the geo-replication status of the account. The request is
+    /// sent to the secondary endpoint so it is available only if read-access
+    /// geo-redundant replication is enabled.
+    pub async fn execute(
+        &self,
+    ) -> Result<GetTableServiceStatsResponse, Box<dyn std::error::Error + Sync + Send>> {
+        let mut url = self
+            .table_service_client
+            .storage_account_client()
+            .table_storage_secondary_endpoint()
+            .ok_or_else(|| {
+                crate::Error::OperationNotSupported(
+                    "Get table service stats".to_owned(),
+                    "the secondary endpoint cannot be derived from a custom endpoint".to_owned(),
+                )
+            })?
+            .to_owned();
+
+        url.query_pairs_mut().append_pair("restype", "service");
+        url.query_pairs_mut().append_pair("comp", "stats");
+
+        self.timeout.append_to_url_query(&mut url);
+
+        debug!("url = {}", url);
+
+        let request = self.table_service_client.prepare_request(
+            url.as_str(),
+            &Method::GET,
+            &|mut request| {
+                request = add_optional_header(&self.client_request_id, request);
+                request
+            },
+            None,
+        )?;
+
+        let response = self
+            .table_service_client
+            .http_client()
+            .execute_storage_request_check_status(request.0, StatusCode::OK)
+            .await?;
+
+        Ok((&response).try_into()?)
+    }
+}
@@ -1,3 +1,4 @@
+use crate::core::StorageHttpClient;
 use crate::table::prelude::*;
 use crate::table::responses::*;
 use crate::table::TransactionOperation;
@@ -68,7 +69,10 @@ impl<'a> InsertEntityBuilder<'a> {
         let response = self
             .table_client
             .http_client()
-            .execute_request_check_status(request.0, self.return_entity.expected_return_code())
+            .execute_storage_request_check_status(
+                request.0,
+                self.return_entity.expected_return_code(),
+            )
             .await?;
 
         Ok((&response).try_into()?)
@@ -1,3 +1,4 @@
+use crate::core::StorageHttpClient;
 use crate::table::responses::*;
 use crate::{table::prelude::*, ContinuationNextTableName};
 use azure_core::prelude::*;
@@ -69,7 +70,7 @@ impl<'a> ListTablesBuilder<'a> {
         let response = self
             .table_service_client
             .http_client()
-            .execute_request_check_status(request.0, StatusCode::OK)
+            .execute_storage_request_check_status(request.0, StatusCode::OK)
             .await?;
 
         Ok((&response).try_into()?)
@@ -2,19 +2,25 @@ mod create_table_builder;
 mod delete_entity_builder;
 mod delete_table_builder;
 mod get_entity_builder;
+mod get_table_service_properties_builder;
+mod get_table_service_stats_builder;
 mod insert_entity_builder;
 pub(crate) mod insert_or_replace_or_merge_entity_builder;
 mod list_tables_builder;
 mod query_entity_builder;
+mod set_table_service_properties_builder;
 mod submit_transaction_builder;
 pub(crate) mod update_or_merge_entity_builder;
 pub use create_table_builder::CreateTableBuilder;
 pub use delete_entity_builder::DeleteEntityBuilder;
 pub use delete_table_builder::DeleteTableBuilder;
 pub use get_entity_builder::GetEntityBuilder;
+pub use get_table_service_properties_builder::GetTableServicePropertiesBuilder;
+pub use get_table_service_stats_builder::GetTableServiceStatsBuilder;
 pub use insert_entity_builder::InsertEntityBuilder;
 pub use insert_or_replace_or_merge_entity_builder::InsertOrReplaceOrMergeEntityBuilder;
 pub use list_tables_builder::ListTablesBuilder;
 pub use query_entity_builder::QueryEntityBuilder;
+pub use set_table_service_properties_builder::SetTableServicePropertiesBuilder;
 pub use submit_transaction_builder::SubmitTransactionBuilder;
 pub use update_or_merge_entity_builder::UpdateOrMergeEntityBuilder;
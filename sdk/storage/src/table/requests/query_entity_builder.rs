@@ -1,3 +1,4 @@
+use crate::core::StorageHttpClient;
 use crate::table::responses::*;
 use crate::{table::prelude::*, ContinuationNextPartitionAndRowKey};
 use azure_core::prelude::*;
@@ -73,7 +74,7 @@ impl<'a> QueryEntityBuilder<'a> {
         let response = self
             .table_client
             .http_client()
-            .execute_request_check_status(request.0, StatusCode::OK)
+            .execute_storage_request_check_status(request.0, StatusCode::OK)
             .await?;
 
         Ok((&response).try_into()?)
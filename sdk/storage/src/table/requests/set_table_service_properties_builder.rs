@@ -0,0 +1,77 @@
+use crate::core::StorageHttpClient;
+use crate::table::prelude::*;
+use crate::table::responses::*;
+use crate::TableServiceProperties;
+use azure_core::headers::add_optional_header;
+use azure_core::prelude::*;
+use http::method::Method;
+use http::status::StatusCode;
+use std::convert::TryInto;
+
+#[cfg(test)]
+use std::println as debug;
+
+#[derive(Debug, Clone)]
+pub struct SetTableServicePropertiesBuilder<'a> {
+    table_service_client: &'a TableServiceClient,
+    table_service_properties: &'a TableServiceProperties,
+    timeout: Option<Timeout>,
+    client_request_id: Option<ClientRequestId<'a>>,
+}
+
+impl<'a> SetTableServicePropertiesBuilder<'a> {
+    pub(crate) fn new(
+        table_service_client: &'a TableServiceClient,
+        table_service_properties: &'a TableServiceProperties,
+    ) -> Self {
+        Self {
+            table_service_client,
+            table_service_properties,
+            timeout: None,
+            client_request_id: None,
+        }
+    }
+
+    setters! {
+        timeout: Timeout => Some(timeout),
+        client_request_id: ClientRequestId<'a> => Some(client_request_id),
+    }
+
+    pub async fn execute(
+        &self,
+    ) -> Result<SetTableServicePropertiesResponse, Box<dyn std::error::Error + Sync + Send>> {
+        let mut url = self
+            .table_service_client
+            .storage_account_client()
+            .table_storage_url()
+            .to_owned();
+
+        url.query_pairs_mut().append_pair("restype", "service");
+        url.query_pairs_mut().append_pair("comp", "properties");
+
+        self.timeout.append_to_url_query(&mut url);
+
+        debug!("url = {}", url);
+
+        let xml_body = self.table_service_properties.to_xml();
+        debug!("xml about to be sent == {}", xml_body);
+
+        let request = self.table_service_client.prepare_request(
+            url.as_str(),
+            &Method::PUT,
+            &|mut request| {
+                request = add_optional_header(&self.client_request_id, request);
+                request
+            },
+            Some(xml_body.into()),
+        )?;
+
+        let response = self
+            .table_service_client
+            .http_client()
+            .execute_storage_request_check_status(request.0, StatusCode::ACCEPTED)
+            .await?;
+
+        Ok((&response).try_into()?)
+    }
+}
@@ -1,3 +1,4 @@
+use crate::core::StorageHttpClient;
 use crate::table::prelude::*;
 use crate::table::responses::*;
 use azure_core::headers::add_optional_header;
@@ -64,7 +65,7 @@ impl<'a> SubmitTransactionBuilder<'a> {
         let response = self
             .partition_key_client
             .http_client()
-            .execute_request_check_status(request.0, StatusCode::ACCEPTED)
+            .execute_storage_request_check_status(request.0, StatusCode::ACCEPTED)
             .await?;
 
         Ok((&response).try_into()?)
@@ -0,0 +1,25 @@
+use crate::{xml::read_xml, TableServiceProperties};
+use azure_core::headers::CommonStorageResponseHeaders;
+use bytes::Bytes;
+use http::Response;
+use std::convert::{TryFrom, TryInto};
+
+#[derive(Debug, Clone)]
+pub struct GetTableServicePropertiesResponse {
+    pub common_storage_response_headers: CommonStorageResponseHeaders,
+    pub table_service_properties: TableServiceProperties,
+}
+
+impl TryFrom<&Response<Bytes>> for GetTableServicePropertiesResponse {
+    type Error = crate::Error;
+
+    fn try_from(response: &Response<Bytes>) -> Result<Self, Self::Error> {
+        debug!("{}", std::str::from_utf8(response.body())?);
+        debug!("headers == {:#?}", response.headers());
+
+        Ok(GetTableServicePropertiesResponse {
+            common_storage_response_headers: response.headers().try_into()?,
+            table_service_properties: read_xml(response.body())?,
+        })
+    }
+}
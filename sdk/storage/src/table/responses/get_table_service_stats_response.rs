@@ -0,0 +1,51 @@
+use crate::core::GeoReplicationStatus;
+use crate::xml::read_xml;
+use azure_core::headers::CommonStorageResponseHeaders;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use http::Response;
+use std::convert::{TryFrom, TryInto};
+
+#[derive(Debug, Clone)]
+pub struct GetTableServiceStatsResponse {
+    pub common_storage_response_headers: CommonStorageResponseHeaders,
+    pub status: GeoReplicationStatus,
+    pub last_sync_time: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GetTableServiceStatsResponseInternal {
+    pub geo_replication: GeoReplication,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GeoReplication {
+    pub status: GeoReplicationStatus,
+    pub last_sync_time: Option<String>,
+}
+
+impl TryFrom<&Response<Bytes>> for GetTableServiceStatsResponse {
+    type Error = crate::Error;
+
+    fn try_from(response: &Response<Bytes>) -> Result<Self, Self::Error> {
+        debug!("{}", std::str::from_utf8(response.body())?);
+        debug!("headers == {:#?}", response.headers());
+
+        let response_internal: GetTableServiceStatsResponseInternal = read_xml(response.body())?;
+
+        Ok(GetTableServiceStatsResponse {
+            common_storage_response_headers: response.headers().try_into()?,
+            status: response_internal.geo_replication.status,
+            last_sync_time: response_internal
+                .geo_replication
+                .last_sync_time
+                // the service returns an empty element if no sync happened yet
+                .filter(|t| !t.is_empty())
+                .map(|t| DateTime::parse_from_rfc2822(&t))
+                .transpose()?
+                .map(|t| t.with_timezone(&Utc)),
+        })
+    }
+}
@@ -2,17 +2,23 @@ mod create_table_response;
 mod delete_entity_response;
 mod delete_table_response;
 mod get_entity_response;
+mod get_table_service_properties_response;
+mod get_table_service_stats_response;
 mod insert_entity_response;
 mod list_tables_response;
 mod operation_on_entity_response;
 mod query_entity_response;
+mod set_table_service_properties_response;
 mod submit_transaction_response;
 pub use create_table_response::CreateTableResponse;
 pub use delete_entity_response::DeleteEntityResponse;
 pub use delete_table_response::DeleteTableResponse;
 pub use get_entity_response::GetEntityResponse;
+pub use get_table_service_properties_response::GetTableServicePropertiesResponse;
+pub use get_table_service_stats_response::GetTableServiceStatsResponse;
 pub use insert_entity_response::InsertEntityResponse;
 pub use list_tables_response::ListTablesResponse;
 pub use operation_on_entity_response::OperationOnEntityResponse;
 pub use query_entity_response::QueryEntityResponse;
+pub use set_table_service_properties_response::SetTableServicePropertiesResponse;
 pub use submit_transaction_response::SubmitTransactionResponse;
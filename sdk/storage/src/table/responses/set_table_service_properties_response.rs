@@ -0,0 +1,21 @@
+use azure_core::headers::CommonStorageResponseHeaders;
+use bytes::Bytes;
+use http::Response;
+use std::convert::{TryFrom, TryInto};
+
+#[derive(Debug, Clone)]
+pub struct SetTableServicePropertiesResponse {
+    pub common_storage_response_headers: CommonStorageResponseHeaders,
+}
+
+impl TryFrom<&Response<Bytes>> for SetTableServicePropertiesResponse {
+    type Error = crate::Error;
+
+    fn try_from(response: &Response<Bytes>) -> Result<Self, Self::Error> {
+        debug!("headers == {:#?}", response.headers());
+
+        Ok(SetTableServicePropertiesResponse {
+            common_storage_response_headers: response.headers().try_into()?,
+        })
+    }
+}
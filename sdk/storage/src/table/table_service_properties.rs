@@ -0,0 +1,113 @@
+use crate::core::{Cors, Logging, Metrics};
+
+/// Properties of the table service of a storage account.
+///
+/// Every element is optional: when setting the properties the service leaves
+/// the omitted ones unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct TableServiceProperties {
+    pub logging: Option<Logging>,
+    pub hour_metrics: Option<Metrics>,
+    pub minute_metrics: Option<Metrics>,
+    pub cors: Option<Cors>,
+}
+
+impl TableServiceProperties {
+    pub fn to_xml(&self) -> String {
+        let mut s = String::new();
+        s.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<StorageServiceProperties>");
+        if let Some(logging) = &self.logging {
+            s.push_str(&logging.to_xml());
+        }
+        if let Some(hour_metrics) = &self.hour_metrics {
+            s.push_str(&hour_metrics.to_xml("HourMetrics"));
+        }
+        if let Some(minute_metrics) = &self.minute_metrics {
+            s.push_str(&minute_metrics.to_xml("MinuteMetrics"));
+        }
+        if let Some(cors) = &self.cors {
+            s.push_str(&cors.to_xml());
+        }
+        s.push_str("</StorageServiceProperties>");
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{CorsRule, RetentionPolicy};
+    use crate::xml::read_xml;
+    use bytes::Bytes;
+
+    const S: &str = "<?xml version=\"1.0\" encoding=\"utf-8\"?>
+<StorageServiceProperties>
+    <Logging>
+        <Version>1.0</Version>
+        <Delete>true</Delete>
+        <Read>false</Read>
+        <Write>true</Write>
+        <RetentionPolicy>
+            <Enabled>true</Enabled>
+            <Days>7</Days>
+        </RetentionPolicy>
+    </Logging>
+    <HourMetrics>
+        <Version>1.0</Version>
+        <Enabled>true</Enabled>
+        <IncludeAPIs>false</IncludeAPIs>
+        <RetentionPolicy>
+            <Enabled>true</Enabled>
+            <Days>7</Days>
+        </RetentionPolicy>
+    </HourMetrics>
+    <MinuteMetrics>
+        <Version>1.0</Version>
+        <Enabled>false</Enabled>
+        <RetentionPolicy>
+            <Enabled>false</Enabled>
+        </RetentionPolicy>
+    </MinuteMetrics>
+    <Cors />
+</StorageServiceProperties>";
+
+    #[test]
+    fn deserde_table_service_properties() {
+        let properties: TableServiceProperties = read_xml(&Bytes::from(S)).unwrap();
+
+        assert!(properties.logging.unwrap().write);
+        assert_eq!(properties.hour_metrics.unwrap().include_apis, Some(false));
+        assert!(!properties.minute_metrics.unwrap().enabled);
+        assert_eq!(properties.cors.unwrap().cors_rule, None);
+    }
+
+    #[test]
+    fn to_xml_round_trip() {
+        let properties = TableServiceProperties {
+            hour_metrics: Some(Metrics {
+                version: "1.0".to_owned(),
+                enabled: true,
+                include_apis: Some(true),
+                retention_policy: RetentionPolicy {
+                    enabled: true,
+                    days: Some(3),
+                },
+            }),
+            cors: Some(Cors {
+                cors_rule: Some(vec![CorsRule {
+                    allowed_origins: "https://contoso.com".to_owned(),
+                    allowed_methods: "GET,MERGE".to_owned(),
+                    max_age_in_seconds: 60,
+                    exposed_headers: "*".to_owned(),
+                    allowed_headers: "*".to_owned(),
+                }]),
+            }),
+            ..Default::default()
+        };
+
+        let xml = properties.to_xml();
+        let round_trip: TableServiceProperties = read_xml(&Bytes::from(xml)).unwrap();
+        assert_eq!(round_trip, properties);
+    }
+}